@@ -0,0 +1,92 @@
+//! In-memory cache of manifests (config, accounts, profiles) shared across
+//! commands.
+//!
+//! Entries are keyed by file path and revalidated against the file's mtime and
+//! size on every read, so writes made by the CLI or another window are picked
+//! up automatically. A rewrite of the same size within the filesystem's mtime
+//! resolution looks unchanged, so commands that save a manifest drop its
+//! entry with [`ManifestCache::invalidate`]. `bust_manifest_cache_cmd` clears
+//! everything explicitly.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    /// `None` when the file is missing; a missing file is still cacheable
+    /// since loaders fall back to defaults
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+struct Entry {
+    stamp: Option<FileStamp>,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+#[derive(Default)]
+pub struct ManifestCache {
+    entries: Mutex<HashMap<PathBuf, Entry>>,
+}
+
+impl ManifestCache {
+    /// Return the cached value for `path`, or run `load` if the file changed
+    /// since it was cached
+    pub fn get_or_load<T, E, F>(&self, path: &Path, load: F) -> Result<T, String>
+    where
+        T: Clone + Send + Sync + 'static,
+        E: std::fmt::Display,
+        F: FnOnce() -> Result<T, E>,
+    {
+        // Stat before loading: a write racing the load is seen on the next read
+        let stamp = FileStamp::of(path);
+        if let Ok(entries) = self.entries.lock() {
+            let cached = entries
+                .get(path)
+                .filter(|entry| entry.stamp == stamp)
+                .and_then(|entry| entry.value.downcast_ref::<T>());
+            if let Some(value) = cached {
+                return Ok(value.clone());
+            }
+        }
+
+        let value = load().map_err(|e| e.to_string())?;
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(
+                path.to_path_buf(),
+                Entry {
+                    stamp,
+                    value: Arc::new(value.clone()),
+                },
+            );
+        }
+        Ok(value)
+    }
+
+    /// Forget the entry for `path` after writing it
+    pub fn invalidate(&self, path: &Path) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.remove(path);
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
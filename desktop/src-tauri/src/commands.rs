@@ -1,17 +1,63 @@
-use serde::{Deserialize, Serialize};
+use crate::cache::ManifestCache;
+use crate::confirm::ConfirmTokens;
+use crate::dto::{
+    AccountCacheEvent, AccountInfo, AdoptedFileDto, ConfigDto, ContentItemDto, ContentVersionDto, CreateProfileInput,
+    DiffResult, GameSessionEvents, InstallDisclosureDto, InstanceMetricsEvent, JavaInstallationDto, JavaValidationDto,
+    JobInfoDto, LaunchEvent, LaunchPlanDto, LibraryFilterInput, LibraryItemDto, LibraryItemInputDto,
+    LibraryItemUpdateInput, LibraryStatsDto, LoaderInfoDto, LocaleMatchDto, LocaleReportDto, LogEntryDto,
+    PrefetchEvent, PrepareEstimateDto, PresetAppliedDto, ProfileDto, ProfileOrganization, PurgeResultDto,
+    QuickstartSummaryDto, RunningInstanceDto, SnapshotUpdateDto, StorageStatsDto, StoreInstallInput,
+    StoreSearchInput, StoreVerifyEvent, TemplateDto, TimedGameEventDto, UnusedItemsSummaryDto, UpdateCheckResultDto,
+    VersionListDto, WatchedProjectDto,
+};
+use serde::Deserialize;
+use shard::account_export::{export_account, import_account};
 use shard::accounts::{Account, Accounts, load_accounts, remove_account, save_accounts, set_active};
-use shard::auth::{DeviceCode, request_device_code};
-use shard::config::{Config, load_config, save_config};
-use shard::content_store::{ContentStore, ContentType, Platform, SearchOptions, ContentItem, ContentVersion};
-use shard::java::{JavaInstallation, JavaValidation, AdoptiumRelease, detect_installations, validate_java_path, get_required_java_version, is_java_compatible, fetch_adoptium_release, download_and_install_java, find_compatible_java, get_managed_java, list_managed_runtimes};
-use shard::library::{Library, LibraryItem, LibraryFilter, LibraryItemInput, LibraryContentType, LibraryStats, Tag, ImportResult, UnusedItemsSummary, PurgeResult};
-use shard::logs::{LogEntry, LogFile, LogWatcher, list_log_files, list_crash_reports, read_log_file, read_log_tail};
-use shard::minecraft::{LaunchPlan, prepare};
-use shard::ops::{finish_device_code_flow, parse_loader, resolve_input, resolve_launch_account, ensure_fresh_account};
+use shard::api_keys::{ApiKeyHealth, track_api_key_health, verify_curseforge_key};
+use shard::auth::{DeviceCode, request_device_code, start_browser_auth};
+use shard::blocklist::{BlockedEntry, block_hash, block_project, export_blocklist, import_blocklist, load_blocklist, unblock};
+use shard::config::{load_config, save_config};
+use shard::config_history::{ConfigChange, ConfigSnapshot, list_config_snapshots, restore_config_snapshot};
+use shard::content_store::{ContentStore, ContentType, Platform, SearchOptions, InstallDisclosure};
+use shard::crash_stats::{CrashSignatureStats, CrashStats, collect_profile_crashes};
+use shard::disk::user_message;
+use shard::download_queue::{FailedDownload, QueueRetryResult, load_download_queue, retry_download_queue};
+use shard::jobs::{JobKind, cancel_job, list_jobs, run_job, set_job_listener};
+use shard::game_events::{SessionSummary, extract_events, summarize_events};
+use shard::installer::find_installer_error;
+use shard::instance::{adopt_unmanaged_files, reimport_modified_files};
+use shard::instance_folders::relocate_aux_folder;
+use shard::java::{AdoptiumRelease, detect_installations, validate_java_path, get_required_java_version, is_java_compatible, fetch_adoptium_release, download_and_install_java, find_compatible_java, get_managed_java, list_managed_runtimes};
+use shard::keybinds::{KeybindConflict, keybind_conflicts};
+use shard::legacy::migrate_legacy_data;
+use shard::library::{Library, LibraryItemInput, LibraryContentType, Tag, ImportResult};
+use shard::library_report::format_size;
+use shard::loader::{LoaderKind, LoaderSuggestion, list_loaders, suggest_loader};
+use shard::locale::{find_locale_packs, install_locale_packs};
+use shard::lockfile::{InstallSummary, install_locked, install_profile, load_lockfile};
+use shard::logs::{LogFile, LogWatcher, crash_report_path, list_log_files, list_crash_reports, read_log_file, read_log_tail};
+use shard::metered::{MeteredMode, is_metered, needs_confirmation};
+use shard::minecraft::{
+    GameFilesReport, estimate_prepare, game_command, list_liteloader_versions, list_optifine_versions,
+    prepare, prepare_with_progress, verify_game_files, warm_up,
+};
+use shard::mod_config::{ModConfigFile, list_mod_configs, read_mod_config, restore_mod_config, write_mod_config};
+use shard::modpack::{MrpackExport, export_mrpack};
+use shard::mirrors::set_download_mirrors;
+use shard::net::set_retry_policy;
+use shard::ops::{add_content, add_store_content, finish_browser_auth_flow, finish_device_code_flow, parse_loader, remove_content, resolve_input, resolve_launch_account, refresh_plan_account, ensure_fresh_account};
+use shard::pack_meta::{PackInspection, PackKind, inspect_pack};
 use shard::paths::Paths;
-use shard::profile::{ContentRef, Loader, Profile, Runtime, clone_profile, create_profile, delete_profile, diff_profiles, list_profiles, load_profile, remove_mod, remove_resourcepack, remove_shaderpack, rename_profile, save_profile, upsert_mod, upsert_resourcepack, upsert_shaderpack};
+use shard::playtime::{PlaytimeStats, SessionRecord, load_playtime, record_session};
+use shard::presets::{ContentPreset, apply_preset, delete_preset, save_preset};
+use shard::process::{KILL_GRACE_PERIOD, kill_profile, list_running, register_instance, unregister_instance};
+use shard::profile::{CloneMode, ContentRef, Loader, Runtime, clone_profile, create_profile, delete_profile, diff_profiles, is_plain_folder_name, list_profiles, load_profile, load_profile_notes, rename_profile, save_profile, save_profile_notes, upsert_mod, upsert_resourcepack, upsert_shaderpack};
+use shard::quickstart::{Recipe, RECIPES, find_recipe, run_quickstart};
+use shard::resource_monitor::{DEFAULT_SAMPLE_INTERVAL, ResourceMonitor};
+use shard::server_properties::{ServerPropertiesView, load_server_properties, save_server_properties, set_server_property};
+use shard::session::{SessionCrash, detect_crash};
+use shard::shader_settings::{copy_shader_settings, list_shader_settings};
 use shard::skin::{
-    MinecraftProfile,
     get_profile as get_mc_profile,
     get_avatar_url,
     get_body_url,
@@ -28,81 +74,28 @@ use shard::skin::{
     download_and_cache_skin,
     download_and_cache_cape,
 };
-use shard::store::{ContentKind, store_content};
-use shard::template::{Template, list_templates, load_template, init_builtin_templates};
-use shard::updates::{StorageStats, UpdateCheckResult, get_storage_stats, check_all_updates, check_profile_updates, set_content_pinned, set_content_enabled, apply_update};
+use shard::snapshot::{SNAPSHOT_CHECK_INTERVAL, sync_snapshot_profile};
+use shard::spaces::{SpaceInfo, create_space, data_dir_owner_warning, delete_space, list_spaces, set_active_space};
+use shard::store::{ContentKind, StoreVerifyReport, configured_store_hash, prefixed_hash, verify_store};
+use shard::template::{list_templates, load_template, init_builtin_templates, resolve_template_version};
+use shard::throttle::Throttle;
+use shard::tunnel::start_profile_tunnel;
+use shard::updates::{get_storage_stats, check_all_updates, check_profile_updates, set_content_pinned, set_content_enabled, apply_update};
+use shard::util::now_epoch_secs;
+use shard::vanilla_launcher::{VanillaImportResult, default_minecraft_dir, import_vanilla_launcher};
+use shard::version_tags::{AutoTagResult, auto_tag_due, auto_tag_library};
+use shard::versions::{VersionChannel, list_versions};
+use shard::watchlist::{load_watchlist, mark_release_seen, unwatch_project, watch_project};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::process::Command;
-use tauri::{AppHandle, Emitter};
-
-#[derive(Serialize)]
-pub struct DiffResult {
-    pub only_a: Vec<String>,
-    pub only_b: Vec<String>,
-    pub both: Vec<String>,
-}
-
-#[derive(Serialize)]
-pub struct LaunchPlanDto {
-    pub instance_dir: String,
-    pub java_exec: String,
-    pub jvm_args: Vec<String>,
-    pub classpath: String,
-    pub main_class: String,
-    pub game_args: Vec<String>,
-}
-
-#[derive(Clone, Serialize)]
-pub struct LaunchEvent {
-    pub stage: String,
-    pub message: Option<String>,
-}
-
-#[derive(Deserialize)]
-pub struct CreateProfileInput {
-    pub id: String,
-    pub mc_version: String,
-    pub loader_type: Option<String>,
-    pub loader_version: Option<String>,
-    pub java: Option<String>,
-    pub memory: Option<String>,
-    pub args: Option<String>,
-    pub template: Option<String>,
-}
-
-#[derive(Clone, Serialize)]
-pub struct AccountInfo {
-    pub uuid: String,
-    pub username: String,
-    pub avatar_url: String,
-    pub body_url: String,
-    pub skin_url: String,
-    pub cape_url: String,
-    pub profile: Option<MinecraftProfile>,
-}
-
-#[derive(Deserialize)]
-pub struct StoreSearchInput {
-    pub query: String,
-    pub content_type: Option<String>,
-    pub game_version: Option<String>,
-    pub loader: Option<String>,
-    pub platform: Option<String>,
-    pub limit: Option<u32>,
-}
-
-#[derive(Deserialize)]
-pub struct StoreInstallInput {
-    pub profile_id: String,
-    pub project_id: String,
-    pub platform: String,
-    pub version_id: Option<String>,
-    pub content_type: Option<String>,
-}
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_opener::OpenerExt;
 
 fn load_paths() -> Result<Paths, String> {
     let paths = Paths::new().map_err(|e| e.to_string())?;
-    paths.ensure().map_err(|e| e.to_string())?;
+    paths.ensure().map_err(|e| user_message(&e))?;
     Ok(paths)
 }
 
@@ -125,19 +118,26 @@ fn resolve_credentials(
 }
 
 #[tauri::command]
-pub fn list_profiles_cmd() -> Result<Vec<String>, String> {
+pub fn list_profiles_cmd(cache: State<'_, ManifestCache>) -> Result<Vec<String>, String> {
     let paths = load_paths()?;
-    list_profiles(&paths).map_err(|e| e.to_string())
+    // The directory mtime changes when profiles are added or removed
+    cache.get_or_load(&paths.profiles, || list_profiles(&paths))
 }
 
 #[tauri::command]
-pub fn load_profile_cmd(id: String) -> Result<Profile, String> {
+pub fn load_profile_cmd(cache: State<'_, ManifestCache>, id: String) -> Result<ProfileDto, String> {
     let paths = load_paths()?;
-    load_profile(&paths, &id).map_err(|e| e.to_string())
+    cache.get_or_load(&paths.profile_json(&id), || load_profile(&paths, &id)).map(Into::into)
+}
+
+/// Drop the cached profile list and manifest after changing a profile
+fn forget_profile(cache: &ManifestCache, paths: &Paths, id: &str) {
+    cache.invalidate(&paths.profiles);
+    cache.invalidate(&paths.profile_json(id));
 }
 
 #[tauri::command]
-pub fn create_profile_cmd(input: CreateProfileInput) -> Result<Profile, String> {
+pub fn create_profile_cmd(cache: State<'_, ManifestCache>, input: CreateProfileInput) -> Result<ProfileDto, String> {
     let paths = load_paths()?;
     let loader = match (input.loader_type, input.loader_version) {
         (Some(loader_type), Some(loader_version)) => {
@@ -156,41 +156,83 @@ pub fn create_profile_cmd(input: CreateProfileInput) -> Result<Profile, String>
         .split_whitespace()
         .map(|s| s.to_string())
         .collect::<Vec<_>>();
+    let wrapper = input.wrapper
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
     let runtime = Runtime {
         java: input.java.filter(|v| !v.trim().is_empty()),
         memory: input.memory.filter(|v| !v.trim().is_empty()),
         args,
+        wrapper,
+        jvm_preset: input.jvm_preset,
+        priority: input.priority,
+        cpu_affinity: input.cpu_affinity.unwrap_or_default(),
+        gamescope: input.gamescope,
     };
 
-    create_profile(&paths, &input.id, &input.mc_version, loader, runtime)
-        .map_err(|e| e.to_string())
+    let profile = create_profile(&paths, &input.id, &input.mc_version, loader, runtime)
+        .map_err(|e| e.to_string())?;
+    forget_profile(&cache, &paths, &profile.id);
+    Ok(profile.into())
 }
 
 #[tauri::command]
-pub fn clone_profile_cmd(src: String, dst: String) -> Result<Profile, String> {
+pub fn clone_profile_cmd(cache: State<'_, ManifestCache>, src: String, dst: String, manifest_only: Option<bool>) -> Result<ProfileDto, String> {
     let paths = load_paths()?;
-    clone_profile(&paths, &src, &dst).map_err(|e| e.to_string())
+    let mode = if manifest_only.unwrap_or(false) {
+        CloneMode::ManifestOnly
+    } else {
+        CloneMode::Full
+    };
+    let result = clone_profile(&paths, &src, &dst, mode);
+    forget_profile(&cache, &paths, &dst);
+    result.map(Into::into).map_err(|e| user_message(&e))
+}
+
+/// One-time token for a destructive command on `target` (a profile id or
+/// account uuid; empty for store-wide commands), see [`ConfirmTokens`]
+#[tauri::command]
+pub fn request_confirmation_cmd(tokens: State<'_, ConfirmTokens>, command: String, target: String) -> Result<String, String> {
+    const GUARDED: [&str; 3] = ["delete_profile_cmd", "remove_account_cmd", "purge_unused_items_cmd"];
+    if !GUARDED.contains(&command.as_str()) {
+        return Err(format!("{command} needs no confirmation"));
+    }
+    Ok(tokens.issue(&command, &target))
 }
 
 #[tauri::command]
-pub fn delete_profile_cmd(id: String) -> Result<(), String> {
+pub fn delete_profile_cmd(
+    tokens: State<'_, ConfirmTokens>,
+    cache: State<'_, ManifestCache>,
+    id: String,
+    confirm_token: String,
+) -> Result<(), String> {
+    tokens.redeem(&confirm_token, "delete_profile_cmd", &id)?;
     let paths = load_paths()?;
-    delete_profile(&paths, &id).map_err(|e| e.to_string())
+    let result = delete_profile(&paths, &id);
+    forget_profile(&cache, &paths, &id);
+    result.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn rename_profile_cmd(id: String, new_id: String) -> Result<Profile, String> {
+pub fn rename_profile_cmd(cache: State<'_, ManifestCache>, id: String, new_id: String) -> Result<ProfileDto, String> {
     let paths = load_paths()?;
-    rename_profile(&paths, &id, &new_id).map_err(|e| e.to_string())
+    let result = rename_profile(&paths, &id, &new_id);
+    forget_profile(&cache, &paths, &id);
+    forget_profile(&cache, &paths, &new_id);
+    result.map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub fn update_profile_version_cmd(
+    cache: State<'_, ManifestCache>,
     id: String,
     mc_version: String,
     loader_type: Option<String>,
     loader_version: Option<String>,
-) -> Result<Profile, String> {
+) -> Result<ProfileDto, String> {
     let paths = load_paths()?;
     let mut profile = load_profile(&paths, &id).map_err(|e| e.to_string())?;
 
@@ -206,8 +248,9 @@ pub fn update_profile_version_cmd(
         _ => None,
     };
 
-    save_profile(&paths, &profile).map_err(|e| e.to_string())?;
-    Ok(profile)
+    save_profile(&paths, &profile).map_err(|e| user_message(&e))?;
+    forget_profile(&cache, &paths, &id);
+    Ok(profile.into())
 }
 
 #[tauri::command]
@@ -219,7 +262,123 @@ pub fn diff_profiles_cmd(a: String, b: String) -> Result<DiffResult, String> {
     Ok(DiffResult { only_a, only_b, both })
 }
 
-fn add_content(
+#[tauri::command]
+pub fn get_profile_notes_cmd(id: String) -> Result<Option<String>, String> {
+    let paths = load_paths()?;
+    load_profile_notes(&paths, &id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_profile_notes_cmd(id: String, notes: String) -> Result<(), String> {
+    let paths = load_paths()?;
+    save_profile_notes(&paths, &id, &notes).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_profile_preset_cmd(
+    cache: State<'_, ManifestCache>,
+    profile_id: String,
+    name: String,
+    preset: ContentPreset,
+) -> Result<ProfileDto, String> {
+    let paths = load_paths()?;
+    let result = save_preset(&paths, &profile_id, &name, preset);
+    forget_profile(&cache, &paths, &profile_id);
+    result.map(Into::into).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_profile_preset_cmd(cache: State<'_, ManifestCache>, profile_id: String, name: String) -> Result<ProfileDto, String> {
+    let paths = load_paths()?;
+    let result = delete_preset(&paths, &profile_id, &name);
+    forget_profile(&cache, &paths, &profile_id);
+    result.map(Into::into).map_err(|e| e.to_string())
+}
+
+/// Switch packs to a preset; the frontend calls this before launching
+#[tauri::command]
+pub fn apply_profile_preset_cmd(cache: State<'_, ManifestCache>, profile_id: String, name: String) -> Result<PresetAppliedDto, String> {
+    let paths = load_paths()?;
+    let result = apply_preset(&paths, &profile_id, &name);
+    forget_profile(&cache, &paths, &profile_id);
+    result.map(Into::into).map_err(|e| user_message(&e))
+}
+
+#[tauri::command]
+pub fn keybind_conflicts_cmd(profile_id: String) -> Result<Vec<KeybindConflict>, String> {
+    let paths = load_paths()?;
+    keybind_conflicts(&paths, &profile_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn adopt_unmanaged_files_cmd(cache: State<'_, ManifestCache>, profile_id: String) -> Result<Vec<AdoptedFileDto>, String> {
+    let paths = load_paths()?;
+    let mut profile = load_profile(&paths, &profile_id).map_err(|e| e.to_string())?;
+    let result = adopt_unmanaged_files(&paths, &mut profile);
+    forget_profile(&cache, &paths, &profile_id);
+    result
+        .map(|files| files.into_iter().map(Into::into).collect())
+        .map_err(|e| user_message(&e))
+}
+
+#[tauri::command]
+pub fn reimport_modified_files_cmd(profile_id: String) -> Result<Vec<AdoptedFileDto>, String> {
+    let paths = load_paths()?;
+    let mut profile = load_profile(&paths, &profile_id).map_err(|e| e.to_string())?;
+    reimport_modified_files(&paths, &mut profile)
+        .map(|files| files.into_iter().map(Into::into).collect())
+        .map_err(|e| user_message(&e))
+}
+
+#[tauri::command]
+pub fn list_shader_settings_cmd(profile_id: String) -> Result<Vec<String>, String> {
+    let paths = load_paths()?;
+    list_shader_settings(&paths, &profile_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn copy_shader_settings_cmd(from: String, to: String, pack: Option<String>) -> Result<Vec<String>, String> {
+    let paths = load_paths()?;
+    copy_shader_settings(&paths, &from, &to, pack.as_deref()).map_err(|e| user_message(&e))
+}
+
+#[tauri::command]
+pub fn install_profile_cmd(id: String, locked: bool) -> Result<InstallSummary, String> {
+    let paths = load_paths()?;
+    if locked {
+        let lock = load_lockfile(&paths.profile_lock(&id)).map_err(|e| e.to_string())?;
+        install_locked(&paths, &id, &lock).map_err(|e| e.to_string())
+    } else {
+        install_profile(&paths, &id).map_err(|e| e.to_string())
+    }
+}
+
+#[tauri::command]
+pub fn export_mrpack_cmd(profile_id: String, out: String) -> Result<MrpackExport, String> {
+    let paths = load_paths()?;
+    export_mrpack(&paths, &profile_id, &PathBuf::from(out)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_extra_folders_cmd(cache: State<'_, ManifestCache>, profile_id: String, folders: Vec<String>) -> Result<ProfileDto, String> {
+    let paths = load_paths()?;
+    let mut profile = load_profile(&paths, &profile_id).map_err(|e| e.to_string())?;
+    let folders: Vec<String> = folders
+        .into_iter()
+        .map(|f| f.trim().trim_matches('/').to_string())
+        .filter(|f| !f.is_empty())
+        .collect();
+    if let Some(bad) = folders.iter().find(|f| !is_plain_folder_name(f)) {
+        return Err(format!("invalid folder name: {bad}"));
+    }
+    profile.files.extra_folders = folders;
+    save_profile(&paths, &profile).map_err(|e| user_message(&e))?;
+    forget_profile(&cache, &paths, &profile_id);
+    Ok(profile.into())
+}
+
+fn add_profile_content(
+    cache: &ManifestCache,
     profile_id: &str,
     input: &str,
     name: Option<String>,
@@ -227,120 +386,92 @@ fn add_content(
     kind: ContentKind,
 ) -> Result<bool, String> {
     let paths = load_paths()?;
-    let mut profile_data = load_profile(&paths, profile_id).map_err(|e| e.to_string())?;
-    let (path, source, file_name_hint) = resolve_input(&paths, input).map_err(|e| e.to_string())?;
-    let stored = store_content(&paths, kind, &path, source.clone(), file_name_hint.clone()).map_err(|e| e.to_string())?;
-
-    // Auto-add to library
-    if let Ok(library) = Library::from_paths(&paths) {
-        let lib_content_type = match kind {
-            ContentKind::Mod => "mod",
-            ContentKind::ResourcePack => "resourcepack",
-            ContentKind::ShaderPack => "shaderpack",
-            ContentKind::Skin => "skin",
-        };
-        let hash = stored.hash.strip_prefix("sha256:").unwrap_or(&stored.hash);
-        let lib_input = LibraryItemInput {
-            hash: hash.to_string(),
-            content_type: Some(lib_content_type.to_string()),
-            name: Some(name.clone().unwrap_or_else(|| stored.name.clone())),
-            file_name: file_name_hint.clone(),
-            source_url: source.clone(),
-            source_platform: if input.contains("modrinth.com") { Some("modrinth".to_string()) }
-                else if input.contains("curseforge.com") { Some("curseforge".to_string()) }
-                else { Some("local".to_string()) },
-            ..Default::default()
-        };
-        if let Ok(lib_item) = library.add_item(&lib_input) {
-            let version_tag = format!("mc:{}", profile_data.mc_version);
-            let _ = library.add_tag_to_item(lib_item.id, &version_tag);
-            if let Some(loader) = profile_data.loader.as_ref() {
-                let loader_tag = format!("loader:{}", loader.loader_type);
-                let _ = library.add_tag_to_item(lib_item.id, &loader_tag);
-            }
-        }
-    }
-
-    let content_ref = ContentRef {
-        name: name.unwrap_or(stored.name),
-        hash: stored.hash,
-        version,
-        source: stored.source,
-        file_name: Some(stored.file_name),
-        platform: None, // Manual import via UI
-        project_id: None,
-        version_id: None,
-        enabled: true,
-        pinned: false,
-    };
-
-    let changed = match kind {
-        ContentKind::Mod => upsert_mod(&mut profile_data, content_ref),
-        ContentKind::ResourcePack => upsert_resourcepack(&mut profile_data, content_ref),
-        ContentKind::ShaderPack => upsert_shaderpack(&mut profile_data, content_ref),
-        ContentKind::Skin => false, // Skins are not added to profiles
-    };
-    save_profile(&paths, &profile_data).map_err(|e| e.to_string())?;
-    Ok(changed)
+    let added = add_content(&paths, profile_id, input, name, version, kind);
+    forget_profile(cache, &paths, profile_id);
+    Ok(added.map_err(|e| user_message(&e))?.changed)
 }
 
-fn remove_content(profile_id: &str, target: &str, kind: ContentKind) -> Result<bool, String> {
+fn remove_profile_content(cache: &ManifestCache, profile_id: &str, target: &str, kind: ContentKind) -> Result<bool, String> {
     let paths = load_paths()?;
-    let mut profile_data = load_profile(&paths, profile_id).map_err(|e| e.to_string())?;
-    let changed = match kind {
-        ContentKind::Mod => remove_mod(&mut profile_data, target),
-        ContentKind::ResourcePack => remove_resourcepack(&mut profile_data, target),
-        ContentKind::ShaderPack => remove_shaderpack(&mut profile_data, target),
-        ContentKind::Skin => false, // Skins are not removed from profiles
-    };
-    if changed {
-        save_profile(&paths, &profile_data).map_err(|e| e.to_string())?;
-    }
-    Ok(changed)
+    let result = remove_content(&paths, profile_id, target, kind);
+    forget_profile(cache, &paths, profile_id);
+    result.map_err(|e| user_message(&e))
+}
+
+#[tauri::command]
+pub fn add_mod_cmd(
+    cache: State<'_, ManifestCache>,
+    profile_id: String,
+    input: String,
+    name: Option<String>,
+    version: Option<String>,
+) -> Result<bool, String> {
+    add_profile_content(&cache, &profile_id, &input, name, version, ContentKind::Mod)
 }
 
 #[tauri::command]
-pub fn add_mod_cmd(profile_id: String, input: String, name: Option<String>, version: Option<String>) -> Result<bool, String> {
-    add_content(&profile_id, &input, name, version, ContentKind::Mod)
+pub fn add_resourcepack_cmd(
+    cache: State<'_, ManifestCache>,
+    profile_id: String,
+    input: String,
+    name: Option<String>,
+    version: Option<String>,
+) -> Result<bool, String> {
+    add_profile_content(&cache, &profile_id, &input, name, version, ContentKind::ResourcePack)
 }
 
 #[tauri::command]
-pub fn add_resourcepack_cmd(profile_id: String, input: String, name: Option<String>, version: Option<String>) -> Result<bool, String> {
-    add_content(&profile_id, &input, name, version, ContentKind::ResourcePack)
+pub fn add_shaderpack_cmd(
+    cache: State<'_, ManifestCache>,
+    profile_id: String,
+    input: String,
+    name: Option<String>,
+    version: Option<String>,
+) -> Result<bool, String> {
+    add_profile_content(&cache, &profile_id, &input, name, version, ContentKind::ShaderPack)
 }
 
+/// Pack metadata and reasons Minecraft would ignore it, checked against the
+/// profile's version when one is given
 #[tauri::command]
-pub fn add_shaderpack_cmd(profile_id: String, input: String, name: Option<String>, version: Option<String>) -> Result<bool, String> {
-    add_content(&profile_id, &input, name, version, ContentKind::ShaderPack)
+pub fn inspect_pack_cmd(input: String, kind: PackKind, profile_id: Option<String>) -> Result<PackInspection, String> {
+    let paths = load_paths()?;
+    let mc_version = match profile_id {
+        Some(id) => Some(load_profile(&paths, &id).map_err(|e| e.to_string())?.mc_version),
+        None => None,
+    };
+    let (path, _, _) = resolve_input(&paths, &input).map_err(|e| e.to_string())?;
+    inspect_pack(&path, kind, mc_version.as_deref()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn remove_mod_cmd(profile_id: String, target: String) -> Result<bool, String> {
-    remove_content(&profile_id, &target, ContentKind::Mod)
+pub fn remove_mod_cmd(cache: State<'_, ManifestCache>, profile_id: String, target: String) -> Result<bool, String> {
+    remove_profile_content(&cache, &profile_id, &target, ContentKind::Mod)
 }
 
 #[tauri::command]
-pub fn remove_resourcepack_cmd(profile_id: String, target: String) -> Result<bool, String> {
-    remove_content(&profile_id, &target, ContentKind::ResourcePack)
+pub fn remove_resourcepack_cmd(cache: State<'_, ManifestCache>, profile_id: String, target: String) -> Result<bool, String> {
+    remove_profile_content(&cache, &profile_id, &target, ContentKind::ResourcePack)
 }
 
 #[tauri::command]
-pub fn remove_shaderpack_cmd(profile_id: String, target: String) -> Result<bool, String> {
-    remove_content(&profile_id, &target, ContentKind::ShaderPack)
+pub fn remove_shaderpack_cmd(cache: State<'_, ManifestCache>, profile_id: String, target: String) -> Result<bool, String> {
+    remove_profile_content(&cache, &profile_id, &target, ContentKind::ShaderPack)
 }
 
 #[tauri::command]
-pub fn list_accounts_cmd() -> Result<Accounts, String> {
+pub fn list_accounts_cmd(cache: State<'_, ManifestCache>) -> Result<Accounts, String> {
     let paths = load_paths()?;
-    load_accounts(&paths).map_err(|e| e.to_string())
+    cache.get_or_load(&paths.accounts, || load_accounts(&paths))
 }
 
 #[tauri::command]
-pub fn set_active_account_cmd(id: String) -> Result<(), String> {
+pub fn set_active_account_cmd(cache: State<'_, ManifestCache>, id: String) -> Result<(), String> {
     let paths = load_paths()?;
     let mut accounts = load_accounts(&paths).map_err(|e| e.to_string())?;
     if set_active(&mut accounts, &id) {
-        save_accounts(&paths, &accounts).map_err(|e| e.to_string())?;
+        save_accounts(&paths, &accounts).map_err(|e| user_message(&e))?;
+        cache.invalidate(&paths.accounts);
         Ok(())
     } else {
         Err("account not found".to_string())
@@ -348,11 +479,18 @@ pub fn set_active_account_cmd(id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn remove_account_cmd(id: String) -> Result<(), String> {
+pub fn remove_account_cmd(
+    tokens: State<'_, ConfirmTokens>,
+    cache: State<'_, ManifestCache>,
+    id: String,
+    confirm_token: String,
+) -> Result<(), String> {
+    tokens.redeem(&confirm_token, "remove_account_cmd", &id)?;
     let paths = load_paths()?;
     let mut accounts = load_accounts(&paths).map_err(|e| e.to_string())?;
     if remove_account(&mut accounts, &id) {
-        save_accounts(&paths, &accounts).map_err(|e| e.to_string())?;
+        save_accounts(&paths, &accounts).map_err(|e| user_message(&e))?;
+        cache.invalidate(&paths.accounts);
         Ok(())
     } else {
         Err("account not found".to_string())
@@ -360,19 +498,44 @@ pub fn remove_account_cmd(id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn get_config_cmd() -> Result<Config, String> {
+pub fn get_config_cmd(cache: State<'_, ManifestCache>) -> Result<ConfigDto, String> {
     let paths = load_paths()?;
-    load_config(&paths).map_err(|e| e.to_string())
+    cache.get_or_load(&paths.config, || load_config(&paths)).map(Into::into)
+}
+
+/// Drop all cached manifests, e.g. after editing files by hand
+#[tauri::command]
+pub fn bust_manifest_cache_cmd(cache: State<'_, ManifestCache>) {
+    cache.clear();
 }
 
 #[tauri::command]
-pub fn save_config_cmd(client_id: Option<String>, client_secret: Option<String>) -> Result<Config, String> {
+pub fn save_config_cmd(cache: State<'_, ManifestCache>, client_id: Option<String>, client_secret: Option<String>) -> Result<ConfigDto, String> {
     let paths = load_paths()?;
     let mut config = load_config(&paths).map_err(|e| e.to_string())?;
     config.msa_client_id = client_id.filter(|v| !v.trim().is_empty());
     config.msa_client_secret = client_secret.filter(|v| !v.trim().is_empty());
-    save_config(&paths, &config).map_err(|e| e.to_string())?;
-    Ok(config)
+    save_config(&paths, &config).map_err(|e| user_message(&e))?;
+    cache.invalidate(&paths.config);
+    Ok(config.into())
+}
+
+/// Check a CurseForge API key, or the configured one, and record its health
+#[tauri::command]
+pub async fn verify_curseforge_key_cmd(api_key: Option<String>) -> Result<ApiKeyHealth, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let paths = load_paths()?;
+        let key = match api_key.filter(|v| !v.trim().is_empty()) {
+            Some(key) => key,
+            None => load_config(&paths)
+                .map_err(|e| e.to_string())?
+                .curseforge_api_key
+                .ok_or_else(|| "No CurseForge API key configured".to_string())?,
+        };
+        verify_curseforge_key(&paths, &key).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
@@ -384,24 +547,147 @@ pub fn request_device_code_cmd(client_id: Option<String>, client_secret: Option<
 
 #[tauri::command]
 pub fn finish_device_code_flow_cmd(
+    cache: State<'_, ManifestCache>,
     client_id: Option<String>,
     client_secret: Option<String>,
     device: DeviceCode,
 ) -> Result<Account, String> {
     let paths = load_paths()?;
     let (id, secret) = resolve_credentials(&paths, client_id, client_secret)?;
-    finish_device_code_flow(&paths, &id, secret.as_deref(), &device).map_err(|e| e.to_string())
+    let account = finish_device_code_flow(&paths, &id, secret.as_deref(), &device).map_err(|e| e.to_string())?;
+    cache.invalidate(&paths.accounts);
+    Ok(account)
+}
+
+/// Sign in through the system browser with a localhost redirect. Resolves
+/// once the browser comes back; the device code flow stays available when
+/// the browser can't reach the redirect port.
+#[tauri::command]
+pub async fn start_browser_auth_cmd(
+    app: AppHandle,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+) -> Result<Account, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let paths = load_paths()?;
+        let (id, secret) = resolve_credentials(&paths, client_id, client_secret)?;
+        let auth = start_browser_auth(&id).map_err(|e| e.to_string())?;
+        app.opener()
+            .open_url(auth.auth_url.clone(), None::<&str>)
+            .map_err(|e| format!("failed to open browser: {e}"))?;
+        let account = finish_browser_auth_flow(&paths, &id, secret.as_deref(), auth).map_err(|e| e.to_string())?;
+        app.state::<ManifestCache>().invalidate(&paths.accounts);
+        Ok(account)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn export_account_cmd(id: String, passphrase: String, path: String) -> Result<(), String> {
+    let paths = load_paths()?;
+    let data = export_account(&paths, &id, &passphrase).map_err(|e| e.to_string())?;
+    std::fs::write(&path, data).map_err(|e| format!("Failed to write export: {}", e))
+}
+
+#[tauri::command]
+pub fn import_account_cmd(cache: State<'_, ManifestCache>, path: String, passphrase: String) -> Result<Account, String> {
+    let paths = load_paths()?;
+    let data = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read export: {}", e))?;
+    let account = import_account(&paths, &data, &passphrase).map_err(|e| e.to_string())?;
+    cache.invalidate(&paths.accounts);
+    Ok(account)
 }
 
 #[tauri::command]
-pub fn prepare_profile_cmd(profile_id: String, account_id: Option<String>) -> Result<LaunchPlanDto, String> {
+pub fn prepare_profile_cmd(
+    cache: State<'_, ManifestCache>,
+    profile_id: String,
+    account_id: Option<String>,
+) -> Result<LaunchPlanDto, String> {
     let paths = load_paths()?;
     let profile = load_profile(&paths, &profile_id).map_err(|e| e.to_string())?;
     let account = resolve_launch_account(&paths, account_id).map_err(|e| e.to_string())?;
-    let plan = prepare(&paths, &profile, &account).map_err(|e| e.to_string())?;
+    cache.invalidate(&paths.accounts);
+    let plan = prepare(&paths, &profile, &account, None);
+    forget_profile(&cache, &paths, &profile_id);
+    let plan = plan.map_err(|e| e.to_string())?;
     Ok(LaunchPlanDto::from(plan))
 }
 
+/// Warm up a profile in the background so its first launch is instant,
+/// reporting each stage
+#[tauri::command]
+pub fn prepare_in_background_cmd(app: AppHandle, profile_id: String) -> Result<(), String> {
+    let paths = load_paths()?;
+    let profile = load_profile(&paths, &profile_id).map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let emit = |stage: &str, message: Option<String>| {
+            let _ = app.emit("prefetch-status", PrefetchEvent {
+                profile_id: profile.id.clone(),
+                stage: stage.to_string(),
+                message,
+            });
+        };
+        // The files are fetched at the first launch instead
+        if is_metered(&paths) {
+            emit("deferred", Some("Skipped on a metered connection".to_string()));
+            return;
+        }
+        match warm_up(&paths, &profile, |stage| emit(stage.as_str(), Some(stage.label().to_string()))) {
+            Ok(_) => emit("done", None),
+            Err(err) => emit("error", Some(err.to_string())),
+        }
+    });
+    Ok(())
+}
+
+/// Game files that failed to download and were queued
+#[tauri::command]
+pub fn list_failed_downloads_cmd() -> Result<Vec<FailedDownload>, String> {
+    let paths = load_paths()?;
+    load_download_queue(&paths).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn retry_failed_downloads_cmd() -> Result<QueueRetryResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let paths = load_paths()?;
+        run_job(&paths, JobKind::Download, "download queue", |job| retry_download_queue(&paths, job))
+            .map_err(|e| user_message(&e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Re-hash a profile's game files and download missing or corrupt ones again
+#[tauri::command]
+pub async fn repair_instance_cmd(profile_id: String) -> Result<GameFilesReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let paths = load_paths()?;
+        let profile = load_profile(&paths, &profile_id).map_err(|e| e.to_string())?;
+        run_job(&paths, JobKind::Audit, &profile.id, |job| {
+            verify_game_files(&paths, &profile, job, &mut Throttle::unlimited(job.cancel_token()))
+        })
+        .map_err(|e| user_message(&e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// What launching a profile would download, to confirm on metered connections
+#[tauri::command]
+pub async fn estimate_prepare_cmd(profile_id: String) -> Result<PrepareEstimateDto, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let paths = load_paths()?;
+        let profile = load_profile(&paths, &profile_id).map_err(|e| e.to_string())?;
+        estimate_prepare(&paths, &profile).map(PrepareEstimateDto::from).map_err(|e| user_message(&e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
 pub fn launch_profile_cmd(app: AppHandle, profile_id: String, account_id: Option<String>) -> Result<(), String> {
     let app_handle = app.clone();
@@ -410,16 +696,18 @@ pub fn launch_profile_cmd(app: AppHandle, profile_id: String, account_id: Option
     let _ = app.emit("launch-status", LaunchEvent {
         stage: "queued".to_string(),
         message: Some("Starting launch...".to_string()),
+        crash: None,
     });
 
     // Use spawn_blocking for blocking I/O operations (HTTP requests, file I/O)
     tauri::async_runtime::spawn_blocking(move || {
         match run_launch(app_handle.clone(), profile_id.clone(), account_id) {
             Ok(()) => {}
-            Err(err) => {
+            Err(failure) => {
                 let _ = app_handle.emit("launch-status", LaunchEvent {
                     stage: "error".to_string(),
-                    message: Some(err),
+                    message: Some(failure.message),
+                    crash: failure.crash.map(Into::into),
                 });
             }
         }
@@ -433,72 +721,148 @@ pub fn instance_path_cmd(profile_id: String) -> Result<String, String> {
     Ok(paths.instance_dir(&profile_id).to_string_lossy().to_string())
 }
 
-fn run_launch(app: AppHandle, profile_id: String, account_id: Option<String>) -> Result<(), String> {
+#[tauri::command]
+pub fn get_playtime_stats_cmd(profile_id: String) -> Result<PlaytimeStats, String> {
+    let paths = load_paths()?;
+    load_playtime(&paths, &profile_id).map_err(|e| e.to_string())
+}
+
+/// Why a launch failed, with what the game left behind if it crashed
+struct LaunchFailure {
+    message: String,
+    crash: Option<SessionCrash>,
+}
+
+impl From<String> for LaunchFailure {
+    fn from(message: String) -> Self {
+        Self { message, crash: None }
+    }
+}
+
+fn run_launch(app: AppHandle, profile_id: String, account_id: Option<String>) -> Result<(), LaunchFailure> {
     let _ = app.emit("launch-status", LaunchEvent {
         stage: "preparing".to_string(),
         message: Some("Downloading game files...".to_string()),
+        crash: None,
     });
 
     let paths = load_paths()?;
     let profile = load_profile(&paths, &profile_id).map_err(|e| format!("Failed to load profile: {}", e))?;
     let account = resolve_launch_account(&paths, account_id).map_err(|e| format!("Failed to resolve account: {}", e))?;
-    let plan = prepare(&paths, &profile, &account).map_err(|e| format!("Failed to prepare launch: {}", e))?;
+    let mut plan = prepare_with_progress(&paths, &profile, &account, None, |stage| {
+        let _ = app.emit("launch-status", LaunchEvent {
+            stage: "preparing".to_string(),
+            message: Some(format!("{}...", stage.label())),
+            crash: None,
+        });
+    })
+    .map_err(|e| {
+        // Kind, hint and log location of a failed loader install
+        if let Some(installer) = find_installer_error(&e) {
+            let _ = app.emit("installer-error", installer);
+        }
+        format!("Failed to prepare launch: {}", e)
+    })?;
+    refresh_plan_account(&paths, &mut plan, &account).map_err(|e| format!("Failed to refresh account: {}", e))?;
+    // Preparing adopts hand-added files into the profile and refreshing
+    // rewrites the account's tokens
+    let manifests = app.state::<ManifestCache>();
+    forget_profile(&manifests, &paths, &profile_id);
+    manifests.invalidate(&paths.accounts);
+
+    // Dropped when this function returns, which stops the tunnel with the game
+    let mut tunnel = start_profile_tunnel(&paths, &profile).map_err(|e| format!("Failed to start tunnel: {}", e))?;
+    if let Some(handle) = tunnel.as_mut() {
+        let address = handle.wait_for_address(Duration::from_secs(15));
+        let _ = app.emit("launch-status", LaunchEvent {
+            stage: "tunnel".to_string(),
+            message: Some(match address {
+                Some(address) => address,
+                None => format!("{} tunnel started", handle.provider().as_str()),
+            }),
+            crash: None,
+        });
+    }
 
     let _ = app.emit("launch-status", LaunchEvent {
         stage: "launching".to_string(),
         message: Some("Starting Minecraft...".to_string()),
+        crash: None,
     });
 
-    let mut child = Command::new(&plan.java_exec)
-        .args(&plan.jvm_args)
-        .arg("-cp")
-        .arg(&plan.classpath)
-        .arg(&plan.main_class)
-        .args(&plan.game_args)
-        .current_dir(&plan.instance_dir)
+    let started_at = now_epoch_secs();
+    let mut child = game_command(&plan)
         .spawn()
-        .map_err(|e| format!("Failed to start Java: {}", e))?;
+        .map_err(|e| format!("Failed to start {}: {}", plan.program(), e))?;
+    plan.check_scheduling(child.id());
+    // Best effort; lets the running instance be listed and stopped
+    let instance = register_instance(&paths, &profile_id, child.id(), started_at).ok();
+
+    let metrics_app = app.clone();
+    let metrics_profile = profile_id.clone();
+    let monitor = ResourceMonitor::start(child.id(), DEFAULT_SAMPLE_INTERVAL, move |metrics| {
+        let _ = metrics_app.emit("instance-metrics", InstanceMetricsEvent::new(metrics_profile.clone(), metrics));
+    });
 
     let _ = app.emit("launch-status", LaunchEvent {
         stage: "running".to_string(),
         message: Some("Minecraft is running".to_string()),
+        crash: None,
     });
 
-    let status = child.wait().map_err(|e| format!("Failed to wait for process: {}", e))?;
+    let status = child.wait().map_err(|e| format!("Failed to wait for process: {}", e));
+    let metrics = monitor.stop();
+    if let Some(instance) = &instance {
+        unregister_instance(&paths, instance);
+    }
+    let status = status?;
+    let _ = record_session(&paths, &profile_id, SessionRecord::new(started_at, &metrics, status.code()));
+
+    // Best effort; a no-op unless crash aggregation is enabled
+    let _ = collect_profile_crashes(&paths, &profile_id);
 
-    if !status.success() {
-        return Err(format!("Minecraft exited with status {}", status));
+    if let Some(crash) = detect_crash(&paths, &profile_id, started_at, status) {
+        return Err(LaunchFailure {
+            message: format!("Minecraft exited with status {}", crash.status),
+            crash: Some(crash),
+        });
     }
 
     let _ = app.emit("launch-status", LaunchEvent {
         stage: "done".to_string(),
         message: None,
+        crash: None,
     });
 
     Ok(())
 }
 
-impl From<LaunchPlan> for LaunchPlanDto {
-    fn from(plan: LaunchPlan) -> Self {
-        Self {
-            instance_dir: plan.instance_dir.to_string_lossy().to_string(),
-            java_exec: plan.java_exec,
-            jvm_args: plan.jvm_args,
-            classpath: plan.classpath,
-            main_class: plan.main_class,
-            game_args: plan.game_args,
+// ==================== Account Info / Skin / Cape Commands ====================
+
+/// Account info from the last fetch or warm-up pass, keyed by account uuid
+#[derive(Default)]
+pub struct AccountInfoCache(Mutex<HashMap<String, AccountInfo>>);
+
+impl AccountInfoCache {
+    fn get(&self, id: &str) -> Option<AccountInfo> {
+        self.0.lock().ok().and_then(|entries| entries.get(id).cloned())
+    }
+
+    fn insert(&self, info: &AccountInfo) {
+        if let Ok(mut entries) = self.0.lock() {
+            entries.insert(info.uuid.clone(), info.clone());
         }
     }
 }
 
-// ==================== Account Info / Skin / Cape Commands ====================
-
-#[tauri::command]
-pub fn get_account_info_cmd(id: Option<String>) -> Result<AccountInfo, String> {
-    let paths = load_paths()?;
+/// Serve a file cached in the store through the asset protocol
+fn asset_url(path: &std::path::Path) -> String {
+    format!("asset://localhost/{}", path.to_string_lossy().replace('\\', "/"))
+}
 
+fn fetch_account_info(paths: &Paths, id: Option<String>) -> Result<AccountInfo, String> {
     // Ensure tokens are fresh before fetching profile
-    let account = ensure_fresh_account(&paths, id.clone()).map_err(|e| e.to_string())?;
+    let account = ensure_fresh_account(paths, id).map_err(|e| e.to_string())?;
 
     let profile = get_mc_profile(&account.minecraft.access_token).ok();
 
@@ -521,10 +885,7 @@ pub fn get_account_info_cmd(id: Option<String>) -> Result<AccountInfo, String> {
 
     // Download and cache the skin to local store, return asset:// URL
     let skin_url = match download_and_cache_skin(&raw_skin_url, &paths.store_skins) {
-        Ok(cached_path) => {
-            // Return as asset:// URL for Tauri to serve
-            format!("asset://localhost/{}", cached_path.to_string_lossy().replace('\\', "/"))
-        }
+        Ok(cached_path) => asset_url(&cached_path),
         Err(_) => {
             // Fallback to mc-heads.net which has CORS support
             get_skin_url(&account.uuid)
@@ -534,19 +895,23 @@ pub fn get_account_info_cmd(id: Option<String>) -> Result<AccountInfo, String> {
     // Download and cache the cape if available
     let cape_url = if let Some(ref url) = raw_cape_url {
         match download_and_cache_cape(url, &paths.store_skins) {
-            Ok(Some(cached_path)) => {
-                format!("asset://localhost/{}", cached_path.to_string_lossy().replace('\\', "/"))
-            }
+            Ok(Some(cached_path)) => asset_url(&cached_path),
             _ => get_cape_url(&account.uuid)
         }
     } else {
         get_cape_url(&account.uuid)
     };
 
+    // Renders are cached the same way so the account list doesn't refetch them
+    let remote_avatar_url = get_avatar_url(&account.uuid, 128);
+    let avatar_url = download_and_cache_skin(&remote_avatar_url, &paths.store_skins)
+        .map(|cached_path| asset_url(&cached_path))
+        .unwrap_or(remote_avatar_url);
+
     Ok(AccountInfo {
         uuid: account.uuid.clone(),
         username: account.username.clone(),
-        avatar_url: get_avatar_url(&account.uuid, 128),
+        avatar_url,
         body_url: get_body_url(&account.uuid, 256),
         skin_url,
         cape_url,
@@ -555,7 +920,64 @@ pub fn get_account_info_cmd(id: Option<String>) -> Result<AccountInfo, String> {
 }
 
 #[tauri::command]
-pub fn upload_skin_cmd(id: Option<String>, path: String, variant: String, save_to_library: Option<bool>) -> Result<Option<LibraryItem>, String> {
+pub fn get_account_info_cmd(
+    cache: State<'_, AccountInfoCache>,
+    manifests: State<'_, ManifestCache>,
+    id: Option<String>,
+) -> Result<AccountInfo, String> {
+    let paths = load_paths()?;
+    let info = fetch_account_info(&paths, id);
+    // Fetching refreshes expired tokens
+    manifests.invalidate(&paths.accounts);
+    let info = info?;
+    cache.insert(&info);
+    Ok(info)
+}
+
+/// Account info cached by the last fetch, for rendering before a fresh one
+/// arrives. `None` until the account was fetched or warmed.
+#[tauri::command]
+pub fn get_cached_account_info_cmd(cache: State<'_, AccountInfoCache>, id: Option<String>) -> Result<Option<AccountInfo>, String> {
+    let id = match id {
+        Some(id) => id,
+        None => {
+            let paths = load_paths()?;
+            match load_accounts(&paths).map_err(|e| e.to_string())?.active {
+                Some(active) => active,
+                None => return Ok(None),
+            }
+        }
+    };
+    Ok(cache.get(&id))
+}
+
+/// Refresh tokens and cache skins, capes and avatars of every account in
+/// one background pass, emitting `account-cache-warmed` when done
+#[tauri::command]
+pub fn warm_account_cache_cmd(app: AppHandle) -> Result<(), String> {
+    let paths = load_paths()?;
+    let accounts = load_accounts(&paths).map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let cache = app.state::<AccountInfoCache>();
+        let mut event = AccountCacheEvent { warmed: 0, failed: Vec::new() };
+        for account in &accounts.accounts {
+            match fetch_account_info(&paths, Some(account.uuid.clone())) {
+                Ok(info) => {
+                    cache.insert(&info);
+                    event.warmed += 1;
+                }
+                Err(err) => event.failed.push(format!("{}: {err}", account.username)),
+            }
+        }
+        app.state::<ManifestCache>().invalidate(&paths.accounts);
+        let _ = app.emit("account-cache-warmed", event);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn upload_skin_cmd(id: Option<String>, path: String, variant: String, save_to_library: Option<bool>) -> Result<Option<LibraryItemDto>, String> {
     let paths = load_paths()?;
     let accounts = load_accounts(&paths).map_err(|e| e.to_string())?;
 
@@ -576,7 +998,7 @@ pub fn upload_skin_cmd(id: Option<String>, path: String, variant: String, save_t
         let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
         let item = library.import_file(&paths, &skin_path, LibraryContentType::Skin)
             .map_err(|e| e.to_string())?;
-        Ok(Some(item))
+        Ok(Some(item.into()))
     } else {
         Ok(None)
     }
@@ -684,19 +1106,52 @@ pub fn list_templates_cmd() -> Result<Vec<String>, String> {
 }
 
 #[tauri::command]
-pub fn load_template_cmd(id: String) -> Result<Template, String> {
+pub fn load_template_cmd(id: String) -> Result<TemplateDto, String> {
     let paths = load_paths()?;
     init_builtin_templates(&paths).map_err(|e| e.to_string())?;
-    load_template(&paths, &id).map_err(|e| e.to_string())
+    load_template(&paths, &id).map(Into::into).map_err(|e| e.to_string())
 }
 
+/// Built-in setup recipes for the quickstart wizard
 #[tauri::command]
-pub fn create_profile_from_template_cmd(input: CreateProfileInput) -> Result<Profile, String> {
+pub fn list_quickstart_recipes_cmd() -> Vec<Recipe> {
+    RECIPES.to_vec()
+}
+
+/// Create a profile from a quickstart recipe, resolving current versions
+#[tauri::command]
+pub async fn quickstart_cmd(
+    cache: State<'_, ManifestCache>,
+    recipe: String,
+    profile_id: String,
+    mc_version: Option<String>,
+) -> Result<QuickstartSummaryDto, String> {
+    let paths = load_paths()?;
+    let summary = tauri::async_runtime::spawn_blocking({
+        let paths = paths.clone();
+        let profile_id = profile_id.clone();
+        move || {
+            let recipe = find_recipe(&recipe).map_err(|e| e.to_string())?;
+            let store = ContentStore::modrinth_only();
+            run_quickstart(&paths, &store, recipe, &profile_id, mc_version.as_deref())
+                .map_err(|e| user_message(&e))
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    forget_profile(&cache, &paths, &profile_id);
+    summary.map(Into::into)
+}
+
+#[tauri::command]
+pub fn create_profile_from_template_cmd(cache: State<'_, ManifestCache>, input: CreateProfileInput) -> Result<ProfileDto, String> {
     let paths = load_paths()?;
 
     if let Some(template_id) = input.template {
         init_builtin_templates(&paths).map_err(|e| e.to_string())?;
         let template = load_template(&paths, &template_id).map_err(|e| e.to_string())?;
+        template.validate_pins().map_err(|e| e.to_string())?;
+        let algorithm = configured_store_hash(&paths);
 
         let loader = template.loader.map(|l| Loader {
             loader_type: l.loader_type,
@@ -711,6 +1166,11 @@ pub fn create_profile_from_template_cmd(input: CreateProfileInput) -> Result<Pro
             } else {
                 template.runtime.args
             },
+            wrapper: input.wrapper.unwrap_or_default().split_whitespace().map(String::from).collect(),
+            jvm_preset: input.jvm_preset,
+            priority: input.priority,
+            cpu_affinity: input.cpu_affinity.unwrap_or_default(),
+            gamescope: input.gamescope,
         };
 
         let mut profile = create_profile(&paths, &input.id, &template.mc_version, loader.clone(), runtime)
@@ -725,13 +1185,16 @@ pub fn create_profile_from_template_cmd(input: CreateProfileInput) -> Result<Pro
                 continue;
             }
             if let shard::template::ContentSource::Modrinth { project } = &mod_content.source {
-                if let Ok(version) = store.get_latest_version(
-                    Platform::Modrinth,
+                let pin = mod_content.version_pin().map_err(|e| e.to_string())?;
+                if let Ok(version) = resolve_template_version(
+                    &store,
                     project,
+                    &pin,
                     Some(&template.mc_version),
                     loader_type,
                 ) {
-                    if let Ok(content_ref) = store.download_to_store(&paths, &version, ContentType::Mod) {
+                    if let Ok(mut content_ref) = store.download_to_store(&paths, &version, ContentType::Mod, algorithm) {
+                        content_ref.pinned = pin.is_pinned();
                         upsert_mod(&mut profile, content_ref);
                     }
                 }
@@ -743,8 +1206,10 @@ pub fn create_profile_from_template_cmd(input: CreateProfileInput) -> Result<Pro
                 continue;
             }
             if let shard::template::ContentSource::Modrinth { project } = &shader.source {
-                if let Ok(version) = store.get_latest_version(Platform::Modrinth, project, None, None) {
-                    if let Ok(content_ref) = store.download_to_store(&paths, &version, ContentType::ShaderPack) {
+                let pin = shader.version_pin().map_err(|e| e.to_string())?;
+                if let Ok(version) = resolve_template_version(&store, project, &pin, None, None) {
+                    if let Ok(mut content_ref) = store.download_to_store(&paths, &version, ContentType::ShaderPack, algorithm) {
+                        content_ref.pinned = pin.is_pinned();
                         upsert_shaderpack(&mut profile, content_ref);
                     }
                 }
@@ -756,16 +1221,19 @@ pub fn create_profile_from_template_cmd(input: CreateProfileInput) -> Result<Pro
                 continue;
             }
             if let shard::template::ContentSource::Modrinth { project } = &pack.source {
-                if let Ok(version) = store.get_latest_version(Platform::Modrinth, project, None, None) {
-                    if let Ok(content_ref) = store.download_to_store(&paths, &version, ContentType::ResourcePack) {
+                let pin = pack.version_pin().map_err(|e| e.to_string())?;
+                if let Ok(version) = resolve_template_version(&store, project, &pin, None, None) {
+                    if let Ok(mut content_ref) = store.download_to_store(&paths, &version, ContentType::ResourcePack, algorithm) {
+                        content_ref.pinned = pin.is_pinned();
                         upsert_resourcepack(&mut profile, content_ref);
                     }
                 }
             }
         }
 
-        save_profile(&paths, &profile).map_err(|e| e.to_string())?;
-        Ok(profile)
+        save_profile(&paths, &profile).map_err(|e| user_message(&e))?;
+        forget_profile(&cache, &paths, &profile.id);
+        Ok(profile.into())
     } else {
         // No template, create regular profile
         let loader = match (input.loader_type, input.loader_version) {
@@ -781,14 +1249,25 @@ pub fn create_profile_from_template_cmd(input: CreateProfileInput) -> Result<Pro
             .split_whitespace()
             .map(|s| s.to_string())
             .collect::<Vec<_>>();
+        let wrapper = input.wrapper.unwrap_or_default()
+            .split_whitespace()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
         let runtime = Runtime {
             java: input.java.filter(|v| !v.trim().is_empty()),
             memory: input.memory.filter(|v| !v.trim().is_empty()),
             args,
+            wrapper,
+            jvm_preset: input.jvm_preset,
+            priority: input.priority,
+            cpu_affinity: input.cpu_affinity.unwrap_or_default(),
+            gamescope: input.gamescope,
         };
 
-        create_profile(&paths, &input.id, &input.mc_version, loader, runtime)
-            .map_err(|e| e.to_string())
+        let profile = create_profile(&paths, &input.id, &input.mc_version, loader, runtime)
+            .map_err(|e| e.to_string())?;
+        forget_profile(&cache, &paths, &profile.id);
+        Ok(profile.into())
     }
 }
 
@@ -813,7 +1292,7 @@ fn parse_content_type(s: &str) -> Result<ContentType, String> {
 }
 
 #[tauri::command]
-pub fn store_search_cmd(input: StoreSearchInput) -> Result<Vec<ContentItem>, String> {
+pub fn store_search_cmd(input: StoreSearchInput) -> Result<Vec<ContentItemDto>, String> {
     let paths = load_paths()?;
     let config = load_config(&paths).map_err(|e| e.to_string())?;
     let has_cf_key = config.curseforge_api_key.is_some();
@@ -832,25 +1311,28 @@ pub fn store_search_cmd(input: StoreSearchInput) -> Result<Vec<ContentItem>, Str
         offset: 0,
     };
 
-    match input.platform.as_deref() {
-        Some("modrinth") => store.search_modrinth(&options).map_err(|e| e.to_string()),
+    let items = match input.platform.as_deref() {
+        Some("modrinth") => store.search_modrinth(&options),
         Some("curseforge") => {
             if !has_cf_key {
                 return Err("CurseForge search requires an API key. Add it in Settings.".to_string());
             }
-            store.search_curseforge_only(&options).map_err(|e| e.to_string())
+            store.search_curseforge_only(&options)
         }
-        _ => store.search(&options).map_err(|e| e.to_string()),
-    }
+        _ => store.search(&options),
+    };
+    items
+        .map(|items| items.into_iter().map(Into::into).collect())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn store_get_project_cmd(project_id: String, platform: String) -> Result<ContentItem, String> {
+pub fn store_get_project_cmd(project_id: String, platform: String) -> Result<ContentItemDto, String> {
     let paths = load_paths()?;
     let config = load_config(&paths).map_err(|e| e.to_string())?;
     let store = ContentStore::new(config.curseforge_api_key.as_deref());
     let platform = parse_platform(&platform)?;
-    store.get_project(platform, &project_id).map_err(|e| e.to_string())
+    store.get_project(platform, &project_id).map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -860,7 +1342,7 @@ pub fn store_get_versions_cmd(
     game_version: Option<String>,
     loader: Option<String>,
     profile_id: Option<String>,
-) -> Result<Vec<ContentVersion>, String> {
+) -> Result<Vec<ContentVersionDto>, String> {
     let paths = load_paths()?;
     let config = load_config(&paths).map_err(|e| e.to_string())?;
     let store = ContentStore::new(config.curseforge_api_key.as_deref());
@@ -888,16 +1370,66 @@ pub fn store_get_versions_cmd(
     };
 
     store.get_versions(platform, &project_id, game_version.as_deref(), effective_loader.as_deref())
+        .map(|versions| versions.into_iter().map(Into::into).collect())
         .map_err(|e| e.to_string())
 }
 
+/// Find translation resource packs for a profile's mods
+#[tauri::command]
+pub async fn find_locale_packs_cmd(profile_id: String, language: String) -> Result<LocaleReportDto, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let paths = load_paths()?;
+        let profile = load_profile(&paths, &profile_id).map_err(|e| e.to_string())?;
+        let store = ContentStore::modrinth_only();
+        find_locale_packs(&store, &profile, &language).map(Into::into).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn install_locale_packs_cmd(
+    cache: State<'_, ManifestCache>,
+    profile_id: String,
+    matches: Vec<LocaleMatchDto>,
+) -> Result<ProfileDto, String> {
+    let paths = load_paths()?;
+    let store = ContentStore::modrinth_only();
+    let matches: Vec<_> = matches.into_iter().map(Into::into).collect();
+    let result = install_locale_packs(&paths, &store, &profile_id, &matches);
+    forget_profile(&cache, &paths, &profile_id);
+    result.map_err(|e| user_message(&e))?;
+    load_profile(&paths, &profile_id).map(Into::into).map_err(|e| e.to_string())
+}
+
+/// License and dependency details to show before installing a version
 #[tauri::command]
-pub fn store_install_cmd(input: StoreInstallInput) -> Result<Profile, String> {
+pub fn store_install_disclosure_cmd(
+    project_id: String,
+    platform: String,
+    version_id: String,
+) -> Result<InstallDisclosureDto, String> {
     let paths = load_paths()?;
     let config = load_config(&paths).map_err(|e| e.to_string())?;
     let store = ContentStore::new(config.curseforge_api_key.as_deref());
+    let platform = parse_platform(&platform)?;
 
-    let mut profile = load_profile(&paths, &input.profile_id).map_err(|e| e.to_string())?;
+    let item = store.get_project(platform, &project_id).map_err(|e| e.to_string())?;
+    let version = store.get_versions(platform, &project_id, None, None)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|v| v.id == version_id || v.version == version_id)
+        .ok_or_else(|| "version not found".to_string())?;
+    Ok(InstallDisclosure::new(&item, &version).into())
+}
+
+#[tauri::command]
+pub fn store_install_cmd(cache: State<'_, ManifestCache>, input: StoreInstallInput) -> Result<ProfileDto, String> {
+    let paths = load_paths()?;
+    let config = load_config(&paths).map_err(|e| e.to_string())?;
+    let store = ContentStore::new(config.curseforge_api_key.as_deref());
+
+    let mut profile = load_profile(&paths, &input.profile_id).map_err(|e| e.to_string())?;
     let platform = parse_platform(&input.platform)?;
 
     // Get project info to determine content type
@@ -927,9 +1459,15 @@ pub fn store_install_cmd(input: StoreInstallInput) -> Result<Profile, String> {
         store.get_latest_version(platform, &input.project_id, Some(&profile.mc_version), effective_loader.as_deref())
             .map_err(|e| e.to_string())?
     };
+    if !input.accept_metered && needs_confirmation(&paths, version.size) {
+        return Err(format!(
+            "the connection is metered; confirm the {} download first",
+            format_size(version.size)
+        ));
+    }
 
     // Download and store
-    let mut content_ref = store.download_to_store(&paths, &version, ct).map_err(|e| e.to_string())?;
+    let mut content_ref = store.download_to_store(&paths, &version, ct, config.store_hash).map_err(|e| e.to_string())?;
 
     // Add platform/project tracking for update checking
     content_ref.platform = Some(input.platform.clone());
@@ -937,37 +1475,55 @@ pub fn store_install_cmd(input: StoreInstallInput) -> Result<Profile, String> {
     content_ref.version_id = Some(version.id.clone());
     content_ref.pinned = false;
 
-    // Auto-add to library
-    if let Ok(library) = Library::from_paths(&paths) {
-        let lib_content_type = match ct {
-            ContentType::Mod | ContentType::ModPack => "mod",
-            ContentType::ResourcePack => "resourcepack",
-            ContentType::ShaderPack => "shaderpack",
-        };
-        let hash = content_ref.hash.strip_prefix("sha256:").unwrap_or(&content_ref.hash);
-        let lib_input = LibraryItemInput {
-            hash: hash.to_string(),
-            content_type: Some(lib_content_type.to_string()),
-            name: Some(content_ref.name.clone()),
-            file_name: content_ref.file_name.clone(),
-            source_url: content_ref.source.clone(),
-            source_platform: Some(input.platform.clone()),
-            source_project_id: Some(input.project_id.clone()),
-            source_version: input.version_id.clone().or_else(|| Some(version.version.clone())),
-            ..Default::default()
-        };
-        let _ = library.add_item(&lib_input);
-    }
+    // Add to profile and library
+    add_store_content(&paths, &mut profile, content_ref, ct.to_content_kind()).map_err(|e| user_message(&e))?;
+    forget_profile(&cache, &paths, &profile.id);
+    Ok(profile.into())
+}
 
-    // Add to profile
-    match ct {
-        ContentType::Mod | ContentType::ModPack => upsert_mod(&mut profile, content_ref),
-        ContentType::ResourcePack => upsert_resourcepack(&mut profile, content_ref),
-        ContentType::ShaderPack => upsert_shaderpack(&mut profile, content_ref),
-    };
+// ==================== Blocklist Commands ====================
+
+#[tauri::command]
+pub fn list_blocklist_cmd() -> Result<Vec<BlockedEntry>, String> {
+    let paths = load_paths()?;
+    load_blocklist(&paths).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn block_project_cmd(
+    platform: String,
+    project_id: String,
+    name: Option<String>,
+    reason: String,
+) -> Result<BlockedEntry, String> {
+    let paths = load_paths()?;
+    let platform = parse_platform(&platform)?;
+    block_project(&paths, Some(platform), &project_id, name, &reason).map_err(|e| user_message(&e))
+}
+
+#[tauri::command]
+pub fn block_hash_cmd(hash: String, name: Option<String>, reason: String) -> Result<BlockedEntry, String> {
+    let paths = load_paths()?;
+    block_hash(&paths, &hash, name, &reason).map_err(|e| user_message(&e))
+}
+
+/// Remove the blocklist entries for a project id, hash or name
+#[tauri::command]
+pub fn unblock_cmd(target: String) -> Result<usize, String> {
+    let paths = load_paths()?;
+    unblock(&paths, &target).map_err(|e| user_message(&e))
+}
 
-    save_profile(&paths, &profile).map_err(|e| e.to_string())?;
-    Ok(profile)
+#[tauri::command]
+pub fn import_blocklist_cmd(path: String) -> Result<usize, String> {
+    let paths = load_paths()?;
+    import_blocklist(&paths, &PathBuf::from(path)).map_err(|e| user_message(&e))
+}
+
+#[tauri::command]
+pub fn export_blocklist_cmd(path: String) -> Result<usize, String> {
+    let paths = load_paths()?;
+    export_blocklist(&paths, &PathBuf::from(path)).map_err(|e| user_message(&e))
 }
 
 // ==================== Logs Commands ====================
@@ -979,7 +1535,7 @@ pub fn list_log_files_cmd(profile_id: String) -> Result<Vec<LogFile>, String> {
 }
 
 #[tauri::command]
-pub fn read_logs_cmd(profile_id: String, file: Option<String>, lines: Option<usize>) -> Result<Vec<LogEntry>, String> {
+pub fn read_logs_cmd(profile_id: String, file: Option<String>, lines: Option<usize>) -> Result<Vec<LogEntryDto>, String> {
     let paths = load_paths()?;
     let log_path = if let Some(filename) = file {
         paths.instance_logs_dir(&profile_id).join(filename)
@@ -991,11 +1547,39 @@ pub fn read_logs_cmd(profile_id: String, file: Option<String>, lines: Option<usi
         return Ok(Vec::new());
     }
 
-    if let Some(n) = lines {
-        read_log_tail(&log_path, n).map_err(|e| e.to_string())
+    let entries = if let Some(n) = lines {
+        read_log_tail(&log_path, n)
+    } else {
+        read_log_file(&log_path)
+    };
+    entries
+        .map(|entries| entries.into_iter().map(Into::into).collect())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_game_events_cmd(profile_id: String, file: Option<String>) -> Result<GameSessionEvents, String> {
+    let paths = load_paths()?;
+    let log_path = if let Some(filename) = file {
+        paths.instance_logs_dir(&profile_id).join(filename)
     } else {
-        read_log_file(&log_path).map_err(|e| e.to_string())
+        paths.instance_latest_log(&profile_id)
+    };
+
+    if !log_path.exists() {
+        return Ok(GameSessionEvents {
+            events: Vec::new(),
+            summary: SessionSummary::default().into(),
+        });
     }
+
+    let entries = read_log_file(&log_path).map_err(|e| e.to_string())?;
+    let events = extract_events(&entries);
+    let summary = summarize_events(&events);
+    Ok(GameSessionEvents {
+        events: events.into_iter().map(Into::into).collect(),
+        summary: summary.into(),
+    })
 }
 
 #[tauri::command]
@@ -1007,10 +1591,9 @@ pub fn list_crash_reports_cmd(profile_id: String) -> Result<Vec<LogFile>, String
 #[tauri::command]
 pub fn read_crash_report_cmd(profile_id: String, file: Option<String>) -> Result<String, String> {
     let paths = load_paths()?;
-    let crash_dir = paths.instance_crash_reports(&profile_id);
 
     let crash_path = if let Some(filename) = file {
-        crash_dir.join(filename)
+        crash_report_path(&paths, &profile_id, &filename)
     } else {
         let files = list_crash_reports(&paths, &profile_id).map_err(|e| e.to_string())?;
         files.into_iter().next().map(|f| f.path)
@@ -1044,15 +1627,23 @@ pub async fn start_log_watch(
     std::thread::spawn(move || {
         let mut watcher = LogWatcher::from_start(log_path.clone());
         let event_name = format!("log-entries-{}", sanitize_event_segment(&profile_id));
+        let game_event_name = format!("game-events-{}", sanitize_event_segment(&profile_id));
 
         loop {
             // Read new entries
             match watcher.read_new() {
                 Ok(entries) if !entries.is_empty() => {
+                    // Emit structured game events (joins, advancements, disconnects)
+                    let game_events: Vec<TimedGameEventDto> =
+                        extract_events(&entries).into_iter().map(Into::into).collect();
                     // Emit event with new log entries
+                    let entries: Vec<LogEntryDto> = entries.into_iter().map(Into::into).collect();
                     if app.emit(&event_name, &entries).is_err() {
                         break; // Window closed
                     }
+                    if !game_events.is_empty() {
+                        let _ = app.emit(&game_event_name, &game_events);
+                    }
                 }
                 Ok(_) => {
                     // No new entries
@@ -1070,58 +1661,65 @@ pub async fn start_log_watch(
 }
 
 // ============================================================================
-// Version fetching commands
+// Mod config commands
 // ============================================================================
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct ManifestVersion {
-    pub id: String,
-    #[serde(rename = "type")]
-    pub version_type: String,
-    #[serde(rename = "releaseTime")]
-    pub release_time: Option<String>,
+#[tauri::command]
+pub fn list_mod_configs_cmd(profile_id: String) -> Result<Vec<ModConfigFile>, String> {
+    let paths = load_paths()?;
+    list_mod_configs(&paths, &profile_id).map_err(|e| e.to_string())
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-struct VersionManifestResponse {
-    versions: Vec<ManifestVersion>,
-    latest: Option<LatestVersions>,
+#[tauri::command]
+pub fn read_mod_config_cmd(profile_id: String, file: String) -> Result<String, String> {
+    let paths = load_paths()?;
+    read_mod_config(&paths, &profile_id, &file).map_err(|e| e.to_string())
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-struct LatestVersions {
-    release: Option<String>,
-    snapshot: Option<String>,
+/// Save a config file; returns the backup path when an older version existed
+#[tauri::command]
+pub fn write_mod_config_cmd(profile_id: String, file: String, content: String) -> Result<Option<String>, String> {
+    let paths = load_paths()?;
+    write_mod_config(&paths, &profile_id, &file, &content)
+        .map(|backup| backup.map(|p| p.to_string_lossy().to_string()))
+        .map_err(|e| e.to_string())
 }
 
-#[derive(Clone, Serialize)]
-pub struct MinecraftVersionsResponse {
-    pub versions: Vec<ManifestVersion>,
-    pub latest_release: Option<String>,
-    pub latest_snapshot: Option<String>,
+#[tauri::command]
+pub fn restore_mod_config_cmd(profile_id: String, file: String) -> Result<(), String> {
+    let paths = load_paths()?;
+    restore_mod_config(&paths, &profile_id, &file).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn fetch_minecraft_versions_cmd() -> Result<MinecraftVersionsResponse, String> {
-    let client = reqwest::blocking::Client::new();
-    let resp = client
-        .get("https://piston-meta.mojang.com/mc/game/version_manifest_v2.json")
-        .send()
-        .map_err(|e| format!("Failed to fetch Minecraft versions: {}", e))?;
+pub fn list_config_snapshots_cmd(profile_id: String) -> Result<Vec<ConfigSnapshot>, String> {
+    let paths = load_paths()?;
+    list_config_snapshots(&paths, &profile_id).map_err(|e| e.to_string())
+}
 
-    if !resp.status().is_success() {
-        return Err(format!("HTTP error: {}", resp.status()));
-    }
+#[tauri::command]
+pub fn restore_config_snapshot_cmd(profile_id: String, snapshot_id: String) -> Result<Vec<ConfigChange>, String> {
+    let paths = load_paths()?;
+    restore_config_snapshot(&paths, &profile_id, &snapshot_id).map_err(|e| user_message(&e))
+}
 
-    let manifest: VersionManifestResponse = resp
-        .json()
-        .map_err(|e| format!("Failed to parse version manifest: {}", e))?;
+// ============================================================================
+// Version fetching commands
+// ============================================================================
 
-    Ok(MinecraftVersionsResponse {
-        versions: manifest.versions,
-        latest_release: manifest.latest.as_ref().and_then(|l| l.release.clone()),
-        latest_snapshot: manifest.latest.as_ref().and_then(|l| l.snapshot.clone()),
-    })
+/// Minecraft versions, newest first, optionally only those in `channels`
+/// (release, snapshot, beta, alpha, april_fools)
+#[tauri::command]
+pub fn fetch_minecraft_versions_cmd(channels: Option<Vec<String>>) -> Result<VersionListDto, String> {
+    let paths = load_paths()?;
+    let channels = channels
+        .unwrap_or_default()
+        .iter()
+        .map(|c| c.parse::<VersionChannel>().map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, String>>()?;
+    list_versions(&paths, &channels)
+        .map(Into::into)
+        .map_err(|e| format!("Failed to fetch Minecraft versions: {}", e))
 }
 
 /// Fabric loader version entry from the Fabric Meta API
@@ -1288,6 +1886,21 @@ pub fn fetch_forge_versions_cmd(mc_version: Option<String>) -> Result<Vec<String
     Ok(versions)
 }
 
+/// OptiFine builds for a Minecraft version, newest first (e.g. `HD_U_I6`)
+#[tauri::command]
+pub fn fetch_optifine_versions_cmd(mc_version: Option<String>) -> Result<Vec<String>, String> {
+    let mc_version = mc_version.ok_or("OptiFine versions depend on the Minecraft version")?;
+    let versions = list_optifine_versions(&mc_version).map_err(|e| format!("Failed to fetch OptiFine versions: {}", e))?;
+    Ok(versions.iter().map(|v| v.id()).collect())
+}
+
+/// LiteLoader versions for a Minecraft version (1.12.2 and older), newest first
+#[tauri::command]
+pub fn fetch_liteloader_versions_cmd(mc_version: Option<String>) -> Result<Vec<String>, String> {
+    let mc_version = mc_version.ok_or("LiteLoader versions depend on the Minecraft version")?;
+    list_liteloader_versions(&mc_version).map_err(|e| format!("Failed to fetch LiteLoader versions: {}", e))
+}
+
 /// Compare two version strings semantically (for descending sort)
 /// Returns Ordering based on semantic version comparison
 fn compare_versions_desc(a: &str, b: &str) -> std::cmp::Ordering {
@@ -1314,29 +1927,46 @@ fn compare_versions_desc(a: &str, b: &str) -> std::cmp::Ordering {
 /// Fetch loader versions for any supported loader type
 #[tauri::command]
 pub fn fetch_loader_versions_cmd(loader_type: String, mc_version: Option<String>) -> Result<Vec<String>, String> {
-    match loader_type.to_lowercase().as_str() {
-        "fabric" => fetch_fabric_versions_cmd(),
-        "quilt" => fetch_quilt_versions_cmd(),
-        "neoforge" => fetch_neoforge_versions_cmd(mc_version),
-        "forge" => fetch_forge_versions_cmd(mc_version),
-        other => Err(format!("Unsupported loader type: {}", other)),
+    let kind = loader_type.parse::<LoaderKind>().map_err(|e| e.to_string())?;
+    match kind {
+        LoaderKind::Fabric => fetch_fabric_versions_cmd(),
+        LoaderKind::Quilt => fetch_quilt_versions_cmd(),
+        LoaderKind::NeoForge => fetch_neoforge_versions_cmd(mc_version),
+        LoaderKind::Forge => fetch_forge_versions_cmd(mc_version),
+        LoaderKind::OptiFine => fetch_optifine_versions_cmd(mc_version),
+        LoaderKind::LiteLoader => fetch_liteloader_versions_cmd(mc_version),
     }
 }
 
+/// Loaders the launcher can install, with their capabilities.
+#[tauri::command]
+pub fn list_loaders_cmd() -> Vec<LoaderInfoDto> {
+    list_loaders().into_iter().map(Into::into).collect()
+}
+
+/// The usual loader and its latest version for a Minecraft version, for
+/// profiles created without a loader. None when no loader targets it.
+#[tauri::command]
+pub async fn suggest_loader_cmd(mc_version: String) -> Result<Option<LoaderSuggestion>, String> {
+    tauri::async_runtime::spawn_blocking(move || suggest_loader(&mc_version).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
 // ============================================================================
 // Java detection and validation commands
 // ============================================================================
 
 /// Detect all Java installations on the system.
 #[tauri::command]
-pub fn detect_java_installations_cmd() -> Vec<JavaInstallation> {
-    detect_installations()
+pub fn detect_java_installations_cmd() -> Vec<JavaInstallationDto> {
+    detect_installations().into_iter().map(Into::into).collect()
 }
 
 /// Validate a specific Java path.
 #[tauri::command]
-pub fn validate_java_path_cmd(path: String) -> JavaValidation {
-    validate_java_path(&path)
+pub fn validate_java_path_cmd(path: String) -> JavaValidationDto {
+    validate_java_path(&path).into()
 }
 
 /// Get the minimum required Java version for a Minecraft version.
@@ -1397,67 +2027,58 @@ pub fn get_managed_java_cmd(java_major: u32) -> Result<Option<String>, String> {
 
 /// List all managed Java runtimes.
 #[tauri::command]
-pub fn list_managed_runtimes_cmd() -> Result<Vec<JavaInstallation>, String> {
+pub fn list_managed_runtimes_cmd() -> Result<Vec<JavaInstallationDto>, String> {
     let paths = Paths::new().map_err(|e| e.to_string())?;
-    Ok(list_managed_runtimes(&paths.java_runtimes))
+    Ok(list_managed_runtimes(&paths.java_runtimes).into_iter().map(Into::into).collect())
 }
 
 // ============================================================================
 // Library commands
 // ============================================================================
 
-#[derive(Deserialize)]
-pub struct LibraryFilterInput {
-    pub content_type: Option<String>,
-    pub search: Option<String>,
-    pub tags: Option<Vec<String>>,
-    pub limit: Option<u32>,
-    pub offset: Option<u32>,
-}
-
-#[derive(Deserialize)]
-pub struct LibraryItemUpdateInput {
-    pub name: Option<String>,
-    pub notes: Option<String>,
+#[tauri::command]
+pub fn library_list_items_cmd(filter: LibraryFilterInput) -> Result<Vec<LibraryItemDto>, String> {
+    let paths = load_paths()?;
+    let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
+    library
+        .list_items(&filter.into())
+        .map(|items| items.into_iter().map(Into::into).collect())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn library_list_items_cmd(filter: LibraryFilterInput) -> Result<Vec<LibraryItem>, String> {
+pub fn library_get_item_cmd(id: i64) -> Result<Option<LibraryItemDto>, String> {
     let paths = load_paths()?;
     let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
-    let filter = LibraryFilter {
-        content_type: filter.content_type,
-        search: filter.search,
-        tags: filter.tags,
-        limit: filter.limit,
-        offset: filter.offset,
-    };
-    library.list_items(&filter).map_err(|e| e.to_string())
+    library.get_item(id).map(|item| item.map(Into::into)).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn library_get_item_cmd(id: i64) -> Result<Option<LibraryItem>, String> {
+pub fn library_get_item_by_hash_cmd(hash: String) -> Result<Option<LibraryItemDto>, String> {
     let paths = load_paths()?;
     let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
-    library.get_item(id).map_err(|e| e.to_string())
+    library.get_item_by_hash(&hash).map(|item| item.map(Into::into)).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn library_get_item_by_hash_cmd(hash: String) -> Result<Option<LibraryItem>, String> {
+pub fn library_get_item_history_cmd(id: i64) -> Result<Vec<LibraryItemDto>, String> {
     let paths = load_paths()?;
     let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
-    library.get_item_by_hash(&hash).map_err(|e| e.to_string())
+    library
+        .get_item_history(id)
+        .map(|items| items.into_iter().map(Into::into).collect())
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn library_add_item_cmd(input: LibraryItemInput) -> Result<LibraryItem, String> {
+pub fn library_add_item_cmd(input: LibraryItemInputDto) -> Result<LibraryItemDto, String> {
     let paths = load_paths()?;
     let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
-    library.add_item(&input).map_err(|e| e.to_string())
+    library.add_item(&input.into()).map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn library_update_item_cmd(id: i64, input: LibraryItemUpdateInput) -> Result<LibraryItem, String> {
+pub fn library_update_item_cmd(id: i64, input: LibraryItemUpdateInput) -> Result<LibraryItemDto, String> {
     let paths = load_paths()?;
     let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
     let item = library.get_item(id).map_err(|e| e.to_string())?
@@ -1468,7 +2089,7 @@ pub fn library_update_item_cmd(id: i64, input: LibraryItemUpdateInput) -> Result
         notes: input.notes,
         ..Default::default()
     };
-    library.update_item(id, &update).map_err(|e| e.to_string())
+    library.update_item(id, &update).map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -1516,12 +2137,12 @@ pub fn library_get_item_path_cmd(id: i64) -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-pub fn library_import_file_cmd(path: String, content_type: String) -> Result<LibraryItem, String> {
+pub fn library_import_file_cmd(path: String, content_type: String) -> Result<LibraryItemDto, String> {
     let paths = load_paths()?;
     let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
     let ct = LibraryContentType::from_str(&content_type)
         .ok_or_else(|| "invalid content type".to_string())?;
-    library.import_file(&paths, &PathBuf::from(path), ct).map_err(|e| e.to_string())
+    library.import_file(&paths, &PathBuf::from(path), ct).map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -1534,10 +2155,10 @@ pub fn library_import_folder_cmd(path: String, content_type: String, recursive:
 }
 
 #[tauri::command]
-pub fn library_get_stats_cmd() -> Result<LibraryStats, String> {
+pub fn library_get_stats_cmd() -> Result<LibraryStatsDto, String> {
     let paths = load_paths()?;
     let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
-    library.stats().map_err(|e| e.to_string())
+    library.stats().map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -1554,6 +2175,100 @@ pub fn library_sync_cmd() -> Result<ImportResult, String> {
     Ok(result)
 }
 
+fn run_library_auto_tag(paths: &Paths) -> Result<AutoTagResult, String> {
+    let config = load_config(paths).map_err(|e| e.to_string())?;
+    let library = Library::from_paths(paths).map_err(|e| e.to_string())?;
+    let store = ContentStore::new(config.curseforge_api_key.as_deref());
+    auto_tag_library(paths, &library, &store).map_err(|e| user_message(&e))
+}
+
+/// Tag library items with the Minecraft versions they support (`mc:1.21.x`)
+#[tauri::command]
+pub async fn library_auto_tag_cmd() -> Result<AutoTagResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let paths = load_paths()?;
+        run_library_auto_tag(&paths)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Refresh `mc:` tags once a day while automatic update checks are on,
+/// emitting `library-tags-updated` when any item changed
+pub fn spawn_library_auto_tagger(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        if let Ok(paths) = load_paths() {
+            let enabled = load_config(&paths).map(|c| c.auto_update_enabled).unwrap_or(false);
+            if enabled && auto_tag_due(&paths) && !is_metered(&paths) {
+                if let Ok(result) = run_library_auto_tag(&paths) {
+                    if result.tagged > 0 {
+                        let _ = app.emit("library-tags-updated", result);
+                    }
+                }
+            }
+        }
+        std::thread::sleep(Duration::from_secs(60 * 60));
+    });
+}
+
+/// Job id of the running store verification
+#[derive(Default)]
+pub struct VerifyJob(Mutex<Option<String>>);
+
+/// Re-hash the content store in the background, emitting `store-verify`
+/// events. Low-priority mode throttles reads so a running game keeps its IO.
+#[tauri::command]
+pub fn library_verify_cmd(app: AppHandle, job: State<'_, VerifyJob>, low_priority: bool) -> Result<(), String> {
+    let paths = load_paths()?;
+    if let Ok(mut current) = job.0.lock() {
+        if let Some(previous) = current.take() {
+            let _ = cancel_job(&paths, &previous);
+        }
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let emit = |stage: &str, report: Option<StoreVerifyReport>, message: Option<String>| {
+            let _ = app.emit("store-verify", StoreVerifyEvent {
+                stage: stage.to_string(),
+                report,
+                message,
+            });
+        };
+        let result = run_job(&paths, JobKind::Audit, "content store", |running| {
+            if let Ok(mut current) = app.state::<VerifyJob>().0.lock() {
+                *current = Some(running.id());
+            }
+            let mut throttle = if low_priority {
+                Throttle::low_priority(running.cancel_token())
+            } else {
+                Throttle::unlimited(running.cancel_token())
+            };
+            verify_store(&paths, &mut throttle, |report| {
+                running.progress(report.checked as u64, None, None);
+                emit("progress", Some(report.clone()), None);
+            })
+        });
+        match result {
+            Ok(report) if report.cancelled => emit("cancelled", Some(report), None),
+            Ok(report) => emit("done", Some(report), None),
+            Err(err) => emit("error", None, Some(user_message(&err))),
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn library_cancel_verify_cmd(job: State<'_, VerifyJob>) -> Result<(), String> {
+    let paths = load_paths()?;
+    if let Ok(mut current) = job.0.lock() {
+        // The job may have ended already
+        if let Some(id) = current.take() {
+            let _ = cancel_job(&paths, &id);
+        }
+    }
+    Ok(())
+}
+
 /// Enrich library items with metadata from all profiles
 fn enrich_library_from_profiles(paths: &Paths, library: &Library) -> Result<usize, String> {
     let profiles = list_profiles(paths).map_err(|e| e.to_string())?;
@@ -1647,7 +2362,14 @@ pub fn library_set_item_tags_cmd(item_id: i64, tag_names: Vec<String>) -> Result
 }
 
 #[tauri::command]
-pub fn library_add_to_profile_cmd(profile_id: String, item_id: i64) -> Result<Profile, String> {
+pub fn library_add_tag_to_matching_cmd(filter: LibraryFilterInput, tag_name: String) -> Result<usize, String> {
+    let paths = load_paths()?;
+    let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
+    library.add_tag_to_matching(&filter.into(), &tag_name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn library_add_to_profile_cmd(cache: State<'_, ManifestCache>, profile_id: String, item_id: i64) -> Result<ProfileDto, String> {
     let paths = load_paths()?;
     let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
     let mut profile = load_profile(&paths, &profile_id).map_err(|e| e.to_string())?;
@@ -1657,7 +2379,7 @@ pub fn library_add_to_profile_cmd(profile_id: String, item_id: i64) -> Result<Pr
 
     let content_ref = ContentRef {
         name: item.name.clone(),
-        hash: format!("sha256:{}", item.hash),
+        hash: prefixed_hash(&item.hash),
         version: item.source_version.clone(),
         source: item.source_url.clone(),
         file_name: item.file_name.clone(),
@@ -1678,8 +2400,9 @@ pub fn library_add_to_profile_cmd(profile_id: String, item_id: i64) -> Result<Pr
     // Link in library
     library.link_item_to_profile(item_id, &profile_id, item.content_type).map_err(|e| e.to_string())?;
 
-    save_profile(&paths, &profile).map_err(|e| e.to_string())?;
-    Ok(profile)
+    save_profile(&paths, &profile).map_err(|e| user_message(&e))?;
+    forget_profile(&cache, &paths, &profile_id);
+    Ok(profile.into())
 }
 
 // ============================================================================
@@ -1696,20 +2419,33 @@ pub fn get_data_path_cmd() -> Result<String, String> {
 }
 
 #[tauri::command]
-pub fn get_storage_stats_cmd() -> Result<StorageStats, String> {
+pub fn get_storage_stats_cmd() -> Result<StorageStatsDto, String> {
+    let paths = load_paths()?;
+    get_storage_stats(&paths).map(Into::into).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn relocate_instance_folder_cmd(profile_id: String, folder: String, target: String) -> Result<String, String> {
     let paths = load_paths()?;
-    get_storage_stats(&paths).map_err(|e| e.to_string())
+    let moved = relocate_aux_folder(&paths, &profile_id, &folder, &PathBuf::from(target))
+        .map_err(|e| user_message(&e))?;
+    Ok(moved.to_string_lossy().into_owned())
 }
 
 #[tauri::command]
-pub fn get_unused_items_cmd() -> Result<UnusedItemsSummary, String> {
+pub fn get_unused_items_cmd() -> Result<UnusedItemsSummaryDto, String> {
     let paths = load_paths()?;
     let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
-    library.get_unused_items().map_err(|e| e.to_string())
+    library.get_unused_items().map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn purge_unused_items_cmd(content_types: Vec<String>) -> Result<PurgeResult, String> {
+pub fn purge_unused_items_cmd(
+    tokens: State<'_, ConfirmTokens>,
+    content_types: Vec<String>,
+    confirm_token: String,
+) -> Result<PurgeResultDto, String> {
+    tokens.redeem(&confirm_token, "purge_unused_items_cmd", "")?;
     let paths = load_paths()?;
     let library = Library::from_paths(&paths).map_err(|e| e.to_string())?;
 
@@ -1720,7 +2456,7 @@ pub fn purge_unused_items_cmd(content_types: Vec<String>) -> Result<PurgeResult,
         .collect();
 
     // Always delete files from store when purging
-    library.purge_unused_items(&paths, &types, true).map_err(|e| e.to_string())
+    library.purge_unused_items(&paths, &types, true).map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -1731,12 +2467,233 @@ pub fn get_auto_update_enabled_cmd() -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub fn set_auto_update_enabled_cmd(enabled: bool) -> Result<Config, String> {
+pub fn set_auto_update_enabled_cmd(cache: State<'_, ManifestCache>, enabled: bool) -> Result<ConfigDto, String> {
     let paths = load_paths()?;
     let mut config = load_config(&paths).map_err(|e| e.to_string())?;
     config.auto_update_enabled = enabled;
-    save_config(&paths, &config).map_err(|e| e.to_string())?;
-    Ok(config)
+    save_config(&paths, &config).map_err(|e| user_message(&e))?;
+    cache.invalidate(&paths.config);
+    Ok(config.into())
+}
+
+#[tauri::command]
+pub fn get_crash_aggregation_enabled_cmd() -> Result<bool, String> {
+    let paths = load_paths()?;
+    let config = load_config(&paths).map_err(|e| e.to_string())?;
+    Ok(config.crash_aggregation_enabled)
+}
+
+#[tauri::command]
+pub fn set_crash_aggregation_enabled_cmd(cache: State<'_, ManifestCache>, enabled: bool) -> Result<ConfigDto, String> {
+    let paths = load_paths()?;
+    let mut config = load_config(&paths).map_err(|e| e.to_string())?;
+    config.crash_aggregation_enabled = enabled;
+    save_config(&paths, &config).map_err(|e| user_message(&e))?;
+    cache.invalidate(&paths.config);
+    Ok(config.into())
+}
+
+#[tauri::command]
+pub fn get_auto_install_java_cmd() -> Result<bool, String> {
+    let paths = load_paths()?;
+    let config = load_config(&paths).map_err(|e| e.to_string())?;
+    Ok(config.auto_install_java)
+}
+
+#[tauri::command]
+pub fn set_auto_install_java_cmd(cache: State<'_, ManifestCache>, enabled: bool) -> Result<ConfigDto, String> {
+    let paths = load_paths()?;
+    let mut config = load_config(&paths).map_err(|e| e.to_string())?;
+    config.auto_install_java = enabled;
+    save_config(&paths, &config).map_err(|e| user_message(&e))?;
+    cache.invalidate(&paths.config);
+    Ok(config.into())
+}
+
+#[tauri::command]
+pub fn get_warm_up_enabled_cmd() -> Result<bool, String> {
+    let paths = load_paths()?;
+    let config = load_config(&paths).map_err(|e| e.to_string())?;
+    Ok(!config.skip_warm_up)
+}
+
+#[tauri::command]
+pub fn set_warm_up_enabled_cmd(cache: State<'_, ManifestCache>, enabled: bool) -> Result<ConfigDto, String> {
+    let paths = load_paths()?;
+    let mut config = load_config(&paths).map_err(|e| e.to_string())?;
+    config.skip_warm_up = !enabled;
+    save_config(&paths, &config).map_err(|e| user_message(&e))?;
+    cache.invalidate(&paths.config);
+    Ok(config.into())
+}
+
+/// Whether large downloads are held back, per the setting and the OS
+#[tauri::command]
+pub fn is_metered_cmd() -> Result<bool, String> {
+    let paths = load_paths()?;
+    Ok(is_metered(&paths))
+}
+
+/// Whether downloading `bytes` should be confirmed first on this connection
+#[tauri::command]
+pub fn needs_metered_confirmation_cmd(bytes: u64) -> Result<bool, String> {
+    let paths = load_paths()?;
+    Ok(needs_confirmation(&paths, bytes))
+}
+
+#[tauri::command]
+pub fn set_metered_mode_cmd(cache: State<'_, ManifestCache>, mode: String) -> Result<ConfigDto, String> {
+    let paths = load_paths()?;
+    let mode: MeteredMode = mode.parse().map_err(|e: anyhow::Error| e.to_string())?;
+    let mut config = load_config(&paths).map_err(|e| e.to_string())?;
+    config.metered = mode;
+    save_config(&paths, &config).map_err(|e| user_message(&e))?;
+    cache.invalidate(&paths.config);
+    Ok(config.into())
+}
+
+#[tauri::command]
+pub fn get_snapshot_profile_cmd() -> Result<Option<String>, String> {
+    let paths = load_paths()?;
+    let config = load_config(&paths).map_err(|e| e.to_string())?;
+    Ok(config.snapshot_profile)
+}
+
+#[tauri::command]
+pub fn set_snapshot_profile_cmd(cache: State<'_, ManifestCache>, profile_id: Option<String>) -> Result<ConfigDto, String> {
+    let paths = load_paths()?;
+    let mut config = load_config(&paths).map_err(|e| e.to_string())?;
+    config.snapshot_profile = profile_id.filter(|id| !id.trim().is_empty());
+    save_config(&paths, &config).map_err(|e| user_message(&e))?;
+    cache.invalidate(&paths.config);
+    Ok(config.into())
+}
+
+#[tauri::command]
+pub async fn sync_snapshot_profile_cmd(app: AppHandle) -> Result<Option<SnapshotUpdateDto>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let paths = load_paths()?;
+        let update = sync_snapshot_profile(&paths).map_err(|e| user_message(&e))?;
+        if let Some(update) = &update {
+            forget_profile(&app.state::<ManifestCache>(), &paths, &update.profile_id);
+        }
+        Ok(update.map(Into::into))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn list_running_instances_cmd() -> Result<Vec<RunningInstanceDto>, String> {
+    let paths = load_paths()?;
+    list_running(&paths)
+        .map(|instances| instances.into_iter().map(Into::into).collect())
+        .map_err(|e| e.to_string())
+}
+
+/// Stop the running instances of a profile, forcing them after a grace period
+#[tauri::command]
+pub async fn kill_instance_cmd(profile_id: String) -> Result<Vec<RunningInstanceDto>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let paths = load_paths()?;
+        kill_profile(&paths, &profile_id, KILL_GRACE_PERIOD)
+            .map(|instances| instances.into_iter().map(Into::into).collect())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Use the configured retry policy and download mirrors for network
+/// requests made by the app
+pub fn apply_network_config() {
+    if let Ok(paths) = load_paths() {
+        if let Ok(config) = load_config(&paths) {
+            set_retry_policy(config.network_retry);
+            set_download_mirrors(config.mirrors);
+        }
+    }
+}
+
+/// Upgrade profiles and accounts written by the original CLI, once per
+/// data directory
+pub fn upgrade_legacy_data() {
+    if let Ok(paths) = load_paths() {
+        match migrate_legacy_data(&paths) {
+            Ok(changes) => {
+                for change in changes {
+                    eprintln!("upgraded {change}");
+                }
+            }
+            Err(err) => eprintln!("warning: failed to upgrade data from the old CLI: {err:#}"),
+        }
+    }
+}
+
+/// Forward this process's jobs to the frontend as `job-updated` events
+pub fn forward_job_events(app: AppHandle) {
+    set_job_listener(move |job| {
+        let _ = app.emit("job-updated", JobInfoDto::from(job.clone()));
+    });
+}
+
+/// Running jobs of the app and of any shard CLI
+#[tauri::command]
+pub fn list_jobs_cmd() -> Result<Vec<JobInfoDto>, String> {
+    let paths = load_paths()?;
+    list_jobs(&paths)
+        .map(|jobs| jobs.into_iter().map(Into::into).collect())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn cancel_job_cmd(job_id: String) -> Result<JobInfoDto, String> {
+    let paths = load_paths()?;
+    cancel_job(&paths, &job_id).map(Into::into).map_err(|e| e.to_string())
+}
+
+/// Record CurseForge key status and rate limits from the app's requests
+pub fn track_api_keys() {
+    if let Ok(paths) = load_paths() {
+        track_api_key_health(&paths);
+    }
+}
+
+/// Check for new snapshots in the background, emitting `snapshot-update`
+/// whenever the snapshot profile is created or moved to a new version
+pub fn spawn_snapshot_watcher(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        // A new snapshot is downloaded at its first launch; wait for a
+        // connection where that is cheap
+        if let Some(paths) = load_paths().ok().filter(|paths| !is_metered(paths)) {
+            if let Ok(Some(update)) = sync_snapshot_profile(&paths) {
+                forget_profile(&app.state::<ManifestCache>(), &paths, &update.profile_id);
+                let _ = app.emit("snapshot-update", SnapshotUpdateDto::from(update));
+            }
+        }
+        std::thread::sleep(SNAPSHOT_CHECK_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub fn get_crash_stats_cmd() -> Result<Vec<CrashSignatureStats>, String> {
+    let paths = load_paths()?;
+    // No database means nothing was recorded; don't create one just to list it
+    if !paths.crash_stats_db().exists() {
+        return Ok(Vec::new());
+    }
+    let stats = CrashStats::from_paths(&paths).map_err(|e| e.to_string())?;
+    stats.list().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn clear_crash_stats_cmd() -> Result<(), String> {
+    let paths = load_paths()?;
+    if !paths.crash_stats_db().exists() {
+        return Ok(());
+    }
+    let stats = CrashStats::from_paths(&paths).map_err(|e| e.to_string())?;
+    stats.clear().map_err(|e| e.to_string())
 }
 
 // ============================================================================
@@ -1744,71 +2701,123 @@ pub fn set_auto_update_enabled_cmd(enabled: bool) -> Result<Config, String> {
 // ============================================================================
 
 #[tauri::command]
-pub fn check_all_updates_cmd() -> Result<UpdateCheckResult, String> {
+pub fn check_all_updates_cmd() -> Result<UpdateCheckResultDto, String> {
     let paths = load_paths()?;
     let config = load_config(&paths).map_err(|e| e.to_string())?;
-    check_all_updates(&paths, config.curseforge_api_key.as_deref()).map_err(|e| e.to_string())
+    check_all_updates(&paths, config.curseforge_api_key.as_deref())
+        .map(Into::into)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn check_profile_updates_cmd(profile_id: String) -> Result<UpdateCheckResult, String> {
+pub fn check_profile_updates_cmd(profile_id: String) -> Result<UpdateCheckResultDto, String> {
     let paths = load_paths()?;
     let config = load_config(&paths).map_err(|e| e.to_string())?;
-    check_profile_updates(&paths, &profile_id, config.curseforge_api_key.as_deref()).map_err(|e| e.to_string())
+    check_profile_updates(&paths, &profile_id, config.curseforge_api_key.as_deref())
+        .map(Into::into)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_watched_projects_cmd() -> Result<Vec<WatchedProjectDto>, String> {
+    let paths = load_paths()?;
+    load_watchlist(&paths)
+        .map(|projects| projects.into_iter().map(Into::into).collect())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn watch_project_cmd(
+    platform: String,
+    project_id: String,
+    mc_version: Option<String>,
+    loader: Option<String>,
+) -> Result<WatchedProjectDto, String> {
+    let paths = load_paths()?;
+    let platform = parse_platform(&platform)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let config = load_config(&paths).map_err(|e| e.to_string())?;
+        let store = ContentStore::new(config.curseforge_api_key.as_deref());
+        watch_project(&paths, &store, platform, &project_id, mc_version.as_deref(), loader.as_deref())
+            .map(Into::into)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn unwatch_project_cmd(platform: String, project_id: String) -> Result<WatchedProjectDto, String> {
+    let paths = load_paths()?;
+    let platform = parse_platform(&platform)?;
+    unwatch_project(&paths, platform, &project_id).map(Into::into).map_err(|e| e.to_string())
+}
+
+/// Dismiss a watched project's new release until the next one comes out
+#[tauri::command]
+pub fn dismiss_watched_release_cmd(
+    platform: String,
+    project_id: String,
+    version_id: String,
+    version: String,
+) -> Result<(), String> {
+    let paths = load_paths()?;
+    let platform = parse_platform(&platform)?;
+    mark_release_seen(&paths, platform, &project_id, &version_id, &version).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub fn apply_content_update_cmd(
+    cache: State<'_, ManifestCache>,
     profile_id: String,
     content_name: String,
     content_type: String,
     new_version_id: String,
-) -> Result<Profile, String> {
+) -> Result<ProfileDto, String> {
     let paths = load_paths()?;
     let config = load_config(&paths).map_err(|e| e.to_string())?;
-    apply_update(&paths, &profile_id, &content_name, &content_type, &new_version_id, config.curseforge_api_key.as_deref())
-        .map_err(|e| e.to_string())
+    let label = format!("{profile_id}: {content_name}");
+    let result = run_job(&paths, JobKind::Update, &label, |_| {
+        apply_update(
+            &paths,
+            &profile_id,
+            &content_name,
+            &content_type,
+            &new_version_id,
+            config.curseforge_api_key.as_deref(),
+            config.store_hash,
+        )
+    });
+    forget_profile(&cache, &paths, &profile_id);
+    result.map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub fn set_content_pinned_cmd(
+    cache: State<'_, ManifestCache>,
     profile_id: String,
     content_name: String,
     content_type: String,
     pinned: bool,
-) -> Result<Profile, String> {
+) -> Result<ProfileDto, String> {
     let paths = load_paths()?;
-    set_content_pinned(&paths, &profile_id, &content_name, &content_type, pinned).map_err(|e| e.to_string())
+    let result = set_content_pinned(&paths, &profile_id, &content_name, &content_type, pinned);
+    forget_profile(&cache, &paths, &profile_id);
+    result.map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub fn set_content_enabled_cmd(
+    cache: State<'_, ManifestCache>,
     profile_id: String,
     content_name: String,
     content_type: String,
     enabled: bool,
-) -> Result<Profile, String> {
+) -> Result<ProfileDto, String> {
     let paths = load_paths()?;
-    set_content_enabled(&paths, &profile_id, &content_name, &content_type, enabled).map_err(|e| e.to_string())
-}
-
-// Profile organization types (mirrors frontend types)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ProfileFolder {
-    pub id: String,
-    pub name: String,
-    pub profiles: Vec<String>,
-    pub collapsed: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct ProfileOrganization {
-    pub folders: Vec<ProfileFolder>,
-    pub ungrouped: Vec<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub favorite_profile: Option<String>,
+    let result = set_content_enabled(&paths, &profile_id, &content_name, &content_type, enabled);
+    forget_profile(&cache, &paths, &profile_id);
+    result.map(Into::into).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -1827,9 +2836,93 @@ pub fn load_profile_organization_cmd() -> Result<ProfileOrganization, String> {
 #[tauri::command]
 pub fn save_profile_organization_cmd(organization: ProfileOrganization) -> Result<(), String> {
     let paths = load_paths()?;
+    paths.ensure_writable().map_err(|e| user_message(&e))?;
     let data = serde_json::to_string_pretty(&organization)
         .map_err(|e| format!("Failed to serialize profile organization: {}", e))?;
     std::fs::write(&paths.profile_organization, data)
         .map_err(|e| format!("Failed to write profile organization: {}", e))?;
     Ok(())
 }
+
+// ============================================================================
+// Server Properties Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_server_properties_cmd(profile_id: String) -> Result<ServerPropertiesView, String> {
+    let paths = load_paths()?;
+    let properties = load_server_properties(&paths, &profile_id).map_err(|e| e.to_string())?;
+    Ok(properties.view())
+}
+
+#[tauri::command]
+pub fn set_server_property_cmd(profile_id: String, key: String, value: String) -> Result<ServerPropertiesView, String> {
+    let paths = load_paths()?;
+    let properties = set_server_property(&paths, &profile_id, &key, &value).map_err(|e| e.to_string())?;
+    Ok(properties.view())
+}
+
+/// Apply several properties at once (validated together, saved only if all are valid)
+#[tauri::command]
+pub fn save_server_properties_cmd(profile_id: String, values: Vec<(String, String)>) -> Result<ServerPropertiesView, String> {
+    let paths = load_paths()?;
+    if !paths.is_profile_present(&profile_id) {
+        return Err(format!("profile not found: {}", profile_id));
+    }
+    let mut properties = load_server_properties(&paths, &profile_id).map_err(|e| e.to_string())?;
+    for (key, value) in &values {
+        properties.set(key, value).map_err(|e| e.to_string())?;
+    }
+    save_server_properties(&paths, &profile_id, &properties).map_err(|e| e.to_string())?;
+    Ok(properties.view())
+}
+
+// ============================================================================
+// Official Launcher Import Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn import_vanilla_launcher_cmd(minecraft_dir: Option<String>) -> Result<VanillaImportResult, String> {
+    let paths = load_paths()?;
+    let dir = match minecraft_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => default_minecraft_dir().ok_or_else(|| "could not determine .minecraft directory".to_string())?,
+    };
+    let label = dir.display().to_string();
+    run_job(&paths, JobKind::Import, &label, |_| import_vanilla_launcher(&paths, &dir)).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Space Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn list_spaces_cmd() -> Result<Vec<SpaceInfo>, String> {
+    let paths = load_paths()?;
+    list_spaces(&paths).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_space_cmd(name: String) -> Result<(), String> {
+    let paths = load_paths()?;
+    create_space(&paths, &name).map_err(|e| e.to_string())
+}
+
+/// Switch spaces; the frontend reloads afterwards so every view re-reads its data
+#[tauri::command]
+pub fn set_active_space_cmd(name: String) -> Result<(), String> {
+    let paths = load_paths()?;
+    set_active_space(&paths, &name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_space_cmd(name: String) -> Result<(), String> {
+    let paths = load_paths()?;
+    delete_space(&paths, &name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_data_dir_warning_cmd() -> Result<Option<String>, String> {
+    let paths = load_paths()?;
+    Ok(data_dir_owner_warning(&paths))
+}
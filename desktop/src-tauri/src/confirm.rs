@@ -0,0 +1,57 @@
+//! One-time tokens guarding destructive commands.
+//!
+//! Deleting a profile, removing an account and purging the store don't run
+//! on a bare call. The UI asks `request_confirmation_cmd` for a token naming
+//! the command and its target once the user has confirmed, then passes it
+//! along. Each token works once, for that exact action, within
+//! [`TOKEN_TTL`], so frontend state pointing at the wrong profile or a
+//! handler firing twice fails instead of deleting something.
+
+use shard::util::random_token;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long an issued token can be redeemed
+const TOKEN_TTL: Duration = Duration::from_secs(60);
+
+/// Outstanding tokens, mapped to the action they allow and when they were
+/// issued
+#[derive(Default)]
+pub struct ConfirmTokens(Mutex<HashMap<String, (String, Instant)>>);
+
+impl ConfirmTokens {
+    /// Token allowing `command` on `target` once
+    pub fn issue(&self, command: &str, target: &str) -> String {
+        let token = random_token();
+        if let Ok(mut tokens) = self.0.lock() {
+            tokens.retain(|_, (_, issued)| issued.elapsed() < TOKEN_TTL);
+            tokens.insert(token.clone(), (action(command, target), Instant::now()));
+        }
+        token
+    }
+
+    /// Use up `token`, failing unless it was issued for `command` on
+    /// `target` and is still fresh
+    pub fn redeem(&self, token: &str, command: &str, target: &str) -> Result<(), String> {
+        let entry = self.0.lock().ok().and_then(|mut tokens| tokens.remove(token));
+        match entry {
+            Some((allowed, issued)) if issued.elapsed() < TOKEN_TTL => {
+                if allowed == action(command, target) {
+                    Ok(())
+                } else {
+                    Err(format!("Confirmation was for {allowed}, not {}", action(command, target)))
+                }
+            }
+            _ => Err("Confirmation expired; try again".to_string()),
+        }
+    }
+}
+
+fn action(command: &str, target: &str) -> String {
+    if target.is_empty() {
+        command.to_string()
+    } else {
+        format!("{command} {target}")
+    }
+}
@@ -0,0 +1,1360 @@
+//! Shapes the UI sends and receives
+//!
+//! Commands and events go through these types rather than the launcher's own
+//! structs, so every field reaches the frontend in camelCase, whatever the
+//! core type calls it or however it is stored on disk. Types mirroring a core
+//! struct are named after it with a `Dto` suffix and convert with `From`;
+//! their TypeScript counterparts live in `src/types/index.ts`.
+
+use serde::{Deserialize, Serialize};
+use shard::config::Config;
+use shard::content_store::{
+    ContentDependency, ContentItem, ContentLicense, ContentType, ContentVersion, InstallDisclosure,
+    Platform,
+};
+use shard::game_events::{GameEvent, SessionSummary, TimedGameEvent};
+use shard::instance::{AdoptedFile, InstanceLinkMode};
+use shard::instance_folders::FolderUsage;
+use shard::java::{JavaInstallation, JavaValidation};
+use shard::jobs::{JobInfo, JobKind, JobState};
+use shard::library::{
+    LibraryContentType, LibraryFilter, LibraryItem, LibraryItemInput, LibraryStats, PurgeResult,
+    Tag, UnusedItem, UnusedItemsSummary,
+};
+use shard::loader::LoaderInfo;
+use shard::locale::{LocaleMatch, LocaleReport};
+use shard::logs::{LogEntry, LogLevel};
+use shard::metered::MeteredMode;
+use shard::minecraft::{DownloadEstimate, LaunchPlan, PrepareEstimate};
+use shard::mirrors::DownloadMirrors;
+use shard::presets::{ContentPreset, PresetApplied};
+use shard::process::RunningInstance;
+use shard::profile::{
+    ContentRef, Files, Gamescope, JvmPreset, Loader, ProcessPriority, Profile, ProfileKind, Runtime,
+};
+use shard::quickstart::QuickstartSummary;
+use shard::resource_monitor::InstanceMetrics;
+use shard::session::SessionCrash;
+use shard::skin::MinecraftProfile;
+use shard::snapshot::SnapshotUpdate;
+use shard::store::{HashAlgorithm, StoreVerifyReport};
+use shard::template::{ContentSource, Template, TemplateContent, TemplateLoader, TemplateRuntime};
+use shard::tunnel::TunnelConfig;
+use shard::updates::{ContentUpdate, StorageStats, UpdateCheckResult};
+use shard::versions::{GameVersion, VersionList};
+use shard::watchlist::{WatchedProject, WatchedRelease};
+use std::collections::BTreeMap;
+
+// ==================== Inputs ====================
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProfileInput {
+    pub id: String,
+    pub mc_version: String,
+    pub loader_type: Option<String>,
+    pub loader_version: Option<String>,
+    pub java: Option<String>,
+    pub memory: Option<String>,
+    pub args: Option<String>,
+    /// Wrapper command line, e.g. "gamemoderun mangohud"
+    pub wrapper: Option<String>,
+    pub jvm_preset: Option<JvmPreset>,
+    pub priority: Option<ProcessPriority>,
+    /// CPU cores the game may run on
+    pub cpu_affinity: Option<Vec<usize>>,
+    /// Fullscreen resolution for Gamescope (Steam Deck game mode)
+    pub gamescope: Option<Gamescope>,
+    pub template: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreSearchInput {
+    pub query: String,
+    pub content_type: Option<String>,
+    pub game_version: Option<String>,
+    pub loader: Option<String>,
+    pub platform: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreInstallInput {
+    pub profile_id: String,
+    pub project_id: String,
+    pub platform: String,
+    pub version_id: Option<String>,
+    pub content_type: Option<String>,
+    /// The user agreed to a large download on a metered connection
+    #[serde(default)]
+    pub accept_metered: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryFilterInput {
+    pub content_type: Option<String>,
+    pub search: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+impl From<LibraryFilterInput> for LibraryFilter {
+    fn from(filter: LibraryFilterInput) -> Self {
+        Self {
+            content_type: filter.content_type,
+            search: filter.search,
+            tags: filter.tags,
+            limit: filter.limit,
+            offset: filter.offset,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryItemInputDto {
+    pub hash: String,
+    pub content_type: Option<String>,
+    pub name: Option<String>,
+    pub file_name: Option<String>,
+    pub file_size: Option<i64>,
+    pub source_url: Option<String>,
+    pub source_platform: Option<String>,
+    pub source_project_id: Option<String>,
+    pub source_version: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl From<LibraryItemInputDto> for LibraryItemInput {
+    fn from(input: LibraryItemInputDto) -> Self {
+        Self {
+            hash: input.hash,
+            content_type: input.content_type,
+            name: input.name,
+            file_name: input.file_name,
+            file_size: input.file_size,
+            source_url: input.source_url,
+            source_platform: input.source_platform,
+            source_project_id: input.source_project_id,
+            source_version: input.source_version,
+            notes: input.notes,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryItemUpdateInput {
+    pub name: Option<String>,
+    pub notes: Option<String>,
+}
+
+// ==================== Results ====================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffResult {
+    pub only_a: Vec<String>,
+    pub only_b: Vec<String>,
+    pub both: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchPlanDto {
+    pub instance_dir: String,
+    pub wrapper: Vec<String>,
+    pub java_exec: String,
+    pub jvm_args: Vec<String>,
+    pub classpath: String,
+    pub main_class: String,
+    pub game_args: Vec<String>,
+    pub priority: Option<ProcessPriority>,
+    pub cpu_affinity: Vec<usize>,
+    pub env: Vec<(String, String)>,
+}
+
+impl From<LaunchPlan> for LaunchPlanDto {
+    fn from(plan: LaunchPlan) -> Self {
+        Self {
+            instance_dir: plan.instance_dir.to_string_lossy().to_string(),
+            wrapper: plan.wrapper,
+            java_exec: plan.java_exec,
+            jvm_args: plan.jvm_args,
+            classpath: plan.classpath,
+            main_class: plan.main_class,
+            game_args: plan.game_args,
+            priority: plan.priority,
+            cpu_affinity: plan.cpu_affinity,
+            env: plan.env,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadEstimateDto {
+    pub files: usize,
+    pub bytes: u64,
+    pub unknown_size: usize,
+}
+
+impl From<DownloadEstimate> for DownloadEstimateDto {
+    fn from(estimate: DownloadEstimate) -> Self {
+        Self {
+            files: estimate.files,
+            bytes: estimate.bytes,
+            unknown_size: estimate.unknown_size,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrepareEstimateDto {
+    pub client: DownloadEstimateDto,
+    pub libraries: DownloadEstimateDto,
+    pub assets: DownloadEstimateDto,
+    pub loader_pending: bool,
+    pub java_download: Option<u32>,
+}
+
+impl From<PrepareEstimate> for PrepareEstimateDto {
+    fn from(estimate: PrepareEstimate) -> Self {
+        Self {
+            client: estimate.client.into(),
+            libraries: estimate.libraries.into(),
+            assets: estimate.assets.into(),
+            loader_pending: estimate.loader_pending,
+            java_download: estimate.java_download,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo {
+    pub uuid: String,
+    pub username: String,
+    pub avatar_url: String,
+    pub body_url: String,
+    pub skin_url: String,
+    pub cape_url: String,
+    pub profile: Option<MinecraftProfile>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotUpdateDto {
+    pub profile_id: String,
+    pub previous: Option<String>,
+    pub version: String,
+}
+
+impl From<SnapshotUpdate> for SnapshotUpdateDto {
+    fn from(update: SnapshotUpdate) -> Self {
+        Self {
+            profile_id: update.profile_id,
+            previous: update.previous,
+            version: update.version,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimedGameEventDto {
+    pub timestamp: Option<String>,
+    pub line_number: u64,
+    #[serde(flatten)]
+    pub event: GameEvent,
+}
+
+impl From<TimedGameEvent> for TimedGameEventDto {
+    fn from(event: TimedGameEvent) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            line_number: event.line_number,
+            event: event.event,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummaryDto {
+    pub servers: Vec<String>,
+    pub players: Vec<String>,
+    pub advancements: Vec<(String, String)>,
+    pub last_disconnect: Option<String>,
+}
+
+impl From<SessionSummary> for SessionSummaryDto {
+    fn from(summary: SessionSummary) -> Self {
+        Self {
+            servers: summary.servers,
+            players: summary.players,
+            advancements: summary.advancements,
+            last_disconnect: summary.last_disconnect,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct GameSessionEvents {
+    pub events: Vec<TimedGameEventDto>,
+    pub summary: SessionSummaryDto,
+}
+
+// ==================== Profiles ====================
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentRefDto {
+    pub name: String,
+    pub hash: String,
+    pub version: Option<String>,
+    pub source: Option<String>,
+    pub file_name: Option<String>,
+    pub platform: Option<String>,
+    pub project_id: Option<String>,
+    pub version_id: Option<String>,
+    pub enabled: bool,
+    pub pinned: bool,
+}
+
+impl From<ContentRef> for ContentRefDto {
+    fn from(content: ContentRef) -> Self {
+        Self {
+            name: content.name,
+            hash: content.hash,
+            version: content.version,
+            source: content.source,
+            file_name: content.file_name,
+            platform: content.platform,
+            project_id: content.project_id,
+            version_id: content.version_id,
+            enabled: content.enabled,
+            pinned: content.pinned,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeDto {
+    pub java: Option<String>,
+    pub memory: Option<String>,
+    pub args: Vec<String>,
+    pub wrapper: Vec<String>,
+    pub jvm_preset: Option<JvmPreset>,
+    pub priority: Option<ProcessPriority>,
+    pub cpu_affinity: Vec<usize>,
+    pub gamescope: Option<Gamescope>,
+}
+
+impl From<Runtime> for RuntimeDto {
+    fn from(runtime: Runtime) -> Self {
+        Self {
+            java: runtime.java,
+            memory: runtime.memory,
+            args: runtime.args,
+            wrapper: runtime.wrapper,
+            jvm_preset: runtime.jvm_preset,
+            priority: runtime.priority,
+            cpu_affinity: runtime.cpu_affinity,
+            gamescope: runtime.gamescope,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesDto {
+    pub config_overrides: String,
+    pub extra_folders: Vec<String>,
+}
+
+impl From<Files> for FilesDto {
+    fn from(files: Files) -> Self {
+        Self {
+            config_overrides: files.config_overrides,
+            extra_folders: files.extra_folders,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileDto {
+    pub id: String,
+    pub mc_version: String,
+    pub kind: ProfileKind,
+    pub loader: Option<Loader>,
+    pub mods: Vec<ContentRefDto>,
+    pub resourcepacks: Vec<ContentRefDto>,
+    pub shaderpacks: Vec<ContentRefDto>,
+    pub runtime: RuntimeDto,
+    pub files: FilesDto,
+    pub tunnel: Option<TunnelConfig>,
+    pub presets: BTreeMap<String, ContentPreset>,
+}
+
+impl From<Profile> for ProfileDto {
+    fn from(profile: Profile) -> Self {
+        Self {
+            id: profile.id,
+            mc_version: profile.mc_version,
+            kind: profile.kind,
+            loader: profile.loader,
+            mods: profile.mods.into_iter().map(Into::into).collect(),
+            resourcepacks: profile.resourcepacks.into_iter().map(Into::into).collect(),
+            shaderpacks: profile.shaderpacks.into_iter().map(Into::into).collect(),
+            runtime: profile.runtime.into(),
+            files: profile.files.into(),
+            tunnel: profile.tunnel,
+            presets: profile.presets,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetAppliedDto {
+    pub profile: ProfileDto,
+    pub changed: Vec<String>,
+    pub shader_config: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+impl From<PresetApplied> for PresetAppliedDto {
+    fn from(applied: PresetApplied) -> Self {
+        Self {
+            profile: applied.profile.into(),
+            changed: applied.changed,
+            shader_config: applied
+                .shader_config
+                .map(|path| path.to_string_lossy().to_string()),
+            warnings: applied.warnings,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdoptedFileDto {
+    pub path: String,
+    pub content: ContentRefDto,
+    pub replaces: Option<String>,
+}
+
+impl From<AdoptedFile> for AdoptedFileDto {
+    fn from(file: AdoptedFile) -> Self {
+        Self {
+            path: file.path,
+            content: file.content.into(),
+            replaces: file.replaces,
+        }
+    }
+}
+
+// ==================== Config ====================
+
+/// The settings the app shows; the network retry policy is left to the CLI
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDto {
+    pub msa_client_id: Option<String>,
+    pub msa_client_secret: Option<String>,
+    pub curseforge_api_key: Option<String>,
+    pub auto_update_enabled: bool,
+    pub crash_aggregation_enabled: bool,
+    pub snapshot_profile: Option<String>,
+    pub store_hash: HashAlgorithm,
+    pub auto_install_java: bool,
+    pub skip_warm_up: bool,
+    pub instance_links: InstanceLinkMode,
+    pub mirrors: DownloadMirrors,
+    pub read_only: bool,
+    pub keep_lwjgl_on_arm: bool,
+    pub metered: MeteredMode,
+}
+
+impl From<Config> for ConfigDto {
+    fn from(config: Config) -> Self {
+        Self {
+            msa_client_id: config.msa_client_id,
+            msa_client_secret: config.msa_client_secret,
+            curseforge_api_key: config.curseforge_api_key,
+            auto_update_enabled: config.auto_update_enabled,
+            crash_aggregation_enabled: config.crash_aggregation_enabled,
+            snapshot_profile: config.snapshot_profile,
+            store_hash: config.store_hash,
+            auto_install_java: config.auto_install_java,
+            skip_warm_up: config.skip_warm_up,
+            instance_links: config.instance_links,
+            mirrors: config.mirrors,
+            read_only: config.read_only,
+            keep_lwjgl_on_arm: config.keep_lwjgl_on_arm,
+            metered: config.metered,
+        }
+    }
+}
+
+// ==================== Library ====================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryItemDto {
+    pub id: i64,
+    pub hash: String,
+    pub content_type: LibraryContentType,
+    pub name: String,
+    pub file_name: Option<String>,
+    pub file_size: Option<i64>,
+    pub source_url: Option<String>,
+    pub source_platform: Option<String>,
+    pub source_project_id: Option<String>,
+    pub source_version: Option<String>,
+    pub added_at: String,
+    pub updated_at: String,
+    pub notes: Option<String>,
+    pub sha1: Option<String>,
+    pub sha512: Option<String>,
+    pub tags: Vec<Tag>,
+    pub used_by_profiles: Vec<String>,
+    pub replaces: Option<i64>,
+    pub replaced_by: Option<i64>,
+}
+
+impl From<LibraryItem> for LibraryItemDto {
+    fn from(item: LibraryItem) -> Self {
+        Self {
+            id: item.id,
+            hash: item.hash,
+            content_type: item.content_type,
+            name: item.name,
+            file_name: item.file_name,
+            file_size: item.file_size,
+            source_url: item.source_url,
+            source_platform: item.source_platform,
+            source_project_id: item.source_project_id,
+            source_version: item.source_version,
+            added_at: item.added_at,
+            updated_at: item.updated_at,
+            notes: item.notes,
+            sha1: item.sha1,
+            sha512: item.sha512,
+            tags: item.tags,
+            used_by_profiles: item.used_by_profiles,
+            replaces: item.replaces,
+            replaced_by: item.replaced_by,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryStatsDto {
+    pub total_items: u32,
+    pub mods_count: u32,
+    pub resourcepacks_count: u32,
+    pub shaderpacks_count: u32,
+    pub skins_count: u32,
+    pub total_size: u64,
+    pub tags_count: u32,
+}
+
+impl From<LibraryStats> for LibraryStatsDto {
+    fn from(stats: LibraryStats) -> Self {
+        Self {
+            total_items: stats.total_items,
+            mods_count: stats.mods_count,
+            resourcepacks_count: stats.resourcepacks_count,
+            shaderpacks_count: stats.shaderpacks_count,
+            skins_count: stats.skins_count,
+            total_size: stats.total_size,
+            tags_count: stats.tags_count,
+        }
+    }
+}
+
+// ==================== Processes and jobs ====================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningInstanceDto {
+    pub profile_id: String,
+    pub pid: u32,
+    pub started_at: u64,
+}
+
+impl From<RunningInstance> for RunningInstanceDto {
+    fn from(instance: RunningInstance) -> Self {
+        Self {
+            profile_id: instance.profile_id,
+            pid: instance.pid,
+            started_at: instance.started_at,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfoDto {
+    pub id: String,
+    pub kind: JobKind,
+    pub label: String,
+    pub pid: u32,
+    pub started_at: u64,
+    pub state: JobState,
+    pub done: u64,
+    pub total: Option<u64>,
+    pub message: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<JobInfo> for JobInfoDto {
+    fn from(job: JobInfo) -> Self {
+        Self {
+            id: job.id,
+            kind: job.kind,
+            label: job.label,
+            pid: job.pid,
+            started_at: job.started_at,
+            state: job.state,
+            done: job.done,
+            total: job.total,
+            message: job.message,
+            error: job.error,
+        }
+    }
+}
+
+// ==================== Updates ====================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentUpdateDto {
+    pub profile_id: String,
+    pub content: ContentRefDto,
+    pub content_type: String,
+    pub current_version: Option<String>,
+    pub latest_version: String,
+    pub latest_version_id: String,
+    pub changelog: Option<String>,
+}
+
+impl From<ContentUpdate> for ContentUpdateDto {
+    fn from(update: ContentUpdate) -> Self {
+        Self {
+            profile_id: update.profile_id,
+            content: update.content.into(),
+            content_type: update.content_type,
+            current_version: update.current_version,
+            latest_version: update.latest_version,
+            latest_version_id: update.latest_version_id,
+            changelog: update.changelog,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedProjectDto {
+    pub platform: Platform,
+    pub project_id: String,
+    pub name: String,
+    pub content_type: ContentType,
+    pub mc_version: Option<String>,
+    pub loader: Option<String>,
+    pub seen_version_id: Option<String>,
+    pub seen_version: Option<String>,
+    pub added_at: u64,
+}
+
+impl From<WatchedProject> for WatchedProjectDto {
+    fn from(project: WatchedProject) -> Self {
+        Self {
+            platform: project.platform,
+            project_id: project.project_id,
+            name: project.name,
+            content_type: project.content_type,
+            mc_version: project.mc_version,
+            loader: project.loader,
+            seen_version_id: project.seen_version_id,
+            seen_version: project.seen_version,
+            added_at: project.added_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedReleaseDto {
+    pub project: WatchedProjectDto,
+    pub latest_version: String,
+    pub latest_version_id: String,
+}
+
+impl From<WatchedRelease> for WatchedReleaseDto {
+    fn from(release: WatchedRelease) -> Self {
+        Self {
+            project: release.project.into(),
+            latest_version: release.latest_version,
+            latest_version_id: release.latest_version_id,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct UpdateCheckResultDto {
+    pub updates: Vec<ContentUpdateDto>,
+    pub watched: Vec<WatchedReleaseDto>,
+    pub checked: u32,
+    pub skipped: u32,
+    pub errors: Vec<String>,
+}
+
+impl From<UpdateCheckResult> for UpdateCheckResultDto {
+    fn from(result: UpdateCheckResult) -> Self {
+        Self {
+            updates: result.updates.into_iter().map(Into::into).collect(),
+            watched: result.watched.into_iter().map(Into::into).collect(),
+            checked: result.checked,
+            skipped: result.skipped,
+            errors: result.errors,
+        }
+    }
+}
+
+// ==================== Store ====================
+
+// Also sent back by the UI when installing translation packs
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentItemDto {
+    pub id: String,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub body: Option<String>,
+    pub icon_url: Option<String>,
+    pub platform: Platform,
+    pub content_type: ContentType,
+    pub downloads: u64,
+    pub updated: String,
+    pub categories: Vec<String>,
+    pub game_versions: Vec<String>,
+    pub loaders: Vec<String>,
+    pub license: Option<ContentLicense>,
+    pub distribution_allowed: Option<bool>,
+}
+
+impl From<ContentItem> for ContentItemDto {
+    fn from(item: ContentItem) -> Self {
+        Self {
+            id: item.id,
+            slug: item.slug,
+            name: item.name,
+            description: item.description,
+            body: item.body,
+            icon_url: item.icon_url,
+            platform: item.platform,
+            content_type: item.content_type,
+            downloads: item.downloads,
+            updated: item.updated,
+            categories: item.categories,
+            game_versions: item.game_versions,
+            loaders: item.loaders,
+            license: item.license,
+            distribution_allowed: item.distribution_allowed,
+        }
+    }
+}
+
+impl From<ContentItemDto> for ContentItem {
+    fn from(item: ContentItemDto) -> Self {
+        Self {
+            id: item.id,
+            slug: item.slug,
+            name: item.name,
+            description: item.description,
+            body: item.body,
+            icon_url: item.icon_url,
+            platform: item.platform,
+            content_type: item.content_type,
+            downloads: item.downloads,
+            updated: item.updated,
+            categories: item.categories,
+            game_versions: item.game_versions,
+            loaders: item.loaders,
+            license: item.license,
+            distribution_allowed: item.distribution_allowed,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentDependencyDto {
+    pub project_id: String,
+    pub dependency_type: String,
+}
+
+impl From<ContentDependency> for ContentDependencyDto {
+    fn from(dependency: ContentDependency) -> Self {
+        Self {
+            project_id: dependency.project_id,
+            dependency_type: dependency.dependency_type,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentVersionDto {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub version: String,
+    pub download_url: String,
+    pub filename: String,
+    pub size: u64,
+    pub sha256: Option<String>,
+    pub sha1: Option<String>,
+    pub platform: Platform,
+    pub game_versions: Vec<String>,
+    pub loaders: Vec<String>,
+    pub release_type: String,
+    pub dependencies: Vec<ContentDependencyDto>,
+}
+
+impl From<ContentVersion> for ContentVersionDto {
+    fn from(version: ContentVersion) -> Self {
+        Self {
+            id: version.id,
+            project_id: version.project_id,
+            name: version.name,
+            version: version.version,
+            download_url: version.download_url,
+            filename: version.filename,
+            size: version.size,
+            sha256: version.sha256,
+            sha1: version.sha1,
+            platform: version.platform,
+            game_versions: version.game_versions,
+            loaders: version.loaders,
+            release_type: version.release_type,
+            dependencies: version.dependencies.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallDisclosureDto {
+    pub project: String,
+    pub version: String,
+    pub license: Option<ContentLicense>,
+    pub distribution_allowed: Option<bool>,
+    pub required: Vec<String>,
+    pub embedded: Vec<String>,
+    pub size: u64,
+}
+
+impl From<InstallDisclosure> for InstallDisclosureDto {
+    fn from(disclosure: InstallDisclosure) -> Self {
+        Self {
+            project: disclosure.project,
+            version: disclosure.version,
+            license: disclosure.license,
+            distribution_allowed: disclosure.distribution_allowed,
+            required: disclosure.required,
+            embedded: disclosure.embedded,
+            size: disclosure.size,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LocaleMatchDto {
+    pub mods: Vec<String>,
+    pub pack: ContentItemDto,
+}
+
+impl From<LocaleMatch> for LocaleMatchDto {
+    fn from(locale_match: LocaleMatch) -> Self {
+        Self {
+            mods: locale_match.mods,
+            pack: locale_match.pack.into(),
+        }
+    }
+}
+
+impl From<LocaleMatchDto> for LocaleMatch {
+    fn from(locale_match: LocaleMatchDto) -> Self {
+        Self {
+            mods: locale_match.mods,
+            pack: locale_match.pack.into(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct LocaleReportDto {
+    pub language: String,
+    pub matches: Vec<LocaleMatchDto>,
+    pub unmatched: Vec<String>,
+}
+
+impl From<LocaleReport> for LocaleReportDto {
+    fn from(report: LocaleReport) -> Self {
+        Self {
+            language: report.language,
+            matches: report.matches.into_iter().map(Into::into).collect(),
+            unmatched: report.unmatched,
+        }
+    }
+}
+
+// ==================== Versions, loaders and Java ====================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionListDto {
+    pub versions: Vec<GameVersion>,
+    pub latest_release: Option<String>,
+    pub latest_snapshot: Option<String>,
+}
+
+impl From<VersionList> for VersionListDto {
+    fn from(list: VersionList) -> Self {
+        Self {
+            versions: list.versions,
+            latest_release: list.latest_release,
+            latest_snapshot: list.latest_snapshot,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderInfoDto {
+    pub id: String,
+    pub name: String,
+    pub supports_server: bool,
+    pub needs_installer: bool,
+    pub latest_per_mc_version: bool,
+}
+
+impl From<LoaderInfo> for LoaderInfoDto {
+    fn from(info: LoaderInfo) -> Self {
+        Self {
+            id: info.id,
+            name: info.name,
+            supports_server: info.capabilities.supports_server,
+            needs_installer: info.capabilities.needs_installer,
+            latest_per_mc_version: info.capabilities.latest_per_mc_version,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaInstallationDto {
+    pub path: String,
+    pub version: Option<String>,
+    pub major: Option<u32>,
+    pub vendor: Option<String>,
+    pub arch: Option<String>,
+    pub is_valid: bool,
+}
+
+impl From<JavaInstallation> for JavaInstallationDto {
+    fn from(java: JavaInstallation) -> Self {
+        Self {
+            path: java.path,
+            version: java.version,
+            major: java.major,
+            vendor: java.vendor,
+            arch: java.arch,
+            is_valid: java.is_valid,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaValidationDto {
+    pub is_valid: bool,
+    pub version: Option<String>,
+    pub major: Option<u32>,
+    pub vendor: Option<String>,
+    pub arch: Option<String>,
+    pub error: Option<String>,
+}
+
+impl From<JavaValidation> for JavaValidationDto {
+    fn from(validation: JavaValidation) -> Self {
+        Self {
+            is_valid: validation.is_valid,
+            version: validation.version,
+            major: validation.major,
+            vendor: validation.vendor,
+            arch: validation.arch,
+            error: validation.error,
+        }
+    }
+}
+
+// ==================== Templates and quickstart ====================
+
+#[derive(Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "lowercase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ContentSourceDto {
+    Modrinth { project: String },
+    CurseForge { project_id: u32 },
+    Url { url: String },
+}
+
+impl From<ContentSource> for ContentSourceDto {
+    fn from(source: ContentSource) -> Self {
+        match source {
+            ContentSource::Modrinth { project } => Self::Modrinth { project },
+            ContentSource::CurseForge { project_id } => Self::CurseForge { project_id },
+            ContentSource::Url { url } => Self::Url { url },
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TemplateContentDto {
+    pub name: String,
+    pub source: ContentSourceDto,
+    pub version: Option<String>,
+    pub required: bool,
+}
+
+impl From<TemplateContent> for TemplateContentDto {
+    fn from(content: TemplateContent) -> Self {
+        Self {
+            name: content.name,
+            source: content.source.into(),
+            version: content.version,
+            required: content.required,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateDto {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub mc_version: String,
+    pub loader: Option<TemplateLoader>,
+    pub mods: Vec<TemplateContentDto>,
+    pub resourcepacks: Vec<TemplateContentDto>,
+    pub shaderpacks: Vec<TemplateContentDto>,
+    pub runtime: TemplateRuntime,
+}
+
+impl From<Template> for TemplateDto {
+    fn from(template: Template) -> Self {
+        let contents = |list: Vec<TemplateContent>| list.into_iter().map(Into::into).collect();
+        Self {
+            id: template.id,
+            name: template.name,
+            description: template.description,
+            mc_version: template.mc_version,
+            loader: template.loader,
+            mods: contents(template.mods),
+            resourcepacks: contents(template.resourcepacks),
+            shaderpacks: contents(template.shaderpacks),
+            runtime: template.runtime,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickstartSummaryDto {
+    pub profile_id: String,
+    pub mc_version: String,
+    pub installed: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+impl From<QuickstartSummary> for QuickstartSummaryDto {
+    fn from(summary: QuickstartSummary) -> Self {
+        Self {
+            profile_id: summary.profile_id,
+            mc_version: summary.mc_version,
+            installed: summary.installed,
+            skipped: summary.skipped,
+            failed: summary.failed,
+        }
+    }
+}
+
+// ==================== Storage ====================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderUsageDto {
+    pub profile_id: String,
+    pub folder: String,
+    pub bytes: u64,
+    pub relocated_to: Option<String>,
+}
+
+impl From<FolderUsage> for FolderUsageDto {
+    fn from(usage: FolderUsage) -> Self {
+        Self {
+            profile_id: usage.profile_id,
+            folder: usage.folder,
+            bytes: usage.bytes,
+            relocated_to: usage
+                .relocated_to
+                .map(|path| path.to_string_lossy().to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageStatsDto {
+    pub total_bytes: u64,
+    pub mods_bytes: u64,
+    pub resourcepacks_bytes: u64,
+    pub shaderpacks_bytes: u64,
+    pub skins_bytes: u64,
+    pub minecraft_bytes: u64,
+    pub database_bytes: u64,
+    pub instance_folders_bytes: u64,
+    pub instance_folders: Vec<FolderUsageDto>,
+    pub unique_items: u32,
+    pub total_references: u32,
+    pub deduplication_savings: u64,
+}
+
+impl From<StorageStats> for StorageStatsDto {
+    fn from(stats: StorageStats) -> Self {
+        Self {
+            total_bytes: stats.total_bytes,
+            mods_bytes: stats.mods_bytes,
+            resourcepacks_bytes: stats.resourcepacks_bytes,
+            shaderpacks_bytes: stats.shaderpacks_bytes,
+            skins_bytes: stats.skins_bytes,
+            minecraft_bytes: stats.minecraft_bytes,
+            database_bytes: stats.database_bytes,
+            instance_folders_bytes: stats.instance_folders_bytes,
+            instance_folders: stats.instance_folders.into_iter().map(Into::into).collect(),
+            unique_items: stats.unique_items,
+            total_references: stats.total_references,
+            deduplication_savings: stats.deduplication_savings,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnusedItemDto {
+    pub id: i64,
+    pub hash: String,
+    pub content_type: LibraryContentType,
+    pub name: String,
+    pub file_size: Option<i64>,
+}
+
+impl From<UnusedItem> for UnusedItemDto {
+    fn from(item: UnusedItem) -> Self {
+        Self {
+            id: item.id,
+            hash: item.hash,
+            content_type: item.content_type,
+            name: item.name,
+            file_size: item.file_size,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnusedItemsSummaryDto {
+    pub mods: Vec<UnusedItemDto>,
+    pub resourcepacks: Vec<UnusedItemDto>,
+    pub shaderpacks: Vec<UnusedItemDto>,
+    pub skins: Vec<UnusedItemDto>,
+    pub total_count: usize,
+    pub total_bytes: u64,
+}
+
+impl From<UnusedItemsSummary> for UnusedItemsSummaryDto {
+    fn from(summary: UnusedItemsSummary) -> Self {
+        let items = |list: Vec<UnusedItem>| list.into_iter().map(Into::into).collect();
+        Self {
+            mods: items(summary.mods),
+            resourcepacks: items(summary.resourcepacks),
+            shaderpacks: items(summary.shaderpacks),
+            skins: items(summary.skins),
+            total_count: summary.total_count,
+            total_bytes: summary.total_bytes,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeResultDto {
+    pub deleted_count: usize,
+    pub freed_bytes: u64,
+    pub items: Vec<UnusedItemDto>,
+    pub errors: Vec<String>,
+}
+
+impl From<PurgeResult> for PurgeResultDto {
+    fn from(result: PurgeResult) -> Self {
+        Self {
+            deleted_count: result.deleted_count,
+            freed_bytes: result.freed_bytes,
+            items: result.items.into_iter().map(Into::into).collect(),
+            errors: result.errors,
+        }
+    }
+}
+
+// ==================== Logs ====================
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntryDto {
+    pub timestamp: Option<String>,
+    pub level: LogLevel,
+    pub thread: Option<String>,
+    pub message: String,
+    pub raw: String,
+    pub line_number: u64,
+}
+
+impl From<LogEntry> for LogEntryDto {
+    fn from(entry: LogEntry) -> Self {
+        Self {
+            timestamp: entry.timestamp,
+            level: entry.level,
+            thread: entry.thread,
+            message: entry.message,
+            raw: entry.raw,
+            line_number: entry.line_number,
+        }
+    }
+}
+
+// ==================== Events ====================
+
+/// What the game left behind when it exited with a failure status
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCrashDto {
+    pub status: String,
+    pub report: Option<String>,
+    pub jvm_crash: bool,
+    pub lines: Vec<String>,
+}
+
+impl From<SessionCrash> for SessionCrashDto {
+    fn from(crash: SessionCrash) -> Self {
+        Self {
+            status: crash.status,
+            report: crash.report.map(|path| path.to_string_lossy().to_string()),
+            jvm_crash: crash.jvm_crash,
+            lines: crash.lines,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct LaunchEvent {
+    pub stage: String,
+    pub message: Option<String>,
+    pub crash: Option<SessionCrashDto>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefetchEvent {
+    pub profile_id: String,
+    pub stage: String,
+    pub message: Option<String>,
+}
+
+/// Live resource sample of a running instance
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceMetricsEvent {
+    pub profile_id: String,
+    pub pid: u32,
+    pub cpu_percent: f32,
+    pub rss_bytes: u64,
+    pub elapsed_secs: u64,
+}
+
+impl InstanceMetricsEvent {
+    pub fn new(profile_id: String, metrics: &InstanceMetrics) -> Self {
+        Self {
+            profile_id,
+            pid: metrics.pid,
+            cpu_percent: metrics.cpu_percent,
+            rss_bytes: metrics.rss_bytes,
+            elapsed_secs: metrics.elapsed_secs,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct AccountCacheEvent {
+    pub warmed: usize,
+    /// Accounts that couldn't be refreshed, with the reason
+    pub failed: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct StoreVerifyEvent {
+    /// "progress", "done", "cancelled" or "error"
+    pub stage: String,
+    pub report: Option<StoreVerifyReport>,
+    pub message: Option<String>,
+}
+
+// ==================== Profile organization ====================
+
+// Also the format of profile_organization.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileFolder {
+    pub id: String,
+    pub name: String,
+    pub profiles: Vec<String>,
+    pub collapsed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileOrganization {
+    pub folders: Vec<ProfileFolder>,
+    pub ungrouped: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favorite_profile: Option<String>,
+}
@@ -1,4 +1,7 @@
+mod cache;
 mod commands;
+mod confirm;
+mod dto;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -6,38 +9,76 @@ pub fn run() {
         .setup(|app| {
             #[cfg(desktop)]
             let _ = app.handle().plugin(tauri_plugin_updater::Builder::new().build());
+            commands::upgrade_legacy_data();
+            commands::apply_network_config();
+            commands::forward_job_events(app.handle().clone());
+            commands::track_api_keys();
+            commands::spawn_snapshot_watcher(app.handle().clone());
+            commands::spawn_library_auto_tagger(app.handle().clone());
             Ok(())
         })
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_os::init())
+        .manage(cache::ManifestCache::default())
+        .manage(commands::VerifyJob::default())
+        .manage(commands::AccountInfoCache::default())
+        .manage(confirm::ConfirmTokens::default())
         .invoke_handler(tauri::generate_handler![
             // Profile commands
             commands::list_profiles_cmd,
             commands::load_profile_cmd,
             commands::create_profile_cmd,
             commands::clone_profile_cmd,
+            commands::request_confirmation_cmd,
             commands::delete_profile_cmd,
             commands::rename_profile_cmd,
             commands::update_profile_version_cmd,
             commands::diff_profiles_cmd,
+            commands::get_profile_notes_cmd,
+            commands::save_profile_notes_cmd,
+            commands::save_profile_preset_cmd,
+            commands::delete_profile_preset_cmd,
+            commands::apply_profile_preset_cmd,
+            commands::keybind_conflicts_cmd,
+            commands::adopt_unmanaged_files_cmd,
+            commands::reimport_modified_files_cmd,
+            commands::list_shader_settings_cmd,
+            commands::copy_shader_settings_cmd,
+            commands::install_profile_cmd,
+            commands::export_mrpack_cmd,
+            commands::set_extra_folders_cmd,
             commands::add_mod_cmd,
             commands::add_resourcepack_cmd,
             commands::add_shaderpack_cmd,
+            commands::inspect_pack_cmd,
             commands::remove_mod_cmd,
             commands::remove_resourcepack_cmd,
             commands::remove_shaderpack_cmd,
             commands::prepare_profile_cmd,
+            commands::prepare_in_background_cmd,
+            commands::list_failed_downloads_cmd,
+            commands::retry_failed_downloads_cmd,
+            commands::repair_instance_cmd,
+            commands::estimate_prepare_cmd,
             commands::launch_profile_cmd,
             commands::instance_path_cmd,
+            commands::get_playtime_stats_cmd,
+            commands::list_running_instances_cmd,
+            commands::kill_instance_cmd,
             // Account commands
             commands::list_accounts_cmd,
             commands::set_active_account_cmd,
             commands::remove_account_cmd,
             commands::request_device_code_cmd,
             commands::finish_device_code_flow_cmd,
+            commands::start_browser_auth_cmd,
+            commands::export_account_cmd,
+            commands::import_account_cmd,
             // Account skin/cape commands
             commands::get_account_info_cmd,
+            commands::get_cached_account_info_cmd,
+            commands::warm_account_cache_cmd,
             commands::upload_skin_cmd,
             commands::set_skin_url_cmd,
             commands::reset_skin_cmd,
@@ -47,28 +88,54 @@ pub fn run() {
             // Config commands
             commands::get_config_cmd,
             commands::save_config_cmd,
+            commands::verify_curseforge_key_cmd,
+            commands::bust_manifest_cache_cmd,
             // Template commands
             commands::list_templates_cmd,
             commands::load_template_cmd,
             commands::create_profile_from_template_cmd,
+            // Quickstart commands
+            commands::list_quickstart_recipes_cmd,
+            commands::quickstart_cmd,
             // Store commands
             commands::store_search_cmd,
             commands::store_get_project_cmd,
             commands::store_get_versions_cmd,
+            commands::find_locale_packs_cmd,
+            commands::install_locale_packs_cmd,
+            commands::store_install_disclosure_cmd,
             commands::store_install_cmd,
+            commands::list_blocklist_cmd,
+            commands::block_project_cmd,
+            commands::block_hash_cmd,
+            commands::unblock_cmd,
+            commands::import_blocklist_cmd,
+            commands::export_blocklist_cmd,
             // Logs commands
             commands::list_log_files_cmd,
             commands::read_logs_cmd,
+            commands::get_game_events_cmd,
             commands::list_crash_reports_cmd,
             commands::read_crash_report_cmd,
             commands::start_log_watch,
+            // Mod config commands
+            commands::list_mod_configs_cmd,
+            commands::read_mod_config_cmd,
+            commands::write_mod_config_cmd,
+            commands::restore_mod_config_cmd,
+            commands::list_config_snapshots_cmd,
+            commands::restore_config_snapshot_cmd,
             // Version fetching commands
             commands::fetch_minecraft_versions_cmd,
             commands::fetch_fabric_versions_cmd,
             commands::fetch_quilt_versions_cmd,
             commands::fetch_neoforge_versions_cmd,
             commands::fetch_forge_versions_cmd,
+            commands::fetch_optifine_versions_cmd,
+            commands::fetch_liteloader_versions_cmd,
             commands::fetch_loader_versions_cmd,
+            commands::list_loaders_cmd,
+            commands::suggest_loader_cmd,
             // Java detection commands
             commands::detect_java_installations_cmd,
             commands::validate_java_path_cmd,
@@ -84,6 +151,7 @@ pub fn run() {
             commands::library_list_items_cmd,
             commands::library_get_item_cmd,
             commands::library_get_item_by_hash_cmd,
+            commands::library_get_item_history_cmd,
             commands::library_add_item_cmd,
             commands::library_update_item_cmd,
             commands::library_delete_item_cmd,
@@ -92,28 +160,65 @@ pub fn run() {
             commands::library_import_folder_cmd,
             commands::library_get_stats_cmd,
             commands::library_sync_cmd,
+            commands::library_auto_tag_cmd,
+            commands::library_verify_cmd,
+            commands::library_cancel_verify_cmd,
+            commands::list_jobs_cmd,
+            commands::cancel_job_cmd,
             commands::library_enrich_from_profiles_cmd,
             commands::library_list_tags_cmd,
             commands::library_create_tag_cmd,
             commands::library_delete_tag_cmd,
             commands::library_set_item_tags_cmd,
+            commands::library_add_tag_to_matching_cmd,
             commands::library_add_to_profile_cmd,
             // Settings and storage commands
             commands::get_data_path_cmd,
             commands::get_storage_stats_cmd,
+            commands::relocate_instance_folder_cmd,
             commands::get_unused_items_cmd,
             commands::purge_unused_items_cmd,
             commands::get_auto_update_enabled_cmd,
             commands::set_auto_update_enabled_cmd,
+            commands::get_crash_aggregation_enabled_cmd,
+            commands::set_crash_aggregation_enabled_cmd,
+            commands::get_auto_install_java_cmd,
+            commands::set_auto_install_java_cmd,
+            commands::get_warm_up_enabled_cmd,
+            commands::set_warm_up_enabled_cmd,
+            commands::is_metered_cmd,
+            commands::needs_metered_confirmation_cmd,
+            commands::set_metered_mode_cmd,
+            commands::get_snapshot_profile_cmd,
+            commands::set_snapshot_profile_cmd,
+            commands::sync_snapshot_profile_cmd,
+            commands::get_crash_stats_cmd,
+            commands::clear_crash_stats_cmd,
             // Update checking commands
             commands::check_all_updates_cmd,
             commands::check_profile_updates_cmd,
+            commands::list_watched_projects_cmd,
+            commands::watch_project_cmd,
+            commands::unwatch_project_cmd,
+            commands::dismiss_watched_release_cmd,
             commands::apply_content_update_cmd,
             commands::set_content_pinned_cmd,
             commands::set_content_enabled_cmd,
             // Profile organization commands
             commands::load_profile_organization_cmd,
-            commands::save_profile_organization_cmd
+            commands::save_profile_organization_cmd,
+            // Server properties commands
+            commands::get_server_properties_cmd,
+            commands::set_server_property_cmd,
+            commands::save_server_properties_cmd,
+            // Official launcher import commands
+            commands::import_vanilla_launcher_cmd,
+            // Space commands
+            commands::list_spaces_cmd,
+            commands::create_space_cmd,
+            commands::set_active_space_cmd,
+            commands::delete_space_cmd,
+            commands::get_data_dir_warning_cmd
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
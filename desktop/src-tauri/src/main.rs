@@ -21,5 +21,18 @@ fn main() {
         }
     }
 
+    // `--data-dir <dir>` opens another launcher root, like SHARD_HOME
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let dir = match arg.strip_prefix("--data-dir=") {
+            Some(dir) => Some(dir.to_string()),
+            None if arg == "--data-dir" => args.next(),
+            None => None,
+        };
+        if let Some(dir) = dir {
+            std::env::set_var(shard::paths::HOME_ENV, dir);
+        }
+    }
+
     shard_ui::run();
 }
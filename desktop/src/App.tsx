@@ -9,7 +9,7 @@ import { platform } from "@tauri-apps/plugin-os";
 
 import { useAppStore } from "./store";
 import { useOnline } from "./hooks";
-import type { LaunchEvent, ContentRef, ContentTab, Profile, LibraryItem } from "./types";
+import type { LaunchEvent, PrefetchEvent, SnapshotUpdate, ContentRef, ContentTab, Profile, LibraryItem, GameFilesReport } from "./types";
 import {
   ErrorBoundary,
   Sidebar,
@@ -26,7 +26,7 @@ import {
   JavaDownloadModal,
   WindowControls,
 } from "./components";
-import { formatContentName } from "./utils";
+import { formatContentName, requestConfirmation } from "./utils";
 import type { CreateProfileForm } from "./components";
 
 // Lazy load heavy components (three.js/skinview3d)
@@ -111,6 +111,8 @@ function App() {
       void precacheMcVersions();
       void precacheFabricVersions();
       void prefetchActiveAccountSkin();
+      // Refresh every account's tokens and skins so the accounts screen opens instantly
+      void invoke("warm_account_cache_cmd").catch(() => {});
     };
     void loadInitial();
   }, [loadProfiles, loadAccounts, loadConfig, loadProfileOrganization, precacheMcVersions, precacheFabricVersions, prefetchActiveAccountSkin]);
@@ -129,13 +131,57 @@ function App() {
     const unlisten = listen<LaunchEvent>("launch-status", (event) => {
       setLaunchStatus(event.payload);
       if (event.payload.stage === "error") {
-        notify("Launch failed", event.payload.message ?? "Unknown error");
+        const crash = event.payload.crash;
+        if (crash) {
+          const details = crash.lines.length > 0 ? crash.lines.join("\n") : event.payload.message ?? "";
+          notify(
+            crash.jvmCrash ? "Minecraft crashed (JVM)" : "Minecraft crashed",
+            crash.report ? `${details}\n\nSee ${crash.report}` : details
+          );
+        } else {
+          notify("Launch failed", event.payload.message ?? "Unknown error");
+        }
+        // A failed token refresh may have flagged the account for re-auth
+        void loadAccounts();
+      }
+      if (event.payload.stage === "tunnel" && event.payload.message) {
+        notify("Tunnel ready", event.payload.message);
       }
     });
     return () => {
       void unlisten.then((fn) => fn());
     };
-  }, [setLaunchStatus, notify]);
+  }, [setLaunchStatus, notify, loadAccounts]);
+
+  // Background pre-download of game files for new profiles
+  useEffect(() => {
+    const unlisten = listen<PrefetchEvent>("prefetch-status", (event) => {
+      const { profileId, stage, message } = event.payload;
+      if (stage === "done") {
+        notify("Game files ready", `${profileId} is ready to launch`);
+      } else if (stage === "error") {
+        notify("Pre-download failed", message ?? "Unknown error");
+      }
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, [notify]);
+
+  // The snapshot profile was created or moved to a new snapshot
+  useEffect(() => {
+    const unlisten = listen<SnapshotUpdate>("snapshot-update", (event) => {
+      const { profileId, previous, version } = event.payload;
+      notify(
+        `Snapshot ${version} is out`,
+        previous ? `${profileId} updated from ${previous}` : `Created profile ${profileId}`
+      );
+      void loadProfiles();
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, [notify, loadProfiles]);
 
   // Background app update check (non-blocking)
   useEffect(() => {
@@ -262,9 +308,9 @@ function App() {
     await runAction(async () => {
       const payload = {
         id: form.id.trim(),
-        mc_version: form.mcVersion.trim(),
-        loader_type: form.loaderType.trim() || null,
-        loader_version: form.loaderVersion.trim() || null,
+        mcVersion: form.mcVersion.trim(),
+        loaderType: form.loaderType.trim() || null,
+        loaderVersion: form.loaderVersion.trim() || null,
         java: form.java.trim() || null,
         memory: form.memory.trim() || null,
         args: form.args.trim() || null,
@@ -273,12 +319,15 @@ function App() {
       await loadProfiles();
       setSelectedProfileId(payload.id);
       setActiveModal(null);
+      if (isOnline && (await invoke<boolean>("get_warm_up_enabled_cmd").catch(() => true))) {
+        void invoke("prepare_in_background_cmd", { profileId: payload.id }).catch(() => {});
+      }
     });
-  }, [runAction, loadProfiles, setSelectedProfileId, setActiveModal]);
+  }, [runAction, loadProfiles, setSelectedProfileId, setActiveModal, isOnline]);
 
-  const handleCloneProfile = useCallback(async (src: string, dst: string) => {
+  const handleCloneProfile = useCallback(async (src: string, dst: string, manifestOnly: boolean) => {
     await runAction(async () => {
-      await invoke("clone_profile_cmd", { src, dst });
+      await invoke("clone_profile_cmd", { src, dst, manifestOnly });
       await loadProfiles();
       setSelectedProfileId(dst);
       setActiveModal(null);
@@ -376,6 +425,22 @@ function App() {
     }
   }, [selectedProfileId, notify]);
 
+  const handleRepairInstance = useCallback(async () => {
+    if (!selectedProfileId) return;
+    notify("Repairing instance", "Checking game files...");
+    try {
+      const report = await invoke<GameFilesReport>("repair_instance_cmd", { profileId: selectedProfileId });
+      const summary = `${report.checked} files checked, ${report.repaired.length} repaired`;
+      if (report.failed.length > 0) {
+        notify("Repair incomplete", `${summary}, ${report.failed.length} failed to download`);
+      } else {
+        notify("Instance repaired", summary);
+      }
+    } catch (err) {
+      notify("Repair failed", String(err));
+    }
+  }, [selectedProfileId, notify]);
+
   const handleCopyCommand = useCallback(async () => {
     if (!selectedProfileId) return;
     const command = `shard launch ${selectedProfileId}`;
@@ -392,7 +457,8 @@ function App() {
       onConfirm: async () => {
         setConfirmState(null);
         await runAction(async () => {
-          await invoke("delete_profile_cmd", { id });
+          const confirmToken = await requestConfirmation("delete_profile_cmd", id);
+          await invoke("delete_profile_cmd", { id, confirmToken });
           await loadProfiles();
           if (selectedProfileId === id) {
             setSelectedProfileId(null);
@@ -451,6 +517,7 @@ function App() {
                     key={profile.id}
                     onLaunch={handleLaunch}
                     onOpenInstance={handleOpenInstance}
+                    onRepairInstance={handleRepairInstance}
                     onCopyCommand={handleCopyCommand}
                     onShowJson={() => setActiveModal("json")}
                     onAddContent={openAddContentModal}
@@ -6,8 +6,9 @@ import { useAppStore } from "../store";
 import { SkinViewer, type ModelVariant } from "./SkinViewer";
 import { SkinHead } from "./SkinThumbnail";
 import { Field } from "./Field";
-import type { AccountInfo, Cape, Account, LibraryItem, LibraryFilter } from "../types";
+import type { AccountInfo, Cape, Account, AccountStatus, LibraryItem, LibraryFilter } from "../types";
 import { preloadCapeTextures } from "../lib/player-model";
+import { requestConfirmation } from "../utils";
 
 // Cape preview - extracts the front portion of the cape texture
 // Defined outside component to prevent remounting on each render
@@ -67,6 +68,16 @@ interface AccountViewProps {
   onAddAccount: () => void;
 }
 
+const ACCOUNT_STATUS_HINTS: Record<AccountStatus, string> = {
+  consent_revoked: "Launcher access to this account was revoked. Sign in again to grant access.",
+  password_changed: "The account password changed. Sign in again with the new password.",
+  session_expired: "The saved session expired. Sign in again.",
+  interaction_required: "Microsoft requires an interactive sign-in (e.g. two-step verification).",
+  child_account: "This is a child account. An adult must add it to a Microsoft family, then sign in again.",
+  no_xbox_account: "This account has no Xbox profile. Create one at xbox.com, then sign in again.",
+  region_blocked: "Xbox Live is not available in this account's region.",
+};
+
 export function AccountView({ onAddAccount }: AccountViewProps) {
   const { accounts, loadAccounts, notify, runAction, getActiveAccount, setActiveAccountSkinUrl } = useAppStore();
   const activeAccount = getActiveAccount();
@@ -90,7 +101,7 @@ export function AccountView({ onAddAccount }: AccountViewProps) {
     setLibraryLoading(true);
     try {
       const filter: LibraryFilter = {
-        content_type: "skin",
+        contentType: "skin",
         search: search || undefined,
         limit: 50,
       };
@@ -103,10 +114,10 @@ export function AccountView({ onAddAccount }: AccountViewProps) {
             const path = await invoke<string | null>("library_get_item_path_cmd", { id: item.id });
             return {
               ...item,
-              resolvedUrl: path ? convertFileSrc(path) : item.source_url || "",
+              resolvedUrl: path ? convertFileSrc(path) : item.sourceUrl || "",
             };
           } catch {
-            return { ...item, resolvedUrl: item.source_url || "" };
+            return { ...item, resolvedUrl: item.sourceUrl || "" };
           }
         })
       );
@@ -158,8 +169,14 @@ export function AccountView({ onAddAccount }: AccountViewProps) {
   }, []);
 
   const loadAccountInfo = useCallback(async (accountId: string) => {
-    setLoading(true);
     setError(null);
+    // Show what the warm-up pass cached while the fresh fetch runs
+    const cached = await invoke<AccountInfo | null>("get_cached_account_info_cmd", { id: accountId }).catch(() => null);
+    if (cached) {
+      setInfo(cached);
+    } else {
+      setLoading(true);
+    }
     try {
       const data = await invoke<AccountInfo>("get_account_info_cmd", { id: accountId });
       setInfo(data);
@@ -169,7 +186,7 @@ export function AccountView({ onAddAccount }: AccountViewProps) {
         setSkinVariant(activeSkin.variant as ModelVariant);
       }
       // Update global skin URL for sidebar
-      const skinUrl = activeSkin?.url || data.skin_url || null;
+      const skinUrl = activeSkin?.url || data.skinUrl || null;
       setActiveAccountSkinUrl(skinUrl);
       // Preload all cape textures for instant switching
       const capeUrls = data.profile?.capes?.map((c) => c.url).filter(Boolean) ?? [];
@@ -208,7 +225,8 @@ export function AccountView({ onAddAccount }: AccountViewProps) {
       return;
     }
     await runAction(async () => {
-      await invoke("remove_account_cmd", { id: account.uuid });
+      const confirmToken = await requestConfirmation("remove_account_cmd", account.uuid);
+      await invoke("remove_account_cmd", { id: account.uuid, confirmToken });
       await loadAccounts();
     });
   };
@@ -334,12 +352,12 @@ export function AccountView({ onAddAccount }: AccountViewProps) {
     if (!url) return null;
     return url.startsWith("http://") ? url.replace("http://", "https://") : url;
   };
-  const activeSkinUrl = normalizeTextureUrl(activeSkin?.url ?? info?.skin_url) ?? "";
+  const activeSkinUrl = normalizeTextureUrl(activeSkin?.url ?? info?.skinUrl) ?? "";
   const activeCapeUrl = activeCape
     ? normalizeTextureUrl(activeCape.url)
     : info?.profile
       ? null
-      : normalizeTextureUrl(info?.cape_url ?? null);
+      : normalizeTextureUrl(info?.capeUrl ?? null);
 
   // Get avatar URL - use skin texture directly for active account, mc-heads for others
   const getAvatarUrl = (uuid: string, skinUrl?: string) => {
@@ -352,6 +370,8 @@ export function AccountView({ onAddAccount }: AccountViewProps) {
     return `https://mc-heads.net/avatar/${cleanUuid}/64`;
   };
 
+  const statusHint = activeAccount?.status ? ACCOUNT_STATUS_HINTS[activeAccount.status] : null;
+
   if (!accounts || accounts.accounts.length === 0) {
     return (
       <div className="view-transition" >
@@ -382,6 +402,17 @@ export function AccountView({ onAddAccount }: AccountViewProps) {
         </div>
       )}
 
+      {statusHint && (
+        <div className="account-error">
+          <p>{statusHint}</p>
+          {activeAccount?.status !== "region_blocked" && (
+            <button className="btn btn-primary btn-sm" onClick={onAddAccount}>
+              Sign in again
+            </button>
+          )}
+        </div>
+      )}
+
       {error && !loading && (
         <div className="account-error">
           <p>{error}</p>
@@ -656,7 +687,7 @@ export function AccountView({ onAddAccount }: AccountViewProps) {
                                 {item.name}
                               </span>
                               <span className="account-library-card-date">
-                                {new Date(item.added_at).toLocaleDateString()}
+                                {new Date(item.addedAt).toLocaleDateString()}
                               </span>
                             </div>
                             {isSelected && (
@@ -30,12 +30,12 @@ export interface ContentItemData {
   hash: string;
   version?: string | null;
   platform?: string | null;
-  project_id?: string | null;
-  source_platform?: string | null;
-  source_project_id?: string | null;
-  source_version?: string | null;
-  file_name?: string | null;
-  file_size?: number | null;
+  projectId?: string | null;
+  sourcePlatform?: string | null;
+  sourceProjectId?: string | null;
+  sourceVersion?: string | null;
+  fileName?: string | null;
+  fileSize?: number | null;
   enabled?: boolean;
   pinned?: boolean;
 }
@@ -60,8 +60,8 @@ function getPlatformColor(platform: Platform): string {
 }
 
 function getSourceUrl(item: ContentItemData, contentType: ContentType): string | null {
-  const platformRaw = (item.platform || item.source_platform)?.toLowerCase();
-  const projectId = item.project_id || item.source_project_id;
+  const platformRaw = (item.platform || item.sourcePlatform)?.toLowerCase();
+  const projectId = item.projectId || item.sourceProjectId;
 
   if (!projectId || !platformRaw || platformRaw === "local" || platformRaw === "store") return null;
 
@@ -87,11 +87,11 @@ export function ContentItemRow({
   actions,
   showBadges = true,
 }: ContentItemRowProps) {
-  const platform = ((item.platform || item.source_platform)?.toLowerCase() || "local") as Platform;
+  const platform = ((item.platform || item.sourcePlatform)?.toLowerCase() || "local") as Platform;
   const isPinned = item.pinned ?? false;
   const isEnabled = item.enabled ?? true;
   const platformColor = getPlatformColor(platform);
-  const version = formatVersion(item.version || item.source_version);
+  const version = formatVersion(item.version || item.sourceVersion);
   const sourceUrl = getSourceUrl(item, contentType);
 
   return (
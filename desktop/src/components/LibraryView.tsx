@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { convertFileSrc } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import { openUrl } from "@tauri-apps/plugin-opener";
@@ -8,7 +9,7 @@ import { useAppStore } from "../store";
 import { SkinHead } from "./SkinThumbnail";
 import { ContentItemRow } from "./ContentItemRow";
 import { PlatformIcon, PLATFORM_COLORS, type Platform } from "./PlatformIcon";
-import type { LibraryItem, LibraryTag, LibraryStats, LibraryFilter, LibraryImportResult, LibraryContentType } from "../types";
+import type { LibraryItem, LibraryTag, LibraryStats, LibraryFilter, LibraryImportResult, LibraryAutoTagResult, LibraryContentType, StoreVerifyEvent } from "../types";
 import { formatFileSize, formatContentName, formatFileName, formatVersion } from "../utils";
 
 // Extended library item with resolved skin URL for skins
@@ -39,9 +40,33 @@ export function LibraryView() {
   const [importing, setImporting] = useState(false);
   const [showTagDropdown, setShowTagDropdown] = useState(false);
   const [searchExpanded, setSearchExpanded] = useState(false);
+  const [verifying, setVerifying] = useState(false);
   const searchInputRef = useRef<HTMLInputElement>(null);
   const isSystemTag = (name: string) => name.startsWith("mc:") || name.startsWith("loader:");
-  const visibleTags = tags.filter((tag) => !isSystemTag(tag.name));
+  // Version family tags from store data (mc:1.21.x) are useful filters
+  const isVersionFamilyTag = (name: string) => /^mc:\d+\.\d+\.x$/.test(name);
+  const visibleTags = tags.filter((tag) => !isSystemTag(tag.name) || isVersionFamilyTag(tag.name));
+
+  // Store verification runs in the background and reports through events
+  useEffect(() => {
+    const unlisten = listen<StoreVerifyEvent>("store-verify", (event) => {
+      const { stage, report, message } = event.payload;
+      if (stage === "progress") return;
+      setVerifying(false);
+      if (stage === "error") {
+        notify("Verify failed", message ?? "Unknown error");
+      } else if (report) {
+        const corrupt = report.corrupt.length;
+        const summary = corrupt > 0
+          ? `${corrupt} of ${report.checked} files are corrupted`
+          : `${report.checked} files OK`;
+        notify(stage === "cancelled" ? "Verify cancelled" : "Verify complete", summary);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [notify]);
 
   // Focus input when search expands
   useEffect(() => {
@@ -69,7 +94,7 @@ export function LibraryView() {
   const loadItems = useCallback(async () => {
     try {
       const filter: LibraryFilter = {
-        content_type: category === "all" ? undefined : category,
+        contentType: category === "all" ? undefined : category,
         search: search || undefined,
         tags: selectedTagFilter ? [selectedTagFilter] : undefined,
         limit: 100,
@@ -79,15 +104,15 @@ export function LibraryView() {
       // Resolve file paths to asset URLs for skin items
       const itemsWithUrls: LibraryItemWithUrl[] = await Promise.all(
         data.map(async (item) => {
-          if (item.content_type === "skin") {
+          if (item.contentType === "skin") {
             try {
               const path = await invoke<string | null>("library_get_item_path_cmd", { id: item.id });
               return {
                 ...item,
-                resolvedUrl: path ? convertFileSrc(path) : item.source_url || "",
+                resolvedUrl: path ? convertFileSrc(path) : item.sourceUrl || "",
               };
             } catch {
-              return { ...item, resolvedUrl: item.source_url || "" };
+              return { ...item, resolvedUrl: item.sourceUrl || "" };
             }
           }
           return item;
@@ -127,6 +152,17 @@ export function LibraryView() {
     load();
   }, [loadItems, loadTags, loadStats]);
 
+  // The desktop app refreshes version tags from store data once a day
+  useEffect(() => {
+    const unlisten = listen("library-tags-updated", () => {
+      loadItems();
+      loadTags();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [loadItems, loadTags]);
+
   const handleCategoryChange = (newCategory: LibraryCategory) => {
     setCategory(newCategory);
     setSelectedItem(null);
@@ -155,7 +191,7 @@ export function LibraryView() {
 
     for (const path of paths) {
       try {
-        await invoke<LibraryItem>("library_import_file_cmd", { path, content_type: contentType });
+        await invoke<LibraryItem>("library_import_file_cmd", { path, contentType });
         added++;
       } catch (err) {
         const errStr = String(err);
@@ -186,7 +222,7 @@ export function LibraryView() {
     try {
       const importResult = await invoke<LibraryImportResult>("library_import_folder_cmd", {
         path: result,
-        content_type: contentType,
+        contentType,
         recursive: true,
       });
       notify("Import complete", `Added ${importResult.added}, skipped ${importResult.skipped}`);
@@ -213,6 +249,32 @@ export function LibraryView() {
     await Promise.all([loadItems(), loadStats()]);
   };
 
+  const handleAutoTag = async () => {
+    setImporting(true);
+    try {
+      const result = await invoke<LibraryAutoTagResult>("library_auto_tag_cmd");
+      notify("Tagging complete", `Updated Minecraft version tags on ${result.tagged} items`);
+    } catch (err) {
+      notify("Tagging failed", String(err));
+    }
+    setImporting(false);
+    await Promise.all([loadItems(), loadTags()]);
+  };
+
+  const handleVerify = async () => {
+    try {
+      if (verifying) {
+        await invoke("library_cancel_verify_cmd");
+      } else {
+        // Low priority so a running game or the UI doesn't stutter
+        await invoke("library_verify_cmd", { lowPriority: true });
+        setVerifying(true);
+      }
+    } catch (err) {
+      notify("Verify failed", String(err));
+    }
+  };
+
   const handleAddToProfile = async (item: LibraryItem) => {
     if (!selectedProfileId) {
       notify("No profile selected", "Select a profile first");
@@ -253,8 +315,8 @@ export function LibraryView() {
   };
 
   const getSourceUrl = (item: LibraryItem): string | null => {
-    const platform = item.source_platform?.toLowerCase();
-    const projectId = item.source_project_id;
+    const platform = item.sourcePlatform?.toLowerCase();
+    const projectId = item.sourceProjectId;
 
     if (!projectId || platform === "local" || platform === "store") return null;
 
@@ -264,7 +326,7 @@ export function LibraryView() {
       shaderpack: { modrinth: "shader", curseforge: "shaders" },
     };
 
-    const paths = typeMap[item.content_type];
+    const paths = typeMap[item.contentType];
     if (!paths) return null;
 
     if (platform === "modrinth") {
@@ -298,10 +360,10 @@ export function LibraryView() {
             {CATEGORY_LABELS[cat]}
             {stats && cat !== "all" && (
               <span style={{ marginLeft: 6, opacity: 0.6, fontSize: 11 }}>
-                ({cat === "mod" ? stats.mods_count :
-                  cat === "resourcepack" ? stats.resourcepacks_count :
-                  cat === "shaderpack" ? stats.shaderpacks_count :
-                  stats.skins_count})
+                ({cat === "mod" ? stats.modsCount :
+                  cat === "resourcepack" ? stats.resourcepacksCount :
+                  cat === "shaderpack" ? stats.shaderpacksCount :
+                  stats.skinsCount})
               </span>
             )}
           </button>
@@ -404,12 +466,18 @@ export function LibraryView() {
         <button className="btn btn-secondary" onClick={handleSync} disabled={importing}>
           Sync
         </button>
+        <button className="btn btn-secondary" onClick={handleAutoTag} disabled={importing}>
+          Tag Versions
+        </button>
+        <button className="btn btn-secondary" onClick={handleVerify} disabled={importing}>
+          {verifying ? "Cancel Verify" : "Verify"}
+        </button>
       </div>
 
       {/* Stats */}
       {stats && (
         <p style={{ fontSize: 12, color: "var(--text-muted)", marginBottom: 20 }}>
-          {stats.total_items} items &middot; {formatFileSize(stats.total_size)} total
+          {stats.totalItems} items &middot; {formatFileSize(stats.totalSize)} total
         </p>
       )}
 
@@ -439,20 +507,20 @@ export function LibraryView() {
           <div className="content-list">
           {items.map((item) => {
             // Use ContentItemRow for mods, resourcepacks, and shaderpacks
-            if (item.content_type !== "skin") {
+            if (item.contentType !== "skin") {
               return (
                 <ContentItemRow
                   key={item.id}
                   item={{
                     name: item.name,
                     hash: item.hash,
-                    version: item.source_version,
-                    source_platform: item.source_platform,
-                    source_project_id: item.source_project_id,
-                    file_name: item.file_name,
-                    file_size: item.file_size,
+                    version: item.sourceVersion,
+                    sourcePlatform: item.sourcePlatform,
+                    sourceProjectId: item.sourceProjectId,
+                    fileName: item.fileName,
+                    fileSize: item.fileSize,
                   }}
-                  contentType={item.content_type === "mod" ? "mods" : item.content_type === "resourcepack" ? "resourcepacks" : "shaderpacks"}
+                  contentType={item.contentType === "mod" ? "mods" : item.contentType === "resourcepack" ? "resourcepacks" : "shaderpacks"}
                   selected={selectedItem?.id === item.id}
                   onClick={() => setSelectedItem(item)}
                   showBadges={false}
@@ -505,9 +573,9 @@ export function LibraryView() {
                     <span className="content-meta-platform" style={{ color: "var(--text-muted)" }}>
                       Skin
                     </span>
-                    {item.file_size && (
+                    {item.fileSize && (
                       <span style={{ color: "var(--text-muted)", fontSize: 12 }}>
-                        {formatFileSize(item.file_size)}
+                        {formatFileSize(item.fileSize)}
                       </span>
                     )}
                   </div>
@@ -542,44 +610,44 @@ export function LibraryView() {
                 <div style={{ color: "var(--text-muted)", fontSize: 11, textTransform: "uppercase", marginBottom: 2 }}>
                   Type
                 </div>
-                <div>{getContentTypeLabel(selectedItem.content_type)}</div>
+                <div>{getContentTypeLabel(selectedItem.contentType)}</div>
               </div>
 
-              {selectedItem.file_name && (
+              {selectedItem.fileName && (
                 <div>
                   <div style={{ color: "var(--text-muted)", fontSize: 11, textTransform: "uppercase", marginBottom: 2 }}>
                     File
                   </div>
-                  <div style={{ wordBreak: "break-all" }}>{formatFileName(selectedItem.file_name)}</div>
+                  <div style={{ wordBreak: "break-all" }}>{formatFileName(selectedItem.fileName)}</div>
                 </div>
               )}
 
-              {selectedItem.file_size && (
+              {selectedItem.fileSize && (
                 <div>
                   <div style={{ color: "var(--text-muted)", fontSize: 11, textTransform: "uppercase", marginBottom: 2 }}>
                     Size
                   </div>
-                  <div>{formatFileSize(selectedItem.file_size)}</div>
+                  <div>{formatFileSize(selectedItem.fileSize)}</div>
                 </div>
               )}
 
-              {selectedItem.source_platform && (
+              {selectedItem.sourcePlatform && (
                 <div>
                   <div style={{ color: "var(--text-muted)", fontSize: 11, textTransform: "uppercase", marginBottom: 2 }}>
                     Source
                   </div>
                   {(() => {
                     const sourceUrl = getSourceUrl(selectedItem);
-                    const platformColor = getPlatformColor(selectedItem.source_platform);
+                    const platformColor = getPlatformColor(selectedItem.sourcePlatform);
                     if (sourceUrl) {
                       return (
                         <button
                           className="content-meta-platform content-meta-platform-link"
                           style={{ color: platformColor, padding: 0, fontSize: 13 }}
                           onClick={() => openUrl(sourceUrl)}
-                          title={`Open on ${selectedItem.source_platform}`}
+                          title={`Open on ${selectedItem.sourcePlatform}`}
                         >
-                          {selectedItem.source_platform.charAt(0).toUpperCase() + selectedItem.source_platform.slice(1)}
+                          {selectedItem.sourcePlatform.charAt(0).toUpperCase() + selectedItem.sourcePlatform.slice(1)}
                           <svg width="10" height="10" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round" style={{ marginLeft: 4 }}>
                             <path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6" />
                             <polyline points="15 3 21 3 21 9" />
@@ -588,7 +656,7 @@ export function LibraryView() {
                         </button>
                       );
                     }
-                    return <div style={{ textTransform: "capitalize" }}>{selectedItem.source_platform}</div>;
+                    return <div style={{ textTransform: "capitalize" }}>{selectedItem.sourcePlatform}</div>;
                   })()}
                 </div>
               )}
@@ -597,15 +665,15 @@ export function LibraryView() {
                 <div style={{ color: "var(--text-muted)", fontSize: 11, textTransform: "uppercase", marginBottom: 2 }}>
                   Added
                 </div>
-                <div>{new Date(selectedItem.added_at).toLocaleDateString()}</div>
+                <div>{new Date(selectedItem.addedAt).toLocaleDateString()}</div>
               </div>
 
-              {selectedItem.used_by_profiles.length > 0 && (
+              {selectedItem.usedByProfiles.length > 0 && (
                 <div>
                   <div style={{ color: "var(--text-muted)", fontSize: 11, textTransform: "uppercase", marginBottom: 2 }}>
                     Used by
                   </div>
-                  <div>{selectedItem.used_by_profiles.join(", ")}</div>
+                  <div>{selectedItem.usedByProfiles.join(", ")}</div>
                 </div>
               )}
 
@@ -615,7 +683,7 @@ export function LibraryView() {
             </div>
 
             <div style={{ display: "flex", flexDirection: "column", gap: 8, marginTop: 20 }}>
-              {selectedItem.content_type !== "skin" && (
+              {selectedItem.contentType !== "skin" && (
                 <button
                   className="btn btn-primary"
                   onClick={() => handleAddToProfile(selectedItem)}
@@ -6,7 +6,7 @@ import forgeIcon from "../assets/icons/forge.png";
 import neoforgeIcon from "../assets/icons/neoforge.png";
 import quiltIcon from "../assets/icons/quilt.png";
 
-export type LoaderType = "fabric" | "forge" | "neoforge" | "quilt" | "vanilla" | null;
+export type LoaderType = "fabric" | "forge" | "neoforge" | "quilt" | "optifine" | "liteloader" | "vanilla" | null;
 
 interface LoaderIconProps {
   loader: LoaderType;
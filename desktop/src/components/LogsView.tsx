@@ -77,7 +77,7 @@ export function LogsView() {
           thread: null,
           message: content,
           raw: content,
-          line_number: 1,
+          lineNumber: 1,
         }]);
       } else {
         const entries = await invoke<LogEntry[]>("read_logs_cmd", {
@@ -352,6 +352,7 @@ export function LogsView() {
                   <span className="logs-file-meta">
                     {formatFileSize(file.size)} · {formatTimeAgo(file.modified)}
                     {file.is_current && <span className="logs-file-current">Current</span>}
+                    {file.jvm_crash && <span className="logs-file-current">JVM crash</span>}
                   </span>
                 </div>
                 <svg width="14" height="14" viewBox="0 0 14 14" fill="none" style={{ opacity: 0.3 }}>
@@ -407,7 +408,7 @@ export function LogsView() {
             <>
               <div ref={logsContainerRef} className="logs-output">
                 {filteredLogs.map((entry, i) => (
-                  <div key={`${entry.line_number}-${i}`} className="logs-line" data-level={entry.level}>
+                  <div key={`${entry.lineNumber}-${i}`} className="logs-line" data-level={entry.level}>
                     {entry.timestamp && (
                       <span className="logs-time">{entry.timestamp}</span>
                     )}
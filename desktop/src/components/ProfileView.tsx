@@ -2,7 +2,7 @@ import { useState, useRef, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import clsx from "clsx";
 import { useAppStore } from "../store";
-import type { ContentRef, ContentTab, Profile } from "../types";
+import type { ContentRef, ContentTab, LoaderInfo, PresetApplied, Profile, RunningInstance } from "../types";
 import { getContentTypeLabel, getContentTypeLabelPlural } from "../utils";
 import { ContentItemRow } from "./ContentItemRow";
 import type { Platform } from "./PlatformIcon";
@@ -10,6 +10,7 @@ import type { Platform } from "./PlatformIcon";
 interface ProfileViewProps {
   onLaunch: () => void;
   onOpenInstance: () => void;
+  onRepairInstance: () => void;
   onCopyCommand: () => void;
   onShowJson: () => void;
   onAddContent: (kind: ContentTab) => void;
@@ -21,6 +22,7 @@ type ExpandedDropdown = "version" | "loader" | null;
 export function ProfileView({
   onLaunch,
   onOpenInstance,
+  onRepairInstance,
   onCopyCommand,
   onShowJson,
   onAddContent,
@@ -51,15 +53,55 @@ export function ProfileView({
   const [selectedLoaderType, setSelectedLoaderType] = useState<string>("");
   const [selectedLoaderVersion, setSelectedLoaderVersion] = useState<string>("");
   const [saving, setSaving] = useState(false);
+  const [stopping, setStopping] = useState(false);
   const dropdownRef = useRef<HTMLDivElement>(null);
 
-  // Loader versions state (fetched dynamically based on loader type)
+  // The launch-status events report the exit once the game is gone
+  const handleStop = useCallback(async () => {
+    if (!profile) return;
+    setStopping(true);
+    try {
+      await invoke<RunningInstance[]>("kill_instance_cmd", { profileId: profile.id });
+    } catch (err) {
+      notify("Failed to stop", String(err));
+    } finally {
+      setStopping(false);
+    }
+  }, [profile, notify]);
+
+  // Switch resource/shader packs to a saved preset before launching
+  const handleApplyPreset = useCallback(async (name: string) => {
+    if (!profile || !name) return;
+    try {
+      const applied = await invoke<PresetApplied>("apply_profile_preset_cmd", { profileId: profile.id, name });
+      for (const warning of applied.warnings) {
+        notify("Preset applied", warning);
+      }
+      await loadProfile(profile.id);
+    } catch (err) {
+      notify("Failed to apply preset", String(err));
+    }
+  }, [profile, notify, loadProfile]);
+
+  // Profile notes (README.md shipped with the profile)
+  const [notes, setNotes] = useState<string | null>(null);
+  const [notesDraft, setNotesDraft] = useState<string | null>(null);
+  const [savingNotes, setSavingNotes] = useState(false);
+
+  // Loaders the launcher supports, and their versions (fetched dynamically based on loader type)
+  const [loaders, setLoaders] = useState<LoaderInfo[]>([]);
   const [loaderVersions, setLoaderVersions] = useState<string[]>([]);
   const [loaderVersionsLoading, setLoaderVersionsLoading] = useState(false);
   const loaderVersionsCacheRef = useRef<Record<string, string[]>>({});
   // Track expected loader type to avoid race conditions when switching quickly
   const expectedLoaderTypeRef = useRef<string>("");
 
+  useEffect(() => {
+    invoke<LoaderInfo[]>("list_loaders_cmd")
+      .then(setLoaders)
+      .catch((err) => console.error("Failed to list loaders:", err));
+  }, []);
+
   // Close dropdown when clicking outside
   useEffect(() => {
     const handleClickOutside = (e: MouseEvent) => {
@@ -86,7 +128,8 @@ export function ProfileView({
     // Track expected loader type to avoid race conditions when switching quickly
     expectedLoaderTypeRef.current = normalizedType;
     // Create cache key including MC version for loaders that depend on it
-    const cacheKey = ["forge", "neoforge"].includes(normalizedType) ? `${normalizedType}:${mcVersion}` : normalizedType;
+    const perMcVersion = loaders.find((l) => l.id === normalizedType)?.latestPerMcVersion ?? true;
+    const cacheKey = perMcVersion ? `${normalizedType}:${mcVersion}` : normalizedType;
 
     // Check cache first
     if (loaderVersionsCacheRef.current[cacheKey]) {
@@ -120,7 +163,7 @@ export function ProfileView({
         setLoaderVersionsLoading(false);
       }
     }
-  }, []);
+  }, [loaders]);
 
   // Ensure versions are loaded (fallback if precache hasn't completed yet)
   const handleExpandVersion = useCallback(() => {
@@ -162,6 +205,28 @@ export function ProfileView({
     }
   }, [fetchLoaderVersions, profile?.mcVersion]);
 
+  useEffect(() => {
+    if (!profile?.id) return;
+    setNotesDraft(null);
+    invoke<string | null>("get_profile_notes_cmd", { id: profile.id })
+      .then(setNotes)
+      .catch(() => setNotes(null));
+  }, [profile?.id]);
+
+  const handleSaveNotes = async () => {
+    if (!profile || notesDraft === null) return;
+    setSavingNotes(true);
+    try {
+      await invoke("save_profile_notes_cmd", { id: profile.id, notes: notesDraft });
+      setNotes(notesDraft.trim() ? notesDraft : null);
+      setNotesDraft(null);
+    } catch (err) {
+      notify("Failed to save notes", String(err));
+    } finally {
+      setSavingNotes(false);
+    }
+  };
+
   const handleVersionSelect = async (version: string) => {
     if (!profile || version === profile.mcVersion) {
       setExpandedDropdown(null);
@@ -361,10 +426,9 @@ export function ProfileView({
                       onChange={(e) => handleLoaderTypeChange(e.target.value)}
                     >
                       <option value="">Vanilla (no loader)</option>
-                      <option value="fabric">Fabric</option>
-                      <option value="quilt">Quilt</option>
-                      <option value="forge">Forge</option>
-                      <option value="neoforge">NeoForge</option>
+                      {loaders.map((l) => (
+                        <option key={l.id} value={l.id}>{l.name}</option>
+                      ))}
                     </select>
                   </div>
                   {selectedLoaderType && (
@@ -416,6 +480,19 @@ export function ProfileView({
             </div>
           </div>
         </div>
+        {profile.presets && Object.keys(profile.presets).length > 0 && (
+          <select
+            className="select select-sm"
+            value=""
+            onChange={(e) => handleApplyPreset(e.target.value)}
+            disabled={isWorking || !!launchStatus}
+          >
+            <option value="">Preset...</option>
+            {Object.keys(profile.presets).map((name) => (
+              <option key={name} value={name}>{name}</option>
+            ))}
+          </select>
+        )}
         <button
           className="btn-launch"
           onClick={onLaunch}
@@ -426,6 +503,11 @@ export function ProfileView({
           </svg>
           <span>{launchStatus ? launchStatus.stage.charAt(0).toUpperCase() + launchStatus.stage.slice(1) : "Launch"}</span>
         </button>
+        {launchStatus?.stage === "running" && (
+          <button className="btn btn-danger btn-sm" onClick={handleStop} disabled={stopping}>
+            {stopping ? "Stopping..." : "Stop"}
+          </button>
+        )}
       </div>
 
       {/* Content section */}
@@ -490,7 +572,7 @@ export function ProfileView({
                     hash: item.hash,
                     version: item.version,
                     platform: item.platform,
-                    project_id: item.project_id,
+                    projectId: item.projectId,
                     enabled: item.enabled,
                     pinned: item.pinned,
                   }}
@@ -547,6 +629,39 @@ export function ProfileView({
         )}
       </div>
 
+      {/* Notes section */}
+      <div className="section-panel">
+        <div className="section-header">
+          <span>Notes</span>
+          {notesDraft === null && (
+            <button className="link" onClick={() => setNotesDraft(notes ?? "")}>
+              {notes ? "Edit" : "+ Add"}
+            </button>
+          )}
+        </div>
+        {notesDraft !== null ? (
+          <>
+            <textarea
+              className="input profile-notes-editor"
+              value={notesDraft}
+              onChange={(e) => setNotesDraft(e.target.value)}
+              placeholder="Markdown notes shipped with this profile (install steps, server info, ...)"
+              rows={8}
+            />
+            <div className="actions-row">
+              <button className="btn btn-ghost btn-sm" onClick={() => setNotesDraft(null)}>Cancel</button>
+              <button className="btn btn-primary btn-sm" onClick={handleSaveNotes} disabled={savingNotes}>
+                {savingNotes ? "Saving..." : "Save"}
+              </button>
+            </div>
+          </>
+        ) : notes ? (
+          <div className="profile-notes">{notes}</div>
+        ) : (
+          <div className="profile-notes profile-notes-empty">No notes</div>
+        )}
+      </div>
+
       {/* Actions section */}
       <div className="section-panel">
         <div className="section-header">
@@ -554,6 +669,7 @@ export function ProfileView({
         </div>
         <div className="actions-row">
           <button className="btn btn-ghost btn-sm" onClick={onOpenInstance}>Open folder</button>
+          <button className="btn btn-ghost btn-sm" onClick={onRepairInstance}>Repair instance</button>
           <button className="btn btn-ghost btn-sm" onClick={onCopyCommand}>Copy CLI command</button>
           <button className="btn btn-ghost btn-sm" onClick={onShowJson}>View JSON</button>
         </div>
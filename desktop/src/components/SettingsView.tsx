@@ -3,9 +3,10 @@ import { invoke } from "@tauri-apps/api/core";
 import { getVersion } from "@tauri-apps/api/app";
 import { revealItemInDir, openUrl } from "@tauri-apps/plugin-opener";
 import { check } from "@tauri-apps/plugin-updater";
+import { open } from "@tauri-apps/plugin-dialog";
 import { platform, arch, version as osVersion } from "@tauri-apps/plugin-os";
 import { useAppStore } from "../store";
-import type { StorageStats, UpdateCheckResult, ContentUpdate, JavaInstallation, PurgeResult } from "../types";
+import type { StorageStats, UpdateCheckResult, ContentUpdate, WatchedRelease, JavaInstallation, PurgeResult, SpaceInfo, CrashSignatureStats, SnapshotUpdate, FolderUsage } from "../types";
 import { formatFileSize } from "../utils";
 import { PurgeStorageModal } from "./modals/PurgeStorageModal";
 
@@ -19,10 +20,15 @@ type StorageCategory = {
 type SettingsSection = "general" | "storage" | "java" | "updates" | "about";
 
 export function SettingsView() {
-  const { notify } = useAppStore();
+  const { notify, loadProfiles } = useAppStore();
   const [activeSection, setActiveSection] = useState<SettingsSection>("general");
   const [stats, setStats] = useState<StorageStats | null>(null);
   const [autoUpdate, setAutoUpdate] = useState(true);
+  const [crashAggregation, setCrashAggregation] = useState(false);
+  const [autoInstallJava, setAutoInstallJava] = useState(false);
+  const [warmUp, setWarmUp] = useState(true);
+  const [crashStats, setCrashStats] = useState<CrashSignatureStats[]>([]);
+  const [snapshotProfile, setSnapshotProfile] = useState<string | null>(null);
   const [loading, setLoading] = useState(true);
   const [checkingUpdates, setCheckingUpdates] = useState(false);
   const [updateResult, setUpdateResult] = useState<UpdateCheckResult | null>(null);
@@ -39,6 +45,10 @@ export function SettingsView() {
   // Purge modal state
   const [purgeModalOpen, setPurgeModalOpen] = useState(false);
 
+  // Spaces state
+  const [spaces, setSpaces] = useState<SpaceInfo[]>([]);
+  const [dataDirWarning, setDataDirWarning] = useState<string | null>(null);
+
   // OS info state
   const [osInfo, setOsInfo] = useState<{ platform: string; arch: string; version: string } | null>(null);
 
@@ -60,6 +70,56 @@ export function SettingsView() {
     }
   }, []);
 
+  const loadAutoInstallJava = useCallback(async () => {
+    try {
+      setAutoInstallJava(await invoke<boolean>("get_auto_install_java_cmd"));
+    } catch {
+      setAutoInstallJava(false);
+    }
+  }, []);
+
+  const loadWarmUp = useCallback(async () => {
+    try {
+      setWarmUp(await invoke<boolean>("get_warm_up_enabled_cmd"));
+    } catch {
+      setWarmUp(true);
+    }
+  }, []);
+
+  const loadSnapshotProfile = useCallback(async () => {
+    try {
+      setSnapshotProfile(await invoke<string | null>("get_snapshot_profile_cmd"));
+    } catch {
+      setSnapshotProfile(null);
+    }
+  }, []);
+
+  const loadCrashStats = useCallback(async () => {
+    try {
+      const [enabled, list] = await Promise.all([
+        invoke<boolean>("get_crash_aggregation_enabled_cmd"),
+        invoke<CrashSignatureStats[]>("get_crash_stats_cmd"),
+      ]);
+      setCrashAggregation(enabled);
+      setCrashStats(list);
+    } catch {
+      setCrashStats([]);
+    }
+  }, []);
+
+  const loadSpaces = useCallback(async () => {
+    try {
+      const [list, warning] = await Promise.all([
+        invoke<SpaceInfo[]>("list_spaces_cmd"),
+        invoke<string | null>("get_data_dir_warning_cmd"),
+      ]);
+      setSpaces(list);
+      setDataDirWarning(warning);
+    } catch {
+      setSpaces([]);
+    }
+  }, []);
+
   const loadJavaInstallations = useCallback(async () => {
     setDetectingJava(true);
     try {
@@ -74,11 +134,11 @@ export function SettingsView() {
   useEffect(() => {
     const load = async () => {
       setLoading(true);
-      await Promise.all([loadStats(), loadAutoUpdate()]);
+      await Promise.all([loadStats(), loadAutoUpdate(), loadSpaces(), loadCrashStats(), loadSnapshotProfile(), loadAutoInstallJava(), loadWarmUp()]);
       setLoading(false);
     };
     load();
-  }, [loadStats, loadAutoUpdate]);
+  }, [loadStats, loadAutoUpdate, loadSpaces, loadCrashStats, loadSnapshotProfile, loadAutoInstallJava, loadWarmUp]);
 
   useEffect(() => {
     getVersion()
@@ -115,14 +175,76 @@ export function SettingsView() {
     }
   };
 
+  const handleCrashAggregationToggle = async () => {
+    const newValue = !crashAggregation;
+    try {
+      await invoke("set_crash_aggregation_enabled_cmd", { enabled: newValue });
+      setCrashAggregation(newValue);
+      notify("Settings saved", `Crash statistics ${newValue ? "enabled" : "disabled"}`);
+    } catch (err) {
+      notify("Failed to save settings", String(err));
+    }
+  };
+
+  const handleAutoInstallJavaToggle = async () => {
+    const newValue = !autoInstallJava;
+    try {
+      await invoke("set_auto_install_java_cmd", { enabled: newValue });
+      setAutoInstallJava(newValue);
+      notify("Settings saved", `Automatic Java downloads ${newValue ? "enabled" : "disabled"}`);
+    } catch (err) {
+      notify("Failed to save settings", String(err));
+    }
+  };
+
+  const handleWarmUpToggle = async () => {
+    const newValue = !warmUp;
+    try {
+      await invoke("set_warm_up_enabled_cmd", { enabled: newValue });
+      setWarmUp(newValue);
+      notify("Settings saved", `Background warm-up ${newValue ? "enabled" : "disabled"}`);
+    } catch (err) {
+      notify("Failed to save settings", String(err));
+    }
+  };
+
+  const handleSnapshotToggle = async () => {
+    const profileId = snapshotProfile ? null : "snapshots";
+    try {
+      await invoke("set_snapshot_profile_cmd", { profileId });
+      setSnapshotProfile(profileId);
+      notify("Settings saved", profileId ? `${profileId} will follow new snapshots` : "Snapshot tracking disabled");
+      if (profileId) {
+        const update = await invoke<SnapshotUpdate | null>("sync_snapshot_profile_cmd");
+        if (update) {
+          notify(`Snapshot ${update.version} ready`, `Profile ${update.profileId} is on the latest snapshot`);
+          void loadProfiles();
+        }
+      }
+    } catch (err) {
+      notify("Failed to save settings", String(err));
+    }
+  };
+
+  const handleClearCrashStats = async () => {
+    try {
+      await invoke("clear_crash_stats_cmd");
+      setCrashStats([]);
+    } catch (err) {
+      notify("Failed to clear crash statistics", String(err));
+    }
+  };
+
   const handleCheckUpdates = async () => {
     setCheckingUpdates(true);
     setUpdateResult(null);
     try {
       const result = await invoke<UpdateCheckResult>("check_all_updates_cmd");
       setUpdateResult(result);
-      if (result.updates.length === 0) {
+      if (result.updates.length === 0 && result.watched.length === 0) {
         notify("All up to date", `Checked ${result.checked} items, no updates available`);
+      } else if (result.updates.length === 0) {
+        notify("New releases", `${result.watched.length} watched project${result.watched.length !== 1 ? "s have" : " has"} a new release`);
       } else {
         notify("Updates available", `Found ${result.updates.length} updates`);
       }
@@ -133,16 +255,16 @@ export function SettingsView() {
   };
 
   const handleApplyUpdate = async (update: ContentUpdate) => {
-    const key = `${update.profile_id}:${update.content.name}`;
+    const key = `${update.profileId}:${update.content.name}`;
     setApplyingUpdate(key);
     try {
       await invoke("apply_content_update_cmd", {
-        profileId: update.profile_id,
+        profileId: update.profileId,
         contentName: update.content.name,
-        contentType: update.content_type,
-        newVersionId: update.latest_version_id,
+        contentType: update.contentType,
+        newVersionId: update.latestVersionId,
       });
-      notify("Update applied", `${update.content.name} updated to ${update.latest_version}`);
+      notify("Update applied", `${update.content.name} updated to ${update.latestVersion}`);
       await handleCheckUpdates();
     } catch (err) {
       notify("Update failed", String(err));
@@ -150,6 +272,23 @@ export function SettingsView() {
     setApplyingUpdate(null);
   };
 
+  const handleDismissRelease = async (release: WatchedRelease) => {
+    try {
+      await invoke("dismiss_watched_release_cmd", {
+        platform: release.project.platform,
+        projectId: release.project.projectId,
+        versionId: release.latestVersionId,
+        version: release.latestVersion,
+      });
+      setUpdateResult((prev) => prev && {
+        ...prev,
+        watched: prev.watched.filter((w) => w !== release),
+      });
+    } catch (err) {
+      notify("Failed to dismiss release", String(err));
+    }
+  };
+
   const handleApplyAllUpdates = async () => {
     if (!updateResult) return;
     for (const update of updateResult.updates) {
@@ -191,17 +330,44 @@ export function SettingsView() {
   const getStorageCategories = (): StorageCategory[] => {
     if (!stats) return [];
     return [
-      { key: "mods", label: "Mods", bytes: stats.mods_bytes, color: "#7cc7ff" },
-      { key: "resourcepacks", label: "Resources", bytes: stats.resourcepacks_bytes, color: "#a78bfa" },
-      { key: "shaderpacks", label: "Shaders", bytes: stats.shaderpacks_bytes, color: "#f472b6" },
-      { key: "skins", label: "Skins", bytes: stats.skins_bytes, color: "#34d399" },
-      { key: "minecraft", label: "Minecraft", bytes: stats.minecraft_bytes, color: "#fbbf24" },
-      { key: "database", label: "Database", bytes: stats.database_bytes, color: "#94a3b8" },
+      { key: "mods", label: "Mods", bytes: stats.modsBytes, color: "#7cc7ff" },
+      { key: "resourcepacks", label: "Resources", bytes: stats.resourcepacksBytes, color: "#a78bfa" },
+      { key: "shaderpacks", label: "Shaders", bytes: stats.shaderpacksBytes, color: "#f472b6" },
+      { key: "skins", label: "Skins", bytes: stats.skinsBytes, color: "#34d399" },
+      { key: "minecraft", label: "Minecraft", bytes: stats.minecraftBytes, color: "#fbbf24" },
+      { key: "database", label: "Database", bytes: stats.databaseBytes, color: "#94a3b8" },
+      { key: "instance_folders", label: "Replays & schematics", bytes: stats.instanceFoldersBytes, color: "#fb923c" },
     ].filter((c) => c.bytes > 0);
   };
 
+  const handleRelocateFolder = async (usage: FolderUsage) => {
+    const target = await open({ directory: true, multiple: false });
+    if (!target || Array.isArray(target)) return;
+    try {
+      const moved = await invoke<string>("relocate_instance_folder_cmd", {
+        profileId: usage.profileId,
+        folder: usage.folder,
+        target,
+      });
+      notify("Folder moved", moved);
+      await loadStats();
+    } catch (err) {
+      notify("Failed to move folder", String(err));
+    }
+  };
+
   const categories = getStorageCategories();
-  const totalStorageBytes = stats?.total_bytes ?? 0;
+  const totalStorageBytes = stats?.totalBytes ?? 0;
+
+  const handleSpaceChange = async (name: string) => {
+    try {
+      await invoke("set_active_space_cmd", { name });
+      // Profiles, accounts and config all change with the space
+      window.location.reload();
+    } catch (err) {
+      notify("Failed to switch space", String(err));
+    }
+  };
 
   const handleOpenDataFolder = async () => {
     try {
@@ -214,10 +380,10 @@ export function SettingsView() {
   };
 
   const handlePurgeCompleted = (result: PurgeResult) => {
-    if (result.deleted_count > 0) {
+    if (result.deletedCount > 0) {
       notify(
         "Storage cleaned",
-        `Deleted ${result.deleted_count} item${result.deleted_count !== 1 ? "s" : ""}, freed ${formatFileSize(result.freed_bytes)}`
+        `Deleted ${result.deletedCount} item${result.deletedCount !== 1 ? "s" : ""}, freed ${formatFileSize(result.freedBytes)}`
       );
       // Refresh storage stats
       loadStats();
@@ -337,6 +503,75 @@ export function SettingsView() {
                   <span className="toggle-switch-thumb" />
                 </button>
               </div>
+
+              <div className="settings-row">
+                <div className="settings-row-content">
+                  <div className="settings-row-title">Collect crash statistics</div>
+                  <div className="settings-row-description">
+                    Count anonymized crash signatures locally to spot crashes that started after an update
+                  </div>
+                </div>
+                <button
+                  className="toggle-switch"
+                  data-active={crashAggregation}
+                  onClick={handleCrashAggregationToggle}
+                >
+                  <span className="toggle-switch-thumb" />
+                </button>
+              </div>
+
+              <div className="settings-row">
+                <div className="settings-row-content">
+                  <div className="settings-row-title">Warm up new profiles</div>
+                  <div className="settings-row-description">
+                    Download game files and find Java in the background after creating a profile, so the first launch is instant
+                  </div>
+                </div>
+                <button
+                  className="toggle-switch"
+                  data-active={warmUp}
+                  onClick={handleWarmUpToggle}
+                >
+                  <span className="toggle-switch-thumb" />
+                </button>
+              </div>
+
+              <div className="settings-row">
+                <div className="settings-row-content">
+                  <div className="settings-row-title">Follow snapshots</div>
+                  <div className="settings-row-description">
+                    {snapshotProfile
+                      ? `Keep the ${snapshotProfile} profile on the newest snapshot and notify when one is released`
+                      : "Create a profile that moves to each new snapshot as soon as it is released"}
+                  </div>
+                </div>
+                <button
+                  className="toggle-switch"
+                  data-active={!!snapshotProfile}
+                  onClick={handleSnapshotToggle}
+                >
+                  <span className="toggle-switch-thumb" />
+                </button>
+              </div>
+
+              {crashStats.length > 0 && (
+                <div className="settings-row">
+                  <div className="settings-row-content">
+                    {crashStats.slice(0, 5).map((entry) => (
+                      <div key={entry.signature} className="settings-row-description">
+                        {entry.count}x {entry.exception}
+                        {entry.suspected_mod ? ` (suspected: ${entry.suspected_mod})` : ""}
+                        {entry.after_updates[0]
+                          ? `, ${entry.after_updates[0].count} after updating ${entry.after_updates[0].content}`
+                          : ""}
+                      </div>
+                    ))}
+                  </div>
+                  <button className="btn btn-ghost btn-sm" onClick={handleClearCrashStats}>
+                    Clear
+                  </button>
+                </div>
+              )}
             </section>
 
             <section className="settings-card" style={{ marginBottom: 24 }}>
@@ -357,6 +592,29 @@ export function SettingsView() {
                   Open
                 </button>
               </div>
+
+              {spaces.length > 1 && (
+                <div className="settings-row">
+                  <div className="settings-row-content">
+                    <div className="settings-row-title">Space</div>
+                    <div className="settings-row-description">
+                      {dataDirWarning ?? "Separate profiles, accounts, and settings for each person sharing this launcher"}
+                    </div>
+                  </div>
+                  <select
+                    className="input"
+                    style={{ width: "auto" }}
+                    value={spaces.find((space) => space.active)?.name ?? "default"}
+                    onChange={(e) => void handleSpaceChange(e.target.value)}
+                  >
+                    {spaces.map((space) => (
+                      <option key={space.name} value={space.name}>
+                        {space.name} ({space.profile_count})
+                      </option>
+                    ))}
+                  </select>
+                </div>
+              )}
             </section>
 
             <section className="settings-card settings-card-muted">
@@ -417,20 +675,37 @@ export function SettingsView() {
               {/* Stats row */}
               <div className="storage-stats">
                 <div className="storage-stat">
-                  <span className="storage-stat-value">{stats.unique_items}</span>
+                  <span className="storage-stat-value">{stats.uniqueItems}</span>
                   <span className="storage-stat-label">files</span>
                 </div>
                 <div className="storage-stat">
-                  <span className="storage-stat-value">{stats.total_references}</span>
+                  <span className="storage-stat-value">{stats.totalReferences}</span>
                   <span className="storage-stat-label">references</span>
                 </div>
-                {stats.deduplication_savings > 0 && (
+                {stats.deduplicationSavings > 0 && (
                   <div className="storage-stat storage-stat-highlight">
-                    <span className="storage-stat-value">{formatFileSize(stats.deduplication_savings)}</span>
+                    <span className="storage-stat-value">{formatFileSize(stats.deduplicationSavings)}</span>
                     <span className="storage-stat-label">saved</span>
                   </div>
                 )}
               </div>
+
+              {stats.instanceFolders.map((usage) => (
+                <div key={`${usage.profileId}/${usage.folder}`} className="settings-row">
+                  <div className="settings-row-content">
+                    <div className="settings-row-title">
+                      {usage.profileId} / {usage.folder}
+                    </div>
+                    <div className="settings-row-description">
+                      {formatFileSize(usage.bytes)}
+                      {usage.relocatedTo ? ` on ${usage.relocatedTo}` : ""}
+                    </div>
+                  </div>
+                  <button className="btn btn-ghost btn-sm" onClick={() => handleRelocateFolder(usage)}>
+                    Move…
+                  </button>
+                </div>
+              ))}
             </section>
 
             <section className="settings-card" style={{ marginBottom: 24 }}>
@@ -459,16 +734,16 @@ export function SettingsView() {
                   <path d="M10 10l5-5M11 5h4v4" stroke="currentColor" strokeWidth="1.5" strokeLinecap="round" strokeLinejoin="round" />
                 </svg>
                 <span>Deduplication</span>
-                {stats.deduplication_savings > 0 && (
+                {stats.deduplicationSavings > 0 && (
                   <span className="settings-card-badge" style={{ marginLeft: "auto", background: "rgba(52, 211, 153, 0.15)", color: "#34d399" }}>
-                    {formatFileSize(stats.deduplication_savings)} saved
+                    {formatFileSize(stats.deduplicationSavings)} saved
                   </span>
                 )}
               </div>
               <p className="settings-tip">
                 Shard uses content-addressed storage with SHA-256 hashing. When the same mod is used across multiple profiles, it's stored only once.
-                {stats.deduplication_savings > 0 && stats.total_references > stats.unique_items && (
-                  <> You have {stats.total_references} references to {stats.unique_items} unique files, saving <strong style={{ color: "#34d399" }}>{formatFileSize(stats.deduplication_savings)}</strong> of disk space.</>
+                {stats.deduplicationSavings > 0 && stats.totalReferences > stats.uniqueItems && (
+                  <> You have {stats.totalReferences} references to {stats.uniqueItems} unique files, saving <strong style={{ color: "#34d399" }}>{formatFileSize(stats.deduplicationSavings)}</strong> of disk space.</>
                 )}
               </p>
             </section>
@@ -519,7 +794,7 @@ export function SettingsView() {
                         {java.arch && (
                           <span className="badge badge-muted">{java.arch}</span>
                         )}
-                        {java.is_valid ? (
+                        {java.isValid ? (
                           <span className="badge badge-success">Valid</span>
                         ) : (
                           <span className="badge badge-warning">Invalid</span>
@@ -529,6 +804,22 @@ export function SettingsView() {
                   ))}
                 </div>
               )}
+
+              <div className="settings-row">
+                <div className="settings-row-content">
+                  <div className="settings-row-title">Download Java automatically</div>
+                  <div className="settings-row-description">
+                    Install Temurin at launch when no installed Java fits the profile's Minecraft version
+                  </div>
+                </div>
+                <button
+                  className="toggle-switch"
+                  data-active={autoInstallJava}
+                  onClick={handleAutoInstallJavaToggle}
+                >
+                  <span className="toggle-switch-thumb" />
+                </button>
+              </div>
             </section>
 
             <section className="settings-card settings-card-muted">
@@ -625,7 +916,7 @@ export function SettingsView() {
                     </button>
                   </div>
                   {updateResult.updates.map((update) => {
-                    const key = `${update.profile_id}:${update.content.name}`;
+                    const key = `${update.profileId}:${update.content.name}`;
                     const isPinned = update.content.pinned;
                     const isManual = !update.content.platform;
                     return (
@@ -635,7 +926,7 @@ export function SettingsView() {
                           {isPinned && <span className="badge badge-warning">Pinned</span>}
                           {isManual && <span className="badge badge-muted">Manual</span>}
                           <span className="settings-update-version">
-                            {update.current_version ?? "?"} → {update.latest_version}
+                            {update.currentVersion ?? "?"} → {update.latestVersion}
                           </span>
                         </div>
                         <button
@@ -657,6 +948,29 @@ export function SettingsView() {
                 </p>
               )}
 
+              {/* New releases of watched projects */}
+              {updateResult && updateResult.watched.length > 0 && (
+                <div className="settings-updates-list">
+                  <div className="settings-updates-header">
+                    <span>{updateResult.watched.length} new release{updateResult.watched.length !== 1 ? "s" : ""} of watched projects</span>
+                  </div>
+                  {updateResult.watched.map((release) => (
+                    <div key={`${release.project.platform}:${release.project.projectId}`} className="settings-update-item">
+                      <div className="settings-update-info">
+                        <span className="settings-update-name">{release.project.name}</span>
+                        {release.project.mcVersion && <span className="badge badge-muted">{release.project.mcVersion}</span>}
+                        <span className="settings-update-version">
+                          {release.project.seenVersion ?? "?"} → {release.latestVersion}
+                        </span>
+                      </div>
+                      <button className="btn btn-sm btn-secondary" onClick={() => handleDismissRelease(release)}>
+                        Dismiss
+                      </button>
+                    </div>
+                  ))}
+                </div>
+              )}
+
               {updateResult && updateResult.errors.length > 0 && (
                 <p className="settings-error" style={{ marginTop: 12 }}>
                   {updateResult.errors.length} error{updateResult.errors.length !== 1 ? "s" : ""} during check
@@ -3,8 +3,8 @@ import { invoke } from "@tauri-apps/api/core";
 import { openUrl } from "@tauri-apps/plugin-opener";
 import clsx from "clsx";
 import { useAppStore } from "../store";
-import type { StoreProject, StoreVersion } from "../types";
-import { formatDownloads, formatFileSize } from "../utils";
+import type { StoreProject, StoreVersion, WatchedProject } from "../types";
+import { confirmStoreInstall, formatDownloads, formatFileSize } from "../utils";
 
 type StoreCategory = "mods" | "resourcepacks" | "shaderpacks";
 
@@ -17,8 +17,8 @@ const CATEGORY_TO_CONTENT_TYPE: Record<StoreCategory, string> = {
 
 interface StoreSearchInput {
   query: string;
-  content_type: string;
-  game_version?: string | null;
+  contentType: string;
+  gameVersion?: string | null;
   loader?: string | null;
   limit?: number;
   platform?: string | null;
@@ -65,8 +65,14 @@ export function StoreView() {
   const [loadingVersions, setLoadingVersions] = useState(false);
   const [installing, setInstalling] = useState<string | null>(null);
   const [quickInstalling, setQuickInstalling] = useState<string | null>(null);
+  const [watched, setWatched] = useState<WatchedProject[]>([]);
+  const [togglingWatch, setTogglingWatch] = useState(false);
   const [, forceUpdate] = useState(0);
 
+  useEffect(() => {
+    invoke<WatchedProject[]>("list_watched_projects_cmd").then(setWatched).catch(() => {});
+  }, []);
+
   // Load popular content for current category
   useEffect(() => {
     const loadPopular = async () => {
@@ -76,8 +82,8 @@ export function StoreView() {
       try {
         const input: StoreSearchInput = {
           query: "",
-          content_type: CATEGORY_TO_CONTENT_TYPE[category],
-          game_version: null,
+          contentType: CATEGORY_TO_CONTENT_TYPE[category],
+          gameVersion: null,
           loader: null,
           limit: 5,
         };
@@ -111,10 +117,10 @@ export function StoreView() {
     const projectSlugNorm = normalizeForComparison(project.slug);
 
     return contentArray.some((item) => {
-      // Try exact platform + project_id match (project_id could be id or slug)
+      // Try exact platform + projectId match (projectId could be id or slug)
       // Use case-insensitive comparison for platform since storage may have inconsistent casing
       if (item.platform?.toLowerCase() === project.platform.toLowerCase()) {
-        if (item.project_id === project.id || item.project_id === project.slug) {
+        if (item.projectId === project.id || item.projectId === project.slug) {
           return true;
         }
       }
@@ -124,6 +130,35 @@ export function StoreView() {
     });
   }, [profile, category]);
 
+  const isProjectWatched = useCallback((project: StoreProject): boolean => {
+    return watched.some((w) => w.platform === project.platform && w.projectId === project.id);
+  }, [watched]);
+
+  // Watch for new releases, filtered to the current profile's version and loader
+  const handleToggleWatch = useCallback(async (project: StoreProject) => {
+    setTogglingWatch(true);
+    try {
+      if (isProjectWatched(project)) {
+        await invoke("unwatch_project_cmd", { platform: project.platform, projectId: project.id });
+        setWatched((prev) => prev.filter((w) => !(w.platform === project.platform && w.projectId === project.id)));
+        notify("Stopped watching", project.name);
+      } else {
+        const entry = await invoke<WatchedProject>("watch_project_cmd", {
+          platform: project.platform,
+          projectId: project.id,
+          mcVersion: profile?.mcVersion ?? null,
+          loader: category === "mods" ? profile?.loader?.type ?? null : null,
+        });
+        setWatched((prev) => [...prev, entry]);
+        notify("Watching", `You'll hear about new releases of ${project.name}`);
+      }
+    } catch (err) {
+      notify("Watch failed", String(err));
+    } finally {
+      setTogglingWatch(false);
+    }
+  }, [isProjectWatched, profile, category, notify]);
+
   const handleSearch = useCallback(async () => {
     if (!query.trim()) {
       setSearchResults(null);
@@ -138,8 +173,8 @@ export function StoreView() {
     try {
       const input: StoreSearchInput = {
         query: query.trim(),
-        content_type: CATEGORY_TO_CONTENT_TYPE[category],
-        game_version: profile?.mcVersion ?? null,
+        contentType: CATEGORY_TO_CONTENT_TYPE[category],
+        gameVersion: profile?.mcVersion ?? null,
         loader: profile?.loader?.type ?? null,
         limit: 20,
         platform: platform === "all" ? null : platform,
@@ -187,12 +222,14 @@ export function StoreView() {
 
     setInstalling(version.id);
     try {
+      if (!(await confirmStoreInstall(selectedProject, version))) return;
       const input = {
-        profile_id: selectedProfileId,
+        profileId: selectedProfileId,
         platform: selectedProject.platform,
-        project_id: selectedProject.id,
-        version_id: version.id,
-        content_type: CATEGORY_TO_CONTENT_TYPE[category],
+        projectId: selectedProject.id,
+        versionId: version.id,
+        contentType: CATEGORY_TO_CONTENT_TYPE[category],
+        acceptMetered: true,
       };
       await invoke("store_install_cmd", { input });
       await loadProfile(selectedProfileId);
@@ -228,12 +265,14 @@ export function StoreView() {
 
       // Install the first (latest) version
       const latestVersion = versions[0];
+      if (!(await confirmStoreInstall(project, latestVersion))) return;
       const input = {
-        profile_id: selectedProfileId,
+        profileId: selectedProfileId,
         platform: project.platform,
-        project_id: project.id,
-        version_id: latestVersion.id,
-        content_type: CATEGORY_TO_CONTENT_TYPE[category],
+        projectId: project.id,
+        versionId: latestVersion.id,
+        contentType: CATEGORY_TO_CONTENT_TYPE[category],
+        acceptMetered: true,
       };
       await invoke("store_install_cmd", { input });
       await loadProfile(selectedProfileId);
@@ -351,9 +390,9 @@ export function StoreView() {
             >
               {/* Project icon */}
               <div className="content-item-icon" style={{ width: 48, height: 48, alignSelf: "flex-start" }}>
-                {project.icon_url ? (
+                {project.iconUrl ? (
                   <img
-                    src={project.icon_url}
+                    src={project.iconUrl}
                     alt=""
                     style={{
                       width: 48,
@@ -494,9 +533,18 @@ export function StoreView() {
                 </svg>
               </button>
             </div>
-            <p style={{ margin: "0 0 20px", fontSize: 13, color: "var(--text-secondary)" }}>
+            <p style={{ margin: "0 0 12px", fontSize: 13, color: "var(--text-secondary)" }}>
               {selectedProject.description}
             </p>
+            <button
+              className="btn btn-secondary btn-sm"
+              style={{ marginBottom: 20 }}
+              onClick={() => handleToggleWatch(selectedProject)}
+              disabled={togglingWatch}
+              title="Report new releases in update checks, even if not installed"
+            >
+              {togglingWatch ? "..." : isProjectWatched(selectedProject) ? "Unwatch" : "Watch releases"}
+            </button>
 
             {loadingVersions && (
               <p style={{ color: "var(--text-muted)", fontSize: 13 }}>Loading versions...</p>
@@ -527,8 +575,8 @@ export function StoreView() {
                       <div>
                         <div style={{ fontSize: 13, fontWeight: 500 }}>{version.version}</div>
                         <div style={{ fontSize: 11, color: "var(--text-muted)", marginTop: 2 }}>
-                          {formatFileSize(version.size)} &middot; {version.game_versions.slice(0, 3).join(", ")}
-                          {version.game_versions.length > 3 && ` +${version.game_versions.length - 3}`}
+                          {formatFileSize(version.size)} &middot; {version.gameVersions.slice(0, 3).join(", ")}
+                          {version.gameVersions.length > 3 && ` +${version.gameVersions.length - 3}`}
                         </div>
                       </div>
                       <button
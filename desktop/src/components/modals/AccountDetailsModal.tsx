@@ -50,7 +50,7 @@ export function AccountDetailsModal({ open: isOpen, accountId, onClose }: Accoun
     setLoadingLibrary(true);
     try {
       const filter: LibraryFilter = {
-        content_type: "skin",
+        contentType: "skin",
         limit: 50,
       };
       const items = await invoke<LibraryItem[]>("library_list_items_cmd", { filter });
@@ -87,7 +87,7 @@ export function AccountDetailsModal({ open: isOpen, accountId, onClose }: Accoun
         id: accountId,
         path: file,
         variant: skinVariant,
-        save_to_library: true,
+        saveToLibrary: true,
       });
       await loadAccountInfo();
       await loadLibrarySkins();
@@ -105,7 +105,7 @@ export function AccountDetailsModal({ open: isOpen, accountId, onClose }: Accoun
     try {
       await invoke("apply_library_skin_cmd", {
         id: accountId,
-        item_id: selectedLibrarySkin.id,
+        itemId: selectedLibrarySkin.id,
         variant: skinVariant,
       });
       await loadAccountInfo();
@@ -163,8 +163,8 @@ export function AccountDetailsModal({ open: isOpen, accountId, onClose }: Accoun
 
   const activeSkin = info?.profile?.skins?.find((s) => s.state === "ACTIVE");
   const activeCape = info?.profile?.capes?.find((c) => c.state === "ACTIVE");
-  const activeSkinUrl = activeSkin?.url || info?.skin_url || "";
-  const activeCapeUrl = activeCape?.url ?? (info?.profile ? null : info?.cape_url ?? null);
+  const activeSkinUrl = activeSkin?.url || info?.skinUrl || "";
+  const activeCapeUrl = activeCape?.url ?? (info?.profile ? null : info?.capeUrl ?? null);
 
   return (
     <Modal open={isOpen} onClose={onClose} className="modal-lg">
@@ -3,8 +3,8 @@ import { invoke } from "@tauri-apps/api/core";
 import { open as dialogOpen } from "@tauri-apps/plugin-dialog";
 import clsx from "clsx";
 import { Modal } from "../Modal";
-import type { ContentTab, LibraryItem, LibraryFilter, StoreProject } from "../../types";
-import { getContentTypeLabel, formatFileSize, formatContentName } from "../../utils";
+import type { ContentTab, LibraryItem, LibraryFilter, StoreProject, StoreVersion } from "../../types";
+import { confirmStoreInstall, getContentTypeLabel, formatFileSize, formatContentName } from "../../utils";
 import { useAppStore } from "../../store";
 import { PlatformIcon } from "../PlatformIcon";
 
@@ -47,7 +47,7 @@ export function AddContentModal({ open, kind, onClose, onAddFromLibrary }: AddCo
     setLoading(true);
     try {
       const filter: LibraryFilter = {
-        content_type: contentTypeMap[kind],
+        contentType: contentTypeMap[kind],
         search: search || undefined,
         limit: 50,
       };
@@ -66,8 +66,8 @@ export function AddContentModal({ open, kind, onClose, onAddFromLibrary }: AddCo
     try {
       const input = {
         query: search.trim(),
-        content_type: contentTypeMap[kind],
-        game_version: profile?.mcVersion ?? null,
+        contentType: contentTypeMap[kind],
+        gameVersion: profile?.mcVersion ?? null,
         loader: profile?.loader?.type ?? null,
         limit: 20,
       };
@@ -173,11 +173,25 @@ export function AddContentModal({ open, kind, onClose, onAddFromLibrary }: AddCo
 
     setInstalling(true);
     try {
+      const versions = await invoke<StoreVersion[]>("store_get_versions_cmd", {
+        projectId: selectedStoreItem.id,
+        platform: selectedStoreItem.platform,
+        gameVersion: profile.mcVersion,
+        loader: profile.loader?.type ?? null,
+        profileId: profile.id,
+      });
+      if (versions.length === 0) {
+        notify("No compatible version", `No compatible version found for ${selectedStoreItem.name}`);
+        return;
+      }
+      if (!(await confirmStoreInstall(selectedStoreItem, versions[0]))) return;
       const input = {
-        profile_id: profile.id,
-        project_id: selectedStoreItem.id,
+        profileId: profile.id,
+        projectId: selectedStoreItem.id,
         platform: selectedStoreItem.platform,
-        content_type: contentTypeMap[kind],
+        versionId: versions[0].id,
+        contentType: contentTypeMap[kind],
+        acceptMetered: true,
       };
       await invoke("store_install_cmd", { input });
       await loadProfile(profile.id);
@@ -279,11 +293,11 @@ export function AddContentModal({ open, kind, onClose, onAddFromLibrary }: AddCo
               <div className="add-content-item-info">
                 <span className="add-content-item-name">{formatContentName(item.name)}</span>
                 <span className="add-content-item-meta">
-                  {item.file_size && formatFileSize(item.file_size)}
-                  {item.source_platform && (
+                  {item.fileSize && formatFileSize(item.fileSize)}
+                  {item.sourcePlatform && (
                     <>
                       <span className="dot">·</span>
-                      <PlatformIcon platform={item.source_platform as "modrinth" | "curseforge" | "local"} size="sm" />
+                      <PlatformIcon platform={item.sourcePlatform as "modrinth" | "curseforge" | "local"} size="sm" />
                     </>
                   )}
                 </span>
@@ -303,8 +317,8 @@ export function AddContentModal({ open, kind, onClose, onAddFromLibrary }: AddCo
               className={clsx("add-content-item", selectedStoreItem?.id === item.id && "selected")}
               onClick={() => setSelectedStoreItem(item)}
             >
-              {item.icon_url && (
-                <img src={item.icon_url} alt="" className="add-content-item-icon" />
+              {item.iconUrl && (
+                <img src={item.iconUrl} alt="" className="add-content-item-icon" />
               )}
               <div className="add-content-item-info">
                 <span className="add-content-item-name">{item.name}</span>
@@ -8,18 +8,18 @@ import { useAppStore } from "../../store";
 interface CloneProfileModalProps {
   open: boolean;
   onClose: () => void;
-  onSubmit: (src: string, dst: string) => Promise<void>;
+  onSubmit: (src: string, dst: string, manifestOnly: boolean) => Promise<void>;
 }
 
 export function CloneProfileModal({ open, onClose, onSubmit }: CloneProfileModalProps) {
   const { profiles, selectedProfileId } = useAppStore();
 
-  const [form, setForm] = useState({ src: "", dst: "" });
+  const [form, setForm] = useState({ src: "", dst: "", copyInstance: true });
   const [errors, setErrors] = useState<Record<string, string>>({});
 
   useEffect(() => {
     if (open) {
-      setForm({ src: selectedProfileId ?? "", dst: "" });
+      setForm({ src: selectedProfileId ?? "", dst: "", copyInstance: true });
       setErrors({});
     }
   }, [open, selectedProfileId]);
@@ -32,7 +32,7 @@ export function CloneProfileModal({ open, onClose, onSubmit }: CloneProfileModal
     setErrors(newErrors);
     if (Object.keys(newErrors).length > 0) return;
 
-    await onSubmit(form.src, form.dst);
+    await onSubmit(form.src, form.dst, !form.copyInstance);
   };
 
   return (
@@ -47,6 +47,14 @@ export function CloneProfileModal({ open, onClose, onSubmit }: CloneProfileModal
         <Field label="New profile ID" error={errors.dst}>
           <input className={clsx("input", errors.dst && "input-error")} value={form.dst} onChange={(e) => setForm({ ...form, dst: e.target.value })} placeholder="my-modpack-copy" />
         </Field>
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={form.copyInstance}
+            onChange={(e) => setForm({ ...form, copyInstance: e.target.checked })}
+          />
+          <span>Copy worlds and settings</span>
+        </label>
         <ModalFooter onCancel={onClose} onSubmit={handleSubmit} submitLabel="Clone" />
       </div>
     </Modal>
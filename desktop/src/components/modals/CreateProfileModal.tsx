@@ -5,7 +5,7 @@ import { Modal } from "../Modal";
 import { ModalFooter } from "../ModalFooter";
 import { Field } from "../Field";
 import { useAppStore } from "../../store";
-import type { Template, MinecraftVersionsResponse, ManifestVersion } from "../../types";
+import type { Template, MinecraftVersionsResponse, ManifestVersion, LoaderSuggestion } from "../../types";
 
 interface CreateProfileModalProps {
   open: boolean;
@@ -34,6 +34,8 @@ export function CreateProfileModal({ open, onClose, onSubmit }: CreateProfileMod
   const [mcVersions, setMcVersions] = useState<ManifestVersion[]>([]);
   const [mcVersionsLoading, setMcVersionsLoading] = useState(false);
   const [showSnapshots, setShowSnapshots] = useState(false);
+  const [suggestion, setSuggestion] = useState<LoaderSuggestion | null>(null);
+  const [useSuggestion, setUseSuggestion] = useState(false);
   const [error, setError] = useState("");
 
   // Load Minecraft versions
@@ -43,8 +45,8 @@ export function CreateProfileModal({ open, onClose, onSubmit }: CreateProfileMod
       const response = await invoke<MinecraftVersionsResponse>("fetch_minecraft_versions_cmd");
       setMcVersions(response.versions);
       // Set default to latest release if not already set
-      if (!mcVersion && response.latest_release) {
-        setMcVersion(response.latest_release);
+      if (!mcVersion && response.latestRelease) {
+        setMcVersion(response.latestRelease);
       }
     } catch (err) {
       console.error("Failed to load MC versions:", err);
@@ -81,13 +83,13 @@ export function CreateProfileModal({ open, onClose, onSubmit }: CreateProfileMod
       if (loaded.some(t => t.id === "default")) {
         setSelectedTemplateId("default");
         const defaultTemplate = loaded.find(t => t.id === "default");
-        if (defaultTemplate?.mc_version) {
-          setMcVersion(defaultTemplate.mc_version);
+        if (defaultTemplate?.mcVersion) {
+          setMcVersion(defaultTemplate.mcVersion);
         }
       } else if (loaded.length > 0) {
         setSelectedTemplateId(loaded[0].id);
-        if (loaded[0].mc_version) {
-          setMcVersion(loaded[0].mc_version);
+        if (loaded[0].mcVersion) {
+          setMcVersion(loaded[0].mcVersion);
         }
       }
     } catch (err) {
@@ -107,12 +109,32 @@ export function CreateProfileModal({ open, onClose, onSubmit }: CreateProfileMod
     }
   }, [open, loadTemplates, loadMcVersions]);
 
+  const templateHasLoader = Boolean(templates.find(t => t.id === selectedTemplateId)?.loader?.type);
+
+  // Suggest the usual loader when the template doesn't pick one
+  useEffect(() => {
+    setSuggestion(null);
+    setUseSuggestion(false);
+    if (!open || templateHasLoader || !mcVersion) return;
+    let cancelled = false;
+    invoke<LoaderSuggestion | null>("suggest_loader_cmd", { mcVersion })
+      .then((result) => {
+        if (!cancelled) setSuggestion(result);
+      })
+      .catch(() => {
+        // No suggestion offline; the profile stays vanilla
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [open, templateHasLoader, mcVersion]);
+
   // Update MC version when template changes
   const handleTemplateSelect = (templateId: string) => {
     setSelectedTemplateId(templateId);
     const template = templates.find(t => t.id === templateId);
-    if (template?.mc_version) {
-      setMcVersion(template.mc_version);
+    if (template?.mcVersion) {
+      setMcVersion(template.mcVersion);
     }
   };
 
@@ -135,11 +157,12 @@ export function CreateProfileModal({ open, onClose, onSubmit }: CreateProfileMod
     }
 
     // Build form from template with user-selected MC version
+    const suggested = useSuggestion && !selectedTemplate.loader?.type ? suggestion : null;
     const form: CreateProfileForm = {
       id: profileName.trim(),
       mcVersion: mcVersion.trim(),
-      loaderType: selectedTemplate.loader?.type ?? "",
-      loaderVersion: selectedTemplate.loader?.version ?? "",
+      loaderType: suggested?.loader ?? selectedTemplate.loader?.type ?? "",
+      loaderVersion: suggested?.version ?? selectedTemplate.loader?.version ?? "",
       java: "",
       memory: selectedTemplate.runtime?.memory ?? "",
       args: selectedTemplate.runtime?.args?.join(" ") ?? "",
@@ -154,7 +177,7 @@ export function CreateProfileModal({ open, onClose, onSubmit }: CreateProfileMod
   // Filter versions based on showSnapshots toggle
   const filteredVersions = showSnapshots
     ? mcVersions
-    : mcVersions.filter(v => v.type === "release");
+    : mcVersions.filter(v => v.channel === "release");
 
   return (
     <Modal open={open} onClose={onClose} title="New profile">
@@ -212,6 +235,17 @@ export function CreateProfileModal({ open, onClose, onSubmit }: CreateProfileMod
               <span>Snapshots</span>
             </label>
           </div>
+          {suggestion && (
+            <label className="checkbox-label">
+              <input
+                type="checkbox"
+                checked={useSuggestion}
+                onChange={(e) => setUseSuggestion(e.target.checked)}
+              />
+              <span>Use {suggestion.loader} {suggestion.version}</span>
+            </label>
+          )}
+          {suggestion && <p className="field-hint">Suggested because {suggestion.reason}.</p>}
         </Field>
 
         {/* Profile name */}
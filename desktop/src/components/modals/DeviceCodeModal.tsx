@@ -27,20 +27,36 @@ export function DeviceCodeModal({ open, onClose, onSuccess }: DeviceCodeModalPro
   const handleRequestCode = async () => {
     await runAction(async () => {
       const data = await invoke<DeviceCode>("request_device_code_cmd", {
-        client_id: config?.msa_client_id ?? null,
-        client_secret: config?.msa_client_secret ?? null,
+        clientId: config?.msaClientId ?? null,
+        clientSecret: config?.msaClientSecret ?? null,
       });
       setDeviceCode(data);
     });
   };
 
+  const handleBrowserSignIn = async () => {
+    setPending(true);
+    try {
+      await invoke("start_browser_auth_cmd", {
+        clientId: config?.msaClientId ?? null,
+        clientSecret: config?.msaClientSecret ?? null,
+      });
+      await onSuccess();
+      onClose();
+    } catch (err) {
+      notify("Sign-in failed", `${String(err)}. You can sign in with a code instead.`);
+    } finally {
+      setPending(false);
+    }
+  };
+
   const handleFinish = async () => {
     if (!deviceCode) return;
     setPending(true);
     try {
       await invoke("finish_device_code_flow_cmd", {
-        client_id: config?.msa_client_id ?? null,
-        client_secret: config?.msa_client_secret ?? null,
+        clientId: config?.msaClientId ?? null,
+        clientSecret: config?.msaClientSecret ?? null,
         device: deviceCode,
       });
       await onSuccess();
@@ -60,7 +76,10 @@ export function DeviceCodeModal({ open, onClose, onSuccess }: DeviceCodeModalPro
             <p style={{ margin: 0, fontSize: 14, color: "rgba(255,255,255,0.6)" }}>
               Sign in with your Microsoft account to play Minecraft.
             </p>
-            <button className="btn btn-primary" onClick={handleRequestCode}>Get sign-in code</button>
+            <button className="btn btn-primary" onClick={handleBrowserSignIn} disabled={pending}>
+              {pending ? "Waiting for browser…" : "Sign in with browser"}
+            </button>
+            <button className="btn btn-secondary" onClick={handleRequestCode} disabled={pending}>Use a sign-in code instead</button>
           </>
         ) : (
           <>
@@ -62,11 +62,11 @@ export function DiffProfilesModal({ open, onClose }: DiffProfilesModalProps) {
           <div style={{ display: "grid", gridTemplateColumns: "1fr 1fr 1fr", gap: 16, marginTop: 8 }}>
             <div>
               <div className="field-label">Only in A</div>
-              {result.only_a.length === 0 ? <div style={{ color: "rgba(255,255,255,0.4)", fontSize: 13 }}>—</div> : result.only_a.map((n) => <div key={n} style={{ fontSize: 13 }}>{n}</div>)}
+              {result.onlyA.length === 0 ? <div style={{ color: "rgba(255,255,255,0.4)", fontSize: 13 }}>—</div> : result.onlyA.map((n) => <div key={n} style={{ fontSize: 13 }}>{n}</div>)}
             </div>
             <div>
               <div className="field-label">Only in B</div>
-              {result.only_b.length === 0 ? <div style={{ color: "rgba(255,255,255,0.4)", fontSize: 13 }}>—</div> : result.only_b.map((n) => <div key={n} style={{ fontSize: 13 }}>{n}</div>)}
+              {result.onlyB.length === 0 ? <div style={{ color: "rgba(255,255,255,0.4)", fontSize: 13 }}>—</div> : result.onlyB.map((n) => <div key={n} style={{ fontSize: 13 }}>{n}</div>)}
             </div>
             <div>
               <div className="field-label">In both</div>
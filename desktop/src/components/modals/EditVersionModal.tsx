@@ -4,7 +4,7 @@ import { Modal } from "../Modal";
 import { ModalFooter } from "../ModalFooter";
 import { Field } from "../Field";
 import { useAppStore } from "../../store";
-import type { Profile, MinecraftVersionsResponse, ManifestVersion } from "../../types";
+import type { Profile, MinecraftVersionsResponse, ManifestVersion, LoaderInfo } from "../../types";
 
 interface EditVersionModalProps {
   open: boolean;
@@ -22,6 +22,7 @@ export function EditVersionModal({ open, onClose, mode }: EditVersionModalProps)
   const [showSnapshots, setShowSnapshots] = useState(false);
 
   // Loader state
+  const [loaders, setLoaders] = useState<LoaderInfo[]>([]);
   const [loaderType, setLoaderType] = useState("");
   const [loaderVersion, setLoaderVersion] = useState("");
   const [fabricVersions, setFabricVersions] = useState<string[]>([]);
@@ -55,6 +56,14 @@ export function EditVersionModal({ open, onClose, mode }: EditVersionModalProps)
     }
   }, []);
 
+  const loadLoaders = useCallback(async () => {
+    try {
+      setLoaders(await invoke<LoaderInfo[]>("list_loaders_cmd"));
+    } catch (err) {
+      console.error("Failed to list loaders:", err);
+    }
+  }, []);
+
   // Initialize state from profile when modal opens
   useEffect(() => {
     if (open && profile) {
@@ -63,8 +72,9 @@ export function EditVersionModal({ open, onClose, mode }: EditVersionModalProps)
       setLoaderVersion(profile.loader?.version || "");
       void loadMcVersions();
       void loadFabricVersions();
+      void loadLoaders();
     }
-  }, [open, profile, loadMcVersions, loadFabricVersions]);
+  }, [open, profile, loadMcVersions, loadFabricVersions, loadLoaders]);
 
   // Handle loader type change
   const handleLoaderTypeChange = (newType: string) => {
@@ -101,7 +111,7 @@ export function EditVersionModal({ open, onClose, mode }: EditVersionModalProps)
   // Filter versions based on showSnapshots toggle
   const filteredVersions = showSnapshots
     ? mcVersions
-    : mcVersions.filter((v) => v.type === "release");
+    : mcVersions.filter((v) => v.channel === "release");
 
   const title = mode === "version" ? "Change Minecraft Version" : "Change Mod Loader";
 
@@ -148,10 +158,11 @@ export function EditVersionModal({ open, onClose, mode }: EditVersionModalProps)
                 onChange={(e) => handleLoaderTypeChange(e.target.value)}
               >
                 <option value="">Vanilla (no loader)</option>
-                <option value="fabric">Fabric</option>
-                <option value="quilt">Quilt</option>
-                <option value="forge">Forge</option>
-                <option value="neoforge">NeoForge</option>
+                {loaders.map((l) => (
+                  <option key={l.id} value={l.id}>
+                    {l.name}
+                  </option>
+                ))}
               </select>
             </Field>
 
@@ -176,8 +187,8 @@ export function EditVersionModal({ open, onClose, mode }: EditVersionModalProps)
               </Field>
             )}
 
-            {(loaderType === "quilt" || loaderType === "forge" || loaderType === "neoforge") && (
-              <Field label={`${loaderType.charAt(0).toUpperCase() + loaderType.slice(1)} version`}>
+            {loaderType && loaderType !== "fabric" && (
+              <Field label={`${loaders.find((l) => l.id === loaderType)?.name ?? loaderType} version`}>
                 <input
                   className="input"
                   value={loaderVersion}
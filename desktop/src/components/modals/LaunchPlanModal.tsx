@@ -12,11 +12,14 @@ export function LaunchPlanModal({ open, plan, onClose }: LaunchPlanModalProps) {
     <Modal open={open} onClose={onClose} title="Launch plan" large>
       {plan && (
         <div style={{ fontSize: 13, fontFamily: "var(--font-mono)", display: "flex", flexDirection: "column", gap: 8 }}>
-          <div><span style={{ color: "rgba(255,255,255,0.5)" }}>instance:</span> {plan.instance_dir}</div>
-          <div><span style={{ color: "rgba(255,255,255,0.5)" }}>java:</span> {plan.java_exec}</div>
-          <div><span style={{ color: "rgba(255,255,255,0.5)" }}>main class:</span> {plan.main_class}</div>
-          <div><span style={{ color: "rgba(255,255,255,0.5)" }}>jvm args:</span> {plan.jvm_args.join(" ")}</div>
-          <div><span style={{ color: "rgba(255,255,255,0.5)" }}>game args:</span> {plan.game_args.join(" ")}</div>
+          <div><span style={{ color: "rgba(255,255,255,0.5)" }}>instance:</span> {plan.instanceDir}</div>
+          {plan.wrapper.length > 0 && (
+            <div><span style={{ color: "rgba(255,255,255,0.5)" }}>wrapper:</span> {plan.wrapper.join(" ")}</div>
+          )}
+          <div><span style={{ color: "rgba(255,255,255,0.5)" }}>java:</span> {plan.javaExec}</div>
+          <div><span style={{ color: "rgba(255,255,255,0.5)" }}>main class:</span> {plan.mainClass}</div>
+          <div><span style={{ color: "rgba(255,255,255,0.5)" }}>jvm args:</span> {plan.jvmArgs.join(" ")}</div>
+          <div><span style={{ color: "rgba(255,255,255,0.5)" }}>game args:</span> {plan.gameArgs.join(" ")}</div>
         </div>
       )}
     </Modal>
@@ -3,7 +3,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { Modal } from "../Modal";
 import { ModalFooter } from "../ModalFooter";
 import type { UnusedItemsSummary, PurgeResult } from "../../types";
-import { formatFileSize } from "../../utils";
+import { formatFileSize, requestConfirmation } from "../../utils";
 
 interface PurgeStorageModalProps {
   open: boolean;
@@ -87,16 +87,16 @@ export function PurgeStorageModal({ open, onClose, onPurged }: PurgeStorageModal
     if (!unused) return 0;
     let bytes = 0;
     if (selected.has("mods")) {
-      bytes += unused.mods.reduce((sum, item) => sum + (item.file_size ?? 0), 0);
+      bytes += unused.mods.reduce((sum, item) => sum + (item.fileSize ?? 0), 0);
     }
     if (selected.has("resourcepacks")) {
-      bytes += unused.resourcepacks.reduce((sum, item) => sum + (item.file_size ?? 0), 0);
+      bytes += unused.resourcepacks.reduce((sum, item) => sum + (item.fileSize ?? 0), 0);
     }
     if (selected.has("shaderpacks")) {
-      bytes += unused.shaderpacks.reduce((sum, item) => sum + (item.file_size ?? 0), 0);
+      bytes += unused.shaderpacks.reduce((sum, item) => sum + (item.fileSize ?? 0), 0);
     }
     if (selected.has("skins")) {
-      bytes += unused.skins.reduce((sum, item) => sum + (item.file_size ?? 0), 0);
+      bytes += unused.skins.reduce((sum, item) => sum + (item.fileSize ?? 0), 0);
     }
     return bytes;
   };
@@ -111,8 +111,10 @@ export function PurgeStorageModal({ open, onClose, onPurged }: PurgeStorageModal
       const contentTypes = Array.from(selected).map(
         (key) => CATEGORIES.find((c) => c.key === key)!.typeString
       );
+      const confirmToken = await requestConfirmation("purge_unused_items_cmd");
       const result = await invoke<PurgeResult>("purge_unused_items_cmd", {
-        content_types: contentTypes,
+        contentTypes,
+        confirmToken,
       });
       onPurged(result);
       onClose();
@@ -130,7 +132,7 @@ export function PurgeStorageModal({ open, onClose, onPurged }: PurgeStorageModal
 
   const getCategoryBytes = (key: CategoryKey): number => {
     if (!unused) return 0;
-    return unused[key].reduce((sum, item) => sum + (item.file_size ?? 0), 0);
+    return unused[key].reduce((sum, item) => sum + (item.fileSize ?? 0), 0);
   };
 
   const selectedCount = getSelectedCount();
@@ -158,7 +160,7 @@ export function PurgeStorageModal({ open, onClose, onPurged }: PurgeStorageModal
           </div>
         )}
 
-        {!loading && !error && unused && unused.total_count === 0 && (
+        {!loading && !error && unused && unused.totalCount === 0 && (
           <div className="purge-empty">
             <svg width="32" height="32" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="1.5">
               <path d="M9 12l2 2 4-4" strokeLinecap="round" strokeLinejoin="round" />
@@ -169,7 +171,7 @@ export function PurgeStorageModal({ open, onClose, onPurged }: PurgeStorageModal
           </div>
         )}
 
-        {!loading && !error && unused && unused.total_count > 0 && (
+        {!loading && !error && unused && unused.totalCount > 0 && (
           <>
             <p className="purge-description">
               Select which categories of unused content to remove. Items that are not used by any profile will be permanently deleted.
@@ -424,7 +424,7 @@ export const useAppStore = create<AppState>((set, get) => ({
     if (!accountId) return;
     try {
       const info = await invoke<AccountInfo>("get_account_info_cmd", { id: accountId });
-      set({ activeAccountSkinUrl: info.skin_url });
+      set({ activeAccountSkinUrl: info.skinUrl });
     } catch {
       // Silently fail - sidebar will fall back to mc-heads.net
     }
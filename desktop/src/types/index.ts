@@ -3,12 +3,12 @@ export type ContentRef = {
   hash: string;
   version?: string | null;
   source?: string | null;
-  file_name?: string | null;
+  fileName?: string | null;
   platform?: string | null;
-  project_id?: string | null;
-  version_id?: string | null;
-  enabled?: boolean;
-  pinned?: boolean;
+  projectId?: string | null;
+  versionId?: string | null;
+  enabled: boolean;
+  pinned: boolean;
 };
 
 export type Loader = {
@@ -20,22 +20,112 @@ export type Runtime = {
   java?: string | null;
   memory?: string | null;
   args: string[];
+  wrapper: string[];
+  jvmPreset?: JvmPreset | null;
+  priority?: ProcessPriority | null;
+  cpuAffinity: number[];
+  gamescope?: Gamescope | null;
+};
+
+// Fullscreen resolution for Gamescope (Steam Deck game mode)
+export type Gamescope = {
+  width: number;
+  height: number;
 };
 
+export type JvmPreset = "aikar" | "g1gc" | "zgc";
+
+export type ProcessPriority = "low" | "below_normal" | "normal" | "above_normal" | "high";
+
+// Server profiles run a dedicated server (`shard server start`) instead of the game
+export type ProfileKind = "client" | "server";
+
 export type Profile = {
   id: string;
   mcVersion: string;
+  kind: ProfileKind;
   loader?: Loader | null;
   mods: ContentRef[];
   resourcepacks: ContentRef[];
   shaderpacks: ContentRef[];
   runtime: Runtime;
+  files: ProfileFiles;
+  tunnel?: TunnelConfig | null;
+  presets: Record<string, ContentPreset>;
+};
+
+// Public tunnel started next to a server profile
+export type TunnelConfig = {
+  provider: "ngrok" | "playit" | "custom";
+  binary?: string;
+  args?: string[];
+  port?: number;
+};
+
+export type ContentPreset = {
+  enable?: string[];
+  disable?: string[];
+  shaderpack?: string;
+};
+
+export type PresetApplied = {
+  profile: Profile;
+  changed: string[];
+  shaderConfig?: string | null;
+  warnings: string[];
+};
+
+export type Keybind = {
+  action: string;
+  key: string;
+  modifier?: string | null;
+  source: string;
+};
+
+export type KeybindConflict = {
+  key: string;
+  bindings: Keybind[];
+};
+
+// A file dropped into (or changed in) an instance folder, added to its profile
+export type AdoptedFile = {
+  path: string; // relative to the instance, e.g. mods/sodium.jar
+  content: ContentRef;
+  replaces?: string | null; // hash the changed file was placed from
 };
 
+export type ProfileFiles = {
+  configOverrides: string;
+  extraFolders: string[];
+};
+
+export type InstallSummary = {
+  downloaded: number;
+  present: number;
+};
+
+export type MrpackExport = {
+  linked: number;
+  embedded: number;
+  extra_folders: string[];
+  notes: boolean;
+  shader_settings: number;
+};
+
+export type AccountStatus =
+  | "consent_revoked"
+  | "password_changed"
+  | "session_expired"
+  | "interaction_required"
+  | "child_account"
+  | "no_xbox_account"
+  | "region_blocked";
+
 export type Account = {
   uuid: string;
   username: string;
   xuid?: string | null;
+  status?: AccountStatus | null;
 };
 
 export type Accounts = {
@@ -43,10 +133,53 @@ export type Accounts = {
   accounts: Account[];
 };
 
+// The network retry policy is only set from the CLI
 export type Config = {
-  msa_client_id?: string | null;
-  msa_client_secret?: string | null;
-  auto_update_enabled?: boolean;
+  msaClientId?: string | null;
+  msaClientSecret?: string | null;
+  curseforgeApiKey?: string | null;
+  autoUpdateEnabled: boolean;
+  crashAggregationEnabled: boolean;
+  snapshotProfile?: string | null;
+  storeHash: "sha256" | "blake3";
+  autoInstallJava: boolean;
+  skipWarmUp: boolean;
+  instanceLinks: "hardlink" | "symlink" | "copy";
+  mirrors: DownloadMirrors;
+  // Open the data dir read-only on this machine
+  readOnly: boolean;
+  // Keep each version's own LWJGL on ARM machines
+  keepLwjglOnArm: boolean;
+  // Hold back large downloads and background checks; auto asks the OS
+  metered: "auto" | "on" | "off";
+};
+
+export type DownloadMirrors = {
+  manifest?: string | null;
+  libraries?: string | null;
+  assets?: string | null;
+};
+
+export type SnapshotUpdate = {
+  profileId: string;
+  previous?: string | null;
+  version: string;
+};
+
+export type CrashUpdateCount = {
+  content: string;
+  count: number;
+};
+
+export type CrashSignatureStats = {
+  signature: string;
+  exception: string;
+  frames: string[];
+  suspected_mod?: string | null;
+  count: number;
+  first_seen: number;
+  last_seen: number;
+  after_updates: CrashUpdateCount[];
 };
 
 export type DeviceCode = {
@@ -59,35 +192,151 @@ export type DeviceCode = {
 };
 
 export type LaunchPlan = {
-  instance_dir: string;
-  java_exec: string;
-  jvm_args: string[];
+  instanceDir: string;
+  wrapper: string[];
+  javaExec: string;
+  jvmArgs: string[];
   classpath: string;
-  main_class: string;
-  game_args: string[];
+  mainClass: string;
+  gameArgs: string[];
+  priority?: ProcessPriority | null;
+  cpuAffinity: number[];
+  env: [string, string][];
 };
 
 export type DiffResult = {
-  only_a: string[];
-  only_b: string[];
+  onlyA: string[];
+  onlyB: string[];
   both: string[];
 };
 
+export type ApiKeyStatus = "unchecked" | "valid" | "invalid" | "rate_limited";
+
+export type ApiKeyHealth = {
+  status: ApiKeyStatus;
+  key_hint: string;
+  checked_at?: number | null;
+  last_error?: number | null;
+  requests: number;
+  rate_limit?: number | null;
+  rate_remaining?: number | null;
+  rate_reset?: number | null;
+};
+
+// What the game left behind when it exited with a failure status
+export type SessionCrash = {
+  status: string;
+  // Crash report or JVM error log written during the session
+  report?: string | null;
+  jvmCrash: boolean;
+  lines: string[];
+};
+
 export type LaunchEvent = {
   stage: string;
   message?: string | null;
+  crash?: SessionCrash | null;
 };
 
+export type InstallerErrorKind = "wrong_java" | "missing_vanilla_jar" | "network" | "timed_out" | "failed";
+
+// Payload of the installer-error event when a Forge/NeoForge install fails
+export type InstallerError = {
+  kind: InstallerErrorKind;
+  message: string;
+  detail?: string | null;
+  log_path?: string | null;
+  log_tail: string[];
+};
+
+export type FailedDownload = {
+  url: string;
+  path: string;
+  sha1?: string | null;
+  error: string;
+  failures: number;
+  last_failed_at: number;
+};
+
+export type QueueRetryResult = {
+  downloaded: number;
+  failed: FailedDownload[];
+};
+
+// Payload of job-updated events and list_jobs_cmd
+export type JobInfo = {
+  id: string;
+  kind: "download" | "import" | "update" | "audit";
+  label: string;
+  pid: number;
+  startedAt: number;
+  state: "running" | "completed" | "failed" | "cancelled";
+  done: number;
+  total?: number | null;
+  message?: string | null;
+  error?: string | null;
+};
+
+export type GameFilesReport = {
+  checked: number;
+  bytes: number;
+  missing: number;
+  corrupt: number;
+  repaired: string[];
+  failed: FailedDownload[];
+};
+
+export type DownloadEstimate = {
+  files: number;
+  bytes: number;
+  // Files without a known size, not counted in bytes
+  unknownSize: number;
+};
+
+// What launching a profile would download
+export type PrepareEstimate = {
+  client: DownloadEstimate;
+  libraries: DownloadEstimate;
+  assets: DownloadEstimate;
+  // Forge, NeoForge or OptiFine isn't installed yet; its downloads aren't counted
+  loaderPending: boolean;
+  javaDownload?: number | null;
+};
+
+export type PrefetchEvent = {
+  profileId: string;
+  stage: "resolve_version" | "install_loader" | "ensure_client" | "ensure_assets" | "ensure_libraries" | "done" | "deferred" | "error";
+  message?: string | null;
+};
+
+export type VersionChannel = "release" | "snapshot" | "beta" | "alpha" | "april_fools";
+
 export type ManifestVersion = {
   id: string;
   type: string;
-  releaseTime?: string;
+  releaseTime?: string | null;
+  channel: VersionChannel;
 };
 
 export type MinecraftVersionsResponse = {
   versions: ManifestVersion[];
-  latest_release?: string | null;
-  latest_snapshot?: string | null;
+  latestRelease?: string | null;
+  latestSnapshot?: string | null;
+};
+
+export type LoaderInfo = {
+  id: string;
+  name: string;
+  supportsServer: boolean;
+  needsInstaller: boolean;
+  latestPerMcVersion: boolean;
+};
+
+export type LoaderSuggestion = {
+  loader: string;
+  version: string;
+  // Why this loader was picked
+  reason: string;
 };
 
 export type ContentTab = "mods" | "resourcepacks" | "shaderpacks";
@@ -145,10 +394,10 @@ export type MinecraftProfile = {
 export type AccountInfo = {
   uuid: string;
   username: string;
-  avatar_url: string;
-  body_url: string;
-  skin_url: string;
-  cape_url: string;
+  avatarUrl: string;
+  bodyUrl: string;
+  skinUrl: string;
+  capeUrl: string;
   profile?: MinecraftProfile | null;
 };
 
@@ -160,7 +409,7 @@ export type TemplateLoader = {
 
 export type ContentSource =
   | { type: "modrinth"; project: string }
-  | { type: "curseforge"; project_id: number }
+  | { type: "curseforge"; projectId: number }
   | { type: "url"; url: string };
 
 export type TemplateContent = {
@@ -180,7 +429,7 @@ export type Template = {
   id: string;
   name: string;
   description: string;
-  mc_version: string;
+  mcVersion: string;
   loader?: TemplateLoader | null;
   mods: TemplateContent[];
   resourcepacks: TemplateContent[];
@@ -195,11 +444,11 @@ export type JavaInstallation = {
   major?: number | null;
   vendor?: string | null;
   arch?: string | null;
-  is_valid: boolean;
+  isValid: boolean;
 };
 
 export type JavaValidation = {
-  is_valid: boolean;
+  isValid: boolean;
   version?: string | null;
   major?: number | null;
   vendor?: string | null;
@@ -207,6 +456,31 @@ export type JavaValidation = {
   error?: string | null;
 };
 
+// Quickstart recipes - match Rust quickstart::Recipe
+export type RecipeEntry = {
+  name: string;
+  project: string;
+  required: boolean;
+};
+
+export type Recipe = {
+  id: string;
+  name: string;
+  description: string;
+  loader: string;
+  memory: string;
+  mods: RecipeEntry[];
+  shaderpacks: RecipeEntry[];
+};
+
+export type QuickstartSummary = {
+  profileId: string;
+  mcVersion: string;
+  installed: string[];
+  skipped: string[];
+  failed: string[];
+};
+
 // Content store types - matches Rust ContentItem
 export type StoreProject = {
   id: string;
@@ -214,31 +488,63 @@ export type StoreProject = {
   name: string;
   description: string;
   body?: string | null;
-  icon_url?: string | null;
+  iconUrl?: string | null;
   platform: "modrinth" | "curseforge";
-  content_type: "mod" | "resourcepack" | "shaderpack" | "modpack";
+  contentType: "mod" | "resourcepack" | "shaderpack" | "modpack";
   downloads: number;
   updated: string;
   categories: string[];
-  game_versions: string[];
+  gameVersions: string[];
   loaders: string[];
+  license?: ContentLicense | null;
+  distributionAllowed?: boolean | null;
+};
+
+export type ContentLicense = {
+  id: string;
+  name: string;
+  url?: string | null;
+};
+
+// Translation packs - match Rust locale::LocaleReport
+export type LocaleMatch = {
+  mods: string[];
+  pack: StoreProject;
+};
+
+export type LocaleReport = {
+  language: string;
+  matches: LocaleMatch[];
+  unmatched: string[];
+};
+
+// Shown before installing - matches Rust InstallDisclosure
+export type InstallDisclosure = {
+  project: string;
+  version: string;
+  license?: ContentLicense | null;
+  distributionAllowed?: boolean | null;
+  required: string[];
+  embedded: string[];
+  size: number;
 };
 
 // Content store version - matches Rust ContentVersion
 export type StoreVersion = {
   id: string;
-  project_id: string;
+  projectId: string;
   name: string;
   version: string;
-  download_url: string;
+  downloadUrl: string;
   filename: string;
   size: number;
   sha256?: string | null;
   sha1?: string | null;
   platform: "modrinth" | "curseforge";
-  game_versions: string[];
+  gameVersions: string[];
   loaders: string[];
-  release_type: string;
+  releaseType: string;
+  dependencies?: { projectId: string; dependencyType: string }[];
 };
 
 // Logs types
@@ -250,7 +556,82 @@ export type LogEntry = {
   thread?: string | null;
   message: string;
   raw: string;
-  line_number: number;
+  lineNumber: number;
+};
+
+export type GameEvent =
+  | { kind: "server_connect"; address: string }
+  | { kind: "player_joined"; player: string }
+  | { kind: "player_left"; player: string }
+  | { kind: "advancement"; player: string; advancement: string }
+  | { kind: "disconnected"; reason: string };
+
+export type TimedGameEvent = GameEvent & {
+  timestamp?: string | null;
+  lineNumber: number;
+};
+
+export type SessionSummary = {
+  servers: string[];
+  players: string[];
+  advancements: [string, string][];
+  lastDisconnect?: string | null;
+};
+
+export type GameSessionEvents = {
+  events: TimedGameEvent[];
+  summary: SessionSummary;
+};
+
+export type InstanceMetricsEvent = {
+  profileId: string;
+  pid: number;
+  cpuPercent: number;
+  rssBytes: number;
+  elapsedSecs: number;
+};
+
+export type SessionRecord = {
+  started_at: number;
+  duration_secs: number;
+  peak_rss_bytes: number;
+  peak_cpu_percent: number;
+  avg_cpu_percent: number;
+  exit_code?: number | null;
+};
+
+export type RunningInstance = {
+  profileId: string;
+  pid: number;
+  startedAt: number;
+};
+
+export type PlaytimeStats = {
+  total_secs: number;
+  launches: number;
+  last_played?: number | null;
+  peak_rss_bytes: number;
+  sessions: SessionRecord[];
+};
+
+export type ConfigFormat = "toml" | "json" | "json5" | "properties" | "yaml" | "cfg" | "other";
+
+export type ModConfigFile = {
+  path: string;
+  format: ConfigFormat;
+  size: number;
+  modified: number;
+};
+
+export type ConfigSnapshot = {
+  id: string;
+  created_at: number;
+  files: Record<string, string>;
+};
+
+export type ConfigChange = {
+  path: string;
+  kind: "added" | "removed" | "modified";
 };
 
 export type LogFile = {
@@ -259,6 +640,7 @@ export type LogFile = {
   size: number;
   modified: number;
   is_current: boolean;
+  jvm_crash?: boolean;
 };
 
 export type ConfirmState = {
@@ -286,23 +668,27 @@ export type LibraryTag = {
 export type LibraryItem = {
   id: number;
   hash: string;
-  content_type: LibraryContentType;
+  contentType: LibraryContentType;
   name: string;
-  file_name?: string | null;
-  file_size?: number | null;
-  source_url?: string | null;
-  source_platform?: string | null;
-  source_project_id?: string | null;
-  source_version?: string | null;
-  added_at: string;
-  updated_at: string;
+  fileName?: string | null;
+  fileSize?: number | null;
+  sourceUrl?: string | null;
+  sourcePlatform?: string | null;
+  sourceProjectId?: string | null;
+  sourceVersion?: string | null;
+  addedAt: string;
+  updatedAt: string;
   notes?: string | null;
+  sha1?: string | null;
+  sha512?: string | null;
   tags: LibraryTag[];
-  used_by_profiles: string[];
+  usedByProfiles: string[];
+  replaces?: number | null;
+  replacedBy?: number | null;
 };
 
 export type LibraryFilter = {
-  content_type?: string;
+  contentType?: string;
   search?: string;
   tags?: string[];
   limit?: number;
@@ -310,42 +696,86 @@ export type LibraryFilter = {
 };
 
 export type LibraryStats = {
-  total_items: number;
-  mods_count: number;
-  resourcepacks_count: number;
-  shaderpacks_count: number;
-  skins_count: number;
-  total_size: number;
-  tags_count: number;
+  totalItems: number;
+  modsCount: number;
+  resourcepacksCount: number;
+  shaderpacksCount: number;
+  skinsCount: number;
+  totalSize: number;
+  tagsCount: number;
 };
 
 export type LibraryImportResult = {
   added: number;
   skipped: number;
+  renamed?: number;
+  digested?: number;
   errors: string[];
 };
 
+export type LibraryAutoTagResult = {
+  tagged: number;
+  unchanged: number;
+  unknown: number;
+  errors: string[];
+};
+
+export type CorruptContent = {
+  kind: string;
+  hash: string;
+  actual: string;
+  path: string;
+};
+
+export type StoreVerifyReport = {
+  checked: number;
+  bytes: number;
+  corrupt: CorruptContent[];
+  cancelled: boolean;
+};
+
+export type StoreVerifyEvent = {
+  stage: "progress" | "done" | "cancelled" | "error";
+  report: StoreVerifyReport | null;
+  message: string | null;
+};
+
 // Storage statistics types
+export type SpaceInfo = {
+  name: string;
+  active: boolean;
+  profile_count: number;
+};
+
 export type StorageStats = {
-  total_bytes: number;
-  mods_bytes: number;
-  resourcepacks_bytes: number;
-  shaderpacks_bytes: number;
-  skins_bytes: number;
-  minecraft_bytes: number;
-  database_bytes: number;
-  unique_items: number;
-  total_references: number;
-  deduplication_savings: number;
+  totalBytes: number;
+  modsBytes: number;
+  resourcepacksBytes: number;
+  shaderpacksBytes: number;
+  skinsBytes: number;
+  minecraftBytes: number;
+  databaseBytes: number;
+  instanceFoldersBytes: number;
+  instanceFolders: FolderUsage[];
+  uniqueItems: number;
+  totalReferences: number;
+  deduplicationSavings: number;
+};
+
+export type FolderUsage = {
+  profileId: string;
+  folder: string;
+  bytes: number;
+  relocatedTo?: string | null;
 };
 
 // Purge/cleanup types
 export type UnusedItem = {
   id: number;
   hash: string;
-  content_type: LibraryContentType;
+  contentType: LibraryContentType;
   name: string;
-  file_size?: number | null;
+  fileSize?: number | null;
 };
 
 export type UnusedItemsSummary = {
@@ -353,31 +783,78 @@ export type UnusedItemsSummary = {
   resourcepacks: UnusedItem[];
   shaderpacks: UnusedItem[];
   skins: UnusedItem[];
-  total_count: number;
-  total_bytes: number;
+  totalCount: number;
+  totalBytes: number;
 };
 
 export type PurgeResult = {
-  deleted_count: number;
-  freed_bytes: number;
+  deletedCount: number;
+  freedBytes: number;
   items: UnusedItem[];
   errors: string[];
 };
 
+// Content refused by store installs and imports
+export type BlockedEntry = {
+  platform?: string | null;
+  project_id?: string | null;
+  hash?: string | null;
+  name?: string | null;
+  reason: string;
+  added_at: number;
+};
+
 // Update checking types
 export type ContentUpdate = {
-  profile_id: string;
+  profileId: string;
   content: ContentRef;
-  content_type: string;
-  current_version?: string | null;
-  latest_version: string;
-  latest_version_id: string;
+  contentType: string;
+  currentVersion?: string | null;
+  latestVersion: string;
+  latestVersionId: string;
   changelog?: string | null;
 };
 
+export type WatchedProject = {
+  platform: string;
+  projectId: string;
+  name: string;
+  contentType: string;
+  mcVersion?: string | null;
+  loader?: string | null;
+  seenVersionId?: string | null;
+  seenVersion?: string | null;
+  addedAt: number;
+};
+
+export type WatchedRelease = {
+  project: WatchedProject;
+  latestVersion: string;
+  latestVersionId: string;
+};
+
 export type UpdateCheckResult = {
   updates: ContentUpdate[];
+  watched: WatchedRelease[];
   checked: number;
   skipped: number;
   errors: string[];
 };
+
+export type PackKind = "data" | "resource";
+
+export type PackMeta = {
+  description: string;
+  pack_format: number;
+  supported_formats?: [number, number] | null;
+  data_namespaces: string[];
+  asset_namespaces: string[];
+  worldgen: boolean;
+};
+
+export type PackInspection = {
+  kind: PackKind;
+  meta: PackMeta;
+  target_versions?: string | null;
+  warnings: string[];
+};
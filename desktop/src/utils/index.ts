@@ -1,4 +1,41 @@
-import type { ContentTab } from "../types";
+import { invoke } from "@tauri-apps/api/core";
+import type { ContentTab, InstallDisclosure, StoreProject, StoreVersion } from "../types";
+
+/**
+ * One-time token the backend requires for a destructive command on target
+ * (profile id or account uuid, empty for store-wide commands); request it
+ * once the user has confirmed and pass it as confirmToken
+ */
+export function requestConfirmation(command: string, target = ""): Promise<string> {
+  return invoke<string>("request_confirmation_cmd", { command, target });
+}
+
+/**
+ * Show license, bundled/required dependencies and, on a metered connection,
+ * the download size before installing; pass acceptMetered once this is true
+ */
+export async function confirmStoreInstall(project: StoreProject, version: StoreVersion): Promise<boolean> {
+  const disclosure = await invoke<InstallDisclosure>("store_install_disclosure_cmd", {
+    projectId: project.id,
+    platform: project.platform,
+    versionId: version.id,
+  });
+  const lines = [`Install ${disclosure.project} ${disclosure.version}?`, ""];
+  lines.push(`License: ${disclosure.license ? (disclosure.license.name || disclosure.license.id) : "unknown (check the project page)"}`);
+  if (disclosure.distributionAllowed === false) {
+    lines.push("Redistribution is not allowed by the author.");
+  }
+  if (disclosure.required.length > 0) {
+    lines.push(`Requires: ${disclosure.required.join(", ")}`);
+  }
+  if (disclosure.embedded.length > 0) {
+    lines.push(`Embeds: ${disclosure.embedded.join(", ")}`);
+  }
+  if (await invoke<boolean>("needs_metered_confirmation_cmd", { bytes: disclosure.size })) {
+    lines.push(`The connection is metered; this downloads ${formatFileSize(disclosure.size)}.`);
+  }
+  return confirm(lines.join("\n"));
+}
 
 /**
  * Get human-readable label for content type
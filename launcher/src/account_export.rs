@@ -0,0 +1,224 @@
+//! Passphrase-encrypted account export for moving between devices
+//!
+//! Only the Microsoft refresh token (plus identifying info) is exported. It is
+//! encrypted with AES-256-GCM using a key derived from the passphrase with
+//! PBKDF2-HMAC-SHA256. Importing redeems the refresh token for fresh tokens, so
+//! the device-code flow does not have to be repeated.
+
+use crate::accounts::{Account, MinecraftTokens, MsaTokens, find_account_mut, load_accounts, save_accounts, upsert_account};
+use crate::auth::{exchange_for_minecraft, refresh_msa_token};
+use crate::config::load_config;
+use crate::paths::Paths;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::ops::RangeInclusive;
+
+const EXPORT_FORMAT: &str = "shard-account";
+const EXPORT_VERSION: u32 = 1;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// Iteration counts accepted from an export file: fewer make the key cheap
+/// to guess, more make importing hang
+const ACCEPTED_ITERATIONS: RangeInclusive<u32> = 100_000..=10_000_000;
+const SALT_LEN: usize = 16;
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// On-disk export envelope. Username and UUID are left readable so a file can
+/// be identified without the passphrase; the token is not.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountExport {
+    pub format: String,
+    pub version: u32,
+    pub username: String,
+    pub uuid: String,
+    pub kdf: String,
+    pub iterations: u32,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExportPayload {
+    uuid: String,
+    username: String,
+    #[serde(default)]
+    xuid: Option<String>,
+    refresh_token: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    pbkdf2::pbkdf2_hmac_array::<Sha256, 32>(passphrase.as_bytes(), salt, iterations)
+}
+
+fn encrypt_payload(payload: &ExportPayload, passphrase: &str, iterations: u32) -> Result<AccountExport> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        bail!("passphrase must be at least {MIN_PASSPHRASE_LEN} characters");
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, iterations);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let plaintext = serde_json::to_vec(payload).context("failed to serialize account")?;
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_ref())
+        .map_err(|_| anyhow::anyhow!("failed to encrypt account"))?;
+
+    Ok(AccountExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        username: payload.username.clone(),
+        uuid: payload.uuid.clone(),
+        kdf: "pbkdf2-sha256".to_string(),
+        iterations,
+        salt: hex::encode(salt),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    })
+}
+
+fn decrypt_payload(export: &AccountExport, passphrase: &str) -> Result<ExportPayload> {
+    if export.format != EXPORT_FORMAT {
+        bail!("not a shard account export");
+    }
+    if export.version != EXPORT_VERSION {
+        bail!("unsupported account export version: {}", export.version);
+    }
+    if export.kdf != "pbkdf2-sha256" {
+        bail!("unsupported key derivation: {}", export.kdf);
+    }
+    if !ACCEPTED_ITERATIONS.contains(&export.iterations) {
+        bail!(
+            "unsupported iteration count: {} (expected {} to {})",
+            export.iterations,
+            ACCEPTED_ITERATIONS.start(),
+            ACCEPTED_ITERATIONS.end()
+        );
+    }
+
+    let salt = hex::decode(&export.salt).context("invalid salt")?;
+    let nonce = hex::decode(&export.nonce).context("invalid nonce")?;
+    let ciphertext = hex::decode(&export.ciphertext).context("invalid ciphertext")?;
+    if nonce.len() != 12 {
+        bail!("invalid nonce length");
+    }
+
+    let key = derive_key(passphrase, &salt, export.iterations);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| anyhow::anyhow!("wrong passphrase or corrupted export"))?;
+
+    serde_json::from_slice(&plaintext).context("failed to parse decrypted account")
+}
+
+/// Encrypt an account's refresh token; returns the export file contents
+pub fn export_account(paths: &Paths, id: &str, passphrase: &str) -> Result<String> {
+    let mut accounts = load_accounts(paths)?;
+    let account = find_account_mut(&mut accounts, id)
+        .with_context(|| format!("account not found: {id}"))?;
+
+    let payload = ExportPayload {
+        uuid: account.uuid.clone(),
+        username: account.username.clone(),
+        xuid: account.xuid.clone(),
+        refresh_token: account.msa.refresh_token.clone(),
+    };
+    let export = encrypt_payload(&payload, passphrase, PBKDF2_ITERATIONS)?;
+    serde_json::to_string_pretty(&export).context("failed to serialize account export")
+}
+
+/// Decrypt an export, redeem its refresh token and add the account
+pub fn import_account(paths: &Paths, data: &str, passphrase: &str) -> Result<Account> {
+    let export: AccountExport =
+        serde_json::from_str(data).context("failed to parse account export")?;
+    let payload = decrypt_payload(&export, passphrase)?;
+
+    let config = load_config(paths)?;
+    let client_id = config.msa_client_id.context(
+        "missing Microsoft client id; set SHARD_MS_CLIENT_ID or shard config set-client-id",
+    )?;
+    let token = refresh_msa_token(
+        &client_id,
+        config.msa_client_secret.as_deref(),
+        &payload.refresh_token,
+    )
+    .context("exported session could not be refreshed; it may have expired or use a different client id")?;
+    let minecraft_auth = exchange_for_minecraft(&token.access_token)?;
+
+    let account = Account {
+        uuid: minecraft_auth.uuid,
+        username: minecraft_auth.username,
+        xuid: minecraft_auth.xuid.or(payload.xuid),
+        msa: MsaTokens {
+            access_token: token.access_token,
+            refresh_token: token.refresh_token,
+            expires_at: token.expires_at,
+        },
+        minecraft: MinecraftTokens {
+            access_token: minecraft_auth.access_token,
+            expires_at: minecraft_auth.expires_at,
+        },
+        status: None,
+    };
+
+    let mut accounts = load_accounts(paths)?;
+    if accounts.active.is_none() {
+        accounts.active = Some(account.uuid.clone());
+    }
+    upsert_account(&mut accounts, account.clone());
+    save_accounts(paths, &accounts)?;
+
+    Ok(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> ExportPayload {
+        ExportPayload {
+            uuid: "069a79f444e94726a5befca90e38aaf5".to_string(),
+            username: "Notch".to_string(),
+            xuid: None,
+            refresh_token: "M.R3_BAY.secret".to_string(),
+        }
+    }
+
+    /// The cheapest count an import accepts, to keep the tests fast
+    const ITERATIONS: u32 = *ACCEPTED_ITERATIONS.start();
+
+    #[test]
+    fn test_export_round_trip() {
+        let export = encrypt_payload(&payload(), "correct horse battery", ITERATIONS).unwrap();
+        assert!(!export.ciphertext.contains("secret"));
+        let decrypted = decrypt_payload(&export, "correct horse battery").unwrap();
+        assert_eq!(decrypted.refresh_token, "M.R3_BAY.secret");
+        assert_eq!(decrypted.username, "Notch");
+    }
+
+    #[test]
+    fn test_export_rejects_wrong_passphrase() {
+        let export = encrypt_payload(&payload(), "correct horse battery", ITERATIONS).unwrap();
+        assert!(decrypt_payload(&export, "wrong passphrase").is_err());
+        assert!(encrypt_payload(&payload(), "short", ITERATIONS).is_err());
+    }
+
+    #[test]
+    fn test_import_rejects_iteration_counts() {
+        for iterations in [0, 1_000, u32::MAX] {
+            let mut export = encrypt_payload(&payload(), "correct horse battery", 1_000).unwrap();
+            export.iterations = iterations;
+            let Err(err) = decrypt_payload(&export, "correct horse battery") else {
+                panic!("{iterations} iterations were accepted");
+            };
+            assert!(err.to_string().contains("iteration count"), "{err}");
+        }
+    }
+}
@@ -23,6 +23,73 @@ pub struct Account {
     pub xuid: Option<String>,
     pub msa: MsaTokens,
     pub minecraft: MinecraftTokens,
+    /// Set when the last token refresh failed in a way that needs user action
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<AccountStatus>,
+}
+
+/// Actionable reason an account can no longer refresh its tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountStatus {
+    /// The user revoked the launcher's consent (AADSTS65001)
+    ConsentRevoked,
+    /// The password changed, invalidating the refresh token (AADSTS50173)
+    PasswordChanged,
+    /// The refresh token expired or was revoked (AADSTS70008/700082/70000)
+    SessionExpired,
+    /// Microsoft requires an interactive sign-in, e.g. for MFA (AADSTS50076/50079)
+    InteractionRequired,
+    /// Child account that must be added to a family by an adult (XErr 2148916238)
+    ChildAccount,
+    /// No Xbox profile exists for this Microsoft account (XErr 2148916233)
+    NoXboxAccount,
+    /// Xbox Live is unavailable in the account's region (XErr 2148916235)
+    RegionBlocked,
+}
+
+impl AccountStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AccountStatus::ConsentRevoked => "consent_revoked",
+            AccountStatus::PasswordChanged => "password_changed",
+            AccountStatus::SessionExpired => "session_expired",
+            AccountStatus::InteractionRequired => "interaction_required",
+            AccountStatus::ChildAccount => "child_account",
+            AccountStatus::NoXboxAccount => "no_xbox_account",
+            AccountStatus::RegionBlocked => "region_blocked",
+        }
+    }
+
+    /// What the user should do to recover
+    pub fn hint(self) -> &'static str {
+        match self {
+            AccountStatus::ConsentRevoked => {
+                "Launcher access was revoked. Sign in again to grant access."
+            }
+            AccountStatus::PasswordChanged => {
+                "The account password changed. Sign in again with the new password."
+            }
+            AccountStatus::SessionExpired => "The saved session expired. Sign in again.",
+            AccountStatus::InteractionRequired => {
+                "Microsoft requires an interactive sign-in (e.g. two-step verification). Sign in again."
+            }
+            AccountStatus::ChildAccount => {
+                "This is a child account. An adult must add it to a Microsoft family at https://account.xbox.com/Profile, then sign in again."
+            }
+            AccountStatus::NoXboxAccount => {
+                "This account has no Xbox profile. Sign in at https://xbox.com to create one, then sign in again."
+            }
+            AccountStatus::RegionBlocked => {
+                "Xbox Live is not available in this account's region."
+            }
+        }
+    }
+
+    /// Whether signing in again (device code flow) can resolve the state
+    pub fn needs_reauth(self) -> bool {
+        !matches!(self, AccountStatus::RegionBlocked)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +115,11 @@ impl MinecraftTokens {
     pub fn is_expired(&self) -> bool {
         now_epoch_secs() + 60 >= self.expires_at
     }
+
+    /// Whether the token runs out in the next `secs` seconds
+    pub fn expires_within(&self, secs: u64) -> bool {
+        now_epoch_secs() + secs >= self.expires_at
+    }
 }
 
 pub fn load_accounts(paths: &Paths) -> Result<Accounts> {
@@ -66,6 +138,7 @@ pub fn load_accounts(paths: &Paths) -> Result<Accounts> {
 }
 
 pub fn save_accounts(paths: &Paths, accounts: &Accounts) -> Result<()> {
+    paths.ensure_writable()?;
     if let Some(parent) = Path::new(&paths.accounts).parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("failed to create directory: {}", parent.display()))?;
@@ -0,0 +1,191 @@
+//! Health and usage of third-party API keys
+//!
+//! CurseForge requests need an API key. A mistyped or revoked key makes every
+//! request fail with 401/403, which otherwise only shows up as a failed search
+//! or install. Keys are checked when they are saved, and every CurseForge
+//! response updates the key's recorded status, request count and rate-limit
+//! headers in `manifests/api-keys.json` in the cache dir, which
+//! `shard config show` reports.
+
+use crate::curseforge::CurseForgeClient;
+use crate::disk::WriteContext;
+use crate::paths::Paths;
+use crate::util::now_epoch_secs;
+use anyhow::{Context, Result, bail};
+use reqwest::StatusCode;
+use reqwest::blocking::Response;
+use reqwest::header::HeaderValue;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+pub const CURSEFORGE: &str = "curseforge";
+
+/// Health file updated by API responses; `None` until
+/// [`track_api_key_health`] is called
+static HEALTH_FILE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Serializes read-modify-write of the health file within the process
+static HEALTH_LOCK: Mutex<()> = Mutex::new(());
+
+impl Paths {
+    fn api_key_health_file(&self) -> PathBuf {
+        self.cache_manifests.join("api-keys.json")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStatus {
+    /// No response seen for this key yet
+    #[default]
+    Unchecked,
+    Valid,
+    /// The platform rejected the key (401/403)
+    Invalid,
+    /// The key works but hit its rate limit (429)
+    RateLimited,
+}
+
+impl KeyStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unchecked => "unchecked",
+            Self::Valid => "valid",
+            Self::Invalid => "invalid",
+            Self::RateLimited => "rate_limited",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyHealth {
+    pub status: KeyStatus,
+    /// Last characters of the key the entry is about
+    pub key_hint: String,
+    /// Time of the last response
+    #[serde(default)]
+    pub checked_at: Option<u64>,
+    /// HTTP status of the last response that wasn't a success
+    #[serde(default)]
+    pub last_error: Option<u16>,
+    /// Requests made with this key since it was first seen
+    #[serde(default)]
+    pub requests: u64,
+    #[serde(default)]
+    pub rate_limit: Option<u64>,
+    #[serde(default)]
+    pub rate_remaining: Option<u64>,
+    /// When the rate limit resets, as sent by the server
+    #[serde(default)]
+    pub rate_reset: Option<u64>,
+}
+
+fn key_hint(key: &str) -> String {
+    let chars: Vec<char> = key.trim().chars().collect();
+    let tail: String = chars[chars.len().saturating_sub(4)..].iter().collect();
+    format!("...{tail}")
+}
+
+/// Record the health of keys used by API clients in this process, unless
+/// the data dir is open read-only
+pub fn track_api_key_health(paths: &Paths) {
+    if paths.read_only {
+        return;
+    }
+    if let Ok(mut current) = HEALTH_FILE.write() {
+        *current = Some(paths.api_key_health_file());
+    }
+}
+
+fn load_health_file(path: &Path) -> Result<BTreeMap<String, ApiKeyHealth>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let data = fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Recorded health of `key` on `platform`; `None` if the key hasn't been
+/// used since it was set
+pub fn api_key_health(paths: &Paths, platform: &str, key: &str) -> Result<Option<ApiKeyHealth>> {
+    let mut entries = load_health_file(&paths.api_key_health_file())?;
+    Ok(entries.remove(platform).filter(|health| health.key_hint == key_hint(key)))
+}
+
+/// Fold a response into the key's health
+fn apply_response(health: &mut ApiKeyHealth, resp: &Response) {
+    let status = resp.status();
+    health.requests += 1;
+    health.checked_at = Some(now_epoch_secs());
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => health.status = KeyStatus::Invalid,
+        StatusCode::TOO_MANY_REQUESTS => health.status = KeyStatus::RateLimited,
+        _ if status.is_success() => health.status = KeyStatus::Valid,
+        // A server error says nothing about the key
+        _ => {}
+    }
+    health.last_error = (!status.is_success()).then_some(status.as_u16());
+    let header = |name: &str| {
+        resp.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+    if let Some(limit) = header("x-ratelimit-limit") {
+        health.rate_limit = Some(limit);
+    }
+    if let Some(remaining) = header("x-ratelimit-remaining") {
+        health.rate_remaining = Some(remaining);
+    }
+    if let Some(reset) = header("x-ratelimit-reset") {
+        health.rate_reset = Some(reset);
+    }
+}
+
+fn update_health_file(path: &Path, platform: &str, key: &str, resp: &Response) -> Result<ApiKeyHealth> {
+    let _guard = HEALTH_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut entries = load_health_file(path).unwrap_or_default();
+    let hint = key_hint(key);
+    let health = entries.entry(platform.to_string()).or_default();
+    // A new key starts over
+    if health.key_hint != hint {
+        *health = ApiKeyHealth {
+            key_hint: hint,
+            ..Default::default()
+        };
+    }
+    apply_response(health, resp);
+    let health = health.clone();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    let data = serde_json::to_string_pretty(&entries).context("failed to serialize api key health")?;
+    fs::write(path, data).write_context(path, || format!("failed to write {}", path.display()))?;
+    Ok(health)
+}
+
+/// Record an API response made with `key`, if tracking is enabled. Best
+/// effort: a failed write never fails the request.
+pub(crate) fn record_response(platform: &str, key: &str, resp: &Response) {
+    let path = HEALTH_FILE.read().ok().and_then(|file| file.clone());
+    if let Some(path) = path {
+        let _ = update_health_file(&path, platform, key, resp);
+    }
+}
+
+/// Check a CurseForge API key with a single request and record the result.
+/// Fails only when CurseForge can't be reached.
+pub fn verify_curseforge_key(paths: &Paths, key: &str) -> Result<ApiKeyHealth> {
+    let key = key.trim();
+    if key.is_empty() || HeaderValue::from_str(key).is_err() {
+        bail!("CurseForge API key is empty or contains invalid characters");
+    }
+    let resp = CurseForgeClient::new(key)
+        .ping()
+        .context("failed to reach CurseForge")?;
+    update_health_file(&paths.api_key_health_file(), CURSEFORGE, key, &resp)
+}
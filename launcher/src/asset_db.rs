@@ -0,0 +1,432 @@
+//! Bookkeeping for downloaded asset objects
+//!
+//! A game version references thousands of asset objects. Re-hashing all of
+//! them on every launch, verify or storage-stats run is slow, so each object
+//! is recorded in a small SQLite table with its size and when it was last
+//! verified. Launches trust a recorded object whose size still matches,
+//! verification can re-check only objects not verified recently, and garbage
+//! collection and stats read the table instead of walking the objects dir.
+
+use crate::paths::Paths;
+use crate::sql_profile::profile_connection;
+use crate::throttle::{Cancelled, Throttle};
+use crate::util::now_epoch_secs;
+use anyhow::{Context, Result, bail};
+use rusqlite::{Connection, OpenFlags, params};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+impl Paths {
+    /// Database of downloaded asset objects, shared by all spaces like the
+    /// objects themselves
+    pub fn asset_db(&self) -> PathBuf {
+        self.minecraft_assets_objects.with_file_name("objects.db")
+    }
+}
+
+/// A downloaded asset object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetRecord {
+    /// SHA-1 of the object, also its file name
+    pub hash: String,
+    pub size: u64,
+    /// Epoch seconds of the last successful hash check; 0 for objects that
+    /// were found on disk but never checked
+    pub verified_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct AssetUsage {
+    pub objects: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetVerifyReport {
+    pub checked: usize,
+    /// Objects verified recently enough to skip
+    pub skipped: usize,
+    pub bytes: u64,
+    /// Objects recorded but no longer on disk
+    pub missing: Vec<String>,
+    /// Objects whose content no longer matched; they were removed so the next
+    /// launch downloads them again
+    pub corrupt: Vec<String>,
+    /// Stopped early; the counts cover only what was checked
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetGcReport {
+    pub removed: usize,
+    pub bytes: u64,
+    /// Stopped early; only the counted objects were removed
+    pub cancelled: bool,
+}
+
+/// Local asset object database
+pub struct AssetDb {
+    conn: Connection,
+}
+
+impl AssetDb {
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("failed to create dir: {}", parent.display()))?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("failed to open asset database: {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    /// Open an existing database without changing it, for inspect mode; a
+    /// database that doesn't exist yet opens empty
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Self::init(Connection::open_in_memory().context("failed to open in-memory asset database")?);
+        }
+        let mut conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+            .with_context(|| format!("failed to open asset database: {}", path.display()))?;
+        profile_connection(&mut conn);
+        Ok(Self { conn })
+    }
+
+    fn init(mut conn: Connection) -> Result<Self> {
+        profile_connection(&mut conn);
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS asset_objects (
+                hash TEXT PRIMARY KEY,
+                size INTEGER NOT NULL,
+                verified_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_asset_objects_verified_at ON asset_objects(verified_at);
+            "#,
+        )
+        .context("failed to initialize asset database schema")?;
+        Ok(Self { conn })
+    }
+
+    /// Open the database, recording objects already on disk the first time;
+    /// read-only in inspect mode
+    pub fn from_paths(paths: &Paths) -> Result<Self> {
+        if paths.read_only {
+            return Self::open_read_only(&paths.asset_db());
+        }
+        let db = Self::open(&paths.asset_db())?;
+        if db.usage()?.objects == 0 {
+            db.adopt_existing(paths)?;
+        }
+        Ok(db)
+    }
+
+    /// Record objects present on disk but missing from the table, as
+    /// unverified. Returns the number added.
+    pub fn adopt_existing(&self, paths: &Paths) -> Result<usize> {
+        let known = self.sizes()?;
+        let mut found = Vec::new();
+        let Ok(prefixes) = fs::read_dir(&paths.minecraft_assets_objects) else {
+            return Ok(0);
+        };
+        for prefix in prefixes.flatten() {
+            let Ok(objects) = fs::read_dir(prefix.path()) else {
+                continue;
+            };
+            for object in objects.flatten() {
+                let hash = object.file_name().to_string_lossy().to_ascii_lowercase();
+                // Skip partial downloads and anything else not named by its hash
+                if hash.len() != 40 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) || known.contains_key(&hash) {
+                    continue;
+                }
+                let Ok(metadata) = object.metadata() else {
+                    continue;
+                };
+                if metadata.is_file() {
+                    found.push(AssetRecord {
+                        hash,
+                        size: metadata.len(),
+                        verified_at: 0,
+                    });
+                }
+            }
+        }
+        self.record(&found)?;
+        Ok(found.len())
+    }
+
+    /// Recorded size of every object
+    pub fn sizes(&self) -> Result<HashMap<String, u64>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT hash, size FROM asset_objects")
+            .context("failed to query asset objects")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64)))
+            .context("failed to query asset objects")?;
+        rows.collect::<rusqlite::Result<_>>()
+            .context("failed to read asset objects")
+    }
+
+    /// Objects last verified at or before `cutoff`, oldest first
+    pub fn verified_before(&self, cutoff: u64) -> Result<Vec<AssetRecord>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT hash, size, verified_at FROM asset_objects WHERE verified_at <= ?1 ORDER BY verified_at")
+            .context("failed to query asset objects")?;
+        let rows = stmt
+            .query_map(params![cutoff.min(i64::MAX as u64) as i64], |row| {
+                Ok(AssetRecord {
+                    hash: row.get(0)?,
+                    size: row.get::<_, i64>(1)? as u64,
+                    verified_at: row.get::<_, i64>(2)? as u64,
+                })
+            })
+            .context("failed to query asset objects")?;
+        rows.collect::<rusqlite::Result<_>>()
+            .context("failed to read asset objects")
+    }
+
+    /// Insert or update objects in one transaction
+    pub fn record(&self, records: &[AssetRecord]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("failed to start asset database transaction")?;
+        {
+            let mut stmt = tx
+                .prepare(
+                    r#"
+                    INSERT INTO asset_objects (hash, size, verified_at) VALUES (?1, ?2, ?3)
+                    ON CONFLICT(hash) DO UPDATE SET size = excluded.size, verified_at = excluded.verified_at
+                    "#,
+                )
+                .context("failed to prepare asset record")?;
+            for record in records {
+                stmt.execute(params![record.hash, record.size as i64, record.verified_at as i64])
+                    .context("failed to record asset object")?;
+            }
+        }
+        tx.commit().context("failed to save asset objects")
+    }
+
+    /// Forget objects in one transaction
+    pub fn remove(&self, hashes: &[String]) -> Result<()> {
+        if hashes.is_empty() {
+            return Ok(());
+        }
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("failed to start asset database transaction")?;
+        {
+            let mut stmt = tx
+                .prepare("DELETE FROM asset_objects WHERE hash = ?1")
+                .context("failed to prepare asset removal")?;
+            for hash in hashes {
+                stmt.execute(params![hash]).context("failed to remove asset object")?;
+            }
+        }
+        tx.commit().context("failed to save asset objects")
+    }
+
+    pub fn usage(&self) -> Result<AssetUsage> {
+        self.conn
+            .query_row("SELECT COUNT(*), COALESCE(SUM(size), 0) FROM asset_objects", [], |row| {
+                Ok(AssetUsage {
+                    objects: row.get::<_, i64>(0)? as u64,
+                    bytes: row.get::<_, i64>(1)? as u64,
+                })
+            })
+            .context("failed to query asset usage")
+    }
+}
+
+/// Count and total size of downloaded asset objects
+pub fn asset_usage(paths: &Paths) -> Result<AssetUsage> {
+    AssetDb::from_paths(paths)?.usage()
+}
+
+/// SHA-1 of a file while reporting reads to the throttle
+pub(crate) fn sha1_file_throttled(path: &Path, throttle: &mut Throttle) -> Result<std::result::Result<String, Cancelled>> {
+    let mut file = fs::File::open(path).with_context(|| format!("failed to open asset: {}", path.display()))?;
+    let mut hasher = Sha1::new();
+    let mut buf = [0u8; 1024 * 64];
+    loop {
+        let read = file.read(&mut buf).context("failed to hash asset")?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+        if let Err(cancelled) = throttle.consume(read as u64) {
+            return Ok(Err(cancelled));
+        }
+    }
+    Ok(Ok(hex::encode(hasher.finalize())))
+}
+
+/// Re-hash asset objects not verified within `max_age_secs` (all of them when
+/// `None`). Corrupt objects are deleted so the next launch downloads them
+/// again. A cancelled throttle keeps what was checked so far.
+pub fn verify_assets(paths: &Paths, max_age_secs: Option<u64>, throttle: &mut Throttle) -> Result<AssetVerifyReport> {
+    paths.ensure_writable()?;
+    let db = AssetDb::from_paths(paths)?;
+    let now = now_epoch_secs();
+    let cutoff = match max_age_secs {
+        Some(age) => now.saturating_sub(age),
+        None => {
+            // A full check also picks up objects added behind our back
+            db.adopt_existing(paths)?;
+            u64::MAX
+        }
+    };
+    let due = db.verified_before(cutoff)?;
+    let mut report = AssetVerifyReport {
+        skipped: (db.usage()?.objects as usize).saturating_sub(due.len()),
+        ..AssetVerifyReport::default()
+    };
+
+    let mut verified = Vec::new();
+    let mut gone = Vec::new();
+    for record in due {
+        let path = paths.minecraft_asset_object(&record.hash);
+        let Ok(metadata) = fs::metadata(&path) else {
+            report.missing.push(record.hash.clone());
+            gone.push(record.hash);
+            continue;
+        };
+        let actual = match sha1_file_throttled(&path, throttle)? {
+            Ok(actual) => actual,
+            Err(Cancelled) => {
+                report.cancelled = true;
+                break;
+            }
+        };
+        report.checked += 1;
+        report.bytes += metadata.len();
+        if actual.eq_ignore_ascii_case(&record.hash) {
+            verified.push(AssetRecord {
+                size: metadata.len(),
+                verified_at: now,
+                ..record
+            });
+        } else {
+            fs::remove_file(&path).with_context(|| format!("failed to remove corrupt asset: {}", path.display()))?;
+            report.corrupt.push(record.hash.clone());
+            gone.push(record.hash);
+        }
+    }
+    db.record(&verified)?;
+    db.remove(&gone)?;
+    Ok(report)
+}
+
+#[derive(Deserialize)]
+struct IndexFile {
+    objects: HashMap<String, IndexObject>,
+}
+
+#[derive(Deserialize)]
+struct IndexObject {
+    hash: String,
+}
+
+/// Hashes referenced by any asset index on disk, reporting the index reads to
+/// the throttle
+fn referenced_hashes(
+    paths: &Paths,
+    throttle: &mut Throttle,
+) -> Result<std::result::Result<HashSet<String>, Cancelled>> {
+    let mut hashes = HashSet::new();
+    let Ok(entries) = fs::read_dir(&paths.minecraft_assets_indexes) else {
+        return Ok(Ok(hashes));
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let data = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read asset index: {}", path.display()))?;
+        if let Err(cancelled) = throttle.consume(data.len() as u64) {
+            return Ok(Err(cancelled));
+        }
+        let index: IndexFile = serde_json::from_str(&data)
+            .with_context(|| format!("failed to parse asset index: {}", path.display()))?;
+        hashes.extend(index.objects.into_values().map(|object| object.hash.to_ascii_lowercase()));
+    }
+    Ok(Ok(hashes))
+}
+
+/// Delete asset objects no asset index references anymore. Index reads are
+/// paced by `throttle`; cancelling it before all indexes are read removes
+/// nothing, and afterwards keeps what was removed so far.
+pub fn gc_assets(paths: &Paths, throttle: &mut Throttle) -> Result<AssetGcReport> {
+    paths.ensure_writable()?;
+    let mut report = AssetGcReport::default();
+    let Ok(referenced) = referenced_hashes(paths, throttle)? else {
+        report.cancelled = true;
+        return Ok(report);
+    };
+    if referenced.is_empty() {
+        bail!("no asset indexes found; refusing to remove every asset object");
+    }
+    let db = AssetDb::from_paths(paths)?;
+    let mut removed = Vec::new();
+    for (hash, size) in db.sizes()? {
+        if referenced.contains(&hash) {
+            continue;
+        }
+        if throttle.is_cancelled() {
+            report.cancelled = true;
+            break;
+        }
+        let path = paths.minecraft_asset_object(&hash);
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                report.removed += 1;
+                report.bytes += size;
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to remove asset: {}", path.display()));
+            }
+        }
+        removed.push(hash);
+    }
+    db.remove(&removed)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::TempDir;
+
+    #[test]
+    fn test_record_and_query() {
+        let dir = TempDir::new("asset-db");
+        let db = AssetDb::open(&dir.join("objects.db")).unwrap();
+        let record = |hash: &str, size, verified_at| AssetRecord {
+            hash: hash.to_string(),
+            size,
+            verified_at,
+        };
+        db.record(&[record("aa", 10, 100), record("bb", 20, 0)]).unwrap();
+        db.record(&[record("aa", 12, 200)]).unwrap();
+
+        let usage = db.usage().unwrap();
+        assert_eq!((usage.objects, usage.bytes), (2, 32));
+        assert_eq!(db.verified_before(150).unwrap(), vec![record("bb", 20, 0)]);
+
+        db.remove(&["bb".to_string()]).unwrap();
+        assert_eq!(db.sizes().unwrap(), HashMap::from([("aa".to_string(), 12)]));
+    }
+}
@@ -1,14 +1,24 @@
-use crate::util::now_epoch_secs;
+use crate::accounts::AccountStatus;
+use crate::net::RetryExt;
+use crate::util::{now_epoch_secs, random_token};
 use anyhow::{Context, Result, bail};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use reqwest::Url;
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 const MS_DEVICE_CODE_URL: &str =
     "https://login.microsoftonline.com/consumers/oauth2/v2.0/devicecode";
 const MS_TOKEN_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/token";
+const MS_AUTHORIZE_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/authorize";
+const MS_SCOPE: &str = "XboxLive.signin offline_access";
 const XBL_AUTH_URL: &str = "https://user.auth.xboxlive.com/user/authenticate";
 const XSTS_AUTH_URL: &str = "https://xsts.auth.xboxlive.com/xsts/authorize";
 const MC_LOGIN_URL: &str = "https://api.minecraftservices.com/authentication/login_with_xbox";
@@ -145,8 +155,7 @@ struct McProfile {
 
 pub fn request_device_code(client_id: &str, client_secret: Option<&str>) -> Result<DeviceCode> {
     let client = Client::new();
-    let scope = "XboxLive.signin offline_access";
-    let mut params = vec![("client_id", client_id), ("scope", scope)];
+    let mut params = vec![("client_id", client_id), ("scope", MS_SCOPE)];
     if let Some(secret) = client_secret {
         params.push(("client_secret", secret));
     }
@@ -154,7 +163,7 @@ pub fn request_device_code(client_id: &str, client_secret: Option<&str>) -> Resu
     let resp = client
         .post(MS_DEVICE_CODE_URL)
         .form(&params)
-        .send()
+        .send_with_cautious_retry()
         .context("failed to request device code")?;
 
     if !resp.status().is_success() {
@@ -200,7 +209,7 @@ pub fn poll_device_code(
         let resp = client
             .post(MS_TOKEN_URL)
             .form(&params)
-            .send()
+            .send_with_cautious_retry()
             .context("failed to poll token endpoint")?;
 
         if resp.status().is_success() {
@@ -240,6 +249,167 @@ pub fn poll_device_code(
     }
 }
 
+/// A browser sign-in waiting for Microsoft to redirect back. Created by
+/// [`start_browser_auth`]; the user signs in at `auth_url` and
+/// [`finish_browser_auth`] picks up the code on the localhost redirect.
+#[derive(Debug)]
+pub struct BrowserAuth {
+    pub auth_url: String,
+    redirect_uri: String,
+    listener: TcpListener,
+    verifier: String,
+    state: String,
+}
+
+const BROWSER_AUTH_DONE_PAGE: &str = "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Shard</title></head>\
+<body style=\"font-family: system-ui, sans-serif; text-align: center; margin-top: 4rem\">\
+<h2>{title}</h2><p>You can close this tab and return to Shard.</p></body></html>";
+
+/// PKCE S256 challenge for a code verifier
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Start an authorization-code sign-in with PKCE: listen on a free localhost
+/// port and build the URL to open in the browser. The app registration needs
+/// `http://localhost` as a redirect URI (any port matches).
+pub fn start_browser_auth(client_id: &str) -> Result<BrowserAuth> {
+    let listener = TcpListener::bind("127.0.0.1:0").context("failed to listen for the sign-in redirect")?;
+    let port = listener.local_addr().context("failed to read redirect port")?.port();
+    let redirect_uri = format!("http://localhost:{port}");
+    let verifier = random_token();
+    let state = random_token();
+    let auth_url = Url::parse_with_params(
+        MS_AUTHORIZE_URL,
+        &[
+            ("client_id", client_id),
+            ("response_type", "code"),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("response_mode", "query"),
+            ("scope", MS_SCOPE),
+            ("state", state.as_str()),
+            ("code_challenge", pkce_challenge(&verifier).as_str()),
+            ("code_challenge_method", "S256"),
+            ("prompt", "select_account"),
+        ],
+    )
+    .context("failed to build sign-in url")?;
+    Ok(BrowserAuth {
+        auth_url: auth_url.into(),
+        redirect_uri,
+        listener,
+        verifier,
+        state,
+    })
+}
+
+/// Wait up to `timeout` for the browser to come back with a code and trade it
+/// for tokens
+pub fn finish_browser_auth(
+    auth: BrowserAuth,
+    client_id: &str,
+    client_secret: Option<&str>,
+    timeout: Duration,
+) -> Result<OAuthToken> {
+    let code = wait_for_redirect(&auth, timeout)?;
+
+    let mut params = vec![
+        ("grant_type", "authorization_code"),
+        ("client_id", client_id),
+        ("code", code.as_str()),
+        ("redirect_uri", auth.redirect_uri.as_str()),
+        ("code_verifier", auth.verifier.as_str()),
+        ("scope", MS_SCOPE),
+    ];
+    if let Some(secret) = client_secret {
+        params.push(("client_secret", secret));
+    }
+
+    let resp = Client::new()
+        .post(MS_TOKEN_URL)
+        .form(&params)
+        .send_with_cautious_retry()
+        .context("failed to exchange sign-in code")?;
+    if !resp.status().is_success() {
+        return Err(format_oauth_error("sign-in code exchange failed", resp));
+    }
+
+    let data: TokenResponse = resp.json().context("failed to parse token response")?;
+    let refresh_token = data
+        .refresh_token
+        .context("refresh token missing; ensure offline_access scope")?;
+    Ok(OAuthToken {
+        access_token: data.access_token,
+        refresh_token,
+        expires_at: now_epoch_secs() + data.expires_in,
+    })
+}
+
+/// Accept connections on the redirect port until one carries the code (or
+/// an error). Other requests, like the browser asking for a favicon, get a
+/// 404.
+fn wait_for_redirect(auth: &BrowserAuth, timeout: Duration) -> Result<String> {
+    auth.listener
+        .set_nonblocking(true)
+        .context("failed to configure redirect listener")?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        let stream = match auth.listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    bail!("timed out waiting for the browser sign-in");
+                }
+                sleep(Duration::from_millis(100));
+                continue;
+            }
+            Err(err) => return Err(err).context("failed to accept sign-in redirect"),
+        };
+        if let Some(result) = handle_redirect(stream, &auth.state) {
+            return result;
+        }
+    }
+}
+
+/// `None` when the request wasn't the redirect: a stray or broken
+/// connection, or one without our `state`, which could come from any page
+/// able to reach localhost. Only a matching request may end the sign-in.
+fn handle_redirect(mut stream: TcpStream, state: &str) -> Option<Result<String>> {
+    stream.set_nonblocking(false).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(5))).ok();
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line).ok()?;
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let url = Url::parse(&format!("http://localhost{target}")).ok()?;
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    if param("state").as_deref() != Some(state) {
+        let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        return None;
+    }
+    let result = match (param("code"), param("error")) {
+        (_, Some(error)) => {
+            let desc = param("error_description").unwrap_or_default();
+            Err(anyhow::anyhow!("sign-in failed: {error}: {desc}"))
+        }
+        (Some(code), None) => Ok(code),
+        (None, None) => Err(anyhow::anyhow!("sign-in failed: redirect carried no code")),
+    };
+
+    let title = if result.is_ok() { "Signed in" } else { "Sign-in failed" };
+    let body = BROWSER_AUTH_DONE_PAGE.replace("{title}", title);
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes());
+    Some(result)
+}
+
 pub fn refresh_msa_token(
     client_id: &str,
     client_secret: Option<&str>,
@@ -258,7 +428,7 @@ pub fn refresh_msa_token(
     let resp = client
         .post(MS_TOKEN_URL)
         .form(&params)
-        .send()
+        .send_with_cautious_retry()
         .context("failed to refresh token")?;
 
     if !resp.status().is_success() {
@@ -315,7 +485,7 @@ fn xbox_live_auth(ms_access_token: &str) -> Result<(String, String, Option<Strin
     let resp = client
         .post(XBL_AUTH_URL)
         .json(&body)
-        .send()
+        .send_with_cautious_retry()
         .context("failed xbox live auth request")?;
 
     if !resp.status().is_success() {
@@ -347,7 +517,7 @@ fn xsts_auth(xbl_token: &str) -> Result<(String, String, Option<String>)> {
     let resp = client
         .post(XSTS_AUTH_URL)
         .json(&body)
-        .send()
+        .send_with_cautious_retry()
         .context("failed xsts auth request")?;
 
     if !resp.status().is_success() {
@@ -377,7 +547,7 @@ fn minecraft_login(xsts_token: &str, user_hash: &str) -> Result<MinecraftToken>
     let resp = client
         .post(MC_LOGIN_URL)
         .json(&body)
-        .send()
+        .send_with_cautious_retry()
         .context("failed minecraft login request")?
         .error_for_status()
         .context("minecraft login failed")?;
@@ -390,12 +560,42 @@ fn minecraft_login(xsts_token: &str, user_hash: &str) -> Result<MinecraftToken>
     })
 }
 
+/// Result of checking a Minecraft access token against the profile endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionState {
+    Valid,
+    /// The token was rejected (revoked, or expired early)
+    Invalid,
+    /// The token works but there is no Java profile: the game isn't owned
+    NoProfile,
+    /// The endpoint couldn't be reached or answered oddly
+    Unknown,
+}
+
+/// Check that Minecraft still accepts an access token. A single quick request,
+/// so an unreachable endpoint doesn't hold up a launch.
+pub fn validate_minecraft_session(access_token: &str) -> SessionState {
+    let client = match Client::builder().timeout(Duration::from_secs(10)).build() {
+        Ok(client) => client,
+        Err(_) => return SessionState::Unknown,
+    };
+    match client.get(MC_PROFILE_URL).bearer_auth(access_token).send() {
+        Ok(resp) if resp.status().is_success() => SessionState::Valid,
+        Ok(resp) => match resp.status().as_u16() {
+            401 | 403 => SessionState::Invalid,
+            404 => SessionState::NoProfile,
+            _ => SessionState::Unknown,
+        },
+        Err(_) => SessionState::Unknown,
+    }
+}
+
 fn minecraft_profile(access_token: &str) -> Result<McProfile> {
     let client = Client::new();
     let resp = client
         .get(MC_PROFILE_URL)
         .bearer_auth(access_token)
-        .send()
+        .send_with_retry()
         .context("failed minecraft profile request")?
         .error_for_status()
         .context("minecraft profile request failed (does the account own Minecraft?)")?;
@@ -408,6 +608,65 @@ struct MinecraftToken {
     expires_at: u64,
 }
 
+/// Auth failure that maps to an actionable account state. Returned inside
+/// `anyhow::Error`; use `auth_failure_status` to recover the status.
+#[derive(Debug)]
+pub struct AuthError {
+    pub status: AccountStatus,
+    pub message: String,
+}
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}. {}", self.message, self.status.hint())
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Actionable account status carried by an auth error chain, if any
+pub fn auth_failure_status(err: &anyhow::Error) -> Option<AccountStatus> {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<AuthError>())
+        .map(|auth| auth.status)
+}
+
+/// Extract the numeric AADSTS code from an Entra ID error description
+fn parse_aadsts_code(desc: &str) -> Option<u32> {
+    let start = desc.find("AADSTS")? + "AADSTS".len();
+    let digits: String = desc[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+/// Map an OAuth error to an account status
+fn classify_oauth_error(error: &str, desc: &str) -> Option<AccountStatus> {
+    match parse_aadsts_code(desc) {
+        Some(65001) => Some(AccountStatus::ConsentRevoked),
+        Some(50173) => Some(AccountStatus::PasswordChanged),
+        Some(70000 | 70008 | 700082) => Some(AccountStatus::SessionExpired),
+        Some(50076 | 50079) => Some(AccountStatus::InteractionRequired),
+        _ => match error {
+            "invalid_grant" => Some(AccountStatus::SessionExpired),
+            "interaction_required" => Some(AccountStatus::InteractionRequired),
+            "consent_required" => Some(AccountStatus::ConsentRevoked),
+            _ => None,
+        },
+    }
+}
+
+/// Map an XSTS XErr code to an account status
+fn classify_xsts_error(xerr: i64) -> Option<AccountStatus> {
+    match xerr {
+        2148916233 => Some(AccountStatus::NoXboxAccount),
+        2148916235 => Some(AccountStatus::RegionBlocked),
+        2148916238 => Some(AccountStatus::ChildAccount),
+        _ => None,
+    }
+}
+
 fn format_oauth_error(prefix: &str, resp: reqwest::blocking::Response) -> anyhow::Error {
     let status = resp.status();
     let body = resp.json::<Value>().unwrap_or(Value::Null);
@@ -419,7 +678,15 @@ fn format_oauth_error(prefix: &str, resp: reqwest::blocking::Response) -> anyhow
         .get("error_description")
         .and_then(|v| v.as_str())
         .unwrap_or("unknown error");
-    anyhow::anyhow!("{prefix}: {status} {error}: {desc}")
+    let message = format!("{prefix}: {status} {error}: {desc}");
+    match classify_oauth_error(error, desc) {
+        Some(account_status) => AuthError {
+            status: account_status,
+            message,
+        }
+        .into(),
+        None => anyhow::anyhow!(message),
+    }
 }
 
 fn format_xbox_error(prefix: &str, resp: reqwest::blocking::Response) -> anyhow::Error {
@@ -440,22 +707,87 @@ fn format_xsts_error(prefix: &str, resp: reqwest::blocking::Response) -> anyhow:
         .and_then(|v| v.as_str())
         .unwrap_or("unknown error");
     let xerr = body.get("XErr").and_then(|v| v.as_i64());
-    let hint = match xerr {
-        Some(2148916233) => Some(
-            "This account has no Xbox Live account. Sign in at https://xbox.com and accept terms, then retry.",
-        ),
-        Some(2148916235) => Some(
-            "This account is in a family or underage. Update Xbox privacy/family settings, then retry.",
-        ),
-        Some(2148916236) => {
-            Some("This account is blocked by region. Check account region settings, then retry.")
+    let message = format!("{prefix}: {status} {message} (XErr={xerr:?})");
+
+    if let Some(account_status) = xerr.and_then(classify_xsts_error) {
+        return AuthError {
+            status: account_status,
+            message,
         }
-        _ => None,
-    };
+        .into();
+    }
+    match xerr {
+        Some(2148916236 | 2148916237) => anyhow::anyhow!(
+            "{message}. This account needs adult verification on the Xbox website, then retry."
+        ),
+        _ => anyhow::anyhow!(message),
+    }
+}
 
-    if let Some(hint) = hint {
-        anyhow::anyhow!("{prefix}: {status} {message} (XErr={xerr:?}). {hint}")
-    } else {
-        anyhow::anyhow!("{prefix}: {status} {message} (XErr={xerr:?})")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_oauth_error() {
+        let desc = "AADSTS65001: The user or administrator has not consented to use the application. Trace ID: abc";
+        assert_eq!(parse_aadsts_code(desc), Some(65001));
+        assert_eq!(
+            classify_oauth_error("invalid_grant", desc),
+            Some(AccountStatus::ConsentRevoked)
+        );
+        assert_eq!(
+            classify_oauth_error("invalid_grant", "AADSTS50173: The provided grant has expired due to it being revoked"),
+            Some(AccountStatus::PasswordChanged)
+        );
+        assert_eq!(
+            classify_oauth_error("invalid_grant", "no code here"),
+            Some(AccountStatus::SessionExpired)
+        );
+        assert_eq!(classify_oauth_error("temporarily_unavailable", "AADSTS90033"), None);
+    }
+
+    #[test]
+    fn test_pkce_challenge() {
+        // RFC 7636, appendix B
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_handle_redirect_checks_state() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let redirect = |request: &str| {
+            let mut client = TcpStream::connect(addr).unwrap();
+            client.write_all(request.as_bytes()).unwrap();
+            client.shutdown(std::net::Shutdown::Write).unwrap();
+            let (stream, _) = listener.accept().unwrap();
+            handle_redirect(stream, "abc")
+        };
+
+        assert_eq!(
+            redirect("GET /?code=c1&state=abc HTTP/1.1\r\n\r\n").unwrap().unwrap(),
+            "c1"
+        );
+        assert!(redirect("GET /?error=access_denied&state=abc HTTP/1.1\r\n\r\n").unwrap().is_err());
+        assert!(redirect("GET /?code=c1&state=xyz HTTP/1.1\r\n\r\n").is_none());
+        assert!(redirect("GET /?error=access_denied HTTP/1.1\r\n\r\n").is_none());
+        assert!(redirect("GET /favicon.ico HTTP/1.1\r\n\r\n").is_none());
+        assert!(redirect("").is_none());
+    }
+
+    #[test]
+    fn test_auth_failure_status_survives_context() {
+        let err: anyhow::Error = AuthError {
+            status: AccountStatus::ChildAccount,
+            message: "xsts auth failed".to_string(),
+        }
+        .into();
+        let err = err.context("failed to refresh account");
+        assert_eq!(auth_failure_status(&err), Some(AccountStatus::ChildAccount));
+        assert_eq!(auth_failure_status(&anyhow::anyhow!("network down")), None);
     }
 }
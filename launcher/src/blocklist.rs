@@ -0,0 +1,305 @@
+//! Content that must not be installed
+//!
+//! Server communities ban some client mods (x-ray, auto-clickers, ...). The
+//! blocklist names them by store project or by file hash, each with a reason.
+//! Store installs, updates, template and modpack imports refuse a blocked
+//! project, and every file entering the content store is checked against the
+//! blocked hashes, so local imports are covered as well. A list can be
+//! exported and imported to share it with other players.
+
+use crate::content_store::Platform;
+use crate::disk::WriteContext;
+use crate::paths::Paths;
+use crate::util::now_epoch_secs;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+impl Paths {
+    fn blocklist(&self) -> PathBuf {
+        self.config.with_file_name("blocklist.json")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockedEntry {
+    /// Platform of `project_id`; `None` matches the id on any platform
+    #[serde(default)]
+    pub platform: Option<Platform>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    /// File hash as `sha1:<hex>`, `sha512:<hex>` or a store hash
+    /// (`sha256:<hex>`, `blake3:<hex>`)
+    #[serde(default)]
+    pub hash: Option<String>,
+    /// Display name, for listing
+    #[serde(default)]
+    pub name: Option<String>,
+    pub reason: String,
+    #[serde(default)]
+    pub added_at: u64,
+}
+
+impl BlockedEntry {
+    fn label(&self) -> String {
+        match (&self.name, &self.project_id, &self.hash) {
+            (Some(name), _, _) => name.clone(),
+            (None, Some(id), _) => id.clone(),
+            (None, None, Some(hash)) => hash.clone(),
+            (None, None, None) => "content".to_string(),
+        }
+    }
+
+    fn matches_project(&self, platform: Platform, project_id: &str) -> bool {
+        self.project_id.as_deref() == Some(project_id) && self.platform.is_none_or(|p| p == platform)
+    }
+
+    fn matches_hash(&self, hash: &str) -> bool {
+        self.hash.as_deref().is_some_and(|h| h.eq_ignore_ascii_case(hash))
+    }
+}
+
+/// Installing something on the blocklist was refused
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlockedError {
+    /// What was being installed
+    pub subject: String,
+    pub reason: String,
+}
+
+impl fmt::Display for BlockedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is on the blocklist: {}", self.subject, self.reason)
+    }
+}
+
+impl std::error::Error for BlockedError {}
+
+/// Find a refused install anywhere in an error chain
+pub fn find_blocked_error(err: &anyhow::Error) -> Option<&BlockedError> {
+    err.chain().find_map(|cause| cause.downcast_ref::<BlockedError>())
+}
+
+/// Normalize a hash given by a user: lowercase, and bare 40/128 character
+/// hex strings become `sha1:`/`sha512:`. Bare 64 character strings are taken
+/// as SHA-256.
+pub fn normalize_blocked_hash(hash: &str) -> Result<String> {
+    let hash = hash.trim().to_ascii_lowercase();
+    let (algorithm, hex) = match hash.split_once(':') {
+        Some((algorithm, hex)) => (algorithm.to_string(), hex),
+        None => {
+            let algorithm = match hash.len() {
+                40 => "sha1",
+                64 => "sha256",
+                128 => "sha512",
+                _ => bail!("unrecognized hash: {hash} (use sha1:, sha256:, sha512: or blake3:)"),
+            };
+            (algorithm.to_string(), hash.as_str())
+        }
+    };
+    let expected_len = match algorithm.as_str() {
+        "sha1" => 40,
+        "sha256" | "blake3" => 64,
+        "sha512" => 128,
+        _ => bail!("unsupported hash algorithm: {algorithm}"),
+    };
+    if hex.len() != expected_len || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        bail!("invalid {algorithm} hash: {hex}");
+    }
+    Ok(format!("{algorithm}:{hex}"))
+}
+
+pub fn load_blocklist(paths: &Paths) -> Result<Vec<BlockedEntry>> {
+    read_blocklist(&paths.blocklist())
+}
+
+fn read_blocklist(path: &Path) -> Result<Vec<BlockedEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn write_blocklist(path: &Path, entries: &[BlockedEntry]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    let data = serde_json::to_string_pretty(entries).context("failed to serialize blocklist")?;
+    fs::write(path, data).write_context(path, || format!("failed to write {}", path.display()))
+}
+
+/// Add an entry, replacing the reason of an existing entry for the same
+/// project or hash. Returns whether the entry is new.
+fn merge_entry(entries: &mut Vec<BlockedEntry>, entry: BlockedEntry) -> bool {
+    let existing = entries.iter_mut().find(|e| {
+        (entry.project_id.is_some() && e.project_id == entry.project_id && e.platform == entry.platform)
+            || (entry.hash.is_some() && e.hash == entry.hash)
+    });
+    match existing {
+        Some(existing) => {
+            existing.reason = entry.reason;
+            if entry.name.is_some() {
+                existing.name = entry.name;
+            }
+            false
+        }
+        None => {
+            entries.push(entry);
+            true
+        }
+    }
+}
+
+/// Block a store project
+pub fn block_project(
+    paths: &Paths,
+    platform: Option<Platform>,
+    project_id: &str,
+    name: Option<String>,
+    reason: &str,
+) -> Result<BlockedEntry> {
+    let entry = BlockedEntry {
+        platform,
+        project_id: Some(project_id.to_string()),
+        hash: None,
+        name,
+        reason: reason.to_string(),
+        added_at: now_epoch_secs(),
+    };
+    let mut entries = load_blocklist(paths)?;
+    merge_entry(&mut entries, entry.clone());
+    write_blocklist(&paths.blocklist(), &entries)?;
+    Ok(entry)
+}
+
+/// Block a file by hash
+pub fn block_hash(paths: &Paths, hash: &str, name: Option<String>, reason: &str) -> Result<BlockedEntry> {
+    let entry = BlockedEntry {
+        platform: None,
+        project_id: None,
+        hash: Some(normalize_blocked_hash(hash)?),
+        name,
+        reason: reason.to_string(),
+        added_at: now_epoch_secs(),
+    };
+    let mut entries = load_blocklist(paths)?;
+    merge_entry(&mut entries, entry.clone());
+    write_blocklist(&paths.blocklist(), &entries)?;
+    Ok(entry)
+}
+
+/// Remove the entries for a project id, hash or name. Returns how many were
+/// removed.
+pub fn unblock(paths: &Paths, target: &str) -> Result<usize> {
+    let hash = normalize_blocked_hash(target).ok();
+    let mut entries = load_blocklist(paths)?;
+    let before = entries.len();
+    entries.retain(|e| {
+        e.project_id.as_deref() != Some(target)
+            && !e.name.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(target))
+            && (hash.is_none() || e.hash != hash)
+    });
+    let removed = before - entries.len();
+    if removed > 0 {
+        write_blocklist(&paths.blocklist(), &entries)?;
+    }
+    Ok(removed)
+}
+
+/// Merge a shared blocklist file into this one. Returns how many entries
+/// were new.
+pub fn import_blocklist(paths: &Paths, file: &Path) -> Result<usize> {
+    let mut imported = read_blocklist(file)?;
+    for entry in &mut imported {
+        if let Some(hash) = &entry.hash {
+            entry.hash = Some(normalize_blocked_hash(hash).with_context(|| format!("in {}", file.display()))?);
+        }
+        if entry.project_id.is_none() && entry.hash.is_none() {
+            bail!("blocklist entry without a project id or hash in {}", file.display());
+        }
+    }
+    let mut entries = load_blocklist(paths)?;
+    let added = imported
+        .into_iter()
+        .filter(|entry| merge_entry(&mut entries, entry.clone()))
+        .count();
+    write_blocklist(&paths.blocklist(), &entries)?;
+    Ok(added)
+}
+
+/// Write the blocklist to `file` for sharing
+pub fn export_blocklist(paths: &Paths, file: &Path) -> Result<usize> {
+    let entries = load_blocklist(paths)?;
+    write_blocklist(file, &entries)?;
+    Ok(entries.len())
+}
+
+/// Fail with a [`BlockedError`] when a store project is blocked
+pub fn check_project(paths: &Paths, platform: Platform, project_id: &str, subject: &str) -> Result<()> {
+    let entries = load_blocklist(paths)?;
+    match entries.iter().find(|e| e.matches_project(platform, project_id)) {
+        Some(entry) => Err(BlockedError {
+            subject: subject.to_string(),
+            reason: entry.reason.clone(),
+        }
+        .into()),
+        None => Ok(()),
+    }
+}
+
+/// Fail with a [`BlockedError`] when any of a file's hashes is blocked.
+/// `hashes` are prefixed (`sha1:<hex>`, ...).
+pub fn check_file_hashes(paths: &Paths, hashes: &[String], subject: &str) -> Result<()> {
+    let entries = load_blocklist(paths)?;
+    let blocked = entries
+        .iter()
+        .find(|e| hashes.iter().any(|hash| e.matches_hash(hash)));
+    match blocked {
+        Some(entry) => Err(BlockedError {
+            subject: format!("{subject} ({})", entry.label()),
+            reason: entry.reason.clone(),
+        }
+        .into()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_blocked_hash() {
+        let sha1 = "A".repeat(40);
+        assert_eq!(normalize_blocked_hash(&sha1).unwrap(), format!("sha1:{}", "a".repeat(40)));
+        let sha256 = "b".repeat(64);
+        assert_eq!(normalize_blocked_hash(&sha256).unwrap(), format!("sha256:{sha256}"));
+        let blake3 = format!("blake3:{sha256}");
+        assert_eq!(normalize_blocked_hash(&blake3).unwrap(), blake3);
+        assert!(normalize_blocked_hash("sha1:abc").is_err());
+        assert!(normalize_blocked_hash("md5:0123").is_err());
+        assert!(normalize_blocked_hash(&"z".repeat(40)).is_err());
+    }
+
+    #[test]
+    fn test_merge_entry_replaces_reason() {
+        let entry = |reason: &str| BlockedEntry {
+            platform: Some(Platform::Modrinth),
+            project_id: Some("xray".to_string()),
+            hash: None,
+            name: None,
+            reason: reason.to_string(),
+            added_at: 0,
+        };
+        let mut entries = Vec::new();
+        assert!(merge_entry(&mut entries, entry("x-ray")));
+        assert!(!merge_entry(&mut entries, entry("banned on the server")));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].reason, "banned on the server");
+        assert!(entries[0].matches_project(Platform::Modrinth, "xray"));
+        assert!(!entries[0].matches_project(Platform::CurseForge, "xray"));
+    }
+}
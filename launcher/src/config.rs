@@ -1,4 +1,9 @@
+use crate::instance::InstanceLinkMode;
+use crate::metered::MeteredMode;
+use crate::mirrors::DownloadMirrors;
+use crate::net::RetryPolicy;
 use crate::paths::Paths;
+use crate::store::HashAlgorithm;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -21,6 +26,43 @@ pub struct Config {
     /// Whether to automatically check for content updates on launcher start
     #[serde(default = "default_auto_update")]
     pub auto_update_enabled: bool,
+    /// Opt-in: count anonymized crash signatures in a local database
+    #[serde(default)]
+    pub crash_aggregation_enabled: bool,
+    /// Profile kept on the newest snapshot; `None` disables tracking
+    #[serde(default)]
+    pub snapshot_profile: Option<String>,
+    /// Hash used to address newly stored content; existing files keep theirs
+    #[serde(default)]
+    pub store_hash: HashAlgorithm,
+    /// Retries and backoff for network requests
+    #[serde(default)]
+    pub network_retry: RetryPolicy,
+    /// Download Temurin when no installed Java fits a profile's Minecraft
+    /// version, instead of failing the launch
+    #[serde(default)]
+    pub auto_install_java: bool,
+    /// Don't warm up new profiles in the background in the desktop app (see
+    /// `minecraft::warm_up`)
+    #[serde(default)]
+    pub skip_warm_up: bool,
+    /// How store content is placed into instances
+    #[serde(default)]
+    pub instance_links: InstanceLinkMode,
+    /// Mirrors tried before Mojang's download servers
+    #[serde(default)]
+    pub mirrors: DownloadMirrors,
+    /// Open the data dir read-only (inspect mode); `SHARD_READ_ONLY`
+    /// overrides it either way
+    #[serde(default)]
+    pub read_only: bool,
+    /// Launch versions whose LWJGL has no ARM natives with their own LWJGL
+    /// on ARM machines, instead of swapping in a release that has them
+    #[serde(default)]
+    pub keep_lwjgl_on_arm: bool,
+    /// Whether to hold back large downloads as on a metered connection
+    #[serde(default)]
+    pub metered: MeteredMode,
 }
 
 fn default_auto_update() -> bool {
@@ -102,6 +144,7 @@ pub fn load_config(paths: &Paths) -> Result<Config> {
 }
 
 pub fn save_config(paths: &Paths, config: &Config) -> Result<()> {
+    paths.ensure_writable()?;
     if let Some(parent) = Path::new(&paths.config).parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("failed to create config dir: {}", parent.display()))?;
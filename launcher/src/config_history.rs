@@ -0,0 +1,278 @@
+//! Launch-time snapshots of an instance's `config/` and `options.txt`
+//!
+//! Mods sometimes rewrite or reset their config on startup. Before each launch
+//! the current files are hashed and recorded as a snapshot; file contents are
+//! stored once per hash under `.shard-backups/config-history/objects/`, so an
+//! unchanged config costs nothing. The last [`MAX_SNAPSHOTS`] are kept and any
+//! of them can be restored.
+
+use crate::disk::WriteContext;
+use crate::mod_config::list_mod_configs;
+use crate::paths::Paths;
+use crate::util::now_epoch_secs;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+/// Snapshots kept per profile
+pub const MAX_SNAPSHOTS: usize = 10;
+
+/// Files larger than this are left out of snapshots
+const MAX_SNAPSHOT_FILE_SIZE: u64 = 8 * 1024 * 1024;
+
+const OPTIONS_FILE: &str = "options.txt";
+
+impl Paths {
+    pub fn instance_config_history(&self, profile_id: &str) -> PathBuf {
+        self.instance_dir(profile_id).join(".shard-backups").join("config-history")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSnapshot {
+    pub id: String,
+    pub created_at: u64,
+    /// Paths relative to the instance (`config/...` or `options.txt`) and
+    /// their SHA-256
+    pub files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub path: String,
+    pub kind: ConfigChangeKind,
+}
+
+/// Changes from `old` to `new`
+pub fn diff_snapshots(old: &ConfigSnapshot, new: &ConfigSnapshot) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+    for (path, hash) in &new.files {
+        match old.files.get(path) {
+            None => changes.push(ConfigChange {
+                path: path.clone(),
+                kind: ConfigChangeKind::Added,
+            }),
+            Some(previous) if previous != hash => changes.push(ConfigChange {
+                path: path.clone(),
+                kind: ConfigChangeKind::Modified,
+            }),
+            Some(_) => {}
+        }
+    }
+    for path in old.files.keys().filter(|path| !new.files.contains_key(*path)) {
+        changes.push(ConfigChange {
+            path: path.clone(),
+            kind: ConfigChangeKind::Removed,
+        });
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+fn snapshots_dir(paths: &Paths, profile_id: &str) -> PathBuf {
+    paths.instance_config_history(profile_id).join("snapshots")
+}
+
+fn objects_dir(paths: &Paths, profile_id: &str) -> PathBuf {
+    paths.instance_config_history(profile_id).join("objects")
+}
+
+/// Current config files of the instance, relative to the instance dir
+fn tracked_files(paths: &Paths, profile_id: &str) -> Result<Vec<String>> {
+    let mut files: Vec<String> = list_mod_configs(paths, profile_id)?
+        .into_iter()
+        .filter(|file| file.size <= MAX_SNAPSHOT_FILE_SIZE)
+        .map(|file| format!("config/{}", file.path))
+        .collect();
+    if paths.instance_dir(profile_id).join(OPTIONS_FILE).is_file() {
+        files.push(OPTIONS_FILE.to_string());
+    }
+    Ok(files)
+}
+
+/// Snapshots of a profile, newest first
+pub fn list_config_snapshots(paths: &Paths, profile_id: &str) -> Result<Vec<ConfigSnapshot>> {
+    let dir = snapshots_dir(paths, profile_id);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("failed to read dir: {}", dir.display()))? {
+        let path = entry.context("failed to read dir entry")?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let data = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read snapshot: {}", path.display()))?;
+        let snapshot: ConfigSnapshot = serde_json::from_str(&data)
+            .with_context(|| format!("failed to parse snapshot: {}", path.display()))?;
+        snapshots.push(snapshot);
+    }
+    snapshots.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(snapshots)
+}
+
+/// Record the current config files. Returns `None` when nothing changed since
+/// the latest snapshot.
+pub fn snapshot_configs(paths: &Paths, profile_id: &str) -> Result<Option<ConfigSnapshot>> {
+    let instance_dir = paths.instance_dir(profile_id);
+    let objects = objects_dir(paths, profile_id);
+    let mut files = BTreeMap::new();
+    for relative in tracked_files(paths, profile_id)? {
+        let path = instance_dir.join(&relative);
+        let data = fs::read(&path).with_context(|| format!("failed to read config: {}", path.display()))?;
+        let hash = hex::encode(Sha256::digest(&data));
+        let object = objects.join(&hash);
+        if !object.exists() {
+            fs::create_dir_all(&objects)
+                .write_context(&objects, || format!("failed to create dir: {}", objects.display()))?;
+            fs::write(&object, &data)
+                .write_context(&object, || format!("failed to write snapshot object: {}", object.display()))?;
+        }
+        files.insert(relative, hash);
+    }
+
+    let existing = list_config_snapshots(paths, profile_id)?;
+    if existing.first().is_some_and(|latest| latest.files == files) {
+        return Ok(None);
+    }
+    if files.is_empty() && existing.is_empty() {
+        return Ok(None);
+    }
+
+    let created_at = now_epoch_secs();
+    let dir = snapshots_dir(paths, profile_id);
+    fs::create_dir_all(&dir).write_context(&dir, || format!("failed to create dir: {}", dir.display()))?;
+    // Zero-padded so ids sort by time; kept after the latest one when several
+    // snapshots land in the same second
+    let stamp = existing
+        .first()
+        .and_then(|latest| latest.id.parse::<u64>().ok())
+        .map_or(created_at, |latest| created_at.max(latest + 1));
+    let snapshot = ConfigSnapshot {
+        id: format!("{stamp:012}"),
+        created_at,
+        files,
+    };
+    let path = dir.join(format!("{}.json", snapshot.id));
+    let data = serde_json::to_string_pretty(&snapshot).context("failed to serialize snapshot")?;
+    fs::write(&path, data).write_context(&path, || format!("failed to write snapshot: {}", path.display()))?;
+
+    prune_snapshots(paths, profile_id)?;
+    Ok(Some(snapshot))
+}
+
+/// Drop snapshots beyond [`MAX_SNAPSHOTS`] and objects no snapshot uses
+fn prune_snapshots(paths: &Paths, profile_id: &str) -> Result<()> {
+    let snapshots = list_config_snapshots(paths, profile_id)?;
+    let dir = snapshots_dir(paths, profile_id);
+    for old in snapshots.iter().skip(MAX_SNAPSHOTS) {
+        let path = dir.join(format!("{}.json", old.id));
+        fs::remove_file(&path).with_context(|| format!("failed to remove snapshot: {}", path.display()))?;
+    }
+
+    let used: HashSet<&String> = snapshots
+        .iter()
+        .take(MAX_SNAPSHOTS)
+        .flat_map(|s| s.files.values())
+        .collect();
+    let objects = objects_dir(paths, profile_id);
+    let Ok(entries) = fs::read_dir(&objects) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !used.contains(&name) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Put the instance's config files back as they were in `snapshot_id`. The
+/// current state is snapshotted first so the restore can be undone. Returns
+/// the changes applied.
+pub fn restore_config_snapshot(paths: &Paths, profile_id: &str, snapshot_id: &str) -> Result<Vec<ConfigChange>> {
+    let target = list_config_snapshots(paths, profile_id)?
+        .into_iter()
+        .find(|s| s.id == snapshot_id)
+        .with_context(|| format!("config snapshot not found: {snapshot_id}"))?;
+    // Read contents up front: recording the current state may prune the
+    // target snapshot if it is the oldest one kept
+    let objects = objects_dir(paths, profile_id);
+    let mut contents = BTreeMap::new();
+    for (relative, hash) in &target.files {
+        let Ok(data) = fs::read(objects.join(hash)) else {
+            bail!("config snapshot {snapshot_id} is incomplete (missing object {hash})");
+        };
+        contents.insert(relative.clone(), data);
+    }
+
+    snapshot_configs(paths, profile_id)?;
+    let current = list_config_snapshots(paths, profile_id)?
+        .into_iter()
+        .next()
+        .context("failed to record current configs")?;
+
+    let instance_dir = paths.instance_dir(profile_id);
+    let changes = diff_snapshots(&current, &target);
+    for change in &changes {
+        let path = instance_dir.join(&change.path);
+        match change.kind {
+            ConfigChangeKind::Removed => {
+                fs::remove_file(&path).with_context(|| format!("failed to remove {}", path.display()))?;
+            }
+            ConfigChangeKind::Added | ConfigChangeKind::Modified => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)
+                        .write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+                }
+                fs::write(&path, &contents[&change.path])
+                    .write_context(&path, || format!("failed to restore {}", path.display()))?;
+            }
+        }
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(files: &[(&str, &str)]) -> ConfigSnapshot {
+        ConfigSnapshot {
+            id: String::new(),
+            created_at: 0,
+            files: files.iter().map(|(p, h)| (p.to_string(), h.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let old = snapshot(&[("config/a.toml", "1"), ("config/b.json", "2"), ("options.txt", "3")]);
+        let new = snapshot(&[("config/a.toml", "1"), ("config/b.json", "9"), ("config/c.cfg", "4")]);
+        let changes: Vec<_> = diff_snapshots(&old, &new)
+            .into_iter()
+            .map(|c| (c.path, c.kind))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("config/b.json".to_string(), ConfigChangeKind::Modified),
+                ("config/c.cfg".to_string(), ConfigChangeKind::Added),
+                ("options.txt".to_string(), ConfigChangeKind::Removed),
+            ]
+        );
+    }
+}
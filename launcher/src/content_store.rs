@@ -3,12 +3,15 @@
 //! This module provides a single interface for searching and downloading
 //! content from multiple sources.
 
+use crate::blocklist::check_project;
 use crate::curseforge::{self, CurseForgeClient, ModLoaderType};
 use crate::modrinth::{ModrinthClient, ProjectType, SearchFacets};
 use crate::paths::Paths;
-use crate::store::store_from_url;
+use crate::profile::Profile;
+use crate::store::{HashAlgorithm, store_from_url};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Content type for unified search
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -49,8 +52,21 @@ impl ContentType {
     }
 }
 
+/// Loader to filter store versions by: the profile's mod loader, or its
+/// shader loader for shader packs
+pub fn store_loader_filter(profile: &Profile, ct: ContentType) -> Option<String> {
+    match ct {
+        ContentType::Mod | ContentType::ModPack => profile.loader.as_ref().map(|l| l.loader_type.clone()),
+        // For shaders, detect if profile has iris/optifine installed
+        ContentType::ShaderPack => profile
+            .primary_shader_loader()
+            .map(|sl| sl.modrinth_name().to_string()),
+        ContentType::ResourcePack => None,
+    }
+}
+
 /// Source platform
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Platform {
     Modrinth,
@@ -99,6 +115,28 @@ pub struct ContentItem {
     /// Supported loaders
     #[serde(default)]
     pub loaders: Vec<String>,
+    /// Project license, when the platform reports one
+    #[serde(default)]
+    pub license: Option<ContentLicense>,
+    /// Whether the author allows redistribution (CurseForge only)
+    #[serde(default)]
+    pub distribution_allowed: Option<bool>,
+}
+
+/// License of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentLicense {
+    /// SPDX identifier, or `LicenseRef-*` for custom licenses
+    pub id: String,
+    pub name: String,
+    pub url: Option<String>,
+}
+
+impl ContentLicense {
+    /// Display name, falling back to the identifier
+    pub fn label(&self) -> &str {
+        if self.name.is_empty() { &self.id } else { &self.name }
+    }
 }
 
 /// A downloadable version/file of content
@@ -132,7 +170,7 @@ pub struct ContentVersion {
     pub loaders: Vec<String>,
     /// Release type (release, beta, alpha)
     pub release_type: String,
-    /// Required dependencies
+    /// Declared dependencies (required, optional, embedded, ...)
     #[serde(default)]
     pub dependencies: Vec<ContentDependency>,
 }
@@ -142,7 +180,7 @@ pub struct ContentVersion {
 pub struct ContentDependency {
     /// Project ID on the platform
     pub project_id: String,
-    /// Dependency type (required, optional)
+    /// Dependency type (required, optional, embedded, incompatible)
     pub dependency_type: String,
 }
 
@@ -207,7 +245,19 @@ impl ContentStore {
 
     /// Search only Modrinth
     pub fn search_modrinth(&self, options: &SearchOptions) -> Result<Vec<ContentItem>> {
-        let mut facets = SearchFacets::default();
+        self.search_modrinth_in_categories(options, &[])
+    }
+
+    /// Search Modrinth restricted to categories (e.g. `locale` resource packs)
+    pub fn search_modrinth_in_categories(
+        &self,
+        options: &SearchOptions,
+        categories: &[&str],
+    ) -> Result<Vec<ContentItem>> {
+        let mut facets = SearchFacets {
+            categories: categories.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        };
 
         if let Some(ct) = options.content_type {
             facets.project_type = Some(ct.to_modrinth_type());
@@ -244,6 +294,8 @@ impl ContentStore {
                 categories: hit.categories,
                 game_versions: hit.versions,
                 loaders: vec![],
+                license: None,
+                distribution_allowed: None,
             })
             .collect())
     }
@@ -253,6 +305,28 @@ impl ContentStore {
         self.curseforge.is_some()
     }
 
+    /// Game versions supported by the exact files with the given SHA-1
+    /// hashes, as published on Modrinth. Unknown files are left out.
+    pub fn game_versions_by_sha1(&self, hashes: &[&str]) -> Result<HashMap<String, Vec<String>>> {
+        Ok(self
+            .modrinth
+            .get_versions_by_sha1(hashes)?
+            .into_iter()
+            .map(|(hash, version)| (hash, version.game_versions))
+            .collect())
+    }
+
+    /// Modrinth project and version ids of the exact files with the given
+    /// SHA-1 hashes. Unknown files are left out.
+    pub fn modrinth_ids_by_sha1(&self, hashes: &[&str]) -> Result<HashMap<String, (String, String)>> {
+        Ok(self
+            .modrinth
+            .get_versions_by_sha1(hashes)?
+            .into_iter()
+            .map(|(hash, version)| (hash, (version.project_id, version.id)))
+            .collect())
+    }
+
     /// Search only CurseForge (public API)
     pub fn search_curseforge_only(&self, options: &SearchOptions) -> Result<Vec<ContentItem>> {
         let cf = self
@@ -312,6 +386,8 @@ impl ContentStore {
                         .map(|f| f.game_version.clone())
                         .collect(),
                     loaders: vec![],
+                    license: None,
+                    distribution_allowed: m.allow_mod_distribution,
                 }
             })
             .collect())
@@ -341,6 +417,12 @@ impl ContentStore {
                     categories: project.categories,
                     game_versions: project.game_versions,
                     loaders: project.loaders,
+                    license: project.license.map(|l| ContentLicense {
+                        id: l.id,
+                        name: l.name,
+                        url: l.url,
+                    }),
+                    distribution_allowed: None,
                 })
             }
             Platform::CurseForge => {
@@ -377,6 +459,8 @@ impl ContentStore {
                         .map(|f| f.game_version.clone())
                         .collect(),
                     loaders: vec![],
+                    license: None,
+                    distribution_allowed: m.allow_mod_distribution,
                 })
             }
         }
@@ -470,10 +554,11 @@ impl ContentStore {
                             dependencies: f
                                 .dependencies
                                 .into_iter()
-                                .filter(|d| d.relation_type == 3) // Required only
-                                .map(|d| ContentDependency {
-                                    project_id: d.mod_id.to_string(),
-                                    dependency_type: "required".to_string(),
+                                .filter_map(|d| {
+                                    Some(ContentDependency {
+                                        project_id: d.mod_id.to_string(),
+                                        dependency_type: curseforge_relation(d.relation_type)?.to_string(),
+                                    })
                                 })
                                 .collect(),
                         })
@@ -491,27 +576,34 @@ impl ContentStore {
         game_version: Option<&str>,
         loader: Option<&str>,
     ) -> Result<ContentVersion> {
+        self.find_latest_version(platform, id, game_version, loader)?
+            .context("no compatible versions found")
+    }
+
+    /// Like [`Self::get_latest_version`], but `None` when no version matches
+    pub fn find_latest_version(
+        &self,
+        platform: Platform,
+        id: &str,
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Result<Option<ContentVersion>> {
         let versions = self.get_versions(platform, id, game_version, loader)?;
 
         // Prefer release versions
-        let release = versions.iter().find(|v| v.release_type == "release");
-        if let Some(v) = release {
-            return Ok(v.clone());
-        }
-
-        versions
-            .into_iter()
-            .next()
-            .context("no compatible versions found")
+        let release = versions.iter().position(|v| v.release_type == "release").unwrap_or(0);
+        Ok(versions.into_iter().nth(release))
     }
 
-    /// Download content to the store and return a ContentRef
+    /// Download content to the store under `algorithm` and return a ContentRef
     pub fn download_to_store(
         &self,
         paths: &Paths,
         version: &ContentVersion,
         content_type: ContentType,
+        algorithm: HashAlgorithm,
     ) -> Result<crate::profile::ContentRef> {
+        check_project(paths, version.platform, &version.project_id, &version.filename)?;
         let (download_path, file_name) = store_from_url(paths, &version.download_url)?;
         let stored = crate::store::store_content(
             paths,
@@ -519,6 +611,7 @@ impl ContentStore {
             &download_path,
             Some(version.download_url.clone()),
             Some(file_name),
+            algorithm,
         )?;
 
         Ok(crate::profile::ContentRef {
@@ -536,6 +629,88 @@ impl ContentStore {
     }
 }
 
+/// Dependency type for a CurseForge relation; tools are not listed
+fn curseforge_relation(relation_type: u32) -> Option<&'static str> {
+    match relation_type {
+        1 | 6 => Some("embedded"),
+        2 => Some("optional"),
+        3 => Some("required"),
+        5 => Some("incompatible"),
+        _ => None,
+    }
+}
+
+/// What installing a version brings along, shown before it is installed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallDisclosure {
+    pub project: String,
+    pub version: String,
+    pub license: Option<ContentLicense>,
+    pub distribution_allowed: Option<bool>,
+    /// Project ids that must be installed alongside
+    pub required: Vec<String>,
+    /// Project ids bundled inside the file
+    pub embedded: Vec<String>,
+    /// Download size in bytes
+    pub size: u64,
+}
+
+impl InstallDisclosure {
+    pub fn new(item: &ContentItem, version: &ContentVersion) -> Self {
+        let ids_of = |kind: &str| {
+            version
+                .dependencies
+                .iter()
+                .filter(|d| d.dependency_type == kind)
+                .map(|d| d.project_id.clone())
+                .collect()
+        };
+        Self {
+            project: item.name.clone(),
+            version: version.version.clone(),
+            license: item.license.clone(),
+            distribution_allowed: item.distribution_allowed,
+            required: ids_of("required"),
+            embedded: ids_of("embedded"),
+            size: version.size,
+        }
+    }
+
+    /// Whether the license restricts reuse: unknown, custom or all rights
+    /// reserved
+    pub fn is_restrictive(&self) -> bool {
+        self.distribution_allowed == Some(false)
+            || self
+                .license
+                .as_ref()
+                .is_none_or(|l| l.id.starts_with("LicenseRef-"))
+    }
+
+    /// Human-readable lines for terminals and dialogs
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "license: {}",
+            self.license
+                .as_ref()
+                .map(|l| l.label().to_string())
+                .unwrap_or_else(|| "unknown (check the project page)".to_string())
+        )];
+        if let Some(url) = self.license.as_ref().and_then(|l| l.url.as_ref()) {
+            lines.push(format!("license text: {url}"));
+        }
+        if self.distribution_allowed == Some(false) {
+            lines.push("redistribution: not allowed by the author".to_string());
+        }
+        if !self.required.is_empty() {
+            lines.push(format!("requires: {}", self.required.join(", ")));
+        }
+        if !self.embedded.is_empty() {
+            lines.push(format!("embeds: {}", self.embedded.join(", ")));
+        }
+        lines
+    }
+}
+
 /// Convenience functions for direct Modrinth access
 pub mod modrinth_helpers {
     use super::*;
@@ -590,3 +765,64 @@ pub mod modrinth_helpers {
         store.get_latest_version(Platform::Modrinth, id_or_slug, game_version, loader)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_disclosure() {
+        let item = ContentItem {
+            id: "AANobbMI".to_string(),
+            slug: "sodium".to_string(),
+            name: "Sodium".to_string(),
+            description: String::new(),
+            body: None,
+            icon_url: None,
+            platform: Platform::Modrinth,
+            content_type: ContentType::Mod,
+            downloads: 0,
+            updated: String::new(),
+            categories: Vec::new(),
+            game_versions: Vec::new(),
+            loaders: Vec::new(),
+            license: Some(ContentLicense {
+                id: "LicenseRef-Polyform-Shield-1.0.0".to_string(),
+                name: String::new(),
+                url: Some("https://polyformproject.org/licenses/shield/1.0.0".to_string()),
+            }),
+            distribution_allowed: None,
+        };
+        let dependency = |project_id: &str, dependency_type: &str| ContentDependency {
+            project_id: project_id.to_string(),
+            dependency_type: dependency_type.to_string(),
+        };
+        let version = ContentVersion {
+            id: "v1".to_string(),
+            project_id: item.id.clone(),
+            name: "Sodium 0.5.8".to_string(),
+            version: "0.5.8".to_string(),
+            download_url: String::new(),
+            filename: "sodium.jar".to_string(),
+            size: 0,
+            sha256: None,
+            sha1: None,
+            platform: Platform::Modrinth,
+            game_versions: Vec::new(),
+            loaders: Vec::new(),
+            release_type: "release".to_string(),
+            dependencies: vec![
+                dependency("P7dR8mSH", "required"),
+                dependency("iris", "optional"),
+                dependency("lwjgl", "embedded"),
+            ],
+        };
+
+        let disclosure = InstallDisclosure::new(&item, &version);
+        assert_eq!(disclosure.required, vec!["P7dR8mSH"]);
+        assert_eq!(disclosure.embedded, vec!["lwjgl"]);
+        assert!(disclosure.is_restrictive());
+        assert_eq!(disclosure.lines()[0], "license: LicenseRef-Polyform-Shield-1.0.0");
+        assert_eq!(curseforge_relation(4), None);
+    }
+}
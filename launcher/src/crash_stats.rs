@@ -0,0 +1,561 @@
+//! Opt-in crash signature aggregation
+//!
+//! When enabled in config, crash reports written by a profile are reduced to an
+//! anonymized signature (exception class, top stack frames without line numbers
+//! or messages, suspected mod) and counted in a local SQLite database. Each
+//! occurrence also records the most recent content update in the profile, so
+//! repeated crashes can be traced back to the update that preceded them.
+
+use crate::config::load_config;
+use crate::disk::DiskError;
+use crate::library::Library;
+use crate::logs::list_crash_reports;
+use crate::paths::Paths;
+use crate::sql_profile::profile_connection;
+use crate::profile::load_profile;
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Number of stack frames kept in a signature
+const SIGNATURE_FRAMES: usize = 5;
+
+/// Package prefixes that belong to the game, loaders or the JVM rather than mods
+const PLATFORM_PACKAGES: [&str; 14] = [
+    "java.",
+    "javax.",
+    "jdk.",
+    "sun.",
+    "com.sun.",
+    "net.minecraft.",
+    "com.mojang.",
+    "net.fabricmc.",
+    "org.quiltmc.",
+    "net.minecraftforge.",
+    "net.neoforged.",
+    "cpw.mods.",
+    "org.spongepowered.",
+    "org.lwjgl.",
+];
+
+impl Paths {
+    /// Database of aggregated crash signatures
+    pub fn crash_stats_db(&self) -> PathBuf {
+        self.state_root.join("crash-stats.db")
+    }
+}
+
+/// Anonymized identity of a crash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrashSignature {
+    /// SHA-256 over exception class and frames
+    pub signature: String,
+    pub exception: String,
+    pub frames: Vec<String>,
+    pub suspected_mod: Option<String>,
+}
+
+/// Aggregated counts for one signature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashSignatureStats {
+    pub signature: String,
+    pub exception: String,
+    pub frames: Vec<String>,
+    pub suspected_mod: Option<String>,
+    pub count: u64,
+    pub first_seen: u64,
+    pub last_seen: u64,
+    /// Occurrence counts grouped by the content update that preceded them
+    pub after_updates: Vec<CrashUpdateCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashUpdateCount {
+    pub content: String,
+    pub count: u64,
+}
+
+/// Extract an anonymized signature from a crash report's text
+pub fn parse_crash_signature(report: &str) -> Option<CrashSignature> {
+    let lines: Vec<&str> = report.lines().collect();
+
+    // The exception line is the first non-empty line after "Description:"
+    let description = lines
+        .iter()
+        .position(|line| line.starts_with("Description:"))?;
+    let exception_idx = (description + 1..lines.len()).find(|&i| !lines[i].trim().is_empty())?;
+    let exception_line = lines[exception_idx].trim();
+    // Drop the message; it often contains paths, usernames or coordinates
+    let exception = exception_line
+        .split(':')
+        .next()
+        .unwrap_or(exception_line)
+        .trim()
+        .to_string();
+    if exception.is_empty() || exception.contains(' ') {
+        return None;
+    }
+
+    let frames: Vec<String> = lines[exception_idx + 1..]
+        .iter()
+        .map(|line| line.trim())
+        .take_while(|line| line.starts_with("at "))
+        .map(|line| strip_frame_location(&line["at ".len()..]))
+        .take(SIGNATURE_FRAMES)
+        .collect();
+
+    let suspected_mod = lines
+        .iter()
+        .find_map(|line| {
+            let line = line.trim();
+            line.strip_prefix("Suspected Mods:")
+                .or_else(|| line.strip_prefix("Suspected Mod:"))
+                .map(str::trim)
+        })
+        .filter(|value| !value.is_empty() && !value.eq_ignore_ascii_case("none") && !value.eq_ignore_ascii_case("unknown"))
+        .map(str::to_string)
+        .or_else(|| frames.iter().find_map(|frame| guess_mod_package(frame)));
+
+    let mut hasher = Sha256::new();
+    hasher.update(exception.as_bytes());
+    for frame in &frames {
+        hasher.update(b"\n");
+        hasher.update(frame.as_bytes());
+    }
+    let signature = hex::encode(hasher.finalize());
+
+    Some(CrashSignature {
+        signature,
+        exception,
+        frames,
+        suspected_mod,
+    })
+}
+
+/// Extract a signature from a JVM fatal error log (`hs_err_pid*.log`): the
+/// signal/exception name plus the problematic and top native frames, with
+/// addresses and offsets removed
+pub fn parse_jvm_crash_signature(log: &str) -> Option<CrashSignature> {
+    let exception = log.lines().find_map(|line| {
+        let line = line.trim_start_matches('#').trim();
+        line.contains(" at pc=")
+            .then(|| line.split_whitespace().next())
+            .flatten()
+            .map(str::to_string)
+    })?;
+
+    let mut frames = Vec::new();
+    let mut lines = log.lines();
+    while let Some(line) = lines.next() {
+        if line.trim_start_matches('#').trim() == "Problematic frame:" {
+            if let Some(frame) = lines.next() {
+                frames.push(strip_native_offsets(frame.trim_start_matches('#').trim()));
+            }
+        } else if line.starts_with("Native frames:") {
+            frames.extend(
+                lines
+                    .by_ref()
+                    .map(str::trim)
+                    .take_while(|l| !l.is_empty())
+                    .map(strip_native_offsets),
+            );
+            break;
+        }
+    }
+    frames.dedup();
+    frames.truncate(SIGNATURE_FRAMES);
+
+    let mut hasher = Sha256::new();
+    hasher.update(exception.as_bytes());
+    for frame in &frames {
+        hasher.update(b"\n");
+        hasher.update(frame.as_bytes());
+    }
+
+    Some(CrashSignature {
+        signature: hex::encode(hasher.finalize()),
+        exception,
+        frames,
+        suspected_mod: None,
+    })
+}
+
+/// "C  [libfoo.so+0x1a2b]  bar+0x10" -> "C [libfoo.so] bar"
+fn strip_native_offsets(frame: &str) -> String {
+    frame
+        .split_whitespace()
+        .map(|token| match token.find('+') {
+            Some(idx) => {
+                let closing = if token.ends_with(']') { "]" } else { "" };
+                format!("{}{}", &token[..idx], closing)
+            }
+            None => token.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// "a.b.C.m(C.java:12) ~[mod.jar:?]" -> "a.b.C.m"
+fn strip_frame_location(frame: &str) -> String {
+    let method = frame.split('(').next().unwrap_or(frame).trim();
+    // Forge/Mixin may prefix the module: "TRANSFORMER/minecraft@1.20.1/net.minecraft..."
+    method.rsplit('/').next().unwrap_or(method).to_string()
+}
+
+/// Package of the first non-platform frame, e.g. "com.example.mymod"
+fn guess_mod_package(frame: &str) -> Option<String> {
+    if PLATFORM_PACKAGES.iter().any(|prefix| frame.starts_with(prefix)) {
+        return None;
+    }
+    let parts: Vec<&str> = frame.split('.').collect();
+    if parts.len() < 3 {
+        return None;
+    }
+    Some(parts[..parts.len().min(5) - 2].join("."))
+}
+
+/// Local crash signature database
+pub struct CrashStats {
+    conn: Connection,
+    /// Database path when opened read-only for inspect mode
+    read_only: Option<PathBuf>,
+}
+
+impl CrashStats {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("failed to open crash stats database: {}", path.display()))?;
+        Self::init(conn, None)
+    }
+
+    /// Open an existing database without changing it, for inspect mode; a
+    /// database that doesn't exist yet opens empty
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        let read_only = Some(path.to_path_buf());
+        if !path.exists() {
+            let conn = Connection::open_in_memory().context("failed to open in-memory crash stats database")?;
+            return Self::init(conn, read_only);
+        }
+        let mut conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+            .with_context(|| format!("failed to open crash stats database: {}", path.display()))?;
+        profile_connection(&mut conn);
+        Ok(Self { conn, read_only })
+    }
+
+    fn init(mut conn: Connection, read_only: Option<PathBuf>) -> Result<Self> {
+        profile_connection(&mut conn);
+        conn.execute("PRAGMA foreign_keys = ON", [])
+            .context("failed to enable foreign key constraints")?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS crash_signatures (
+                signature TEXT PRIMARY KEY,
+                exception TEXT NOT NULL,
+                frames TEXT NOT NULL,
+                suspected_mod TEXT
+            );
+
+            CREATE TABLE IF NOT EXISTS crash_occurrences (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                signature TEXT NOT NULL REFERENCES crash_signatures(signature) ON DELETE CASCADE,
+                profile_id TEXT NOT NULL,
+                report_name TEXT NOT NULL,
+                occurred_at INTEGER NOT NULL,
+                recent_update TEXT,
+                UNIQUE(profile_id, report_name)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_crash_occurrences_signature ON crash_occurrences(signature);
+            "#,
+        )
+        .context("failed to initialize crash stats schema")?;
+        Ok(Self { conn, read_only })
+    }
+
+    /// Open the database, read-only in inspect mode
+    pub fn from_paths(paths: &Paths) -> Result<Self> {
+        if paths.read_only {
+            return Self::open_read_only(&paths.crash_stats_db());
+        }
+        Self::open(&paths.crash_stats_db())
+    }
+
+    fn ensure_writable(&self) -> Result<()> {
+        match &self.read_only {
+            Some(path) => Err(DiskError::inspect(path).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether a report has already been counted
+    pub fn is_recorded(&self, profile_id: &str, report_name: &str) -> Result<bool> {
+        let found = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM crash_occurrences WHERE profile_id = ?1 AND report_name = ?2",
+                params![profile_id, report_name],
+                |_| Ok(()),
+            )
+            .optional()
+            .context("failed to query crash occurrences")?;
+        Ok(found.is_some())
+    }
+
+    /// Count one occurrence of a signature; returns false if the report was already counted
+    pub fn record(
+        &self,
+        signature: &CrashSignature,
+        profile_id: &str,
+        report_name: &str,
+        occurred_at: u64,
+        recent_update: Option<&str>,
+    ) -> Result<bool> {
+        self.ensure_writable()?;
+        self.conn
+            .execute(
+                r#"
+                INSERT INTO crash_signatures (signature, exception, frames, suspected_mod)
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT(signature) DO UPDATE SET
+                    suspected_mod = COALESCE(crash_signatures.suspected_mod, excluded.suspected_mod)
+                "#,
+                params![
+                    signature.signature,
+                    signature.exception,
+                    signature.frames.join("\n"),
+                    signature.suspected_mod,
+                ],
+            )
+            .context("failed to record crash signature")?;
+
+        let inserted = self
+            .conn
+            .execute(
+                r#"
+                INSERT OR IGNORE INTO crash_occurrences
+                    (signature, profile_id, report_name, occurred_at, recent_update)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+                params![
+                    signature.signature,
+                    profile_id,
+                    report_name,
+                    occurred_at as i64,
+                    recent_update,
+                ],
+            )
+            .context("failed to record crash occurrence")?;
+        Ok(inserted > 0)
+    }
+
+    /// All signatures with counts, most frequent first
+    pub fn list(&self) -> Result<Vec<CrashSignatureStats>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT s.signature, s.exception, s.frames, s.suspected_mod,
+                   COUNT(o.id), MIN(o.occurred_at), MAX(o.occurred_at)
+            FROM crash_signatures s
+            JOIN crash_occurrences o ON o.signature = s.signature
+            GROUP BY s.signature
+            ORDER BY COUNT(o.id) DESC, MAX(o.occurred_at) DESC
+            "#,
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let frames: String = row.get(2)?;
+            Ok(CrashSignatureStats {
+                signature: row.get(0)?,
+                exception: row.get(1)?,
+                frames: frames.lines().map(str::to_string).collect(),
+                suspected_mod: row.get(3)?,
+                count: row.get::<_, i64>(4)? as u64,
+                first_seen: row.get::<_, i64>(5)? as u64,
+                last_seen: row.get::<_, i64>(6)? as u64,
+                after_updates: Vec::new(),
+            })
+        })?;
+        let mut stats = rows
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("failed to list crash signatures")?;
+
+        let mut update_stmt = self.conn.prepare(
+            r#"
+            SELECT recent_update, COUNT(*) FROM crash_occurrences
+            WHERE signature = ?1 AND recent_update IS NOT NULL
+            GROUP BY recent_update
+            ORDER BY COUNT(*) DESC
+            "#,
+        )?;
+        for entry in &mut stats {
+            entry.after_updates = update_stmt
+                .query_map(params![entry.signature], |row| {
+                    Ok(CrashUpdateCount {
+                        content: row.get(0)?,
+                        count: row.get::<_, i64>(1)? as u64,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .context("failed to count crash updates")?;
+        }
+
+        Ok(stats)
+    }
+
+    /// Delete all collected data
+    pub fn clear(&self) -> Result<()> {
+        self.ensure_writable()?;
+        self.conn
+            .execute_batch("DELETE FROM crash_occurrences; DELETE FROM crash_signatures;")
+            .context("failed to clear crash stats")?;
+        Ok(())
+    }
+}
+
+/// Record new crash reports of a profile if aggregation is enabled.
+/// Returns the number of newly counted crashes.
+pub fn collect_profile_crashes(paths: &Paths, profile_id: &str) -> Result<usize> {
+    if !load_config(paths)?.crash_aggregation_enabled {
+        return Ok(0);
+    }
+
+    let reports = list_crash_reports(paths, profile_id)?;
+    if reports.is_empty() {
+        return Ok(0);
+    }
+
+    let stats = CrashStats::from_paths(paths)?;
+    let content_hashes: Vec<String> = load_profile(paths, profile_id)
+        .map(|profile| {
+            profile
+                .mods
+                .iter()
+                .chain(&profile.resourcepacks)
+                .chain(&profile.shaderpacks)
+                .map(|content| content.hash.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    let library = Library::from_paths(paths).ok();
+
+    let mut recorded = 0;
+    for report in reports {
+        if stats.is_recorded(profile_id, &report.name)? {
+            continue;
+        }
+        let Ok(text) = fs::read_to_string(&report.path) else {
+            continue;
+        };
+        let parsed = if report.jvm_crash {
+            parse_jvm_crash_signature(&text)
+        } else {
+            parse_crash_signature(&text)
+        };
+        let Some(signature) = parsed else {
+            continue;
+        };
+        let occurred_at = fs::metadata(&report.path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(report.modified);
+        let recent_update = library
+            .as_ref()
+            .and_then(|lib| lib.latest_replacement_among(&content_hashes, occurred_at).ok())
+            .flatten();
+
+        if stats.record(
+            &signature,
+            profile_id,
+            &report.name,
+            occurred_at,
+            recent_update.as_deref(),
+        )? {
+            recorded += 1;
+        }
+    }
+
+    Ok(recorded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::TempDir;
+
+    const REPORT: &str = "---- Minecraft Crash Report ----
+// Don't be sad, have a hug! <3
+
+Time: 2024-05-01 12:00:00
+Description: Ticking entity
+
+java.lang.NullPointerException: Cannot invoke \"Object.toString()\" because \"/home/alice\" is null
+\tat com.example.coolmod.entity.CoolEntity.tick(CoolEntity.java:42) ~[coolmod-1.2.jar%2399!/:?]
+\tat net.minecraft.world.level.Level.guardEntityTick(Level.java:500) ~[client-1.20.1.jar:?]
+\tat net.minecraft.client.multiplayer.ClientLevel.tickEntities(ClientLevel.java:300) ~[client-1.20.1.jar:?]
+
+A detailed walkthrough of the error, its code path and all known details is as follows:
+";
+
+    #[test]
+    fn test_parse_crash_signature() {
+        let sig = parse_crash_signature(REPORT).unwrap();
+        assert_eq!(sig.exception, "java.lang.NullPointerException");
+        assert_eq!(sig.frames.len(), 3);
+        assert_eq!(sig.frames[0], "com.example.coolmod.entity.CoolEntity.tick");
+        assert_eq!(sig.suspected_mod.as_deref(), Some("com.example.coolmod"));
+        assert!(!sig.frames.iter().any(|f| f.contains("alice")));
+
+        // Same crash with a different message and line numbers has the same signature
+        let other = REPORT.replace("/home/alice", "/home/bob").replace(":42)", ":43)");
+        assert_eq!(parse_crash_signature(&other).unwrap().signature, sig.signature);
+    }
+
+    #[test]
+    fn test_parse_jvm_crash_signature() {
+        let log = "#
+# A fatal error has been detected by the Java Runtime Environment:
+#
+#  SIGSEGV (0xb) at pc=0x00007f3a1c2b3c4d, pid=4242, tid=4243
+#
+# Problematic frame:
+# C  [libnvidia-glcore.so.535.54.03+0xe1b2c0]
+#
+
+Native frames: (J=compiled Java code, j=interpreted, Vv=VM code, C=native code)
+C  [libnvidia-glcore.so.535.54.03+0xe1b2c0]
+C  [liblwjgl_opengl.so+0x1a2b]  Java_org_lwjgl_opengl_GL11C_glDrawElements+0x20
+
+";
+        let sig = parse_jvm_crash_signature(log).unwrap();
+        assert_eq!(sig.exception, "SIGSEGV");
+        assert_eq!(
+            sig.frames,
+            vec![
+                "C [libnvidia-glcore.so.535.54.03]",
+                "C [liblwjgl_opengl.so] Java_org_lwjgl_opengl_GL11C_glDrawElements",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_crash_stats_counts() {
+        let dir = TempDir::new("crash-stats");
+        let stats = CrashStats::open(&dir.join("crash-stats.db")).unwrap();
+        let sig = parse_crash_signature(REPORT).unwrap();
+
+        assert!(stats.record(&sig, "p", "crash-1.txt", 100, Some("Cool Mod")).unwrap());
+        assert!(stats.record(&sig, "p", "crash-2.txt", 200, Some("Cool Mod")).unwrap());
+        assert!(!stats.record(&sig, "p", "crash-2.txt", 200, Some("Cool Mod")).unwrap());
+
+        let list = stats.list().unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].count, 2);
+        assert_eq!((list[0].first_seen, list[0].last_seen), (100, 200));
+        assert_eq!(list[0].after_updates[0].content, "Cool Mod");
+        assert_eq!(list[0].after_updates[0].count, 2);
+    }
+}
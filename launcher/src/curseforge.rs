@@ -1,5 +1,8 @@
+use crate::api_keys::{CURSEFORGE, record_response};
+use crate::net::RetryExt;
 use anyhow::{Context, Result, bail};
-use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use reqwest::blocking::{Client, Response};
 use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
 use serde::{Deserialize, Serialize};
 
@@ -259,6 +262,7 @@ pub enum SearchSortField {
 /// CurseForge API client
 pub struct CurseForgeClient {
     client: Client,
+    api_key: String,
 }
 
 impl CurseForgeClient {
@@ -275,7 +279,29 @@ impl CurseForgeClient {
             .build()
             .expect("failed to build HTTP client");
 
-        Self { client }
+        Self {
+            client,
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// Record the response against the key's health and fail with a clear
+    /// message when CurseForge rejects the key
+    fn check_key(&self, resp: Response) -> Result<Response> {
+        record_response(CURSEFORGE, &self.api_key, &resp);
+        if matches!(resp.status(), StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) {
+            bail!(
+                "CurseForge rejected the API key ({}); set a valid one with `shard config set-curseforge-key`",
+                resp.status()
+            );
+        }
+        Ok(resp)
+    }
+
+    /// Cheapest authenticated request, used to check the key
+    pub(crate) fn ping(&self) -> reqwest::Result<Response> {
+        let url = format!("{}/games/{}", API_BASE, MINECRAFT_GAME_ID);
+        self.client.get(&url).send_with_retry()
     }
 
     /// Search for mods
@@ -314,8 +340,10 @@ impl CurseForgeClient {
         let resp = self
             .client
             .get(&url)
-            .send()
-            .context("failed to search CurseForge")?
+            .send_with_retry()
+            .context("failed to search CurseForge")?;
+        let resp = self
+            .check_key(resp)?
             .error_for_status()
             .context("CurseForge search failed")?;
 
@@ -329,8 +357,9 @@ impl CurseForgeClient {
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_with_retry()
             .context("failed to fetch mod")?;
+        let resp = self.check_key(resp)?;
 
         if resp.status() == reqwest::StatusCode::NOT_FOUND {
             bail!("mod not found: {}", mod_id);
@@ -365,8 +394,10 @@ impl CurseForgeClient {
             .json(&GetModsBody {
                 mod_ids: mod_ids.to_vec(),
             })
-            .send()
-            .context("failed to fetch mods")?
+            .send_with_retry()
+            .context("failed to fetch mods")?;
+        let resp = self
+            .check_key(resp)?
             .error_for_status()
             .context("CurseForge request failed")?;
 
@@ -403,8 +434,10 @@ impl CurseForgeClient {
         let resp = self
             .client
             .get(&url)
-            .send()
-            .context("failed to fetch mod files")?
+            .send_with_retry()
+            .context("failed to fetch mod files")?;
+        let resp = self
+            .check_key(resp)?
             .error_for_status()
             .context("CurseForge request failed")?;
 
@@ -418,8 +451,10 @@ impl CurseForgeClient {
         let resp = self
             .client
             .get(&url)
-            .send()
-            .context("failed to fetch file")?
+            .send_with_retry()
+            .context("failed to fetch file")?;
+        let resp = self
+            .check_key(resp)?
             .error_for_status()
             .context("CurseForge request failed")?;
 
@@ -453,7 +488,7 @@ impl CurseForgeClient {
         let resp = self
             .client
             .get(url)
-            .send()
+            .send_with_retry()
             .context("failed to download file")?
             .error_for_status()
             .context("download failed")?;
@@ -472,8 +507,10 @@ impl CurseForgeClient {
         let resp = self
             .client
             .get(&url)
-            .send()
-            .context("failed to fetch categories")?
+            .send_with_retry()
+            .context("failed to fetch categories")?;
+        let resp = self
+            .check_key(resp)?
             .error_for_status()
             .context("CurseForge request failed")?;
 
@@ -493,8 +530,10 @@ impl CurseForgeClient {
         let resp = self
             .client
             .get(&url)
-            .send()
-            .context("failed to fetch game versions")?
+            .send_with_retry()
+            .context("failed to fetch game versions")?;
+        let resp = self
+            .check_key(resp)?
             .error_for_status()
             .context("CurseForge request failed")?;
 
@@ -0,0 +1,156 @@
+//! Detection of full and read-only disks
+//!
+//! Writes to the store, profiles and caches go through [`WriteContext`], which
+//! turns out-of-space and read-only filesystem failures into a [`DiskError`]
+//! naming the path and what to do about it, instead of a generic
+//! "failed to write" context. The same error reports changes refused
+//! because the data dir was opened read-only on purpose (inspect mode, see
+//! [`Paths::ensure_writable`](crate::paths::Paths::ensure_writable)).
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiskErrorKind {
+    Full,
+    ReadOnly,
+    /// The data dir was opened read-only with `SHARD_READ_ONLY` or the
+    /// config's `read_only` flag
+    Inspect,
+}
+
+impl DiskErrorKind {
+    pub fn from_io(err: &io::Error) -> Option<Self> {
+        match err.kind() {
+            io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded => Some(Self::Full),
+            io::ErrorKind::ReadOnlyFilesystem => Some(Self::ReadOnly),
+            // ERROR_WRITE_PROTECT
+            _ if cfg!(windows) && err.raw_os_error() == Some(19) => Some(Self::ReadOnly),
+            _ => None,
+        }
+    }
+}
+
+/// A write failed because the disk is full or mounted read-only, or was
+/// refused in inspect mode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiskError {
+    pub kind: DiskErrorKind,
+    /// Path being written, when known
+    pub path: Option<PathBuf>,
+}
+
+impl DiskError {
+    /// A change to `path` refused in inspect mode
+    pub fn inspect(path: &Path) -> Self {
+        Self {
+            kind: DiskErrorKind::Inspect,
+            path: Some(path.to_path_buf()),
+        }
+    }
+}
+
+impl fmt::Display for DiskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let target = self
+            .path
+            .as_ref()
+            .map(|p| format!(" ({})", p.display()))
+            .unwrap_or_default();
+        match self.kind {
+            DiskErrorKind::Full => write!(
+                f,
+                "disk is full{target}; free up space and try again"
+            ),
+            DiskErrorKind::ReadOnly => write!(
+                f,
+                "disk is read-only{target}; remount it writable or point SHARD_HOME at a writable directory"
+            ),
+            DiskErrorKind::Inspect => write!(
+                f,
+                "data directory is open read-only{target}; unset SHARD_READ_ONLY or the read_only config flag to make changes"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DiskError {}
+
+/// Like `anyhow::Context::with_context` for I/O writes, but reports full and
+/// read-only disks as a [`DiskError`] for `path`
+pub trait WriteContext<T> {
+    fn write_context<C, F>(self, path: &Path, context: F) -> Result<T>
+    where
+        C: fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> C;
+}
+
+impl<T> WriteContext<T> for io::Result<T> {
+    fn write_context<C, F>(self, path: &Path, context: F) -> Result<T>
+    where
+        C: fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> C,
+    {
+        self.map_err(|err| match DiskErrorKind::from_io(&err) {
+            Some(kind) => anyhow::Error::new(err).context(DiskError {
+                kind,
+                path: Some(path.to_path_buf()),
+            }),
+            None => anyhow::Error::new(err).context(context()),
+        })
+    }
+}
+
+/// Find a full or read-only disk anywhere in an error chain
+pub fn find_disk_error(err: &anyhow::Error) -> Option<DiskError> {
+    if let Some(disk) = err.downcast_ref::<DiskError>() {
+        return Some(disk.clone());
+    }
+    err.chain().find_map(|cause| {
+        let kind = DiskErrorKind::from_io(cause.downcast_ref::<io::Error>()?)?;
+        Some(DiskError { kind, path: None })
+    })
+}
+
+/// Message to show users: the disk problem when there is one, otherwise the
+/// error itself
+pub fn user_message(err: &anyhow::Error) -> String {
+    match find_disk_error(err) {
+        Some(disk) => disk.to_string(),
+        None => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_disk_error_found_under_context() {
+        let full: io::Result<()> = Err(io::ErrorKind::StorageFull.into());
+        let err = full
+            .write_context(Path::new("/data/profile.json"), || "failed to write profile")
+            .context("failed to save profile")
+            .unwrap_err();
+        let disk = find_disk_error(&err).unwrap();
+        assert_eq!(disk.kind, DiskErrorKind::Full);
+        assert_eq!(disk.path.as_deref(), Some(Path::new("/data/profile.json")));
+        assert!(user_message(&err).starts_with("disk is full"));
+
+        let raw: Result<()> = Err(io::Error::from(io::ErrorKind::ReadOnlyFilesystem))
+            .context("failed to create dir");
+        let disk = find_disk_error(&raw.unwrap_err()).unwrap();
+        assert_eq!(disk.kind, DiskErrorKind::ReadOnly);
+        assert_eq!(disk.path, None);
+
+        let other: io::Result<()> = Err(io::ErrorKind::NotFound.into());
+        let err = other.write_context(Path::new("x"), || "failed to write x").unwrap_err();
+        assert!(find_disk_error(&err).is_none());
+        assert_eq!(user_message(&err), "failed to write x");
+    }
+}
@@ -0,0 +1,376 @@
+//! Build a profile from an existing `.minecraft` folder
+//!
+//! For players moving over from a manual install: the game version and mod
+//! loader are detected from the launcher files (`launcher_profiles.json`,
+//! `versions/<id>/<id>.json`) or, failing that, from the `version.json`
+//! inside a client jar. Mods, resource packs and shader packs are imported
+//! into the content store; settings and worlds are copied when asked.
+
+use crate::disk::WriteContext;
+use crate::minecraft::read_jar_entry;
+use crate::paths::Paths;
+use crate::profile::{
+    ContentRef, Loader, Profile, Runtime, create_profile, save_profile, upsert_mod, upsert_resourcepack, upsert_shaderpack,
+};
+use crate::store::{ContentKind, configured_store_hash, store_content};
+use crate::util::{copy_dir_all, slugify};
+use crate::vanilla_launcher::{parse_vanilla_version_id, read_vanilla_profiles};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Game settings copied with `copy_options`, into the profile overrides
+const OPTION_FILES: &[&str] = &["options.txt", "optionsof.txt", "optionsshaders.txt", "servers.dat"];
+
+#[derive(Debug, Clone, Default)]
+pub struct DotMinecraftOptions {
+    /// Profile id (defaults to the folder name)
+    pub profile_id: Option<String>,
+    /// Version id to use instead of detecting one, e.g. `1.20.1` or
+    /// `fabric-loader-0.15.7-1.20.1`
+    pub version: Option<String>,
+    /// Copy `options.txt`, the server list and mod configs (`config/`)
+    pub copy_options: bool,
+    /// Copy worlds from `saves/`
+    pub copy_saves: bool,
+}
+
+/// Where the game version was found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionSource {
+    /// Given by the user
+    Explicit,
+    /// The most recently used profile in `launcher_profiles.json`
+    LauncherProfile,
+    /// The most recently installed version in `versions/`
+    VersionsDir,
+    /// `version.json` inside a client jar
+    ClientJar,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DotMinecraftImport {
+    pub profile_id: String,
+    /// Version id the game was detected as
+    pub version_id: String,
+    pub mc_version: String,
+    pub loader: Option<Loader>,
+    pub version_source: VersionSource,
+    pub mods: usize,
+    pub resourcepacks: usize,
+    pub shaderpacks: usize,
+    pub worlds: usize,
+    /// Files that couldn't be imported, with the reason
+    pub skipped: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct VersionJson {
+    id: String,
+    #[serde(default, rename = "inheritsFrom")]
+    inherits_from: Option<String>,
+    #[serde(default)]
+    libraries: Vec<VersionLibrary>,
+}
+
+#[derive(Deserialize)]
+struct VersionLibrary {
+    name: String,
+}
+
+/// `version.json` bundled in client jars since 18w47b
+#[derive(Deserialize)]
+struct JarVersion {
+    id: String,
+}
+
+/// Installed versions in `versions/`, most recently modified first
+fn installed_versions(minecraft_dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(minecraft_dir.join("versions")) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<(SystemTime, String, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            let json = entry.path().join(format!("{id}.json"));
+            let modified = json.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, id, json))
+        })
+        .collect();
+    versions.sort_by_key(|(modified, _, _)| std::cmp::Reverse(*modified));
+    versions.into_iter().map(|(_, id, json)| (id, json)).collect()
+}
+
+/// Minecraft version and loader from a version JSON. Installers name their
+/// versions freely, so the loader is read from the libraries when the id
+/// doesn't follow a known pattern.
+fn inspect_version_json(json_path: &Path) -> Result<(String, Option<Loader>)> {
+    let data = fs::read_to_string(json_path).with_context(|| format!("failed to read {}", json_path.display()))?;
+    let version: VersionJson =
+        serde_json::from_str(&data).with_context(|| format!("failed to parse {}", json_path.display()))?;
+    let (parsed_mc, loader) = parse_vanilla_version_id(&version.id);
+    let mc_version = version.inherits_from.clone().unwrap_or(parsed_mc);
+    let loader = loader.or_else(|| {
+        version
+            .libraries
+            .iter()
+            .find_map(|library| loader_from_library(&library.name, &mc_version))
+    });
+    Ok((mc_version, loader))
+}
+
+/// Loader named by a library coordinate, e.g. `net.fabricmc:fabric-loader:0.15.7`
+fn loader_from_library(name: &str, mc_version: &str) -> Option<Loader> {
+    let mut parts = name.split(':');
+    let (group, artifact, version) = (parts.next()?, parts.next()?, parts.next()?);
+    let loader_type = match (group, artifact) {
+        ("net.fabricmc", "fabric-loader") => "fabric",
+        ("org.quiltmc", "quilt-loader") => "quilt",
+        ("net.neoforged", "neoforge") => "neoforge",
+        ("net.minecraftforge", "forge" | "fmlloader") => "forge",
+        _ => return None,
+    };
+    // Forge coordinates carry the Minecraft version: 1.20.1-47.2.0
+    let version = version
+        .strip_prefix(&format!("{mc_version}-"))
+        .unwrap_or(version);
+    Some(Loader {
+        loader_type: loader_type.to_string(),
+        version: version.to_string(),
+    })
+}
+
+fn version_from_jar(jar: &Path) -> Option<String> {
+    let data = read_jar_entry(jar, "version.json").ok()?;
+    serde_json::from_str::<JarVersion>(&data).ok().map(|v| v.id)
+}
+
+/// Game version of a `.minecraft` folder: the version id, Minecraft version,
+/// loader and where they were found
+fn detect_version(
+    minecraft_dir: &Path,
+    explicit: Option<&str>,
+) -> Result<(String, String, Option<Loader>, VersionSource)> {
+    let installed = installed_versions(minecraft_dir);
+    let resolve = |id: &str, source: VersionSource| {
+        let (mc_version, loader) = match installed.iter().find(|(installed_id, _)| installed_id == id) {
+            Some((_, json)) => inspect_version_json(json)?,
+            None => parse_vanilla_version_id(id),
+        };
+        Ok::<_, anyhow::Error>((id.to_string(), mc_version, loader, source))
+    };
+
+    if let Some(id) = explicit {
+        return resolve(id, VersionSource::Explicit);
+    }
+
+    // The launcher stores lastUsed as an ISO timestamp, which sorts as text
+    let mut profiles = read_vanilla_profiles(minecraft_dir).unwrap_or_default();
+    profiles.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    let last_used = profiles.iter().find_map(|profile| {
+        profile
+            .version_id
+            .as_deref()
+            .filter(|id| installed.iter().any(|(installed_id, _)| installed_id == id))
+    });
+    if let Some(id) = last_used {
+        return resolve(id, VersionSource::LauncherProfile);
+    }
+    if let Some((id, _)) = installed.first() {
+        return resolve(id, VersionSource::VersionsDir);
+    }
+
+    // Manual installs without launcher files: a jar in versions/ or bin/
+    let jars = fs::read_dir(minecraft_dir.join("versions"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            Some(entry.path().join(format!("{id}.jar"))).filter(|jar| jar.is_file())
+        })
+        .chain(Some(minecraft_dir.join("bin").join("minecraft.jar")).filter(|jar| jar.is_file()));
+    for jar in jars {
+        if let Some(id) = version_from_jar(&jar) {
+            return resolve(&id, VersionSource::ClientJar);
+        }
+    }
+
+    bail!(
+        "could not detect the Minecraft version in {} (pass one with --version)",
+        minecraft_dir.display()
+    )
+}
+
+/// Store every pack file in `dir`. Unpacked folders can't go into the store,
+/// so they are copied into the profile overrides instead.
+fn import_content_dir(
+    paths: &Paths,
+    profile: &mut Profile,
+    dir: &Path,
+    kind: ContentKind,
+    skipped: &mut Vec<String>,
+) -> Result<usize> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(0);
+    };
+    let folder = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let algorithm = configured_store_hash(paths);
+    let mut imported = 0;
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if !matches!(kind, ContentKind::Mod) {
+                let target = paths.profile_overrides(&profile.id).join(&folder).join(&file_name);
+                copy_dir_all(&path, &target)?;
+                imported += 1;
+            }
+            continue;
+        }
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+        let wanted = match kind {
+            ContentKind::Mod => extension == "jar",
+            _ => extension == "zip",
+        };
+        if !wanted {
+            continue;
+        }
+        let stored = match store_content(paths, kind, &path, None, None, algorithm) {
+            Ok(stored) => stored,
+            Err(err) => {
+                skipped.push(format!("{folder}/{file_name}: {err}"));
+                continue;
+            }
+        };
+        let content_ref = ContentRef {
+            name: stored.name,
+            hash: stored.hash,
+            version: None,
+            source: stored.source,
+            file_name: Some(stored.file_name),
+            platform: None,
+            project_id: None,
+            version_id: None,
+            enabled: true,
+            pinned: false,
+        };
+        match kind {
+            ContentKind::Mod => upsert_mod(profile, content_ref),
+            ContentKind::ResourcePack => upsert_resourcepack(profile, content_ref),
+            ContentKind::ShaderPack => upsert_shaderpack(profile, content_ref),
+            ContentKind::Skin => false,
+        };
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+/// Create a profile from a `.minecraft` folder
+pub fn import_dotminecraft(paths: &Paths, minecraft_dir: &Path, options: &DotMinecraftOptions) -> Result<DotMinecraftImport> {
+    if !minecraft_dir.is_dir() {
+        bail!("minecraft directory not found: {}", minecraft_dir.display());
+    }
+    let profile_id = match &options.profile_id {
+        Some(id) => id.clone(),
+        None => {
+            let name = minecraft_dir
+                .canonicalize()
+                .ok()
+                .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_default();
+            slugify(&name)
+        }
+    };
+    if profile_id.is_empty() {
+        bail!("could not derive a profile id from {}; pass one with --id", minecraft_dir.display());
+    }
+    if paths.is_profile_present(&profile_id) {
+        bail!("profile already exists: {profile_id}");
+    }
+
+    let (version_id, mc_version, loader, version_source) = detect_version(minecraft_dir, options.version.as_deref())?;
+    let mut profile = create_profile(paths, &profile_id, &mc_version, loader.clone(), Runtime::default())?;
+
+    let mut skipped = Vec::new();
+    let mods = import_content_dir(paths, &mut profile, &minecraft_dir.join("mods"), ContentKind::Mod, &mut skipped)?;
+    let resourcepacks = import_content_dir(
+        paths,
+        &mut profile,
+        &minecraft_dir.join("resourcepacks"),
+        ContentKind::ResourcePack,
+        &mut skipped,
+    )?;
+    let shaderpacks = import_content_dir(
+        paths,
+        &mut profile,
+        &minecraft_dir.join("shaderpacks"),
+        ContentKind::ShaderPack,
+        &mut skipped,
+    )?;
+    save_profile(paths, &profile)?;
+    if mods > 0 && loader.is_none() {
+        skipped.push(format!(
+            "no mod loader detected for {mods} mods; import again with --version <loader version id>"
+        ));
+    }
+
+    if options.copy_options {
+        let overrides = paths.profile_overrides(&profile_id);
+        fs::create_dir_all(&overrides)
+            .write_context(&overrides, || format!("failed to create dir: {}", overrides.display()))?;
+        for file in OPTION_FILES {
+            let src = minecraft_dir.join(file);
+            if src.is_file() {
+                let dst = overrides.join(file);
+                fs::copy(&src, &dst).write_context(&dst, || format!("failed to copy {}", src.display()))?;
+            }
+        }
+        copy_dir_all(&minecraft_dir.join("config"), &overrides.join("config"))?;
+    }
+
+    let mut worlds = 0;
+    if options.copy_saves {
+        let saves = minecraft_dir.join("saves");
+        let target = paths.instance_dir(&profile_id).join("saves");
+        for entry in fs::read_dir(&saves).into_iter().flatten().flatten() {
+            if entry.path().join("level.dat").is_file() {
+                copy_dir_all(&entry.path(), &target.join(entry.file_name()))?;
+                worlds += 1;
+            }
+        }
+    }
+
+    Ok(DotMinecraftImport {
+        profile_id,
+        version_id,
+        mc_version,
+        loader,
+        version_source,
+        mods,
+        resourcepacks,
+        shaderpacks,
+        worlds,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loader_from_library() {
+        let loader = loader_from_library("net.fabricmc:fabric-loader:0.15.7", "1.20.4").unwrap();
+        assert_eq!((loader.loader_type.as_str(), loader.version.as_str()), ("fabric", "0.15.7"));
+        let loader = loader_from_library("net.minecraftforge:forge:1.20.1-47.2.0", "1.20.1").unwrap();
+        assert_eq!((loader.loader_type.as_str(), loader.version.as_str()), ("forge", "47.2.0"));
+        assert!(loader_from_library("org.ow2.asm:asm:9.6", "1.20.4").is_none());
+    }
+}
@@ -0,0 +1,218 @@
+//! Downloads that survive a crash or restart
+//!
+//! A download is written to `<dest>.part`, with `<dest>.part.json` recording
+//! the URL, the expected SHA-1, the bytes written so far and the server's
+//! validator (ETag or Last-Modified). The next attempt for the same file,
+//! whether a retry or a run after the launcher was closed, asks for the rest
+//! with a `Range` request instead of starting over. `If-Range` makes the
+//! server send the whole file again if it changed in the meantime, and the
+//! hash check throws away a partial file that still ends up wrong.
+//!
+//! `<dest>.lock` is held for the whole download, so two launches fetching
+//! the same library or asset don't write the same partial file; the one
+//! that waited finds the file already in place.
+
+use crate::disk::WriteContext;
+use crate::file_lock::FileLock;
+use crate::minecraft::sha1_file;
+use crate::net::retry;
+use anyhow::{Context, Result, bail};
+use reqwest::StatusCode;
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// How often the sidecar is rewritten while data comes in
+const SAVE_INTERVAL: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PartialDownload {
+    url: String,
+    #[serde(default)]
+    expected_sha1: Option<String>,
+    bytes_done: u64,
+    /// Strong ETag or Last-Modified of the response the bytes came from
+    #[serde(default)]
+    validator: Option<String>,
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn part_path(dest: &Path) -> PathBuf {
+    with_suffix(dest, ".part")
+}
+
+fn state_path(dest: &Path) -> PathBuf {
+    with_suffix(dest, ".part.json")
+}
+
+fn lock_path(dest: &Path) -> PathBuf {
+    with_suffix(dest, ".lock")
+}
+
+/// Whether `dest` is already a complete download
+fn is_complete(dest: &Path, expected_sha1: Option<&str>) -> bool {
+    match expected_sha1 {
+        Some(expected) => sha1_file(dest).is_ok_and(|actual| actual.eq_ignore_ascii_case(expected)),
+        None => fs::metadata(dest).is_ok_and(|meta| meta.len() > 0),
+    }
+}
+
+fn load_state(path: &Path) -> Option<PartialDownload> {
+    let data = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+fn save_state(path: &Path, state: &PartialDownload) -> Result<()> {
+    let data = serde_json::to_string(state).context("failed to serialize download state")?;
+    fs::write(path, data).write_context(path, || format!("failed to write {}", path.display()))
+}
+
+fn discard_partial(dest: &Path) {
+    let _ = fs::remove_file(part_path(dest));
+    let _ = fs::remove_file(state_path(dest));
+}
+
+/// Validator usable with `If-Range`: weak ETags are not
+fn response_validator(resp: &Response) -> Option<String> {
+    let header = |name| resp.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    header(ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(LAST_MODIFIED))
+}
+
+/// Bytes of an earlier attempt that can be continued, with the validator to
+/// send along. A partial file without a validator or expected hash can't be
+/// checked, so it is started over.
+fn resume_point(dest: &Path, url: &str, expected_sha1: Option<&str>) -> Option<(u64, Option<String>)> {
+    let state = load_state(&state_path(dest))?;
+    if state.url != url || state.expected_sha1.as_deref() != expected_sha1 {
+        return None;
+    }
+    if state.validator.is_none() && expected_sha1.is_none() {
+        return None;
+    }
+    let len = fs::metadata(part_path(dest)).ok()?.len();
+    (len > 0).then_some((len, state.validator))
+}
+
+/// One request; appends to the partial file when the server honours the range
+fn fetch(client: &Client, url: &str, dest: &Path, expected_sha1: Option<&str>) -> Result<()> {
+    let part = part_path(dest);
+    let state_file = state_path(dest);
+    let resume = resume_point(dest, url, expected_sha1);
+
+    let mut request = client.get(url);
+    if let Some((offset, validator)) = &resume {
+        request = request.header(RANGE, format!("bytes={offset}-"));
+        if let Some(validator) = validator {
+            request = request.header(IF_RANGE, validator.as_str());
+        }
+    }
+    let resp = request.send().with_context(|| format!("failed to download: {url}"))?;
+    // The partial file already holds everything
+    if resume.is_some() && resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(());
+    }
+    let mut resp = resp.error_for_status().with_context(|| format!("download failed: {url}"))?;
+
+    let offset = match resume {
+        Some((offset, _)) if resp.status() == StatusCode::PARTIAL_CONTENT => offset,
+        _ => 0,
+    };
+    let mut out = if offset > 0 {
+        fs::OpenOptions::new().append(true).open(&part)
+    } else {
+        fs::File::create(&part)
+    }
+    .write_context(&part, || format!("failed to open {}", part.display()))?;
+
+    let mut state = PartialDownload {
+        url: url.to_string(),
+        expected_sha1: expected_sha1.map(str::to_string),
+        bytes_done: offset,
+        validator: response_validator(&resp),
+    };
+    save_state(&state_file, &state)?;
+
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut unsaved = 0;
+    loop {
+        let read = resp.read(&mut buf).with_context(|| format!("failed to read download: {url}"))?;
+        if read == 0 {
+            break;
+        }
+        out.write_all(&buf[..read]).write_context(&part, || "failed to write download")?;
+        state.bytes_done += read as u64;
+        unsaved += read as u64;
+        if unsaved >= SAVE_INTERVAL {
+            out.flush().write_context(&part, || "failed to flush download")?;
+            save_state(&state_file, &state)?;
+            unsaved = 0;
+        }
+    }
+    out.flush().write_context(&part, || "failed to flush download")?;
+    save_state(&state_file, &state)
+}
+
+/// Download `url` to `dest`, continuing an earlier partial download of the
+/// same URL and hash. Retried per the network retry policy; every retry
+/// resumes where the last one stopped. Another process downloading the
+/// same file is waited for instead.
+pub fn download_resumable(client: &Client, url: &str, dest: &Path, expected_sha1: Option<&str>) -> Result<()> {
+    let lock = FileLock::acquire(&lock_path(dest))?.remove_on_release();
+    if lock.waited() && is_complete(dest, expected_sha1) {
+        return Ok(());
+    }
+    retry(|| fetch(client, url, dest, expected_sha1))?;
+
+    let part = part_path(dest);
+    if let Some(expected) = expected_sha1 {
+        let actual = sha1_file(&part)?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            discard_partial(dest);
+            bail!("sha1 mismatch for {}", dest.display());
+        }
+    }
+    fs::rename(&part, dest).with_context(|| format!("failed to move file into place: {}", dest.display()))?;
+    let _ = fs::remove_file(state_path(dest));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::TempDir;
+
+    #[test]
+    fn test_resume_point() {
+        let dir = TempDir::new("resume");
+        let dest = dir.join("client.jar");
+        let state = PartialDownload {
+            url: "https://example.com/client.jar".to_string(),
+            expected_sha1: None,
+            bytes_done: 3,
+            validator: Some("\"abc\"".to_string()),
+        };
+        save_state(&state_path(&dest), &state).unwrap();
+        fs::write(part_path(&dest), b"abc").unwrap();
+
+        assert_eq!(
+            resume_point(&dest, &state.url, None),
+            Some((3, Some("\"abc\"".to_string())))
+        );
+        // A different URL or hash starts over
+        assert_eq!(resume_point(&dest, "https://example.com/other.jar", None), None);
+        assert_eq!(resume_point(&dest, &state.url, Some("deadbeef")), None);
+
+        discard_partial(&dest);
+        assert_eq!(resume_point(&dest, &state.url, None), None);
+    }
+}
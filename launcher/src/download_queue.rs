@@ -0,0 +1,163 @@
+//! Quarantine for game files that keep failing to download
+//!
+//! A CDN hiccup on one asset or library shouldn't throw away the rest of a
+//! multi-thousand-file download. Files that still fail after the network
+//! retries are recorded here with the error instead, the remaining files are
+//! fetched, and `shard downloads retry` tries the queued ones again later. A
+//! full disk or a permission problem is not a download problem and still
+//! stops the operation right away, and so do several failures in a row,
+//! which mean the network or the server is down rather than a few files.
+
+use crate::disk::{WriteContext, find_disk_error};
+use crate::jobs::Job;
+use crate::minecraft::download_with_sha1;
+use crate::paths::Paths;
+use crate::util::now_epoch_secs;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Failures in a row after which the batch is given up
+const MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+impl Paths {
+    fn download_queue(&self) -> PathBuf {
+        self.cache_manifests.join("download-queue.json")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedDownload {
+    pub url: String,
+    pub path: PathBuf,
+    #[serde(default)]
+    pub sha1: Option<String>,
+    /// Error of the last attempt
+    pub error: String,
+    /// Attempts that failed, counting each run of the network retries once
+    pub failures: u32,
+    pub last_failed_at: u64,
+}
+
+/// Outcome of [`retry_download_queue`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueueRetryResult {
+    pub downloaded: usize,
+    /// Files still failing, with their new error
+    pub failed: Vec<FailedDownload>,
+}
+
+pub fn load_download_queue(paths: &Paths) -> Result<Vec<FailedDownload>> {
+    let path = paths.download_queue();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn save_download_queue(path: &Path, queue: &[FailedDownload]) -> Result<()> {
+    if queue.is_empty() {
+        if path.exists() {
+            fs::remove_file(path).with_context(|| format!("failed to remove {}", path.display()))?;
+        }
+        return Ok(());
+    }
+    let data = serde_json::to_string_pretty(queue).context("failed to serialize download queue")?;
+    fs::write(path, data).write_context(path, || format!("failed to write {}", path.display()))
+}
+
+/// The queue while a batch of downloads runs; saved once at the end
+pub(crate) struct DownloadQueue {
+    file: PathBuf,
+    entries: Vec<FailedDownload>,
+    /// Files that failed during this batch
+    pub(crate) failed: Vec<FailedDownload>,
+    consecutive_failures: u32,
+    changed: bool,
+}
+
+impl DownloadQueue {
+    pub(crate) fn load(paths: &Paths) -> Result<Self> {
+        Ok(Self {
+            file: paths.download_queue(),
+            entries: load_download_queue(paths)?,
+            failed: Vec::new(),
+            consecutive_failures: 0,
+            changed: false,
+        })
+    }
+
+    /// Download a file, quarantining it if it fails for a reason other than
+    /// the local disk. Returns whether the file is in place.
+    pub(crate) fn download(&mut self, url: &str, path: &Path, sha1: Option<&str>) -> Result<bool> {
+        match download_with_sha1(url, path, sha1) {
+            Ok(()) => {
+                self.consecutive_failures = 0;
+                let before = self.entries.len();
+                self.entries.retain(|entry| entry.path != path);
+                self.changed |= self.entries.len() != before;
+                Ok(true)
+            }
+            Err(err) if find_disk_error(&err).is_some() => Err(err),
+            Err(err) => {
+                let failures = self
+                    .entries
+                    .iter()
+                    .find(|entry| entry.path == path)
+                    .map_or(0, |entry| entry.failures);
+                self.entries.retain(|entry| entry.path != path);
+                let failed = FailedDownload {
+                    url: url.to_string(),
+                    path: path.to_path_buf(),
+                    sha1: sha1.map(str::to_string),
+                    error: format!("{err:#}"),
+                    failures: failures + 1,
+                    last_failed_at: now_epoch_secs(),
+                };
+                self.entries.push(failed.clone());
+                self.failed.push(failed);
+                self.changed = true;
+                self.consecutive_failures += 1;
+                if self.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                    let _ = self.save();
+                    return Err(err.context("several downloads in a row failed; the network or download server may be down"));
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    pub(crate) fn save(&self) -> Result<()> {
+        if self.changed {
+            save_download_queue(&self.file, &self.entries)?;
+        }
+        Ok(())
+    }
+}
+
+/// Download every queued file again
+pub fn retry_download_queue(paths: &Paths, job: &Job) -> Result<QueueRetryResult> {
+    let mut queue = DownloadQueue::load(paths)?;
+    let pending = queue.entries.clone();
+    let mut result = QueueRetryResult::default();
+    for (done, entry) in pending.iter().enumerate() {
+        job.progress(done as u64, Some(pending.len() as u64), Some(&entry.url));
+        if let Err(err) = job.check_cancelled() {
+            let _ = queue.save();
+            return Err(err);
+        }
+        let downloaded = queue.download(&entry.url, &entry.path, entry.sha1.as_deref());
+        // Keep what was learned so far if the disk gives out midway
+        if downloaded.is_err() {
+            let _ = queue.save();
+        }
+        if downloaded? {
+            result.downloaded += 1;
+        }
+    }
+    queue.save()?;
+    result.failed = queue.failed;
+    Ok(result)
+}
@@ -0,0 +1,273 @@
+//! Local stand-in for Mojang's and Modrinth's servers in tests
+//!
+//! [`FakeCdn`] serves fixture files over plain HTTP on a loopback port and
+//! points the download mirrors at itself, so the version manifest and asset
+//! objects, whose official URLs are hard-coded, come from it as well. The
+//! fixtures it publishes link everything else (version JSONs, client jars,
+//! libraries, mod files) to its own URLs. That lets `prepare`, modpack
+//! imports and store downloads run end to end without the network.
+//!
+//! The mirrors are process-wide, so only one fake CDN runs at a time; tests
+//! that start one wait for the previous one to be dropped.
+
+use crate::mirrors::{DownloadMirrors, set_download_mirrors};
+use crate::paths::Paths;
+use serde_json::{Value, json};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+static SERIAL: Mutex<()> = Mutex::new(());
+
+type Files = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+pub(crate) struct FakeCdn {
+    base: String,
+    files: Files,
+    requests: Arc<Mutex<Vec<String>>>,
+    stop: Arc<AtomicBool>,
+    server: Option<thread::JoinHandle<()>>,
+    _serial: MutexGuard<'static, ()>,
+}
+
+impl FakeCdn {
+    /// Serve on a free loopback port and send Mojang downloads here
+    pub(crate) fn start() -> FakeCdn {
+        // A test that failed while holding the lock poisoned it, nothing else
+        let serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind fake cdn");
+        let base = format!("http://{}/", listener.local_addr().expect("fake cdn has no address"));
+        let files = Files::default();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let server = {
+            let (files, requests, stop) = (files.clone(), requests.clone(), stop.clone());
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let Ok(stream) = stream else {
+                        continue;
+                    };
+                    let (files, requests) = (files.clone(), requests.clone());
+                    thread::spawn(move || serve(stream, &files, &requests));
+                }
+            })
+        };
+
+        set_download_mirrors(DownloadMirrors {
+            manifest: Some(base.clone()),
+            libraries: Some(format!("{base}maven/")),
+            assets: Some(format!("{base}assets/")),
+        });
+        FakeCdn {
+            base,
+            files,
+            requests,
+            stop,
+            server: Some(server),
+            _serial: serial,
+        }
+    }
+
+    /// Serve `body` at `path`; returns its URL
+    pub(crate) fn publish(&self, path: &str, body: impl Into<Vec<u8>>) -> String {
+        let path = path.trim_start_matches('/');
+        self.files.lock().unwrap().insert(path.to_string(), body.into());
+        self.url(path)
+    }
+
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base, path.trim_start_matches('/'))
+    }
+
+    /// Paths requested so far, in order
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Publish a vanilla version with one library, one asset and a server
+    /// jar, listed in the version manifest
+    pub(crate) fn publish_version(&self, id: &str) {
+        let client = format!("client jar of {id}").into_bytes();
+        let client_sha1 = sha1_hex(&client);
+        let client_url = self.publish(&format!("v1/objects/{client_sha1}/client.jar"), client.clone());
+        let server = format!("server jar of {id}").into_bytes();
+        let server_sha1 = sha1_hex(&server);
+        let server_url = self.publish(&format!("v1/objects/{server_sha1}/server.jar"), server.clone());
+
+        let library_path = "com/example/fixture/1.0/fixture-1.0.jar";
+        let library = b"fixture library".to_vec();
+        let library_sha1 = sha1_hex(&library);
+        let library_url = self.publish(&format!("maven/{library_path}"), library.clone());
+
+        let asset = b"fixture sound".to_vec();
+        let asset_sha1 = sha1_hex(&asset);
+        self.publish(&format!("assets/{}/{asset_sha1}", &asset_sha1[..2]), asset.clone());
+        let asset_index = serde_json::to_vec(&json!({
+            "objects": {
+                "minecraft/sounds/fixture.ogg": { "hash": asset_sha1, "size": asset.len() }
+            }
+        }))
+        .unwrap();
+        let asset_index_sha1 = sha1_hex(&asset_index);
+        let asset_index_url = self.publish(&format!("v1/packages/{asset_index_sha1}/{id}.json"), asset_index.clone());
+
+        let version = serde_json::to_vec(&json!({
+            "id": id,
+            "type": "release",
+            "mainClass": "net.minecraft.client.main.Main",
+            "assetIndex": {
+                "id": id,
+                "sha1": asset_index_sha1,
+                "size": asset_index.len(),
+                "totalSize": asset.len(),
+                "url": asset_index_url,
+            },
+            "assets": id,
+            "downloads": {
+                "client": { "sha1": client_sha1, "size": client.len(), "url": client_url },
+                "server": { "sha1": server_sha1, "size": server.len(), "url": server_url }
+            },
+            "libraries": [{
+                "name": "com.example:fixture:1.0",
+                "downloads": {
+                    "artifact": {
+                        "path": library_path,
+                        "sha1": library_sha1,
+                        "size": library.len(),
+                        "url": library_url,
+                    }
+                }
+            }],
+            "arguments": {
+                "game": ["--username", "${auth_player_name}", "--version", "${version_name}", "--gameDir", "${game_directory}"],
+                "jvm": ["-Djava.library.path=${natives_directory}", "-cp", "${classpath}"]
+            }
+        }))
+        .unwrap();
+        let version_sha1 = sha1_hex(&version);
+        let version_url = self.publish(&format!("v1/packages/{version_sha1}/{id}.json"), version);
+
+        let mut manifest = self.manifest();
+        manifest["latest"] = json!({ "release": id, "snapshot": id });
+        manifest["versions"].as_array_mut().unwrap().push(json!({
+            "id": id,
+            "type": "release",
+            "url": version_url,
+            "time": "2026-01-01T00:00:00+00:00",
+            "releaseTime": "2026-01-01T00:00:00+00:00",
+            "sha1": version_sha1,
+        }));
+        self.publish("mc/game/version_manifest_v2.json", serde_json::to_vec(&manifest).unwrap());
+    }
+
+    fn manifest(&self) -> Value {
+        self.files
+            .lock()
+            .unwrap()
+            .get("mc/game/version_manifest_v2.json")
+            .and_then(|data| serde_json::from_slice(data).ok())
+            .unwrap_or_else(|| json!({ "latest": {}, "versions": [] }))
+    }
+}
+
+impl Drop for FakeCdn {
+    fn drop(&mut self) {
+        set_download_mirrors(DownloadMirrors::default());
+        self.stop.store(true, Ordering::Relaxed);
+        // Wake the accept loop so it sees the stop flag
+        let _ = TcpStream::connect(self.base.trim_start_matches("http://").trim_end_matches('/'));
+        if let Some(server) = self.server.take() {
+            let _ = server.join();
+        }
+    }
+}
+
+/// Answer one GET with the published file or a 404. Ranges are ignored, as
+/// servers are allowed to.
+fn serve(stream: TcpStream, files: &Files, requests: &Mutex<Vec<String>>) {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // Skip the headers
+    let mut line = String::new();
+    while reader.read_line(&mut line).is_ok_and(|read| read > 0) && line != "\r\n" {
+        line.clear();
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/").trim_start_matches('/').to_string();
+    requests.lock().unwrap().push(path.clone());
+
+    let body = files.lock().unwrap().get(&path).cloned();
+    let (status, body) = match body {
+        Some(body) => ("200 OK", body),
+        None => ("404 Not Found", Vec::new()),
+    };
+    let mut stream = &stream;
+    let head = format!("HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(&body);
+}
+
+pub(crate) fn sha1_hex(data: &[u8]) -> String {
+    hex::encode(Sha1::digest(data))
+}
+
+/// Empty directory in the temp dir, removed when dropped
+pub(crate) struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub(crate) fn new(name: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!("shard-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).expect("failed to create test dir");
+        TempDir { path }
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Empty data root in the temp dir, removed when dropped
+pub(crate) struct TempRoot {
+    pub(crate) paths: Paths,
+    _dir: TempDir,
+}
+
+impl TempRoot {
+    pub(crate) fn new(name: &str) -> TempRoot {
+        let dir = TempDir::new(name);
+        let paths = Paths::at(dir.to_path_buf());
+        paths.ensure().expect("failed to create test data root");
+        TempRoot { paths, _dir: dir }
+    }
+}
@@ -0,0 +1,129 @@
+//! Locks shared by every launcher process on the machine
+//!
+//! The desktop app and any number of CLI invocations can prepare games at
+//! the same time, and they share the version, library and asset files. A
+//! [`FileLock`] is an exclusive OS lock on a lock file, held until dropped,
+//! so two of them never write the same download or install the same loader
+//! at once. Locks are released by the OS if the process dies.
+
+use crate::disk::WriteContext;
+use anyhow::{Context, Result};
+use std::fs::{self, File, TryLockError};
+use std::path::{Path, PathBuf};
+
+pub struct FileLock {
+    file: File,
+    path: PathBuf,
+    /// Whether another holder had to be waited for
+    waited: bool,
+    remove_on_release: bool,
+}
+
+impl FileLock {
+    /// Lock `path`, creating it if needed, waiting while someone else holds it
+    pub fn acquire(path: &Path) -> Result<FileLock> {
+        Ok(Self::lock(path, true)?.expect("a blocking lock waits until it is acquired"))
+    }
+
+    /// Lock `path` unless someone else holds it
+    pub fn try_acquire(path: &Path) -> Result<Option<FileLock>> {
+        Self::lock(path, false)
+    }
+
+    fn lock(path: &Path, block: bool) -> Result<Option<FileLock>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+        }
+        let mut waited = false;
+        loop {
+            let file = fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(path)
+                .write_context(path, || format!("failed to open lock file: {}", path.display()))?;
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) if block => {
+                    waited = true;
+                    file.lock().with_context(|| format!("failed to lock {}", path.display()))?;
+                }
+                Err(TryLockError::WouldBlock) => return Ok(None),
+                Err(TryLockError::Error(err)) => {
+                    return Err(err).with_context(|| format!("failed to lock {}", path.display()));
+                }
+            }
+            // The previous holder may have removed the file while we waited;
+            // a lock on a removed file excludes nobody
+            if is_same_file(&file, path) {
+                return Ok(Some(FileLock {
+                    file,
+                    path: path.to_path_buf(),
+                    waited,
+                    remove_on_release: false,
+                }));
+            }
+        }
+    }
+
+    /// Delete the lock file when released, for locks on files that are
+    /// only briefly contended, like single downloads
+    pub fn remove_on_release(mut self) -> Self {
+        self.remove_on_release = true;
+        self
+    }
+
+    /// Whether the lock was held by someone else when asked for, i.e. they
+    /// may have done the work in the meantime
+    pub fn waited(&self) -> bool {
+        self.waited
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Removed before unlocking, so a waiter finds it gone and starts over
+        if self.remove_on_release {
+            let _ = fs::remove_file(&self.path);
+        }
+        let _ = self.file.unlock();
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(held), Ok(current)) => held.dev() == current.dev() && held.ino() == current.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::TempDir;
+
+    #[test]
+    fn test_file_lock() {
+        let dir = TempDir::new("file-lock");
+        let path = dir.join("prepare.lock");
+        let held = FileLock::acquire(&path).unwrap();
+        assert!(!held.waited());
+        assert!(FileLock::try_acquire(&path).unwrap().is_none());
+
+        let waiter = std::thread::spawn({
+            let path = path.clone();
+            move || FileLock::acquire(&path).unwrap().waited()
+        });
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        drop(held.remove_on_release());
+        assert!(waiter.join().unwrap());
+        assert!(FileLock::try_acquire(&path).unwrap().is_some());
+    }
+}
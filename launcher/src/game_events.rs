@@ -0,0 +1,220 @@
+//! Structured game events extracted from Minecraft log output
+//!
+//! Recognizes server connections, players joining/leaving, advancements and
+//! disconnects in parsed log entries so callers can build session summaries or
+//! notify on joins without scraping raw text themselves.
+
+use crate::logs::LogEntry;
+use serde::{Deserialize, Serialize};
+
+/// A notable event in the game output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GameEvent {
+    /// The client started connecting to a multiplayer server
+    ServerConnect { address: String },
+    /// A player joined the game (as seen in chat or server output)
+    PlayerJoined { player: String },
+    /// A player left the game
+    PlayerLeft { player: String },
+    /// A player unlocked an advancement, goal or challenge
+    Advancement { player: String, advancement: String },
+    /// The client was disconnected from a server
+    Disconnected { reason: String },
+}
+
+/// A game event with the log position it was extracted from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedGameEvent {
+    pub timestamp: Option<String>,
+    pub line_number: u64,
+    #[serde(flatten)]
+    pub event: GameEvent,
+}
+
+/// Aggregated view of the events in one game session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionSummary {
+    /// Servers connected to, in order
+    pub servers: Vec<String>,
+    /// Distinct players seen joining, in order of first appearance
+    pub players: Vec<String>,
+    /// Advancements unlocked as (player, advancement)
+    pub advancements: Vec<(String, String)>,
+    /// Reason of the last disconnect, if any
+    pub last_disconnect: Option<String>,
+}
+
+const ADVANCEMENT_MARKERS: [&str; 3] = [
+    " has made the advancement [",
+    " has completed the challenge [",
+    " has reached the goal [",
+];
+
+/// Extract a game event from a single log entry
+pub fn extract_event(entry: &LogEntry) -> Option<GameEvent> {
+    let message = entry.message.trim();
+    // Client chat lines are prefixed with "[CHAT] " (older versions: "[System] [CHAT] ")
+    let chat = message
+        .find("[CHAT] ")
+        .map(|idx| &message[idx + "[CHAT] ".len()..])
+        .unwrap_or(message);
+
+    if let Some(rest) = message.strip_prefix("Connecting to ") {
+        // "Connecting to mc.example.net, 25565"
+        let address = match rest.rsplit_once(", ") {
+            Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => {
+                format!("{host}:{port}")
+            }
+            _ => rest.to_string(),
+        };
+        return Some(GameEvent::ServerConnect { address });
+    }
+
+    if let Some(reason) = message
+        .strip_prefix("Client disconnected with reason: ")
+        .or_else(|| message.strip_prefix("Disconnected from server: "))
+    {
+        return Some(GameEvent::Disconnected {
+            reason: reason.trim().to_string(),
+        });
+    }
+
+    for marker in ADVANCEMENT_MARKERS {
+        if let Some((player, rest)) = chat.split_once(marker)
+            && let Some(advancement) = rest.strip_suffix(']')
+            && is_player_name(player)
+        {
+            return Some(GameEvent::Advancement {
+                player: player.to_string(),
+                advancement: advancement.to_string(),
+            });
+        }
+    }
+
+    if let Some(player) = chat.strip_suffix(" joined the game")
+        && is_player_name(player)
+    {
+        return Some(GameEvent::PlayerJoined {
+            player: player.to_string(),
+        });
+    }
+
+    if let Some(player) = chat.strip_suffix(" left the game")
+        && is_player_name(player)
+    {
+        return Some(GameEvent::PlayerLeft {
+            player: player.to_string(),
+        });
+    }
+
+    None
+}
+
+/// Extract all game events from a batch of log entries
+pub fn extract_events(entries: &[LogEntry]) -> Vec<TimedGameEvent> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            extract_event(entry).map(|event| TimedGameEvent {
+                timestamp: entry.timestamp.clone(),
+                line_number: entry.line_number,
+                event,
+            })
+        })
+        .collect()
+}
+
+/// Summarize a session from its events
+pub fn summarize_events(events: &[TimedGameEvent]) -> SessionSummary {
+    let mut summary = SessionSummary::default();
+    for timed in events {
+        match &timed.event {
+            GameEvent::ServerConnect { address } => summary.servers.push(address.clone()),
+            GameEvent::PlayerJoined { player } => {
+                if !summary.players.contains(player) {
+                    summary.players.push(player.clone());
+                }
+            }
+            GameEvent::PlayerLeft { .. } => {}
+            GameEvent::Advancement {
+                player,
+                advancement,
+            } => summary
+                .advancements
+                .push((player.clone(), advancement.clone())),
+            GameEvent::Disconnected { reason } => summary.last_disconnect = Some(reason.clone()),
+        }
+    }
+    summary
+}
+
+/// Minecraft usernames are at most 16 word characters; anything else is chat text
+fn is_player_name(name: &str) -> bool {
+    (1..=16).contains(&name.len())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::parse_log_line;
+
+    fn event(line: &str) -> Option<GameEvent> {
+        extract_event(&parse_log_line(line, 1))
+    }
+
+    #[test]
+    fn test_extract_client_events() {
+        assert_eq!(
+            event("[12:00:01] [Render thread/INFO]: Connecting to mc.example.net, 25565"),
+            Some(GameEvent::ServerConnect {
+                address: "mc.example.net:25565".to_string()
+            })
+        );
+        assert_eq!(
+            event("[12:00:05] [Render thread/INFO]: [System] [CHAT] Steve joined the game"),
+            Some(GameEvent::PlayerJoined {
+                player: "Steve".to_string()
+            })
+        );
+        assert_eq!(
+            event("[12:03:10] [Render thread/INFO]: [CHAT] Alex has made the advancement [Stone Age]"),
+            Some(GameEvent::Advancement {
+                player: "Alex".to_string(),
+                advancement: "Stone Age".to_string()
+            })
+        );
+        assert_eq!(
+            event("[12:09:00] [Render thread/INFO]: Client disconnected with reason: Server closed"),
+            Some(GameEvent::Disconnected {
+                reason: "Server closed".to_string()
+            })
+        );
+        assert_eq!(
+            event("[12:04:00] [Render thread/INFO]: [CHAT] <Steve> has anyone left the game"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_summarize_events() {
+        let entries: Vec<LogEntry> = [
+            "[12:00:01] [Render thread/INFO]: Connecting to play.example.org, 25565",
+            "[12:00:05] [Render thread/INFO]: [CHAT] Steve joined the game",
+            "[12:01:05] [Render thread/INFO]: [CHAT] Steve left the game",
+            "[12:02:05] [Render thread/INFO]: [CHAT] Steve joined the game",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, line)| parse_log_line(line, i as u64 + 1))
+        .collect();
+
+        let events = extract_events(&entries);
+        assert_eq!(events.len(), 4);
+        let summary = summarize_events(&events);
+        assert_eq!(summary.servers, vec!["play.example.org:25565"]);
+        assert_eq!(summary.players, vec!["Steve"]);
+        assert!(summary.last_disconnect.is_none());
+    }
+}
@@ -0,0 +1,60 @@
+//! Launch mode for Gamescope sessions (Steam Deck game mode)
+//!
+//! Gamescope shows one window scaled to the screen, and Steam Input stands
+//! in for the controller with a virtual gamepad. In this mode the game
+//! starts fullscreen at the profile's resolution, and SDL, which controller
+//! mods use, is told to pick up the virtual gamepad and to stay open when
+//! the Steam overlay or keyboard takes focus.
+
+use crate::profile::Gamescope;
+use std::env;
+
+/// SDL settings for Steam Input; ones already in the environment (Steam
+/// sets some itself) are left alone
+const SDL_VARS: [(&str, &str); 2] = [
+    ("SDL_GAMECONTROLLER_ALLOW_STEAM_VIRTUAL_GAMEPAD", "1"),
+    ("SDL_VIDEO_MINIMIZE_ON_FOCUS_LOSS", "0"),
+];
+
+/// Environment variables the game gets in this mode
+pub fn gamescope_env() -> Vec<(String, String)> {
+    SDL_VARS
+        .iter()
+        .filter(|(key, _)| env::var_os(key).is_none())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Add the size and fullscreen flags to `game_args`, where the version's
+/// own arguments didn't already take the resolution
+pub fn add_gamescope_args(game_args: &mut Vec<String>, size: Gamescope) {
+    if !game_args.iter().any(|arg| arg == "--width") {
+        game_args.extend(["--width".to_string(), size.width.to_string()]);
+        game_args.extend(["--height".to_string(), size.height.to_string()]);
+    }
+    if !game_args.iter().any(|arg| arg == "--fullscreen") {
+        game_args.push("--fullscreen".to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gamescope_args() {
+        let size: Gamescope = "1920x1080".parse().unwrap();
+        assert_eq!(size.to_string(), "1920x1080");
+        assert!("1920".parse::<Gamescope>().is_err());
+        assert!("0x800".parse::<Gamescope>().is_err());
+
+        let mut args = vec!["--username".to_string(), "Steve".to_string()];
+        add_gamescope_args(&mut args, Gamescope::default());
+        assert_eq!(args, ["--username", "Steve", "--width", "1280", "--height", "800", "--fullscreen"]);
+
+        // Modern versions pass the resolution through their own arguments
+        let mut args = ["--width", "1920", "--height", "1080"].map(String::from).to_vec();
+        add_gamescope_args(&mut args, size);
+        assert_eq!(args, ["--width", "1920", "--height", "1080", "--fullscreen"]);
+    }
+}
@@ -0,0 +1,833 @@
+//! Running the Forge/NeoForge installer jar
+//!
+//! [`install_headless`] reads the installer's `install_profile.json` and does
+//! its work directly: it writes the version JSON, fetches the libraries and
+//! runs each client processor with an explicit classpath. This avoids
+//! depending on what `--installClient` happens to write into the game
+//! directory. Legacy installers (Forge for 1.12.2 and older) have no
+//! processors: their profile embeds the version JSON and the jar bundles the
+//! universal jar, so both are just extracted.
+//!
+//! [`run_installer`] runs the installer itself, for installer formats the
+//! headless path doesn't handle. It downloads libraries and runs processors
+//! for several minutes without any structured progress, so its output is
+//! captured line by line and mapped to a few [`InstallerMilestone`]s. The
+//! full log is saved under `installer-logs/` in the cache dir, and a failed
+//! or hung install is reported as an [`InstallerError`] saying what went
+//! wrong when the output matches a known failure (wrong Java, missing
+//! vanilla jar, network).
+
+use crate::disk::WriteContext;
+use crate::minecraft::{download_with_sha1, maven_path_from_name, sha1_file};
+use crate::paths::Paths;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long an installer may run before it is killed
+pub const INSTALLER_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Lines of installer output included in error messages
+const LOG_TAIL_LINES: usize = 30;
+
+impl Paths {
+    pub fn installer_logs_dir(&self) -> PathBuf {
+        self.cache_root.join("installer-logs")
+    }
+
+    /// Full output of the last run of an installer jar
+    pub fn installer_log(&self, installer_path: &Path) -> PathBuf {
+        let stem = installer_path.file_stem().and_then(|s| s.to_str()).unwrap_or("installer");
+        self.installer_logs_dir().join(format!("{stem}.log"))
+    }
+}
+
+/// Progress points recognised in installer output, in the order they occur
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallerMilestone {
+    Started,
+    ExtractingFiles,
+    DownloadingLibraries,
+    RunningProcessors,
+    Finished,
+}
+
+impl InstallerMilestone {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Started => "started",
+            Self::ExtractingFiles => "extracting_files",
+            Self::DownloadingLibraries => "downloading_libraries",
+            Self::RunningProcessors => "running_processors",
+            Self::Finished => "finished",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Started => "Running loader installer",
+            Self::ExtractingFiles => "Extracting loader files",
+            Self::DownloadingLibraries => "Downloading loader libraries",
+            Self::RunningProcessors => "Processing game jars",
+            Self::Finished => "Loader installed",
+        }
+    }
+}
+
+/// Milestone a line of installer output marks, if any
+pub fn parse_milestone(line: &str) -> Option<InstallerMilestone> {
+    let line = line.trim();
+    if line.starts_with("Extracting json") || line.starts_with("Considering minecraft client jar") {
+        Some(InstallerMilestone::ExtractingFiles)
+    } else if line.starts_with("Downloading libraries") {
+        Some(InstallerMilestone::DownloadingLibraries)
+    } else if line.starts_with("Building Processors")
+        || line.starts_with("Processing:")
+        || line.starts_with("Data Processing")
+    {
+        Some(InstallerMilestone::RunningProcessors)
+    } else if line.starts_with("Successfully installed") {
+        Some(InstallerMilestone::Finished)
+    } else {
+        None
+    }
+}
+
+/// Why an installer run failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallerErrorKind {
+    /// The installer or a processor doesn't run on the Java it was given
+    WrongJava,
+    /// The vanilla client jar the processors patch is missing or unreadable
+    MissingVanillaJar,
+    /// Libraries could not be downloaded
+    Network,
+    TimedOut,
+    /// Nothing in the output matched a known failure
+    Failed,
+}
+
+impl InstallerErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::WrongJava => "wrong_java",
+            Self::MissingVanillaJar => "missing_vanilla_jar",
+            Self::Network => "network",
+            Self::TimedOut => "timed_out",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn hint(self) -> Option<&'static str> {
+        match self {
+            Self::WrongJava => Some("set the profile's java path to a Java version this loader supports"),
+            Self::MissingVanillaJar => Some("delete the Minecraft version from minecraft/versions and prepare again"),
+            Self::Network => Some("check your connection and try again"),
+            Self::TimedOut | Self::Failed => None,
+        }
+    }
+}
+
+/// A failed installer run. Returned inside `anyhow::Error`; use
+/// [`find_installer_error`] to recover it.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallerError {
+    pub kind: InstallerErrorKind,
+    pub message: String,
+    /// Output line the failure was recognised from
+    pub detail: Option<String>,
+    /// Where the full installer output was saved
+    pub log_path: Option<PathBuf>,
+    pub log_tail: Vec<String>,
+}
+
+impl fmt::Display for InstallerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(detail) = &self.detail {
+            write!(f, " ({detail})")?;
+        }
+        if let Some(hint) = self.kind.hint() {
+            write!(f, "; {hint}")?;
+        }
+        if let Some(path) = &self.log_path {
+            write!(f, "\nfull installer log: {}", path.display())?;
+        }
+        write!(f, "{}", format_log_tail(&self.log_tail))
+    }
+}
+
+impl std::error::Error for InstallerError {}
+
+/// Installer failure carried by an error chain, if any
+pub fn find_installer_error(err: &anyhow::Error) -> Option<InstallerError> {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<InstallerError>())
+        .cloned()
+}
+
+fn is_wrong_java(line: &str) -> bool {
+    line.contains("UnsupportedClassVersionError")
+        || line.contains("Unsupported class file major version")
+        || line.contains("compiled by a more recent version of the Java Runtime")
+        // Old installers on Java 9+
+        || line.contains("cannot be cast to java.net.URLClassLoader")
+        || line.contains("cannot be cast to class java.net.URLClassLoader")
+}
+
+fn is_missing_vanilla_jar(line: &str) -> bool {
+    let lower = line.to_ascii_lowercase();
+    let names_jar = ["client jar", "minecraft jar", "vanilla jar"].iter().any(|n| lower.contains(n));
+    let failed = ["missing", "not found", "failed", "invalid checksum", "corrupt"]
+        .iter()
+        .any(|w| lower.contains(w));
+    (names_jar && failed) || (line.contains("NoSuchFileException") && lower.contains("versions") && lower.ends_with(".jar"))
+}
+
+fn is_network_failure(line: &str) -> bool {
+    line.contains("UnknownHostException")
+        || line.contains("SocketTimeoutException")
+        || line.contains("ConnectException")
+        || line.contains("SSLHandshakeException")
+        || line.trim_start().starts_with("Failed to download")
+}
+
+/// Recognise a known failure in installer output, with the line it was found
+/// on. Java problems win over the errors they cause further down the log.
+pub fn classify_installer_output(log: &[String]) -> Option<(InstallerErrorKind, String)> {
+    let checks = [
+        (InstallerErrorKind::WrongJava, is_wrong_java as fn(&str) -> bool),
+        (InstallerErrorKind::MissingVanillaJar, is_missing_vanilla_jar),
+        (InstallerErrorKind::Network, is_network_failure),
+    ];
+    checks.iter().find_map(|(kind, matches)| {
+        log.iter()
+            .find(|line| matches(line))
+            .map(|line| (*kind, line.trim().to_string()))
+    })
+}
+
+/// Save the full installer output; best effort
+fn save_installer_log(path: &Path, log: &[String]) -> Option<PathBuf> {
+    let parent = path.parent()?;
+    fs::create_dir_all(parent).ok()?;
+    let mut data = log.join("\n");
+    data.push('\n');
+    fs::write(path, data).ok()?;
+    Some(path.to_path_buf())
+}
+
+/// What a Forge or NeoForge installer sets up
+#[derive(Debug, Clone, Copy)]
+pub enum InstallTarget<'a> {
+    /// The client, into a `.minecraft`-style directory
+    Client(&'a Path),
+    /// A dedicated server, into its own directory
+    Server(&'a Path),
+}
+
+impl InstallTarget<'_> {
+    fn flag(self) -> &'static str {
+        match self {
+            InstallTarget::Client(_) => "--installClient",
+            InstallTarget::Server(_) => "--installServer",
+        }
+    }
+}
+
+/// Run `java -jar <installer> --installClient <minecraft_dir>` (or
+/// `--installServer <dir>`) in `work_dir`, reporting each milestone once and
+/// saving the output to `log_path`. Fails with an [`InstallerError`] when it
+/// exits unsuccessfully or runs longer than `timeout`.
+pub fn run_installer(
+    java: &str,
+    installer_path: &Path,
+    target: InstallTarget<'_>,
+    work_dir: &Path,
+    log_path: &Path,
+    timeout: Duration,
+    on_milestone: &mut dyn FnMut(InstallerMilestone),
+) -> Result<()> {
+    let (InstallTarget::Client(dir) | InstallTarget::Server(dir)) = target;
+    let mut child = Command::new(java)
+        .arg("-jar")
+        .arg(installer_path)
+        .arg(target.flag())
+        .arg(dir)
+        .current_dir(work_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run forge installer")?;
+
+    let (tx, rx) = mpsc::channel();
+    let readers = [
+        child.stdout.take().map(|out| spawn_line_reader(out, tx.clone())),
+        child.stderr.take().map(|err| spawn_line_reader(err, tx.clone())),
+    ];
+    drop(tx);
+
+    on_milestone(InstallerMilestone::Started);
+    let mut reached = InstallerMilestone::Started;
+    let mut log = Vec::new();
+    let deadline = Instant::now() + timeout;
+    let mut timed_out = false;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(remaining) {
+            Ok(line) => {
+                if let Some(milestone) = parse_milestone(&line)
+                    && milestone > reached
+                {
+                    reached = milestone;
+                    on_milestone(milestone);
+                }
+                log.push(line);
+            }
+            // Both pipes closed: the installer has exited
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                timed_out = true;
+                break;
+            }
+        }
+    }
+
+    let failure = if timed_out {
+        let _ = child.kill();
+        let _ = child.wait();
+        Some((
+            InstallerErrorKind::TimedOut,
+            format!("forge installer timed out after {} minutes", timeout.as_secs() / 60),
+        ))
+    } else {
+        for reader in readers.into_iter().flatten() {
+            let _ = reader.join();
+        }
+        let status = child.wait().context("failed to wait for forge installer")?;
+        (!status.success()).then(|| (InstallerErrorKind::Failed, format!("forge installer failed with status {status}")))
+    };
+    let log_path = save_installer_log(log_path, &log);
+    let Some((kind, message)) = failure else {
+        return Ok(());
+    };
+
+    // A hang is reported as such even if something failed before it
+    let (kind, detail) = match classify_installer_output(&log) {
+        Some((found, line)) if kind == InstallerErrorKind::Failed => (found, Some(line)),
+        _ => (kind, None),
+    };
+    let start = log.len().saturating_sub(LOG_TAIL_LINES);
+    Err(InstallerError {
+        kind,
+        message,
+        detail,
+        log_path,
+        log_tail: log.split_off(start),
+    }
+    .into())
+}
+
+fn spawn_line_reader(stream: impl Read + Send + 'static, tx: mpsc::Sender<String>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+fn format_log_tail(log: &[String]) -> String {
+    if log.is_empty() {
+        return String::new();
+    }
+    let start = log.len().saturating_sub(LOG_TAIL_LINES);
+    format!("\ninstaller output (last {} lines):\n{}", log.len() - start, log[start..].join("\n"))
+}
+
+#[derive(Debug, Deserialize)]
+struct InstallProfile {
+    /// Id of the version the installer creates
+    version: String,
+    /// Path of the version JSON inside the installer jar
+    json: String,
+    #[serde(default)]
+    data: HashMap<String, SidedValue>,
+    #[serde(default)]
+    processors: Vec<Processor>,
+    #[serde(default)]
+    libraries: Vec<InstallLibrary>,
+}
+
+/// `install_profile.json` of legacy installers
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LegacyInstallProfile {
+    install: LegacyInstall,
+    /// The version JSON itself
+    version_info: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LegacyInstall {
+    /// Maven coordinate of the universal jar
+    path: String,
+    /// Universal jar inside the installer
+    file_path: String,
+}
+
+#[derive(Debug)]
+enum InstallerFormat {
+    Processors(InstallProfile),
+    Legacy(LegacyInstallProfile),
+}
+
+fn parse_install_profile(data: &[u8]) -> Result<InstallerFormat> {
+    let value: serde_json::Value = serde_json::from_slice(data).context("failed to parse install_profile.json")?;
+    if value.get("install").is_some() && value.get("versionInfo").is_some() {
+        let profile = serde_json::from_value(value).context("unsupported legacy installer format (install_profile.json)")?;
+        return Ok(InstallerFormat::Legacy(profile));
+    }
+    let profile = serde_json::from_value(value).context("unsupported installer format (install_profile.json)")?;
+    Ok(InstallerFormat::Processors(profile))
+}
+
+#[derive(Debug, Deserialize)]
+struct SidedValue {
+    #[serde(default)]
+    client: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Processor {
+    jar: String,
+    #[serde(default)]
+    classpath: Vec<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    outputs: HashMap<String, String>,
+    #[serde(default)]
+    sides: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstallLibrary {
+    name: String,
+    #[serde(default)]
+    downloads: Option<InstallLibraryDownloads>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstallLibraryDownloads {
+    #[serde(default)]
+    artifact: Option<InstallArtifact>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstallArtifact {
+    path: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    sha1: Option<String>,
+}
+
+/// Install a Forge/NeoForge version from its installer jar without running
+/// the installer. The vanilla client jar for `mc_version` must already be
+/// downloaded. Returns the id of the installed version.
+pub fn install_headless(
+    paths: &Paths,
+    java: &str,
+    installer_path: &Path,
+    mc_version: &str,
+    on_milestone: &mut dyn FnMut(InstallerMilestone),
+) -> Result<String> {
+    let file = fs::File::open(installer_path)
+        .with_context(|| format!("failed to open installer jar: {}", installer_path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("failed to read installer jar: {}", installer_path.display()))?;
+    let profile = match parse_install_profile(&read_entry(&mut archive, "install_profile.json")?)? {
+        InstallerFormat::Processors(profile) => profile,
+        InstallerFormat::Legacy(profile) => return install_legacy(paths, &mut archive, &profile, on_milestone),
+    };
+
+    on_milestone(InstallerMilestone::Started);
+    on_milestone(InstallerMilestone::ExtractingFiles);
+    let version_json = read_entry(&mut archive, &profile.json)?;
+
+    let stem = installer_path.file_stem().and_then(|s| s.to_str()).unwrap_or("installer");
+    let extract_dir = installer_path.with_file_name(format!("{stem}-data"));
+    let minecraft_dir = paths
+        .minecraft_versions
+        .parent()
+        .context("could not determine minecraft directory")?;
+    let mut data = HashMap::new();
+    for (key, value) in &profile.data {
+        let Some(value) = &value.client else {
+            continue;
+        };
+        let resolved = resolve_data_value(paths, &mut archive, &extract_dir, value)?;
+        data.insert(key.clone(), resolved);
+    }
+    let path_value = |path: &Path| path.to_string_lossy().into_owned();
+    data.insert("SIDE".to_string(), "client".to_string());
+    data.insert("MINECRAFT_VERSION".to_string(), mc_version.to_string());
+    data.insert("MINECRAFT_JAR".to_string(), path_value(&paths.minecraft_version_jar(mc_version)));
+    data.insert("ROOT".to_string(), path_value(minecraft_dir));
+    data.insert("INSTALLER".to_string(), path_value(installer_path));
+    data.insert("LIBRARY_DIR".to_string(), path_value(&paths.minecraft_libraries));
+
+    on_milestone(InstallerMilestone::DownloadingLibraries);
+    for library in &profile.libraries {
+        ensure_install_library(paths, &mut archive, library)?;
+    }
+
+    on_milestone(InstallerMilestone::RunningProcessors);
+    let client_processors = profile
+        .processors
+        .iter()
+        .filter(|p| p.sides.as_ref().is_none_or(|sides| sides.iter().any(|s| s == "client")));
+    for processor in client_processors {
+        run_processor(&paths.minecraft_libraries, java, processor, &data)?;
+    }
+
+    let _ = fs::remove_dir_all(&extract_dir);
+
+    // Written last: an existing version JSON marks the loader as installed
+    let version_path = paths.minecraft_version_json(&profile.version);
+    if let Some(parent) = version_path.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    fs::write(&version_path, &version_json)
+        .write_context(&version_path, || format!("failed to write version json: {}", version_path.display()))?;
+    on_milestone(InstallerMilestone::Finished);
+    Ok(profile.version)
+}
+
+/// Extract the universal jar and the embedded version JSON of a legacy
+/// installer. Its libraries are downloaded with the game's at launch.
+fn install_legacy(
+    paths: &Paths,
+    archive: &mut zip::ZipArchive<fs::File>,
+    profile: &LegacyInstallProfile,
+    on_milestone: &mut dyn FnMut(InstallerMilestone),
+) -> Result<String> {
+    let id = profile
+        .version_info
+        .get("id")
+        .and_then(|id| id.as_str())
+        .context("legacy installer profile has no version id")?
+        .to_string();
+
+    on_milestone(InstallerMilestone::Started);
+    on_milestone(InstallerMilestone::ExtractingFiles);
+    let universal = artifact_path(&paths.minecraft_libraries, &profile.install.path)?;
+    if let Some(parent) = universal.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    let data = read_entry(archive, &profile.install.file_path)?;
+    fs::write(&universal, data).write_context(&universal, || format!("failed to write {}", universal.display()))?;
+
+    // Written last: an existing version JSON marks the loader as installed
+    let version_path = paths.minecraft_version_json(&id);
+    if let Some(parent) = version_path.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    let version_json = serde_json::to_string_pretty(&profile.version_info).context("failed to serialize version json")?;
+    fs::write(&version_path, version_json)
+        .write_context(&version_path, || format!("failed to write version json: {}", version_path.display()))?;
+    on_milestone(InstallerMilestone::Finished);
+    Ok(id)
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<fs::File>, name: &str) -> Result<Vec<u8>> {
+    let name = name.trim_start_matches('/');
+    let mut entry = archive
+        .by_name(name)
+        .with_context(|| format!("{name} not found in installer jar"))?;
+    let mut data = Vec::new();
+    entry
+        .read_to_end(&mut data)
+        .with_context(|| format!("failed to read {name} from installer"))?;
+    Ok(data)
+}
+
+/// Library path of a `group:artifact:version[:classifier][@ext]` coordinate
+fn artifact_path(libraries_dir: &Path, coords: &str) -> Result<PathBuf> {
+    let (name, ext) = match coords.split_once('@') {
+        Some((name, ext)) => (name, Some(ext)),
+        None => (coords, None),
+    };
+    let mut maven_path = maven_path_from_name(name).with_context(|| format!("invalid artifact: {coords}"))?;
+    if let Some(ext) = ext {
+        maven_path = format!("{}.{ext}", maven_path.trim_end_matches(".jar"));
+    }
+    Ok(libraries_dir.join(maven_path))
+}
+
+/// Value of a `data` entry: `[artifact]`, `'literal'`, a `/path` inside the
+/// installer jar (extracted to `extract_dir`), or a plain literal
+fn resolve_data_value(
+    paths: &Paths,
+    archive: &mut zip::ZipArchive<fs::File>,
+    extract_dir: &Path,
+    value: &str,
+) -> Result<String> {
+    if let Some(coords) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return Ok(artifact_path(&paths.minecraft_libraries, coords)?.to_string_lossy().into_owned());
+    }
+    if let Some(literal) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return Ok(literal.to_string());
+    }
+    if let Some(entry) = value.strip_prefix('/') {
+        let target = extract_dir.join(entry);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+        }
+        let data = read_entry(archive, entry)?;
+        fs::write(&target, data).write_context(&target, || format!("failed to write {}", target.display()))?;
+        return Ok(target.to_string_lossy().into_owned());
+    }
+    Ok(value.to_string())
+}
+
+/// Download a library, or extract it from the installer's bundled `maven/`
+/// folder when it has no URL
+fn ensure_install_library(
+    paths: &Paths,
+    archive: &mut zip::ZipArchive<fs::File>,
+    library: &InstallLibrary,
+) -> Result<()> {
+    let Some(artifact) = library.downloads.as_ref().and_then(|d| d.artifact.as_ref()) else {
+        return Ok(());
+    };
+    let target = paths.minecraft_library_path(&artifact.path);
+    if !artifact.url.is_empty() {
+        return download_with_sha1(&artifact.url, &target, artifact.sha1.as_deref());
+    }
+    let up_to_date = match &artifact.sha1 {
+        Some(expected) => sha1_file(&target).is_ok_and(|actual| actual.eq_ignore_ascii_case(expected)),
+        None => target.exists(),
+    };
+    if up_to_date {
+        return Ok(());
+    }
+    let data = read_entry(archive, &format!("maven/{}", artifact.path))
+        .with_context(|| format!("library {} has no download", library.name))?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    fs::write(&target, data).write_context(&target, || format!("failed to write {}", target.display()))
+}
+
+/// Substitute `{KEY}` tokens from `data`; `[artifact]` becomes a library path
+fn expand_arg(libraries_dir: &Path, arg: &str, data: &HashMap<String, String>) -> Result<String> {
+    if let Some(coords) = arg.strip_prefix('[').and_then(|a| a.strip_suffix(']')) {
+        return Ok(artifact_path(libraries_dir, coords)?.to_string_lossy().into_owned());
+    }
+    let mut out = String::new();
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let key = &rest[start + 1..start + len];
+        let value = data
+            .get(key)
+            .with_context(|| format!("installer references unknown value {{{key}}}"))?;
+        out.push_str(&rest[..start]);
+        out.push_str(value);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out.trim_matches('\'').to_string())
+}
+
+fn jar_main_class(jar: &Path) -> Result<String> {
+    let file = fs::File::open(jar).with_context(|| format!("failed to open processor jar: {}", jar.display()))?;
+    let mut archive =
+        zip::ZipArchive::new(file).with_context(|| format!("failed to read processor jar: {}", jar.display()))?;
+    let manifest = String::from_utf8_lossy(&read_entry(&mut archive, "META-INF/MANIFEST.MF")?).into_owned();
+    // Manifest lines wrap at 72 bytes with a leading space on continuations
+    let manifest = manifest.replace("\r\n", "\n").replace("\n ", "");
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|class| class.trim().to_string())
+        .with_context(|| format!("no Main-Class in processor jar: {}", jar.display()))
+}
+
+/// Expected outputs of a processor, with their SHA-1
+fn processor_outputs(
+    libraries_dir: &Path,
+    processor: &Processor,
+    data: &HashMap<String, String>,
+) -> Result<Vec<(PathBuf, String)>> {
+    processor
+        .outputs
+        .iter()
+        .map(|(path, sha1)| {
+            let path = expand_arg(libraries_dir, path, data)?;
+            Ok((PathBuf::from(path), expand_arg(libraries_dir, sha1, data)?))
+        })
+        .collect()
+}
+
+fn run_processor(
+    libraries_dir: &Path,
+    java: &str,
+    processor: &Processor,
+    data: &HashMap<String, String>,
+) -> Result<()> {
+    let outputs = processor_outputs(libraries_dir, processor, data)?;
+    let up_to_date = |outputs: &[(PathBuf, String)]| {
+        outputs
+            .iter()
+            .all(|(path, sha1)| sha1_file(path).is_ok_and(|actual| actual.eq_ignore_ascii_case(sha1)))
+    };
+    if !outputs.is_empty() && up_to_date(&outputs) {
+        return Ok(());
+    }
+
+    let jar = artifact_path(libraries_dir, &processor.jar)?;
+    let main_class = jar_main_class(&jar)?;
+    let mut classpath = vec![jar];
+    for coords in &processor.classpath {
+        classpath.push(artifact_path(libraries_dir, coords)?);
+    }
+    let sep = if cfg!(windows) { ";" } else { ":" };
+    let classpath = classpath
+        .iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join(sep);
+    let args = processor
+        .args
+        .iter()
+        .map(|arg| expand_arg(libraries_dir, arg, data))
+        .collect::<Result<Vec<_>>>()?;
+
+    let output = Command::new(java)
+        .arg("-cp")
+        .arg(&classpath)
+        .arg(&main_class)
+        .args(&args)
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("failed to run installer processor {}", processor.jar))?;
+    if !output.status.success() {
+        let log: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .chain(String::from_utf8_lossy(&output.stderr).lines())
+            .map(str::to_string)
+            .collect();
+        bail!(
+            "installer processor {} failed with status {}{}",
+            processor.jar,
+            output.status,
+            format_log_tail(&log)
+        );
+    }
+    if !up_to_date(&outputs) {
+        bail!("installer processor {} produced unexpected output", processor.jar);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_milestone() {
+        assert_eq!(parse_milestone("Extracting json"), Some(InstallerMilestone::ExtractingFiles));
+        assert_eq!(
+            parse_milestone("Downloading libraries"),
+            Some(InstallerMilestone::DownloadingLibraries)
+        );
+        assert_eq!(
+            parse_milestone("  Building Processors"),
+            Some(InstallerMilestone::RunningProcessors)
+        );
+        assert_eq!(
+            parse_milestone("Successfully installed client into launcher."),
+            Some(InstallerMilestone::Finished)
+        );
+        assert_eq!(parse_milestone("  File exists: Checksum validated."), None);
+    }
+
+    #[test]
+    fn test_classify_installer_output() {
+        let log = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        let java = log(&[
+            "Downloading libraries",
+            "Failed to download library: https://maven.minecraftforge.net/x.jar",
+            "Exception in thread \"main\" java.lang.UnsupportedClassVersionError: net/minecraftforge/installer/SimpleInstaller",
+        ]);
+        let (kind, line) = classify_installer_output(&java).unwrap();
+        assert_eq!(kind, InstallerErrorKind::WrongJava);
+        assert!(line.contains("UnsupportedClassVersionError"));
+
+        let jar = log(&["java.nio.file.NoSuchFileException: /shard/minecraft/versions/1.20.1/1.20.1.jar"]);
+        assert_eq!(classify_installer_output(&jar).unwrap().0, InstallerErrorKind::MissingVanillaJar);
+
+        let network = log(&["java.net.UnknownHostException: maven.minecraftforge.net"]);
+        assert_eq!(classify_installer_output(&network).unwrap().0, InstallerErrorKind::Network);
+
+        assert!(classify_installer_output(&log(&["Processing: x", "Exit code 1"])).is_none());
+    }
+
+    #[test]
+    fn test_parse_install_profile() {
+        let modern = br#"{"version": "1.20.1-forge-47.3.0", "json": "/version.json", "processors": []}"#;
+        match parse_install_profile(modern).unwrap() {
+            InstallerFormat::Processors(profile) => assert_eq!(profile.version, "1.20.1-forge-47.3.0"),
+            InstallerFormat::Legacy(_) => panic!("expected processor format"),
+        }
+
+        let legacy = br#"{
+            "install": {
+                "path": "net.minecraftforge:forge:1.7.10-10.13.4.1614-1.7.10",
+                "filePath": "forge-1.7.10-10.13.4.1614-1.7.10-universal.jar",
+                "target": "1.7.10-Forge10.13.4.1614-1.7.10"
+            },
+            "versionInfo": {"id": "1.7.10-Forge10.13.4.1614-1.7.10", "libraries": []}
+        }"#;
+        match parse_install_profile(legacy).unwrap() {
+            InstallerFormat::Legacy(profile) => {
+                assert_eq!(profile.install.file_path, "forge-1.7.10-10.13.4.1614-1.7.10-universal.jar");
+                assert_eq!(profile.version_info["id"], "1.7.10-Forge10.13.4.1614-1.7.10");
+            }
+            InstallerFormat::Processors(_) => panic!("expected legacy format"),
+        }
+    }
+
+    #[test]
+    fn test_expand_arg() {
+        let libraries = Path::new("/shard/libraries");
+        let data = HashMap::from([
+            ("SIDE".to_string(), "client".to_string()),
+            ("MC_SLIM".to_string(), "/lib/slim.jar".to_string()),
+        ]);
+        assert_eq!(expand_arg(libraries, "--side={SIDE}", &data).unwrap(), "--side=client");
+        assert_eq!(expand_arg(libraries, "{MC_SLIM}", &data).unwrap(), "/lib/slim.jar");
+        assert!(expand_arg(libraries, "{MISSING}", &data).is_err());
+        let path = expand_arg(libraries, "[net.minecraft:client:1.20.1:mappings@txt]", &data).unwrap();
+        assert!(path.ends_with("net/minecraft/client/1.20.1/client-1.20.1-mappings.txt"));
+    }
+}
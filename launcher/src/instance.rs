@@ -1,38 +1,112 @@
+use crate::config::load_config;
+use crate::disk::WriteContext;
 use crate::paths::Paths;
-use crate::profile::{ContentRef, Profile};
-use crate::store::{ContentKind, content_store_path};
-use crate::util::{copy_dir_merge, sanitize_filename, unique_path};
-use anyhow::{Context, Result};
+use crate::library::{Library, LibraryContentType, LibraryItem, LibraryItemInput};
+use crate::profile::{ContentRef, Profile, list_profiles, load_profile, save_profile};
+use crate::shader_settings::is_shader_settings_file;
+use crate::store::{ContentKind, HashAlgorithm, configured_store_hash, content_store_path, hash_file_with, store_content};
+use crate::util::{copy_dir_merge, sanitize_filename};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// Manifest of the content files materialization placed in an instance
+const MANAGED_FILE: &str = ".shard-managed.json";
+
+/// Suffix of disabled content; loaders and the game skip these files
+pub const DISABLED_SUFFIX: &str = ".disabled";
+
+/// Suffix given to changed content files the profile stopped using
+const MODIFIED_SUFFIX: &str = ".modified";
+
+/// How content from the store is placed into instance directories
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstanceLinkMode {
+    /// Hard links look like regular files to the game and to sandboxes that
+    /// can't follow links into the store; copied where the store is on
+    /// another filesystem. A file written in place changes the store copy
+    /// too, so this is opt-in.
+    Hardlink,
+    /// Symbolic links, falling back to hard links and then copies
+    #[default]
+    Symlink,
+    /// Independent copies in every instance
+    Copy,
+}
+
+impl InstanceLinkMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InstanceLinkMode::Hardlink => "hardlink",
+            InstanceLinkMode::Symlink => "symlink",
+            InstanceLinkMode::Copy => "copy",
+        }
+    }
+}
+
+impl FromStr for InstanceLinkMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "hardlink" | "hard" => Ok(InstanceLinkMode::Hardlink),
+            "symlink" | "sym" => Ok(InstanceLinkMode::Symlink),
+            "copy" => Ok(InstanceLinkMode::Copy),
+            other => bail!("unknown link mode: {other} (expected hardlink, symlink or copy)"),
+        }
+    }
+}
+
+/// Content files placed in an instance, by path relative to the instance
+/// (`mods/sodium.jar`) with the hash they were placed from. Anything else in
+/// the content folders was added by the user and is left alone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ManagedFiles {
+    files: BTreeMap<String, String>,
+    /// Placed files the user changed since; they are kept as they are
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    modified: BTreeSet<String>,
+}
+
+fn load_managed_files(instance_dir: &Path) -> Result<Option<ManagedFiles>> {
+    let path = instance_dir.join(MANAGED_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let managed = serde_json::from_str(&data).with_context(|| format!("failed to parse {}", path.display()))?;
+    Ok(Some(managed))
+}
+
+/// When the manifest was last written, i.e. when the instance was last synced
+fn managed_files_written(instance_dir: &Path) -> Option<SystemTime> {
+    fs::metadata(instance_dir.join(MANAGED_FILE)).and_then(|meta| meta.modified()).ok()
+}
+
+fn save_managed_files(instance_dir: &Path, managed: &ManagedFiles) -> Result<()> {
+    let path = instance_dir.join(MANAGED_FILE);
+    let data = serde_json::to_string_pretty(managed).context("failed to serialize managed files")?;
+    fs::write(&path, data).write_context(&path, || format!("failed to write {}", path.display()))
+}
+
+/// A store file the profile wants in an instance folder
+struct DesiredFile {
+    name: String,
+    hash: String,
+    store_path: PathBuf,
+}
 
 pub fn materialize_instance(paths: &Paths, profile: &Profile) -> Result<std::path::PathBuf> {
+    paths.ensure_writable()?;
     let instance_dir = paths.instance_dir(&profile.id);
     fs::create_dir_all(&instance_dir)
         .with_context(|| format!("failed to create instance dir: {}", instance_dir.display()))?;
-
-    sync_dir(&instance_dir.join("mods"))?;
-    sync_dir(&instance_dir.join("resourcepacks"))?;
-    sync_dir(&instance_dir.join("shaderpacks"))?;
-
-    populate_dir(
-        paths,
-        &profile.mods,
-        ContentKind::Mod,
-        &instance_dir.join("mods"),
-    )?;
-    populate_dir(
-        paths,
-        &profile.resourcepacks,
-        ContentKind::ResourcePack,
-        &instance_dir.join("resourcepacks"),
-    )?;
-    populate_dir(
-        paths,
-        &profile.shaderpacks,
-        ContentKind::ShaderPack,
-        &instance_dir.join("shaderpacks"),
-    )?;
+    sync_instance_content(paths, profile)?;
 
     let overrides_dir = paths.profile_overrides(&profile.id);
     if overrides_dir.exists() {
@@ -42,6 +116,38 @@ pub fn materialize_instance(paths: &Paths, profile: &Profile) -> Result<std::pat
     Ok(instance_dir)
 }
 
+/// Bring the instance's content folders in line with the profile. Only files
+/// that changed are touched: content removed from the profile is deleted,
+/// disabled content gets the [`DISABLED_SUFFIX`], new content is linked in
+/// and files the user added stay.
+pub fn sync_instance_content(paths: &Paths, profile: &Profile) -> Result<()> {
+    let mode = load_config(paths).map(|config| config.instance_links).unwrap_or_default();
+    let instance_dir = paths.instance_dir(&profile.id);
+    let previous = load_managed_files(&instance_dir)?;
+    let synced_at = managed_files_written(&instance_dir);
+    let groups = [
+        (&profile.mods, ContentKind::Mod, "mods"),
+        (&profile.resourcepacks, ContentKind::ResourcePack, "resourcepacks"),
+        (&profile.shaderpacks, ContentKind::ShaderPack, "shaderpacks"),
+    ];
+    let mut managed = ManagedFiles::default();
+    for (items, kind, folder) in groups {
+        let dir = instance_dir.join(folder);
+        match &previous {
+            Some(_) => fs::create_dir_all(&dir)
+                .with_context(|| format!("failed to create directory: {}", dir.display()))?,
+            // Instances materialized before the manifest only held our files
+            // (shader settings live next to the packs; keep the user's tuning)
+            None if matches!(kind, ContentKind::ShaderPack) => sync_dir_keeping(&dir, is_shader_settings_file)?,
+            None => sync_dir(&dir)?,
+        }
+        let desired = desired_files(paths, items, kind);
+        let store_path = |hash: &str| content_store_path(paths, kind, hash);
+        sync_content_dir(&instance_dir, folder, &desired, previous.as_ref(), synced_at, mode, &store_path, &mut managed)?;
+    }
+    save_managed_files(&instance_dir, &managed)
+}
+
 fn sync_dir(path: &Path) -> Result<()> {
     if path.exists() {
         fs::remove_dir_all(path)
@@ -52,22 +158,30 @@ fn sync_dir(path: &Path) -> Result<()> {
     Ok(())
 }
 
-fn populate_dir(
-    paths: &Paths,
-    items: &[ContentRef],
-    kind: ContentKind,
-    target_dir: &Path,
-) -> Result<()> {
-    let default_ext = match kind {
-        ContentKind::Mod => "jar",
-        ContentKind::ResourcePack | ContentKind::ShaderPack => "zip",
-        ContentKind::Skin => "png",
-    };
+/// Like [`sync_dir`], but files whose name passes `keep` stay
+fn sync_dir_keeping(path: &Path, keep: fn(&str) -> bool) -> Result<()> {
+    fs::create_dir_all(path)
+        .with_context(|| format!("failed to create directory: {}", path.display()))?;
+    for entry in fs::read_dir(path).with_context(|| format!("failed to read dir: {}", path.display()))? {
+        let entry = entry.context("failed to read dir entry")?;
+        let file_type = entry.file_type().context("failed to read entry type")?;
+        let entry_path = entry.path();
+        if file_type.is_dir() {
+            fs::remove_dir_all(&entry_path)
+                .with_context(|| format!("failed to remove directory: {}", entry_path.display()))?;
+        } else if !keep(&entry.file_name().to_string_lossy()) {
+            fs::remove_file(&entry_path)
+                .with_context(|| format!("failed to remove file: {}", entry_path.display()))?;
+        }
+    }
+    Ok(())
+}
 
+/// Items found in the store, with unique file names
+fn desired_files(paths: &Paths, items: &[ContentRef], kind: ContentKind) -> Vec<DesiredFile> {
+    let mut used = HashSet::new();
+    let mut desired = Vec::new();
     for item in items {
-        if !item.enabled {
-            continue;
-        }
         let store_path = content_store_path(paths, kind, &item.hash);
         if !store_path.exists() {
             eprintln!(
@@ -79,33 +193,528 @@ fn populate_dir(
             continue;
         }
 
-        let file_name = item.file_name.as_deref().unwrap_or(&item.name);
-        let mut file_name = sanitize_filename(file_name);
-        if Path::new(&file_name).extension().is_none() {
-            file_name.push('.');
-            file_name.push_str(default_ext);
+        let file_name = instance_file_name(item, kind);
+        let suffix = if item.enabled { "" } else { DISABLED_SUFFIX };
+        let mut name = format!("{file_name}{suffix}");
+        let mut idx = 1;
+        while !used.insert(name.clone()) {
+            let path = Path::new(&file_name);
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            name = match path.extension() {
+                Some(ext) => format!("{stem}-{idx}.{}{suffix}", ext.to_string_lossy()),
+                None => format!("{stem}-{idx}{suffix}"),
+            };
+            idx += 1;
         }
+        desired.push(DesiredFile {
+            name,
+            hash: item.hash.clone(),
+            store_path,
+        });
+    }
+    desired
+}
 
-        let target_path = unique_path(target_dir, &file_name);
-        link_or_copy(&store_path, &target_path)?;
+/// Diff one content folder against what was placed last time. Managed files
+/// that are no longer wanted (or now come from another hash) are removed,
+/// unchanged ones kept and missing ones linked. Files the user changed since
+/// they were placed are kept with a warning, and a user file in the way of a
+/// wanted one is adopted when it has the same content and kept otherwise.
+/// `store_path` locates the store copy of a hash.
+#[allow(clippy::too_many_arguments)]
+fn sync_content_dir(
+    instance_dir: &Path,
+    folder: &str,
+    desired: &[DesiredFile],
+    previous: Option<&ManagedFiles>,
+    synced_at: Option<SystemTime>,
+    mode: InstanceLinkMode,
+    store_path: &dyn Fn(&str) -> PathBuf,
+    managed: &mut ManagedFiles,
+) -> Result<()> {
+    let key = |name: &str| format!("{folder}/{name}");
+    let previous_hash = |key: &str| previous.and_then(|previous| previous.files.get(key));
+    let prefix = format!("{folder}/");
+
+    if let Some(previous) = previous {
+        for (rel, hash) in previous.files.iter().filter(|(rel, _)| rel.starts_with(&prefix)) {
+            let wanted = desired.iter().any(|file| key(&file.name) == *rel && file.hash == *hash);
+            if wanted {
+                continue;
+            }
+            let path = instance_dir.join(rel);
+            // The user's changes outlive the content they were made to
+            let changed = previous.modified.contains(rel) || (path.is_file() && !matches_hash(&path, hash)?);
+            if changed {
+                detach_from_store(&path, &store_path(hash))?;
+                let mut kept = path.clone().into_os_string();
+                kept.push(MODIFIED_SUFFIX);
+                fs::rename(&path, &kept).with_context(|| format!("failed to move aside {}", path.display()))?;
+                eprintln!(
+                    "warning: {} was changed since it was placed and is no longer used; kept it as {}",
+                    path.display(),
+                    Path::new(&kept).display()
+                );
+                continue;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    return Err(err).with_context(|| format!("failed to remove stale file: {}", path.display()));
+                }
+            }
+        }
+    }
+
+    for file in desired {
+        let rel = key(&file.name);
+        let path = instance_dir.join(&rel);
+        if let Ok(meta) = fs::symlink_metadata(&path) {
+            if previous_hash(&rel) == Some(&file.hash) {
+                let flagged = previous.is_some_and(|previous| previous.modified.contains(&rel));
+                if flagged || is_modified(&path, &file.store_path, &file.hash, synced_at)? {
+                    detach_from_store(&path, &file.store_path)?;
+                    let id = instance_dir.file_name().unwrap_or_default().to_string_lossy();
+                    eprintln!(
+                        "warning: keeping {}, changed since it was placed; `shard profile adopt {id} --modified` saves it as new content, deleting it restores the profile's copy",
+                        path.display()
+                    );
+                    managed.modified.insert(rel.clone());
+                }
+                managed.files.insert(rel, file.hash.clone());
+                continue;
+            } else if meta.is_file() && matches_hash(&path, &file.hash)? {
+                managed.files.insert(rel, file.hash.clone());
+                continue;
+            } else {
+                eprintln!("warning: keeping user file {} in place of the profile's copy", path.display());
+                continue;
+            }
+        }
+        link_or_copy(&file.store_path, &path, mode)?;
+        managed.files.insert(rel, file.hash.clone());
     }
 
     Ok(())
 }
 
-fn link_or_copy(src: &Path, dst: &Path) -> Result<()> {
-    if let Err(err) = symlink_file(src, dst) {
-        fs::copy(src, dst).with_context(|| {
-            format!(
-                "failed to copy {} to {} after symlink error: {err}",
-                src.display(),
-                dst.display()
-            )
-        })?;
+/// Content folders of an instance and the extension of their files
+const CONTENT_FOLDERS: [(ContentKind, &str, &str); 3] = [
+    (ContentKind::Mod, "mods", ".jar"),
+    (ContentKind::ResourcePack, "resourcepacks", ".zip"),
+    (ContentKind::ShaderPack, "shaderpacks", ".zip"),
+];
+
+fn content_items_mut(profile: &mut Profile, kind: ContentKind) -> &mut Vec<ContentRef> {
+    match kind {
+        ContentKind::Mod => &mut profile.mods,
+        ContentKind::ResourcePack => &mut profile.resourcepacks,
+        ContentKind::ShaderPack | ContentKind::Skin => &mut profile.shaderpacks,
     }
+}
+
+/// A file the user put into an instance content folder, or changed there,
+/// now part of the profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdoptedFile {
+    /// Path relative to the instance, e.g. `mods/sodium.jar`
+    pub path: String,
+    pub content: ContentRef,
+    /// Hash of the content the file was placed from, for changed files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaces: Option<String>,
+}
+
+/// Add the files the user dropped into the instance's mods, resourcepacks
+/// and shaderpacks folders to the store, the library and the profile, which
+/// is saved when anything was adopted. Files in place of the profile's own
+/// content, or with the same content as one of them, are left alone.
+pub fn adopt_unmanaged_files(paths: &Paths, profile: &mut Profile) -> Result<Vec<AdoptedFile>> {
+    let instance_dir = paths.instance_dir(&profile.id);
+    // Without the manifest every file counts as ours and the next sync
+    // replaces the folders anyway
+    let Some(mut managed) = load_managed_files(&instance_dir)? else {
+        return Ok(Vec::new());
+    };
+    paths.ensure_writable()?;
+    let algorithm = configured_store_hash(paths);
+    let profile_id = profile.id.clone();
+    let mut library = None;
+    let mut adopted = Vec::new();
+    for (kind, folder, extension) in CONTENT_FOLDERS {
+        let dir = instance_dir.join(folder);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let items = content_items_mut(profile, kind);
+        let taken: HashSet<String> = items
+            .iter()
+            .map(|item| {
+                let suffix = if item.enabled { "" } else { DISABLED_SUFFIX };
+                format!("{}{suffix}", instance_file_name(item, kind))
+            })
+            .collect();
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+
+        for name in names {
+            let rel = format!("{folder}/{name}");
+            let enabled = !name.ends_with(DISABLED_SUFFIX);
+            let file_name = name.strip_suffix(DISABLED_SUFFIX).unwrap_or(&name);
+            if managed.files.contains_key(&rel)
+                || taken.contains(&name)
+                || name.starts_with('.')
+                || !file_name.to_ascii_lowercase().ends_with(extension)
+            {
+                continue;
+            }
+            let path = dir.join(&name);
+            let stored = store_content(paths, kind, &path, None, Some(file_name.to_string()), algorithm)?;
+            if items.iter().any(|item| item.hash == stored.hash) {
+                continue;
+            }
+
+            let content = ContentRef {
+                name: stored.name,
+                hash: stored.hash,
+                version: None,
+                source: None,
+                file_name: Some(stored.file_name),
+                platform: None,
+                project_id: None,
+                version_id: None,
+                enabled,
+                pinned: false,
+            };
+            let library = match &mut library {
+                Some(library) => library,
+                None => library.insert(Library::from_paths(paths)?),
+            };
+            add_to_library(library, &profile_id, kind, &content, &path, stored.sha1, stored.sha512)?;
+            managed.files.insert(rel.clone(), content.hash.clone());
+            items.push(content.clone());
+            adopted.push(AdoptedFile {
+                path: rel,
+                content,
+                replaces: None,
+            });
+        }
+    }
+
+    if !adopted.is_empty() {
+        save_profile(paths, profile)?;
+        save_managed_files(&instance_dir, &managed)?;
+    }
+    Ok(adopted)
+}
+
+/// Save the content files the user changed inside the instance as new
+/// content: each is stored, added to the library as the replacement of the
+/// item it was placed from, and from then on the profile refers to it. The
+/// profile is saved when anything was re-imported.
+pub fn reimport_modified_files(paths: &Paths, profile: &mut Profile) -> Result<Vec<AdoptedFile>> {
+    let instance_dir = paths.instance_dir(&profile.id);
+    let Some(mut managed) = load_managed_files(&instance_dir)? else {
+        return Ok(Vec::new());
+    };
+    paths.ensure_writable()?;
+    let synced_at = managed_files_written(&instance_dir);
+    let algorithm = configured_store_hash(paths);
+    let mut library = None;
+    let mut reimported = Vec::new();
+    for (kind, folder, _) in CONTENT_FOLDERS {
+        let prefix = format!("{folder}/");
+        let placed: Vec<(String, String)> = managed
+            .files
+            .iter()
+            .filter(|(rel, _)| rel.starts_with(&prefix))
+            .map(|(rel, hash)| (rel.clone(), hash.clone()))
+            .collect();
+        for (rel, hash) in placed {
+            let path = instance_dir.join(&rel);
+            let store_path = content_store_path(paths, kind, &hash);
+            if !path.is_file() || !(managed.modified.contains(&rel) || is_modified(&path, &store_path, &hash, synced_at)?) {
+                continue;
+            }
+            let items = content_items_mut(profile, kind);
+            let Some(item) = items.iter_mut().find(|item| item.hash == hash) else {
+                continue;
+            };
+            detach_from_store(&path, &store_path)?;
+            let stored = store_content(paths, kind, &path, None, item.file_name.clone(), algorithm)?;
+            let previous = std::mem::replace(
+                item,
+                ContentRef {
+                    name: item.name.clone(),
+                    hash: stored.hash,
+                    version: None,
+                    source: None,
+                    file_name: item.file_name.clone(),
+                    platform: None,
+                    project_id: None,
+                    version_id: None,
+                    enabled: item.enabled,
+                    pinned: item.pinned,
+                },
+            );
+            let content = item.clone();
+
+            let library = match &mut library {
+                Some(library) => library,
+                None => library.insert(Library::from_paths(paths)?),
+            };
+            let old_item = match library.get_item_by_hash(&previous.hash)? {
+                Some(old_item) => old_item,
+                None => library.add_item(&LibraryItemInput {
+                    hash: previous.hash.clone(),
+                    content_type: Some(LibraryContentType::from_content_kind(kind).as_str().to_string()),
+                    name: Some(previous.name.clone()),
+                    file_name: previous.file_name.clone(),
+                    source_url: previous.source.clone(),
+                    source_platform: previous.platform.clone(),
+                    source_project_id: previous.project_id.clone(),
+                    source_version: previous.version.clone(),
+                    ..Default::default()
+                })?,
+            };
+            let new_item = add_to_library(library, &profile.id, kind, &content, &path, stored.sha1, stored.sha512)?;
+            library.record_replacement(old_item.id, new_item.id)?;
+
+            managed.files.insert(rel.clone(), content.hash.clone());
+            managed.modified.remove(&rel);
+            reimported.push(AdoptedFile {
+                path: rel,
+                content,
+                replaces: Some(previous.hash),
+            });
+        }
+    }
+
+    if !reimported.is_empty() {
+        save_profile(paths, profile)?;
+        save_managed_files(&instance_dir, &managed)?;
+    }
+    Ok(reimported)
+}
+
+/// Record a file taken from an instance as a local library item of the
+/// profile
+fn add_to_library(
+    library: &Library,
+    profile_id: &str,
+    kind: ContentKind,
+    content: &ContentRef,
+    path: &Path,
+    sha1: String,
+    sha512: String,
+) -> Result<LibraryItem> {
+    let content_type = LibraryContentType::from_content_kind(kind);
+    let item = library.add_item(&LibraryItemInput {
+        hash: content.hash.clone(),
+        content_type: Some(content_type.as_str().to_string()),
+        name: Some(content.name.clone()),
+        file_name: content.file_name.clone(),
+        file_size: file_len(path).map(|len| len as i64),
+        source_platform: Some("local".to_string()),
+        sha1: Some(sha1),
+        sha512: Some(sha512),
+        ..Default::default()
+    })?;
+    library.link_item_to_profile(item.id, profile_id, content_type)?;
+    Ok(item)
+}
+
+/// Whether a placed file no longer has the content it was placed from. Only
+/// files of another size, or written to since `synced_at`, are hashed. A file
+/// linked to the store shares its size and timestamp with the store copy, so
+/// only the timestamp tells.
+fn is_modified(path: &Path, store_path: &Path, hash: &str, synced_at: Option<SystemTime>) -> Result<bool> {
+    if !is_same_file(path, store_path) && file_len(path) != file_len(store_path) {
+        return Ok(true);
+    }
+    let written = fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    match (written, synced_at) {
+        // Timestamps are coarse; a write in the same tick as the sync is hashed
+        (Some(written), Some(synced_at)) if written < synced_at => Ok(false),
+        _ => Ok(!matches_hash(path, hash)?),
+    }
+}
+
+/// A changed file still linked to the store was written through the link,
+/// so the store copy no longer has the content its hash names. The instance
+/// keeps the changes in a file of its own and the store copy is removed, so
+/// no other instance gets linked to it; `shard profile install` downloads it
+/// again.
+fn detach_from_store(path: &Path, store_path: &Path) -> Result<()> {
+    if !is_same_file(path, store_path) {
+        return Ok(());
+    }
+    let is_symlink = fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink());
+    if is_symlink {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".shard-detach");
+        fs::copy(path, &tmp).with_context(|| format!("failed to copy {}", path.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("failed to replace {}", path.display()))?;
+    }
+    fs::remove_file(store_path).with_context(|| format!("failed to remove {}", store_path.display()))?;
+    eprintln!(
+        "warning: {} was changed through its link to the store, which changed the store copy too; removed the store copy",
+        path.display()
+    );
     Ok(())
 }
 
+fn file_len(path: &Path) -> Option<u64> {
+    fs::metadata(path).ok().map(|meta| meta.len())
+}
+
+/// Whether a file's content has the given store hash (`sha256:<hex>` or bare)
+fn matches_hash(path: &Path, hash: &str) -> Result<bool> {
+    let (algorithm, expected) = match hash.split_once(':') {
+        Some((_, hex)) => (HashAlgorithm::of(hash), hex),
+        None => (HashAlgorithm::Sha256, hash),
+    };
+    Ok(hash_file_with(path, algorithm)?.eq_ignore_ascii_case(expected))
+}
+
+/// File name a content item gets inside the instance directory
+pub fn instance_file_name(item: &ContentRef, kind: ContentKind) -> String {
+    let default_ext = match kind {
+        ContentKind::Mod => "jar",
+        ContentKind::ResourcePack | ContentKind::ShaderPack => "zip",
+        ContentKind::Skin => "png",
+    };
+    let file_name = item.file_name.as_deref().unwrap_or(&item.name);
+    let mut file_name = sanitize_filename(file_name);
+    if Path::new(&file_name).extension().is_none() {
+        file_name.push('.');
+        file_name.push_str(default_ext);
+    }
+    file_name
+}
+
+/// Store files never change, so a hard link is as good as a symlink where
+/// symlinks are unavailable (e.g. Windows without developer mode). Hard links
+/// can't cross filesystems, so a store on another disk gets copies.
+fn link_or_copy(src: &Path, dst: &Path, mode: InstanceLinkMode) -> Result<()> {
+    let err = match link_file(src, dst, mode) {
+        Ok(()) => return Ok(()),
+        Err(err) => err,
+    };
+    fs::copy(src, dst).with_context(|| {
+        format!(
+            "failed to copy {} to {} after link error: {err}",
+            src.display(),
+            dst.display()
+        )
+    })?;
+    Ok(())
+}
+
+/// Link without copying; `Copy` mode always fails
+fn link_file(src: &Path, dst: &Path, mode: InstanceLinkMode) -> std::io::Result<()> {
+    match mode {
+        InstanceLinkMode::Hardlink => fs::hard_link(src, dst),
+        InstanceLinkMode::Symlink => symlink_file(src, dst).or_else(|_| fs::hard_link(src, dst)),
+        InstanceLinkMode::Copy => Err(std::io::Error::other("instance links are set to copy")),
+    }
+}
+
+/// Result of [`dedupe_instances`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DedupeReport {
+    pub instances: usize,
+    /// Copies replaced by links to the store
+    pub linked: usize,
+    pub bytes_saved: u64,
+    /// Files left alone, with the reason
+    pub skipped: Vec<String>,
+}
+
+/// Replace content copied into instances with links to the store, using the
+/// configured link mode. Files that differ from their store copy were changed
+/// in place and are left alone.
+pub fn dedupe_instances(paths: &Paths, dry_run: bool) -> Result<DedupeReport> {
+    let mode = load_config(paths).map(|config| config.instance_links).unwrap_or_default();
+    if mode == InstanceLinkMode::Copy {
+        bail!("instance links are set to copy; use `shard config set-instance-links hardlink` first");
+    }
+
+    let mut report = DedupeReport::default();
+    for id in list_profiles(paths)? {
+        let instance_dir = paths.instance_dir(&id);
+        if !instance_dir.exists() {
+            continue;
+        }
+        let profile = load_profile(paths, &id)?;
+        report.instances += 1;
+        let groups = [
+            (&profile.mods, ContentKind::Mod, "mods"),
+            (&profile.resourcepacks, ContentKind::ResourcePack, "resourcepacks"),
+            (&profile.shaderpacks, ContentKind::ShaderPack, "shaderpacks"),
+        ];
+        for (items, kind, folder) in groups {
+            for item in items.iter().filter(|item| item.enabled) {
+                let store_path = content_store_path(paths, kind, &item.hash);
+                let path = instance_dir.join(folder).join(instance_file_name(item, kind));
+                let Ok(meta) = fs::symlink_metadata(&path) else {
+                    continue;
+                };
+                if !meta.is_file() || !store_path.exists() || is_same_file(&path, &store_path) {
+                    continue;
+                }
+                let label = format!("{id}/{folder}/{}", path.file_name().unwrap_or_default().to_string_lossy());
+                if !matches_hash(&path, &item.hash)? {
+                    report.skipped.push(format!("{label}: changed since it was installed"));
+                    continue;
+                }
+                if !dry_run
+                    && let Err(err) = relink(&store_path, &path, mode)
+                {
+                    report.skipped.push(format!("{label}: {err}"));
+                    continue;
+                }
+                report.linked += 1;
+                report.bytes_saved += meta.len();
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Swap a file for a link to `src`, leaving it untouched if linking fails
+fn relink(src: &Path, path: &Path, mode: InstanceLinkMode) -> std::io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".shard-link");
+    let tmp = path.with_file_name(tmp_name);
+    let _ = fs::remove_file(&tmp);
+    link_file(src, &tmp, mode)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+#[cfg(unix)]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+/// File identity isn't available on stable Rust here; an already linked file
+/// is simply linked again
+#[cfg(not(unix))]
+fn is_same_file(_a: &Path, _b: &Path) -> bool {
+    false
+}
+
 #[cfg(unix)]
 fn symlink_file(src: &Path, dst: &Path) -> std::io::Result<()> {
     std::os::unix::fs::symlink(src, dst)
@@ -115,3 +724,173 @@ fn symlink_file(src: &Path, dst: &Path) -> std::io::Result<()> {
 fn symlink_file(src: &Path, dst: &Path) -> std::io::Result<()> {
     std::os::windows::fs::symlink_file(src, dst)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::TempDir;
+
+    #[test]
+    fn test_sync_content_dir_keeps_user_files() {
+        let dir = TempDir::new("instance-sync");
+        let store = dir.join("store");
+        let instance = dir.join("instance");
+        fs::create_dir_all(&store).unwrap();
+        fs::create_dir_all(instance.join("mods")).unwrap();
+        let desired = |name: &str, content: &str| {
+            let store_path = store.join(name);
+            fs::write(&store_path, content).unwrap();
+            let hash = format!("sha256:{}", hash_file_with(&store_path, HashAlgorithm::Sha256).unwrap());
+            DesiredFile {
+                name: name.to_string(),
+                hash,
+                store_path,
+            }
+        };
+        let sodium = desired("sodium.jar", "sodium");
+        let lithium = desired("lithium.jar", "lithium");
+        let iris = desired("iris.jar", "iris");
+        fs::write(instance.join("mods/custom.jar"), "custom").unwrap();
+        fs::write(instance.join("mods/iris.jar"), "iris").unwrap();
+
+        let mut first = ManagedFiles::default();
+        let wanted = [sodium, lithium, iris];
+        let no_store = |_: &str| PathBuf::new();
+        sync_content_dir(&instance, "mods", &wanted, None, None, InstanceLinkMode::Copy, &no_store, &mut first).unwrap();
+        assert_eq!(first.files.len(), 3, "identical user file is adopted");
+
+        let mut second = ManagedFiles::default();
+        let [sodium, _, iris] = wanted;
+        let wanted = [sodium, iris];
+        sync_content_dir(&instance, "mods", &wanted, Some(&first), None, InstanceLinkMode::Copy, &no_store, &mut second)
+            .unwrap();
+        assert!(instance.join("mods/sodium.jar").exists());
+        assert!(!instance.join("mods/lithium.jar").exists());
+        assert!(instance.join("mods/custom.jar").exists());
+        assert_eq!(second.files.len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sync_detects_edits_through_hard_links() {
+        use std::io::Write;
+
+        let dir = TempDir::new("instance-hardlink");
+        let store = dir.join("store");
+        let instance = dir.join("instance");
+        fs::create_dir_all(&store).unwrap();
+        fs::create_dir_all(instance.join("mods")).unwrap();
+        let store_path = store.join("pack.zip");
+        fs::write(&store_path, "pack").unwrap();
+        let hash = format!("sha256:{}", hash_file_with(&store_path, HashAlgorithm::Sha256).unwrap());
+        let wanted = [DesiredFile {
+            name: "pack.zip".to_string(),
+            hash,
+            store_path: store_path.clone(),
+        }];
+        let store_of = |_: &str| store_path.clone();
+        let sync = |previous: Option<&ManagedFiles>, synced_at: Option<SystemTime>| {
+            let mut managed = ManagedFiles::default();
+            let mode = InstanceLinkMode::Hardlink;
+            sync_content_dir(&instance, "mods", &wanted, previous, synced_at, mode, &store_of, &mut managed).unwrap();
+            managed
+        };
+
+        let first = sync(None, None);
+        let placed = instance.join("mods/pack.zip");
+        assert!(is_same_file(&placed, &store_path));
+        let synced_at = SystemTime::now();
+
+        // Unchanged linked files are left as they are
+        let second = sync(Some(&first), Some(synced_at));
+        assert!(second.modified.is_empty());
+
+        fs::OpenOptions::new().append(true).open(&placed).unwrap().write_all(b" edited").unwrap();
+        let third = sync(Some(&second), Some(synced_at));
+        assert!(third.modified.contains("mods/pack.zip"));
+        assert_eq!(fs::read_to_string(&placed).unwrap(), "pack edited");
+        assert!(!store_path.exists(), "the changed store copy is removed");
+    }
+    #[test]
+    fn test_adopt_unmanaged_files() {
+        use crate::fake_cdn::TempRoot;
+        use crate::library::LibraryFilter;
+        use crate::profile::{Runtime, create_profile};
+
+        let root = TempRoot::new("instance-adopt");
+        let paths = &root.paths;
+        let mut profile = create_profile(paths, "dropped", "1.21.1", None, Runtime::default()).unwrap();
+        materialize_instance(paths, &profile).unwrap();
+        let instance = paths.instance_dir("dropped");
+        fs::write(instance.join("mods/custom.jar"), "custom").unwrap();
+        fs::write(instance.join("mods/old.jar.disabled"), "old").unwrap();
+        fs::write(instance.join("mods/notes.txt"), "notes").unwrap();
+        fs::write(instance.join("resourcepacks/pack.zip"), "pack").unwrap();
+
+        let adopted = adopt_unmanaged_files(paths, &mut profile).unwrap();
+        let adopted: Vec<_> = adopted.iter().map(|file| (file.path.as_str(), file.content.enabled)).collect();
+        assert_eq!(
+            adopted,
+            [("mods/custom.jar", true), ("mods/old.jar.disabled", false), ("resourcepacks/pack.zip", true)]
+        );
+        let saved = load_profile(paths, "dropped").unwrap();
+        assert_eq!(saved.mods.len(), 2);
+        assert_eq!(saved.resourcepacks.len(), 1);
+        let library = Library::from_paths(paths).unwrap();
+        assert_eq!(library.list_items(&LibraryFilter::default()).unwrap().len(), 3);
+
+        materialize_instance(paths, &saved).unwrap();
+        assert_eq!(fs::read_to_string(instance.join("mods/custom.jar")).unwrap(), "custom");
+        assert!(instance.join("mods/old.jar.disabled").exists());
+        assert!(adopt_unmanaged_files(paths, &mut profile).unwrap().is_empty());
+    }
+    #[test]
+    fn test_modified_files_are_kept() {
+        use crate::fake_cdn::TempRoot;
+        use crate::profile::{Runtime, create_profile};
+        use crate::store::store_content;
+
+        let root = TempRoot::new("instance-modified");
+        let paths = &root.paths;
+        let source = paths.root.join("pack.zip");
+        fs::write(&source, "original pack").unwrap();
+        let stored = store_content(paths, ContentKind::ResourcePack, &source, None, None, HashAlgorithm::Sha256).unwrap();
+        let mut profile = create_profile(paths, "edited", "1.21.1", None, Runtime::default()).unwrap();
+        profile.resourcepacks.push(ContentRef {
+            name: stored.name,
+            hash: stored.hash.clone(),
+            version: None,
+            source: None,
+            file_name: Some(stored.file_name),
+            platform: None,
+            project_id: None,
+            version_id: None,
+            enabled: true,
+            pinned: false,
+        });
+        save_profile(paths, &profile).unwrap();
+        materialize_instance(paths, &profile).unwrap();
+
+        // Editors write a new file rather than into the store's hard link
+        let placed = paths.instance_dir("edited").join("resourcepacks/pack.zip");
+        fs::remove_file(&placed).unwrap();
+        fs::write(&placed, "modified pack").unwrap();
+        materialize_instance(paths, &profile).unwrap();
+        assert_eq!(fs::read_to_string(&placed).unwrap(), "modified pack");
+        materialize_instance(paths, &profile).unwrap();
+        assert_eq!(fs::read_to_string(&placed).unwrap(), "modified pack");
+
+        let reimported = reimport_modified_files(paths, &mut profile).unwrap();
+        assert_eq!(reimported.len(), 1);
+        assert_eq!(reimported[0].replaces.as_deref(), Some(stored.hash.as_str()));
+        let saved = load_profile(paths, "edited").unwrap();
+        assert_eq!(saved.resourcepacks[0].hash, reimported[0].content.hash);
+        let library = Library::from_paths(paths).unwrap();
+        let item = library.get_item_by_hash(&reimported[0].content.hash).unwrap().unwrap();
+        assert_eq!(library.get_item_history(item.id).unwrap().len(), 2);
+
+        materialize_instance(paths, &saved).unwrap();
+        assert_eq!(fs::read_to_string(&placed).unwrap(), "modified pack");
+        assert!(reimport_modified_files(paths, &mut profile).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Large auxiliary instance folders
+//!
+//! ReplayMod recordings and schematics grow far larger than the rest of an
+//! instance. Storage stats report them per profile, and a folder can be moved
+//! to another disk, leaving a directory symlink behind so the game still finds
+//! it at the usual path.
+
+use crate::disk::WriteContext;
+use crate::paths::Paths;
+use crate::profile::list_profiles;
+use crate::util::{copy_dir_all, dir_size};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Instance folders tracked separately in storage stats
+pub const AUX_FOLDERS: &[&str] = &["replay_recordings", "replay_videos", "schematics"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderUsage {
+    pub profile_id: String,
+    pub folder: String,
+    pub bytes: u64,
+    /// Where the folder lives when it was relocated to another disk
+    pub relocated_to: Option<PathBuf>,
+}
+
+/// Size of every auxiliary folder present in any instance
+pub fn aux_folder_usage(paths: &Paths) -> Result<Vec<FolderUsage>> {
+    let mut usage = Vec::new();
+    for profile_id in list_profiles(paths)? {
+        let instance_dir = paths.instance_dir(&profile_id);
+        for folder in AUX_FOLDERS {
+            let path = instance_dir.join(folder);
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            let relocated_to = if metadata.file_type().is_symlink() {
+                fs::read_link(&path).ok()
+            } else {
+                None
+            };
+            usage.push(FolderUsage {
+                profile_id: profile_id.clone(),
+                folder: folder.to_string(),
+                bytes: dir_size(&path),
+                relocated_to,
+            });
+        }
+    }
+    Ok(usage)
+}
+
+/// Move an auxiliary folder of a profile's instance under `target_root` and
+/// link it back into the instance. Returns the folder's new location.
+pub fn relocate_aux_folder(paths: &Paths, profile_id: &str, folder: &str, target_root: &Path) -> Result<PathBuf> {
+    if !AUX_FOLDERS.contains(&folder) {
+        bail!("unknown folder: {folder} (expected one of {})", AUX_FOLDERS.join(", "));
+    }
+    if !paths.is_profile_present(profile_id) {
+        bail!("profile not found: {profile_id}");
+    }
+
+    // A relative symlink target would resolve against the instance folder
+    let target_root = std::path::absolute(target_root)
+        .with_context(|| format!("invalid target: {}", target_root.display()))?;
+    let link = paths.instance_dir(profile_id).join(folder);
+    let current = match fs::symlink_metadata(&link) {
+        Ok(metadata) if metadata.file_type().is_symlink() => Some(
+            fs::read_link(&link).with_context(|| format!("failed to read link: {}", link.display()))?,
+        ),
+        Ok(_) => Some(link.clone()),
+        Err(_) => None,
+    };
+    let target = target_root.join(profile_id).join(folder);
+    if current.as_deref() == Some(target.as_path()) {
+        return Ok(target);
+    }
+    if target.exists() {
+        bail!("target already exists: {}", target.display());
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+
+    match &current {
+        Some(source) => move_dir(source, &target)?,
+        None => fs::create_dir_all(&target)
+            .write_context(&target, || format!("failed to create dir: {}", target.display()))?,
+    }
+    if fs::symlink_metadata(&link).is_ok() {
+        remove_link_or_dir(&link)?;
+    }
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("failed to create dir: {}", parent.display()))?;
+    }
+    symlink_dir(&target, &link)
+        .with_context(|| format!("failed to link {} to {}", link.display(), target.display()))?;
+    Ok(target)
+}
+
+/// Rename, or copy and delete when the target is on another filesystem
+fn move_dir(source: &Path, target: &Path) -> Result<()> {
+    if fs::rename(source, target).is_ok() {
+        return Ok(());
+    }
+    copy_dir_all(source, target)?;
+    fs::remove_dir_all(source).with_context(|| format!("failed to remove {}", source.display()))
+}
+
+fn remove_link_or_dir(path: &Path) -> Result<()> {
+    let metadata = fs::symlink_metadata(path).with_context(|| format!("failed to stat {}", path.display()))?;
+    let result = if metadata.file_type().is_symlink() {
+        // Directory symlinks are removed with remove_dir on Windows
+        fs::remove_file(path).or_else(|_| fs::remove_dir(path))
+    } else {
+        fs::remove_dir_all(path)
+    };
+    result.with_context(|| format!("failed to remove {}", path.display()))
+}
+
+#[cfg(unix)]
+fn symlink_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(src, dst)
+}
+
+#[cfg(windows)]
+fn symlink_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(src, dst)
+}
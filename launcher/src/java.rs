@@ -7,6 +7,7 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
 
 /// Information about a detected Java installation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -250,6 +251,24 @@ fn detect_architecture(output: &str) -> Option<String> {
     }
 }
 
+/// The machine's CPU architecture, named like [`JavaInstallation::arch`].
+/// An x86_64 build of shard running under Rosetta still reports "aarch64",
+/// since the Java it starts and the natives it loads can be native.
+pub fn host_arch() -> &'static str {
+    static HOST_ARCH: OnceLock<&'static str> = OnceLock::new();
+    HOST_ARCH.get_or_init(|| match std::env::consts::ARCH {
+        "x86_64" if cfg!(target_os = "macos") && runs_under_rosetta() => "aarch64",
+        arch => arch,
+    })
+}
+
+fn runs_under_rosetta() -> bool {
+    Command::new("sysctl")
+        .args(["-n", "sysctl.proc_translated"])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+}
+
 fn validate_and_create_installation(path: &Path) -> Option<JavaInstallation> {
     if !path.exists() {
         return None;
@@ -510,6 +529,7 @@ fn compare_mc_versions(a: &str, b: &str) -> i32 {
 
 // === Java Download from Adoptium ===
 
+use crate::net::RetryExt;
 use reqwest::blocking::Client;
 use serde_json::Value;
 use std::fs;
@@ -541,12 +561,11 @@ fn get_adoptium_os() -> &'static str {
 
 /// Get the current platform's architecture for Adoptium API.
 fn get_adoptium_arch() -> &'static str {
-    #[cfg(target_arch = "x86_64")]
-    { "x64" }
-    #[cfg(target_arch = "aarch64")]
-    { "aarch64" }
-    #[cfg(target_arch = "x86")]
-    { "x32" }
+    match host_arch() {
+        "aarch64" => "aarch64",
+        "x86" => "x32",
+        _ => "x64",
+    }
 }
 
 /// Get the archive extension for the current platform.
@@ -573,7 +592,7 @@ pub fn fetch_adoptium_release(java_major: u32) -> Result<AdoptiumRelease> {
         .context("failed to create HTTP client")?;
 
     let resp = client.get(&url)
-        .send()
+        .send_with_retry()
         .context("failed to fetch Adoptium release info")?
         .error_for_status()
         .context("Adoptium API returned error")?;
@@ -672,7 +691,7 @@ fn download_file_with_progress(
         .context("failed to create HTTP client")?;
 
     let mut resp = client.get(url)
-        .send()
+        .send_with_retry()
         .context("failed to start download")?
         .error_for_status()
         .context("download failed")?;
@@ -904,6 +923,18 @@ pub fn find_compatible_java(mc_version: &str, java_runtimes_dir: &Path) -> Optio
     None
 }
 
+/// Find an installed Java for a Minecraft version built for `arch`, e.g. an
+/// x86_64 one to run under Rosetta.
+pub fn find_java_for_arch(mc_version: &str, arch: &str) -> Option<String> {
+    detect_installations()
+        .into_iter()
+        .find(|install| {
+            install.arch.as_deref() == Some(arch)
+                && install.major.is_some_and(|major| is_java_compatible(major, mc_version))
+        })
+        .map(|install| install.path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,363 @@
+//! Background jobs
+//!
+//! Long-running work (downloads, imports, update applies, audits) runs as a
+//! job recorded under `jobs/` with its kind, progress and the PID of the
+//! process doing it, so any shard process can list it: `shard jobs list`
+//! shows what the desktop app is doing and the other way around. Cancelling
+//! writes a marker next to the record, which the job checks between steps.
+//! Records of jobs whose process is gone are removed when listing. Work
+//! paced by a [`Throttle`](crate::throttle::Throttle) stops through the job's
+//! [`CancelToken`] instead.
+
+use crate::disk::WriteContext;
+use crate::paths::Paths;
+use crate::throttle::CancelToken;
+use crate::util::now_epoch_secs;
+use aes_gcm::aead::OsRng;
+use aes_gcm::aead::rand_core::RngCore;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System};
+
+/// Least time between two progress writes of a job
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+impl Paths {
+    pub fn jobs_dir(&self) -> PathBuf {
+        self.state_root.join("jobs")
+    }
+
+    fn job_record(&self, id: &str) -> PathBuf {
+        self.jobs_dir().join(format!("{id}.json"))
+    }
+
+    fn job_cancel_marker(&self, id: &str) -> PathBuf {
+        self.jobs_dir().join(format!("{id}.cancel"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    /// Game files, content or the download queue
+    Download,
+    /// Modpacks and other launchers' data
+    Import,
+    /// Applying content updates
+    Update,
+    /// Verifying game files, library reports
+    Audit,
+}
+
+impl JobKind {
+    pub const ALL: [JobKind; 4] = [JobKind::Download, JobKind::Import, JobKind::Update, JobKind::Audit];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobKind::Download => "download",
+            JobKind::Import => "import",
+            JobKind::Update => "update",
+            JobKind::Audit => "audit",
+        }
+    }
+}
+
+impl FromStr for JobKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "download" => Ok(JobKind::Download),
+            "import" => Ok(JobKind::Import),
+            "update" => Ok(JobKind::Update),
+            "audit" => Ok(JobKind::Audit),
+            other => bail!("unknown job kind: {other} (expected download, import, update or audit)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub id: String,
+    pub kind: JobKind,
+    /// What the job works on, e.g. a profile id
+    pub label: String,
+    pub pid: u32,
+    /// Epoch seconds
+    pub started_at: u64,
+    pub state: JobState,
+    /// Steps finished so far
+    pub done: u64,
+    /// Steps in total, once known
+    pub total: Option<u64>,
+    /// The step in progress
+    pub message: Option<String>,
+    /// Error of a failed job
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+type JobListener = Box<dyn Fn(&JobInfo) + Send + Sync>;
+
+static LISTENER: RwLock<Option<JobListener>> = RwLock::new(None);
+
+/// Call `listener` whenever a job of this process starts, makes progress or
+/// ends, e.g. to forward jobs to a UI
+pub fn set_job_listener(listener: impl Fn(&JobInfo) + Send + Sync + 'static) {
+    if let Ok(mut current) = LISTENER.write() {
+        *current = Some(Box::new(listener));
+    }
+}
+
+fn notify(info: &JobInfo) {
+    if let Ok(listener) = LISTENER.read()
+        && let Some(listener) = listener.as_ref()
+    {
+        listener(info);
+    }
+}
+
+struct JobStatus {
+    info: JobInfo,
+    last_write: Instant,
+}
+
+/// A running job of this process. The record is removed when the job is
+/// dropped.
+pub struct Job {
+    /// `None` in inspect mode, where jobs run unrecorded
+    record: Option<PathBuf>,
+    cancel_marker: PathBuf,
+    cancel: CancelToken,
+    status: Mutex<JobStatus>,
+}
+
+impl Job {
+    /// Record a new job of this process
+    pub fn start(paths: &Paths, kind: JobKind, label: &str) -> Result<Job> {
+        let mut bytes = [0u8; 4];
+        OsRng.fill_bytes(&mut bytes);
+        let info = JobInfo {
+            id: hex::encode(bytes),
+            kind,
+            label: label.to_string(),
+            pid: std::process::id(),
+            started_at: now_epoch_secs(),
+            state: JobState::Running,
+            done: 0,
+            total: None,
+            message: None,
+            error: None,
+        };
+        let record = if paths.read_only {
+            None
+        } else {
+            let dir = paths.jobs_dir();
+            fs::create_dir_all(&dir).write_context(&dir, || format!("failed to create dir: {}", dir.display()))?;
+            Some(paths.job_record(&info.id))
+        };
+        let job = Job {
+            record,
+            cancel_marker: paths.job_cancel_marker(&info.id),
+            cancel: CancelToken::new(),
+            status: Mutex::new(JobStatus {
+                info: info.clone(),
+                last_write: Instant::now(),
+            }),
+        };
+        if let Some(record) = &job.record {
+            write_record(record, &info)?;
+        }
+        notify(&info);
+        Ok(job)
+    }
+
+    pub fn id(&self) -> String {
+        self.info().id
+    }
+
+    pub fn info(&self) -> JobInfo {
+        self.status.lock().map(|status| status.info.clone()).unwrap_or_else(|e| e.into_inner().info.clone())
+    }
+
+    /// Report progress. Writes are throttled; the last step of a known total
+    /// is always written.
+    pub fn progress(&self, done: u64, total: Option<u64>, message: Option<&str>) {
+        let Ok(mut status) = self.status.lock() else {
+            return;
+        };
+        status.info.done = done;
+        status.info.total = total;
+        status.info.message = message.map(str::to_string);
+        let finished = total.is_some_and(|total| done >= total);
+        if !finished && status.last_write.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        status.last_write = Instant::now();
+        if self.cancel_marker.exists() {
+            self.cancel.cancel();
+        }
+        // Progress is informational; the job goes on if the record can't be written
+        if let Some(record) = &self.record {
+            let _ = write_record(record, &status.info);
+        }
+        notify(&status.info);
+    }
+
+    /// Whether any process asked this job to stop
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancel_marker.exists() {
+            self.cancel.cancel();
+        }
+        self.cancel.is_cancelled()
+    }
+
+    /// Token cancelled once the job is asked to stop, noticed when progress
+    /// is reported
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    /// Fail with a cancellation error if the job was asked to stop; call it
+    /// between steps
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.is_cancelled() {
+            bail!("job {} was cancelled", self.id());
+        }
+        Ok(())
+    }
+
+    fn finish(self, error: Option<&anyhow::Error>) {
+        let mut info = self.info();
+        // Throttled work returns what it got done when cancelled
+        info.state = if self.is_cancelled() {
+            JobState::Cancelled
+        } else if error.is_some() {
+            JobState::Failed
+        } else {
+            JobState::Completed
+        };
+        info.error = error.map(|err| format!("{err:#}"));
+        notify(&info);
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        if let Some(record) = &self.record {
+            let _ = fs::remove_file(record);
+            let _ = fs::remove_file(&self.cancel_marker);
+        }
+    }
+}
+
+fn write_record(path: &Path, info: &JobInfo) -> Result<()> {
+    let data = serde_json::to_string_pretty(info).context("failed to serialize job")?;
+    fs::write(path, data).write_context(path, || format!("failed to write {}", path.display()))
+}
+
+/// Run `work` as a job, recorded until it returns
+pub fn run_job<T>(paths: &Paths, kind: JobKind, label: &str, work: impl FnOnce(&Job) -> Result<T>) -> Result<T> {
+    let job = Job::start(paths, kind, label)?;
+    let result = work(&job);
+    job.finish(result.as_ref().err());
+    result
+}
+
+fn is_alive(system: &mut System, pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, ProcessRefreshKind::nothing());
+    system
+        .process(pid)
+        .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+}
+
+/// Running jobs of every shard process, oldest first. Records of jobs whose
+/// process is gone are removed, except in inspect mode.
+pub fn list_jobs(paths: &Paths) -> Result<Vec<JobInfo>> {
+    let Ok(entries) = fs::read_dir(paths.jobs_dir()) else {
+        return Ok(Vec::new());
+    };
+    let remove = |path: &Path| {
+        if !paths.read_only {
+            let _ = fs::remove_file(path);
+        }
+    };
+    let mut system = System::new();
+    let mut jobs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => {}
+            // Markers of jobs that ended before seeing them
+            Some("cancel") => {
+                if !path.with_extension("json").exists() {
+                    remove(&path);
+                }
+                continue;
+            }
+            _ => continue,
+        }
+        let record = fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<JobInfo>(&data).ok());
+        let Some(job) = record else {
+            // A record being written right now reads as garbage; only drop it once it's old
+            if entry
+                .metadata()
+                .and_then(|meta| meta.modified())
+                .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age > Duration::from_secs(60)))
+            {
+                remove(&path);
+            }
+            continue;
+        };
+        if is_alive(&mut system, job.pid) {
+            jobs.push(job);
+        } else {
+            remove(&path);
+            remove(&paths.job_cancel_marker(&job.id));
+        }
+    }
+    jobs.sort_by(|a, b| a.started_at.cmp(&b.started_at).then_with(|| a.id.cmp(&b.id)));
+    Ok(jobs)
+}
+
+/// Ask a running job to stop. It stops at its next step, so it may still be
+/// listed for a moment.
+pub fn cancel_job(paths: &Paths, id: &str) -> Result<JobInfo> {
+    paths.ensure_writable()?;
+    let Some(job) = list_jobs(paths)?.into_iter().find(|job| job.id == id) else {
+        bail!("no running job: {id}");
+    };
+    let marker = paths.job_cancel_marker(&job.id);
+    fs::write(&marker, b"").write_context(&marker, || format!("failed to write {}", marker.display()))?;
+    Ok(job)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_job_kind() {
+        for kind in JobKind::ALL {
+            assert_eq!(kind.as_str().parse::<JobKind>().unwrap(), kind);
+        }
+        assert!("backup".parse::<JobKind>().is_err());
+    }
+}
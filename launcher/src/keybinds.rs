@@ -0,0 +1,163 @@
+//! Keybind conflicts in a profile's instance
+//!
+//! Vanilla and most mods save their bindings in `options.txt` as
+//! `key_<action>:<key>[:<modifier>]` (the modifier is Forge's). Some mods keep
+//! their own bindings in `config/`; those are picked up from lines that pair
+//! a setting name with a `key.keyboard.*`/`key.mouse.*` value. Bindings of
+//! different actions to the same key and modifier are reported as conflicts.
+//! A newly installed mod's defaults only appear once the game has run with it.
+
+use crate::mod_config::list_mod_configs;
+use crate::paths::Paths;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+const OPTIONS_FILE: &str = "options.txt";
+
+/// Largest config file scanned for bindings
+const MAX_SCAN_SIZE: u64 = 1024 * 1024;
+
+const UNBOUND: &str = "key.keyboard.unknown";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keybind {
+    pub action: String,
+    /// `key.keyboard.g`, `key.mouse.left`, ...
+    pub key: String,
+    /// Forge key modifier (`SHIFT`, `CONTROL`, `ALT`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modifier: Option<String>,
+    /// `options.txt` or `config/<file>`
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeybindConflict {
+    /// Key with its modifier, e.g. `SHIFT+key.keyboard.g`
+    pub key: String,
+    pub bindings: Vec<Keybind>,
+}
+
+fn is_key_value(value: &str) -> bool {
+    (value.starts_with("key.keyboard.") || value.starts_with("key.mouse.")) && value != UNBOUND
+}
+
+/// Bindings saved in `options.txt`
+pub fn parse_options_keybinds(content: &str) -> Vec<Keybind> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (action, value) = line.trim().strip_prefix("key_")?.split_once(':')?;
+            let (key, modifier) = match value.split_once(':') {
+                Some((key, modifier)) => (key, Some(modifier)),
+                None => (value, None),
+            };
+            if !is_key_value(key) {
+                return None;
+            }
+            Some(Keybind {
+                action: action.to_string(),
+                key: key.to_string(),
+                modifier: modifier.filter(|m| *m != "NONE").map(str::to_string),
+                source: OPTIONS_FILE.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Bindings a mod keeps in its own config file: `name = "key.keyboard.x"`,
+/// `"name": "key.keyboard.x"` and similar
+pub fn parse_config_keybinds(content: &str, source: &str) -> Vec<Keybind> {
+    let trim = |s: &str| s.trim().trim_matches(|c| c == '"' || c == '\'').trim().to_string();
+    content
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(['=', ':'])?;
+            let key = trim(value.trim().trim_end_matches(','));
+            let action = trim(name);
+            if action.is_empty() || !is_key_value(&key) {
+                return None;
+            }
+            Some(Keybind {
+                action,
+                key,
+                modifier: None,
+                source: source.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Group bindings by key and modifier, keeping keys bound to more than one
+/// action
+pub fn find_conflicts(bindings: Vec<Keybind>) -> Vec<KeybindConflict> {
+    let mut by_key: BTreeMap<String, Vec<Keybind>> = BTreeMap::new();
+    for binding in bindings {
+        let key = match &binding.modifier {
+            Some(modifier) => format!("{modifier}+{}", binding.key),
+            None => binding.key.clone(),
+        };
+        let group = by_key.entry(key).or_default();
+        if !group.iter().any(|b| b.action == binding.action) {
+            group.push(binding);
+        }
+    }
+    by_key
+        .into_iter()
+        .filter(|(_, bindings)| bindings.len() > 1)
+        .map(|(key, bindings)| KeybindConflict { key, bindings })
+        .collect()
+}
+
+/// Keybind conflicts in a profile's `options.txt` and mod configs
+pub fn keybind_conflicts(paths: &Paths, profile_id: &str) -> Result<Vec<KeybindConflict>> {
+    let instance_dir = paths.instance_dir(profile_id);
+    let mut bindings = Vec::new();
+
+    let options = instance_dir.join(OPTIONS_FILE);
+    if options.exists() {
+        let content =
+            fs::read_to_string(&options).with_context(|| format!("failed to read {}", options.display()))?;
+        bindings.extend(parse_options_keybinds(&content));
+    }
+
+    let config_dir = paths.instance_config_dir(profile_id);
+    for file in list_mod_configs(paths, profile_id)? {
+        if file.size > MAX_SCAN_SIZE {
+            continue;
+        }
+        // Binary or non-UTF-8 files hold no bindings we can read
+        let Ok(content) = fs::read_to_string(config_dir.join(&file.path)) else {
+            continue;
+        };
+        bindings.extend(parse_config_keybinds(&content, &format!("config/{}", file.path)));
+    }
+
+    Ok(find_conflicts(bindings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keybind_conflicts() {
+        let options = "version:3700\nkey_key.forward:key.keyboard.w\nkey_key.drop:key.keyboard.q\n\
+                       key_key.journeymap.map:key.keyboard.j:NONE\nkey_key.jei.show:key.keyboard.q:SHIFT\n\
+                       key_key.hotbar.1:key.keyboard.unknown\nkey_key.minimap.zoom:key.keyboard.unknown\n";
+        let mut bindings = parse_options_keybinds(options);
+        assert_eq!(bindings.len(), 4);
+        assert_eq!(bindings[3].modifier.as_deref(), Some("SHIFT"));
+
+        let config = "[keys]\nopenMenu = \"key.keyboard.j\"\ncolor = \"#ffffff\"\n";
+        bindings.extend(parse_config_keybinds(config, "config/waypoints.toml"));
+
+        let conflicts = find_conflicts(bindings);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].key, "key.keyboard.j");
+        let actions: Vec<&str> = conflicts[0].bindings.iter().map(|b| b.action.as_str()).collect();
+        assert_eq!(actions, vec!["key.journeymap.map", "openMenu"]);
+    }
+}
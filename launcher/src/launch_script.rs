@@ -0,0 +1,180 @@
+//! Launch plans as standalone scripts
+//!
+//! `shard launch --export-script` writes the exact java invocation of a
+//! [`LaunchPlan`] to a script, to debug a launch or run the game without the
+//! launcher. The plan carries the account's access token, so the script only
+//! works until the token expires and is written readable by the user alone.
+
+use crate::disk::WriteContext;
+use crate::minecraft::LaunchPlan;
+use anyhow::Result;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptFormat {
+    /// POSIX shell
+    Shell,
+    /// Windows batch file
+    Batch,
+}
+
+impl ScriptFormat {
+    /// Batch for `.bat` and `.cmd` paths, shell for `.sh`, otherwise what the
+    /// current platform runs
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("bat" | "cmd") => ScriptFormat::Batch,
+            Some("sh") => ScriptFormat::Shell,
+            _ if cfg!(windows) => ScriptFormat::Batch,
+            _ => ScriptFormat::Shell,
+        }
+    }
+}
+
+/// The program and arguments without the classpath, in the order
+/// [`game_command`](crate::minecraft::game_command) passes them
+fn command_parts(plan: &LaunchPlan) -> (Vec<&str>, Vec<&str>) {
+    let mut program: Vec<&str> = plan.wrapper.iter().map(String::as_str).collect();
+    program.push(&plan.java_exec);
+    let mut args: Vec<&str> = plan.jvm_args.iter().map(String::as_str).collect();
+    args.push(&plan.main_class);
+    args.extend(plan.game_args.iter().map(String::as_str));
+    (program, args)
+}
+
+pub fn render_shell_script(plan: &LaunchPlan, profile_id: &str) -> String {
+    let (program, args) = command_parts(plan);
+    let mut out = String::from("#!/bin/sh\n");
+    let _ = writeln!(out, "# Launches profile {profile_id}, as exported by `shard launch --export-script`.");
+    out.push_str("# Holds the account's access token: don't share it, and export again once it expires.\n\n");
+    let _ = writeln!(out, "cd {} || exit 1", shell_words::quote(&plan.instance_dir.to_string_lossy()));
+    for (key, value) in &plan.env {
+        let _ = writeln!(out, "export {key}={}", shell_words::quote(value));
+    }
+    let _ = write!(out, "exec {}", shell_words::join(&program));
+    let (jvm_args, rest) = args.split_at(plan.jvm_args.len());
+    for arg in jvm_args {
+        let _ = write!(out, " \\\n  {}", shell_words::quote(arg));
+    }
+    let _ = write!(out, " \\\n  -cp {}", shell_words::quote(&plan.classpath));
+    for arg in rest {
+        let _ = write!(out, " \\\n  {}", shell_words::quote(arg));
+    }
+    out.push('\n');
+    out
+}
+
+/// Quote an argument for cmd.exe; `%` would otherwise expand a variable
+fn batch_quote(arg: &str) -> String {
+    let arg = arg.replace('%', "%%").replace('"', "\\\"");
+    if !arg.is_empty() && !arg.contains([' ', '\t', '&', '|', '<', '>', '^', '(', ')', ',', ';', '=']) {
+        return arg;
+    }
+    format!("\"{arg}\"")
+}
+
+pub fn render_batch_script(plan: &LaunchPlan, profile_id: &str) -> String {
+    let (program, args) = command_parts(plan);
+    let mut out = String::from("@echo off\r\n");
+    let _ = write!(out, "rem Launches profile {profile_id}, as exported by `shard launch --export-script`.\r\n");
+    out.push_str("rem Holds the account's access token: don't share it, and export again once it expires.\r\n");
+    out.push_str("setlocal\r\n");
+    let _ = write!(out, "cd /d {} || exit /b 1\r\n", batch_quote(&plan.instance_dir.to_string_lossy()));
+    for (key, value) in &plan.env {
+        let _ = write!(out, "set \"{key}={}\"\r\n", value.replace('%', "%%"));
+    }
+    // cmd.exe lines are limited to 8191 characters, which a modded classpath
+    // easily exceeds; java reads CLASSPATH when no -cp is given
+    for (i, entry) in plan.classpath.split(';').filter(|entry| !entry.is_empty()).enumerate() {
+        let entry = entry.replace('%', "%%");
+        if i == 0 {
+            let _ = write!(out, "set \"CLASSPATH={entry}\"\r\n");
+        } else {
+            let _ = write!(out, "set \"CLASSPATH=%CLASSPATH%;{entry}\"\r\n");
+        }
+    }
+    let quoted: Vec<String> = program.iter().map(|part| batch_quote(part)).collect();
+    out.push_str(&quoted.join(" "));
+    for arg in args {
+        let _ = write!(out, " ^\r\n  {}", batch_quote(arg));
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// Write `plan` as a script at `path`, in the format its extension asks for
+pub fn export_launch_script(plan: &LaunchPlan, profile_id: &str, path: &Path) -> Result<ScriptFormat> {
+    let format = ScriptFormat::for_path(path);
+    let script = match format {
+        ScriptFormat::Shell => render_shell_script(plan, profile_id),
+        ScriptFormat::Batch => render_batch_script(plan, profile_id),
+    };
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    fs::write(path, script).write_context(path, || format!("failed to write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o700))
+            .write_context(path, || format!("failed to set permissions of {}", path.display()))?;
+    }
+    Ok(format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn plan() -> LaunchPlan {
+        LaunchPlan {
+            instance_dir: PathBuf::from("/home/me/shard/instances/my pack"),
+            wrapper: vec!["gamemoderun".to_string()],
+            java_exec: "/usr/bin/java".to_string(),
+            jvm_args: vec!["-Xmx4G".to_string(), "-Dlog4j.configurationFile=it's.xml".to_string()],
+            classpath: "a.jar:b c.jar".to_string(),
+            main_class: "net.minecraft.client.main.Main".to_string(),
+            game_args: vec!["--username".to_string(), "Steve".to_string(), "--title".to_string(), "100%".to_string()],
+            priority: None,
+            cpu_affinity: Vec::new(),
+            env: vec![("SDL_VIDEO_MINIMIZE_ON_FOCUS_LOSS".to_string(), "0".to_string())],
+        }
+    }
+
+    #[test]
+    fn test_shell_script_round_trips() {
+        let script = render_shell_script(&plan(), "my-pack");
+        let command = script.lines().skip_while(|line| !line.starts_with("exec ")).collect::<Vec<_>>().join("\n");
+        let words = shell_words::split(&command.replace(" \\\n", " ")).unwrap();
+        assert_eq!(
+            words,
+            [
+                "exec",
+                "gamemoderun",
+                "/usr/bin/java",
+                "-Xmx4G",
+                "-Dlog4j.configurationFile=it's.xml",
+                "-cp",
+                "a.jar:b c.jar",
+                "net.minecraft.client.main.Main",
+                "--username",
+                "Steve",
+                "--title",
+                "100%",
+            ]
+        );
+        assert!(script.contains("cd '/home/me/shard/instances/my pack' || exit 1"));
+        assert!(script.contains("export SDL_VIDEO_MINIMIZE_ON_FOCUS_LOSS=0\n"));
+    }
+
+    #[test]
+    fn test_batch_quote() {
+        assert_eq!(batch_quote("-Xmx4G"), "-Xmx4G");
+        assert_eq!(batch_quote("C:\\Program Files\\java.exe"), "\"C:\\Program Files\\java.exe\"");
+        assert_eq!(batch_quote("100%"), "100%%");
+        assert_eq!(batch_quote(""), "\"\"");
+    }
+}
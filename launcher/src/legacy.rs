@@ -0,0 +1,219 @@
+//! Upgrade of data written by the original CLI
+//!
+//! The first `shard` binary (the `src/` crate at the repository root, before
+//! the launcher crate replaced it) used the same data directory with a
+//! slimmer schema: content entries carried only `name`, `hash`, `version`,
+//! `source` and `file_name`, and account tokens had no expiry. Its profiles
+//! still load, but their content has no platform or project to check updates
+//! against; its accounts don't load at all. [`migrate_legacy_data`] fills in
+//! what can be worked out and rewrites both in the current schema, once per
+//! data directory.
+
+use crate::accounts::{Accounts, save_accounts};
+use crate::disk::WriteContext;
+use crate::paths::Paths;
+use crate::profile::{Profile, list_profiles, save_profile};
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+
+const CONTENT_LISTS: [&str; 3] = ["mods", "resourcepacks", "shaderpacks"];
+
+impl Paths {
+    /// Present once the data directory was checked for legacy files
+    fn legacy_migration_stamp(&self) -> PathBuf {
+        self.root.join(".legacy-migrated")
+    }
+}
+
+/// Upgrade legacy profiles and accounts in place, unless that was already
+/// done for this data directory. Returns a line per change.
+pub fn migrate_legacy_data(paths: &Paths) -> Result<Vec<String>> {
+    let stamp = paths.legacy_migration_stamp();
+    if paths.read_only || stamp.exists() {
+        return Ok(Vec::new());
+    }
+    let mut changes = Vec::new();
+    for id in list_profiles(paths)? {
+        let path = paths.profile_json(&id);
+        let Ok(data) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(mut value) = serde_json::from_str::<Value>(&data) else {
+            continue;
+        };
+        let upgraded = upgrade_profile(&mut value);
+        if upgraded.is_empty() {
+            continue;
+        }
+        let profile: Profile = serde_json::from_value(value)
+            .with_context(|| format!("failed to parse upgraded profile: {}", path.display()))?;
+        save_profile(paths, &profile)?;
+        changes.extend(upgraded.into_iter().map(|change| format!("profile {id}: {change}")));
+    }
+
+    if let Ok(data) = fs::read_to_string(&paths.accounts)
+        && let Ok(mut value) = serde_json::from_str::<Value>(&data)
+    {
+        let upgraded = upgrade_accounts(&mut value);
+        if !upgraded.is_empty() {
+            let accounts: Accounts = serde_json::from_value(value).context("failed to parse upgraded accounts")?;
+            save_accounts(paths, &accounts)?;
+            changes.extend(upgraded);
+        }
+    }
+
+    fs::write(&stamp, "").write_context(&stamp, || format!("failed to write {}", stamp.display()))?;
+    Ok(changes)
+}
+
+/// Fill in the platform, project and file name of content entries from
+/// their download url
+fn upgrade_profile(profile: &mut Value) -> Vec<String> {
+    let mut changes = Vec::new();
+    for list in CONTENT_LISTS {
+        let Some(entries) = profile.get_mut(list).and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for entry in entries.iter_mut().filter_map(Value::as_object_mut) {
+            if entry.contains_key("platform") {
+                continue;
+            }
+            let Some(source) = entry.get("source").and_then(Value::as_str).map(str::to_string) else {
+                continue;
+            };
+            let mut filled = Vec::new();
+            if let Some((project_id, version_id)) = modrinth_cdn_ids(&source) {
+                filled.extend(set_missing(entry, "platform", "modrinth"));
+                filled.extend(set_missing(entry, "project_id", project_id));
+                filled.extend(set_missing(entry, "version_id", version_id));
+            } else if source.contains("forgecdn.net/") || source.contains("curseforge.com/") {
+                filled.extend(set_missing(entry, "platform", "curseforge"));
+            } else if !source.starts_with("http://") && !source.starts_with("https://") {
+                filled.extend(set_missing(entry, "platform", "local"));
+            }
+            if let Some(file_name) = url_file_name(&source) {
+                filled.extend(set_missing(entry, "file_name", file_name));
+            }
+            if !filled.is_empty() {
+                let name = entry.get("name").and_then(Value::as_str).unwrap_or("?");
+                changes.push(format!("{name}: added {}", filled.join(", ")));
+            }
+        }
+    }
+    changes
+}
+
+/// Give tokens without an expiry one in the past, so they are refreshed
+/// before their next use
+fn upgrade_accounts(accounts: &mut Value) -> Vec<String> {
+    let mut changes = Vec::new();
+    let Some(list) = accounts.get_mut("accounts").and_then(Value::as_array_mut) else {
+        return changes;
+    };
+    for account in list.iter_mut().filter_map(Value::as_object_mut) {
+        let mut fixed = Vec::new();
+        for key in ["msa", "minecraft"] {
+            if let Some(tokens) = account.get_mut(key).and_then(Value::as_object_mut)
+                && !tokens.contains_key("expires_at")
+            {
+                tokens.insert("expires_at".to_string(), Value::from(0));
+                fixed.push(key);
+            }
+        }
+        if !fixed.is_empty() {
+            let username = account.get("username").and_then(Value::as_str).unwrap_or("?");
+            changes.push(format!("account {username}: {} tokens will be refreshed on next use", fixed.join(" and ")));
+        }
+    }
+    changes
+}
+
+/// Set `key` unless it already has a value; returns the key if it did
+fn set_missing(entry: &mut Map<String, Value>, key: &'static str, value: &str) -> Option<&'static str> {
+    if entry.get(key).is_some_and(|existing| !existing.is_null()) {
+        return None;
+    }
+    entry.insert(key.to_string(), Value::from(value));
+    Some(key)
+}
+
+/// Project and version id of a Modrinth CDN url
+/// (`https://cdn.modrinth.com/data/<project>/versions/<version>/<file>`)
+fn modrinth_cdn_ids(url: &str) -> Option<(&str, &str)> {
+    let rest = url.strip_prefix("https://cdn.modrinth.com/data/")?;
+    let (project_id, rest) = rest.split_once("/versions/")?;
+    let (version_id, _) = rest.split_once('/')?;
+    (!project_id.is_empty() && !version_id.is_empty()).then_some((project_id, version_id))
+}
+
+/// Last path segment of a download url
+fn url_file_name(source: &str) -> Option<&str> {
+    let path = source.strip_prefix("https://").or_else(|| source.strip_prefix("http://"))?;
+    let path = path.split(['?', '#']).next()?;
+    let (_, name) = path.rsplit_once('/')?;
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::load_accounts;
+    use crate::fake_cdn::TempRoot;
+    use crate::profile::load_profile;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_legacy_data() {
+        let root = TempRoot::new("legacy-migration");
+        let paths = &root.paths;
+        fs::create_dir_all(paths.profile_dir("old")).unwrap();
+        let profile = json!({
+            "id": "old",
+            "mcVersion": "1.20.1",
+            "mods": [
+                {
+                    "name": "sodium",
+                    "hash": "sha256:aa",
+                    "source": "https://cdn.modrinth.com/data/AANobbMI/versions/OihdIimA/sodium-fabric-0.5.3.jar"
+                },
+                { "name": "jei", "hash": "sha256:bb", "source": "https://edge.forgecdn.net/files/4712/866/jei.jar" },
+                { "name": "mine", "hash": "sha256:cc", "source": "/home/me/mine.jar", "file_name": "mine.jar" }
+            ]
+        });
+        fs::write(paths.profile_json("old"), profile.to_string()).unwrap();
+        let accounts = json!({
+            "active": "u1",
+            "accounts": [{
+                "uuid": "u1",
+                "username": "Steve",
+                "msa": { "access_token": "a", "refresh_token": "r" },
+                "minecraft": { "access_token": "m" }
+            }]
+        });
+        fs::write(&paths.accounts, accounts.to_string()).unwrap();
+
+        let changes = migrate_legacy_data(paths).unwrap();
+        assert_eq!(changes.len(), 4, "{changes:?}");
+
+        let profile = load_profile(paths, "old").unwrap();
+        let sodium = &profile.mods[0];
+        assert_eq!(sodium.platform.as_deref(), Some("modrinth"));
+        assert_eq!(sodium.project_id.as_deref(), Some("AANobbMI"));
+        assert_eq!(sodium.version_id.as_deref(), Some("OihdIimA"));
+        assert_eq!(sodium.file_name.as_deref(), Some("sodium-fabric-0.5.3.jar"));
+        assert_eq!(profile.mods[1].platform.as_deref(), Some("curseforge"));
+        assert_eq!(profile.mods[2].platform.as_deref(), Some("local"));
+
+        let accounts = load_accounts(paths).unwrap();
+        assert!(accounts.accounts[0].minecraft.is_expired());
+
+        // Only once per data directory
+        fs::write(paths.profile_json("old"), json!({ "id": "old", "mcVersion": "1.20.1", "mods": [
+            { "name": "x", "hash": "sha256:dd", "source": "/x.jar" }
+        ] }).to_string())
+        .unwrap();
+        assert!(migrate_legacy_data(paths).unwrap().is_empty());
+    }
+}
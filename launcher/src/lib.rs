@@ -1,20 +1,73 @@
+pub mod account_export;
 pub mod accounts;
+pub mod api_keys;
+pub mod asset_db;
 pub mod auth;
+pub mod blocklist;
 pub mod config;
+pub mod config_history;
 pub mod content_store;
+pub mod crash_stats;
 pub mod curseforge;
+pub mod disk;
+pub mod dotminecraft;
+pub mod download;
+pub mod download_queue;
+#[cfg(test)]
+mod fake_cdn;
+pub mod file_lock;
+pub mod game_events;
+pub mod gamescope;
+pub mod installer;
 pub mod instance;
+pub mod instance_folders;
 pub mod java;
+pub mod jobs;
+pub mod keybinds;
+pub mod launch_script;
+pub mod legacy;
 pub mod library;
+pub mod library_report;
+pub mod loader;
+pub mod locale;
+pub mod lockfile;
 pub mod logs;
+pub mod metered;
 pub mod minecraft;
+pub mod mirrors;
+pub mod mod_config;
 pub mod modpack;
 pub mod modrinth;
+pub mod net;
 pub mod ops;
+pub mod pack_meta;
 pub mod paths;
+pub mod playtime;
+pub mod presets;
+pub mod process;
 pub mod profile;
+pub mod provision;
+pub mod quickstart;
+pub mod resource_monitor;
+pub mod scheduling;
+pub mod seed;
+pub mod server;
+pub mod server_properties;
+pub mod session;
+pub mod shader_settings;
+pub mod signing;
 pub mod skin;
+pub mod snapshot;
+pub mod spaces;
+pub mod sql_profile;
+pub mod steam_shortcut;
 pub mod store;
 pub mod template;
+pub mod throttle;
+pub mod tunnel;
 pub mod updates;
 pub mod util;
+pub mod vanilla_launcher;
+pub mod version_tags;
+pub mod versions;
+pub mod watchlist;
@@ -3,11 +3,16 @@
 //! Tracks all content (mods, resourcepacks, shaderpacks, skins) with metadata,
 //! tags, and profile relationships.
 
+use crate::disk::DiskError;
 use crate::paths::Paths;
-use crate::store::{hash_file, normalize_hash, ContentKind};
+use crate::sql_profile::profile_connection;
+use crate::store::{hash_file_digests, normalize_hash, ContentKind};
 use anyhow::{Context, Result, bail};
-use rusqlite::{Connection, OptionalExtension, params};
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -87,10 +92,22 @@ pub struct LibraryItem {
     pub added_at: String,
     pub updated_at: String,
     pub notes: Option<String>,
+    /// SHA-1 digest (Modrinth/CurseForge hash lookups)
+    #[serde(default)]
+    pub sha1: Option<String>,
+    /// SHA-512 digest (Modrinth hash lookups)
+    #[serde(default)]
+    pub sha512: Option<String>,
     #[serde(default)]
     pub tags: Vec<Tag>,
     #[serde(default)]
     pub used_by_profiles: Vec<String>,
+    /// Item this one replaced when a project was updated
+    #[serde(default)]
+    pub replaces: Option<i64>,
+    /// Item that replaced this one when a project was updated
+    #[serde(default)]
+    pub replaced_by: Option<i64>,
 }
 
 /// Input for creating/updating a library item
@@ -106,6 +123,8 @@ pub struct LibraryItemInput {
     pub source_project_id: Option<String>,
     pub source_version: Option<String>,
     pub notes: Option<String>,
+    pub sha1: Option<String>,
+    pub sha512: Option<String>,
 }
 
 /// Filter for listing library items
@@ -123,9 +142,23 @@ pub struct LibraryFilter {
 pub struct ImportResult {
     pub added: usize,
     pub skipped: usize,
+    /// Items whose names were cleaned up by [`Library::normalize_names`]
+    #[serde(default)]
+    pub renamed: usize,
+    /// Items whose SHA-1/SHA-512 were computed by [`Library::backfill_digests`]
+    #[serde(default)]
+    pub digested: usize,
     pub errors: Vec<String>,
 }
 
+/// A name changed by [`Library::normalize_names`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameChange {
+    pub id: i64,
+    pub old: String,
+    pub new: String,
+}
+
 /// An unused item candidate for purging
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnusedItem {
@@ -168,31 +201,237 @@ pub struct LibraryStats {
     pub tags_count: u32,
 }
 
+/// Views available to [`Library::query`]:
+///
+/// - `items_with_tags`: id, hash, content_type, name, file_name, file_size,
+///   source_platform, source_project_id, source_version, added_at,
+///   updated_at, tags (comma-separated)
+/// - `profile_usage`: profile_id, item_id, hash, name, content_type,
+///   file_size, added_at (one row per item used by a profile)
+pub const QUERY_VIEWS: [&str; 2] = ["items_with_tags", "profile_usage"];
+
+/// Rows returned by [`Library::query`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// How SQLite runs one of the library's common queries, from
+/// `EXPLAIN QUERY PLAN`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryPlanCheck {
+    /// The filters combined, e.g. "tags + content_type"
+    pub query: String,
+    /// Plan steps as SQLite describes them
+    pub plan: Vec<String>,
+    /// Indexes that would avoid a full scan or a sort, as CREATE INDEX
+    pub missing_indexes: Vec<String>,
+}
+
+/// Index that helps a query whose plan reads one of `aliases` in full, or
+/// sorts results when `for_sort` is set
+#[derive(Clone, Copy)]
+struct IndexHint {
+    aliases: &'static [&'static str],
+    for_sort: bool,
+    name: &'static str,
+    columns: &'static str,
+}
+
+impl IndexHint {
+    fn create_sql(&self) -> String {
+        format!("CREATE INDEX IF NOT EXISTS {} ON {}", self.name, self.columns)
+    }
+
+    fn applies_to(&self, plan: &[String]) -> bool {
+        plan.iter().any(|step| {
+            if self.for_sort {
+                step.starts_with("USE TEMP B-TREE FOR ORDER BY")
+            } else {
+                step.strip_prefix("SCAN ").is_some_and(|rest| {
+                    self.aliases
+                        .iter()
+                        .any(|alias| rest == *alias || rest.starts_with(&format!("{alias} ")))
+                })
+            }
+        })
+    }
+}
+
+/// Parameters of a statement built at runtime
+type SqlParams = Vec<Box<dyn rusqlite::ToSql>>;
+
+/// Item columns read by [`item_from_row`], for a query over `library_items li`.
+/// The provenance links come from the same query rather than one lookup per
+/// item.
+const ITEM_COLUMNS: &str = r#"
+    li.id, li.hash, li.content_type, li.name, li.file_name, li.file_size,
+    li.source_url, li.source_platform, li.source_project_id, li.source_version,
+    li.added_at, li.updated_at, li.notes, li.sha1, li.sha512,
+    (SELECT r.replaces_id FROM item_replacements r WHERE r.item_id = li.id),
+    (SELECT r.item_id FROM item_replacements r WHERE r.replaces_id = li.id
+     ORDER BY r.replaced_at DESC, r.item_id DESC LIMIT 1)
+"#;
+
+/// An item from a row of [`ITEM_COLUMNS`]; tags and profiles are left empty
+fn item_from_row(row: &rusqlite::Row) -> rusqlite::Result<LibraryItem> {
+    Ok(LibraryItem {
+        id: row.get(0)?,
+        hash: row.get(1)?,
+        content_type: LibraryContentType::from_str(&row.get::<_, String>(2)?)
+            .unwrap_or(LibraryContentType::Mod),
+        name: row.get(3)?,
+        file_name: row.get(4)?,
+        file_size: row.get(5)?,
+        source_url: row.get(6)?,
+        source_platform: row.get(7)?,
+        source_project_id: row.get(8)?,
+        source_version: row.get(9)?,
+        added_at: row.get(10)?,
+        updated_at: row.get(11)?,
+        notes: row.get(12)?,
+        sha1: row.get(13)?,
+        sha512: row.get(14)?,
+        tags: vec![],
+        used_by_profiles: vec![],
+        replaces: row.get(15)?,
+        replaced_by: row.get(16)?,
+    })
+}
+
+const ITEM_TAGS_SQL: &str = r#"
+    SELECT t.id, t.name, t.color
+    FROM tags t
+    JOIN item_tags it ON t.id = it.tag_id
+    WHERE it.item_id = ?1
+    ORDER BY t.name
+"#;
+
+const ITEM_PROFILES_SQL: &str = "SELECT profile_id FROM profile_items WHERE item_id = ?1 ORDER BY profile_id";
+
+/// `library_meta` key holding when digests were last backfilled
+const DIGESTS_BACKFILLED_KEY: &str = "digests_backfilled_at";
+
+/// Platforms whose item names come from the store's project title
+const STORE_PLATFORMS: [&str; 2] = ["modrinth", "curseforge"];
+
+/// Loader tags that often follow the name in file names
+const LOADER_TOKENS: [&str; 5] = ["fabric", "forge", "neoforge", "quilt", "liteloader"];
+
+/// Turn a file-derived name like "fabric-api-0.92.0+1.20.1.jar" into a
+/// display name ("Fabric Api"): the extension, version and loader suffixes
+/// are dropped and lowercase words capitalized. Names that already read like
+/// a title ("Fabric API") are kept.
+pub fn normalize_content_name(name: &str) -> String {
+    let trimmed = name.trim();
+    let lower = trimmed.to_ascii_lowercase();
+    let stem = [".jar", ".zip", ".litemod", ".disabled"]
+        .iter()
+        .fold(trimmed, |stem, ext| {
+            if lower.ends_with(ext) && stem.len() == trimmed.len() {
+                &stem[..stem.len() - ext.len()]
+            } else {
+                stem
+            }
+        });
+    if stem.contains(' ') && stem.len() == trimmed.len() {
+        return trimmed.to_string();
+    }
+
+    let mut words = Vec::new();
+    for token in stem.split(['-', '_', '+', ' ']).filter(|t| !t.is_empty()) {
+        let lower = token.to_ascii_lowercase();
+        let versionish = |t: &str| t.starts_with(|c: char| c.is_ascii_digit());
+        let is_version = versionish(&lower)
+            || lower.strip_prefix('v').is_some_and(versionish)
+            || lower.strip_prefix("mc").is_some_and(versionish);
+        if is_version || (!words.is_empty() && LOADER_TOKENS.contains(&lower.as_str())) {
+            break;
+        }
+        words.push(token);
+    }
+    if words.is_empty() {
+        return trimmed.to_string();
+    }
+    words
+        .iter()
+        .map(|word| {
+            if word.chars().all(|c| !c.is_ascii_uppercase()) {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Placeholder names given to store files found without metadata
+fn is_placeholder_name(name: &str, content_type: &str) -> bool {
+    name.strip_prefix(content_type)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .is_some_and(|hash| hash.len() == 8 && hash.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
 /// Library manager
 pub struct Library {
     conn: Connection,
+    /// Database path when opened read-only for inspect mode
+    read_only: Option<PathBuf>,
 }
 
 impl Library {
     /// Open (or create) the library database
     pub fn open(path: &Path) -> Result<Self> {
-        let conn = Connection::open(path)
+        let mut conn = Connection::open(path)
             .with_context(|| format!("failed to open library database: {}", path.display()))?;
+        profile_connection(&mut conn);
 
         // Enable foreign key constraints (SQLite requires this per-connection)
         conn.execute("PRAGMA foreign_keys = ON", [])
             .context("failed to enable foreign key constraints")?;
 
-        let library = Self { conn };
+        let library = Self { conn, read_only: None };
         library.init_schema()?;
         Ok(library)
     }
 
-    /// Open the library from Paths
+    /// Open an existing library database without changing it; changes
+    /// fail with an inspect [`DiskError`]. A database that doesn't exist yet
+    /// opens empty.
+    pub fn open_read_only(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            let conn = Connection::open_in_memory().context("failed to open in-memory library database")?;
+            let library = Self { conn, read_only: Some(path.to_path_buf()) };
+            library.init_schema()?;
+            return Ok(library);
+        }
+        let mut conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+            .with_context(|| format!("failed to open library database: {}", path.display()))?;
+        profile_connection(&mut conn);
+        Ok(Self { conn, read_only: Some(path.to_path_buf()) })
+    }
+
+    /// Open the library from Paths, read-only in inspect mode
     pub fn from_paths(paths: &Paths) -> Result<Self> {
+        if paths.read_only {
+            return Self::open_read_only(&paths.library_db);
+        }
         Self::open(&paths.library_db)
     }
 
+    fn ensure_writable(&self) -> Result<()> {
+        match &self.read_only {
+            Some(path) => Err(DiskError::inspect(path).into()),
+            None => Ok(()),
+        }
+    }
+
     /// Initialize the database schema
     fn init_schema(&self) -> Result<()> {
         self.conn.execute_batch(
@@ -210,7 +449,9 @@ impl Library {
                 source_version TEXT,
                 added_at TEXT NOT NULL DEFAULT (datetime('now')),
                 updated_at TEXT NOT NULL DEFAULT (datetime('now')),
-                notes TEXT
+                notes TEXT,
+                sha1 TEXT,
+                sha512 TEXT
             );
 
             CREATE TABLE IF NOT EXISTS tags (
@@ -233,13 +474,76 @@ impl Library {
                 PRIMARY KEY (profile_id, item_id)
             );
 
+            CREATE TABLE IF NOT EXISTS item_replacements (
+                item_id INTEGER PRIMARY KEY REFERENCES library_items(id) ON DELETE CASCADE,
+                replaces_id INTEGER NOT NULL REFERENCES library_items(id) ON DELETE CASCADE,
+                replaced_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS library_meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+
             CREATE INDEX IF NOT EXISTS idx_library_items_hash ON library_items(hash);
             CREATE INDEX IF NOT EXISTS idx_library_items_content_type ON library_items(content_type);
             CREATE INDEX IF NOT EXISTS idx_profile_items_profile ON profile_items(profile_id);
+            CREATE INDEX IF NOT EXISTS idx_item_replacements_replaces ON item_replacements(replaces_id);
             "#,
         )
         .context("failed to initialize library schema")?;
 
+        // Migrations for databases created before these columns existed
+        self.ensure_column("library_items", "sha1", "TEXT")?;
+        self.ensure_column("library_items", "sha512", "TEXT")?;
+        self.conn
+            .execute_batch(
+                r#"
+                CREATE INDEX IF NOT EXISTS idx_library_items_sha1 ON library_items(sha1);
+                CREATE INDEX IF NOT EXISTS idx_library_items_sha512 ON library_items(sha512);
+                "#,
+            )
+            .context("failed to create digest indexes")?;
+
+        // Recreated on open so the documented columns track the tables
+        self.conn
+            .execute_batch(
+                r#"
+                DROP VIEW IF EXISTS items_with_tags;
+                CREATE VIEW items_with_tags AS
+                SELECT
+                    i.id, i.hash, i.content_type, i.name, i.file_name, i.file_size,
+                    i.source_platform, i.source_project_id, i.source_version,
+                    i.added_at, i.updated_at,
+                    (SELECT group_concat(t.name, ',') FROM item_tags it JOIN tags t ON t.id = it.tag_id
+                     WHERE it.item_id = i.id) AS tags
+                FROM library_items i;
+
+                DROP VIEW IF EXISTS profile_usage;
+                CREATE VIEW profile_usage AS
+                SELECT
+                    p.profile_id, p.item_id, i.hash, i.name, p.content_type, i.file_size,
+                    p.added_at
+                FROM profile_items p JOIN library_items i ON i.id = p.item_id;
+                "#,
+            )
+            .context("failed to create library views")?;
+
+        Ok(())
+    }
+
+    /// Add a column to a table if it is missing
+    fn ensure_column(&self, table: &str, column: &str, decl: &str) -> Result<()> {
+        let mut stmt = self.conn.prepare(&format!("PRAGMA table_info({table})"))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|name| name.ok())
+            .any(|name| name == column);
+        if !exists {
+            self.conn
+                .execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"), [])
+                .with_context(|| format!("failed to add column {table}.{column}"))?;
+        }
         Ok(())
     }
 
@@ -247,6 +551,7 @@ impl Library {
 
     /// Add an item to the library
     pub fn add_item(&self, input: &LibraryItemInput) -> Result<LibraryItem> {
+        self.ensure_writable()?;
         let hash = normalize_hash(&input.hash);
         let content_type = input
             .content_type
@@ -261,8 +566,8 @@ impl Library {
 
         self.conn.execute(
             r#"
-            INSERT INTO library_items (hash, content_type, name, file_name, file_size, source_url, source_platform, source_project_id, source_version, notes)
-            VALUES (?1, ?2, COALESCE(?3, ?11), ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            INSERT INTO library_items (hash, content_type, name, file_name, file_size, source_url, source_platform, source_project_id, source_version, notes, sha1, sha512)
+            VALUES (?1, ?2, COALESCE(?3, ?11), ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?12, ?13)
             ON CONFLICT(hash) DO UPDATE SET
                 name = COALESCE(?3, name),
                 file_name = COALESCE(?4, file_name),
@@ -272,6 +577,8 @@ impl Library {
                 source_project_id = COALESCE(?8, source_project_id),
                 source_version = COALESCE(?9, source_version),
                 notes = COALESCE(?10, notes),
+                sha1 = COALESCE(?12, sha1),
+                sha512 = COALESCE(?13, sha512),
                 updated_at = datetime('now')
             "#,
             params![
@@ -286,6 +593,8 @@ impl Library {
                 input.source_version,
                 input.notes,
                 default_name,
+                input.sha1.as_deref().map(str::to_lowercase),
+                input.sha512.as_deref().map(str::to_lowercase),
             ],
         )
         .context("failed to add library item")?;
@@ -296,91 +605,44 @@ impl Library {
 
     /// Get an item by ID
     pub fn get_item(&self, id: i64) -> Result<Option<LibraryItem>> {
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT id, hash, content_type, name, file_name, file_size, source_url,
-                   source_platform, source_project_id, source_version, added_at, updated_at, notes
-            FROM library_items WHERE id = ?1
-            "#,
-        )?;
-
-        let item = stmt
-            .query_row(params![id], |row| {
-                Ok(LibraryItem {
-                    id: row.get(0)?,
-                    hash: row.get(1)?,
-                    content_type: LibraryContentType::from_str(&row.get::<_, String>(2)?)
-                        .unwrap_or(LibraryContentType::Mod),
-                    name: row.get(3)?,
-                    file_name: row.get(4)?,
-                    file_size: row.get(5)?,
-                    source_url: row.get(6)?,
-                    source_platform: row.get(7)?,
-                    source_project_id: row.get(8)?,
-                    source_version: row.get(9)?,
-                    added_at: row.get(10)?,
-                    updated_at: row.get(11)?,
-                    notes: row.get(12)?,
-                    tags: vec![],
-                    used_by_profiles: vec![],
-                })
-            })
+        let item = self
+            .conn
+            .query_row(
+                &format!("SELECT {ITEM_COLUMNS} FROM library_items li WHERE li.id = ?1"),
+                params![id],
+                item_from_row,
+            )
             .optional()?;
-
-        if let Some(mut item) = item {
-            item.tags = self.get_item_tags(item.id)?;
-            item.used_by_profiles = self.get_item_profiles(item.id)?;
-            Ok(Some(item))
-        } else {
-            Ok(None)
-        }
+        self.with_item_links(item)
     }
 
     /// Get an item by hash
     pub fn get_item_by_hash(&self, hash: &str) -> Result<Option<LibraryItem>> {
         let hash = normalize_hash(hash);
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT id, hash, content_type, name, file_name, file_size, source_url,
-                   source_platform, source_project_id, source_version, added_at, updated_at, notes
-            FROM library_items WHERE hash = ?1
-            "#,
-        )?;
-
-        let item = stmt
-            .query_row(params![hash], |row| {
-                Ok(LibraryItem {
-                    id: row.get(0)?,
-                    hash: row.get(1)?,
-                    content_type: LibraryContentType::from_str(&row.get::<_, String>(2)?)
-                        .unwrap_or(LibraryContentType::Mod),
-                    name: row.get(3)?,
-                    file_name: row.get(4)?,
-                    file_size: row.get(5)?,
-                    source_url: row.get(6)?,
-                    source_platform: row.get(7)?,
-                    source_project_id: row.get(8)?,
-                    source_version: row.get(9)?,
-                    added_at: row.get(10)?,
-                    updated_at: row.get(11)?,
-                    notes: row.get(12)?,
-                    tags: vec![],
-                    used_by_profiles: vec![],
-                })
-            })
+        let item = self
+            .conn
+            .query_row(
+                &format!("SELECT {ITEM_COLUMNS} FROM library_items li WHERE li.hash = ?1"),
+                params![hash],
+                item_from_row,
+            )
             .optional()?;
+        self.with_item_links(item)
+    }
 
-        if let Some(mut item) = item {
-            item.tags = self.get_item_tags(item.id)?;
-            item.used_by_profiles = self.get_item_profiles(item.id)?;
-            Ok(Some(item))
-        } else {
-            Ok(None)
-        }
+    /// Fill in the tags and profiles of a single fetched item
+    fn with_item_links(&self, item: Option<LibraryItem>) -> Result<Option<LibraryItem>> {
+        let Some(mut item) = item else {
+            return Ok(None);
+        };
+        item.tags = self.get_item_tags(item.id)?;
+        item.used_by_profiles = self.get_item_profiles(item.id)?;
+        Ok(Some(item))
     }
 
     /// Update an item
     pub fn update_item(&self, id: i64, input: &LibraryItemInput) -> Result<LibraryItem> {
+        self.ensure_writable()?;
         self.conn.execute(
             r#"
             UPDATE library_items SET
@@ -408,6 +670,7 @@ impl Library {
         source_project_id: Option<&str>,
         source_version: Option<&str>,
     ) -> Result<LibraryItem> {
+        self.ensure_writable()?;
         self.conn.execute(
             r#"
             UPDATE library_items SET
@@ -438,6 +701,7 @@ impl Library {
         project_id: Option<&str>,
         version: Option<&str>,
     ) -> Result<Option<LibraryItem>> {
+        self.ensure_writable()?;
         let normalized_hash = normalize_hash(hash);
         if let Some(item) = self.get_item_by_hash(&normalized_hash)? {
             // Only update if the item has generic/store metadata
@@ -445,12 +709,19 @@ impl Library {
                 || item.source_platform.is_none()
                 || item.name.starts_with("mod-")
                 || item.name.starts_with("resourcepack-")
-                || item.name.starts_with("shaderpack-");
+                || item.name.starts_with("shaderpack-")
+                || normalize_content_name(&item.name) != item.name;
 
             if needs_update {
+                // Store titles are already display names
+                let name = if platform.is_some_and(|p| STORE_PLATFORMS.contains(&p)) {
+                    name.to_string()
+                } else {
+                    normalize_content_name(name)
+                };
                 return Ok(Some(self.update_item_metadata(
                     item.id,
-                    Some(name),
+                    Some(&name),
                     file_name,
                     source,
                     platform,
@@ -465,6 +736,7 @@ impl Library {
 
     /// Delete an item
     pub fn delete_item(&self, id: i64) -> Result<bool> {
+        self.ensure_writable()?;
         let rows = self
             .conn
             .execute("DELETE FROM library_items WHERE id = ?1", params![id])?;
@@ -473,6 +745,7 @@ impl Library {
 
     /// Delete an item by hash
     pub fn delete_item_by_hash(&self, hash: &str) -> Result<bool> {
+        self.ensure_writable()?;
         let hash = normalize_hash(hash);
         let rows = self
             .conn
@@ -480,16 +753,10 @@ impl Library {
         Ok(rows > 0)
     }
 
-    /// List items with optional filtering
-    pub fn list_items(&self, filter: &LibraryFilter) -> Result<Vec<LibraryItem>> {
-        let mut sql = String::from(
-            r#"
-            SELECT DISTINCT li.id, li.hash, li.content_type, li.name, li.file_name, li.file_size,
-                   li.source_url, li.source_platform, li.source_project_id, li.source_version,
-                   li.added_at, li.updated_at, li.notes
-            FROM library_items li
-            "#,
-        );
+    /// `FROM ... WHERE ...` selecting the items matching `filter` as `li`,
+    /// without ordering or paging, and its parameters
+    fn filter_clause(filter: &LibraryFilter) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut sql = String::from(" FROM library_items li");
 
         let mut conditions = Vec::new();
         let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
@@ -534,6 +801,16 @@ impl Library {
             sql.push_str(&conditions.join(" AND "));
         }
 
+        (sql, params_vec)
+    }
+
+    /// The statement [`Library::list_items`] runs for `filter`, with its
+    /// parameters
+    fn list_items_query(filter: &LibraryFilter) -> (String, SqlParams) {
+        let (clause, params_vec) = Self::filter_clause(filter);
+        let mut sql = format!("SELECT DISTINCT {ITEM_COLUMNS}");
+        sql.push_str(&clause);
+
         sql.push_str(" ORDER BY li.updated_at DESC");
 
         if let Some(limit) = filter.limit {
@@ -542,30 +819,16 @@ impl Library {
         if let Some(offset) = filter.offset {
             sql.push_str(&format!(" OFFSET {}", offset));
         }
+        (sql, params_vec)
+    }
 
+    /// List items with optional filtering
+    pub fn list_items(&self, filter: &LibraryFilter) -> Result<Vec<LibraryItem>> {
+        let (sql, params_vec) = Self::list_items_query(filter);
         let mut stmt = self.conn.prepare(&sql)?;
         let params_refs: Vec<&dyn rusqlite::ToSql> =
             params_vec.iter().map(|p| p.as_ref()).collect();
-        let rows = stmt.query_map(params_refs.as_slice(), |row| {
-            Ok(LibraryItem {
-                id: row.get(0)?,
-                hash: row.get(1)?,
-                content_type: LibraryContentType::from_str(&row.get::<_, String>(2)?)
-                    .unwrap_or(LibraryContentType::Mod),
-                name: row.get(3)?,
-                file_name: row.get(4)?,
-                file_size: row.get(5)?,
-                source_url: row.get(6)?,
-                source_platform: row.get(7)?,
-                source_project_id: row.get(8)?,
-                source_version: row.get(9)?,
-                added_at: row.get(10)?,
-                updated_at: row.get(11)?,
-                notes: row.get(12)?,
-                tags: vec![],
-                used_by_profiles: vec![],
-            })
-        })?;
+        let rows = stmt.query_map(params_refs.as_slice(), item_from_row)?;
 
         let mut items = Vec::new();
         for row in rows {
@@ -582,6 +845,7 @@ impl Library {
 
     /// Create a tag
     pub fn create_tag(&self, name: &str, color: Option<&str>) -> Result<Tag> {
+        self.ensure_writable()?;
         self.conn.execute(
             "INSERT INTO tags (name, color) VALUES (?1, ?2) ON CONFLICT(name) DO UPDATE SET color = COALESCE(?2, color)",
             params![name, color],
@@ -626,6 +890,7 @@ impl Library {
 
     /// Delete a tag
     pub fn delete_tag(&self, id: i64) -> Result<bool> {
+        self.ensure_writable()?;
         let rows = self
             .conn
             .execute("DELETE FROM tags WHERE id = ?1", params![id])?;
@@ -634,6 +899,7 @@ impl Library {
 
     /// Delete a tag by name
     pub fn delete_tag_by_name(&self, name: &str) -> Result<bool> {
+        self.ensure_writable()?;
         let rows = self
             .conn
             .execute("DELETE FROM tags WHERE name = ?1", params![name])?;
@@ -642,15 +908,7 @@ impl Library {
 
     /// Get tags for an item
     fn get_item_tags(&self, item_id: i64) -> Result<Vec<Tag>> {
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT t.id, t.name, t.color
-            FROM tags t
-            JOIN item_tags it ON t.id = it.tag_id
-            WHERE it.item_id = ?1
-            ORDER BY t.name
-            "#,
-        )?;
+        let mut stmt = self.conn.prepare(ITEM_TAGS_SQL)?;
 
         let rows = stmt.query_map(params![item_id], |row| {
             Ok(Tag {
@@ -666,6 +924,7 @@ impl Library {
 
     /// Add a tag to an item
     pub fn add_tag_to_item(&self, item_id: i64, tag_name: &str) -> Result<()> {
+        self.ensure_writable()?;
         // Ensure tag exists
         let tag = self
             .create_tag(tag_name, None)?;
@@ -678,8 +937,29 @@ impl Library {
         Ok(())
     }
 
+    /// Add a tag to every item matching `filter` (paging ignored) in one
+    /// transaction. Returns how many items gained the tag.
+    pub fn add_tag_to_matching(&self, filter: &LibraryFilter, tag_name: &str) -> Result<usize> {
+        self.ensure_writable()?;
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("failed to start library transaction")?;
+        let tag = self.create_tag(tag_name, None)?;
+        let (clause, mut params_vec) = Self::filter_clause(filter);
+        params_vec.insert(0, Box::new(tag.id));
+        let sql = format!("INSERT OR IGNORE INTO item_tags (item_id, tag_id) SELECT DISTINCT li.id, ?{clause}");
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+        let added = tx
+            .execute(&sql, params_refs.as_slice())
+            .with_context(|| format!("failed to tag matching items with {tag_name}"))?;
+        tx.commit().context("failed to commit library transaction")?;
+        Ok(added)
+    }
+
     /// Remove a tag from an item
     pub fn remove_tag_from_item(&self, item_id: i64, tag_name: &str) -> Result<()> {
+        self.ensure_writable()?;
         if let Some(tag) = self.get_tag_by_name(tag_name)? {
             self.conn.execute(
                 "DELETE FROM item_tags WHERE item_id = ?1 AND tag_id = ?2",
@@ -691,6 +971,7 @@ impl Library {
 
     /// Set all tags for an item (replace existing)
     pub fn set_item_tags(&self, item_id: i64, tag_names: &[String]) -> Result<()> {
+        self.ensure_writable()?;
         // Remove all existing tags
         self.conn.execute(
             "DELETE FROM item_tags WHERE item_id = ?1",
@@ -705,13 +986,33 @@ impl Library {
         Ok(())
     }
 
+    /// Replace the item's tags starting with `prefix` by `tag_names`, leaving
+    /// its other tags alone. Returns whether anything changed.
+    pub fn set_prefixed_tags(&self, item_id: i64, prefix: &str, tag_names: &[String]) -> Result<bool> {
+        self.ensure_writable()?;
+        let current: Vec<String> = self
+            .get_item_tags(item_id)?
+            .into_iter()
+            .map(|tag| tag.name)
+            .filter(|name| name.starts_with(prefix))
+            .collect();
+        let mut changed = false;
+        for name in current.iter().filter(|name| !tag_names.contains(name)) {
+            self.remove_tag_from_item(item_id, name)?;
+            changed = true;
+        }
+        for name in tag_names.iter().filter(|name| !current.contains(name)) {
+            self.add_tag_to_item(item_id, name)?;
+            changed = true;
+        }
+        Ok(changed)
+    }
+
     // ========== Profile Relationships ==========
 
     /// Get profiles that use an item
     fn get_item_profiles(&self, item_id: i64) -> Result<Vec<String>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT profile_id FROM profile_items WHERE item_id = ?1 ORDER BY profile_id",
-        )?;
+        let mut stmt = self.conn.prepare(ITEM_PROFILES_SQL)?;
 
         let rows = stmt.query_map(params![item_id], |row| row.get(0))?;
         rows.collect::<std::result::Result<Vec<_>, _>>()
@@ -725,6 +1026,7 @@ impl Library {
         profile_id: &str,
         content_type: LibraryContentType,
     ) -> Result<()> {
+        self.ensure_writable()?;
         self.conn.execute(
             "INSERT OR IGNORE INTO profile_items (profile_id, item_id, content_type) VALUES (?1, ?2, ?3)",
             params![profile_id, item_id, content_type.as_str()],
@@ -734,6 +1036,7 @@ impl Library {
 
     /// Unlink an item from a profile
     pub fn unlink_item_from_profile(&self, item_id: i64, profile_id: &str) -> Result<()> {
+        self.ensure_writable()?;
         self.conn.execute(
             "DELETE FROM profile_items WHERE profile_id = ?1 AND item_id = ?2",
             params![profile_id, item_id],
@@ -748,6 +1051,7 @@ impl Library {
         hashes: &[String],
         content_type: LibraryContentType,
     ) -> Result<()> {
+        self.ensure_writable()?;
         // Remove existing links for this content type
         self.conn.execute(
             "DELETE FROM profile_items WHERE profile_id = ?1 AND content_type = ?2",
@@ -764,6 +1068,123 @@ impl Library {
         Ok(())
     }
 
+    // ========== Provenance ==========
+
+    /// Get the ID of the item this one replaced
+    fn get_replaces_id(&self, item_id: i64) -> Result<Option<i64>> {
+        self.conn
+            .query_row(
+                "SELECT replaces_id FROM item_replacements WHERE item_id = ?1",
+                params![item_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("failed to get replaced item")
+    }
+
+    /// Record that `new_item_id` replaces `old_item_id` (e.g. after a mod update)
+    pub fn record_replacement(&self, old_item_id: i64, new_item_id: i64) -> Result<()> {
+        self.ensure_writable()?;
+        if old_item_id == new_item_id {
+            return Ok(());
+        }
+        // Refuse links that would turn the chain into a cycle
+        if self
+            .get_item_history(old_item_id)?
+            .iter()
+            .any(|item| item.id == new_item_id)
+        {
+            bail!("item {new_item_id} is already part of item {old_item_id}'s history");
+        }
+        self.conn
+            .execute(
+                r#"
+                INSERT INTO item_replacements (item_id, replaces_id) VALUES (?1, ?2)
+                ON CONFLICT(item_id) DO UPDATE SET
+                    replaces_id = excluded.replaces_id,
+                    replaced_at = datetime('now')
+                "#,
+                params![new_item_id, old_item_id],
+            )
+            .context("failed to record item replacement")?;
+        Ok(())
+    }
+
+    /// Record a replacement by content hash
+    pub fn record_replacement_by_hash(&self, old_hash: &str, new_hash: &str) -> Result<()> {
+        self.ensure_writable()?;
+        let old_item = self
+            .get_item_by_hash(old_hash)?
+            .with_context(|| format!("item not found: {old_hash}"))?;
+        let new_item = self
+            .get_item_by_hash(new_hash)?
+            .with_context(|| format!("item not found: {new_hash}"))?;
+        self.record_replacement(old_item.id, new_item.id)
+    }
+
+    /// Full version history for the chain containing an item, oldest first
+    pub fn get_item_history(&self, item_id: i64) -> Result<Vec<LibraryItem>> {
+        let mut seen = HashSet::new();
+        seen.insert(item_id);
+
+        // Walk back to the oldest predecessor
+        let mut oldest = item_id;
+        while let Some(prev) = self.get_replaces_id(oldest)? {
+            if !seen.insert(prev) {
+                break;
+            }
+            oldest = prev;
+        }
+
+        // Walk forward to the newest replacement
+        let mut history = Vec::new();
+        let mut visited = HashSet::new();
+        let mut current = Some(oldest);
+        while let Some(id) = current {
+            if !visited.insert(id) {
+                break;
+            }
+            match self.get_item(id)? {
+                Some(item) => {
+                    current = item.replaced_by;
+                    history.push(item);
+                }
+                None => break,
+            }
+        }
+
+        Ok(history)
+    }
+
+    /// Name of the most recently updated item among `hashes`, considering only
+    /// replacements recorded at or before `before` (unix seconds)
+    pub fn latest_replacement_among(&self, hashes: &[String], before: u64) -> Result<Option<String>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT li.name, r.replaced_at
+            FROM item_replacements r
+            JOIN library_items li ON li.id = r.item_id
+            WHERE li.hash = ?1 AND r.replaced_at <= datetime(?2, 'unixepoch')
+            "#,
+        )?;
+
+        let mut latest: Option<(String, String)> = None;
+        for hash in hashes {
+            let found = stmt
+                .query_row(params![hash, before as i64], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                })
+                .optional()
+                .context("failed to query item replacements")?;
+            if let Some((name, replaced_at)) = found
+                && latest.as_ref().is_none_or(|(_, at)| replaced_at > *at)
+            {
+                latest = Some((name, replaced_at));
+            }
+        }
+        Ok(latest.map(|(name, _)| name))
+    }
+
     // ========== Import ==========
 
     /// Import a file into the library
@@ -773,12 +1194,14 @@ impl Library {
         file_path: &Path,
         content_type: LibraryContentType,
     ) -> Result<LibraryItem> {
+        self.ensure_writable()?;
         if !file_path.exists() {
             bail!("file not found: {}", file_path.display());
         }
 
         // Hash the file
-        let hash = hash_file(file_path)?;
+        let digests = hash_file_digests(file_path)?;
+        let hash = digests.sha256;
 
         // Get file metadata
         let metadata = fs::metadata(file_path)?;
@@ -787,10 +1210,9 @@ impl Library {
             .file_name()
             .and_then(|s| s.to_str())
             .map(String::from);
-        let name = file_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .map(String::from)
+        let name = file_name
+            .as_deref()
+            .map(normalize_content_name)
             .unwrap_or_else(|| format!("item-{}", &hash[..hash.len().min(8)]));
 
         // Copy to content store
@@ -813,6 +1235,8 @@ impl Library {
             file_name,
             file_size: Some(file_size),
             source_platform: Some("local".to_string()),
+            sha1: Some(digests.sha1),
+            sha512: Some(digests.sha512),
             ..Default::default()
         })
     }
@@ -825,6 +1249,7 @@ impl Library {
         content_type: LibraryContentType,
         recursive: bool,
     ) -> Result<ImportResult> {
+        self.ensure_writable()?;
         let mut result = ImportResult::default();
 
         if !folder_path.exists() {
@@ -888,7 +1313,8 @@ impl Library {
         }
     }
 
-    fn content_store_path(
+    /// Where an item's file lives in the content store
+    pub fn content_store_path(
         &self,
         paths: &Paths,
         content_type: LibraryContentType,
@@ -958,8 +1384,163 @@ impl Library {
         })
     }
 
+    // ========== Ad-hoc queries ==========
+
+    /// Run a read-only query against the [`QUERY_VIEWS`]. Base tables, writes
+    /// and pragmas are rejected when the statement is prepared.
+    pub fn query(&self, sql: &str) -> Result<QueryResult> {
+        self.conn.authorizer(Some(|ctx: AuthContext<'_>| match ctx.action {
+            AuthAction::Select | AuthAction::Function { .. } | AuthAction::Recursive => Authorization::Allow,
+            // Reads through a view report the view as accessor. Row-only
+            // reads (`count(*)` over a flattened view) name no column and
+            // carry no accessor; they expose no values.
+            AuthAction::Read { table_name, column_name }
+                if QUERY_VIEWS.contains(&table_name)
+                    || column_name.is_empty()
+                    || ctx.accessor.is_some_and(|view| QUERY_VIEWS.contains(&view)) =>
+            {
+                Authorization::Allow
+            }
+            _ => Authorization::Deny,
+        }));
+        let prepared = self.conn.prepare(sql);
+        self.conn.authorizer(None::<fn(AuthContext<'_>) -> Authorization>);
+        let mut stmt = prepared.map_err(|err| {
+            anyhow::anyhow!(
+                "invalid query: {err} (only SELECT over {} is allowed)",
+                QUERY_VIEWS.join(", ")
+            )
+        })?;
+        if !stmt.readonly() {
+            bail!("only read-only queries are allowed");
+        }
+
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let mut rows = Vec::new();
+        let mut query = stmt.query([]).context("failed to run query")?;
+        while let Some(row) = query.next().context("failed to read query row")? {
+            let mut values = Vec::with_capacity(columns.len());
+            for index in 0..columns.len() {
+                values.push(match row.get_ref(index)? {
+                    ValueRef::Null => serde_json::Value::Null,
+                    ValueRef::Integer(value) => value.into(),
+                    ValueRef::Real(value) => value.into(),
+                    ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned().into(),
+                    ValueRef::Blob(blob) => hex::encode(blob).into(),
+                });
+            }
+            rows.push(values);
+        }
+        Ok(QueryResult { columns, rows })
+    }
+
+    // ========== Query plans ==========
+
+    /// Explain the statements behind the common filters (tags, content type,
+    /// search and their combination) and the per-item lookups of
+    /// [`Library::list_items`], suggesting indexes for full scans and sorts
+    pub fn check_query_plans(&self) -> Result<Vec<QueryPlanCheck>> {
+        // Without it, tag filters read every item and look its tags up
+        let tag_hint = IndexHint {
+            aliases: &["it", "li"],
+            for_sort: false,
+            name: "idx_item_tags_tag",
+            columns: "item_tags(tag_id)",
+        };
+        let sort_hint = |typed: bool| IndexHint {
+            aliases: &[],
+            for_sort: true,
+            name: if typed { "idx_library_items_type_updated" } else { "idx_library_items_updated" },
+            columns: if typed { "library_items(content_type, updated_at)" } else { "library_items(updated_at)" },
+        };
+        let filter = |tags: bool, content_type: bool, search: bool| LibraryFilter {
+            tags: tags.then(|| vec!["performance".to_string()]),
+            content_type: content_type.then(|| "mod".to_string()),
+            search: search.then(|| "sodium".to_string()),
+            ..Default::default()
+        };
+
+        let mut probes: Vec<(&str, String, SqlParams, Vec<IndexHint>)> = Vec::new();
+        for (name, tags, content_type, search) in [
+            ("all items", false, false, false),
+            ("tags", true, false, false),
+            ("content_type", false, true, false),
+            ("search", false, false, true),
+            ("tags + content_type + search", true, true, true),
+        ] {
+            let (sql, params) = Self::list_items_query(&filter(tags, content_type, search));
+            let mut hints = vec![sort_hint(content_type)];
+            if tags {
+                hints.push(tag_hint);
+            }
+            probes.push((name, sql, params, hints));
+        }
+        probes.push(("item tags", ITEM_TAGS_SQL.to_string(), vec![Box::new(1i64)], Vec::new()));
+        probes.push((
+            "item profiles",
+            ITEM_PROFILES_SQL.to_string(),
+            vec![Box::new(1i64)],
+            vec![IndexHint {
+                aliases: &["profile_items"],
+                for_sort: false,
+                name: "idx_profile_items_item",
+                columns: "profile_items(item_id)",
+            }],
+        ));
+
+        let mut checks = Vec::new();
+        for (query, sql, params, hints) in probes {
+            let plan = self.explain(&sql, &params)?;
+            let mut missing_indexes = Vec::new();
+            for hint in hints {
+                if hint.applies_to(&plan) && !self.has_index(hint.name)? {
+                    missing_indexes.push(hint.create_sql());
+                }
+            }
+            checks.push(QueryPlanCheck {
+                query: query.to_string(),
+                plan,
+                missing_indexes,
+            });
+        }
+        Ok(checks)
+    }
+
+    /// Create the indexes [`Library::check_query_plans`] finds missing;
+    /// returns the statements run
+    pub fn add_missing_indexes(&self) -> Result<Vec<String>> {
+        self.ensure_writable()?;
+        let mut created = Vec::new();
+        for check in self.check_query_plans()? {
+            for sql in check.missing_indexes {
+                if !created.contains(&sql) {
+                    self.conn.execute_batch(&sql).with_context(|| format!("failed to run {sql}"))?;
+                    created.push(sql);
+                }
+            }
+        }
+        Ok(created)
+    }
+
+    fn explain(&self, sql: &str, params: &SqlParams) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}"))?;
+        let params: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt.query_map(params.as_slice(), |row| row.get::<_, String>(3))?;
+        rows.collect::<std::result::Result<Vec<_>, _>>()
+            .context("failed to explain query")
+    }
+
+    fn has_index(&self, name: &str) -> Result<bool> {
+        Ok(self
+            .conn
+            .query_row("SELECT 1 FROM sqlite_master WHERE type = 'index' AND name = ?1", params![name], |_| Ok(()))
+            .optional()?
+            .is_some())
+    }
+
     /// Sync library with content store (find items in store not in library)
     pub fn sync_with_store(&self, paths: &Paths) -> Result<ImportResult> {
+        self.ensure_writable()?;
         let mut result = ImportResult::default();
 
         // Sync each content type
@@ -1008,9 +1589,179 @@ impl Library {
             }
         }
 
+        match self.backfill_digests(paths) {
+            Ok(count) => result.digested = count,
+            Err(e) => result.errors.push(format!("failed to compute digests: {e}")),
+        }
+        match self.normalize_names() {
+            Ok(changes) => result.renamed = changes.len(),
+            Err(e) => result.errors.push(format!("failed to normalize names: {e}")),
+        }
+
         Ok(result)
     }
 
+    /// Clean up file-derived item names. Items of a project with a store
+    /// title take that title; others get [`normalize_content_name`].
+    /// Placeholder names of unidentified store files are left alone.
+    pub fn normalize_names(&self) -> Result<Vec<NameChange>> {
+        self.ensure_writable()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, content_type, source_platform, source_project_id FROM library_items",
+        )?;
+        struct Row {
+            id: i64,
+            name: String,
+            content_type: String,
+            platform: Option<String>,
+            project_id: Option<String>,
+        }
+        let rows: Vec<Row> = stmt
+            .query_map([], |row| {
+                Ok(Row {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    content_type: row.get(2)?,
+                    platform: row.get(3)?,
+                    project_id: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let mut titles: HashMap<&str, &str> = HashMap::new();
+        for row in &rows {
+            if let (Some(platform), Some(project_id)) = (&row.platform, &row.project_id)
+                && STORE_PLATFORMS.contains(&platform.as_str())
+            {
+                titles.entry(project_id.as_str()).or_insert(row.name.as_str());
+            }
+        }
+
+        let mut changes = Vec::new();
+        for row in &rows {
+            if is_placeholder_name(&row.name, &row.content_type) {
+                continue;
+            }
+            let new = match row.project_id.as_deref().and_then(|project| titles.get(project)) {
+                Some(title) => title.to_string(),
+                None => normalize_content_name(&row.name),
+            };
+            if new != row.name {
+                changes.push(NameChange {
+                    id: row.id,
+                    old: row.name.clone(),
+                    new,
+                });
+            }
+        }
+        for change in &changes {
+            self.conn.execute(
+                "UPDATE library_items SET name = ?2, updated_at = datetime('now') WHERE id = ?1",
+                params![change.id, change.new],
+            )?;
+        }
+        Ok(changes)
+    }
+
+    /// Map `source_id` to the same project as `target_id`: it takes the
+    /// target's name and source, and both end up with the union of their tags.
+    /// Both items keep their files. Returns the updated source item.
+    pub fn merge_items(&self, target_id: i64, source_id: i64) -> Result<LibraryItem> {
+        self.ensure_writable()?;
+        if target_id == source_id {
+            bail!("cannot merge an item with itself");
+        }
+        let target = self
+            .get_item(target_id)?
+            .with_context(|| format!("item not found: {target_id}"))?;
+        let source = self
+            .get_item(source_id)?
+            .with_context(|| format!("item not found: {source_id}"))?;
+        if target.content_type != source.content_type {
+            bail!(
+                "cannot merge a {} into a {}",
+                source.content_type.as_str(),
+                target.content_type.as_str()
+            );
+        }
+
+        self.conn.execute(
+            r#"
+            UPDATE library_items SET
+                name = ?2,
+                source_platform = ?3,
+                source_project_id = ?4,
+                updated_at = datetime('now')
+            WHERE id = ?1
+            "#,
+            params![source_id, target.name, target.source_platform, target.source_project_id],
+        )?;
+        for tag in &target.tags {
+            self.add_tag_to_item(source_id, &tag.name)?;
+        }
+        for tag in &source.tags {
+            self.add_tag_to_item(target_id, &tag.name)?;
+        }
+
+        self.get_item(source_id)?
+            .ok_or_else(|| anyhow::anyhow!("item not found"))
+    }
+
+    /// Compute SHA-1/SHA-512 for items stored before secondary digests existed.
+    /// Runs as part of [`Library::sync_with_store`]. Items whose files are
+    /// missing keep NULL digests. Returns the number of items updated.
+    pub fn backfill_digests(&self, paths: &Paths) -> Result<usize> {
+        self.ensure_writable()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, hash, content_type FROM library_items WHERE sha1 IS NULL OR sha512 IS NULL",
+        )?;
+        let pending = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut updated = 0;
+        for (id, hash, content_type) in pending {
+            let Some(content_type) = LibraryContentType::from_str(&content_type) else {
+                continue;
+            };
+            let path = self.content_store_path(paths, content_type, &hash);
+            if !path.exists() {
+                continue;
+            }
+            let digests = hash_file_digests(&path)?;
+            self.conn.execute(
+                "UPDATE library_items SET sha1 = ?2, sha512 = ?3 WHERE id = ?1",
+                params![id, digests.sha1, digests.sha512],
+            )?;
+            updated += 1;
+        }
+
+        self.conn.execute(
+            "INSERT INTO library_meta (key, value) VALUES (?1, datetime('now'))
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![DIGESTS_BACKFILLED_KEY],
+        )?;
+        Ok(updated)
+    }
+
+    /// When [`Library::backfill_digests`] last finished, if ever
+    pub fn digests_backfilled_at(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT value FROM library_meta WHERE key = ?1",
+                params![DIGESTS_BACKFILLED_KEY],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
     // ========== Purge Unused Items ==========
 
     /// Get all unused items (items not referenced by any profile)
@@ -1059,18 +1810,11 @@ impl Library {
         Ok(summary)
     }
 
-    /// Purge unused items from the library and optionally from the store
-    pub fn purge_unused_items(
-        &self,
-        paths: &Paths,
-        content_types: &[LibraryContentType],
-        delete_files: bool,
-    ) -> Result<PurgeResult> {
-        let mut result = PurgeResult::default();
+    /// Items `purge_unused_items` would delete for these content types (all
+    /// when empty)
+    pub fn purge_candidates(&self, content_types: &[LibraryContentType]) -> Result<Vec<UnusedItem>> {
         let unused = self.get_unused_items()?;
-
-        // Collect items to delete based on selected content types
-        let items_to_delete: Vec<UnusedItem> = if content_types.is_empty() {
+        Ok(if content_types.is_empty() {
             // Delete all unused if no filter specified
             unused.mods.into_iter()
                 .chain(unused.resourcepacks)
@@ -1088,9 +1832,19 @@ impl Library {
                 }
             }
             items
-        };
+        })
+    }
 
-        for item in items_to_delete {
+    /// Purge unused items from the library and optionally from the store
+    pub fn purge_unused_items(
+        &self,
+        paths: &Paths,
+        content_types: &[LibraryContentType],
+        delete_files: bool,
+    ) -> Result<PurgeResult> {
+        self.ensure_writable()?;
+        let mut result = PurgeResult::default();
+        for item in self.purge_candidates(content_types)? {
             // Delete file from store if requested
             if delete_files {
                 let store_path = self.content_store_path(paths, item.content_type, &item.hash);
@@ -1121,3 +1875,162 @@ impl Library {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::TempDir;
+
+    #[test]
+    fn test_normalize_content_name() {
+        assert_eq!(normalize_content_name("fabric-api-0.92.0+1.20.1.jar"), "Fabric Api");
+        assert_eq!(normalize_content_name("sodium-fabric-0.5.3+mc1.20.1.jar"), "Sodium");
+        assert_eq!(normalize_content_name("BetterF3-7.0.1-Fabric-1.20.1"), "BetterF3");
+        assert_eq!(normalize_content_name("Xaeros_Minimap_23.9.1_Fabric_1.20.1.jar"), "Xaeros Minimap");
+        assert_eq!(normalize_content_name("iris-mc1.20.1-1.6.4.jar"), "Iris");
+        assert_eq!(normalize_content_name("Fabric API"), "Fabric API");
+        assert_eq!(normalize_content_name("1.20-pack.zip"), "1.20-pack.zip");
+        assert!(is_placeholder_name("mod-0123abcd", "mod"));
+        assert!(!is_placeholder_name("mod-menu", "mod"));
+    }
+
+    #[test]
+    fn test_query_is_limited_to_views() {
+        let dir = TempDir::new("library-query");
+        let library = Library::open(&dir.join("library.db")).unwrap();
+        library.create_tag("perf", None).unwrap();
+
+        let result = library
+            .query("SELECT name, tags FROM items_with_tags UNION ALL SELECT profile_id, hash FROM profile_usage")
+            .unwrap();
+        assert_eq!(result.columns, vec!["name", "tags"]);
+        assert!(result.rows.is_empty());
+
+        assert!(library.query("SELECT * FROM library_items").is_err());
+        assert!(library.query("SELECT * FROM tags").is_err());
+        assert!(library.query("DELETE FROM library_items").is_err());
+        assert!(library.query("PRAGMA table_info(tags)").is_err());
+        assert_eq!(library.list_tags().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_check_query_plans() {
+        let dir = TempDir::new("library-plans");
+        let library = Library::open(&dir.join("library.db")).unwrap();
+        let checks = library.check_query_plans().unwrap();
+        let tags = checks.iter().find(|check| check.query == "tags").unwrap();
+        assert!(tags.missing_indexes.iter().any(|sql| sql.contains("item_tags(tag_id)")));
+
+        let created = library.add_missing_indexes().unwrap();
+        assert!(created.iter().any(|sql| sql.contains("profile_items(item_id)")));
+        for check in library.check_query_plans().unwrap() {
+            assert!(check.missing_indexes.is_empty(), "{}: {:?}", check.query, check.plan);
+        }
+    }
+
+    #[test]
+    fn test_add_tag_to_matching() {
+        let dir = TempDir::new("library-tag");
+        let library = Library::open(&dir.join("library.db")).unwrap();
+        for (hash, name, content_type) in [
+            ("a1", "Sodium", "mod"),
+            ("b2", "Sodium Extra", "mod"),
+            ("c3", "Lithium", "mod"),
+            ("d4", "Sodium Shaders", "shaderpack"),
+        ] {
+            library
+                .add_item(&LibraryItemInput {
+                    hash: hash.to_string(),
+                    name: Some(name.to_string()),
+                    content_type: Some(content_type.to_string()),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let filter = LibraryFilter {
+            content_type: Some("mod".to_string()),
+            search: Some("sodium".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(library.add_tag_to_matching(&filter, "performance").unwrap(), 2);
+        assert_eq!(library.add_tag_to_matching(&filter, "performance").unwrap(), 0);
+
+        let tagged = library
+            .list_items(&LibraryFilter {
+                tags: Some(vec!["performance".to_string()]),
+                ..Default::default()
+            })
+            .unwrap();
+        let mut names: Vec<_> = tagged.into_iter().map(|item| item.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["Sodium", "Sodium Extra"]);
+    }
+
+    #[test]
+    fn test_item_provenance() {
+        let dir = TempDir::new("library-provenance");
+        let library = Library::open(&dir.join("library.db")).unwrap();
+        let ids: Vec<i64> = ["v1", "v2", "v3"]
+            .into_iter()
+            .map(|hash| {
+                library
+                    .add_item(&LibraryItemInput {
+                        hash: hash.to_string(),
+                        name: Some(format!("Sodium {hash}")),
+                        ..Default::default()
+                    })
+                    .unwrap()
+                    .id
+            })
+            .collect();
+        library.record_replacement_by_hash("v1", "v2").unwrap();
+        library.record_replacement_by_hash("v2", "v3").unwrap();
+        assert!(library.record_replacement(ids[2], ids[0]).is_err());
+
+        let middle = library.get_item_by_hash("v2").unwrap().unwrap();
+        assert_eq!((middle.replaces, middle.replaced_by), (Some(ids[0]), Some(ids[2])));
+        let listed = library.list_items(&LibraryFilter::default()).unwrap();
+        let oldest = listed.iter().find(|item| item.id == ids[0]).unwrap();
+        assert_eq!((oldest.replaces, oldest.replaced_by), (None, Some(ids[1])));
+
+        let history: Vec<i64> = library.get_item_history(ids[2]).unwrap().iter().map(|item| item.id).collect();
+        assert_eq!(history, ids);
+    }
+    #[test]
+    fn test_inspect_mode() {
+        use crate::disk::{DiskErrorKind, find_disk_error};
+        use crate::fake_cdn::TempRoot;
+        use crate::profile::{Runtime, create_profile, load_profile, save_profile};
+
+        let root = TempRoot::new("inspect-mode");
+        let library = Library::from_paths(&root.paths).unwrap();
+        library
+            .add_item(&LibraryItemInput {
+                hash: "a1".to_string(),
+                name: Some("Sodium".to_string()),
+                content_type: Some("mod".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        drop(library);
+        create_profile(&root.paths, "shared", "1.21.1", None, Runtime::default()).unwrap();
+        let before = fs::read(&root.paths.library_db).unwrap();
+
+        let mut paths = root.paths.clone();
+        paths.read_only = true;
+        paths.ensure().unwrap();
+        let library = Library::from_paths(&paths).unwrap();
+        assert_eq!(library.list_items(&LibraryFilter::default()).unwrap().len(), 1);
+        let err = library.create_tag("performance", None).unwrap_err();
+        assert_eq!(find_disk_error(&err).unwrap().kind, DiskErrorKind::Inspect);
+
+        let mut profile = load_profile(&paths, "shared").unwrap();
+        profile.mc_version = "1.20.1".to_string();
+        let err = save_profile(&paths, &profile).unwrap_err();
+        assert_eq!(find_disk_error(&err).unwrap().kind, DiskErrorKind::Inspect);
+        assert_eq!(load_profile(&paths, "shared").unwrap().mc_version, "1.21.1");
+        assert_eq!(fs::read(&paths.library_db).unwrap(), before);
+    }
+}
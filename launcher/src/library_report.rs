@@ -0,0 +1,378 @@
+//! Library audit report
+//!
+//! Summarizes a library for users auditing large collections: item counts
+//! and sizes per content type, the most used tags, items no profile uses and
+//! likely duplicates (several files of the same project, or with the same
+//! normalized name). Rendered as Markdown or a standalone HTML page.
+
+use crate::library::{Library, LibraryContentType, LibraryFilter, LibraryItem, UnusedItem, normalize_content_name};
+use crate::util::now_epoch_secs;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Tags listed in the report
+const TOP_TAGS: usize = 20;
+/// Largest items listed in the report
+const LARGEST_ITEMS: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeUsage {
+    pub content_type: LibraryContentType,
+    pub count: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TagUsage {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Items that look like copies of the same content
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    /// `platform:project` or the normalized name the items share
+    pub key: String,
+    pub items: Vec<LibraryItem>,
+    /// Size of all but the largest item
+    pub redundant_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryReport {
+    pub generated_at: u64,
+    pub total_items: usize,
+    pub total_bytes: u64,
+    pub by_type: Vec<TypeUsage>,
+    pub top_tags: Vec<TagUsage>,
+    pub largest: Vec<LibraryItem>,
+    pub unused: Vec<UnusedItem>,
+    pub unused_bytes: u64,
+    pub duplicates: Vec<DuplicateGroup>,
+}
+
+fn item_size(item: &LibraryItem) -> u64 {
+    item.file_size.unwrap_or(0).max(0) as u64
+}
+
+/// Group items by store project, then by normalized name for items without
+/// one. Only groups with more than one item are returned.
+fn find_duplicates(items: &[LibraryItem]) -> Vec<DuplicateGroup> {
+    let mut groups: HashMap<String, Vec<&LibraryItem>> = HashMap::new();
+    for item in items {
+        let key = match (&item.source_platform, &item.source_project_id) {
+            (Some(platform), Some(project)) => format!("{platform}:{project}"),
+            _ => format!("{}:{}", item.content_type.as_str(), normalize_content_name(&item.name).to_lowercase()),
+        };
+        groups.entry(key).or_default().push(item);
+    }
+    let mut duplicates: Vec<DuplicateGroup> = groups
+        .into_iter()
+        .filter(|(_, items)| items.len() > 1)
+        .map(|(key, items)| {
+            let total: u64 = items.iter().map(|item| item_size(item)).sum();
+            let largest = items.iter().map(|item| item_size(item)).max().unwrap_or(0);
+            DuplicateGroup {
+                key,
+                items: items.into_iter().cloned().collect(),
+                redundant_bytes: total - largest,
+            }
+        })
+        .collect();
+    duplicates.sort_by(|a, b| b.redundant_bytes.cmp(&a.redundant_bytes).then_with(|| a.key.cmp(&b.key)));
+    duplicates
+}
+
+impl Library {
+    /// Gather the audit report for every item in the library
+    pub fn report(&self) -> Result<LibraryReport> {
+        let items = self.list_items(&LibraryFilter::default())?;
+
+        let by_type = [
+            LibraryContentType::Mod,
+            LibraryContentType::ResourcePack,
+            LibraryContentType::ShaderPack,
+            LibraryContentType::Skin,
+        ]
+        .into_iter()
+        .map(|content_type| {
+            let matching = items.iter().filter(|item| item.content_type == content_type);
+            TypeUsage {
+                content_type,
+                count: matching.clone().count(),
+                bytes: matching.map(item_size).sum(),
+            }
+        })
+        .collect();
+
+        let mut tag_counts: HashMap<&str, usize> = HashMap::new();
+        for tag in items.iter().flat_map(|item| &item.tags) {
+            *tag_counts.entry(tag.name.as_str()).or_default() += 1;
+        }
+        let mut top_tags: Vec<TagUsage> = tag_counts
+            .into_iter()
+            .map(|(name, count)| TagUsage {
+                name: name.to_string(),
+                count,
+            })
+            .collect();
+        top_tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+        top_tags.truncate(TOP_TAGS);
+
+        let mut largest = items.clone();
+        largest.sort_by_key(|item| std::cmp::Reverse(item_size(item)));
+        largest.truncate(LARGEST_ITEMS);
+
+        let unused = self.get_unused_items()?;
+        let unused_bytes = unused.total_bytes;
+        let unused = [unused.mods, unused.resourcepacks, unused.shaderpacks, unused.skins].concat();
+
+        Ok(LibraryReport {
+            generated_at: now_epoch_secs(),
+            total_items: items.len(),
+            total_bytes: items.iter().map(item_size).sum(),
+            by_type,
+            top_tags,
+            largest,
+            unused,
+            unused_bytes,
+            duplicates: find_duplicates(&items),
+        })
+    }
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// One report section: a heading and a table
+struct Section {
+    title: String,
+    note: Option<String>,
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+fn sections(report: &LibraryReport) -> Vec<Section> {
+    let item_row = |item: &LibraryItem| {
+        vec![
+            item.name.clone(),
+            item.content_type.label().to_string(),
+            item.source_version.clone().unwrap_or_default(),
+            format_size(item_size(item)),
+        ]
+    };
+    vec![
+        Section {
+            title: "Content types".to_string(),
+            note: None,
+            headers: vec!["Type", "Items", "Size"],
+            rows: report
+                .by_type
+                .iter()
+                .map(|usage| {
+                    vec![
+                        usage.content_type.label().to_string(),
+                        usage.count.to_string(),
+                        format_size(usage.bytes),
+                    ]
+                })
+                .collect(),
+        },
+        Section {
+            title: "Top tags".to_string(),
+            note: None,
+            headers: vec!["Tag", "Items"],
+            rows: report
+                .top_tags
+                .iter()
+                .map(|tag| vec![tag.name.clone(), tag.count.to_string()])
+                .collect(),
+        },
+        Section {
+            title: "Largest items".to_string(),
+            note: None,
+            headers: vec!["Name", "Type", "Version", "Size"],
+            rows: report.largest.iter().map(item_row).collect(),
+        },
+        Section {
+            title: format!("Unused items ({})", report.unused.len()),
+            note: Some(format!(
+                "Not used by any profile; removing them would free {}.",
+                format_size(report.unused_bytes)
+            )),
+            headers: vec!["Name", "Type", "Size"],
+            rows: report
+                .unused
+                .iter()
+                .map(|item| {
+                    vec![
+                        item.name.clone(),
+                        item.content_type.label().to_string(),
+                        format_size(item.file_size.unwrap_or(0).max(0) as u64),
+                    ]
+                })
+                .collect(),
+        },
+        Section {
+            title: format!("Possible duplicates ({})", report.duplicates.len()),
+            note: Some("Several files of the same project or with the same name.".to_string()),
+            headers: vec!["Group", "Name", "Type", "Version", "Size"],
+            rows: report
+                .duplicates
+                .iter()
+                .flat_map(|group| {
+                    group.items.iter().map(|item| {
+                        let mut row = vec![group.key.clone()];
+                        row.extend(item_row(item));
+                        row
+                    })
+                })
+                .collect(),
+        },
+    ]
+}
+
+fn summary_line(report: &LibraryReport) -> String {
+    format!(
+        "{} items, {} in total; {} unused, {} duplicate groups",
+        report.total_items,
+        format_size(report.total_bytes),
+        report.unused.len(),
+        report.duplicates.len()
+    )
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+pub fn render_markdown(report: &LibraryReport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Library report\n\n{}", summary_line(report));
+    for section in sections(report) {
+        let _ = writeln!(out, "\n## {}\n", section.title);
+        if let Some(note) = &section.note {
+            let _ = writeln!(out, "{note}\n");
+        }
+        if section.rows.is_empty() {
+            let _ = writeln!(out, "None.");
+            continue;
+        }
+        let _ = writeln!(out, "| {} |", section.headers.join(" | "));
+        let _ = writeln!(out, "|{}", " --- |".repeat(section.headers.len()));
+        for row in &section.rows {
+            let cells: Vec<String> = row.iter().map(|cell| escape_markdown(cell)).collect();
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn render_html(report: &LibraryReport) -> String {
+    let mut out = String::from(concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Library report</title>\n<style>\n",
+        "body { font-family: system-ui, sans-serif; margin: 2rem; color: #222; }\n",
+        "table { border-collapse: collapse; margin-bottom: 1rem; }\n",
+        "th, td { border: 1px solid #ccc; padding: 0.25rem 0.6rem; text-align: left; }\n",
+        "th { background: #f3f3f3; }\n",
+        "</style>\n</head>\n<body>\n",
+    ));
+    let _ = writeln!(out, "<h1>Library report</h1>\n<p>{}</p>", escape_html(&summary_line(report)));
+    for section in sections(report) {
+        let _ = writeln!(out, "<h2>{}</h2>", escape_html(&section.title));
+        if let Some(note) = &section.note {
+            let _ = writeln!(out, "<p>{}</p>", escape_html(note));
+        }
+        if section.rows.is_empty() {
+            let _ = writeln!(out, "<p>None.</p>");
+            continue;
+        }
+        out.push_str("<table>\n<tr>");
+        for header in &section.headers {
+            let _ = write!(out, "<th>{header}</th>");
+        }
+        out.push_str("</tr>\n");
+        for row in &section.rows {
+            out.push_str("<tr>");
+            for cell in row {
+                let _ = write!(out, "<td>{}</td>", escape_html(cell));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: i64, name: &str, project: Option<&str>, size: i64) -> LibraryItem {
+        LibraryItem {
+            id,
+            hash: format!("sha256:{id}"),
+            content_type: LibraryContentType::Mod,
+            name: name.to_string(),
+            file_name: None,
+            file_size: Some(size),
+            source_url: None,
+            source_platform: project.map(|_| "modrinth".to_string()),
+            source_project_id: project.map(str::to_string),
+            source_version: None,
+            added_at: String::new(),
+            updated_at: String::new(),
+            notes: None,
+            sha1: None,
+            sha512: None,
+            tags: Vec::new(),
+            used_by_profiles: Vec::new(),
+            replaces: None,
+            replaced_by: None,
+        }
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let items = vec![
+            item(1, "Sodium", Some("AANobbMI"), 100),
+            item(2, "Sodium", Some("AANobbMI"), 120),
+            item(3, "fabric-api-0.92.0.jar", None, 50),
+            item(4, "Fabric Api", None, 40),
+            item(5, "Lithium", None, 10),
+        ];
+        let duplicates = find_duplicates(&items);
+        assert_eq!(duplicates.len(), 2);
+        assert_eq!(duplicates[0].key, "modrinth:AANobbMI");
+        assert_eq!(duplicates[0].redundant_bytes, 100);
+        assert_eq!(duplicates[1].key, "mod:fabric api");
+        assert_eq!(duplicates[1].redundant_bytes, 40);
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+}
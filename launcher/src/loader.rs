@@ -0,0 +1,254 @@
+//! Mod loaders supported by the launcher
+//!
+//! Every place that branches on a loader goes through [`LoaderKind`], and the
+//! CLI and UI list the loaders from [`list_loaders`] so they only offer what
+//! the launcher can actually install. [`suggest_loader`] picks the usual
+//! loader for a Minecraft version when a new profile doesn't name one.
+
+use crate::minecraft::{
+    resolve_fabric_latest_version, resolve_forge_latest_version, resolve_liteloader_latest_version,
+    resolve_neoforge_latest_version, resolve_optifine_latest_version, resolve_quilt_latest_version,
+};
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoaderKind {
+    Fabric,
+    Quilt,
+    Forge,
+    NeoForge,
+    /// OptiFine on its own, without Forge
+    OptiFine,
+    /// LiteLoader on its own (Minecraft 1.12.2 and older)
+    LiteLoader,
+}
+
+/// What the launcher can do with a loader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoaderCapabilities {
+    /// A dedicated server can be set up with the loader
+    pub supports_server: bool,
+    /// Installing runs the loader's installer jar (needs Java) instead of
+    /// fetching a ready-made version JSON
+    pub needs_installer: bool,
+    /// The latest loader version depends on the Minecraft version
+    pub latest_per_mc_version: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoaderInfo {
+    pub id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub capabilities: LoaderCapabilities,
+}
+
+impl LoaderKind {
+    pub const ALL: [LoaderKind; 6] = [
+        LoaderKind::Fabric,
+        LoaderKind::Quilt,
+        LoaderKind::Forge,
+        LoaderKind::NeoForge,
+        LoaderKind::OptiFine,
+        LoaderKind::LiteLoader,
+    ];
+
+    /// Identifier stored in profiles (`loader.type`)
+    pub fn id(self) -> &'static str {
+        match self {
+            LoaderKind::Fabric => "fabric",
+            LoaderKind::Quilt => "quilt",
+            LoaderKind::Forge => "forge",
+            LoaderKind::NeoForge => "neoforge",
+            LoaderKind::OptiFine => "optifine",
+            LoaderKind::LiteLoader => "liteloader",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LoaderKind::Fabric => "Fabric",
+            LoaderKind::Quilt => "Quilt",
+            LoaderKind::Forge => "Forge",
+            LoaderKind::NeoForge => "NeoForge",
+            LoaderKind::OptiFine => "OptiFine",
+            LoaderKind::LiteLoader => "LiteLoader",
+        }
+    }
+
+    pub fn capabilities(self) -> LoaderCapabilities {
+        let installer = matches!(self, LoaderKind::Forge | LoaderKind::NeoForge);
+        // Launchwrapper tweakers on top of the vanilla client
+        let tweaker = matches!(self, LoaderKind::OptiFine | LoaderKind::LiteLoader);
+        LoaderCapabilities {
+            supports_server: !tweaker,
+            needs_installer: installer,
+            latest_per_mc_version: installer || tweaker,
+        }
+    }
+
+    /// Latest loader version; `mc_version` is required when
+    /// [`LoaderCapabilities::latest_per_mc_version`] is set
+    pub fn resolve_latest_version(self, mc_version: Option<&str>) -> Result<String> {
+        match (self, mc_version) {
+            (LoaderKind::Fabric, _) => resolve_fabric_latest_version(),
+            (LoaderKind::Quilt, _) => resolve_quilt_latest_version(),
+            (LoaderKind::Forge, Some(mc)) => resolve_forge_latest_version(mc),
+            (LoaderKind::NeoForge, Some(mc)) => resolve_neoforge_latest_version(mc),
+            (LoaderKind::OptiFine, Some(mc)) => resolve_optifine_latest_version(mc),
+            (LoaderKind::LiteLoader, Some(mc)) => resolve_liteloader_latest_version(mc),
+            (kind, None) => bail!("{} versions depend on the minecraft version", kind.name()),
+        }
+    }
+
+    pub fn info(self) -> LoaderInfo {
+        LoaderInfo {
+            id: self.id().to_string(),
+            name: self.name().to_string(),
+            capabilities: self.capabilities(),
+        }
+    }
+}
+
+impl fmt::Display for LoaderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for LoaderKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match LoaderKind::ALL.into_iter().find(|kind| kind.id().eq_ignore_ascii_case(s)) {
+            Some(kind) => Ok(kind),
+            None => bail!(
+                "unsupported loader type: {s} (expected one of {})",
+                LoaderKind::ALL.map(LoaderKind::id).join(", ")
+            ),
+        }
+    }
+}
+
+/// Every loader the launcher can install
+pub fn list_loaders() -> Vec<LoaderInfo> {
+    LoaderKind::ALL.into_iter().map(LoaderKind::info).collect()
+}
+
+/// Loader suggested for a new profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoaderSuggestion {
+    pub loader: LoaderKind,
+    /// Latest loader version for the Minecraft version
+    pub version: String,
+    /// Why this loader was picked
+    pub reason: String,
+}
+
+/// Release ids as numbers: `1.20.1` is (1, 20, 1), `26.1` is (26, 1, 0).
+/// Snapshots, pre-releases and old alpha/beta ids are `None`.
+fn release_number(mc_version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = mc_version.split('.').map(|part| part.parse::<u32>().ok());
+    let major = parts.next()??;
+    let minor = parts.next()??;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+/// Ids from before releases had numbers: `b1.7.3`, `a1.2.6`, `rd-132211`, ...
+fn is_pre_release_era(mc_version: &str) -> bool {
+    let mut chars = mc_version.chars();
+    matches!((chars.next(), chars.next()), (Some('a' | 'b' | 'c'), Some('0'..='9')))
+        || mc_version.starts_with("rd-")
+        || mc_version.starts_with("inf-")
+}
+
+/// Loaders to suggest for a Minecraft version, most popular first
+fn suggestion_candidates(mc_version: &str) -> Vec<(LoaderKind, &'static str)> {
+    let Some((major, minor, patch)) = release_number(mc_version) else {
+        if is_pre_release_era(mc_version) {
+            return Vec::new();
+        }
+        return vec![
+            (LoaderKind::Fabric, "Fabric supports snapshots within days; other loaders only follow releases"),
+            (LoaderKind::Quilt, "Quilt follows snapshots like Fabric, which has no build for this one"),
+        ];
+    };
+    let modern_forge_era = major > 1 || minor > 20 || (minor == 20 && patch >= 2);
+    if modern_forge_era {
+        vec![
+            (LoaderKind::NeoForge, "most Forge-style mods and modpacks moved to NeoForge from 1.20.2 on"),
+            (LoaderKind::Fabric, "NeoForge has no build for this version yet; Fabric is the most used alternative"),
+        ]
+    } else if minor >= 14 {
+        vec![
+            (LoaderKind::Forge, "Forge has the most mods and modpacks for this version"),
+            (LoaderKind::Fabric, "Forge has no build for this version; Fabric is the most used alternative"),
+        ]
+    } else if minor >= 6 {
+        vec![(LoaderKind::Forge, "Forge was the only widely used loader for this version")]
+    } else {
+        Vec::new()
+    }
+}
+
+/// The usual loader for `mc_version` with its latest version, falling back
+/// to the next most popular loader when one has no build for it. `None` for
+/// versions no supported loader targets.
+pub fn suggest_loader(mc_version: &str) -> Result<Option<LoaderSuggestion>> {
+    let mut last_err = None;
+    for (loader, reason) in suggestion_candidates(mc_version) {
+        match loader.resolve_latest_version(Some(mc_version)) {
+            Ok(version) => {
+                return Ok(Some(LoaderSuggestion {
+                    loader,
+                    version,
+                    reason: reason.to_string(),
+                }));
+            }
+            Err(err) => last_err = Some(err),
+        }
+    }
+    match last_err {
+        Some(err) => Err(err.context(format!("no loader build found for minecraft {mc_version}"))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_loader_kind() {
+        for kind in LoaderKind::ALL {
+            assert_eq!(kind.id().parse::<LoaderKind>().unwrap(), kind);
+        }
+        assert_eq!("NeoForge".parse::<LoaderKind>().unwrap(), LoaderKind::NeoForge);
+        assert_eq!("OptiFine".parse::<LoaderKind>().unwrap(), LoaderKind::OptiFine);
+        assert!("rift".parse::<LoaderKind>().is_err());
+    }
+
+    #[test]
+    fn test_suggestion_candidates() {
+        let first = |id| suggestion_candidates(id).first().map(|(kind, _)| *kind);
+        assert_eq!(first("24w14a"), Some(LoaderKind::Fabric));
+        assert_eq!(first("1.21-pre1"), Some(LoaderKind::Fabric));
+        assert_eq!(first("1.21.4"), Some(LoaderKind::NeoForge));
+        assert_eq!(first("1.20.2"), Some(LoaderKind::NeoForge));
+        assert_eq!(first("26.1"), Some(LoaderKind::NeoForge));
+        assert_eq!(first("1.20.1"), Some(LoaderKind::Forge));
+        assert_eq!(first("1.7.10"), Some(LoaderKind::Forge));
+        assert_eq!(first("1.2.5"), None);
+        assert_eq!(first("b1.7.3"), None);
+        assert_eq!(first("rd-132211"), None);
+    }
+}
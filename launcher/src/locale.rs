@@ -0,0 +1,183 @@
+//! Localization resource packs for a profile's mods
+//!
+//! Many mods ship English only and translations are published separately as
+//! resource packs in Modrinth's `locale` category. For each installed mod this
+//! searches those packs for the mod name plus the language, keeps hits whose
+//! title or description mention both, and can install them as resource packs.
+
+use crate::content_store::{ContentItem, ContentStore, ContentType, Platform, SearchOptions};
+use crate::loader::LoaderKind;
+use crate::paths::Paths;
+use crate::profile::{Profile, load_profile, save_profile, upsert_resourcepack};
+use crate::store::configured_store_hash;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// Modrinth category of translation packs
+const LOCALE_CATEGORY: &str = "locale";
+
+/// Minecraft language codes with the names packs are usually titled with
+const LANGUAGES: &[(&str, &[&str])] = &[
+    ("de_de", &["german", "deutsch"]),
+    ("es_es", &["spanish", "español", "espanol"]),
+    ("fr_fr", &["french", "français", "francais"]),
+    ("it_it", &["italian", "italiano"]),
+    ("ja_jp", &["japanese", "日本語"]),
+    ("ko_kr", &["korean", "한국어"]),
+    ("pl_pl", &["polish", "polski"]),
+    ("pt_br", &["portuguese", "brazilian", "português", "portugues"]),
+    ("ru_ru", &["russian", "русский"]),
+    ("tr_tr", &["turkish", "türkçe", "turkce"]),
+    ("uk_ua", &["ukrainian", "українська"]),
+    ("zh_cn", &["chinese", "simplified chinese", "简体中文", "中文"]),
+    ("zh_tw", &["traditional chinese", "繁體中文", "繁体中文"]),
+];
+
+/// Terms that identify a language: its code plus known names. Unknown codes
+/// are matched by the code alone.
+pub fn language_terms(code: &str) -> Vec<String> {
+    let code = code.trim().to_ascii_lowercase().replace('-', "_");
+    let mut terms = vec![code.clone()];
+    if let Some((_, names)) = LANGUAGES.iter().find(|(c, _)| *c == code) {
+        terms.extend(names.iter().map(|n| n.to_string()));
+    }
+    terms
+}
+
+/// Searchable mod name from a content name such as `sodium-fabric-0.5.8+mc1.20.1`
+pub fn mod_search_name(name: &str) -> String {
+    name.split(['-', '_', ' ', '+'])
+        .take_while(|part| {
+            let lower = part.to_ascii_lowercase();
+            let digits = lower
+                .strip_prefix("mc")
+                .or_else(|| lower.strip_prefix('v'))
+                .unwrap_or(&lower);
+            let versionish = digits.starts_with(|c: char| c.is_ascii_digit());
+            !versionish && lower.parse::<LoaderKind>().is_err()
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocaleMatch {
+    /// Mods of the profile this pack translates
+    pub mods: Vec<String>,
+    pub pack: ContentItem,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LocaleReport {
+    pub language: String,
+    pub matches: Vec<LocaleMatch>,
+    /// Mods without a translation pack
+    pub unmatched: Vec<String>,
+}
+
+fn mentions(item: &ContentItem, term: &str) -> bool {
+    let term = term.to_lowercase();
+    item.name.to_lowercase().contains(&term) || item.description.to_lowercase().contains(&term)
+}
+
+/// Find translation packs for the enabled mods of a profile
+pub fn find_locale_packs(store: &ContentStore, profile: &Profile, language: &str) -> Result<LocaleReport> {
+    let terms = language_terms(language);
+    let display = terms.get(1).unwrap_or(&terms[0]).clone();
+    let mut report = LocaleReport {
+        language: terms[0].clone(),
+        ..Default::default()
+    };
+
+    for item in profile.mods.iter().filter(|m| m.enabled) {
+        let mut mod_name = mod_search_name(&item.name);
+        if let (Some("modrinth"), Some(project_id)) = (item.platform.as_deref(), &item.project_id)
+            && let Ok(project) = store.get_project(Platform::Modrinth, project_id)
+        {
+            mod_name = project.name;
+        }
+        if mod_name.is_empty() {
+            continue;
+        }
+
+        let options = SearchOptions {
+            query: format!("{mod_name} {display}"),
+            content_type: Some(ContentType::ResourcePack),
+            game_version: None,
+            loader: None,
+            limit: 10,
+            offset: 0,
+        };
+        let hits = store
+            .search_modrinth_in_categories(&options, &[LOCALE_CATEGORY])
+            .with_context(|| format!("failed to search translations for {mod_name}"))?;
+        let found = hits
+            .into_iter()
+            .find(|hit| mentions(hit, &mod_name) && terms.iter().any(|t| mentions(hit, t)));
+
+        match found {
+            Some(pack) => match report.matches.iter_mut().find(|m| m.pack.id == pack.id) {
+                Some(existing) => existing.mods.push(mod_name),
+                None => report.matches.push(LocaleMatch {
+                    mods: vec![mod_name],
+                    pack,
+                }),
+            },
+            None => report.unmatched.push(mod_name),
+        }
+    }
+    Ok(report)
+}
+
+/// Install matched packs into a profile, returning the names installed
+pub fn install_locale_packs(
+    paths: &Paths,
+    store: &ContentStore,
+    profile_id: &str,
+    matches: &[LocaleMatch],
+) -> Result<Vec<String>> {
+    if matches.is_empty() {
+        bail!("no translation packs to install");
+    }
+    let mut profile = load_profile(paths, profile_id)?;
+    let algorithm = configured_store_hash(paths);
+    let mut installed = Vec::new();
+    for m in matches {
+        // Translation packs rarely track every release; fall back to the newest
+        let version = store
+            .get_latest_version(Platform::Modrinth, &m.pack.id, Some(&profile.mc_version), None)
+            .or_else(|_| store.get_latest_version(Platform::Modrinth, &m.pack.id, None, None))
+            .with_context(|| format!("no downloadable version of {}", m.pack.name))?;
+        let mut content_ref = store.download_to_store(paths, &version, ContentType::ResourcePack, algorithm)?;
+        content_ref.platform = Some(Platform::Modrinth.to_string());
+        content_ref.project_id = Some(m.pack.id.clone());
+        content_ref.version_id = Some(version.id.clone());
+        if upsert_resourcepack(&mut profile, content_ref) {
+            installed.push(m.pack.name.clone());
+        }
+    }
+    save_profile(paths, &profile)?;
+    Ok(installed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mod_search_name() {
+        assert_eq!(mod_search_name("sodium-fabric-0.5.8+mc1.20.1"), "sodium");
+        assert_eq!(mod_search_name("create-1.20.1-0.5.1.f"), "create");
+        assert_eq!(mod_search_name("Xaeros_Minimap_24.0.3_Fabric_1.20"), "Xaeros Minimap");
+        assert_eq!(mod_search_name("jei-1.20.1-forge-15.2.0.27"), "jei");
+        assert_eq!(mod_search_name("modmenu-v7.2.2"), "modmenu");
+    }
+
+    #[test]
+    fn test_language_terms() {
+        assert_eq!(language_terms("zh-CN")[0], "zh_cn");
+        assert!(language_terms("de_de").contains(&"german".to_string()));
+        assert_eq!(language_terms("xx_yy"), vec!["xx_yy"]);
+    }
+}
@@ -0,0 +1,334 @@
+//! Deterministic profile lockfile (`profiles/<id>/profile.lock`)
+//!
+//! The lockfile records the exact loader version and, for every content item,
+//! its content hash, platform version id and download source. It is rewritten
+//! whenever a profile is saved and can rebuild the same profile on another
+//! machine with `profile install --locked`, much like `Cargo.lock`.
+
+use crate::disk::WriteContext;
+use crate::paths::Paths;
+use crate::profile::{
+    ContentRef, Loader, Profile, Runtime, create_profile, load_profile, save_profile,
+};
+use crate::store::{
+    ContentKind, HashAlgorithm, content_store_path, hash_file_with, normalize_hash, prefixed_hash, store_content,
+    store_from_url,
+};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Lockfile format version
+pub const LOCK_VERSION: u32 = 1;
+
+impl Paths {
+    pub fn profile_lock(&self, id: &str) -> PathBuf {
+        self.profile_dir(id).join("profile.lock")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileLock {
+    pub lock_version: u32,
+    #[serde(rename = "mcVersion")]
+    pub mc_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loader: Option<LockedLoader>,
+    #[serde(default)]
+    pub mods: Vec<LockedContent>,
+    #[serde(default)]
+    pub resourcepacks: Vec<LockedContent>,
+    #[serde(default)]
+    pub shaderpacks: Vec<LockedContent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedLoader {
+    #[serde(rename = "type")]
+    pub loader_type: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedContent {
+    pub name: String,
+    /// Content hash (`sha256:<hex>`)
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    /// Download URL used to fetch the exact file again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub enabled: bool,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl From<&ContentRef> for LockedContent {
+    fn from(item: &ContentRef) -> Self {
+        Self {
+            name: item.name.clone(),
+            hash: prefixed_hash(&item.hash),
+            file_name: item.file_name.clone(),
+            version: item.version.clone(),
+            platform: item.platform.clone(),
+            project_id: item.project_id.clone(),
+            version_id: item.version_id.clone(),
+            source: item.source.clone(),
+            enabled: item.enabled,
+            pinned: item.pinned,
+        }
+    }
+}
+
+impl From<&LockedContent> for ContentRef {
+    fn from(item: &LockedContent) -> Self {
+        Self {
+            name: item.name.clone(),
+            hash: item.hash.clone(),
+            version: item.version.clone(),
+            source: item.source.clone(),
+            file_name: item.file_name.clone(),
+            platform: item.platform.clone(),
+            project_id: item.project_id.clone(),
+            version_id: item.version_id.clone(),
+            enabled: item.enabled,
+            pinned: item.pinned,
+        }
+    }
+}
+
+fn lock_items(items: &[ContentRef]) -> Vec<LockedContent> {
+    let mut locked: Vec<LockedContent> = items.iter().map(LockedContent::from).collect();
+    // Sorted so the file only changes when the content does
+    locked.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.hash.cmp(&b.hash)));
+    locked
+}
+
+impl ProfileLock {
+    pub fn from_profile(profile: &Profile) -> Self {
+        Self {
+            lock_version: LOCK_VERSION,
+            mc_version: profile.mc_version.clone(),
+            loader: profile.loader.as_ref().map(|l| LockedLoader {
+                loader_type: l.loader_type.clone(),
+                version: l.version.clone(),
+            }),
+            mods: lock_items(&profile.mods),
+            resourcepacks: lock_items(&profile.resourcepacks),
+            shaderpacks: lock_items(&profile.shaderpacks),
+        }
+    }
+
+    /// Apply the locked version and content to a profile, keeping its runtime
+    /// and file settings
+    pub fn apply_to(&self, profile: &mut Profile) {
+        profile.mc_version = self.mc_version.clone();
+        profile.loader = self.loader.as_ref().map(|l| Loader {
+            loader_type: l.loader_type.clone(),
+            version: l.version.clone(),
+        });
+        profile.mods = self.mods.iter().map(ContentRef::from).collect();
+        profile.resourcepacks = self.resourcepacks.iter().map(ContentRef::from).collect();
+        profile.shaderpacks = self.shaderpacks.iter().map(ContentRef::from).collect();
+    }
+
+    /// Entries that cannot be reproduced exactly
+    pub fn unlocked_entries(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Some(loader) = &self.loader
+            && loader.version.eq_ignore_ascii_case("latest")
+        {
+            problems.push(format!("{} loader version is 'latest'", loader.loader_type));
+        }
+        problems
+    }
+}
+
+pub fn load_lockfile(path: &Path) -> Result<ProfileLock> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("failed to read lockfile: {}", path.display()))?;
+    let lock: ProfileLock = serde_json::from_str(&data)
+        .with_context(|| format!("failed to parse lockfile: {}", path.display()))?;
+    if lock.lock_version > LOCK_VERSION {
+        bail!(
+            "lockfile version {} is newer than supported ({LOCK_VERSION})",
+            lock.lock_version
+        );
+    }
+    Ok(lock)
+}
+
+/// Write the lockfile for a profile, leaving it untouched when nothing changed
+pub fn write_lockfile(paths: &Paths, profile: &Profile) -> Result<()> {
+    paths.ensure_writable()?;
+    let path = paths.profile_lock(&profile.id);
+    let mut data = serde_json::to_string_pretty(&ProfileLock::from_profile(profile))
+        .context("failed to serialize lockfile")?;
+    data.push('\n');
+    if fs::read_to_string(&path).is_ok_and(|existing| existing == data) {
+        return Ok(());
+    }
+    fs::write(&path, data).write_context(&path, || format!("failed to write lockfile: {}", path.display()))
+}
+
+/// Outcome of installing a profile's content
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallSummary {
+    pub downloaded: usize,
+    pub present: usize,
+}
+
+/// Make sure a content item is in the store, downloading it from its source
+/// and checking the hash if it is missing
+fn ensure_content(paths: &Paths, kind: ContentKind, item: &ContentRef) -> Result<bool> {
+    let hash = normalize_hash(&item.hash);
+    if content_store_path(paths, kind, hash).exists() {
+        return Ok(false);
+    }
+    let source = item
+        .source
+        .as_deref()
+        .filter(|s| s.starts_with("http://") || s.starts_with("https://"))
+        .with_context(|| format!("{} '{}' is not in the store and has no download source", kind.label(), item.name))?;
+
+    let (download_path, file_name) = store_from_url(paths, source)?;
+    let algorithm = HashAlgorithm::of(hash);
+    let expected = hash.split_once(':').map_or(hash, |(_, hex)| hex);
+    let actual = hash_file_with(&download_path, algorithm)?;
+    if actual != expected {
+        let _ = fs::remove_file(&download_path);
+        bail!(
+            "hash mismatch for {} '{}': expected {hash}, got {actual}",
+            kind.label(),
+            item.name
+        );
+    }
+    store_content(paths, kind, &download_path, Some(source.to_string()), Some(file_name), algorithm)?;
+    let _ = fs::remove_file(&download_path);
+    Ok(true)
+}
+
+fn ensure_all_content(
+    paths: &Paths,
+    sections: [(ContentKind, Vec<ContentRef>); 3],
+) -> Result<InstallSummary> {
+    let mut summary = InstallSummary::default();
+    for (kind, items) in sections {
+        for item in &items {
+            if ensure_content(paths, kind, item)? {
+                summary.downloaded += 1;
+            } else {
+                summary.present += 1;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Fetch any content of a profile that is missing from the store
+pub fn install_profile(paths: &Paths, profile_id: &str) -> Result<InstallSummary> {
+    let profile = load_profile(paths, profile_id)?;
+    let summary = ensure_all_content(
+        paths,
+        [
+            (ContentKind::Mod, profile.mods.clone()),
+            (ContentKind::ResourcePack, profile.resourcepacks.clone()),
+            (ContentKind::ShaderPack, profile.shaderpacks.clone()),
+        ],
+    )?;
+    save_profile(paths, &profile)?;
+    Ok(summary)
+}
+
+/// Rebuild a profile exactly as recorded in a lockfile. The profile is created
+/// if it does not exist; otherwise its version and content are replaced.
+pub fn install_locked(paths: &Paths, profile_id: &str, lock: &ProfileLock) -> Result<InstallSummary> {
+    let problems = lock.unlocked_entries();
+    if !problems.is_empty() {
+        bail!("lockfile is not fully pinned: {}", problems.join("; "));
+    }
+
+    // Fetch everything first so a failure leaves the profile as it was
+    let to_refs = |items: &[LockedContent]| items.iter().map(ContentRef::from).collect();
+    let summary = ensure_all_content(
+        paths,
+        [
+            (ContentKind::Mod, to_refs(&lock.mods)),
+            (ContentKind::ResourcePack, to_refs(&lock.resourcepacks)),
+            (ContentKind::ShaderPack, to_refs(&lock.shaderpacks)),
+        ],
+    )?;
+
+    let mut profile = if paths.is_profile_present(profile_id) {
+        load_profile(paths, profile_id)?
+    } else {
+        create_profile(paths, profile_id, &lock.mc_version, None, Runtime::default())?
+    };
+    lock.apply_to(&mut profile);
+    save_profile(paths, &profile)?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::Files;
+
+    fn item(name: &str, hash: &str) -> ContentRef {
+        ContentRef {
+            name: name.to_string(),
+            hash: hash.to_string(),
+            version: None,
+            source: Some(format!("https://cdn.modrinth.com/{name}.jar")),
+            file_name: None,
+            platform: Some("modrinth".to_string()),
+            project_id: None,
+            version_id: Some(format!("{name}-v1")),
+            enabled: true,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_lock_is_order_independent_and_round_trips() {
+        let mut profile = Profile {
+            id: "p".to_string(),
+            mc_version: "1.20.1".to_string(),
+            kind: Default::default(),
+            loader: Some(Loader {
+                loader_type: "fabric".to_string(),
+                version: "0.15.11".to_string(),
+            }),
+            mods: vec![item("sodium", "aa"), item("lithium", "sha256:bb")],
+            resourcepacks: Vec::new(),
+            shaderpacks: Vec::new(),
+            runtime: Runtime::default(),
+            files: Files::default(),
+            tunnel: None,
+            presets: Default::default(),
+        };
+        let lock = ProfileLock::from_profile(&profile);
+        assert_eq!(lock.mods[0].name, "lithium");
+        assert_eq!(lock.mods[1].hash, "sha256:aa");
+
+        profile.mods.reverse();
+        assert_eq!(ProfileLock::from_profile(&profile), lock);
+
+        let mut rebuilt = profile.clone();
+        rebuilt.mods.clear();
+        rebuilt.mc_version = "1.21".to_string();
+        lock.apply_to(&mut rebuilt);
+        assert_eq!(ProfileLock::from_profile(&rebuilt), lock);
+        assert!(lock.unlocked_entries().is_empty());
+    }
+}
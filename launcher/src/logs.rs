@@ -70,6 +70,9 @@ pub struct LogFile {
     pub modified: u64,
     /// Whether this is the current/latest log
     pub is_current: bool,
+    /// Whether this is a JVM fatal error log (`hs_err_pid*.log`)
+    #[serde(default)]
+    pub jvm_crash: bool,
 }
 
 /// Log session representing a game run
@@ -226,6 +229,7 @@ pub fn list_log_files(paths: &Paths, profile_id: &str) -> Result<Vec<LogFile>> {
                 size,
                 modified,
                 is_current,
+                jvm_crash: false,
             });
         }
     }
@@ -236,42 +240,40 @@ pub fn list_log_files(paths: &Paths, profile_id: &str) -> Result<Vec<LogFile>> {
     Ok(files)
 }
 
-/// List crash reports for a profile
+/// List crash reports for a profile, including JVM fatal error logs
+/// (`hs_err_pid*.log`) that the JVM writes to the instance directory
 pub fn list_crash_reports(paths: &Paths, profile_id: &str) -> Result<Vec<LogFile>> {
     let crash_dir = paths.instance_crash_reports(profile_id);
     let mut files = Vec::new();
 
-    if !crash_dir.exists() {
-        return Ok(files);
-    }
+    if crash_dir.exists() {
+        for entry in fs::read_dir(&crash_dir)
+            .with_context(|| format!("failed to read crash reports dir: {}", crash_dir.display()))?
+        {
+            let entry = entry.context("failed to read dir entry")?;
+            let path = entry.path();
 
-    for entry in fs::read_dir(&crash_dir)
-        .with_context(|| format!("failed to read crash reports dir: {}", crash_dir.display()))?
-    {
-        let entry = entry.context("failed to read dir entry")?;
-        let path = entry.path();
+            if path.is_file() && path.extension().map(|e| e == "txt").unwrap_or(false) {
+                files.push(crash_log_file(path, false));
+            }
+        }
+    }
 
-        if path.is_file() && path.extension().map(|e| e == "txt").unwrap_or(false) {
-            let name = path.file_name()
+    let instance_dir = paths.instance_dir(profile_id);
+    if instance_dir.exists() {
+        for entry in fs::read_dir(&instance_dir)
+            .with_context(|| format!("failed to read instance dir: {}", instance_dir.display()))?
+        {
+            let entry = entry.context("failed to read dir entry")?;
+            let path = entry.path();
+            let is_hs_err = path
+                .file_name()
                 .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            let metadata = fs::metadata(&path).ok();
-            let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
-            let modified = metadata
-                .and_then(|m| m.modified().ok())
-                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_secs())
-                .unwrap_or(0);
+                .is_some_and(is_jvm_crash_log_name);
 
-            files.push(LogFile {
-                name,
-                path,
-                size,
-                modified,
-                is_current: false,
-            });
+            if path.is_file() && is_hs_err {
+                files.push(crash_log_file(path, true));
+            }
         }
     }
 
@@ -281,6 +283,59 @@ pub fn list_crash_reports(paths: &Paths, profile_id: &str) -> Result<Vec<LogFile
     Ok(files)
 }
 
+fn crash_log_file(path: PathBuf, jvm_crash: bool) -> LogFile {
+    let name = path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let metadata = fs::metadata(&path).ok();
+    let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+    let modified = metadata
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    LogFile {
+        name,
+        path,
+        size,
+        modified,
+        is_current: false,
+        jvm_crash,
+    }
+}
+
+/// Whether a file name is a JVM fatal error log
+pub fn is_jvm_crash_log_name(name: &str) -> bool {
+    name.starts_with("hs_err_pid") && name.ends_with(".log")
+}
+
+/// Resolve a crash report file name to its path; JVM error logs live in the
+/// instance directory rather than `crash-reports/`
+pub fn crash_report_path(paths: &Paths, profile_id: &str, name: &str) -> PathBuf {
+    if is_jvm_crash_log_name(name) {
+        paths.instance_dir(profile_id).join(name)
+    } else {
+        paths.instance_crash_reports(profile_id).join(name)
+    }
+}
+
+/// Newest JVM error log written at or after `since` (unix seconds), if the
+/// game died without Minecraft writing a crash report of its own
+pub fn find_jvm_crash_since(paths: &Paths, profile_id: &str, since: u64) -> Option<LogFile> {
+    let recent: Vec<LogFile> = list_crash_reports(paths, profile_id)
+        .ok()?
+        .into_iter()
+        .filter(|file| file.modified >= since)
+        .collect();
+    if recent.iter().any(|file| !file.jvm_crash) {
+        return None;
+    }
+    recent.into_iter().next()
+}
+
 /// Log watcher for real-time log streaming
 pub struct LogWatcher {
     path: PathBuf,
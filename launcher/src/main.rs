@@ -1,38 +1,112 @@
 use anyhow::{Context, Result, bail};
 use clap::{Parser, Subcommand, ValueEnum};
+use dialoguer::{Password, Select};
+use dialoguer::theme::ColorfulTheme;
 use reqwest::blocking::Client;
 use reqwest::header::CONTENT_TYPE;
 use semver::Version;
 use serde::Deserialize;
-use shard::accounts::{load_accounts, remove_account, save_accounts, set_active};
-use shard::auth::request_device_code;
+use shard::account_export::{export_account, import_account};
+use shard::accounts::{find_account_mut, load_accounts, remove_account, save_accounts, set_active};
+use shard::api_keys::{CURSEFORGE, KeyStatus, api_key_health, track_api_key_health, verify_curseforge_key};
+use shard::asset_db::{asset_usage, gc_assets, verify_assets};
+use shard::auth::{request_device_code, start_browser_auth};
+use shard::blocklist::{block_hash, block_project, export_blocklist, import_blocklist, load_blocklist, unblock};
 use shard::config::{load_config, save_config};
-use shard::content_store::{ContentStore, ContentType, Platform, SearchOptions};
+use shard::config_history::{ConfigChangeKind, diff_snapshots, list_config_snapshots, restore_config_snapshot};
+use shard::content_store::{
+    ContentItem, ContentStore, ContentType, ContentVersion, InstallDisclosure, Platform, SearchOptions,
+    store_loader_filter,
+};
 use shard::library::{
     Library, LibraryContentType, LibraryFilter, LibraryItemInput,
 };
+use shard::library_report::{format_size, render_html, render_markdown};
+use shard::crash_stats::{CrashStats, collect_profile_crashes};
+use shard::disk::find_disk_error;
+use shard::dotminecraft::{DotMinecraftOptions, VersionSource, import_dotminecraft};
+use shard::download_queue::{load_download_queue, retry_download_queue};
+use shard::jobs::{JobKind, cancel_job, list_jobs, run_job};
+use shard::launch_script::{ScriptFormat, export_launch_script};
+use shard::legacy::migrate_legacy_data;
+use shard::game_events::{GameEvent, extract_events, summarize_events};
+use shard::instance::{InstanceLinkMode, adopt_unmanaged_files, instance_file_name, dedupe_instances, reimport_modified_files};
+use shard::instance_folders::{aux_folder_usage, relocate_aux_folder};
+use shard::keybinds::keybind_conflicts;
+use shard::loader::{LoaderKind, list_loaders, suggest_loader};
+use shard::locale::{find_locale_packs, install_locale_packs};
+use shard::lockfile::{install_locked, install_profile, load_lockfile};
 use shard::logs::{
-    filter_by_level, format_entry, list_crash_reports, list_log_files, read_log_file,
-    read_log_tail, search_logs, watch_log, LogLevel,
+    crash_report_path, filter_by_level, format_entry, list_crash_reports, list_log_files,
+    read_log_file, read_log_tail, search_logs, watch_log, LogLevel,
+};
+use shard::metered::{MeteredMode, is_metered, needs_confirmation};
+use shard::minecraft::{
+    LaunchAccount, QuickPlay, VersionRefreshAction, estimate_prepare, launch, launch_detached, list_installed_versions,
+    prefetch, prepare, refresh_version_jsons, verify_game_files, warm_up,
 };
-use shard::minecraft::{launch, prepare};
-use shard::modpack::import_mrpack;
-use shard::ops::{finish_device_code_flow, parse_loader, resolve_input, resolve_launch_account};
-use shard::paths::Paths;
+use shard::mod_config::{list_mod_configs, read_mod_config, restore_mod_config, write_mod_config};
+use shard::modpack::{export_mrpack, import_mrpack, preview_mrpack};
+use shard::mirrors::{DownloadMirrors, set_download_mirrors};
+use shard::net::set_retry_policy;
+use shard::ops::{
+    add_content, add_store_content, finish_browser_auth_flow, finish_device_code_flow, parse_loader, remove_content,
+    resolve_input, resolve_launch_account,
+};
+use shard::pack_meta::{PackInspection, PackKind, inspect_pack, install_datapack, pack_format_for};
+use shard::paths::{HOME_ENV, Paths, READ_ONLY_ENV, SPACE_ENV};
+use shard::presets::{ContentPreset, apply_preset, delete_preset, save_preset};
+use shard::process::{KILL_GRACE_PERIOD, kill_profile, list_running};
 use shard::profile::{
-    ContentRef, Loader, Runtime, clone_profile, create_profile, delete_profile, diff_profiles,
-    list_profiles, load_profile, remove_mod, remove_resourcepack, remove_shaderpack, rename_profile,
-    save_profile, upsert_mod, upsert_resourcepack, upsert_shaderpack,
+    CloneMode, ContentRef, Gamescope, JvmPreset, Loader, ProcessPriority, Profile, ProfileKind, Runtime, check_rename, clone_profile, create_profile, delete_profile, diff_profiles,
+    is_plain_folder_name, list_profiles, load_profile, load_profile_notes, profile_dirs,
+    rename_profile, save_profile, save_profile_notes,
+    upsert_mod, upsert_resourcepack, upsert_shaderpack,
+};
+use shard::provision::{apply_plan, load_plan};
+use shard::quickstart::{find_recipe, run_quickstart};
+use shard::resource_monitor::format_mib;
+use shard::scheduling::{format_cpu_list, parse_cpu_list};
+use shard::steam_shortcut::{
+    SteamShortcut, add_to_shortcuts_vdf, desktop_entry_path, steam_shortcut_files, write_desktop_entry,
+};
+use shard::seed::{DEFAULT_SEED_PORT, pull_seed, serve_seed};
+use shard::server::{accept_eula, connect_console, start_server, start_server_detached, stop_server};
+use shard::server_properties::{
+    load_server_properties, property_spec, save_server_properties, set_server_property,
+    KNOWN_PROPERTIES,
+};
+use shard::shader_settings::{copy_shader_settings, list_shader_settings};
+use shard::signing::{
+    generate_signing_key, load_trusted_keys, open_document, sign_document, signing_public_key, trust_key, untrust_key,
 };
 use shard::skin::{
     get_active_cape, get_active_skin, get_avatar_url, get_body_url, get_profile as get_mc_profile,
     get_skin_url, hide_cape, reset_skin, set_cape, set_skin_url, upload_skin, SkinVariant,
 };
-use shard::store::{ContentKind, store_content};
+use shard::snapshot::sync_snapshot_profile;
+use shard::spaces::{
+    active_space, create_space, data_dir_owner_warning, delete_space, list_spaces, set_active_space,
+};
+use shard::store::{
+    ContentKind, HashAlgorithm, configured_store_hash, content_store_path, normalize_hash, store_content, verify_store,
+};
 use shard::template::{
-    delete_template, init_builtin_templates, list_templates, load_template, save_template,
-    ContentSource, Template, TemplateLoader, TemplateRuntime,
+    delete_template, init_builtin_templates, list_templates, load_template,
+    resolve_template_version, save_template, ContentSource, Template, TemplateLoader,
+    TemplateRuntime, VersionPin,
 };
+use shard::throttle::{CancelToken, Throttle};
+use shard::tunnel::{TunnelConfig, TunnelProvider};
+use shard::updates::{
+    UpdatePlan, apply_update, check_all_updates, check_profile_updates, plan_update, rollback_update,
+    set_content_enabled,
+};
+use shard::util::{dir_size, now_epoch_secs};
+use shard::vanilla_launcher::{default_minecraft_dir, import_vanilla_launcher};
+use shard::version_tags::auto_tag_library;
+use shard::versions::{VersionChannel, list_versions};
+use shard::watchlist::{check_watched_releases, load_watchlist, mark_release_seen, unwatch_project, watch_project};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
@@ -41,6 +115,10 @@ use std::time::Duration;
 #[derive(Parser, Debug)]
 #[command(name = "shard", version, about = "Minimal Minecraft launcher")]
 struct Cli {
+    /// Keep everything under this directory instead of the default
+    /// locations (overrides SHARD_HOME)
+    #[arg(long, global = true, value_name = "DIR")]
+    data_dir: Option<PathBuf>,
     #[command(subcommand)]
     command: Command,
 }
@@ -84,7 +162,12 @@ enum Command {
     /// Shaderpack management
     Shaderpack {
         #[command(subcommand)]
-        command: PackCommand,
+        command: ShaderpackCommand,
+    },
+    /// Data pack inspection and per-world installs
+    Datapack {
+        #[command(subcommand)]
+        command: DatapackCommand,
     },
     /// Account management
     Account {
@@ -101,6 +184,31 @@ enum Command {
         #[command(subcommand)]
         command: StoreCommand,
     },
+    /// Store projects to hear about new releases of, installed or not
+    Watch {
+        #[command(subcommand)]
+        command: WatchCommand,
+    },
+    /// Projects and files that installs and imports refuse
+    Blocklist {
+        #[command(subcommand)]
+        command: BlocklistCommand,
+    },
+    /// Maintenance passes over the data directory
+    Doctor {
+        #[command(subcommand)]
+        command: DoctorCommand,
+    },
+    /// Import games set up outside Shard
+    Import {
+        #[command(subcommand)]
+        command: ImportCommand,
+    },
+    /// Keys for signing template exports, and the keys trusted on import
+    Keys {
+        #[command(subcommand)]
+        command: KeysCommand,
+    },
     /// Log viewing
     Logs {
         #[command(subcommand)]
@@ -116,16 +224,81 @@ enum Command {
         #[command(subcommand)]
         command: ModpackCommand,
     },
+    /// Game versions and installed version files
+    Versions {
+        #[command(subcommand)]
+        command: VersionsCommand,
+    },
+    /// Downloaded game asset objects
+    Assets {
+        #[command(subcommand)]
+        command: AssetsCommand,
+    },
+    /// Game files that failed to download and were queued
+    Downloads {
+        #[command(subcommand)]
+        command: DownloadsCommand,
+    },
+    /// Background work of every shard process (downloads, imports, updates, audits)
+    Jobs {
+        #[command(subcommand)]
+        command: JobsCommand,
+    },
+    /// Supported mod loaders
+    Loaders {
+        #[command(subcommand)]
+        command: LoadersCommand,
+    },
     /// Configuration
     Config {
         #[command(subcommand)]
         command: ConfigCommand,
     },
+    /// Server profile management
+    Server {
+        #[command(subcommand)]
+        command: ServerCommand,
+    },
+    /// Named spaces that scope profiles, accounts and config
+    Space {
+        #[command(subcommand)]
+        command: SpaceCommand,
+    },
+    /// Store updates for the mods, resource packs and shaders in profiles
+    Update {
+        #[command(subcommand)]
+        command: UpdateCommand,
+    },
     /// Desktop app update checks
     AppUpdate {
         #[command(subcommand)]
         command: AppUpdateCommand,
     },
+    /// Share assets, libraries and the content store with another machine on the LAN
+    #[command(group(clap::ArgGroup::new("mode").required(true).args(["listen", "from"])))]
+    Seed {
+        /// Serve local files to other machines
+        #[arg(long)]
+        listen: bool,
+        /// Pull missing files from a machine running `seed --listen` (host or host:port)
+        #[arg(long)]
+        from: Option<String>,
+        /// Port to listen on
+        #[arg(long, default_value_t = DEFAULT_SEED_PORT)]
+        port: u16,
+    },
+    /// Create a profile from a built-in setup recipe (performance, vanilla-plus, shaders)
+    Quickstart {
+        recipe: String,
+        profile: String,
+        /// Minecraft version (default: newest release all required mods support)
+        #[arg(long = "mc")]
+        mc_version: Option<String>,
+    },
+    /// Create and update profiles and accounts from a JSON or YAML plan file
+    Apply {
+        plan: PathBuf,
+    },
     /// Prepare and launch a profile
     Launch {
         profile: String,
@@ -133,6 +306,54 @@ enum Command {
         account: Option<String>,
         #[arg(long)]
         prepare_only: bool,
+        /// Print the prepared launch plan as JSON
+        #[arg(long, requires = "prepare_only")]
+        json: bool,
+        /// Start the game and return without waiting for it to exit
+        #[arg(long, conflicts_with = "prepare_only")]
+        detach: bool,
+        /// Prepare and write the java invocation to a shell script (or a .bat
+        /// file on Windows) instead of launching
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["prepare_only", "detach"])]
+        export_script: Option<PathBuf>,
+        /// Join a server right away (host or host:port)
+        #[arg(long, value_name = "HOST:PORT", conflicts_with = "world")]
+        server: Option<String>,
+        /// Open a singleplayer world right away (folder name under saves/, 1.20+)
+        #[arg(long, value_name = "SAVE")]
+        world: Option<String>,
+        /// Play the demo: no signed-in account or purchase needed
+        #[arg(long, conflicts_with = "account")]
+        demo: bool,
+        /// Only show how much would be downloaded
+        #[arg(long, conflicts_with_all = ["prepare_only", "detach", "export_script"])]
+        estimate: bool,
+        /// Don't ask before large downloads on a metered connection
+        #[arg(long)]
+        accept: bool,
+        /// Start fullscreen for Gamescope and Steam Input this once, at the
+        /// profile's resolution or the one given (1280x800 by default)
+        #[arg(long, value_name = "WIDTHxHEIGHT", num_args = 0..=1, default_missing_value = "")]
+        gamescope: Option<String>,
+    },
+    /// List running game instances
+    Ps,
+    /// Stop the running instances of a profile
+    Kill {
+        profile: String,
+        /// Seconds to wait for a clean exit before forcing it
+        #[arg(long, default_value_t = KILL_GRACE_PERIOD.as_secs())]
+        grace: u64,
+    },
+    /// Re-hash a profile's game files and download missing or corrupt ones again
+    Verify {
+        profile: String,
+        /// Throttle disk reads so games and the desktop app stay responsive
+        #[arg(long)]
+        low_priority: bool,
+        /// Read limit in MiB/s (implies --low-priority)
+        #[arg(long, value_name = "MIB")]
+        rate: Option<u64>,
     },
 }
 
@@ -151,27 +372,166 @@ enum ProfileCommand {
         memory: Option<String>,
         #[arg(long = "arg")]
         args: Vec<String>,
+        /// Run java through a wrapper (gamemoderun, mangohud, prime-run);
+        /// repeat to chain wrappers or pass wrapper arguments
+        #[arg(long = "wrapper")]
+        wrapper: Vec<String>,
+        /// Curated GC flags: aikar, g1gc or zgc (Java 21)
+        #[arg(long)]
+        jvm_preset: Option<JvmPreset>,
+        /// Process priority: low, below-normal, normal, above-normal or high
+        #[arg(long)]
+        priority: Option<ProcessPriority>,
+        /// CPU cores the game may run on, e.g. 0-3,6
+        #[arg(long)]
+        cpus: Option<String>,
+        /// Start fullscreen for Gamescope and Steam Input (Steam Deck game
+        /// mode), at 1280x800 unless a resolution is given
+        #[arg(long, value_name = "WIDTHxHEIGHT", num_args = 0..=1, default_missing_value = "1280x800")]
+        gamescope: Option<Gamescope>,
         /// Create from a template
         #[arg(long)]
         template: Option<String>,
+        /// Use the usual loader for the Minecraft version (NeoForge, Forge,
+        /// Fabric for snapshots, ...)
+        #[arg(long, conflicts_with_all = ["loader", "template"])]
+        suggest: bool,
+        /// Make a dedicated server profile, run with `shard server start`
+        #[arg(long, conflicts_with = "template")]
+        server: bool,
     },
     /// Clone an existing profile
-    Clone { src: String, dst: String },
+    Clone {
+        src: String,
+        dst: String,
+        /// Copy only the manifest; the clone gets a fresh instance
+        #[arg(long)]
+        manifest_only: bool,
+    },
     /// Rename a profile
     Rename {
         /// Current profile ID
         id: String,
         /// New profile ID
         new_id: String,
+        /// Print what would change without changing it
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Diff two profiles by mod names
     Diff { a: String, b: String },
     /// Print a profile manifest
     Show { id: String },
+    /// Report problems in a profile's instance, such as conflicting keybinds
+    Check { id: String },
+    /// Add mods and packs dropped into the instance folders to the profile
+    Adopt {
+        id: String,
+        /// Also save the profile's files changed inside the instance as new content
+        #[arg(long)]
+        modified: bool,
+    },
+    /// Download the game version, assets and libraries ahead of the first launch
+    Prefetch { id: String },
+    /// Prefetch, link the instance and find (or install) Java, without launching
+    Warm { id: String },
+    /// Add a profile to Steam as a non-Steam game that launches in Gamescope mode
+    SteamShortcut {
+        id: String,
+        /// Where to write the .desktop entry (defaults to the applications folder)
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+        /// Also add it to Steam's shortcuts.vdf (close Steam first)
+        #[arg(long)]
+        steam: bool,
+    },
+    /// Download missing content; with --locked, rebuild exactly from the lockfile
+    Install {
+        id: String,
+        /// Reproduce the versions and hashes recorded in the lockfile
+        #[arg(long)]
+        locked: bool,
+        /// Lockfile to install from (defaults to the profile's profile.lock)
+        #[arg(long, requires = "locked")]
+        lockfile: Option<PathBuf>,
+    },
+    /// Show or edit a profile's markdown notes (README.md next to the manifest)
+    Notes {
+        id: String,
+        /// Replace the notes with the contents of a file ("-" reads stdin)
+        #[arg(long, conflicts_with = "clear")]
+        set: Option<PathBuf>,
+        /// Remove the notes
+        #[arg(long)]
+        clear: bool,
+    },
     /// Delete a profile
-    Delete { id: String },
+    Delete {
+        id: String,
+        /// Print what would change without changing it
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// List all profiles
     List,
+    /// Import profiles from the official Minecraft launcher
+    ImportVanilla {
+        /// Path to the .minecraft directory (defaults to the OS location)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Create or update the snapshot profile if a newer snapshot is out
+    SnapshotSync,
+    /// Show sizes of large instance folders (replay recordings, schematics)
+    Folders {
+        /// Only show this profile
+        id: Option<String>,
+    },
+    /// Move a large instance folder to another disk, linking it back in place
+    Relocate {
+        id: String,
+        /// replay_recordings, replay_videos or schematics
+        folder: String,
+        /// Directory on the other disk; the folder moves to <target>/<id>/<folder>
+        target: PathBuf,
+    },
+    /// List launch-time snapshots of config/ and options.txt with their changes
+    ConfigHistory { id: String },
+    /// Restore config/ and options.txt from a snapshot
+    ConfigRestore {
+        id: String,
+        /// Snapshot ID from `config-history`
+        snapshot: String,
+    },
+    /// Named presets that switch resource packs and shader packs
+    Preset {
+        #[command(subcommand)]
+        command: PresetCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PresetCommand {
+    /// List a profile's presets
+    List { id: String },
+    /// Create or replace a preset
+    Save {
+        id: String,
+        name: String,
+        /// Pack to enable (name or hash); repeatable
+        #[arg(long)]
+        enable: Vec<String>,
+        /// Pack to disable (name or hash); repeatable
+        #[arg(long)]
+        disable: Vec<String>,
+        /// Shader pack to make active
+        #[arg(long)]
+        shader: Option<String>,
+    },
+    /// Delete a preset
+    Delete { id: String, name: String },
+    /// Apply a preset before launching
+    Apply { id: String, name: String },
 }
 
 #[derive(Subcommand, Debug)]
@@ -189,6 +549,41 @@ enum ModCommand {
     Remove { profile: String, target: String },
     /// List mods in a profile
     List { profile: String },
+    /// Enable a disabled mod
+    Enable { profile: String, name: String },
+    /// Keep a mod in the profile but stop loading it (`.jar.disabled`)
+    Disable { profile: String, name: String },
+    /// Mod config files in the profile's instance
+    Config {
+        #[command(subcommand)]
+        command: ModConfigCommand,
+    },
+    /// Find translation resource packs for the profile's mods
+    Locale {
+        profile: String,
+        /// Minecraft language code (e.g. de_de, zh_cn)
+        language: String,
+        /// Install the packs that were found
+        #[arg(long)]
+        install: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ModConfigCommand {
+    /// List config files with their format
+    List { profile: String },
+    /// Print a config file
+    Show { profile: String, file: String },
+    /// Replace a config file with the contents of a local file (keeps a backup)
+    Set {
+        profile: String,
+        file: String,
+        /// File to read the new contents from
+        source: PathBuf,
+    },
+    /// Restore the most recent backup of a config file
+    Restore { profile: String, file: String },
 }
 
 #[derive(Subcommand, Debug)]
@@ -208,6 +603,121 @@ enum PackCommand {
     List { profile: String },
 }
 
+#[derive(Subcommand, Debug)]
+enum ShaderpackCommand {
+    #[command(flatten)]
+    Pack(PackCommand),
+    /// Per-pack shader settings (shaderpacks/*.txt) saved by Iris and OptiFine
+    Settings {
+        #[command(subcommand)]
+        command: ShaderSettingsCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ShaderSettingsCommand {
+    /// List the settings files of a profile
+    List { profile: String },
+    /// Copy settings to another profile, replacing its files
+    Copy {
+        from: String,
+        to: String,
+        /// Only this shader pack (name, hash or file name)
+        #[arg(long)]
+        pack: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum VersionsCommand {
+    /// List Minecraft versions, newest first
+    List {
+        /// Only these channels: release, snapshot, beta, alpha, april-fools
+        #[arg(long = "channel", value_name = "CHANNEL")]
+        channels: Vec<VersionChannel>,
+        /// Maximum versions to show
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Re-download version JSONs and clear the cached version manifest
+    #[command(group(clap::ArgGroup::new("target").required(true).args(["id", "all"])))]
+    Refresh {
+        id: Option<String>,
+        /// Refresh every installed version
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AssetsCommand {
+    /// Re-hash asset objects, deleting corrupt ones so they are downloaded again
+    Verify {
+        /// Only objects not verified within this many days
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u64>,
+        /// Throttle disk reads so games and the desktop app stay responsive
+        #[arg(long)]
+        low_priority: bool,
+        /// Read limit in MiB/s (implies --low-priority)
+        #[arg(long, value_name = "MIB")]
+        rate: Option<u64>,
+    },
+    /// Delete asset objects no asset index references
+    Gc {
+        /// Throttle disk reads so games and the desktop app stay responsive
+        #[arg(long)]
+        low_priority: bool,
+        /// Read limit in MiB/s (implies --low-priority)
+        #[arg(long, value_name = "MIB")]
+        rate: Option<u64>,
+    },
+    /// Count and size of downloaded asset objects
+    Stats,
+}
+
+#[derive(Subcommand, Debug)]
+enum DownloadsCommand {
+    /// List queued files with their last error
+    List,
+    /// Download the queued files again
+    Retry,
+}
+
+#[derive(Subcommand, Debug)]
+enum JobsCommand {
+    /// List running jobs with their progress
+    List,
+    /// Ask a running job to stop
+    Cancel { id: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum LoadersCommand {
+    /// List loaders and what the launcher supports for each
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum DatapackCommand {
+    /// Show a pack's target Minecraft versions and namespaces
+    Inspect {
+        input: String,
+        /// Check compatibility with this profile's Minecraft version
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Add a data pack file or URL to a world of a profile
+    Add {
+        profile: String,
+        world: String,
+        input: String,
+        /// Install even if the pack doesn't match the profile's version
+        #[arg(long)]
+        force: bool,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum ModpackCommand {
     /// Import a Modrinth .mrpack into a new profile
@@ -217,6 +727,26 @@ enum ModpackCommand {
         /// Optional profile id (defaults to pack name)
         #[arg(long)]
         id: Option<String>,
+        /// Print what would change without changing it
+        #[arg(long)]
+        dry_run: bool,
+        /// Don't ask before large downloads on a metered connection
+        #[arg(long)]
+        accept: bool,
+    },
+    /// Export a profile as a Modrinth .mrpack
+    Export {
+        profile: String,
+        /// Output .mrpack path
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Show or set the instance folders exported as overrides (e.g. kubejs,scripts)
+    Folders {
+        profile: String,
+        /// Comma-separated folder list; pass an empty string to clear
+        #[arg(long, value_delimiter = ',')]
+        set: Option<Vec<String>>,
     },
 }
 
@@ -228,6 +758,9 @@ enum AccountCommand {
         client_id: Option<String>,
         #[arg(long)]
         client_secret: Option<String>,
+        /// Sign in through a browser redirect to localhost instead of a code
+        #[arg(long)]
+        browser: bool,
     },
     /// List accounts
     List,
@@ -235,6 +768,17 @@ enum AccountCommand {
     Use { id: String },
     /// Remove an account by UUID or username
     Remove { id: String },
+    /// Sign in again to an account that needs re-authentication
+    Reauth { id: String },
+    /// Export an account encrypted with a passphrase (for another device)
+    Export {
+        id: String,
+        /// Output file
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Import an account exported with `account export`
+    Import { file: PathBuf },
     /// Show account profile info (skin, cape)
     Info { id: Option<String> },
     /// Skin management
@@ -332,12 +876,31 @@ enum TemplateCommand {
         #[arg(long)]
         loader: Option<String>,
     },
-    /// Import a template from JSON file
-    Import { path: PathBuf },
+    /// Import a template from JSON file, checking its signature if signed
+    Import {
+        path: PathBuf,
+        /// Refuse templates without a signature by a trusted key
+        #[arg(long)]
+        require_signed: bool,
+    },
     /// Export a template to JSON file
-    Export { id: String, path: PathBuf },
+    Export {
+        id: String,
+        path: PathBuf,
+        /// Sign the export with this device's signing key
+        #[arg(long)]
+        sign: bool,
+    },
     /// Delete a template
     Delete { id: String },
+    /// Pin a template content entry to a version id/number or range (omit to unpin)
+    Pin {
+        id: String,
+        /// Content name or Modrinth project
+        content: String,
+        /// e.g. "mOgUt4GM", "0.5.3" or ">=0.5, <0.6"
+        version: Option<String>,
+    },
     /// Initialize built-in templates
     Init,
 }
@@ -362,6 +925,12 @@ enum StoreCommand {
         /// Maximum results
         #[arg(long, default_value = "10")]
         limit: u32,
+        /// Pick a result and version with the arrow keys and install it
+        #[arg(long, short = 'i')]
+        interactive: bool,
+        /// Profile to install into with --interactive (default: pick one)
+        #[arg(long)]
+        profile: Option<String>,
     },
     /// Get project info
     Info {
@@ -400,7 +969,132 @@ enum StoreCommand {
         /// Content type (default: auto-detect)
         #[arg(long, short = 't')]
         content_type: Option<StoreContentType>,
+        /// Accept the license, dependencies and a large download on a metered
+        /// connection without prompting
+        #[arg(long)]
+        accept: bool,
+        /// Print what would change without changing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum WatchCommand {
+    /// Watch a project for new releases
+    Add {
+        /// Project slug or ID
+        project: String,
+        /// Platform
+        #[arg(long, default_value = "modrinth")]
+        platform: StorePlatform,
+        /// Only report releases for this Minecraft version
+        #[arg(long = "mc")]
+        game_version: Option<String>,
+        /// Only report releases for this loader
+        #[arg(long)]
+        loader: Option<String>,
+    },
+    /// Stop watching a project
+    Remove {
+        /// Project ID or name
+        project: String,
+        #[arg(long, default_value = "modrinth")]
+        platform: StorePlatform,
+    },
+    /// List watched projects
+    List,
+    /// Show new releases of watched projects and mark them as seen
+    Check {
+        /// Report the releases without marking them as seen
+        #[arg(long)]
+        keep: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BlocklistCommand {
+    /// Block a store project
+    Add {
+        /// Project slug or ID
+        project: String,
+        #[arg(long, default_value = "modrinth")]
+        platform: StorePlatform,
+        /// Why the project is blocked, shown when an install is refused
+        #[arg(long)]
+        reason: String,
+    },
+    /// Block a file by hash (sha1:, sha256:, sha512: or blake3:)
+    AddHash {
+        hash: String,
+        #[arg(long)]
+        reason: String,
+        /// Name to list the entry under
+        #[arg(long)]
+        name: Option<String>,
     },
+    /// Unblock a project id, hash or name
+    Remove { target: String },
+    /// List blocked projects and files
+    List,
+    /// Merge a shared blocklist file into this one
+    Import { file: PathBuf },
+    /// Write the blocklist to a file for sharing
+    Export { file: PathBuf },
+}
+
+#[derive(Subcommand, Debug)]
+enum DoctorCommand {
+    /// Replace content copied into instances with links to the store
+    Dedupe {
+        /// Only report what would be linked
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ImportCommand {
+    /// Build a profile from an existing .minecraft folder (mods, packs, version)
+    Dotminecraft {
+        path: PathBuf,
+        /// Profile id (defaults to the folder name)
+        #[arg(long)]
+        id: Option<String>,
+        /// Version id instead of detecting it, e.g. 1.20.1 or fabric-loader-0.15.7-1.20.1
+        #[arg(long)]
+        version: Option<String>,
+        /// Copy options.txt, the server list and config/
+        #[arg(long)]
+        options: bool,
+        /// Copy worlds from saves/
+        #[arg(long)]
+        saves: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum KeysCommand {
+    /// Generate this device's signing key
+    Generate {
+        /// Replace an existing key
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show this device's public key, to share with others who should trust it
+    Show,
+    /// Accept signatures by a public key on import
+    Trust {
+        /// Public key (ed25519:<hex>)
+        public_key: String,
+        /// Name to list the key under
+        #[arg(long)]
+        name: String,
+    },
+    /// Stop trusting a key, by public key or name
+    Untrust { target: String },
+    /// List trusted keys
+    List,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -426,15 +1120,6 @@ enum StorePlatform {
     Curseforge,
 }
 
-impl StorePlatform {
-    fn as_str(&self) -> &'static str {
-        match self {
-            StorePlatform::Modrinth => "modrinth",
-            StorePlatform::Curseforge => "curseforge",
-        }
-    }
-}
-
 impl From<StorePlatform> for Platform {
     fn from(p: StorePlatform) -> Self {
         match p {
@@ -471,7 +1156,14 @@ enum LogsCommand {
         #[arg(long)]
         level: Option<String>,
     },
-    /// List crash reports for a profile
+    /// Show game events (joins, advancements, disconnects) from a log
+    Events {
+        profile: String,
+        /// Show specific log file instead of latest
+        #[arg(long)]
+        file: Option<String>,
+    },
+    /// List crash reports for a profile
     Crashes { profile: String },
     /// Show a crash report
     Crash {
@@ -479,6 +1171,15 @@ enum LogsCommand {
         /// Crash report filename (default: latest)
         file: Option<String>,
     },
+    /// Show aggregated crash signatures (requires crash aggregation)
+    CrashStats {
+        /// Scan this profile's crash reports first
+        #[arg(long)]
+        profile: Option<String>,
+        /// Delete all collected crash data
+        #[arg(long)]
+        clear: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -489,8 +1190,189 @@ enum ConfigCommand {
     SetClientId { client_id: String },
     /// Set Microsoft client secret
     SetClientSecret { client_secret: String },
-    /// Set CurseForge API key
-    SetCurseforgeKey { api_key: String },
+    /// Set CurseForge API key (checked with CurseForge first)
+    SetCurseforgeKey {
+        api_key: String,
+        /// Save the key without checking it, e.g. while offline
+        #[arg(long)]
+        skip_verify: bool,
+    },
+    /// Check the configured CurseForge API key
+    VerifyCurseforgeKey,
+    /// Enable or disable local crash signature aggregation (on/off)
+    SetCrashAggregation {
+        #[arg(action = clap::ArgAction::Set, value_parser = clap::builder::BoolishValueParser::new())]
+        enabled: bool,
+    },
+    /// Download Temurin at launch when no installed Java fits (on/off)
+    SetAutoInstallJava {
+        #[arg(action = clap::ArgAction::Set, value_parser = clap::builder::BoolishValueParser::new())]
+        enabled: bool,
+    },
+    /// Warm up new profiles in the background in the desktop app (on/off)
+    SetWarmUp {
+        #[arg(action = clap::ArgAction::Set, value_parser = clap::builder::BoolishValueParser::new())]
+        enabled: bool,
+    },
+    /// Swap in an LWJGL with ARM natives for versions that lack them (on/off)
+    SetArmLwjgl {
+        #[arg(action = clap::ArgAction::Set, value_parser = clap::builder::BoolishValueParser::new())]
+        enabled: bool,
+    },
+    /// Open the data dir read-only on this machine (on/off)
+    SetReadOnly {
+        #[arg(action = clap::ArgAction::Set, value_parser = clap::builder::BoolishValueParser::new())]
+        enabled: bool,
+    },
+    /// Keep a profile on the newest snapshot ("off" to stop)
+    SetSnapshotProfile { profile: String },
+    /// Hash used for newly stored content (sha256 or blake3)
+    SetStoreHash { algorithm: HashAlgorithm },
+    /// How content is placed into instances (hardlink, symlink or copy)
+    SetInstanceLinks { mode: InstanceLinkMode },
+    /// Treat the connection as metered: auto (ask the OS), on or off
+    SetMetered { mode: MeteredMode },
+    /// Retries for network requests (1 disables retrying)
+    SetNetworkRetry {
+        attempts: u32,
+        /// Delay before the first retry; doubled for each one after
+        #[arg(long)]
+        base_delay_ms: Option<u64>,
+        /// Fraction of each delay randomized (0.0 - 1.0)
+        #[arg(long)]
+        jitter: Option<f64>,
+    },
+    /// Mirrors for Mojang downloads, with fallback to the official servers
+    SetMirrors {
+        /// Start from a built-in set: bmclapi, or off for the official servers only
+        preset: Option<String>,
+        /// Base URL replacing piston-meta/piston-data ("off" to clear)
+        #[arg(long)]
+        manifest: Option<String>,
+        /// Base URL replacing libraries.minecraft.net ("off" to clear)
+        #[arg(long)]
+        libraries: Option<String>,
+        /// Base URL replacing resources.download.minecraft.net ("off" to clear)
+        #[arg(long)]
+        assets: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ServerCommand {
+    /// Run a server profile with its console on this terminal
+    Start {
+        profile: String,
+        /// Accept the Minecraft EULA (https://aka.ms/MinecraftEULA) for this server
+        #[arg(long)]
+        accept_eula: bool,
+        /// Run in the background; reach the console with `shard server console`
+        #[arg(long)]
+        detach: bool,
+    },
+    /// Stop a running server, saving its worlds
+    Stop { profile: String },
+    /// Send console commands over RCON; without a command, read them from stdin
+    Console {
+        profile: String,
+        #[arg(trailing_var_arg = true)]
+        command: Vec<String>,
+    },
+    /// Edit server.properties for a profile
+    Config {
+        #[command(subcommand)]
+        command: ServerConfigCommand,
+    },
+    /// Configure the tunnel started alongside launches
+    Tunnel {
+        #[command(subcommand)]
+        command: ServerTunnelCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ServerConfigCommand {
+    /// Show all properties (known keys with defaults, plus extras)
+    Show { profile: String },
+    /// Get a single property
+    Get { profile: String, key: String },
+    /// Set a property (known keys are validated)
+    Set {
+        profile: String,
+        key: String,
+        value: String,
+    },
+    /// Remove a property
+    Unset { profile: String, key: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum ServerTunnelCommand {
+    /// Set the tunnel for a profile (ngrok, playit, custom)
+    Set {
+        profile: String,
+        provider: String,
+        /// Path to the tunnel binary (required for custom)
+        #[arg(long)]
+        binary: Option<String>,
+        /// Argument passed to the binary; `{port}` is replaced with the local port
+        #[arg(long = "arg", allow_hyphen_values = true)]
+        args: Vec<String>,
+        /// Local port to expose (defaults to server-port)
+        #[arg(long)]
+        port: Option<u16>,
+    },
+    /// Remove the tunnel from a profile
+    Clear { profile: String },
+    /// Show the tunnel configured for a profile
+    Show { profile: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum SpaceCommand {
+    /// List spaces
+    List,
+    /// Print the active space
+    Current,
+    /// Create a new space
+    Create { name: String },
+    /// Switch the active space ("default" returns to the shared root)
+    Use { name: String },
+    /// Delete a space and its profiles, accounts and config
+    Delete { name: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum UpdateCommand {
+    /// List available updates
+    Check {
+        /// Only check this profile
+        profile: Option<String>,
+    },
+    /// Replace a profile's content with another store version
+    Apply {
+        profile: String,
+        /// Content name as shown by `update check`
+        content: String,
+        /// Content type
+        #[arg(long, short = 't', default_value = "mod")]
+        content_type: StoreContentType,
+        /// Version ID to switch to (default: the latest compatible version)
+        #[arg(long)]
+        version: Option<String>,
+        /// Print what would change without changing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Go back to the version an update replaced and pin it
+    Rollback {
+        profile: String,
+        /// Content name as shown in the profile
+        content: String,
+        /// Content type
+        #[arg(long, short = 't', default_value = "mod")]
+        content_type: StoreContentType,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -534,6 +1416,11 @@ enum LibraryCommand {
         /// Item ID or hash
         id: String,
     },
+    /// Show the version history of a library item
+    History {
+        /// Item ID or hash
+        id: String,
+    },
     /// Import a file or folder into the library
     Import {
         /// Path to file or folder
@@ -564,10 +1451,67 @@ enum LibraryCommand {
         #[arg(long)]
         notes: Option<String>,
     },
+    /// Delete items no profile uses
+    Purge {
+        /// Only these content types (mod, resourcepack, shaderpack, skin)
+        #[arg(long = "type", short = 't')]
+        content_types: Vec<String>,
+        /// Also delete the files from the content store
+        #[arg(long)]
+        delete_files: bool,
+        /// Print what would change without changing it
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Show library statistics
     Stats,
+    /// Write an audit report: sizes by type, top tags, unused items and
+    /// duplicates. Prints Markdown when no file is given.
+    Report {
+        /// Write the report as HTML
+        #[arg(long)]
+        html: Option<PathBuf>,
+        /// Write the report as Markdown
+        #[arg(long)]
+        markdown: Option<PathBuf>,
+    },
+    /// Run a read-only SQL query against the items_with_tags and
+    /// profile_usage views
+    Query {
+        sql: String,
+        /// Print rows as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Explain the queries behind the common filters and report missing
+    /// indexes; set SHARD_SQL_PROFILE=<ms> to time statements as they run
+    Doctor {
+        /// Create the missing indexes
+        #[arg(long)]
+        fix: bool,
+    },
     /// Sync library with content store
     Sync,
+    /// Clean up file-derived item names ("fabric-api-0.92.0.jar" -> "Fabric Api")
+    Normalize,
+    /// Map an item to the same project as another, taking its name and source
+    Merge {
+        /// Item ID or hash to take the name and source from
+        target: String,
+        /// Item ID or hash to update
+        source: String,
+    },
+    /// Tag items with the Minecraft versions they support (mc:1.21.x) from store data
+    AutoTag,
+    /// Re-hash the content store and report corrupted files
+    Verify {
+        /// Throttle disk reads so games and the desktop app stay responsive
+        #[arg(long)]
+        low_priority: bool,
+        /// Read limit in MiB/s (implies --low-priority)
+        #[arg(long, value_name = "MIB")]
+        rate: Option<u64>,
+    },
     /// Tag management
     Tag {
         #[command(subcommand)]
@@ -606,10 +1550,29 @@ enum TagCommand {
         /// Tag name
         tag: String,
     },
+    /// Add a tag to every item matching a filter
+    AddMatching {
+        /// Tag name
+        tag: String,
+        /// Content type filter (mod, resourcepack, shaderpack, skin)
+        #[arg(long, short = 't')]
+        content_type: Option<String>,
+        /// Search by name
+        #[arg(long, short = 's')]
+        search: Option<String>,
+        /// Only items that already have one of these tags
+        #[arg(long = "with-tag")]
+        with_tag: Option<Vec<String>>,
+    },
 }
 
 fn main() {
     if let Err(err) = run() {
+        // A full or read-only disk is actionable on its own; skip the chain
+        if let Some(disk) = find_disk_error(&err) {
+            eprintln!("error: {disk}");
+            std::process::exit(1);
+        }
         eprintln!("error: {err}");
         let mut source = err.source();
         while let Some(inner) = source {
@@ -623,8 +1586,21 @@ fn main() {
 fn run() -> Result<()> {
     dotenvy::dotenv().ok();
     let cli = Cli::parse();
-    let paths = Paths::new()?;
+    let paths = Paths::with_data_dir(cli.data_dir.as_deref())?;
     paths.ensure()?;
+    match migrate_legacy_data(&paths) {
+        Ok(changes) => {
+            for change in changes {
+                eprintln!("upgraded {change}");
+            }
+        }
+        Err(err) => eprintln!("warning: failed to upgrade data from the old CLI: {err:#}"),
+    }
+    if let Ok(config) = load_config(&paths) {
+        set_retry_policy(config.network_retry);
+        set_download_mirrors(config.mirrors);
+    }
+    track_api_key_health(&paths);
 
     match cli.command {
         Command::List => {
@@ -645,24 +1621,76 @@ fn run() -> Result<()> {
                 java,
                 memory,
                 args,
+                wrapper,
+                jvm_preset,
+                priority,
+                cpus,
+                gamescope,
                 template,
+                suggest,
+                server,
             } => {
+                let runtime = Runtime {
+                    java,
+                    memory,
+                    args,
+                    wrapper,
+                    jvm_preset,
+                    priority,
+                    cpu_affinity: cpus.as_deref().map(parse_cpu_list).transpose()?.unwrap_or_default(),
+                    gamescope,
+                };
                 if let Some(template_id) = template {
                     // Initialize templates first
                     init_builtin_templates(&paths)?;
-                    create_profile_from_template(&paths, &id, &template_id, java, memory, args)?;
+                    create_profile_from_template(&paths, &id, &template_id, runtime)?;
                 } else {
                     let loader = match loader {
                         Some(value) => Some(parse_loader(&value)?),
+                        None if suggest => match suggest_loader(&mc_version)? {
+                            Some(suggestion) => {
+                                println!(
+                                    "suggested loader: {} {} ({})",
+                                    suggestion.loader.name(),
+                                    suggestion.version,
+                                    suggestion.reason
+                                );
+                                Some(parse_loader(&format!("{}@{}", suggestion.loader.id(), suggestion.version))?)
+                            }
+                            None => {
+                                println!("no loader targets minecraft {mc_version}; creating a vanilla profile");
+                                None
+                            }
+                        },
                         None => None,
                     };
-                    let runtime = Runtime { java, memory, args };
-                    create_profile(&paths, &id, &mc_version, loader, runtime)?;
-                    println!("created profile {id}");
+                    if server
+                        && let Some(loader) = &loader
+                        && !loader.loader_type.parse::<LoaderKind>()?.capabilities().supports_server
+                    {
+                        bail!("{} can't run a dedicated server", loader.loader_type);
+                    }
+                    let mut profile = create_profile(&paths, &id, &mc_version, loader, runtime)?;
+                    if server {
+                        profile.kind = ProfileKind::Server;
+                        save_profile(&paths, &profile)?;
+                        println!("created server profile {id}; start it with `shard server start {id} --accept-eula`");
+                    } else {
+                        println!("created profile {id}");
+                    }
                 }
             }
-            ProfileCommand::Clone { src, dst } => {
-                clone_profile(&paths, &src, &dst)?;
+            ProfileCommand::Clone {
+                src,
+                dst,
+                manifest_only,
+            } => {
+                let mode = if manifest_only {
+                    CloneMode::ManifestOnly
+                } else {
+                    CloneMode::Full
+                };
+                clone_profile(&paths, &src, &dst, mode)?;
                 println!("cloned profile {src} -> {dst}");
             }
             ProfileCommand::Diff { a, b } => {
@@ -699,13 +1727,136 @@ fn run() -> Result<()> {
                 let data = serde_json::to_string_pretty(&profile)?;
                 println!("{data}");
             }
-            ProfileCommand::Rename { id, new_id } => {
-                rename_profile(&paths, &id, &new_id)?;
-                println!("renamed profile {id} -> {new_id}");
+            ProfileCommand::Check { id } => {
+                load_profile(&paths, &id)?;
+                let conflicts = keybind_conflicts(&paths, &id)?;
+                if conflicts.is_empty() {
+                    println!("no problems found in profile {id}");
+                }
+                for conflict in &conflicts {
+                    println!("keybind conflict on {}:", conflict.key);
+                    for binding in &conflict.bindings {
+                        println!("  {}\t{}", binding.action, binding.source);
+                    }
+                }
             }
-            ProfileCommand::Delete { id } => {
-                delete_profile(&paths, &id)?;
-                println!("deleted profile {id}");
+            ProfileCommand::Adopt { id, modified } => {
+                let mut profile = load_profile(&paths, &id)?;
+                let mut adopted = adopt_unmanaged_files(&paths, &mut profile)?;
+                if modified {
+                    adopted.extend(reimport_modified_files(&paths, &mut profile)?);
+                }
+                if adopted.is_empty() {
+                    println!("no unmanaged files in profile {id}");
+                }
+                for file in &adopted {
+                    let state = if file.content.enabled { "" } else { " (disabled)" };
+                    match &file.replaces {
+                        Some(_) => println!("saved changed {} as new {}", file.path, file.content.name),
+                        None => println!("adopted {} as {}{state}", file.path, file.content.name),
+                    }
+                }
+            }
+            ProfileCommand::Prefetch { id } => {
+                let profile = load_profile(&paths, &id)?;
+                prefetch(&paths, &profile, |stage| println!("{}...", stage.label().to_lowercase()))?;
+                println!("prefetched {id}");
+            }
+            ProfileCommand::Warm { id } => {
+                let profile = load_profile(&paths, &id)?;
+                let java = warm_up(&paths, &profile, |stage| println!("{}...", stage.label().to_lowercase()))?;
+                println!("{id} is ready to launch (java: {java})");
+            }
+            ProfileCommand::SteamShortcut { id, output, steam } => {
+                load_profile(&paths, &id)?;
+                // The shortcut needs the same data dir when it isn't the default
+                let custom_home = cli.data_dir.is_some() || std::env::var_os(HOME_ENV).is_some();
+                let data_dir = custom_home.then_some(paths.root.as_path());
+                let shortcut = SteamShortcut::for_profile(&id, data_dir)?;
+                let path = match output {
+                    Some(path) => path,
+                    None => desktop_entry_path(&id)?,
+                };
+                write_desktop_entry(&shortcut, &path)?;
+                println!("wrote {}", path.display());
+                if steam {
+                    let files = steam_shortcut_files();
+                    if files.is_empty() {
+                        bail!("no Steam accounts found on this machine");
+                    }
+                    for file in files {
+                        let verb = if add_to_shortcuts_vdf(&file, &shortcut)? { "added to" } else { "updated in" };
+                        println!("{verb} {}", file.display());
+                    }
+                    println!("restart Steam to see \"{}\"", shortcut.name);
+                } else {
+                    println!("add it in Steam with \"Add a Non-Steam Game\", or run again with --steam");
+                }
+            }
+            ProfileCommand::Install {
+                id,
+                locked,
+                lockfile,
+            } => {
+                let summary = if locked {
+                    let lock_path = lockfile.unwrap_or_else(|| paths.profile_lock(&id));
+                    let lock = load_lockfile(&lock_path)?;
+                    install_locked(&paths, &id, &lock)?
+                } else {
+                    install_profile(&paths, &id)?
+                };
+                println!(
+                    "installed profile {id} ({} downloaded, {} already present)",
+                    summary.downloaded, summary.present
+                );
+            }
+            ProfileCommand::Notes { id, set, clear } => {
+                if clear {
+                    save_profile_notes(&paths, &id, "")?;
+                    println!("cleared notes for {id}");
+                } else if let Some(source) = set {
+                    let notes = if source.as_os_str() == "-" {
+                        std::io::read_to_string(std::io::stdin())
+                            .context("failed to read notes from stdin")?
+                    } else {
+                        fs::read_to_string(&source).with_context(|| {
+                            format!("failed to read notes file: {}", source.display())
+                        })?
+                    };
+                    save_profile_notes(&paths, &id, &notes)?;
+                    println!("saved notes for {id}");
+                } else {
+                    match load_profile_notes(&paths, &id)? {
+                        Some(notes) => print!("{notes}"),
+                        None => println!("no notes for {id}"),
+                    }
+                }
+            }
+            ProfileCommand::Rename { id, new_id, dry_run } => {
+                if dry_run {
+                    check_rename(&paths, &id, &new_id)?;
+                    for dir in profile_dirs(&paths, &id) {
+                        let target = dir.with_file_name(&new_id);
+                        println!("would move {} -> {}", dir.display(), target.display());
+                    }
+                    println!("would set the id in profile.json to {new_id}");
+                } else {
+                    rename_profile(&paths, &id, &new_id)?;
+                    println!("renamed profile {id} -> {new_id}");
+                }
+            }
+            ProfileCommand::Delete { id, dry_run } => {
+                if dry_run {
+                    if !paths.is_profile_present(&id) {
+                        bail!("profile not found: {id}");
+                    }
+                    for dir in profile_dirs(&paths, &id) {
+                        println!("would delete {} ({})", dir.display(), format_mib(dir_size(&dir)));
+                    }
+                } else {
+                    delete_profile(&paths, &id)?;
+                    println!("deleted profile {id}");
+                }
             }
             ProfileCommand::List => {
                 let profiles = list_profiles(&paths)?;
@@ -717,6 +1868,101 @@ fn run() -> Result<()> {
                     }
                 }
             }
+            ProfileCommand::ImportVanilla { dir } => {
+                let dir = match dir {
+                    Some(dir) => dir,
+                    None => default_minecraft_dir().context("could not determine .minecraft directory")?,
+                };
+                let label = dir.display().to_string();
+                let result = run_job(&paths, JobKind::Import, &label, |_| import_vanilla_launcher(&paths, &dir))?;
+                if result.profiles.is_empty() {
+                    println!("no launcher profiles found in {}", dir.display());
+                }
+                for entry in &result.profiles {
+                    match (&entry.profile_id, &entry.skipped) {
+                        (Some(id), _) => println!("imported {} as profile {id}", entry.name),
+                        (None, Some(reason)) => println!("skipped {}: {reason}", entry.name),
+                        (None, None) => {}
+                    }
+                }
+                let pending: Vec<_> = result.accounts.iter().filter(|a| !a.already_added).collect();
+                if !pending.is_empty() {
+                    println!("accounts must be signed in again with `shard account add`:");
+                    for account in pending {
+                        println!("  {}", account.username);
+                    }
+                }
+            }
+            ProfileCommand::SnapshotSync => {
+                if load_config(&paths)?.snapshot_profile.is_none() {
+                    bail!("no snapshot profile set; use `shard config set-snapshot-profile <id>`");
+                }
+                match sync_snapshot_profile(&paths)? {
+                    Some(update) => match update.previous {
+                        Some(previous) => println!(
+                            "updated profile {} from {previous} to snapshot {}",
+                            update.profile_id, update.version
+                        ),
+                        None => println!("created profile {} on snapshot {}", update.profile_id, update.version),
+                    },
+                    None => println!("snapshot profile is up to date"),
+                }
+            }
+            ProfileCommand::Folders { id } => {
+                let usage: Vec<_> = aux_folder_usage(&paths)?
+                    .into_iter()
+                    .filter(|u| id.as_ref().is_none_or(|id| &u.profile_id == id))
+                    .collect();
+                if usage.is_empty() {
+                    println!("no large instance folders found");
+                }
+                for u in usage {
+                    match &u.relocated_to {
+                        Some(target) => println!(
+                            "{}\t{}\t{}\t-> {}",
+                            u.profile_id,
+                            u.folder,
+                            format_mib(u.bytes),
+                            target.display()
+                        ),
+                        None => println!("{}\t{}\t{}", u.profile_id, u.folder, format_mib(u.bytes)),
+                    }
+                }
+            }
+            ProfileCommand::Relocate { id, folder, target } => {
+                let target = relocate_aux_folder(&paths, &id, &folder, &target)?;
+                println!("moved {folder} of profile {id} to {}", target.display());
+            }
+            ProfileCommand::ConfigHistory { id } => {
+                let snapshots = list_config_snapshots(&paths, &id)?;
+                if snapshots.is_empty() {
+                    println!("no config snapshots for profile {id} (one is taken before each launch)");
+                }
+                let now = now_epoch_secs();
+                for (i, snapshot) in snapshots.iter().enumerate() {
+                    let age = format_age(now.saturating_sub(snapshot.created_at));
+                    println!("{}\t{age}\t{} files", snapshot.id, snapshot.files.len());
+                    if let Some(previous) = snapshots.get(i + 1) {
+                        for change in diff_snapshots(previous, snapshot) {
+                            let mark = match change.kind {
+                                ConfigChangeKind::Added => '+',
+                                ConfigChangeKind::Removed => '-',
+                                ConfigChangeKind::Modified => '~',
+                            };
+                            println!("  {mark} {}", change.path);
+                        }
+                    }
+                }
+            }
+            ProfileCommand::ConfigRestore { id, snapshot } => {
+                let changes = restore_config_snapshot(&paths, &id, &snapshot)?;
+                if changes.is_empty() {
+                    println!("configs already match snapshot {snapshot}");
+                } else {
+                    println!("restored {} files from snapshot {snapshot}", changes.len());
+                }
+            }
+            ProfileCommand::Preset { command } => handle_preset_command(&paths, command)?,
         },
         Command::Mod { command } => match command {
             ModCommand::Add {
@@ -725,34 +1971,41 @@ fn run() -> Result<()> {
                 name,
                 version,
             } => {
-                let mut profile_data = load_profile(&paths, &profile)?;
-                let (path, source, file_name_hint) = resolve_input(&paths, &input)?;
-                let stored =
-                    store_content(&paths, ContentKind::Mod, &path, source, file_name_hint)?;
-                let mod_ref = ContentRef {
-                    name: name.unwrap_or(stored.name),
-                    hash: stored.hash,
-                    version,
-                    source: stored.source,
-                    file_name: Some(stored.file_name),
-                    platform: None, // CLI imports are local
-                    project_id: None,
-                    version_id: None,
-                    enabled: true,
-                    pinned: false,
-                };
-                let changed = upsert_mod(&mut profile_data, mod_ref);
-                save_profile(&paths, &profile_data)?;
-                if changed {
+                let added = add_content(&paths, &profile, &input, name, version, ContentKind::Mod)?;
+                if added.changed {
                     println!("updated profile {profile}");
                 } else {
                     println!("mod already present in profile {profile}");
                 }
+                let conflicts = keybind_conflicts(&paths, &profile)?;
+                if !conflicts.is_empty() {
+                    println!(
+                        "warning: {} conflicting keybinds in profile {profile}; see `shard profile check {profile}`",
+                        conflicts.len()
+                    );
+                }
+            }
+            ModCommand::Locale {
+                profile,
+                language,
+                install,
+            } => {
+                let profile_data = load_profile(&paths, &profile)?;
+                let store = ContentStore::modrinth_only();
+                let report = find_locale_packs(&store, &profile_data, &language)?;
+                for m in &report.matches {
+                    println!("{} ({}) for {}", m.pack.name, m.pack.slug, m.mods.join(", "));
+                }
+                if !report.unmatched.is_empty() {
+                    println!("no {} pack for: {}", report.language, report.unmatched.join(", "));
+                }
+                if install && !report.matches.is_empty() {
+                    let installed = install_locale_packs(&paths, &store, &profile, &report.matches)?;
+                    println!("installed {} translation packs to profile {profile}", installed.len());
+                }
             }
             ModCommand::Remove { profile, target } => {
-                let mut profile_data = load_profile(&paths, &profile)?;
-                if remove_mod(&mut profile_data, &target) {
-                    save_profile(&paths, &profile_data)?;
+                if remove_content(&paths, &profile, &target, ContentKind::Mod)? {
                     println!("removed mod from profile {profile}");
                 } else {
                     bail!("mod not found in profile {profile}");
@@ -764,28 +2017,259 @@ fn run() -> Result<()> {
                     println!("no mods in profile {profile}");
                 } else {
                     for mod_ref in profile_data.mods {
-                        println!("{}\t{}", mod_ref.name, mod_ref.hash);
+                        let state = if mod_ref.enabled { "" } else { "\t(disabled)" };
+                        println!("{}\t{}{state}", mod_ref.name, mod_ref.hash);
                     }
                 }
             }
+            ModCommand::Enable { profile, name } => {
+                set_content_enabled(&paths, &profile, &name, "mod", true)?;
+                println!("enabled {name} in profile {profile}");
+            }
+            ModCommand::Disable { profile, name } => {
+                set_content_enabled(&paths, &profile, &name, "mod", false)?;
+                println!("disabled {name} in profile {profile}");
+            }
+            ModCommand::Config { command } => handle_mod_config_command(&paths, command)?,
         },
         Command::Resourcepack { command } => {
             handle_pack_command(&paths, ContentKind::ResourcePack, command)?
         }
-        Command::Shaderpack { command } => {
-            handle_pack_command(&paths, ContentKind::ShaderPack, command)?
-        }
+        Command::Shaderpack { command } => match command {
+            ShaderpackCommand::Pack(command) => handle_pack_command(&paths, ContentKind::ShaderPack, command)?,
+            ShaderpackCommand::Settings { command } => match command {
+                ShaderSettingsCommand::List { profile } => {
+                    let files = list_shader_settings(&paths, &profile)?;
+                    if files.is_empty() {
+                        println!("no shader settings in profile {profile}");
+                    }
+                    for file in files {
+                        println!("{file}");
+                    }
+                }
+                ShaderSettingsCommand::Copy { from, to, pack } => {
+                    let copied = copy_shader_settings(&paths, &from, &to, pack.as_deref())?;
+                    println!("copied {} settings files from {from} to {to}", copied.len());
+                }
+            },
+        },
+        Command::Datapack { command } => handle_datapack_command(&paths, command)?,
         Command::Account { command } => handle_account_command(&paths, command)?,
         Command::Template { command } => handle_template_command(&paths, command)?,
         Command::Store { command } => handle_store_command(&paths, command)?,
+        Command::Watch { command } => handle_watch_command(&paths, command)?,
+        Command::Blocklist { command } => handle_blocklist_command(&paths, command)?,
+        Command::Doctor { command } => match command {
+            DoctorCommand::Dedupe { dry_run } => {
+                let report = dedupe_instances(&paths, dry_run)?;
+                let verb = if dry_run { "would link" } else { "linked" };
+                println!(
+                    "{verb} {} files in {} instances, saving {}",
+                    report.linked,
+                    report.instances,
+                    format_mib(report.bytes_saved)
+                );
+                for skipped in &report.skipped {
+                    println!("skipped {skipped}");
+                }
+            }
+        },
+        Command::Import { command } => match command {
+            ImportCommand::Dotminecraft {
+                path,
+                id,
+                version,
+                options,
+                saves,
+            } => {
+                let options = DotMinecraftOptions {
+                    profile_id: id,
+                    version,
+                    copy_options: options,
+                    copy_saves: saves,
+                };
+                let label = path.display().to_string();
+                let result = run_job(&paths, JobKind::Import, &label, |_| import_dotminecraft(&paths, &path, &options))?;
+                let loader = result
+                    .loader
+                    .as_ref()
+                    .map(|l| format!(" with {} {}", l.loader_type, l.version))
+                    .unwrap_or_default();
+                let source = match result.version_source {
+                    VersionSource::Explicit => "given",
+                    VersionSource::LauncherProfile => "last used launcher profile",
+                    VersionSource::VersionsDir => "versions/",
+                    VersionSource::ClientJar => "client jar",
+                };
+                println!(
+                    "created profile {}: minecraft {}{loader} (from {source}: {})",
+                    result.profile_id, result.mc_version, result.version_id
+                );
+                println!(
+                    "imported {} mods, {} resource packs, {} shader packs",
+                    result.mods, result.resourcepacks, result.shaderpacks
+                );
+                if saves {
+                    println!("copied {} worlds", result.worlds);
+                }
+                for skipped in &result.skipped {
+                    println!("warning: {skipped}");
+                }
+            }
+        },
+        Command::Keys { command } => handle_keys_command(&paths, command)?,
         Command::Logs { command } => handle_logs_command(&paths, command)?,
         Command::Library { command } => handle_library_command(&paths, command)?,
         Command::Modpack { command } => handle_modpack_command(&paths, command)?,
+        Command::Versions { command } => match command {
+            VersionsCommand::List { channels, limit } => {
+                let list = list_versions(&paths, &channels)?;
+                if list.versions.is_empty() {
+                    println!("no versions found");
+                }
+                for version in list.versions.iter().take(limit.unwrap_or(usize::MAX)) {
+                    let date = version.release_time.as_deref().and_then(|t| t.get(..10)).unwrap_or("");
+                    let latest = if list.latest_release.as_deref() == Some(version.id.as_str())
+                        || list.latest_snapshot.as_deref() == Some(version.id.as_str())
+                    {
+                        " (latest)"
+                    } else {
+                        ""
+                    };
+                    println!("{}\t{}\t{date}{latest}", version.id, version.channel.as_str());
+                }
+            }
+            VersionsCommand::Refresh { id, all } => {
+                let ids = if all { list_installed_versions(&paths)? } else { id.into_iter().collect() };
+                for refreshed in refresh_version_jsons(&paths, &ids)? {
+                    let action = match refreshed.action {
+                        VersionRefreshAction::Redownloaded => "redownloaded",
+                        VersionRefreshAction::Kept => "kept (installer output, still valid)",
+                        VersionRefreshAction::Removed => "removed (corrupt; reinstalled on next launch)",
+                    };
+                    println!("{}: {action}", refreshed.id);
+                }
+            }
+        },
+        Command::Assets { command } => match command {
+            AssetsCommand::Verify {
+                older_than,
+                low_priority,
+                rate,
+            } => {
+                let mut throttle = cli_throttle(low_priority, rate, CancelToken::new());
+                let max_age = older_than.map(|days| days * 24 * 60 * 60);
+                let report = verify_assets(&paths, max_age, &mut throttle)?;
+                println!(
+                    "verified {} objects ({}), skipped {} checked recently",
+                    report.checked,
+                    format_mib(report.bytes),
+                    report.skipped
+                );
+                if !report.missing.is_empty() {
+                    println!("{} recorded objects were missing", report.missing.len());
+                }
+                if report.corrupt.is_empty() {
+                    println!("no corrupted objects found");
+                } else {
+                    println!("removed corrupted objects (downloaded again on next launch):");
+                    for hash in &report.corrupt {
+                        println!("  {hash}");
+                    }
+                }
+            }
+            AssetsCommand::Gc { low_priority, rate } => {
+                let mut throttle = cli_throttle(low_priority, rate, CancelToken::new());
+                let report = gc_assets(&paths, &mut throttle)?;
+                if report.cancelled {
+                    println!("cancelled; only some unreferenced objects were removed");
+                }
+                println!("removed {} objects ({})", report.removed, format_mib(report.bytes));
+            }
+            AssetsCommand::Stats => {
+                let usage = asset_usage(&paths)?;
+                println!("{} objects ({})", usage.objects, format_mib(usage.bytes));
+            }
+        },
+        Command::Downloads { command } => match command {
+            DownloadsCommand::List => {
+                let queue = load_download_queue(&paths)?;
+                if queue.is_empty() {
+                    println!("no failed downloads");
+                }
+                let now = now_epoch_secs();
+                for entry in &queue {
+                    let age = format_age(now.saturating_sub(entry.last_failed_at));
+                    println!("{}\t{} failures, last {age}", entry.path.display(), entry.failures);
+                    println!("  {}", entry.error);
+                }
+            }
+            DownloadsCommand::Retry => {
+                let result = run_job(&paths, JobKind::Download, "download queue", |job| {
+                    retry_download_queue(&paths, job)
+                })?;
+                println!("downloaded {} files", result.downloaded);
+                for entry in &result.failed {
+                    println!("still failing: {}: {}", entry.path.display(), entry.error);
+                }
+            }
+        },
+        Command::Jobs { command } => match command {
+            JobsCommand::List => {
+                let jobs = list_jobs(&paths)?;
+                if jobs.is_empty() {
+                    println!("no running jobs");
+                }
+                let now = now_epoch_secs();
+                for job in jobs {
+                    let progress = match job.total {
+                        Some(total) => format!("{}/{total}", job.done),
+                        None => job.done.to_string(),
+                    };
+                    println!(
+                        "{}\t{:<8}\t{}\t{progress}\tpid {}, started {}",
+                        job.id,
+                        job.kind.as_str(),
+                        job.label,
+                        job.pid,
+                        format_age(now.saturating_sub(job.started_at))
+                    );
+                    if let Some(message) = &job.message {
+                        println!("  {message}");
+                    }
+                }
+            }
+            JobsCommand::Cancel { id } => {
+                let job = cancel_job(&paths, &id)?;
+                println!("asked {} job {} ({}) to stop", job.kind.as_str(), job.id, job.label);
+            }
+        },
+        Command::Loaders { command } => match command {
+            LoadersCommand::List => {
+                for loader in list_loaders() {
+                    let caps = &loader.capabilities;
+                    let mut notes = Vec::new();
+                    if caps.needs_installer {
+                        notes.push("installer");
+                    }
+                    if caps.supports_server {
+                        notes.push("server");
+                    }
+                    if caps.latest_per_mc_version {
+                        notes.push("latest per minecraft version");
+                    }
+                    println!("{:<10} {:<10} {}", loader.id, loader.name, notes.join(", "));
+                }
+            }
+        },
         Command::Config { command } => match command {
             ConfigCommand::Show => {
                 let config = load_config(&paths)?;
-                let data = serde_json::to_string_pretty(&config)?;
-                println!("{data}");
+                let mut data = serde_json::to_value(&config)?;
+                if let Some(key) = &config.curseforge_api_key {
+                    data["curseforge_key_health"] = serde_json::to_value(api_key_health(&paths, CURSEFORGE, key)?)?;
+                }
+                println!("{}", serde_json::to_string_pretty(&data)?);
             }
             ConfigCommand::SetClientId { client_id } => {
                 let mut config = load_config(&paths)?;
@@ -799,31 +2283,392 @@ fn run() -> Result<()> {
                 save_config(&paths, &config)?;
                 println!("saved Microsoft client secret");
             }
-            ConfigCommand::SetCurseforgeKey { api_key } => {
-                let mut config = load_config(&paths)?;
-                config.curseforge_api_key = Some(api_key.clone());
-                save_config(&paths, &config)?;
-                println!("saved CurseForge API key");
+            ConfigCommand::SetCurseforgeKey { api_key, skip_verify } => {
+                let api_key = api_key.trim().to_string();
+                if !skip_verify {
+                    let health = verify_curseforge_key(&paths, &api_key)
+                        .context("could not check the key; use --skip-verify to save it anyway")?;
+                    if health.status == KeyStatus::Invalid {
+                        bail!("CurseForge rejected the API key; it was not saved");
+                    }
+                }
+                let mut config = load_config(&paths)?;
+                config.curseforge_api_key = Some(api_key);
+                save_config(&paths, &config)?;
+                println!("saved CurseForge API key");
+            }
+            ConfigCommand::VerifyCurseforgeKey => {
+                let config = load_config(&paths)?;
+                let key = config.curseforge_api_key.context("no CurseForge API key configured")?;
+                let health = verify_curseforge_key(&paths, &key)?;
+                if health.status == KeyStatus::Invalid {
+                    bail!("CurseForge rejected the API key {}; set a new one with `shard config set-curseforge-key`", health.key_hint);
+                }
+                println!("CurseForge key {}: {}", health.key_hint, health.status.as_str());
+                if let (Some(remaining), Some(limit)) = (health.rate_remaining, health.rate_limit) {
+                    println!("rate limit: {remaining}/{limit} remaining");
+                }
+                println!("requests recorded: {}", health.requests);
+            }
+            ConfigCommand::SetCrashAggregation { enabled } => {
+                let mut config = load_config(&paths)?;
+                config.crash_aggregation_enabled = enabled;
+                save_config(&paths, &config)?;
+                if enabled {
+                    println!("crash aggregation enabled");
+                } else {
+                    println!("crash aggregation disabled");
+                }
+            }
+            ConfigCommand::SetAutoInstallJava { enabled } => {
+                let mut config = load_config(&paths)?;
+                config.auto_install_java = enabled;
+                save_config(&paths, &config)?;
+                if enabled {
+                    println!("missing Java versions will be downloaded at launch");
+                } else {
+                    println!("automatic Java downloads disabled");
+                }
+            }
+            ConfigCommand::SetWarmUp { enabled } => {
+                let mut config = load_config(&paths)?;
+                config.skip_warm_up = !enabled;
+                save_config(&paths, &config)?;
+                if enabled {
+                    println!("new profiles will be warmed up in the background");
+                } else {
+                    println!("background warm-up disabled");
+                }
+            }
+            ConfigCommand::SetArmLwjgl { enabled } => {
+                let mut config = load_config(&paths)?;
+                config.keep_lwjgl_on_arm = !enabled;
+                save_config(&paths, &config)?;
+                if enabled {
+                    println!("versions without ARM natives will use LWJGL with them on ARM machines");
+                } else {
+                    println!("versions will keep their own LWJGL on ARM machines");
+                }
+            }
+            ConfigCommand::SetReadOnly { enabled } => {
+                let mut config = load_config(&paths)?;
+                config.read_only = enabled;
+                // The switch itself is saved in inspect mode, or it could never be turned off
+                save_config(&Paths { read_only: false, ..paths.clone() }, &config)?;
+                if enabled {
+                    println!("data directory will be opened read-only");
+                } else if std::env::var(READ_ONLY_ENV).is_ok() {
+                    println!("read_only disabled; {READ_ONLY_ENV} still opens the data directory read-only");
+                } else {
+                    println!("read_only disabled");
+                }
+            }
+            ConfigCommand::SetSnapshotProfile { profile } => {
+                let mut config = load_config(&paths)?;
+                if profile == "off" {
+                    config.snapshot_profile = None;
+                    save_config(&paths, &config)?;
+                    println!("snapshot tracking disabled");
+                } else {
+                    config.snapshot_profile = Some(profile.clone());
+                    save_config(&paths, &config)?;
+                    println!("profile {profile} will follow new snapshots");
+                }
+            }
+            ConfigCommand::SetStoreHash { algorithm } => {
+                let mut config = load_config(&paths)?;
+                config.store_hash = algorithm;
+                save_config(&paths, &config)?;
+                println!("new content will be stored by {}", algorithm.as_str());
+            }
+            ConfigCommand::SetInstanceLinks { mode } => {
+                let mut config = load_config(&paths)?;
+                config.instance_links = mode;
+                save_config(&paths, &config)?;
+                println!("instances will use {} on next launch", mode.as_str());
+            }
+            ConfigCommand::SetMetered { mode } => {
+                let mut config = load_config(&paths)?;
+                config.metered = mode;
+                save_config(&paths, &config)?;
+                let state = if is_metered(&paths) { "metered" } else { "not metered" };
+                println!("metered: {} (connection is {state})", mode.as_str());
+            }
+            ConfigCommand::SetNetworkRetry {
+                attempts,
+                base_delay_ms,
+                jitter,
+            } => {
+                if attempts == 0 {
+                    bail!("attempts must be at least 1");
+                }
+                let mut config = load_config(&paths)?;
+                let policy = &mut config.network_retry;
+                policy.attempts = attempts;
+                if let Some(base_delay_ms) = base_delay_ms {
+                    policy.base_delay_ms = base_delay_ms;
+                }
+                if let Some(jitter) = jitter {
+                    policy.jitter = jitter.clamp(0.0, 1.0);
+                }
+                save_config(&paths, &config)?;
+                println!(
+                    "network requests will be tried up to {} times ({}ms base delay)",
+                    config.network_retry.attempts, config.network_retry.base_delay_ms
+                );
+            }
+            ConfigCommand::SetMirrors {
+                preset,
+                manifest,
+                libraries,
+                assets,
+            } => {
+                let mut config = load_config(&paths)?;
+                if let Some(preset) = preset {
+                    config.mirrors = DownloadMirrors::preset(&preset)?;
+                }
+                for (mirror, value) in [
+                    (&mut config.mirrors.manifest, manifest),
+                    (&mut config.mirrors.libraries, libraries),
+                    (&mut config.mirrors.assets, assets),
+                ] {
+                    if let Some(value) = value {
+                        *mirror = Some(value.trim().to_string()).filter(|url| !url.is_empty() && url != "off");
+                    }
+                }
+                save_config(&paths, &config)?;
+                if config.mirrors.is_empty() {
+                    println!("downloads will use the official servers");
+                }
+                for (name, mirror) in [
+                    ("manifest", &config.mirrors.manifest),
+                    ("libraries", &config.mirrors.libraries),
+                    ("assets", &config.mirrors.assets),
+                ] {
+                    if let Some(url) = mirror {
+                        println!("{name}: {url}");
+                    }
+                }
+            }
+        },
+        Command::Server { command } => handle_server_command(&paths, command)?,
+        Command::Space { command } => handle_space_command(&paths, command)?,
+        Command::Update { command } => handle_update_command(&paths, command)?,
+        Command::AppUpdate { command } => handle_app_update_command(command)?,
+        Command::Quickstart {
+            recipe,
+            profile,
+            mc_version,
+        } => {
+            let recipe = find_recipe(&recipe)?;
+            println!("resolving {} for profile {profile}...", recipe.name);
+            let store = ContentStore::modrinth_only();
+            let summary = run_quickstart(&paths, &store, recipe, &profile, mc_version.as_deref())?;
+            println!(
+                "created profile {} (minecraft {}, {})",
+                summary.profile_id, summary.mc_version, recipe.loader
+            );
+            for name in &summary.installed {
+                println!("  + {name}");
+            }
+            for name in &summary.skipped {
+                println!("  - {name} (not available for this version)");
+            }
+            for failure in &summary.failed {
+                println!("  ! {failure}");
+            }
+        }
+        Command::Apply { plan } => {
+            let plan = load_plan(&plan)?;
+            let config = load_config(&paths)?;
+            let store = ContentStore::new(config.curseforge_api_key.as_deref());
+            let changes = apply_plan(&paths, &store, &plan)?;
+            if changes.is_empty() {
+                println!("already up to date");
             }
-        },
-        Command::AppUpdate { command } => handle_app_update_command(command)?,
+            for change in &changes {
+                println!("{change}");
+            }
+        }
+        Command::Seed { listen, from, port } => {
+            if listen {
+                println!("indexing shared files...");
+                serve_seed(&paths, port, |manifest| {
+                    println!(
+                        "seeding {} files ({}) on port {port}, press Ctrl+C to stop",
+                        manifest.entries.len(),
+                        format_mib(manifest.total_size())
+                    );
+                })?;
+            } else if let Some(host) = from {
+                let summary = pull_seed(&paths, &host, |done, total, _| {
+                    if done % 500 == 0 || done == total {
+                        println!("  checked {done}/{total}");
+                    }
+                })?;
+                println!(
+                    "fetched {} files ({}), {} already present",
+                    summary.fetched,
+                    format_mib(summary.bytes),
+                    summary.skipped
+                );
+                if summary.unlisted > 0 {
+                    println!(
+                        "  {} libraries/indexes not listed by an installed version were not pulled",
+                        summary.unlisted
+                    );
+                }
+            }
+        }
         Command::Launch {
             profile,
             account,
             prepare_only,
+            json,
+            detach,
+            export_script,
+            server,
+            world,
+            demo,
+            estimate,
+            accept,
+            gamescope,
         } => {
-            let profile_data = load_profile(&paths, &profile)?;
-            let launch_account = resolve_launch_account(&paths, account)?;
-            if prepare_only {
-                let plan = prepare(&paths, &profile_data, &launch_account)?;
+            let mut profile_data = load_profile(&paths, &profile)?;
+            if let Some(size) = gamescope {
+                profile_data.runtime.gamescope = Some(match size.as_str() {
+                    "" => profile_data.runtime.gamescope.unwrap_or_default(),
+                    size => size.parse()?,
+                });
+            }
+            if estimate {
+                let estimate = estimate_prepare(&paths, &profile_data)?;
+                for (label, count) in [
+                    ("client", estimate.client),
+                    ("libraries", estimate.libraries),
+                    ("assets", estimate.assets),
+                ] {
+                    let unknown = if count.unknown_size > 0 {
+                        format!(", {} of unknown size", count.unknown_size)
+                    } else {
+                        String::new()
+                    };
+                    println!("{label:<10} {} files, {}{unknown}", count.files, format_size(count.bytes));
+                }
+                println!("total      {} files, {}", estimate.files(), format_size(estimate.bytes()));
+                if estimate.loader_pending {
+                    println!("the loader installer will download more on first launch");
+                }
+                if let Some(java) = estimate.java_download {
+                    println!("Java {java} will be downloaded");
+                }
+                return Ok(());
+            }
+            if !accept && is_metered(&paths) {
+                let bytes = estimate_prepare(&paths, &profile_data)?.bytes();
+                if needs_confirmation(&paths, bytes)
+                    && !confirm(&format!("the connection is metered; download {} for {profile}?", format_size(bytes)))?
+                {
+                    bail!("launch cancelled");
+                }
+            }
+            let launch_account = if demo {
+                LaunchAccount::demo()
+            } else {
+                resolve_launch_account(&paths, account)?
+            };
+            let quick_play = server.map(QuickPlay::Server).or(world.map(QuickPlay::World));
+            if let Some(path) = export_script {
+                let plan = prepare(&paths, &profile_data, &launch_account, quick_play.as_ref())?;
+                let format = export_launch_script(&plan, &profile, &path)?;
+                let kind = match format {
+                    ScriptFormat::Shell => "shell script",
+                    ScriptFormat::Batch => "batch file",
+                };
+                println!("wrote {kind} {}", path.display());
+                println!("it contains the account's access token; don't share it");
+            } else if prepare_only {
+                let plan = prepare(&paths, &profile_data, &launch_account, quick_play.as_ref())?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&plan)?);
+                    eprintln!("the plan contains the account's access token; don't share it");
+                    return Ok(());
+                }
                 println!("prepared instance: {}", plan.instance_dir.display());
+                if !plan.wrapper.is_empty() {
+                    println!("wrapper: {}", plan.wrapper.join(" "));
+                }
                 println!("java: {}", plan.java_exec);
+                if let Some(priority) = plan.priority {
+                    println!("priority: {}", priority.as_str());
+                }
+                if !plan.cpu_affinity.is_empty() {
+                    println!("cpus: {}", format_cpu_list(&plan.cpu_affinity));
+                }
+                for (key, value) in &plan.env {
+                    println!("env: {key}={value}");
+                }
                 println!("main class: {}", plan.main_class);
                 println!("classpath: {}", plan.classpath);
                 println!("jvm args: {}", plan.jvm_args.join(" "));
                 println!("game args: {}", plan.game_args.join(" "));
+            } else if detach {
+                let instance = launch_detached(&paths, &profile_data, &launch_account, quick_play.as_ref())?;
+                println!("started {} (pid {})", instance.profile_id, instance.pid);
             } else {
-                launch(&paths, &profile_data, &launch_account)?;
+                launch(&paths, &profile_data, &launch_account, quick_play.as_ref())?;
+            }
+        }
+        Command::Ps => {
+            let running = list_running(&paths)?;
+            if running.is_empty() {
+                println!("no running instances");
+            }
+            let now = now_epoch_secs();
+            for instance in running {
+                let uptime = now.saturating_sub(instance.started_at);
+                println!(
+                    "{:<8} {:<24} {}m{:02}s",
+                    instance.pid,
+                    instance.profile_id,
+                    uptime / 60,
+                    uptime % 60
+                );
+            }
+        }
+        Command::Kill { profile, grace } => {
+            for instance in kill_profile(&paths, &profile, Duration::from_secs(grace))? {
+                println!("stopped {} (pid {})", instance.profile_id, instance.pid);
+            }
+        }
+        Command::Verify {
+            profile,
+            low_priority,
+            rate,
+        } => {
+            let profile_data = load_profile(&paths, &profile)?;
+            println!("verifying game files of {profile}...");
+            let report = run_job(&paths, JobKind::Audit, &profile, |job| {
+                let mut throttle = cli_throttle(low_priority, rate, job.cancel_token());
+                verify_game_files(&paths, &profile_data, job, &mut throttle)
+            })?;
+            for path in &report.repaired {
+                println!("repaired {}", path.display());
+            }
+            for failed in &report.failed {
+                eprintln!("failed {}: {}", failed.path.display(), failed.error);
+            }
+            println!(
+                "checked {} files ({}): {} missing, {} corrupt, {} repaired, {} failed",
+                report.checked,
+                format_mib(report.bytes),
+                report.missing,
+                report.corrupt,
+                report.repaired.len(),
+                report.failed.len()
+            );
+            if !report.failed.is_empty() {
+                bail!("some files could not be repaired; run `shard downloads retry` to try again");
             }
         }
     }
@@ -831,6 +2676,164 @@ fn run() -> Result<()> {
     Ok(())
 }
 
+fn handle_update_command(paths: &Paths, command: UpdateCommand) -> Result<()> {
+    let config = load_config(paths)?;
+    let api_key = config.curseforge_api_key.as_deref();
+    match command {
+        UpdateCommand::Check { profile } => {
+            let result = match profile {
+                Some(profile) => check_profile_updates(paths, &profile, api_key)?,
+                None => check_all_updates(paths, api_key)?,
+            };
+            for err in &result.errors {
+                eprintln!("warning: {err}");
+            }
+            for update in &result.updates {
+                println!(
+                    "{}: {} {} {} -> {} ({})",
+                    update.profile_id,
+                    update.content_type,
+                    update.content.name,
+                    update.current_version.as_deref().unwrap_or("?"),
+                    update.latest_version,
+                    update.latest_version_id
+                );
+            }
+            println!(
+                "{} updates ({} checked, {} skipped)",
+                result.updates.len(),
+                result.checked,
+                result.skipped
+            );
+        }
+        UpdateCommand::Apply {
+            profile,
+            content,
+            content_type,
+            version,
+            dry_run,
+        } => {
+            let content_type = match content_type {
+                StoreContentType::Mod => "mod",
+                StoreContentType::Resourcepack => "resourcepack",
+                StoreContentType::Shader => "shaderpack",
+            };
+            let version_id = match version {
+                Some(version) => version,
+                None => {
+                    let result = check_profile_updates(paths, &profile, api_key)?;
+                    let update = result
+                        .updates
+                        .into_iter()
+                        .find(|u| u.content.name == content && u.content_type == content_type);
+                    match update {
+                        Some(update) => update.latest_version_id,
+                        None => {
+                            println!("{content} is up to date");
+                            return Ok(());
+                        }
+                    }
+                }
+            };
+
+            let plan = plan_update(paths, &profile, &content, content_type, &version_id, api_key)?;
+            if dry_run {
+                print_update_plan(paths, &plan);
+                return Ok(());
+            }
+            apply_update(paths, &profile, &content, content_type, &version_id, api_key, config.store_hash)?;
+            println!(
+                "updated {} in profile {}: {} -> {}",
+                content,
+                profile,
+                plan.current.version.as_deref().unwrap_or("?"),
+                plan.version.version
+            );
+        }
+        UpdateCommand::Rollback {
+            profile,
+            content,
+            content_type,
+        } => {
+            let content_type = match content_type {
+                StoreContentType::Mod => "mod",
+                StoreContentType::Resourcepack => "resourcepack",
+                StoreContentType::Shader => "shaderpack",
+            };
+            let updated = rollback_update(paths, &profile, &content, content_type)?;
+            let version = [&updated.mods, &updated.resourcepacks, &updated.shaderpacks]
+                .into_iter()
+                .flatten()
+                .find(|c| c.name == content)
+                .and_then(|c| c.version.clone());
+            println!(
+                "rolled back {} in profile {} to {}",
+                content,
+                profile,
+                version.as_deref().unwrap_or("the previous version")
+            );
+            println!("it is pinned, so update checks skip it; `update apply --version` still moves it");
+        }
+    }
+    Ok(())
+}
+
+/// `update apply --dry-run`: the files and ContentRef fields the update changes
+fn print_update_plan(paths: &Paths, plan: &UpdatePlan) {
+    let current = &plan.current;
+    let version = &plan.version;
+    let kind = match plan.content_type.as_str() {
+        "resourcepack" => ContentKind::ResourcePack,
+        "shaderpack" => ContentKind::ShaderPack,
+        _ => ContentKind::Mod,
+    };
+    let updated = ContentRef {
+        version: Some(version.version.clone()),
+        version_id: Some(version.id.clone()),
+        file_name: Some(version.filename.clone()),
+        source: Some(version.download_url.clone()),
+        platform: Some(plan.platform.clone()),
+        project_id: Some(plan.project_id.clone()),
+        ..current.clone()
+    };
+
+    println!(
+        "would update {} in profile {}: {} -> {}",
+        current.name,
+        plan.profile_id,
+        current.version.as_deref().unwrap_or("?"),
+        version.version
+    );
+    println!(
+        "  old file: {} ({})",
+        instance_file_name(current, kind),
+        content_store_path(paths, kind, &current.hash).display()
+    );
+    println!(
+        "  new file: {} (download {}, {})",
+        instance_file_name(&updated, kind),
+        version.download_url,
+        format_mib(version.size)
+    );
+    println!("  hash: {} -> computed on download", current.hash);
+    for (field, old, new) in [
+        ("version", &current.version, &updated.version),
+        ("version_id", &current.version_id, &updated.version_id),
+        ("file_name", &current.file_name, &updated.file_name),
+        ("source", &current.source, &updated.source),
+        ("platform", &current.platform, &updated.platform),
+        ("project_id", &current.project_id, &updated.project_id),
+    ] {
+        if old != new {
+            println!(
+                "  {field}: {} -> {}",
+                old.as_deref().unwrap_or("-"),
+                new.as_deref().unwrap_or("-")
+            );
+        }
+    }
+}
+
 fn handle_app_update_command(command: AppUpdateCommand) -> Result<()> {
     match command {
         AppUpdateCommand::Check {
@@ -950,6 +2953,81 @@ fn normalize_texture_url(url: &str) -> String {
     }
 }
 
+/// Throttle for the `--low-priority` and `--rate` flags of integrity commands
+fn cli_throttle(low_priority: bool, rate: Option<u64>, cancel: CancelToken) -> Throttle {
+    match rate {
+        Some(mib) => Throttle::new(Some(mib * 1024 * 1024), cancel),
+        None if low_priority => Throttle::low_priority(cancel),
+        None => Throttle::unlimited(cancel),
+    }
+}
+
+fn format_age(secs: u64) -> String {
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+fn print_pack_warnings(inspection: &PackInspection) {
+    for warning in &inspection.warnings {
+        println!("warning: {warning}");
+    }
+}
+
+fn handle_datapack_command(paths: &Paths, command: DatapackCommand) -> Result<()> {
+    match command {
+        DatapackCommand::Inspect { input, profile } => {
+            let mc_version = match profile {
+                Some(id) => Some(load_profile(paths, &id)?.mc_version),
+                None => None,
+            };
+            let (path, _, _) = resolve_input(paths, &input)?;
+            let inspection = inspect_pack(&path, PackKind::Data, mc_version.as_deref())?;
+            let meta = &inspection.meta;
+            if !meta.description.is_empty() {
+                println!("{}", meta.description);
+            }
+            match &inspection.target_versions {
+                Some(versions) => println!("pack format: {} (Minecraft {versions})", meta.pack_format),
+                None => println!("pack format: {}", meta.pack_format),
+            }
+            if !meta.data_namespaces.is_empty() {
+                println!("data namespaces: {}", meta.data_namespaces.join(", "));
+            }
+            if !meta.asset_namespaces.is_empty() {
+                println!("asset namespaces: {}", meta.asset_namespaces.join(", "));
+            }
+            print_pack_warnings(&inspection);
+        }
+        DatapackCommand::Add {
+            profile,
+            world,
+            input,
+            force,
+        } => {
+            let profile_data = load_profile(paths, &profile)?;
+            let (path, _, file_name_hint) = resolve_input(paths, &input)?;
+            let inspection = inspect_pack(&path, PackKind::Data, Some(&profile_data.mc_version))?;
+            print_pack_warnings(&inspection);
+            let mismatched = !inspection.meta.is_data_pack()
+                || pack_format_for(PackKind::Data, &profile_data.mc_version)
+                    .is_some_and(|format| !inspection.meta.supports_format(format));
+            if mismatched && !force {
+                bail!("data pack would be ignored by Minecraft {}; use --force to add it anyway", profile_data.mc_version);
+            }
+            let file_name = file_name_hint
+                .or_else(|| path.file_name().map(|n| n.to_string_lossy().into_owned()))
+                .context("data pack has no file name")?;
+            let dest = install_datapack(paths, &profile, &world, &path, &file_name)?;
+            println!("added {} to world {world}", dest.display());
+        }
+    }
+    Ok(())
+}
+
 fn handle_pack_command(paths: &Paths, kind: ContentKind, command: PackCommand) -> Result<()> {
     match command {
         PackCommand::Add {
@@ -958,42 +3036,22 @@ fn handle_pack_command(paths: &Paths, kind: ContentKind, command: PackCommand) -
             name,
             version,
         } => {
-            let mut profile_data = load_profile(paths, &profile)?;
-            let (path, source, file_name_hint) = resolve_input(paths, &input)?;
-            let stored = store_content(paths, kind, &path, source, file_name_hint)?;
-            let pack_ref = ContentRef {
-                name: name.unwrap_or(stored.name),
-                hash: stored.hash,
-                version,
-                source: stored.source,
-                file_name: Some(stored.file_name),
-                platform: None, // CLI imports are local
-                project_id: None,
-                version_id: None,
-                enabled: true,
-                pinned: false,
-            };
-            let changed = match kind {
-                ContentKind::ResourcePack => upsert_resourcepack(&mut profile_data, pack_ref),
-                ContentKind::ShaderPack => upsert_shaderpack(&mut profile_data, pack_ref),
-                ContentKind::Mod | ContentKind::Skin => false,
-            };
-            save_profile(paths, &profile_data)?;
-            if changed {
+            let added = add_content(paths, &profile, &input, name, version, kind)?;
+            if matches!(kind, ContentKind::ResourcePack) {
+                let stored_path = content_store_path(paths, kind, &added.stored.hash);
+                match inspect_pack(&stored_path, PackKind::Resource, Some(&added.profile.mc_version)) {
+                    Ok(inspection) => print_pack_warnings(&inspection),
+                    Err(err) => println!("warning: {err}"),
+                }
+            }
+            if added.changed {
                 println!("updated profile {profile}");
             } else {
                 println!("pack already present in profile {profile}");
             }
         }
         PackCommand::Remove { profile, target } => {
-            let mut profile_data = load_profile(paths, &profile)?;
-            let changed = match kind {
-                ContentKind::ResourcePack => remove_resourcepack(&mut profile_data, &target),
-                ContentKind::ShaderPack => remove_shaderpack(&mut profile_data, &target),
-                ContentKind::Mod | ContentKind::Skin => false,
-            };
-            if changed {
-                save_profile(paths, &profile_data)?;
+            if remove_content(paths, &profile, &target, kind)? {
                 println!("removed pack from profile {profile}");
             } else {
                 bail!("pack not found in profile {profile}");
@@ -1023,13 +3081,18 @@ fn handle_account_command(paths: &Paths, command: AccountCommand) -> Result<()>
         AccountCommand::Add {
             client_id,
             client_secret,
+            browser,
         } => {
             let config = load_config(paths)?;
             let client_id = client_id.or(config.msa_client_id).context(
                 "missing Microsoft client id; set SHARD_MS_CLIENT_ID or shard config set-client-id",
             )?;
             let secret = client_secret.or(config.msa_client_secret);
-            add_account_flow(paths, &client_id, secret.as_deref())?;
+            if browser {
+                add_account_browser_flow(paths, &client_id, secret.as_deref())?;
+            } else {
+                add_account_flow(paths, &client_id, secret.as_deref())?;
+            }
         }
         AccountCommand::List => {
             let accounts = load_accounts(paths)?;
@@ -1039,7 +3102,15 @@ fn handle_account_command(paths: &Paths, command: AccountCommand) -> Result<()>
                 for account in accounts.accounts {
                     let active = accounts.active.as_deref() == Some(&account.uuid);
                     let marker = if active { "*" } else { " " };
-                    println!("{marker} {} ({})", account.username, account.uuid);
+                    match account.status {
+                        Some(status) => println!(
+                            "{marker} {} ({}) [{}]",
+                            account.username,
+                            account.uuid,
+                            status.as_str()
+                        ),
+                        None => println!("{marker} {} ({})", account.username, account.uuid),
+                    }
                 }
             }
         }
@@ -1064,6 +3135,50 @@ fn handle_account_command(paths: &Paths, command: AccountCommand) -> Result<()>
                 bail!("account not found: {id}");
             }
         }
+        AccountCommand::Reauth { id } => {
+            let mut accounts = load_accounts(paths)?;
+            let account = find_account_mut(&mut accounts, &id)
+                .with_context(|| format!("account not found: {id}"))?;
+            if let Some(status) = account.status {
+                println!("{}", status.hint());
+            }
+            let expected = account.uuid.clone();
+
+            let config = load_config(paths)?;
+            let client_id = config.msa_client_id.context(
+                "missing Microsoft client id; set SHARD_MS_CLIENT_ID or shard config set-client-id",
+            )?;
+            let device = request_device_code(&client_id, config.msa_client_secret.as_deref())?;
+            println!("{}", device.message);
+            let account = finish_device_code_flow(
+                paths,
+                &client_id,
+                config.msa_client_secret.as_deref(),
+                &device,
+            )?;
+            if account.uuid == expected {
+                println!("re-authenticated {}", account.username);
+            } else {
+                println!(
+                    "signed in as {} instead; the account was added separately",
+                    account.username
+                );
+            }
+        }
+        AccountCommand::Export { id, out } => {
+            let passphrase = read_passphrase("Passphrase for the export", true)?;
+            let data = export_account(paths, &id, &passphrase)?;
+            fs::write(&out, data)
+                .with_context(|| format!("failed to write export: {}", out.display()))?;
+            println!("exported account {id} to {}", out.display());
+        }
+        AccountCommand::Import { file } => {
+            let data = fs::read_to_string(&file)
+                .with_context(|| format!("failed to read export: {}", file.display()))?;
+            let passphrase = read_passphrase("Passphrase", false)?;
+            let account = import_account(paths, &data, &passphrase)?;
+            println!("imported account {} ({})", account.username, account.uuid);
+        }
         AccountCommand::Info { id } => {
             let accounts = load_accounts(paths)?;
             let target = id
@@ -1345,20 +3460,53 @@ fn handle_template_command(paths: &Paths, command: TemplateCommand) -> Result<()
             save_template(paths, &template)?;
             println!("created template {id}");
         }
-        TemplateCommand::Import { path } => {
+        TemplateCommand::Pin {
+            id,
+            content,
+            version,
+        } => {
+            init_builtin_templates(paths)?;
+            let mut template = load_template(paths, &id)?;
+            VersionPin::parse(version.as_deref())?;
+            let entry = template
+                .mods
+                .iter_mut()
+                .chain(template.resourcepacks.iter_mut())
+                .chain(template.shaderpacks.iter_mut())
+                .find(|entry| {
+                    entry.name.eq_ignore_ascii_case(&content)
+                        || matches!(&entry.source, ContentSource::Modrinth { project } if *project == content)
+                })
+                .with_context(|| format!("content not found in template {id}: {content}"))?;
+            entry.version = version.clone();
+            save_template(paths, &template)?;
+            match version {
+                Some(version) => println!("pinned {content} to {version}"),
+                None => println!("unpinned {content}"),
+            }
+        }
+        TemplateCommand::Import { path, require_signed } => {
             let data = std::fs::read_to_string(&path)
                 .with_context(|| format!("failed to read file: {}", path.display()))?;
-            let template: Template =
-                serde_json::from_str(&data).context("failed to parse template JSON")?;
+            let opened = open_document::<Template>(paths, &data, "template", require_signed)?;
+            let template = opened.document;
             save_template(paths, &template)?;
-            println!("imported template {}", template.id);
+            match opened.signer {
+                Some(signer) => println!("imported template {} (signed by {})", template.id, signer.name),
+                None => println!("imported template {} (unsigned)", template.id),
+            }
         }
-        TemplateCommand::Export { id, path } => {
+        TemplateCommand::Export { id, path, sign } => {
             let template = load_template(paths, &id)?;
-            let data = serde_json::to_string_pretty(&template)?;
+            let data = if sign {
+                serde_json::to_string_pretty(&sign_document(paths, "template", &template)?)?
+            } else {
+                serde_json::to_string_pretty(&template)?
+            };
             std::fs::write(&path, data)
                 .with_context(|| format!("failed to write file: {}", path.display()))?;
-            println!("exported template {} to {}", id, path.display());
+            let signed = if sign { " (signed)" } else { "" };
+            println!("exported template {} to {}{signed}", id, path.display());
         }
         TemplateCommand::Delete { id } => {
             if delete_template(paths, &id)? {
@@ -1387,6 +3535,8 @@ fn handle_store_command(paths: &Paths, command: StoreCommand) -> Result<()> {
             loader,
             platform,
             limit,
+            interactive,
+            profile,
         } => {
             let options = SearchOptions {
                 query,
@@ -1405,6 +3555,8 @@ fn handle_store_command(paths: &Paths, command: StoreCommand) -> Result<()> {
 
             if results.is_empty() {
                 println!("no results found");
+            } else if interactive {
+                interactive_store_install(paths, &store, &results, profile)?;
             } else {
                 for item in results {
                     println!(
@@ -1469,26 +3621,15 @@ fn handle_store_command(paths: &Paths, command: StoreCommand) -> Result<()> {
             platform,
             version,
             content_type,
+            accept,
+            dry_run,
         } => {
             let mut profile_data = load_profile(paths, &profile)?;
 
             // Get project info to determine content type
             let item = store.get_project(platform.into(), &project)?;
             let ct = content_type.map(ContentType::from).unwrap_or(item.content_type);
-
-            // Determine effective loader based on content type
-            let effective_loader: Option<String> = match ct {
-                ContentType::Mod | ContentType::ModPack => {
-                    profile_data.loader.as_ref().map(|l| l.loader_type.clone())
-                }
-                ContentType::ShaderPack => {
-                    // For shaders, detect if profile has iris/optifine installed
-                    profile_data
-                        .primary_shader_loader()
-                        .map(|sl| sl.modrinth_name().to_string())
-                }
-                ContentType::ResourcePack => None,
-            };
+            let effective_loader = store_loader_filter(&profile_data, ct);
 
             // Get version
             let ver = if let Some(v) = version {
@@ -1506,29 +3647,360 @@ fn handle_store_command(paths: &Paths, command: StoreCommand) -> Result<()> {
                 )?
             };
 
-            // Download and store
-            let mut content_ref = store.download_to_store(paths, &ver, ct)?;
+            if dry_run {
+                print_store_install_preview(&profile_data, &item, &ver, ct, &project);
+            } else {
+                install_store_version(paths, &store, &mut profile_data, &item, &ver, ct, &project, accept)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_watch_command(paths: &Paths, command: WatchCommand) -> Result<()> {
+    match command {
+        WatchCommand::Add {
+            project,
+            platform,
+            game_version,
+            loader,
+        } => {
+            let config = load_config(paths)?;
+            let store = ContentStore::new(config.curseforge_api_key.as_deref());
+            let watched = watch_project(
+                paths,
+                &store,
+                platform.into(),
+                &project,
+                game_version.as_deref(),
+                loader.as_deref(),
+            )?;
+            match &watched.seen_version {
+                Some(version) => println!("watching {} (latest: {version})", watched.name),
+                None => println!("watching {} (no matching release yet)", watched.name),
+            }
+        }
+        WatchCommand::Remove { project, platform } => {
+            let removed = unwatch_project(paths, platform.into(), &project)?;
+            println!("stopped watching {}", removed.name);
+        }
+        WatchCommand::List => {
+            let watched = load_watchlist(paths)?;
+            if watched.is_empty() {
+                println!("not watching any projects");
+            }
+            for w in watched {
+                let filters: Vec<&str> = [w.mc_version.as_deref(), w.loader.as_deref()].into_iter().flatten().collect();
+                let filters = if filters.is_empty() { String::new() } else { format!(" [{}]", filters.join(", ")) };
+                println!(
+                    "[{}] {} ({}){filters} - seen: {}",
+                    w.platform,
+                    w.name,
+                    w.project_id,
+                    w.seen_version.as_deref().unwrap_or("none")
+                );
+            }
+        }
+        WatchCommand::Check { keep } => {
+            let config = load_config(paths)?;
+            let store = ContentStore::new(config.curseforge_api_key.as_deref());
+            let (releases, errors) = check_watched_releases(paths, &store)?;
+            for error in &errors {
+                eprintln!("{error}");
+            }
+            if releases.is_empty() {
+                println!("no new releases");
+            }
+            for release in releases {
+                let project = &release.project;
+                println!(
+                    "[{}] {}: {} -> {}",
+                    project.platform,
+                    project.name,
+                    project.seen_version.as_deref().unwrap_or("none"),
+                    release.latest_version
+                );
+                if !keep {
+                    mark_release_seen(
+                        paths,
+                        project.platform,
+                        &project.project_id,
+                        &release.latest_version_id,
+                        &release.latest_version,
+                    )?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_keys_command(paths: &Paths, command: KeysCommand) -> Result<()> {
+    match command {
+        KeysCommand::Generate { force } => {
+            let public_key = generate_signing_key(paths, force)?;
+            println!("generated signing key");
+            println!("public key: {public_key}");
+        }
+        KeysCommand::Show => match signing_public_key(paths)? {
+            Some(public_key) => println!("{public_key}"),
+            None => bail!("no signing key; create one with `shard keys generate`"),
+        },
+        KeysCommand::Trust { public_key, name } => {
+            let trusted = trust_key(paths, &public_key, &name)?;
+            println!("trusting {} ({})", trusted.name, trusted.public_key);
+        }
+        KeysCommand::Untrust { target } => {
+            if untrust_key(paths, &target)? == 0 {
+                bail!("not a trusted key: {target}");
+            }
+            println!("no longer trusting {target}");
+        }
+        KeysCommand::List => {
+            let keys = load_trusted_keys(paths)?;
+            if keys.is_empty() {
+                println!("no trusted keys");
+            }
+            for key in keys {
+                println!("{}\t{}", key.name, key.public_key);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_blocklist_command(paths: &Paths, command: BlocklistCommand) -> Result<()> {
+    match command {
+        BlocklistCommand::Add {
+            project,
+            platform,
+            reason,
+        } => {
+            // Installs are checked by project id, so resolve slugs first
+            let config = load_config(paths)?;
+            let store = ContentStore::new(config.curseforge_api_key.as_deref());
+            let item = store
+                .get_project(platform.into(), &project)
+                .with_context(|| format!("failed to look up {project}"))?;
+            block_project(paths, Some(item.platform), &item.id, Some(item.name.clone()), &reason)?;
+            println!("blocked {} ({})", item.name, item.id);
+        }
+        BlocklistCommand::AddHash { hash, reason, name } => {
+            let entry = block_hash(paths, &hash, name, &reason)?;
+            println!("blocked {}", entry.hash.unwrap_or_default());
+        }
+        BlocklistCommand::Remove { target } => {
+            let removed = unblock(paths, &target)?;
+            if removed == 0 {
+                bail!("{target} is not on the blocklist");
+            }
+            println!("unblocked {target}");
+        }
+        BlocklistCommand::List => {
+            let entries = load_blocklist(paths)?;
+            if entries.is_empty() {
+                println!("blocklist is empty");
+            }
+            for entry in entries {
+                let target = match (&entry.project_id, &entry.hash) {
+                    (Some(id), _) => match entry.platform {
+                        Some(platform) => format!("[{platform}] {id}"),
+                        None => id.clone(),
+                    },
+                    (None, Some(hash)) => hash.clone(),
+                    (None, None) => continue,
+                };
+                let name = entry.name.as_deref().map(|n| format!(" {n}")).unwrap_or_default();
+                println!("{target}{name} - {}", entry.reason);
+            }
+        }
+        BlocklistCommand::Import { file } => {
+            let added = import_blocklist(paths, &file)?;
+            println!("imported {added} new entries from {}", file.display());
+        }
+        BlocklistCommand::Export { file } => {
+            let count = export_blocklist(paths, &file)?;
+            println!("exported {count} entries to {}", file.display());
+        }
+    }
+    Ok(())
+}
+
+/// Show the license and dependencies of a store version, then download it
+/// and add it to the profile
+#[allow(clippy::too_many_arguments)]
+fn install_store_version(
+    paths: &Paths,
+    store: &ContentStore,
+    profile_data: &mut Profile,
+    item: &ContentItem,
+    ver: &ContentVersion,
+    ct: ContentType,
+    project_id: &str,
+    accept: bool,
+) -> Result<()> {
+    let disclosure = InstallDisclosure::new(item, ver);
+    println!("{} {}", disclosure.project, disclosure.version);
+    for line in disclosure.lines() {
+        println!("  {line}");
+    }
+    if needs_confirmation(paths, disclosure.size) {
+        println!("  the connection is metered; this downloads {}", format_size(disclosure.size));
+    }
+    if !accept && !confirm("install?")? {
+        println!("cancelled");
+        return Ok(());
+    }
+
+    // Download and store
+    let mut content_ref = store.download_to_store(paths, ver, ct, configured_store_hash(paths))?;
+
+    // Add platform/project tracking for update checking
+    content_ref.platform = Some(item.platform.to_string());
+    content_ref.project_id = Some(project_id.to_string());
+    content_ref.version_id = Some(ver.id.clone());
+    content_ref.pinned = false;
+
+    // Add to profile and library
+    let changed = add_store_content(paths, profile_data, content_ref, ct.to_content_kind())?;
+    if changed {
+        println!("installed {} to profile {}", item.name, profile_data.id);
+    } else {
+        println!("{} already in profile {}", item.name, profile_data.id);
+    }
+    Ok(())
+}
+
+/// What `store install` would do, for `--dry-run`
+fn print_store_install_preview(profile: &Profile, item: &ContentItem, ver: &ContentVersion, ct: ContentType, project_id: &str) {
+    let disclosure = InstallDisclosure::new(item, ver);
+    println!("{} {}", disclosure.project, disclosure.version);
+    for line in disclosure.lines() {
+        println!("  {line}");
+    }
+    println!("would download {} ({})", ver.filename, format_mib(ver.size));
+    let kind = ct.to_content_kind();
+    let list = match kind {
+        ContentKind::Mod => &profile.mods,
+        ContentKind::ResourcePack => &profile.resourcepacks,
+        ContentKind::ShaderPack => &profile.shaderpacks,
+        ContentKind::Skin => return,
+    };
+    match list.iter().find(|c| c.project_id.as_deref() == Some(project_id)) {
+        Some(existing) if existing.version_id.as_deref() == Some(ver.id.as_str()) => {
+            println!("{} {} is already in profile {}", item.name, ver.version, profile.id);
+        }
+        Some(existing) => println!(
+            "would replace {} {} with {} in profile {}",
+            existing.name,
+            existing.version.as_deref().unwrap_or("?"),
+            ver.version,
+            profile.id
+        ),
+        None => println!("would add {} {} to profile {} as a {}", item.name, ver.version, profile.id, kind.label()),
+    }
+}
+
+/// `store search -i`: pick a result, a profile and a compatible version
+/// with the arrow keys, then install it. Esc cancels at any step.
+fn interactive_store_install(
+    paths: &Paths,
+    store: &ContentStore,
+    results: &[ContentItem],
+    profile: Option<String>,
+) -> Result<()> {
+    if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stderr) {
+        bail!("not a terminal; use `shard store install` instead of --interactive");
+    }
+    let theme = ColorfulTheme::default();
+    let pick = |prompt: &str, items: &[String]| -> Result<Option<usize>> {
+        Ok(Select::with_theme(&theme)
+            .with_prompt(prompt)
+            .items(items)
+            .default(0)
+            .max_length(15)
+            .interact_opt()?)
+    };
 
-            // Add platform/project tracking for update checking
-            content_ref.platform = Some(platform.as_str().to_string());
-            content_ref.project_id = Some(project.clone());
-            content_ref.version_id = Some(ver.id.clone());
-            content_ref.pinned = false;
+    let labels: Vec<String> = results
+        .iter()
+        .map(|item| format!("[{}] {} ({} downloads) - {}", item.platform, item.name, item.downloads, item.description))
+        .collect();
+    let Some(index) = pick("Project", &labels)? else {
+        println!("cancelled");
+        return Ok(());
+    };
+    let item = &results[index];
 
-            // Add to profile
-            let changed = match ct {
-                ContentType::Mod | ContentType::ModPack => upsert_mod(&mut profile_data, content_ref),
-                ContentType::ResourcePack => upsert_resourcepack(&mut profile_data, content_ref),
-                ContentType::ShaderPack => upsert_shaderpack(&mut profile_data, content_ref),
+    let profile_id = match profile {
+        Some(profile) => profile,
+        None => {
+            let profiles = list_profiles(paths)?;
+            if profiles.is_empty() {
+                bail!("no profiles found; create one with `shard profile create`");
+            }
+            let Some(index) = pick("Profile", &profiles)? else {
+                println!("cancelled");
+                return Ok(());
             };
+            profiles[index].clone()
+        }
+    };
+    let mut profile_data = load_profile(paths, &profile_id)?;
 
-            save_profile(paths, &profile_data)?;
-            if changed {
-                println!("installed {} to profile {}", item.name, profile);
-            } else {
-                println!("{} already in profile {}", item.name, profile);
+    let ct = item.content_type;
+    let loader = store_loader_filter(&profile_data, ct);
+    let versions = store.get_versions(item.platform, &item.id, Some(&profile_data.mc_version), loader.as_deref())?;
+    if versions.is_empty() {
+        bail!(
+            "no versions of {} for Minecraft {}{}",
+            item.name,
+            profile_data.mc_version,
+            loader.map(|l| format!(" with {l}")).unwrap_or_default()
+        );
+    }
+    let labels: Vec<String> = versions
+        .iter()
+        .map(|v| format!("{} - {} [{}]", v.version, v.name, v.release_type))
+        .collect();
+    let Some(index) = pick("Version", &labels)? else {
+        println!("cancelled");
+        return Ok(());
+    };
+
+    install_store_version(paths, store, &mut profile_data, item, &versions[index], ct, &item.id, false)
+}
+
+fn handle_mod_config_command(paths: &Paths, command: ModConfigCommand) -> Result<()> {
+    match command {
+        ModConfigCommand::List { profile } => {
+            let files = list_mod_configs(paths, &profile)?;
+            if files.is_empty() {
+                println!("no config files for profile {profile}");
+            }
+            for file in files {
+                println!("{}\t{}\t{} bytes", file.path, file.format.as_str(), file.size);
+            }
+        }
+        ModConfigCommand::Show { profile, file } => {
+            print!("{}", read_mod_config(paths, &profile, &file)?);
+        }
+        ModConfigCommand::Set {
+            profile,
+            file,
+            source,
+        } => {
+            let content = fs::read_to_string(&source)
+                .with_context(|| format!("failed to read {}", source.display()))?;
+            match write_mod_config(paths, &profile, &file, &content)? {
+                Some(backup) => println!("saved {file} (backup: {})", backup.display()),
+                None => println!("created {file}"),
             }
         }
+        ModConfigCommand::Restore { profile, file } => {
+            restore_mod_config(paths, &profile, &file)?;
+            println!("restored {file} from backup");
+        }
     }
     Ok(())
 }
@@ -1613,20 +4085,59 @@ fn handle_logs_command(paths: &Paths, command: LogsCommand) -> Result<()> {
                 }
             }
         }
+        LogsCommand::Events { profile, file } => {
+            let log_path = if let Some(filename) = file {
+                paths.instance_logs_dir(&profile).join(filename)
+            } else {
+                paths.instance_latest_log(&profile)
+            };
+            if !log_path.exists() {
+                bail!("log file not found: {}", log_path.display());
+            }
+
+            let events = extract_events(&read_log_file(&log_path)?);
+            if events.is_empty() {
+                println!("no game events found");
+                return Ok(());
+            }
+            for timed in &events {
+                let ts = timed.timestamp.as_deref().unwrap_or("--:--:--");
+                let text = match &timed.event {
+                    GameEvent::ServerConnect { address } => format!("connected to {address}"),
+                    GameEvent::PlayerJoined { player } => format!("{player} joined"),
+                    GameEvent::PlayerLeft { player } => format!("{player} left"),
+                    GameEvent::Advancement {
+                        player,
+                        advancement,
+                    } => format!("{player} unlocked [{advancement}]"),
+                    GameEvent::Disconnected { reason } => format!("disconnected: {reason}"),
+                };
+                println!("[{ts}] {text}");
+            }
+
+            let summary = summarize_events(&events);
+            println!();
+            println!(
+                "servers: {}, players seen: {}, advancements: {}",
+                summary.servers.len(),
+                summary.players.len(),
+                summary.advancements.len()
+            );
+        }
         LogsCommand::Crashes { profile } => {
             let files = list_crash_reports(paths, &profile)?;
             if files.is_empty() {
                 println!("no crash reports found for profile {profile}");
             } else {
                 for file in files {
-                    println!("{}\t{} bytes", file.name, file.size);
+                    let kind = if file.jvm_crash { " (jvm)" } else { "" };
+                    println!("{}\t{} bytes{}", file.name, file.size, kind);
                 }
             }
         }
         LogsCommand::Crash { profile, file } => {
-            let crash_dir = paths.instance_crash_reports(&profile);
             let crash_path = if let Some(filename) = file {
-                crash_dir.join(filename)
+                crash_report_path(paths, &profile, &filename)
             } else {
                 let files = list_crash_reports(paths, &profile)?;
                 files
@@ -1643,6 +4154,45 @@ fn handle_logs_command(paths: &Paths, command: LogsCommand) -> Result<()> {
             let content = std::fs::read_to_string(&crash_path)?;
             println!("{content}");
         }
+        LogsCommand::CrashStats { profile, clear } => {
+            if !load_config(paths)?.crash_aggregation_enabled {
+                println!("crash aggregation is disabled; enable with shard config set-crash-aggregation on");
+                // Stats recorded while it was on can still be listed or
+                // cleared, but don't create an empty database
+                if !paths.crash_stats_db().exists() {
+                    return Ok(());
+                }
+            }
+            let stats = CrashStats::from_paths(paths)?;
+            if clear {
+                stats.clear()?;
+                println!("cleared crash stats");
+                return Ok(());
+            }
+            if let Some(profile) = profile {
+                let added = collect_profile_crashes(paths, &profile)?;
+                if added > 0 {
+                    println!("recorded {added} new crash(es)");
+                }
+            }
+
+            let list = stats.list()?;
+            if list.is_empty() {
+                println!("no crashes recorded");
+            }
+            for entry in list {
+                println!("{}x {} ({})", entry.count, entry.exception, &entry.signature[..12]);
+                if let Some(suspect) = &entry.suspected_mod {
+                    println!("  suspected: {suspect}");
+                }
+                for frame in entry.frames.iter().take(3) {
+                    println!("  at {frame}");
+                }
+                for update in entry.after_updates {
+                    println!("  {} time(s) after updating {}", update.count, update.content);
+                }
+            }
+        }
     }
     Ok(())
 }
@@ -1660,15 +4210,23 @@ fn add_account_flow(paths: &Paths, client_id: &str, client_secret: Option<&str>)
     Ok(())
 }
 
+fn add_account_browser_flow(paths: &Paths, client_id: &str, client_secret: Option<&str>) -> Result<()> {
+    let auth = start_browser_auth(client_id)?;
+    println!("Open this URL to sign in:\n{}", auth.auth_url);
+    let account = finish_browser_auth_flow(paths, client_id, client_secret, auth)?;
+    println!("added account {}", account.username);
+    Ok(())
+}
+
 fn create_profile_from_template(
     paths: &Paths,
     profile_id: &str,
     template_id: &str,
-    java: Option<String>,
-    memory: Option<String>,
-    args: Vec<String>,
+    runtime: Runtime,
 ) -> Result<()> {
     let template = load_template(paths, template_id)?;
+    template.validate_pins()?;
+    let algorithm = configured_store_hash(paths);
 
     // Create loader from template
     let loader = template.loader.map(|l| Loader {
@@ -1678,13 +4236,14 @@ fn create_profile_from_template(
 
     // Merge runtime settings (CLI overrides template)
     let runtime = Runtime {
-        java: java.or(template.runtime.java),
-        memory: memory.or(template.runtime.memory),
-        args: if args.is_empty() {
+        java: runtime.java.or(template.runtime.java),
+        memory: runtime.memory.or(template.runtime.memory),
+        args: if runtime.args.is_empty() {
             template.runtime.args
         } else {
-            args
+            runtime.args
         },
+        ..runtime
     };
 
     // Create the profile
@@ -1704,15 +4263,18 @@ fn create_profile_from_template(
         }
         match &mod_content.source {
             ContentSource::Modrinth { project } => {
-                match store.get_latest_version(
-                    Platform::Modrinth,
+                let pin = mod_content.version_pin()?;
+                match resolve_template_version(
+                    &store,
                     project,
+                    &pin,
                     Some(&template.mc_version),
                     loader_type,
                 ) {
                     Ok(version) => {
-                        match store.download_to_store(paths, &version, ContentType::Mod) {
-                            Ok(content_ref) => {
+                        match store.download_to_store(paths, &version, ContentType::Mod, algorithm) {
+                            Ok(mut content_ref) => {
+                                content_ref.pinned = pin.is_pinned();
                                 upsert_mod(&mut profile, content_ref);
                                 println!("  + {}", mod_content.name);
                             }
@@ -1729,7 +4291,7 @@ fn create_profile_from_template(
             ContentSource::Url { url } => {
                 match resolve_input(paths, url) {
                     Ok((path, source, file_name)) => {
-                        match store_content(paths, ContentKind::Mod, &path, source, file_name) {
+                        match store_content(paths, ContentKind::Mod, &path, source, file_name, algorithm) {
                             Ok(stored) => {
                                 let content_ref = ContentRef {
                                     name: mod_content.name.clone(),
@@ -1772,10 +4334,12 @@ fn create_profile_from_template(
         }
         match &shader.source {
             ContentSource::Modrinth { project } => {
-                match store.get_latest_version(Platform::Modrinth, project, None, None) {
+                let pin = shader.version_pin()?;
+                match resolve_template_version(&store, project, &pin, None, None) {
                     Ok(version) => {
-                        match store.download_to_store(paths, &version, ContentType::ShaderPack) {
-                            Ok(content_ref) => {
+                        match store.download_to_store(paths, &version, ContentType::ShaderPack, algorithm) {
+                            Ok(mut content_ref) => {
+                                content_ref.pinned = pin.is_pinned();
                                 upsert_shaderpack(&mut profile, content_ref);
                                 println!("  + {} (shader)", shader.name);
                             }
@@ -1792,7 +4356,7 @@ fn create_profile_from_template(
             ContentSource::Url { url } => {
                 match resolve_input(paths, url) {
                     Ok((path, source, file_name)) => {
-                        match store_content(paths, ContentKind::ShaderPack, &path, source, file_name)
+                        match store_content(paths, ContentKind::ShaderPack, &path, source, file_name, algorithm)
                         {
                             Ok(stored) => {
                                 let content_ref = ContentRef {
@@ -1831,10 +4395,12 @@ fn create_profile_from_template(
         }
         match &pack.source {
             ContentSource::Modrinth { project } => {
-                match store.get_latest_version(Platform::Modrinth, project, None, None) {
+                let pin = pack.version_pin()?;
+                match resolve_template_version(&store, project, &pin, None, None) {
                     Ok(version) => {
-                        match store.download_to_store(paths, &version, ContentType::ResourcePack) {
-                            Ok(content_ref) => {
+                        match store.download_to_store(paths, &version, ContentType::ResourcePack, algorithm) {
+                            Ok(mut content_ref) => {
+                                content_ref.pinned = pin.is_pinned();
                                 upsert_resourcepack(&mut profile, content_ref);
                                 println!("  + {} (resourcepack)", pack.name);
                             }
@@ -1851,7 +4417,7 @@ fn create_profile_from_template(
             ContentSource::Url { url } => {
                 match resolve_input(paths, url) {
                     Ok((path, source, file_name)) => {
-                        match store_content(paths, ContentKind::ResourcePack, &path, source, file_name)
+                        match store_content(paths, ContentKind::ResourcePack, &path, source, file_name, algorithm)
                         {
                             Ok(stored) => {
                                 let content_ref = ContentRef {
@@ -1946,7 +4512,7 @@ fn handle_library_command(paths: &Paths, command: LibraryCommand) -> Result<()>
                         item.content_type.as_str(),
                         item.name,
                         tags_str,
-                        &item.hash[..16]
+                        normalize_hash(&item.hash).get(..16).unwrap_or(&item.hash)
                     );
                 }
             }
@@ -1962,6 +4528,9 @@ fn handle_library_command(paths: &Paths, command: LibraryCommand) -> Result<()>
                 Some(item) => {
                     println!("ID: {}", item.id);
                     println!("Hash: {}", item.hash);
+                    if let Some(sha1) = &item.sha1 {
+                        println!("SHA-1: {sha1}");
+                    }
                     println!("Type: {}", item.content_type.label());
                     println!("Name: {}", item.name);
                     if let Some(file_name) = &item.file_name {
@@ -1987,6 +4556,12 @@ fn handle_library_command(paths: &Paths, command: LibraryCommand) -> Result<()>
                     if !item.used_by_profiles.is_empty() {
                         println!("Used by: {}", item.used_by_profiles.join(", "));
                     }
+                    if let Some(prev) = item.replaces {
+                        println!("Replaces: {prev}");
+                    }
+                    if let Some(next) = item.replaced_by {
+                        println!("Replaced by: {next}");
+                    }
                     if let Some(notes) = &item.notes {
                         println!("Notes: {notes}");
                     }
@@ -1994,6 +4569,24 @@ fn handle_library_command(paths: &Paths, command: LibraryCommand) -> Result<()>
                 None => bail!("item not found: {id}"),
             }
         }
+        LibraryCommand::History { id } => {
+            let item = if let Ok(id_num) = id.parse::<i64>() {
+                library.get_item(id_num)?
+            } else {
+                library.get_item_by_hash(&id)?
+            };
+            let item = item.with_context(|| format!("item not found: {id}"))?;
+            for entry in library.get_item_history(item.id)? {
+                let marker = if entry.id == item.id { "*" } else { " " };
+                println!(
+                    "{marker} {}\t{}\t{}\t{}",
+                    entry.id,
+                    entry.name,
+                    entry.source_version.as_deref().unwrap_or("-"),
+                    normalize_hash(&entry.hash).get(..16).unwrap_or(&entry.hash)
+                );
+            }
+        }
         LibraryCommand::Import {
             path,
             content_type,
@@ -2030,14 +4623,7 @@ fn handle_library_command(paths: &Paths, command: LibraryCommand) -> Result<()>
                 Some(item) => {
                     if delete_file {
                         // Delete from content store
-                        let store_path = match item.content_type {
-                            LibraryContentType::Mod => paths.store_mod_path(&item.hash),
-                            LibraryContentType::ResourcePack => {
-                                paths.store_resourcepack_path(&item.hash)
-                            }
-                            LibraryContentType::ShaderPack => paths.store_shaderpack_path(&item.hash),
-                            LibraryContentType::Skin => paths.store_skin_path(&item.hash),
-                        };
+                        let store_path = library.content_store_path(paths, item.content_type, &item.hash);
                         if store_path.exists() {
                             std::fs::remove_file(&store_path)?;
                             println!("deleted file from store");
@@ -2070,6 +4656,80 @@ fn handle_library_command(paths: &Paths, command: LibraryCommand) -> Result<()>
                 None => bail!("item not found: {id}"),
             }
         }
+        LibraryCommand::Query { sql, json } => {
+            let result = library.query(&sql)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else {
+                println!("{}", result.columns.join("\t"));
+                for row in &result.rows {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|value| match value {
+                            serde_json::Value::Null => String::new(),
+                            serde_json::Value::String(text) => text.clone(),
+                            other => other.to_string(),
+                        })
+                        .collect();
+                    println!("{}", cells.join("\t"));
+                }
+            }
+        }
+        LibraryCommand::Doctor { fix } => {
+            let checks = library.check_query_plans()?;
+            for check in &checks {
+                println!("{}:", check.query);
+                for step in &check.plan {
+                    println!("  {step}");
+                }
+                for sql in &check.missing_indexes {
+                    println!("  missing index: {sql}");
+                }
+            }
+            let missing = checks.iter().any(|check| !check.missing_indexes.is_empty());
+            if !missing {
+                println!("no missing indexes");
+            } else if fix {
+                for sql in library.add_missing_indexes()? {
+                    println!("created: {sql}");
+                }
+            } else {
+                println!("run `shard library doctor --fix` to create the missing indexes");
+            }
+        }
+        LibraryCommand::Purge {
+            content_types,
+            delete_files,
+            dry_run,
+        } => {
+            let content_types = content_types
+                .iter()
+                .map(|ct| {
+                    LibraryContentType::from_str(ct)
+                        .with_context(|| format!("invalid content type: {ct}; use: mod, resourcepack, shaderpack, skin"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if dry_run {
+                let items = library.purge_candidates(&content_types)?;
+                for item in &items {
+                    println!("would remove library item {} ({}, {})", item.id, item.content_type.as_str(), item.name);
+                    if delete_files {
+                        let path = library.content_store_path(paths, item.content_type, &item.hash);
+                        if path.exists() {
+                            println!("  would delete {}", path.display());
+                        }
+                    }
+                }
+                let bytes: i64 = items.iter().filter_map(|item| item.file_size).sum();
+                println!("{} unused items ({})", items.len(), format_mib(bytes.max(0) as u64));
+            } else {
+                let result = library.purge_unused_items(paths, &content_types, delete_files)?;
+                println!("removed {} unused items ({})", result.deleted_count, format_mib(result.freed_bytes));
+                for err in result.errors {
+                    println!("  {err}");
+                }
+            }
+        }
         LibraryCommand::Stats => {
             let stats = library.stats()?;
             println!("Library Statistics:");
@@ -2081,11 +4741,80 @@ fn handle_library_command(paths: &Paths, command: LibraryCommand) -> Result<()>
             println!("  Total size: {} bytes", stats.total_size);
             println!("  Tags: {}", stats.tags_count);
         }
+        LibraryCommand::Report { html, markdown } => {
+            let report = run_job(paths, JobKind::Audit, "library report", |_| library.report())?;
+            if html.is_none() && markdown.is_none() {
+                print!("{}", render_markdown(&report));
+            }
+            if let Some(path) = html {
+                fs::write(&path, render_html(&report))
+                    .with_context(|| format!("failed to write {}", path.display()))?;
+                println!("wrote {}", path.display());
+            }
+            if let Some(path) = markdown {
+                fs::write(&path, render_markdown(&report))
+                    .with_context(|| format!("failed to write {}", path.display()))?;
+                println!("wrote {}", path.display());
+            }
+        }
+        LibraryCommand::Verify { low_priority, rate } => {
+            let report = run_job(paths, JobKind::Audit, "content store", |job| {
+                let mut throttle = cli_throttle(low_priority, rate, job.cancel_token());
+                verify_store(paths, &mut throttle, |report| job.progress(report.checked as u64, None, None))
+            })?;
+            if report.cancelled {
+                println!("cancelled; the results are partial");
+            }
+            println!(
+                "verified {} files ({})",
+                report.checked,
+                format_mib(report.bytes)
+            );
+            if report.corrupt.is_empty() {
+                println!("no corrupted files found");
+            } else {
+                println!("corrupted:");
+                for item in &report.corrupt {
+                    println!("  {} {} (actual {})", item.kind, item.hash, item.actual);
+                }
+            }
+        }
+        LibraryCommand::Normalize => {
+            let changes = library.normalize_names()?;
+            for change in &changes {
+                println!("{}: {} -> {}", change.id, change.old, change.new);
+            }
+            println!("renamed {} items", changes.len());
+        }
+        LibraryCommand::Merge { target, source } => {
+            let find = |id: &str| -> Result<i64> {
+                let item = if let Ok(id_num) = id.parse::<i64>() {
+                    library.get_item(id_num)?
+                } else {
+                    library.get_item_by_hash(id)?
+                };
+                item.map(|item| item.id).with_context(|| format!("item not found: {id}"))
+            };
+            let merged = library.merge_items(find(&target)?, find(&source)?)?;
+            println!("item {} is now {}", merged.id, merged.name);
+        }
+        LibraryCommand::AutoTag => {
+            let config = load_config(paths)?;
+            let store = ContentStore::new(config.curseforge_api_key.as_deref());
+            let result = auto_tag_library(paths, &library, &store)?;
+            for error in &result.errors {
+                eprintln!("warning: {error}");
+            }
+            println!(
+                "tagged {} items ({} unchanged, {} unknown to the stores)",
+                result.tagged, result.unchanged, result.unknown
+            );
+        }
         LibraryCommand::Sync => {
             let result = library.sync_with_store(paths)?;
             println!(
-                "synced library: {} added, {} already present",
-                result.added, result.skipped
+                "synced library: {} added, {} already present, {} renamed, {} digested",
+                result.added, result.skipped, result.renamed, result.digested
             );
             if !result.errors.is_empty() {
                 println!("errors:");
@@ -2124,12 +4853,346 @@ fn handle_library_command(paths: &Paths, command: LibraryCommand) -> Result<()>
     Ok(())
 }
 
+fn handle_server_command(paths: &Paths, command: ServerCommand) -> Result<()> {
+    match command {
+        ServerCommand::Start {
+            profile,
+            accept_eula: accept,
+            detach,
+        } => {
+            let profile = load_profile(paths, &profile)?;
+            if accept {
+                accept_eula(paths, &profile.id)?;
+            }
+            if detach {
+                let instance = start_server_detached(paths, &profile)?;
+                println!("started server {} (pid {})", profile.id, instance.pid);
+            } else {
+                start_server(paths, &profile)?;
+            }
+        }
+        ServerCommand::Stop { profile } => {
+            for instance in stop_server(paths, &profile)? {
+                println!("stopped server {profile} (pid {})", instance.pid);
+            }
+        }
+        ServerCommand::Console { profile, command } => {
+            let mut console = connect_console(paths, &profile)?;
+            if !command.is_empty() {
+                println!("{}", console.command(&command.join(" "))?);
+                return Ok(());
+            }
+            for line in std::io::stdin().lines() {
+                let line = line.context("failed to read console input")?;
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let reply = console.command(line)?;
+                if !reply.is_empty() {
+                    println!("{reply}");
+                }
+                if line == "stop" {
+                    break;
+                }
+            }
+        }
+        ServerCommand::Config { command } => match command {
+            ServerConfigCommand::Show { profile } => {
+                if !paths.is_profile_present(&profile) {
+                    bail!("profile not found: {profile}");
+                }
+                let properties = load_server_properties(paths, &profile)?;
+                for spec in KNOWN_PROPERTIES {
+                    match properties.get(spec.key) {
+                        Some(value) => println!("{}={}", spec.key, value),
+                        None => println!("{}={}\t(default)", spec.key, spec.default),
+                    }
+                }
+                for (key, value) in properties.entries() {
+                    if property_spec(&key).is_none() {
+                        println!("{key}={value}");
+                    }
+                }
+            }
+            ServerConfigCommand::Get { profile, key } => {
+                let properties = load_server_properties(paths, &profile)?;
+                match properties.get(&key) {
+                    Some(value) => println!("{value}"),
+                    None => match property_spec(&key) {
+                        Some(spec) => println!("{}", spec.default),
+                        None => bail!("property not set: {key}"),
+                    },
+                }
+            }
+            ServerConfigCommand::Set {
+                profile,
+                key,
+                value,
+            } => {
+                let properties = set_server_property(paths, &profile, &key, &value)?;
+                println!(
+                    "set {key}={} for profile {profile}",
+                    properties.get(&key).unwrap_or_default()
+                );
+            }
+            ServerConfigCommand::Unset { profile, key } => {
+                let mut properties = load_server_properties(paths, &profile)?;
+                if properties.remove(&key) {
+                    save_server_properties(paths, &profile, &properties)?;
+                    println!("removed {key} from profile {profile}");
+                } else {
+                    bail!("property not set: {key}");
+                }
+            }
+        },
+        ServerCommand::Tunnel { command } => match command {
+            ServerTunnelCommand::Set {
+                profile,
+                provider,
+                binary,
+                args,
+                port,
+            } => {
+                let provider = TunnelProvider::parse(&provider)
+                    .with_context(|| format!("unknown tunnel provider: {provider}"))?;
+                if provider == TunnelProvider::Custom && binary.is_none() {
+                    bail!("custom tunnel requires --binary");
+                }
+                let mut profile_data = load_profile(paths, &profile)?;
+                profile_data.tunnel = Some(TunnelConfig {
+                    provider,
+                    binary,
+                    args,
+                    port,
+                });
+                save_profile(paths, &profile_data)?;
+                println!("set {} tunnel for profile {profile}", provider.as_str());
+            }
+            ServerTunnelCommand::Clear { profile } => {
+                let mut profile_data = load_profile(paths, &profile)?;
+                if profile_data.tunnel.take().is_none() {
+                    bail!("no tunnel configured for profile {profile}");
+                }
+                save_profile(paths, &profile_data)?;
+                println!("removed tunnel from profile {profile}");
+            }
+            ServerTunnelCommand::Show { profile } => {
+                let profile_data = load_profile(paths, &profile)?;
+                match profile_data.tunnel {
+                    Some(tunnel) => println!("{}", serde_json::to_string_pretty(&tunnel)?),
+                    None => println!("no tunnel configured"),
+                }
+            }
+        },
+    }
+    Ok(())
+}
+
+/// Ask a yes/no question on stdin; fails when there is no terminal to ask
+fn confirm(question: &str) -> Result<bool> {
+    if !atty::is(atty::Stream::Stdin) {
+        bail!("not a terminal; pass --accept to continue without confirmation");
+    }
+    eprint!("{question} [y/N] ");
+    std::io::Write::flush(&mut std::io::stderr()).ok();
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .context("failed to read answer")?;
+    Ok(matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Passphrase from SHARD_ACCOUNT_PASSPHRASE, or typed without echo; a new
+/// one (`confirm`) has to be typed twice
+fn read_passphrase(prompt: &str, confirm: bool) -> Result<String> {
+    if let Ok(value) = std::env::var("SHARD_ACCOUNT_PASSPHRASE") {
+        return Ok(value);
+    }
+    let theme = ColorfulTheme::default();
+    let mut input = Password::with_theme(&theme).with_prompt(prompt);
+    if confirm {
+        input = input.with_confirmation("Repeat the passphrase", "passphrases don't match");
+    }
+    input.interact().context("failed to read passphrase (set SHARD_ACCOUNT_PASSPHRASE when not in a terminal)")
+}
+
+fn handle_space_command(paths: &Paths, command: SpaceCommand) -> Result<()> {
+    if let Some(warning) = data_dir_owner_warning(paths) {
+        eprintln!("warning: {warning}");
+    }
+    match command {
+        SpaceCommand::List => {
+            for space in list_spaces(paths)? {
+                let marker = if space.active { "*" } else { " " };
+                println!("{marker} {}\t{} profiles", space.name, space.profile_count);
+            }
+        }
+        SpaceCommand::Current => {
+            println!("{}", active_space(paths));
+        }
+        SpaceCommand::Create { name } => {
+            create_space(paths, &name)?;
+            println!("created space {name}");
+        }
+        SpaceCommand::Use { name } => {
+            set_active_space(paths, &name)?;
+            if std::env::var(SPACE_ENV).is_ok() {
+                eprintln!("note: {SPACE_ENV} is set and overrides the active space");
+            }
+            println!("using space {name}");
+        }
+        SpaceCommand::Delete { name } => {
+            delete_space(paths, &name)?;
+            println!("deleted space {name}");
+        }
+    }
+    Ok(())
+}
+
 fn handle_modpack_command(paths: &Paths, command: ModpackCommand) -> Result<()> {
     match command {
-        ModpackCommand::Import { path, id } => {
-            let profile = import_mrpack(paths, &path, id.as_deref())?;
+        ModpackCommand::Import { path, id, dry_run: true, .. } => {
+            let preview = preview_mrpack(paths, &path, id.as_deref())?;
+            let loader = preview
+                .loader
+                .as_ref()
+                .map(|l| format!(", {}@{}", l.loader_type, l.version))
+                .unwrap_or_default();
+            println!("would create profile {} (minecraft {}{loader})", preview.profile_id, preview.mc_version);
+            println!(
+                "would download {} files ({}):",
+                preview.downloads.len(),
+                format_mib(preview.download_bytes())
+            );
+            for (file, _) in &preview.downloads {
+                println!("  {file}");
+            }
+            if !preview.overrides.is_empty() {
+                println!("would extract {} override files:", preview.overrides.len());
+                for file in &preview.overrides {
+                    println!("  {file}");
+                }
+            }
+            if preview.notes {
+                println!("would save the pack's README as profile notes");
+            }
+        }
+        ModpackCommand::Import {
+            path,
+            id,
+            dry_run: false,
+            accept,
+        } => {
+            let label = path.display().to_string();
+            if !accept && is_metered(paths) {
+                let bytes = preview_mrpack(paths, &path, id.as_deref())?.download_bytes();
+                if needs_confirmation(paths, bytes)
+                    && !confirm(&format!("the connection is metered; download {} for {label}?", format_size(bytes)))?
+                {
+                    bail!("import cancelled");
+                }
+            }
+            let profile = run_job(paths, JobKind::Import, &label, |job| import_mrpack(paths, &path, id.as_deref(), job))?;
             println!("imported modpack into profile {}", profile.id);
         }
+        ModpackCommand::Export { profile, out } => {
+            let summary = export_mrpack(paths, &profile, &out)?;
+            println!(
+                "exported {} to {} ({} linked, {} embedded)",
+                profile,
+                out.display(),
+                summary.linked,
+                summary.embedded
+            );
+            if !summary.extra_folders.is_empty() {
+                println!("included folders: {}", summary.extra_folders.join(", "));
+            }
+            if summary.notes {
+                println!("included profile notes");
+            }
+            if summary.shader_settings > 0 {
+                println!("included {} shader settings files", summary.shader_settings);
+            }
+        }
+        ModpackCommand::Folders { profile, set } => {
+            let mut loaded = load_profile(paths, &profile)?;
+            if let Some(folders) = set {
+                let folders: Vec<String> = folders
+                    .into_iter()
+                    .map(|f| f.trim().trim_matches('/').to_string())
+                    .filter(|f| !f.is_empty())
+                    .collect();
+                if let Some(bad) = folders.iter().find(|f| !is_plain_folder_name(f)) {
+                    bail!("invalid folder name: {bad}");
+                }
+                loaded.files.extra_folders = folders;
+                save_profile(paths, &loaded)?;
+            }
+            if loaded.files.extra_folders.is_empty() {
+                println!("no extra folders");
+            } else {
+                println!("{}", loaded.files.extra_folders.join(", "));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_preset_command(paths: &Paths, command: PresetCommand) -> Result<()> {
+    match command {
+        PresetCommand::List { id } => {
+            let profile = load_profile(paths, &id)?;
+            if profile.presets.is_empty() {
+                println!("no presets");
+            }
+            for (name, preset) in &profile.presets {
+                let mut parts = Vec::new();
+                if let Some(shader) = &preset.shaderpack {
+                    parts.push(format!("shader {shader}"));
+                }
+                if !preset.enable.is_empty() {
+                    parts.push(format!("enable {}", preset.enable.join(", ")));
+                }
+                if !preset.disable.is_empty() {
+                    parts.push(format!("disable {}", preset.disable.join(", ")));
+                }
+                println!("{name}\t{}", parts.join("; "));
+            }
+        }
+        PresetCommand::Save {
+            id,
+            name,
+            enable,
+            disable,
+            shader,
+        } => {
+            let preset = ContentPreset {
+                enable,
+                disable,
+                shaderpack: shader,
+            };
+            save_preset(paths, &id, &name, preset)?;
+            println!("saved preset {name}");
+        }
+        PresetCommand::Delete { id, name } => {
+            delete_preset(paths, &id, &name)?;
+            println!("deleted preset {name}");
+        }
+        PresetCommand::Apply { id, name } => {
+            let applied = apply_preset(paths, &id, &name)?;
+            for warning in &applied.warnings {
+                eprintln!("warning: {warning}");
+            }
+            if applied.changed.is_empty() {
+                println!("packs already match preset {name}");
+            } else {
+                println!("toggled: {}", applied.changed.join(", "));
+            }
+            if let Some(config) = &applied.shader_config {
+                println!("shader pack set in {}", config.display());
+            }
+        }
     }
     Ok(())
 }
@@ -2188,6 +5251,25 @@ fn handle_tag_command(library: &Library, command: TagCommand) -> Result<()> {
                 None => bail!("item not found: {item}"),
             }
         }
+        TagCommand::AddMatching {
+            tag,
+            content_type,
+            search,
+            with_tag,
+        } => {
+            if content_type.is_none() && search.is_none() && with_tag.is_none() {
+                bail!("add-matching needs a filter (--search, --content-type or --with-tag)");
+            }
+            let filter = LibraryFilter {
+                content_type,
+                search,
+                tags: with_tag,
+                limit: None,
+                offset: None,
+            };
+            let added = library.add_tag_to_matching(&filter, &tag)?;
+            println!("added tag '{tag}' to {added} items");
+        }
     }
 
     Ok(())
@@ -0,0 +1,167 @@
+//! Metered connections
+//!
+//! On a metered connection (a phone hotspot, a capped plan) shard holds back
+//! what can wait: the desktop app skips background warm-ups and pauses its
+//! periodic checks, and a launch, modpack import or store install that would
+//! download more than [`LARGE_DOWNLOAD_BYTES`] asks first. `metered` in the
+//! config forces this on or off; `auto` asks the OS where it can tell, which
+//! is NetworkManager on Linux and the connection cost on Windows. macOS
+//! doesn't tell command-line tools, so there it stays off unless set.
+
+use crate::config::load_config;
+use crate::paths::Paths;
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+#[cfg(any(target_os = "linux", windows))]
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Downloads at least this large ask first on a metered connection
+pub const LARGE_DOWNLOAD_BYTES: u64 = 200 * 1024 * 1024;
+
+/// How long a detected state is trusted before asking the OS again
+const DETECTION_TTL: Duration = Duration::from_secs(60);
+
+/// Whether the connection is treated as metered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MeteredMode {
+    /// Ask the OS
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+impl MeteredMode {
+    pub const ALL: [MeteredMode; 3] = [MeteredMode::Auto, MeteredMode::On, MeteredMode::Off];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MeteredMode::Auto => "auto",
+            MeteredMode::On => "on",
+            MeteredMode::Off => "off",
+        }
+    }
+}
+
+impl FromStr for MeteredMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(MeteredMode::Auto),
+            "on" | "true" | "yes" => Ok(MeteredMode::On),
+            "off" | "false" | "no" => Ok(MeteredMode::Off),
+            other => bail!("unknown metered mode: {other} (expected auto, on or off)"),
+        }
+    }
+}
+
+/// Whether large downloads should wait, per the config and the OS
+pub fn is_metered(paths: &Paths) -> bool {
+    match load_config(paths).map(|config| config.metered).unwrap_or_default() {
+        MeteredMode::On => true,
+        MeteredMode::Off => false,
+        MeteredMode::Auto => detected_metered(),
+    }
+}
+
+/// Whether downloading `bytes` should be confirmed first
+pub fn needs_confirmation(paths: &Paths, bytes: u64) -> bool {
+    bytes >= LARGE_DOWNLOAD_BYTES && is_metered(paths)
+}
+
+/// What the OS says, remembered for [`DETECTION_TTL`]; unknown counts as
+/// unmetered
+pub fn detected_metered() -> bool {
+    static LAST: Mutex<Option<(Instant, bool)>> = Mutex::new(None);
+    let mut last = LAST.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((at, metered)) = *last
+        && at.elapsed() < DETECTION_TTL
+    {
+        return metered;
+    }
+    let metered = detect().unwrap_or(false);
+    *last = Some((Instant::now(), metered));
+    metered
+}
+
+#[cfg(target_os = "linux")]
+fn detect() -> Option<bool> {
+    // The primary connection's state, e.g. "u 3"
+    let output = Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_nm_metered(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(windows)]
+fn detect() -> Option<bool> {
+    let script = "[Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime] | Out-Null; \
+                  $profile = [Windows.Networking.Connectivity.NetworkInformation]::GetInternetConnectionProfile(); \
+                  if ($profile) { $profile.GetConnectionCost().NetworkCostType }";
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_network_cost(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn detect() -> Option<bool> {
+    None
+}
+
+/// NetworkManager's `NMMetered`: 1 is yes and 3 a guessed yes; 2 and 4 are
+/// no and 0 unknown
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_nm_metered(output: &str) -> Option<bool> {
+    match output.trim().strip_prefix("u ")?.trim() {
+        "1" | "3" => Some(true),
+        "2" | "4" => Some(false),
+        _ => None,
+    }
+}
+
+/// Windows' `NetworkCostType`: fixed and variable plans are metered
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_network_cost(output: &str) -> Option<bool> {
+    match output.trim() {
+        "Fixed" | "Variable" => Some(true),
+        "Unrestricted" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metered_state() {
+        assert_eq!(parse_nm_metered("u 3\n"), Some(true));
+        assert_eq!(parse_nm_metered("u 4\n"), Some(false));
+        assert_eq!(parse_nm_metered("u 0\n"), None);
+        assert_eq!(parse_nm_metered(""), None);
+        assert_eq!(parse_network_cost("Variable\r\n"), Some(true));
+        assert_eq!(parse_network_cost("Unrestricted\r\n"), Some(false));
+        assert_eq!(parse_network_cost("Unknown"), None);
+        assert_eq!("Off".parse::<MeteredMode>().unwrap(), MeteredMode::Off);
+    }
+}
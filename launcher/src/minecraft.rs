@@ -1,11 +1,36 @@
-use crate::instance::materialize_instance;
-use crate::java::{detect_installations, get_required_java_version, is_java_compatible};
+use crate::asset_db::{AssetDb, AssetRecord, sha1_file_throttled};
+use crate::config_history::snapshot_configs;
+use crate::crash_stats::collect_profile_crashes;
+use crate::disk::WriteContext;
+use crate::download::download_resumable;
+use crate::file_lock::FileLock;
+use crate::gamescope::{add_gamescope_args, gamescope_env};
+use crate::download_queue::{DownloadQueue, FailedDownload};
+use crate::installer::{INSTALLER_TIMEOUT, InstallTarget, InstallerMilestone, install_headless, run_installer};
+use crate::instance::{adopt_unmanaged_files, materialize_instance};
+use crate::jobs::Job;
+use crate::loader::LoaderKind;
+use crate::net::retry;
+use crate::ops::refresh_plan_account;
+use crate::mirrors::with_mirror_fallback;
+use crate::config::load_config;
+use crate::java::{
+    download_and_install_java, find_compatible_java, find_java_for_arch, get_required_java_version, host_arch,
+    validate_java_path,
+};
 use crate::paths::Paths;
-use crate::profile::{Loader, Profile};
-use crate::util::normalize_path_separator;
+use crate::process::{RunningInstance, register_instance, unregister_instance};
+use crate::profile::{Loader, ProcessPriority, Profile, ProfileKind};
+use crate::playtime::{SessionRecord, record_session};
+use crate::resource_monitor::{DEFAULT_SAMPLE_INTERVAL, ResourceMonitor, format_mib};
+use crate::scheduling::{apply_scheduling, check_scheduling};
+use crate::session::detect_crash;
+use crate::throttle::Throttle;
+use crate::tunnel::start_profile_tunnel;
+use crate::util::{normalize_path_separator, now_epoch_secs};
 use anyhow::{Context, Result, bail};
 use reqwest::blocking::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha1::{Digest, Sha1};
 use shell_words::split;
@@ -13,11 +38,15 @@ use std::collections::HashMap;
 use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::time::Duration;
 
 const VERSION_MANIFEST_URL: &str =
     "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
 const LIBRARIES_BASE: &str = "https://libraries.minecraft.net/";
+/// LWJGL 3 release swapped in on ARM machines for versions whose own LWJGL
+/// has no natives for them; the first with Apple Silicon builds
+const ARM_LWJGL_VERSION: &str = "3.3.1";
 
 #[derive(Debug, Clone)]
 pub struct LaunchAccount {
@@ -25,78 +54,398 @@ pub struct LaunchAccount {
     pub username: String,
     pub access_token: String,
     pub xuid: Option<String>,
+    /// Run the game as a demo user: the time-limited demo world, no
+    /// purchase needed
+    pub demo: bool,
+    /// When `access_token` runs out, unix seconds; `None` if it never does
+    pub expires_at: Option<u64>,
 }
 
-#[derive(Debug, Clone)]
+impl LaunchAccount {
+    /// Placeholder account for demo launches, which need no sign-in
+    pub fn demo() -> Self {
+        Self {
+            uuid: "0".repeat(32),
+            username: "Player".to_string(),
+            access_token: "0".to_string(),
+            xuid: None,
+            demo: true,
+            expires_at: None,
+        }
+    }
+}
+
+/// Where the game goes right after it starts, skipping the title screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuickPlay {
+    /// `host` or `host:port`
+    Server(String),
+    /// Folder name of a world under the instance's `saves/`
+    World(String),
+}
+
+const DEFAULT_SERVER_PORT: u16 = 25565;
+
+/// Split `host[:port]`; IPv6 hosts need brackets when a port is given
+fn parse_server_address(address: &str) -> Result<(String, u16)> {
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') || host.ends_with(']') => {
+            let port = port
+                .parse::<u16>()
+                .with_context(|| format!("invalid port in server address: {address}"))?;
+            (host, port)
+        }
+        _ => (address, DEFAULT_SERVER_PORT),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        bail!("invalid server address: {address}");
+    }
+    Ok((host.to_string(), port))
+}
+
+impl QuickPlay {
+    /// Enable the quick play features and variables the version declares
+    /// (1.20+). Older versions only know `--server`/`--port`, returned as
+    /// extra game arguments.
+    fn apply(
+        &self,
+        version: &VersionJson,
+        instance_dir: &Path,
+        ctx: &mut RuleContext,
+        vars: &mut HashMap<String, String>,
+    ) -> Result<Vec<String>> {
+        let supported = version_supports_quick_play(version);
+        match self {
+            QuickPlay::Server(address) => {
+                let (host, port) = parse_server_address(address)?;
+                if !supported {
+                    return Ok(vec!["--server".into(), host, "--port".into(), port.to_string()]);
+                }
+                ctx.features.insert("is_quick_play_multiplayer".to_string(), true);
+                vars.insert("quickPlayMultiplayer".into(), format!("{host}:{port}"));
+            }
+            QuickPlay::World(world) => {
+                if !supported {
+                    bail!("opening a world on launch needs Minecraft 1.20 or newer");
+                }
+                if !instance_dir.join("saves").join(world).is_dir() {
+                    bail!("world not found in saves/: {world}");
+                }
+                ctx.features.insert("is_quick_play_singleplayer".to_string(), true);
+                vars.insert("quickPlaySingleplayer".into(), world.clone());
+            }
+        }
+        Ok(Vec::new())
+    }
+}
+
+/// Whether the version's arguments have quick play entries
+fn version_supports_quick_play(version: &VersionJson) -> bool {
+    version.arguments.as_ref().is_some_and(|arguments| {
+        arguments.game.iter().any(|arg| match arg {
+            Argument::WithRules { rules, .. } => rules.iter().any(|rule| {
+                rule.features
+                    .as_ref()
+                    .is_some_and(|features| features.contains_key("is_quick_play_multiplayer"))
+            }),
+            Argument::Simple(_) => false,
+        })
+    })
+}
+
+/// Everything needed to start the game; serializes to what
+/// `shard launch --prepare-only --json` prints
+#[derive(Debug, Clone, Serialize)]
 pub struct LaunchPlan {
     pub instance_dir: PathBuf,
+    /// Wrapper command java runs under (empty to run java directly)
+    pub wrapper: Vec<String>,
     pub java_exec: String,
     pub jvm_args: Vec<String>,
     pub classpath: String,
     pub main_class: String,
     pub game_args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<ProcessPriority>,
+    /// CPU cores the game may run on; empty for all of them
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cpu_affinity: Vec<usize>,
+    /// Environment variables set for the game on top of the launcher's own
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<(String, String)>,
 }
 
-pub fn prepare(paths: &Paths, profile: &Profile, account: &LaunchAccount) -> Result<LaunchPlan> {
-    let instance_dir = materialize_instance(paths, profile)?;
+impl LaunchPlan {
+    /// The binary that gets executed: the wrapper, or java itself
+    pub fn program(&self) -> &str {
+        self.wrapper.first().unwrap_or(&self.java_exec)
+    }
+
+    /// Warn about priority or affinity settings that didn't take on the
+    /// started game
+    pub fn check_scheduling(&self, pid: u32) {
+        for warning in check_scheduling(pid, self.priority, &self.cpu_affinity) {
+            eprintln!("warning: {warning}");
+        }
+    }
+
+    /// Put a renewed access token in place of `old` in the arguments
+    pub fn replace_access_token(&mut self, old: &str, new: &str) {
+        if old.is_empty() || old == new {
+            return;
+        }
+        for arg in self.jvm_args.iter_mut().chain(self.game_args.iter_mut()) {
+            if arg.contains(old) {
+                *arg = arg.replace(old, new);
+            }
+        }
+    }
+}
 
+/// Stages of `prepare`, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrepareStage {
+    ResolveVersion,
+    /// Forge/NeoForge installer progress, reported during `ResolveVersion`
+    /// the first time a loader version is installed
+    InstallLoader(InstallerMilestone),
+    EnsureClient,
+    EnsureAssets,
+    EnsureLibraries,
+    /// Finding (or installing) Java; only reported by `warm_up`, `prepare`
+    /// does it while building arguments
+    ResolveJava,
+    BuildArgs,
+}
+
+impl PrepareStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ResolveVersion => "resolve_version",
+            Self::InstallLoader(_) => "install_loader",
+            Self::EnsureClient => "ensure_client",
+            Self::EnsureAssets => "ensure_assets",
+            Self::EnsureLibraries => "ensure_libraries",
+            Self::ResolveJava => "resolve_java",
+            Self::BuildArgs => "build_args",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ResolveVersion => "Resolving version",
+            Self::InstallLoader(milestone) => milestone.label(),
+            Self::EnsureClient => "Downloading client",
+            Self::EnsureAssets => "Downloading assets",
+            Self::EnsureLibraries => "Downloading libraries",
+            Self::ResolveJava => "Finding Java",
+            Self::BuildArgs => "Building launch arguments",
+        }
+    }
+}
+
+/// Version JSON chain for a profile, with loaders installed
+#[derive(Clone)]
+pub struct ResolvedGameVersion {
+    pub version_id: String,
+    /// Forge/NeoForge build their own client jar, so the vanilla one stays off the classpath
+    pub uses_processed_client: bool,
+    resolved: ResolvedVersion,
+}
+
+impl ResolvedGameVersion {
+    pub fn main_class(&self) -> Option<&str> {
+        self.resolved.merged.main_class.as_deref()
+    }
+
+    pub fn asset_index_id(&self) -> Option<&str> {
+        self.resolved.merged.asset_index.as_ref().map(|a| a.id.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameAssets {
+    pub asset_index_id: String,
+    pub assets_root: PathBuf,
+    /// `assets/virtual/<index>` for the 1.6-1.7.2 `legacy` index, which the
+    /// game reads by file name
+    pub virtual_dir: Option<PathBuf>,
+    /// The pre-1.6 index: objects go to `<instance>/resources` at launch
+    pub map_to_resources: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GameLibraries {
+    pub classpath: String,
+    pub natives_dir: PathBuf,
+    /// Architecture the Java must have to load the natives, on ARM machines
+    pub java_arch: Option<&'static str>,
+}
+
+/// Prepared stages and launch options the final arguments are built from
+pub struct LaunchInputs<'a> {
+    pub assets: &'a GameAssets,
+    pub libraries: &'a GameLibraries,
+    pub account: &'a LaunchAccount,
+    pub quick_play: Option<&'a QuickPlay>,
+}
+
+/// Resolve the profile's version id (installing the loader if needed) and load
+/// its version JSON chain
+pub fn resolve_game_version(
+    paths: &Paths,
+    profile: &Profile,
+    on_stage: &mut dyn FnMut(PrepareStage),
+) -> Result<ResolvedGameVersion> {
     let java_path = profile.runtime.java.as_deref();
-    let version_id = resolve_version_id(paths, &profile.mc_version, profile.loader.as_ref(), java_path)?;
+    let version_id = resolve_version_id(paths, &profile.mc_version, profile.loader.as_ref(), java_path, on_stage)?;
     let resolved = resolve_version(paths, &version_id)?;
-    let version = resolved.merged;
-
-    // Collect client JARs from versions in the chain.
-    // Forge/NeoForge handle the client JAR internally via their processed JARs,
-    // so we shouldn't add the vanilla client JAR to the classpath for those loaders.
-    let is_forge_loader = profile.loader.as_ref().map_or(false, |l| {
-        l.loader_type == "forge" || l.loader_type == "neoforge"
-    });
+    let uses_processed_client = profile
+        .loader
+        .as_ref()
+        .and_then(|l| l.loader_type.parse::<LoaderKind>().ok())
+        .is_some_and(|kind| kind.capabilities().needs_installer);
+    Ok(ResolvedGameVersion {
+        version_id,
+        uses_processed_client,
+        resolved,
+    })
+}
 
+/// Download the client jars of the version chain; returns the jars that
+/// belong on the classpath
+pub fn ensure_client(paths: &Paths, version: &ResolvedGameVersion) -> Result<Vec<PathBuf>> {
     let mut client_jars = Vec::new();
-    for entry in &resolved.chain {
+    for entry in &version.resolved.chain {
         if entry.downloads.is_some() {
             let jar_path = ensure_client_jar(paths, entry)?;
-            // For Forge/NeoForge, download the client JAR (needed for processing)
-            // but don't add it to the classpath - they handle it internally
-            if !is_forge_loader {
+            // Forge/NeoForge still need the jar downloaded for processing
+            if !version.uses_processed_client {
                 client_jars.push(jar_path);
             }
         }
     }
+    Ok(client_jars)
+}
 
-    let asset_index_id = ensure_assets(paths, &version)?;
-    let (classpath, natives_dir) = ensure_libraries(paths, &version, &instance_dir, &client_jars)?;
-
-    let java_exec = resolve_java(profile.runtime.java.as_deref(), &profile.mc_version);
+/// Download the asset index and all asset objects; a virtual (legacy) index
+/// is also laid out by file name
+pub fn ensure_game_assets(paths: &Paths, version: &ResolvedGameVersion) -> Result<GameAssets> {
+    let index = ensure_assets(paths, &version.resolved.merged)?;
     let assets_root = paths
         .minecraft_assets_objects
         .parent()
         .context("assets root missing")?
         .to_path_buf();
+    let virtual_dir = if index.is_virtual && !index.map_to_resources {
+        let dir = assets_root.join("virtual").join(&index.id);
+        layout_legacy_assets(paths, &index, &dir)?;
+        Some(dir)
+    } else {
+        None
+    };
+    Ok(GameAssets {
+        asset_index_id: index.id,
+        assets_root,
+        virtual_dir,
+        map_to_resources: index.map_to_resources,
+    })
+}
+
+/// Download libraries and extract natives into the version's shared natives
+/// directory
+pub fn ensure_game_libraries(
+    paths: &Paths,
+    version: &ResolvedGameVersion,
+    instance_dir: &Path,
+    client_jars: &[PathBuf],
+) -> Result<GameLibraries> {
+    ensure_libraries(paths, &version.resolved.merged, instance_dir, client_jars)
+}
 
-    let vars = build_var_map(
+/// Build the final JVM/game arguments from the prepared stages
+pub fn build_launch_plan(
+    paths: &Paths,
+    profile: &Profile,
+    version: &ResolvedGameVersion,
+    instance_dir: PathBuf,
+    inputs: LaunchInputs,
+) -> Result<LaunchPlan> {
+    let LaunchInputs { assets, libraries, account, quick_play } = inputs;
+    let version = &version.resolved.merged;
+    let java_exec = resolve_java(paths, profile.runtime.java.as_deref(), &profile.mc_version)?;
+    let java_exec = match libraries.java_arch {
+        Some(arch) => java_for_natives(java_exec, arch, profile)?,
+        None => java_exec,
+    };
+
+    let mut vars = build_var_map(
         &instance_dir,
-        &assets_root,
-        &asset_index_id,
-        &classpath,
-        &natives_dir,
+        &assets.assets_root,
+        &assets.asset_index_id,
+        &libraries.classpath,
+        &libraries.natives_dir,
         &paths.minecraft_libraries,
-        &version,
+        version,
         account,
     );
 
-    let (mut jvm_args, game_args) = build_args(&version, &vars)?;
+    // Versions before 1.7.3 read assets by file name from `${game_assets}`
+    let game_assets = if assets.map_to_resources {
+        let index = load_asset_index(paths, &assets.asset_index_id)?;
+        let dir = instance_dir.join("resources");
+        layout_legacy_assets(paths, &index, &dir)?;
+        dir
+    } else {
+        assets.virtual_dir.clone().unwrap_or_else(|| assets.assets_root.clone())
+    };
+    vars.insert(
+        "game_assets".into(),
+        normalize_path_separator(&game_assets.to_string_lossy()),
+    );
+
+    let mut ctx = RuleContext::new();
+    if account.demo {
+        ctx.features.insert("is_demo_user".to_string(), true);
+    }
+    if let Some(size) = profile.runtime.gamescope {
+        ctx.features.insert("has_custom_resolution".to_string(), true);
+        vars.insert("resolution_width".into(), size.width.to_string());
+        vars.insert("resolution_height".into(), size.height.to_string());
+    }
+    let legacy_quick_play = match quick_play {
+        Some(quick_play) => quick_play.apply(version, &instance_dir, &mut ctx, &mut vars)?,
+        None => Vec::new(),
+    };
+
+    let (mut jvm_args, mut game_args) = build_args(version, &vars, &ctx)?;
+    game_args.extend(legacy_quick_play);
+    // Versions without argument rules take the flag directly
+    if account.demo && !game_args.iter().any(|arg| arg == "--demo") {
+        game_args.push("--demo".to_string());
+    }
+    let mut env = Vec::new();
+    if let Some(size) = profile.runtime.gamescope {
+        add_gamescope_args(&mut game_args, size);
+        env = gamescope_env();
+    }
 
     if let Some(memory) = &profile.runtime.memory
         && !jvm_args.iter().any(|arg| arg.starts_with("-Xmx")) {
             jvm_args.push(format!("-Xmx{memory}"));
         }
 
+    if let Some(preset) = profile.runtime.jvm_preset {
+        jvm_args.extend(preset.jvm_flags().iter().map(|flag| flag.to_string()));
+    }
+
     if !profile.runtime.args.is_empty() {
         jvm_args.extend(profile.runtime.args.iter().cloned());
     }
 
-    ensure_jvm_flag(&mut jvm_args, "-Djava.library.path", &natives_dir)?;
+    ensure_jvm_flag(&mut jvm_args, "-Djava.library.path", &libraries.natives_dir)?;
     strip_classpath_args(&mut jvm_args);
 
     let main_class = version
@@ -106,49 +455,593 @@ pub fn prepare(paths: &Paths, profile: &Profile, account: &LaunchAccount) -> Res
 
     Ok(LaunchPlan {
         instance_dir,
+        wrapper: profile.runtime.wrapper.clone(),
         java_exec,
         jvm_args,
-        classpath,
+        classpath: libraries.classpath.clone(),
         main_class,
         game_args,
+        priority: profile.runtime.priority,
+        cpu_affinity: profile.runtime.cpu_affinity.clone(),
+        env,
+    })
+}
+
+/// Exclusive right to download and install the files of `mc_version`.
+/// Another launch of the same version is waited for, after which
+/// everything it fetched is already cached.
+fn lock_version_files(paths: &Paths, mc_version: &str) -> Result<FileLock> {
+    let path = paths.minecraft_prepare_lock(mc_version);
+    if let Some(lock) = FileLock::try_acquire(&path)? {
+        return Ok(lock);
+    }
+    eprintln!("waiting for another launch to finish preparing {mc_version}...");
+    FileLock::acquire(&path)
+}
+
+/// Download everything a profile needs to launch without building a plan,
+/// e.g. right after the profile is created. No account is required.
+pub fn prefetch(paths: &Paths, profile: &Profile, mut on_stage: impl FnMut(PrepareStage)) -> Result<()> {
+    paths.ensure_writable()?;
+    let _lock = lock_version_files(paths, &profile.mc_version)?;
+    on_stage(PrepareStage::ResolveVersion);
+    let version = resolve_game_version(paths, profile, &mut on_stage)?;
+    on_stage(PrepareStage::EnsureClient);
+    let client_jars = ensure_client(paths, &version)?;
+    on_stage(PrepareStage::EnsureAssets);
+    ensure_game_assets(paths, &version)?;
+    on_stage(PrepareStage::EnsureLibraries);
+    ensure_game_libraries(paths, &version, &paths.instance_dir(&profile.id), &client_jars)?;
+    Ok(())
+}
+
+/// Everything `prepare` does short of building the plan: link the
+/// instance content, download game files and find (or install) Java, so the
+/// first real launch is instant. Returns the Java that will be used.
+pub fn warm_up(paths: &Paths, profile: &Profile, mut on_stage: impl FnMut(PrepareStage)) -> Result<String> {
+    materialize_instance(paths, profile)?;
+    prefetch(paths, profile, &mut on_stage)?;
+    on_stage(PrepareStage::ResolveJava);
+    resolve_java(paths, profile.runtime.java.as_deref(), &profile.mc_version)
+}
+
+/// Files of one kind that `prepare` would download
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct DownloadEstimate {
+    pub files: usize,
+    /// Total size of the files whose size is known
+    pub bytes: u64,
+    /// Files the version JSON gives no size for, not counted in `bytes`
+    pub unknown_size: usize,
+}
+
+impl DownloadEstimate {
+    /// Count `path` unless it is already there with the expected size
+    fn count(&mut self, path: &Path, size: Option<u64>) {
+        let present = fs::metadata(path).is_ok_and(|meta| meta.len() > 0 && size.is_none_or(|size| size == meta.len()));
+        if present {
+            return;
+        }
+        self.files += 1;
+        match size {
+            Some(size) => self.bytes += size,
+            None => self.unknown_size += 1,
+        }
+    }
+}
+
+/// What launching a profile would download, see [`estimate_prepare`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PrepareEstimate {
+    pub client: DownloadEstimate,
+    pub libraries: DownloadEstimate,
+    pub assets: DownloadEstimate,
+    /// Forge, NeoForge or OptiFine still has to be installed; what its
+    /// installer downloads isn't counted
+    pub loader_pending: bool,
+    /// Java version that would be downloaded because no installed one fits
+    pub java_download: Option<u32>,
+}
+
+impl PrepareEstimate {
+    pub fn files(&self) -> usize {
+        self.client.files + self.libraries.files + self.assets.files
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.client.bytes + self.libraries.bytes + self.assets.bytes
+    }
+}
+
+/// Count the client jars, libraries and asset objects `prepare` would
+/// download for a profile, without downloading them, so a launch on a
+/// metered connection can be confirmed first. Only version JSONs and the
+/// asset index are fetched if missing, and files are checked by size, not
+/// hashed. Loaders that run an installer aren't installed for this: until
+/// they are, the vanilla version is counted.
+pub fn estimate_prepare(paths: &Paths, profile: &Profile) -> Result<PrepareEstimate> {
+    let mut estimate = PrepareEstimate::default();
+    let version_id = match estimate_version_id(paths, profile)? {
+        Some(id) => id,
+        None => {
+            estimate.loader_pending = true;
+            profile.mc_version.clone()
+        }
+    };
+    let version = ResolvedGameVersion {
+        resolved: resolve_version(paths, &version_id)?,
+        version_id,
+        uses_processed_client: false,
+    };
+    let merged = &version.resolved.merged;
+
+    for file in client_files(paths, &version) {
+        estimate.client.count(&file.path, file.size);
+    }
+    let host = host_libraries(paths, merged);
+    let (libraries, natives) = library_files(paths, &host);
+    let natives_cached = paths.minecraft_natives_dir(&host.natives_id).join(NATIVES_COMPLETE).exists();
+    let natives = natives.iter().filter(|_| !natives_cached);
+    let mut seen = std::collections::HashSet::new();
+    for file in libraries.iter().chain(natives).filter(|file| seen.insert(&file.path)) {
+        estimate.libraries.count(&file.path, file.size);
+    }
+    if let Some(asset_index) = &merged.asset_index {
+        let index_path = paths.minecraft_asset_index(&asset_index.id);
+        download_with_sha1(&asset_index.url, &index_path, Some(&asset_index.sha1))?;
+        let mut seen = std::collections::HashSet::new();
+        for object in read_asset_index(&index_path)?.objects.into_values() {
+            if object.hash.len() >= 2 && seen.insert(object.hash.clone()) {
+                estimate.assets.count(&paths.minecraft_asset_object(&object.hash), object.size);
+            }
+        }
+    }
+
+    let auto_install = load_config(paths).is_ok_and(|config| config.auto_install_java);
+    if auto_install
+        && profile.runtime.java.is_none()
+        && find_compatible_java(&profile.mc_version, &paths.java_runtimes).is_none()
+    {
+        estimate.java_download = Some(get_required_java_version(&profile.mc_version));
+    }
+    Ok(estimate)
+}
+
+/// Version id a profile launches, without running a loader installer:
+/// `None` while its Forge, NeoForge or OptiFine isn't installed yet
+fn estimate_version_id(paths: &Paths, profile: &Profile) -> Result<Option<String>> {
+    let mc_version = &profile.mc_version;
+    let Some(loader) = &profile.loader else {
+        return Ok(Some(mc_version.clone()));
+    };
+    let latest = loader.version.eq_ignore_ascii_case("latest");
+    let id = match loader.loader_type.parse::<LoaderKind>()? {
+        LoaderKind::Forge => format!("forge-{}", forge_version_id(mc_version, &loader.version)?),
+        LoaderKind::NeoForge if latest => format!("neoforge-{}", resolve_neoforge_latest_version(mc_version)?),
+        LoaderKind::NeoForge => format!("neoforge-{}", loader.version),
+        LoaderKind::OptiFine if latest => {
+            format!("{mc_version}-OptiFine_{}", resolve_optifine_latest_version(mc_version)?)
+        }
+        LoaderKind::OptiFine => format!("{mc_version}-OptiFine_{}", loader.version),
+        // Only a version JSON to fetch
+        LoaderKind::Fabric | LoaderKind::Quilt | LoaderKind::LiteLoader => {
+            return resolve_version_id(paths, mc_version, Some(loader), None, &mut |_| {}).map(Some);
+        }
+    };
+    Ok(is_version_json_valid(&paths.minecraft_version_json(&id)).then_some(id))
+}
+
+/// Outcome of [`verify_game_files`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GameFilesReport {
+    /// Files hashed
+    pub checked: usize,
+    /// Size of the intact files
+    pub bytes: u64,
+    pub missing: usize,
+    /// Files whose hash didn't match (or empty files without a known hash)
+    pub corrupt: usize,
+    /// Missing or corrupt files that were downloaded again
+    pub repaired: Vec<PathBuf>,
+    /// Files that could not be downloaded; queued for `shard downloads retry`
+    pub failed: Vec<FailedDownload>,
+}
+
+/// A file referenced by a version JSON
+struct GameFile {
+    url: String,
+    path: PathBuf,
+    sha1: Option<String>,
+    size: Option<u64>,
+}
+
+/// Client jars of the version chain
+fn client_files(paths: &Paths, version: &ResolvedGameVersion) -> Vec<GameFile> {
+    let mut files = Vec::new();
+    for entry in &version.resolved.chain {
+        if let Some(client) = entry.downloads.as_ref().and_then(|downloads| downloads.client.as_ref()) {
+            files.push(GameFile {
+                url: client.url.clone(),
+                path: paths.minecraft_version_jar(&entry.id),
+                sha1: Some(client.sha1.clone()),
+                size: client.size,
+            });
+        }
+    }
+    files
+}
+
+/// Library jars, and the natives jars for this platform
+fn library_files(paths: &Paths, host: &HostLibraries) -> (Vec<GameFile>, Vec<GameFile>) {
+    let mut files = Vec::new();
+    let mut native_files = Vec::new();
+    for library in host.libraries.iter().filter(|library| library_allowed(library)) {
+        let downloads = library.downloads.as_ref();
+        let base_url = library.url.as_deref().unwrap_or(LIBRARIES_BASE);
+        if let Some(artifact) = downloads.and_then(|downloads| downloads.artifact.as_ref()) {
+            files.push(GameFile {
+                url: artifact.url.clone(),
+                path: paths.minecraft_library_path(&artifact.path),
+                sha1: Some(artifact.sha1.clone()),
+                size: artifact.size,
+            });
+        } else if let Some(path) = maven_path_from_name(&library.name) {
+            files.push(GameFile {
+                url: join_url(base_url, &path),
+                path: paths.minecraft_library_path(&path),
+                sha1: None,
+                size: None,
+            });
+        }
+
+        let Some(classifier) = library.natives.as_ref().and_then(|natives| natives.get(&os_key())) else {
+            continue;
+        };
+        let classifier = classifier.replace("${arch}", arch_marker());
+        if let Some(artifact) = downloads
+            .and_then(|downloads| downloads.classifiers.as_ref())
+            .and_then(|classifiers| classifiers.get(&classifier))
+        {
+            native_files.push(GameFile {
+                url: artifact.url.clone(),
+                path: paths.minecraft_library_path(&artifact.path),
+                sha1: Some(artifact.sha1.clone()),
+                size: artifact.size,
+            });
+        } else if let Some(path) = maven_path_from_name_with_classifier(&library.name, &classifier) {
+            native_files.push(GameFile {
+                url: join_url(base_url, &path),
+                path: paths.minecraft_library_path(&path),
+                sha1: None,
+                size: None,
+            });
+        }
+    }
+    (files, native_files)
+}
+
+/// Re-hash the client jars, libraries and asset objects of a profile's
+/// version and download again whatever is missing or corrupt. Libraries
+/// generated by a loader installer (no download URL) are left alone. Reads
+/// are paced by `throttle`, and cancelling it stops the check with an error.
+pub fn verify_game_files(
+    paths: &Paths,
+    profile: &Profile,
+    job: &Job,
+    throttle: &mut Throttle,
+) -> Result<GameFilesReport> {
+    let version = resolve_game_version(paths, profile, &mut |_| {})?;
+    let merged = &version.resolved.merged;
+    let mut report = GameFilesReport::default();
+    let mut queue = DownloadQueue::load(paths)?;
+
+    let mut files = client_files(paths, &version);
+    let host = host_libraries(paths, merged);
+    let (library_files, native_files) = library_files(paths, &host);
+    files.extend(library_files);
+    if let Some(asset_index) = &merged.asset_index {
+        files.push(GameFile {
+            url: asset_index.url.clone(),
+            path: paths.minecraft_asset_index(&asset_index.id),
+            sha1: Some(asset_index.sha1.clone()),
+            size: None,
+        });
+    }
+
+    for file in files.iter().filter(|file| !file.url.is_empty()) {
+        verify_game_file(file, job, throttle, &mut queue, &mut report)?;
+    }
+    let mut natives_repaired = false;
+    for file in native_files.iter().filter(|file| !file.url.is_empty()) {
+        natives_repaired |= verify_game_file(file, job, throttle, &mut queue, &mut report)?;
+    }
+    // Extracted natives of a repaired jar may be broken too
+    if natives_repaired {
+        let natives_dir = paths.minecraft_natives_dir(&host.natives_id);
+        if natives_dir.exists() {
+            fs::remove_dir_all(&natives_dir)
+                .write_context(&natives_dir, || format!("failed to remove {}", natives_dir.display()))?;
+        }
+    }
+
+    // The index was repaired above if it could be
+    let index = merged
+        .asset_index
+        .as_ref()
+        .and_then(|asset_index| read_asset_index(&paths.minecraft_asset_index(&asset_index.id)).ok());
+    let mut verified = Vec::new();
+    let result = match index {
+        Some(index) => verify_asset_objects(paths, index, job, throttle, &mut queue, &mut report, &mut verified),
+        None => Ok(()),
+    };
+    AssetDb::from_paths(paths)?.record(&verified)?;
+    queue.save()?;
+    result?;
+
+    report.failed = queue.failed;
+    Ok(report)
+}
+
+fn verify_asset_objects(
+    paths: &Paths,
+    index: AssetIndex,
+    job: &Job,
+    throttle: &mut Throttle,
+    queue: &mut DownloadQueue,
+    report: &mut GameFilesReport,
+    verified: &mut Vec<AssetRecord>,
+) -> Result<()> {
+    for object in index.objects.into_values().filter(|object| object.hash.len() >= 2) {
+        let file = GameFile {
+            url: object.url.clone().unwrap_or_else(|| {
+                format!(
+                    "https://resources.download.minecraft.net/{}/{}",
+                    &object.hash[0..2],
+                    object.hash
+                )
+            }),
+            path: paths.minecraft_asset_object(&object.hash),
+            sha1: Some(object.hash.clone()),
+            size: object.size,
+        };
+        verify_game_file(&file, job, throttle, queue, report)?;
+        if let Ok(meta) = fs::metadata(&file.path) {
+            verified.push(AssetRecord {
+                hash: object.hash,
+                size: meta.len(),
+                verified_at: now_epoch_secs(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Hash one file and download it again if it is missing or corrupt. Returns
+/// whether it was repaired.
+fn verify_game_file(
+    file: &GameFile,
+    job: &Job,
+    throttle: &mut Throttle,
+    queue: &mut DownloadQueue,
+    report: &mut GameFilesReport,
+) -> Result<bool> {
+    job.check_cancelled()?;
+    job.progress(report.checked as u64, None, Some(&file.path.to_string_lossy()));
+    report.checked += 1;
+    match fs::metadata(&file.path) {
+        Err(_) => report.missing += 1,
+        Ok(meta) => {
+            let intact = match &file.sha1 {
+                Some(expected) => match sha1_file_throttled(&file.path, throttle) {
+                    Ok(Ok(actual)) => actual.eq_ignore_ascii_case(expected),
+                    Ok(Err(cancelled)) => return Err(cancelled).context("game file check was cancelled"),
+                    Err(_) => false,
+                },
+                None => meta.len() > 0,
+            };
+            if intact {
+                report.bytes += meta.len();
+                return Ok(false);
+            }
+            report.corrupt += 1;
+            // A failed download shouldn't leave the corrupt file looking usable
+            fs::remove_file(&file.path)
+                .write_context(&file.path, || format!("failed to remove {}", file.path.display()))?;
+        }
+    }
+    if !queue.download(&file.url, &file.path, file.sha1.as_deref())? {
+        return Ok(false);
+    }
+    report.repaired.push(file.path.clone());
+    Ok(true)
+}
+
+/// Run every stage, reporting each one before it starts
+pub fn prepare_with_progress(
+    paths: &Paths,
+    profile: &Profile,
+    account: &LaunchAccount,
+    quick_play: Option<&QuickPlay>,
+    mut on_stage: impl FnMut(PrepareStage),
+) -> Result<LaunchPlan> {
+    if profile.kind == ProfileKind::Server {
+        bail!("{} is a server profile; start it with `shard server start {}`", profile.id, profile.id);
+    }
+    // Files dropped into the instance by hand join the profile; like the
+    // config history below, this never blocks a launch
+    let mut profile = profile.clone();
+    match adopt_unmanaged_files(paths, &mut profile) {
+        Ok(adopted) if !adopted.is_empty() => {
+            eprintln!("added {} files found in the instance to {}", adopted.len(), profile.id);
+        }
+        Ok(_) => {}
+        Err(err) => eprintln!("warning: failed to adopt files added to the instance: {err}"),
+    }
+    let profile = &profile;
+    let instance_dir = materialize_instance(paths, profile)?;
+    // History is a safety net; never block a launch on it
+    if let Err(err) = snapshot_configs(paths, &profile.id) {
+        eprintln!("warning: failed to snapshot configs: {err}");
+    }
+
+    let lock = lock_version_files(paths, &profile.mc_version)?;
+    on_stage(PrepareStage::ResolveVersion);
+    let version = resolve_game_version(paths, profile, &mut on_stage)?;
+    on_stage(PrepareStage::EnsureClient);
+    let client_jars = ensure_client(paths, &version)?;
+    on_stage(PrepareStage::EnsureAssets);
+    let assets = ensure_game_assets(paths, &version)?;
+    on_stage(PrepareStage::EnsureLibraries);
+    let libraries = ensure_game_libraries(paths, &version, &instance_dir, &client_jars)?;
+    drop(lock);
+    on_stage(PrepareStage::BuildArgs);
+    let inputs = LaunchInputs {
+        assets: &assets,
+        libraries: &libraries,
+        account,
+        quick_play,
+    };
+    build_launch_plan(paths, profile, &version, instance_dir, inputs)
+}
+
+pub fn prepare(
+    paths: &Paths,
+    profile: &Profile,
+    account: &LaunchAccount,
+    quick_play: Option<&QuickPlay>,
+) -> Result<LaunchPlan> {
+    // Installing a loader can take minutes; say what is happening
+    prepare_with_progress(paths, profile, account, quick_play, |stage| {
+        if let PrepareStage::InstallLoader(_) = stage {
+            eprintln!("{}...", stage.label().to_lowercase());
+        }
     })
 }
 
-pub fn launch(paths: &Paths, profile: &Profile, account: &LaunchAccount) -> Result<()> {
-    let plan = prepare(paths, profile, account)?;
+pub fn launch(
+    paths: &Paths,
+    profile: &Profile,
+    account: &LaunchAccount,
+    quick_play: Option<&QuickPlay>,
+) -> Result<()> {
+    let mut plan = prepare(paths, profile, account, quick_play)?;
+    refresh_plan_account(paths, &mut plan, account)?;
+
+    // Tunnel lives as long as this function; dropping it stops the process
+    let mut tunnel = start_profile_tunnel(paths, profile)?;
+    if let Some(handle) = tunnel.as_mut() {
+        match handle.wait_for_address(Duration::from_secs(15)) {
+            Some(address) => eprintln!("{} tunnel: {address}", handle.provider().as_str()),
+            None => eprintln!("{} tunnel started (public address not reported yet)", handle.provider().as_str()),
+        }
+    }
+
+    let started_at = now_epoch_secs();
+    let mut child = game_command(&plan).spawn().with_context(|| format!("failed to launch {}", plan.program()))?;
+    plan.check_scheduling(child.id());
+    // Best effort; only needed for `shard ps` and `shard kill`
+    let instance = register_instance(paths, &profile.id, child.id(), started_at).ok();
+    let monitor = ResourceMonitor::start(child.id(), DEFAULT_SAMPLE_INTERVAL, |_| {});
+    let status = child.wait().context("failed to wait for java");
+    let metrics = monitor.stop();
+    if let Some(instance) = &instance {
+        unregister_instance(paths, instance);
+    }
+    let status = status?;
+
+    eprintln!(
+        "session: {}m{:02}s, peak memory {}, avg cpu {:.0}%",
+        metrics.duration_secs / 60,
+        metrics.duration_secs % 60,
+        format_mib(metrics.peak_rss_bytes),
+        metrics.avg_cpu_percent
+    );
+    let _ = record_session(
+        paths,
+        &profile.id,
+        SessionRecord::new(started_at, &metrics, status.code()),
+    );
+
+    // Best effort; a no-op unless crash aggregation is enabled
+    let _ = collect_profile_crashes(paths, &profile.id);
+
+    if let Some(crash) = detect_crash(paths, &profile.id, started_at, status) {
+        bail!("{crash}");
+    }
+
+    Ok(())
+}
+
+/// Start the game and return right away. Playtime, resource metrics and
+/// crash collection need the launcher to wait, so they are skipped, and a
+/// profile tunnel is not started.
+pub fn launch_detached(
+    paths: &Paths,
+    profile: &Profile,
+    account: &LaunchAccount,
+    quick_play: Option<&QuickPlay>,
+) -> Result<RunningInstance> {
+    let mut plan = prepare(paths, profile, account, quick_play)?;
+    refresh_plan_account(paths, &mut plan, account)?;
+    if profile.tunnel.is_some() {
+        eprintln!("warning: the tunnel is not started for detached launches");
+    }
+    let started_at = now_epoch_secs();
+    let child = game_command(&plan)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to launch {}", plan.program()))?;
+    plan.check_scheduling(child.id());
+    register_instance(paths, &profile.id, child.id(), started_at)
+}
 
-    let status = Command::new(&plan.java_exec)
+/// The java invocation for a prepared launch, run through the wrapper if
+/// the profile has one and with its priority, CPU affinity and environment
+pub fn game_command(plan: &LaunchPlan) -> Command {
+    let mut command = match plan.wrapper.split_first() {
+        Some((wrapper, wrapper_args)) => {
+            let mut command = Command::new(wrapper);
+            command.args(wrapper_args).arg(&plan.java_exec);
+            command
+        }
+        None => Command::new(&plan.java_exec),
+    };
+    command
         .args(&plan.jvm_args)
         .arg("-cp")
         .arg(&plan.classpath)
         .arg(&plan.main_class)
         .args(&plan.game_args)
-        .current_dir(&plan.instance_dir)
-        .status()
-        .context("failed to launch java")?;
-
-    if !status.success() {
-        bail!("minecraft exited with status {status}");
-    }
-
-    Ok(())
+        .envs(plan.env.iter().map(|(key, value)| (key, value)))
+        .current_dir(&plan.instance_dir);
+    apply_scheduling(&mut command, plan.priority, &plan.cpu_affinity);
+    command
 }
 
-fn resolve_version_id(paths: &Paths, mc_version: &str, loader: Option<&Loader>, java: Option<&str>) -> Result<String> {
+fn resolve_version_id(
+    paths: &Paths,
+    mc_version: &str,
+    loader: Option<&Loader>,
+    java: Option<&str>,
+    on_stage: &mut dyn FnMut(PrepareStage),
+) -> Result<String> {
     match loader {
         None => Ok(mc_version.to_string()),
-        Some(loader) => match loader.loader_type.as_str() {
-            "fabric" => ensure_fabric_profile(paths, mc_version, &loader.version),
-            "quilt" => ensure_quilt_profile(paths, mc_version, &loader.version),
-            "neoforge" => ensure_neoforge_profile(paths, mc_version, &loader.version, java),
-            "forge" => ensure_forge_profile(paths, mc_version, &loader.version, java),
-            other => bail!("unsupported loader type: {other}"),
+        Some(loader) => match loader.loader_type.parse::<LoaderKind>()? {
+            LoaderKind::Fabric => ensure_fabric_profile(paths, mc_version, &loader.version),
+            LoaderKind::Quilt => ensure_quilt_profile(paths, mc_version, &loader.version),
+            LoaderKind::NeoForge => ensure_neoforge_profile(paths, mc_version, &loader.version, java, on_stage),
+            LoaderKind::Forge => ensure_forge_profile(paths, mc_version, &loader.version, java, on_stage),
+            LoaderKind::OptiFine => ensure_optifine_profile(paths, mc_version, &loader.version, java),
+            LoaderKind::LiteLoader => ensure_liteloader_profile(paths, mc_version, &loader.version),
         },
     }
 }
 
 /// Fetch the latest stable Fabric loader version from the Fabric Meta API
-fn resolve_fabric_latest_version() -> Result<String> {
+pub(crate) fn resolve_fabric_latest_version() -> Result<String> {
     let url = "https://meta.fabricmc.net/v2/versions/loader";
     let json = download_json(url)?;
     let versions = json.as_array().context("fabric loader versions not an array")?;
@@ -187,7 +1080,7 @@ fn ensure_fabric_profile(paths: &Paths, mc_version: &str, loader_version: &str)
         .and_then(|v| v.as_str())
         .context("fabric profile missing id")?;
     let target = paths.minecraft_version_json(id);
-    if !target.exists() {
+    if !is_version_json_valid(&target) {
         if let Some(parent) = target.parent() {
             fs::create_dir_all(parent)
                 .with_context(|| format!("failed to create version dir: {}", parent.display()))?;
@@ -200,7 +1093,7 @@ fn ensure_fabric_profile(paths: &Paths, mc_version: &str, loader_version: &str)
 }
 
 /// Fetch the latest Quilt loader version from the Quilt Meta API
-fn resolve_quilt_latest_version() -> Result<String> {
+pub(crate) fn resolve_quilt_latest_version() -> Result<String> {
     let url = "https://meta.quiltmc.org/v3/versions/loader";
     let json = download_json(url)?;
     let versions = json.as_array().context("quilt loader versions not an array")?;
@@ -230,7 +1123,7 @@ fn ensure_quilt_profile(paths: &Paths, mc_version: &str, loader_version: &str) -
         .and_then(|v| v.as_str())
         .context("quilt profile missing id")?;
     let target = paths.minecraft_version_json(id);
-    if !target.exists() {
+    if !is_version_json_valid(&target) {
         if let Some(parent) = target.parent() {
             fs::create_dir_all(parent)
                 .with_context(|| format!("failed to create version dir: {}", parent.display()))?;
@@ -243,7 +1136,7 @@ fn ensure_quilt_profile(paths: &Paths, mc_version: &str, loader_version: &str) -
 }
 
 /// Fetch the latest NeoForge version for a given Minecraft version
-fn resolve_neoforge_latest_version(mc_version: &str) -> Result<String> {
+pub(crate) fn resolve_neoforge_latest_version(mc_version: &str) -> Result<String> {
     // NeoForge versions are based on MC version without the leading "1." (e.g., 1.21.1 -> 21.1)
     let filter = mc_version.strip_prefix("1.").unwrap_or(mc_version);
     let url = format!(
@@ -262,7 +1155,13 @@ fn resolve_neoforge_latest_version(mc_version: &str) -> Result<String> {
         .context("no neoforge versions found for this minecraft version")
 }
 
-fn ensure_neoforge_profile(paths: &Paths, mc_version: &str, loader_version: &str, java: Option<&str>) -> Result<String> {
+fn ensure_neoforge_profile(
+    paths: &Paths,
+    mc_version: &str,
+    loader_version: &str,
+    java: Option<&str>,
+    on_stage: &mut dyn FnMut(PrepareStage),
+) -> Result<String> {
     // Resolve "latest" to actual version number
     let resolved_version = if loader_version.eq_ignore_ascii_case("latest") {
         resolve_neoforge_latest_version(mc_version)?
@@ -271,27 +1170,20 @@ fn ensure_neoforge_profile(paths: &Paths, mc_version: &str, loader_version: &str
     };
 
     // NeoForge version format: just the loader version (e.g., "21.4.156")
-    // The installer JAR is at: maven.neoforged.net/releases/net/neoforged/neoforge/{version}/neoforge-{version}-installer.jar
     // Note: loader_version should NOT contain the MC version prefix
 
     let id = format!("neoforge-{resolved_version}");
     let target = paths.minecraft_version_json(&id);
 
-    if target.exists() {
+    if is_version_json_valid(&target) {
         return Ok(id);
     }
 
-    // Download installer JAR
-    let installer_url = format!(
-        "https://maven.neoforged.net/releases/net/neoforged/neoforge/{resolved_version}/neoforge-{resolved_version}-installer.jar"
-    );
-
-    let installer_path = paths.cache_downloads.join(format!("neoforge-{resolved_version}-installer.jar"));
-    download_with_sha1(&installer_url, &installer_path, None)?;
+    let installer_path = download_loader_installer(paths, LoaderKind::NeoForge, &resolved_version)?;
 
     // Run the installer to process libraries and generate SRG jars.
     // NeoForge installer creates the version with ID "neoforge-{version}" which matches our format.
-    run_forge_installer(paths, &installer_path, mc_version, java)?;
+    run_forge_installer(paths, &installer_path, mc_version, java, on_stage)?;
 
     // Verify the installer created the expected version
     if !target.exists() {
@@ -302,7 +1194,7 @@ fn ensure_neoforge_profile(paths: &Paths, mc_version: &str, loader_version: &str
 }
 
 /// Fetch the latest Forge version for a given Minecraft version
-fn resolve_forge_latest_version(mc_version: &str) -> Result<String> {
+pub(crate) fn resolve_forge_latest_version(mc_version: &str) -> Result<String> {
     let url = "https://files.minecraftforge.net/maven/net/minecraftforge/forge/promotions_slim.json";
     let json = download_json(url)?;
     let promos = json.get("promos")
@@ -323,47 +1215,69 @@ fn resolve_forge_latest_version(mc_version: &str) -> Result<String> {
     bail!("no forge version found for minecraft {}", mc_version)
 }
 
-fn ensure_forge_profile(paths: &Paths, mc_version: &str, loader_version: &str, java: Option<&str>) -> Result<String> {
-    // Resolve "latest" to actual version number
+/// Full Forge version (`<mc>-<forge>`, e.g. "1.20.1-47.3.0") of a profile's
+/// loader version, resolving "latest"
+pub(crate) fn forge_version_id(mc_version: &str, loader_version: &str) -> Result<String> {
     let resolved_loader = if loader_version.eq_ignore_ascii_case("latest") {
         resolve_forge_latest_version(mc_version)?
     } else {
         loader_version.to_string()
     };
-
-    // Forge version format: MC-ForgeVersion (e.g., "1.20.1-47.3.0")
-    let version_id = if resolved_loader.contains('-') {
-        resolved_loader
+    if resolved_loader.contains('-') {
+        Ok(resolved_loader)
     } else {
-        format!("{mc_version}-{resolved_loader}")
+        Ok(format!("{mc_version}-{resolved_loader}"))
+    }
+}
+
+/// Download the installer jar of a Forge (`<mc>-<forge>`) or NeoForge build
+pub(crate) fn download_loader_installer(paths: &Paths, kind: LoaderKind, version: &str) -> Result<PathBuf> {
+    let url = match kind {
+        LoaderKind::Forge => format!(
+            "https://maven.minecraftforge.net/net/minecraftforge/forge/{version}/forge-{version}-installer.jar"
+        ),
+        LoaderKind::NeoForge => format!(
+            "https://maven.neoforged.net/releases/net/neoforged/neoforge/{version}/neoforge-{version}-installer.jar"
+        ),
+        other => bail!("{} has no installer jar", other.name()),
     };
+    let path = paths.cache_downloads.join(format!("{}-{version}-installer.jar", kind.id()));
+    download_with_sha1(&url, &path, None)?;
+    Ok(path)
+}
+
+fn ensure_forge_profile(
+    paths: &Paths,
+    mc_version: &str,
+    loader_version: &str,
+    java: Option<&str>,
+    on_stage: &mut dyn FnMut(PrepareStage),
+) -> Result<String> {
+    // Forge version format: MC-ForgeVersion (e.g., "1.20.1-47.3.0")
+    let version_id = forge_version_id(mc_version, loader_version)?;
 
     let id = format!("forge-{version_id}");
     let target = paths.minecraft_version_json(&id);
 
-    if target.exists() {
+    if is_version_json_valid(&target) {
         return Ok(id);
     }
 
-    // Download installer JAR
-    let installer_url = format!(
-        "https://maven.minecraftforge.net/net/minecraftforge/forge/{version_id}/forge-{version_id}-installer.jar"
-    );
-
-    let installer_path = paths.cache_downloads.join(format!("forge-{version_id}-installer.jar"));
-    download_with_sha1(&installer_url, &installer_path, None)?;
+    let installer_path = download_loader_installer(paths, LoaderKind::Forge, &version_id)?;
 
     // Run the installer to process libraries and generate SRG jars.
     // The installer creates the version at {mc_version}-forge-{forge_version}
-    // (e.g., "1.20.1-forge-47.4.10").
-    run_forge_installer(paths, &installer_path, mc_version, java)?;
+    // (e.g., "1.20.1-forge-47.4.10"); legacy installers use other ids
+    // (e.g., "1.7.10-Forge10.13.4.1614-1.7.10"), which the headless install
+    // reports.
+    let installed_id = run_forge_installer(paths, &installer_path, mc_version, java, on_stage)?;
 
     // The installer created a version with its own ID format.
     // Read that version and copy it with our ID format.
     // Use splitn(2, '-') to handle legacy Forge version formats with multiple dashes
     // (e.g., "1.7.10-10.13.4.1614-1.7.10" should extract "10.13.4.1614-1.7.10")
     let forge_version = version_id.splitn(2, '-').nth(1).unwrap_or(&version_id);
-    let installer_id = format!("{mc_version}-forge-{forge_version}");
+    let installer_id = installed_id.unwrap_or_else(|| format!("{mc_version}-forge-{forge_version}"));
     let installer_json_path = paths.minecraft_version_json(&installer_id);
 
     let profile_json = fs::read_to_string(&installer_json_path)
@@ -377,33 +1291,345 @@ fn ensure_forge_profile(paths: &Paths, mc_version: &str, loader_version: &str, j
         fs::create_dir_all(parent)
             .with_context(|| format!("failed to create version dir: {}", parent.display()))?;
     }
-    fs::write(&target, serde_json::to_string_pretty(&profile)?).with_context(|| {
-        format!("failed to write forge version json: {}", target.display())
-    })?;
+    fs::write(&target, serde_json::to_string_pretty(&profile)?).with_context(|| {
+        format!("failed to write forge version json: {}", target.display())
+    })?;
+
+    Ok(id)
+}
+
+/// BMCLAPI mirrors OptiFine builds; optifine.net has no API and hides its
+/// downloads behind an ad page
+const OPTIFINE_API: &str = "https://bmclapi2.bangbang93.com/optifine";
+const OPTIFINE_TWEAKER: &str = "optifine.OptiFineTweaker";
+const LITELOADER_VERSIONS_URL: &str = "https://dl.liteloader.com/versions/versions.json";
+const LITELOADER_REPO: &str = "https://dl.liteloader.com/versions/";
+const LAUNCHWRAPPER_MAIN: &str = "net.minecraft.launchwrapper.Launch";
+/// Launchwrapper for OptiFine builds that don't bundle their own
+const LAUNCHWRAPPER_LIBRARY: &str = "net.minecraft:launchwrapper:1.12";
+const MAVEN_CENTRAL: &str = "https://repo1.maven.org/maven2/";
+
+/// An OptiFine build for one Minecraft version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptiFineVersion {
+    #[serde(rename = "mcversion")]
+    pub mc_version: String,
+    /// Edition, e.g. `HD_U`
+    #[serde(rename = "type")]
+    pub edition: String,
+    /// Release, e.g. `I6` or `I7_pre3`
+    pub patch: String,
+    pub filename: String,
+}
+
+impl OptiFineVersion {
+    /// Loader version stored in profiles, e.g. `HD_U_I6`
+    pub fn id(&self) -> String {
+        format!("{}_{}", self.edition, self.patch)
+    }
+
+    pub fn is_preview(&self) -> bool {
+        self.filename.starts_with("preview_") || self.patch.contains("pre")
+    }
+}
+
+/// OptiFine builds for a Minecraft version, newest first
+pub fn list_optifine_versions(mc_version: &str) -> Result<Vec<OptiFineVersion>> {
+    let json = download_json(&format!("{OPTIFINE_API}/{mc_version}"))?;
+    let mut versions: Vec<OptiFineVersion> =
+        serde_json::from_value(json).context("failed to parse optifine versions")?;
+    // The mirror lists builds oldest first
+    versions.reverse();
+    Ok(versions)
+}
+
+/// Newest OptiFine release for a Minecraft version, skipping previews
+pub(crate) fn resolve_optifine_latest_version(mc_version: &str) -> Result<String> {
+    list_optifine_versions(mc_version)?
+        .iter()
+        .find(|v| !v.is_preview())
+        .map(OptiFineVersion::id)
+        .with_context(|| format!("no optifine release found for minecraft {mc_version}"))
+}
+
+/// Install OptiFine as a launchwrapper tweaker on top of vanilla. The
+/// OptiFine jar is patched against the vanilla client (with the patcher it
+/// ships) into a library, and its bundled launchwrapper is extracted.
+fn ensure_optifine_profile(paths: &Paths, mc_version: &str, loader_version: &str, java: Option<&str>) -> Result<String> {
+    let resolved = if loader_version.eq_ignore_ascii_case("latest") {
+        resolve_optifine_latest_version(mc_version)?
+    } else {
+        loader_version.to_string()
+    };
+
+    let id = format!("{mc_version}-OptiFine_{resolved}");
+    let target = paths.minecraft_version_json(&id);
+    let library_name = format!("optifine:OptiFine:{mc_version}_{resolved}");
+    let library_path = maven_path_from_name(&library_name)
+        .map(|path| paths.minecraft_library_path(&path))
+        .context("invalid optifine library name")?;
+    if is_version_json_valid(&target) && library_path.exists() {
+        return Ok(id);
+    }
+
+    let build = list_optifine_versions(mc_version)?
+        .into_iter()
+        .find(|v| v.id() == resolved)
+        .with_context(|| format!("optifine {resolved} not found for minecraft {mc_version}"))?;
+    let jar_path = paths.cache_downloads.join(&build.filename);
+    download_with_sha1(
+        &format!("{OPTIFINE_API}/{mc_version}/{}/{}", build.edition, build.patch),
+        &jar_path,
+        None,
+    )?;
+
+    let vanilla = load_version_json(paths, mc_version)?;
+    let vanilla_jar = ensure_client_jar(paths, &vanilla)?;
+    if let Some(parent) = library_path.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    if jar_has_entry(&jar_path, "optifine/Patcher.class")? {
+        // Newer builds ship binary diffs against the vanilla classes
+        let java = resolve_java(paths, java, mc_version)?;
+        let output = Command::new(&java)
+            .arg("-cp")
+            .arg(&jar_path)
+            .arg("optifine.Patcher")
+            .arg(&vanilla_jar)
+            .arg(&jar_path)
+            .arg(&library_path)
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("failed to run optifine patcher with {java}"))?;
+        if !output.status.success() || !library_path.exists() {
+            bail!(
+                "optifine patcher failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    } else {
+        fs::copy(&jar_path, &library_path)
+            .write_context(&library_path, || format!("failed to copy optifine to {}", library_path.display()))?;
+    }
+
+    let mut libraries = vec![serde_json::json!({ "name": library_name })];
+    match read_jar_entry(&jar_path, "launchwrapper-of.txt") {
+        Ok(version) => {
+            let version = version.trim();
+            let name = format!("optifine:launchwrapper-of:{version}");
+            let path = maven_path_from_name(&name)
+                .map(|path| paths.minecraft_library_path(&path))
+                .context("invalid launchwrapper version in optifine jar")?;
+            extract_jar_entry(&jar_path, &format!("launchwrapper-of-{version}.jar"), &path)?;
+            libraries.push(serde_json::json!({ "name": name }));
+        }
+        Err(_) => libraries.push(serde_json::json!({ "name": LAUNCHWRAPPER_LIBRARY })),
+    }
+
+    let mut profile = serde_json::json!({
+        "id": id,
+        "inheritsFrom": mc_version,
+        "type": vanilla.version_type.as_deref().unwrap_or("release"),
+        "mainClass": LAUNCHWRAPPER_MAIN,
+        "libraries": libraries,
+    });
+    match &vanilla.minecraft_arguments {
+        Some(args) => profile["minecraftArguments"] = format!("{args} --tweakClass {OPTIFINE_TWEAKER}").into(),
+        None => profile["arguments"] = serde_json::json!({ "game": ["--tweakClass", OPTIFINE_TWEAKER] }),
+    }
+    write_loader_version_json(&target, &profile)?;
+    Ok(id)
+}
+
+#[derive(Deserialize)]
+struct LiteLoaderManifest {
+    versions: HashMap<String, LiteLoaderMcVersion>,
+}
+
+#[derive(Deserialize)]
+struct LiteLoaderMcVersion {
+    #[serde(default)]
+    artefacts: Option<LiteLoaderArtefacts>,
+}
+
+#[derive(Deserialize)]
+struct LiteLoaderArtefacts {
+    #[serde(rename = "com.mumfrey:liteloader")]
+    liteloader: HashMap<String, LiteLoaderBuild>,
+}
+
+#[derive(Clone, Deserialize)]
+struct LiteLoaderBuild {
+    version: String,
+    #[serde(rename = "tweakClass")]
+    tweak_class: String,
+    #[serde(default)]
+    libraries: Vec<Value>,
+    #[serde(default)]
+    timestamp: String,
+}
+
+/// LiteLoader release builds for a Minecraft version, newest first
+fn liteloader_builds(mc_version: &str) -> Result<Vec<LiteLoaderBuild>> {
+    let manifest: LiteLoaderManifest =
+        serde_json::from_value(download_json(LITELOADER_VERSIONS_URL)?).context("failed to parse liteloader versions")?;
+    let mut builds: Vec<LiteLoaderBuild> = manifest
+        .versions
+        .get(mc_version)
+        .and_then(|v| v.artefacts.as_ref())
+        .map(|artefacts| {
+            artefacts
+                .liteloader
+                .iter()
+                // `latest` duplicates one of the numbered builds
+                .filter(|(key, _)| key.as_str() != "latest")
+                .map(|(_, build)| build.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    builds.sort_by_key(|build| std::cmp::Reverse(build.timestamp.parse::<u64>().unwrap_or(0)));
+    Ok(builds)
+}
+
+/// LiteLoader versions for a Minecraft version, newest first
+pub fn list_liteloader_versions(mc_version: &str) -> Result<Vec<String>> {
+    let mut versions: Vec<String> = Vec::new();
+    for build in liteloader_builds(mc_version)? {
+        if !versions.contains(&build.version) {
+            versions.push(build.version);
+        }
+    }
+    Ok(versions)
+}
+
+pub(crate) fn resolve_liteloader_latest_version(mc_version: &str) -> Result<String> {
+    liteloader_builds(mc_version)?
+        .into_iter()
+        .next()
+        .map(|build| build.version)
+        .with_context(|| format!("no liteloader version found for minecraft {mc_version}"))
+}
+
+/// Install LiteLoader as a launchwrapper tweaker on top of vanilla
+fn ensure_liteloader_profile(paths: &Paths, mc_version: &str, loader_version: &str) -> Result<String> {
+    let id = format!("{mc_version}-LiteLoader{mc_version}");
+    let target = paths.minecraft_version_json(&id);
+    let builds = match liteloader_builds(mc_version) {
+        Ok(builds) => builds,
+        // Offline with LiteLoader already installed
+        Err(_) if is_version_json_valid(&target) => return Ok(id),
+        Err(err) => return Err(err),
+    };
+    let build = if loader_version.eq_ignore_ascii_case("latest") {
+        builds.into_iter().next()
+    } else {
+        builds.into_iter().find(|build| build.version == loader_version)
+    }
+    .with_context(|| format!("liteloader {loader_version} not found for minecraft {mc_version}"))?;
+
+    let vanilla = load_version_json(paths, mc_version)?;
+    let Some(args) = &vanilla.minecraft_arguments else {
+        bail!("liteloader supports minecraft up to 1.12.2, not {mc_version}");
+    };
+
+    let mut libraries = vec![serde_json::json!({
+        "name": format!("com.mumfrey:liteloader:{}", build.version),
+        "url": LITELOADER_REPO,
+    })];
+    for mut library in build.libraries {
+        // Launchwrapper is on Mojang's server; the rest comes from Maven Central
+        let is_mojang = library
+            .get("name")
+            .and_then(|name| name.as_str())
+            .is_some_and(|name| name.starts_with("net.minecraft:"));
+        if library.get("url").is_none() && !is_mojang {
+            library["url"] = MAVEN_CENTRAL.into();
+        }
+        libraries.push(library);
+    }
 
+    let profile = serde_json::json!({
+        "id": id,
+        "inheritsFrom": mc_version,
+        "type": vanilla.version_type.as_deref().unwrap_or("release"),
+        "mainClass": LAUNCHWRAPPER_MAIN,
+        "minecraftArguments": format!("{args} --tweakClass {}", build.tweak_class),
+        "libraries": libraries,
+    });
+    write_loader_version_json(&target, &profile)?;
     Ok(id)
 }
 
-fn extract_version_json_from_jar(jar_path: &Path, json_name: &str) -> Result<String> {
+fn write_loader_version_json(target: &Path, profile: &Value) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create version dir: {}", parent.display()))?;
+    }
+    fs::write(target, serde_json::to_string_pretty(profile)?)
+        .write_context(target, || format!("failed to write version json: {}", target.display()))
+}
+
+fn open_jar(jar_path: &Path) -> Result<zip::ZipArchive<fs::File>> {
     let file = fs::File::open(jar_path)
-        .with_context(|| format!("failed to open installer jar: {}", jar_path.display()))?;
-    let mut archive = zip::ZipArchive::new(file)
-        .with_context(|| format!("failed to read installer jar: {}", jar_path.display()))?;
+        .with_context(|| format!("failed to open jar: {}", jar_path.display()))?;
+    zip::ZipArchive::new(file).with_context(|| format!("failed to read jar: {}", jar_path.display()))
+}
+
+fn jar_has_entry(jar_path: &Path, name: &str) -> Result<bool> {
+    Ok(open_jar(jar_path)?.by_name(name).is_ok())
+}
+
+fn extract_jar_entry(jar_path: &Path, name: &str, dest: &Path) -> Result<()> {
+    let mut archive = open_jar(jar_path)?;
+    let mut entry = archive
+        .by_name(name)
+        .with_context(|| format!("{name} not found in {}", jar_path.display()))?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    let mut out = fs::File::create(dest).write_context(dest, || format!("failed to create {}", dest.display()))?;
+    std::io::copy(&mut entry, &mut out).write_context(dest, || format!("failed to extract {name} to {}", dest.display()))?;
+    Ok(())
+}
 
-    let mut version_json = archive.by_name(json_name)
-        .with_context(|| format!("{} not found in installer jar", json_name))?;
+pub(crate) fn read_jar_entry(jar_path: &Path, name: &str) -> Result<String> {
+    let mut archive = open_jar(jar_path)?;
+    let mut entry = archive
+        .by_name(name)
+        .with_context(|| format!("{name} not found in {}", jar_path.display()))?;
 
     let mut contents = String::new();
-    version_json.read_to_string(&mut contents)
-        .with_context(|| format!("failed to read {} from installer", json_name))?;
+    entry
+        .read_to_string(&mut contents)
+        .with_context(|| format!("failed to read {name} from {}", jar_path.display()))?;
 
     Ok(contents)
 }
 
 /// Run the Forge/NeoForge installer to process libraries and generate SRG jars.
 /// The installer creates the necessary processed artifacts that aren't available via Maven.
-fn run_forge_installer(paths: &Paths, installer_path: &Path, mc_version: &str, java: Option<&str>) -> Result<()> {
-    let java = resolve_java(java, mc_version);
+///
+/// The installer profile is processed headlessly first; the installer jar is
+/// only run when that fails. Returns the id of the installed version when the
+/// headless install created it.
+fn run_forge_installer(
+    paths: &Paths,
+    installer_path: &Path,
+    mc_version: &str,
+    java: Option<&str>,
+    on_stage: &mut dyn FnMut(PrepareStage),
+) -> Result<Option<String>> {
+    let java = resolve_java(paths, java, mc_version)?;
+
+    // Processors patch the vanilla client jar
+    let vanilla = load_version_json(paths, mc_version)?;
+    ensure_client_jar(paths, &vanilla)?;
+    match install_headless(paths, &java, installer_path, mc_version, &mut |milestone| {
+        on_stage(PrepareStage::InstallLoader(milestone))
+    }) {
+        Ok(id) => return Ok(Some(id)),
+        Err(err) => eprintln!("warning: headless loader install failed, running the installer instead: {err:#}"),
+    }
 
     // Derive minecraft_dir from minecraft_versions path
     let minecraft_dir = paths
@@ -420,27 +1646,19 @@ fn run_forge_installer(paths: &Paths, installer_path: &Path, mc_version: &str, j
             .context("failed to create launcher_profiles.json for Forge installer")?;
     }
 
-    eprintln!(
-        "Running installer to process libraries (this may take a minute)..."
-    );
-
     // Run the installer with the working directory set to cache_downloads.
     // This ensures the installer can write its log file (installer.jar.log) without
     // permission issues, especially on Windows.
-    let status = Command::new(&java)
-        .arg("-jar")
-        .arg(installer_path)
-        .arg("--installClient")
-        .arg(minecraft_dir)
-        .current_dir(&paths.cache_downloads)
-        .status()
-        .context("failed to run forge installer")?;
-
-    if !status.success() {
-        bail!("forge installer failed with status {status}");
-    }
-
-    Ok(())
+    run_installer(
+        &java,
+        installer_path,
+        InstallTarget::Client(minecraft_dir),
+        &paths.cache_downloads,
+        &paths.installer_log(installer_path),
+        INSTALLER_TIMEOUT,
+        &mut |milestone| on_stage(PrepareStage::InstallLoader(milestone)),
+    )?;
+    Ok(None)
 }
 
 #[derive(Clone)]
@@ -477,11 +1695,11 @@ fn resolve_version(paths: &Paths, id: &str) -> Result<ResolvedVersion> {
 fn load_version_json(paths: &Paths, id: &str) -> Result<VersionJson> {
     let path = paths.minecraft_version_json(id);
     if path.exists() {
-        let data = fs::read_to_string(&path)
-            .with_context(|| format!("failed to read version json: {}", path.display()))?;
-        let json: VersionJson = serde_json::from_str(&data)
-            .with_context(|| format!("failed to parse version json: {}", path.display()))?;
-        return Ok(json);
+        match read_version_json(&path) {
+            Ok(json) => return Ok(json),
+            // A truncated or corrupt cache is replaced from the manifest
+            Err(err) => eprintln!("warning: {err:#}; downloading it again"),
+        }
     }
 
     let manifest = load_version_manifest(paths)?;
@@ -490,23 +1708,158 @@ fn load_version_json(paths: &Paths, id: &str) -> Result<VersionJson> {
         .iter()
         .find(|v| v.id == id)
         .with_context(|| format!("version not found in manifest: {id}"))?;
+    download_version_json(paths, entry)
+}
+
+fn read_version_json(path: &Path) -> Result<VersionJson> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("failed to read version json: {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| format!("failed to parse version json: {}", path.display()))
+}
+
+/// Whether a cached version JSON exists and parses
+fn is_version_json_valid(path: &Path) -> bool {
+    path.exists() && read_version_json(path).is_ok()
+}
 
+fn download_version_json(paths: &Paths, entry: &VersionEntry) -> Result<VersionJson> {
+    let path = paths.minecraft_version_json(&entry.id);
     let data = download_text(&entry.url)?;
+    if let Some(expected) = &entry.sha1 {
+        let actual = format!("{:x}", Sha1::digest(data.as_bytes()));
+        if !actual.eq_ignore_ascii_case(expected) {
+            bail!("sha1 mismatch for version json {}: expected {expected}, got {actual}", entry.id);
+        }
+    }
+    let json: VersionJson = serde_json::from_str(&data)
+        .with_context(|| format!("failed to parse version json: {}", entry.id))?;
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
-            .with_context(|| format!("failed to create version dir: {}", parent.display()))?;
+            .write_context(parent, || format!("failed to create version dir: {}", parent.display()))?;
     }
     fs::write(&path, &data)
-        .with_context(|| format!("failed to write version json: {}", path.display()))?;
-
-    let json: VersionJson = serde_json::from_str(&data)
-        .with_context(|| format!("failed to parse version json: {}", path.display()))?;
+        .write_context(&path, || format!("failed to write version json: {}", path.display()))?;
     Ok(json)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionRefreshAction {
+    /// Fetched again from Mojang or the loader's meta API
+    Redownloaded,
+    /// Installer-created JSON that still parses; reinstalling is left to the user
+    Kept,
+    /// Corrupt installer-created JSON; the loader is reinstalled on next launch
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionRefresh {
+    pub id: String,
+    pub action: VersionRefreshAction,
+}
+
+/// Ids of the version JSONs under `versions/`
+pub fn list_installed_versions(paths: &Paths) -> Result<Vec<String>> {
+    let Ok(entries) = fs::read_dir(&paths.minecraft_versions) else {
+        return Ok(Vec::new());
+    };
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|id| paths.minecraft_version_json(id).exists())
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+/// SHA-1s the installed version JSONs give for library artifacts and asset
+/// indexes, keyed by path relative to `libraries/` or `assets/indexes/`.
+/// Files without a published SHA-1 (Maven-only loader libraries) are absent.
+pub(crate) fn listed_file_sha1s(paths: &Paths) -> Result<(HashMap<String, String>, HashMap<String, String>)> {
+    let mut libraries = HashMap::new();
+    let mut indexes = HashMap::new();
+    for id in list_installed_versions(paths)? {
+        // Unreadable JSONs are reported by launch and `version refresh`, not here
+        let Ok(json) = read_version_json(&paths.minecraft_version_json(&id)) else {
+            continue;
+        };
+        if let Some(index) = json.asset_index {
+            indexes.insert(format!("{}.json", index.id), index.sha1);
+        }
+        for lib in json.libraries {
+            let Some(downloads) = lib.downloads else {
+                continue;
+            };
+            let classifiers = downloads.classifiers.into_iter().flat_map(|c| c.into_values());
+            for artifact in downloads.artifact.into_iter().chain(classifiers) {
+                libraries.insert(artifact.path, artifact.sha1);
+            }
+        }
+    }
+    Ok((libraries, indexes))
+}
+
+/// Re-fetch the version manifest and the given version JSONs.
+/// Fabric and Quilt profiles are re-fetched from their meta APIs; JSONs
+/// written by the Forge/NeoForge installer can't be re-fetched and are only
+/// removed when they no longer parse.
+pub fn refresh_version_jsons(paths: &Paths, ids: &[String]) -> Result<Vec<VersionRefresh>> {
+    // A zero TTL replaces the cached manifest, keeping it if the fetch fails
+    let manifest = load_version_manifest_within(paths, 0)?;
+
+    let mut refreshed = Vec::new();
+    for id in ids {
+        let path = paths.minecraft_version_json(id);
+        let action = if let Some(entry) = manifest.versions.iter().find(|v| &v.id == id) {
+            download_version_json(paths, entry)?;
+            VersionRefreshAction::Redownloaded
+        } else if !path.exists() {
+            bail!("version not installed: {id}");
+        } else if let Some((loader, mc_version)) = id.strip_prefix("fabric-loader-").and_then(|r| r.split_once('-')) {
+            fs::remove_file(&path).with_context(|| format!("failed to remove {}", path.display()))?;
+            ensure_fabric_profile(paths, mc_version, loader)?;
+            VersionRefreshAction::Redownloaded
+        } else if let Some((loader, mc_version)) = id.strip_prefix("quilt-loader-").and_then(|r| r.split_once('-')) {
+            fs::remove_file(&path).with_context(|| format!("failed to remove {}", path.display()))?;
+            ensure_quilt_profile(paths, mc_version, loader)?;
+            VersionRefreshAction::Redownloaded
+        } else if is_version_json_valid(&path) {
+            VersionRefreshAction::Kept
+        } else {
+            fs::remove_file(&path).with_context(|| format!("failed to remove {}", path.display()))?;
+            VersionRefreshAction::Removed
+        };
+        refreshed.push(VersionRefresh { id: id.clone(), action });
+    }
+    Ok(refreshed)
+}
+
+/// Resolve the current latest release (or snapshot) id from the version manifest
+pub fn resolve_latest_version(paths: &Paths, snapshot: bool) -> Result<String> {
+    let manifest = load_version_manifest(paths)?;
+    let latest = manifest
+        .latest
+        .context("version manifest has no latest entry")?;
+    Ok(if snapshot { latest.snapshot } else { latest.release })
+}
+
+/// Latest snapshot id, refetching the manifest when the cached copy is older
+/// than `max_age`
+pub fn resolve_latest_snapshot(paths: &Paths, max_age: Duration) -> Result<String> {
+    let manifest = load_version_manifest_within(paths, max_age.as_secs())?;
+    let latest = manifest
+        .latest
+        .context("version manifest has no latest entry")?;
+    Ok(latest.snapshot)
+}
+
 fn load_version_manifest(paths: &Paths) -> Result<VersionManifest> {
     const CACHE_TTL_SECS: u64 = 24 * 60 * 60; // 24 hours
+    load_version_manifest_within(paths, CACHE_TTL_SECS)
+}
 
+pub(crate) fn load_version_manifest_within(paths: &Paths, ttl_secs: u64) -> Result<VersionManifest> {
     let cache_path = paths.cache_manifest("version_manifest_v2.json");
     if cache_path.exists() {
         // Check if cache is still valid (within TTL)
@@ -519,7 +1872,7 @@ fn load_version_manifest(paths: &Paths) -> Result<VersionManifest> {
                     .duration_since(modified)
                     .ok()
             })
-            .map(|age| age.as_secs() < CACHE_TTL_SECS)
+            .map(|age| age.as_secs() < ttl_secs)
             .unwrap_or(false);
 
         if cache_valid {
@@ -538,9 +1891,9 @@ fn load_version_manifest(paths: &Paths) -> Result<VersionManifest> {
     let data = download_text(VERSION_MANIFEST_URL)?;
     if let Some(parent) = cache_path.parent() {
         fs::create_dir_all(parent)
-            .with_context(|| format!("failed to create manifest dir: {}", parent.display()))?;
+            .write_context(parent, || format!("failed to create manifest dir: {}", parent.display()))?;
     }
-    fs::write(&cache_path, &data).with_context(|| {
+    fs::write(&cache_path, &data).write_context(&cache_path, || {
         format!(
             "failed to write version manifest cache: {}",
             cache_path.display()
@@ -566,7 +1919,153 @@ fn ensure_client_jar(paths: &Paths, version: &VersionJson) -> Result<PathBuf> {
     Ok(jar_path)
 }
 
-fn ensure_assets(paths: &Paths, version: &VersionJson) -> Result<String> {
+/// Download the vanilla dedicated server jar of a Minecraft version
+pub(crate) fn ensure_server_jar(paths: &Paths, mc_version: &str) -> Result<PathBuf> {
+    let version = load_version_json(paths, mc_version)?;
+    let server = version
+        .downloads
+        .as_ref()
+        .and_then(|downloads| downloads.server.as_ref())
+        .with_context(|| format!("minecraft {mc_version} has no dedicated server download"))?;
+    let jar_path = paths.minecraft_server_jar(mc_version);
+    download_with_sha1(&server.url, &jar_path, Some(&server.sha1))?;
+    Ok(jar_path)
+}
+
+/// Fabric or Quilt on a dedicated server: the loader's main class and
+/// libraries, which run the vanilla server jar named by `game_jar_property`
+pub(crate) struct ServerLoader {
+    pub main_class: String,
+    pub classpath: Vec<PathBuf>,
+    pub game_jar_property: &'static str,
+}
+
+/// Download the server libraries of a Fabric or Quilt loader version
+pub(crate) fn ensure_server_loader(
+    paths: &Paths,
+    kind: LoaderKind,
+    mc_version: &str,
+    loader_version: &str,
+) -> Result<ServerLoader> {
+    let (loader_version, meta, game_jar_property) = match kind {
+        LoaderKind::Fabric if loader_version.eq_ignore_ascii_case("latest") => {
+            (resolve_fabric_latest_version()?, "https://meta.fabricmc.net/v2", "fabric.gameJarPath")
+        }
+        LoaderKind::Fabric => (loader_version.to_string(), "https://meta.fabricmc.net/v2", "fabric.gameJarPath"),
+        LoaderKind::Quilt if loader_version.eq_ignore_ascii_case("latest") => {
+            (resolve_quilt_latest_version()?, "https://meta.quiltmc.org/v3", "loader.gameJarPath")
+        }
+        LoaderKind::Quilt => (loader_version.to_string(), "https://meta.quiltmc.org/v3", "loader.gameJarPath"),
+        other => bail!("{} servers are set up by its installer", other.name()),
+    };
+    // Not under versions/, where it would pass for an installed client version
+    let path = paths.cache_manifest(&format!("{}-server-{mc_version}-{loader_version}.json", kind.id()));
+    let version = if is_version_json_valid(&path) {
+        read_version_json(&path)?
+    } else {
+        let url = format!("{meta}/versions/loader/{mc_version}/{loader_version}/server/json");
+        let data = download_text(&url)?;
+        let version: VersionJson = serde_json::from_str(&data)
+            .with_context(|| format!("failed to parse {} server profile", kind.name()))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+        }
+        fs::write(&path, &data).write_context(&path, || format!("failed to write {}", path.display()))?;
+        version
+    };
+
+    let mut queue = DownloadQueue::load(paths)?;
+    let mut classpath = Vec::new();
+    for library in &version.libraries {
+        let Some(maven_path) = maven_path_from_name(&library.name) else {
+            continue;
+        };
+        let url = join_url(library.url.as_deref().unwrap_or(LIBRARIES_BASE), &maven_path);
+        let lib_path = paths.minecraft_library_path(&maven_path);
+        queue.download(&url, &lib_path, None)?;
+        classpath.push(lib_path);
+    }
+    queue.save()?;
+    if let Some(first) = queue.failed.first() {
+        bail!("failed to download {}: {}", first.path.display(), first.error);
+    }
+    Ok(ServerLoader {
+        main_class: version
+            .main_class
+            .with_context(|| format!("{} server profile has no main class", kind.name()))?,
+        classpath,
+        game_jar_property,
+    })
+}
+
+/// Asset index that was downloaded, with the layout flags of legacy indexes
+struct EnsuredAssetIndex {
+    id: String,
+    is_virtual: bool,
+    map_to_resources: bool,
+    /// File name -> object hash
+    files: Vec<(String, String)>,
+}
+
+impl EnsuredAssetIndex {
+    fn new(id: &str, index: &AssetIndex) -> Self {
+        Self {
+            id: id.to_string(),
+            is_virtual: index.is_virtual,
+            map_to_resources: index.map_to_resources,
+            files: index
+                .objects
+                .iter()
+                .map(|(name, object)| (name.clone(), object.hash.clone()))
+                .collect(),
+        }
+    }
+}
+
+fn read_asset_index(path: &Path) -> Result<AssetIndex> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("failed to read asset index: {}", path.display()))?;
+    serde_json::from_str(&data).context("failed to parse asset index")
+}
+
+fn load_asset_index(paths: &Paths, id: &str) -> Result<EnsuredAssetIndex> {
+    let index = read_asset_index(&paths.minecraft_asset_index(id))?;
+    Ok(EnsuredAssetIndex::new(id, &index))
+}
+
+/// Place the objects of a legacy index under their file names in `dir`,
+/// hard-linked from the object store where possible. Files already in place
+/// with the right size are kept.
+fn layout_legacy_assets(paths: &Paths, index: &EnsuredAssetIndex, dir: &Path) -> Result<()> {
+    for (name, hash) in &index.files {
+        let object = paths.minecraft_asset_object(hash);
+        // Missing objects were queued for a retry when downloading
+        let Ok(meta) = fs::metadata(&object) else {
+            continue;
+        };
+        // Names come from the index; keep them inside the target directory
+        if Path::new(name)
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        {
+            continue;
+        }
+        let target = dir.join(name);
+        if fs::metadata(&target).is_ok_and(|m| m.len() == meta.len()) {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+        }
+        let _ = fs::remove_file(&target);
+        if fs::hard_link(&object, &target).is_err() {
+            fs::copy(&object, &target).write_context(&target, || format!("failed to copy asset: {}", target.display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn ensure_assets(paths: &Paths, version: &VersionJson) -> Result<EnsuredAssetIndex> {
     let asset_index = version
         .asset_index
         .as_ref()
@@ -575,16 +2074,49 @@ fn ensure_assets(paths: &Paths, version: &VersionJson) -> Result<String> {
     let index_path = paths.minecraft_asset_index(&asset_index.id);
     download_with_sha1(&asset_index.url, &index_path, Some(&asset_index.sha1))?;
 
-    let data = fs::read_to_string(&index_path)
-        .with_context(|| format!("failed to read asset index: {}", index_path.display()))?;
-    let index: AssetIndex = serde_json::from_str(&data).context("failed to parse asset index")?;
+    let index = read_asset_index(&index_path)?;
+    let ensured = EnsuredAssetIndex::new(&asset_index.id, &index);
+
+    // Objects recorded with a matching size were verified when downloaded;
+    // only new or changed ones are hashed again
+    let db = AssetDb::from_paths(paths)?;
+    let known = db.sizes()?;
+    let mut fetched = Vec::new();
+    let mut queue = DownloadQueue::load(paths)?;
+    let result = fetch_asset_objects(paths, index.objects.into_values(), &known, &mut fetched, &mut queue);
+    // Keep track of what did download even if a later object failed
+    db.record(&fetched)?;
+    queue.save()?;
+    result?;
+    // The game runs without them (missing sounds or textures at worst)
+    if !queue.failed.is_empty() {
+        eprintln!(
+            "warning: {} asset files failed to download; run `shard downloads retry` to fetch them again",
+            queue.failed.len()
+        );
+    }
+
+    Ok(ensured)
+}
 
-    for (name, object) in index.objects {
-        let _ = name; // reserved for future logging
+fn fetch_asset_objects(
+    paths: &Paths,
+    objects: impl Iterator<Item = AssetObject>,
+    known: &HashMap<String, u64>,
+    fetched: &mut Vec<AssetRecord>,
+    queue: &mut DownloadQueue,
+) -> Result<()> {
+    for object in objects {
         if object.hash.len() < 2 {
             continue;
         }
         let object_path = paths.minecraft_asset_object(&object.hash);
+        if let Some(&size) = known.get(&object.hash)
+            && object.size.is_none_or(|expected| expected == size)
+            && fs::metadata(&object_path).is_ok_and(|m| m.len() == size)
+        {
+            continue;
+        }
         let url = object.url.clone().unwrap_or_else(|| {
             format!(
                 "https://resources.download.minecraft.net/{}/{}",
@@ -592,10 +2124,19 @@ fn ensure_assets(paths: &Paths, version: &VersionJson) -> Result<String> {
                 object.hash
             )
         });
-        download_with_sha1(&url, &object_path, Some(&object.hash))?;
+        if !queue.download(&url, &object_path, Some(&object.hash))? {
+            continue;
+        }
+        let size = fs::metadata(&object_path)
+            .map(|m| m.len())
+            .with_context(|| format!("failed to stat asset: {}", object_path.display()))?;
+        fetched.push(AssetRecord {
+            hash: object.hash,
+            size,
+            verified_at: now_epoch_secs(),
+        });
     }
-
-    Ok(asset_index.id.clone())
+    Ok(())
 }
 
 fn ensure_libraries(
@@ -603,21 +2144,20 @@ fn ensure_libraries(
     version: &VersionJson,
     instance_dir: &Path,
     client_jars: &[PathBuf],
-) -> Result<(String, PathBuf)> {
+) -> Result<GameLibraries> {
     let mut classpath = Vec::new();
-    let natives_dir = instance_dir.join("natives");
-    if natives_dir.exists() {
-        fs::remove_dir_all(&natives_dir).with_context(|| {
-            format!(
-                "failed to clear natives directory: {}",
-                natives_dir.display()
-            )
-        })?;
+    let host = host_libraries(paths, version);
+    if host.java_arch.is_none() && host_arch() == "aarch64" {
+        eprintln!("warning: Minecraft {} has no LWJGL natives for this ARM machine and will likely fail to start", version.id);
     }
-    fs::create_dir_all(&natives_dir)
-        .with_context(|| format!("failed to create natives dir: {}", natives_dir.display()))?;
-
-    for library in &version.libraries {
+    let natives_dir = paths.minecraft_natives_dir(&host.natives_id);
+    let natives_cached = natives_dir.join(NATIVES_COMPLETE).exists();
+    let mut native_jars = Vec::new();
+
+    // Failed libraries are queued so the others still download; the launch
+    // fails below until they are fetched
+    let mut queue = DownloadQueue::load(paths)?;
+    for library in &host.libraries {
         if !library_allowed(library) {
             continue;
         }
@@ -628,17 +2168,18 @@ fn ensure_libraries(
             .and_then(|downloads| downloads.artifact.as_ref())
         {
             let lib_path = paths.minecraft_library_path(&artifact.path);
-            download_with_sha1(&artifact.url, &lib_path, Some(&artifact.sha1))?;
+            queue.download(&artifact.url, &lib_path, Some(&artifact.sha1))?;
             classpath.push(lib_path);
         } else if let Some(path) = maven_path_from_name(&library.name) {
             let base_url = library.url.as_deref().unwrap_or(LIBRARIES_BASE);
             let url = join_url(base_url, &path);
             let lib_path = paths.minecraft_library_path(&path);
-            download_with_sha1(&url, &lib_path, None)?;
+            queue.download(&url, &lib_path, None)?;
             classpath.push(lib_path);
         }
 
-        if let Some(natives) = library.natives.as_ref()
+        if !natives_cached
+            && let Some(natives) = library.natives.as_ref()
             && let Some(classifier) = natives.get(&os_key()) {
                 let classifier = classifier.replace("${arch}", arch_marker());
                 if let Some(native_artifact) = library
@@ -648,24 +2189,41 @@ fn ensure_libraries(
                     .and_then(|classifiers| classifiers.get(&classifier))
                 {
                     let jar_path = paths.minecraft_library_path(&native_artifact.path);
-                    download_with_sha1(
-                        &native_artifact.url,
-                        &jar_path,
-                        Some(&native_artifact.sha1),
-                    )?;
-                    extract_natives(&jar_path, &natives_dir, library.extract.as_ref())?;
+                    if queue.download(&native_artifact.url, &jar_path, Some(&native_artifact.sha1))? {
+                        native_jars.push((jar_path, library.extract.as_ref()));
+                    }
                 } else if let Some(path) =
                     maven_path_from_name_with_classifier(&library.name, &classifier)
                 {
                     let base_url = library.url.as_deref().unwrap_or(LIBRARIES_BASE);
                     let url = join_url(base_url, &path);
                     let jar_path = paths.minecraft_library_path(&path);
-                    download_with_sha1(&url, &jar_path, None)?;
-                    extract_natives(&jar_path, &natives_dir, library.extract.as_ref())?;
+                    if queue.download(&url, &jar_path, None)? {
+                        native_jars.push((jar_path, library.extract.as_ref()));
+                    }
                 }
             }
     }
 
+    queue.save()?;
+    if let Some(first) = queue.failed.first() {
+        bail!(
+            "{} libraries failed to download (run `shard downloads retry`); {}: {}",
+            queue.failed.len(),
+            first.path.display(),
+            first.error
+        );
+    }
+
+    if !natives_cached {
+        populate_natives(&natives_dir, &native_jars)?;
+    }
+    // Natives used to be extracted into every instance
+    let instance_natives = instance_dir.join("natives");
+    if instance_natives.is_dir() {
+        let _ = fs::remove_dir_all(&instance_natives);
+    }
+
     for jar in client_jars {
         classpath.push(jar.to_path_buf());
     }
@@ -675,35 +2233,181 @@ fn ensure_libraries(
         .map(|p| normalize_path_separator(&p.to_string_lossy()))
         .collect::<Vec<_>>()
         .join(sep);
-    Ok((classpath, natives_dir))
+    Ok(GameLibraries {
+        classpath,
+        natives_dir,
+        java_arch: host.java_arch,
+    })
+}
+
+/// A version's libraries as this machine loads them
+struct HostLibraries {
+    libraries: Vec<Library>,
+    /// Name of the natives dir: the version id, marked when LWJGL was swapped
+    /// so natives extracted for the version's own LWJGL aren't picked up
+    natives_id: String,
+    java_arch: Option<&'static str>,
+}
+
+/// On ARM machines, versions whose LWJGL 3 has no natives for the machine
+/// get [`ARM_LWJGL_VERSION`] instead (or just the ARM natives of their own
+/// release, if it is newer) unless `keep_lwjgl_on_arm` is set. LWJGL 2 has
+/// no ARM builds at all; on Apple Silicon those versions keep their x86_64
+/// natives and run on an x86_64 Java under Rosetta.
+fn host_libraries(paths: &Paths, version: &VersionJson) -> HostLibraries {
+    let unchanged = |java_arch| HostLibraries {
+        libraries: version.libraries.clone(),
+        natives_id: version.id.clone(),
+        java_arch,
+    };
+    let Some(classifier) = arm_lwjgl_classifier() else {
+        return unchanged(None);
+    };
+    let has_arm_natives = version
+        .libraries
+        .iter()
+        .any(|library| library.name.ends_with(&format!(":{classifier}")) && library_allowed(library));
+    if has_arm_natives {
+        return unchanged(Some("aarch64"));
+    }
+    let keep = load_config(paths).is_ok_and(|config| config.keep_lwjgl_on_arm);
+    match arm_lwjgl_libraries(&version.libraries, classifier) {
+        Some(libraries) if !keep => HostLibraries {
+            libraries,
+            natives_id: format!("{}-{classifier}", version.id),
+            java_arch: Some("aarch64"),
+        },
+        _ if cfg!(target_os = "macos") => unchanged(Some("x86_64")),
+        _ => unchanged(None),
+    }
+}
+
+/// Classifier of LWJGL 3's natives for this machine, if it is ARM
+fn arm_lwjgl_classifier() -> Option<&'static str> {
+    if host_arch() != "aarch64" {
+        return None;
+    }
+    match std::env::consts::OS {
+        "macos" => Some("natives-macos-arm64"),
+        "linux" => Some("natives-linux-arm64"),
+        "windows" => Some("natives-windows-arm64"),
+        _ => None,
+    }
+}
+
+/// `libraries` with LWJGL 3 moved to a release that has `classifier`
+/// natives, downloaded from Maven Central and loaded from the classpath like
+/// newer versions do; `None` if the version doesn't use LWJGL 3
+fn arm_lwjgl_libraries(libraries: &[Library], classifier: &str) -> Option<Vec<Library>> {
+    let mut swapped = Vec::with_capacity(libraries.len());
+    let mut seen = std::collections::HashSet::new();
+    for library in libraries {
+        if !library.name.starts_with("org.lwjgl:") {
+            swapped.push(library.clone());
+            continue;
+        }
+        let parts: Vec<&str> = library.name.split(':').collect();
+        // Natives jars of the version's own release are replaced below
+        if parts.len() != 3 || !library_allowed(library) || !seen.insert(parts[1]) {
+            continue;
+        }
+        let version = if lwjgl_at_least(parts[2], ARM_LWJGL_VERSION) {
+            parts[2]
+        } else {
+            ARM_LWJGL_VERSION
+        };
+        for name in [
+            format!("org.lwjgl:{}:{version}", parts[1]),
+            format!("org.lwjgl:{}:{version}:{classifier}", parts[1]),
+        ] {
+            swapped.push(Library {
+                name,
+                downloads: None,
+                rules: None,
+                natives: None,
+                extract: None,
+                url: Some(MAVEN_CENTRAL.to_string()),
+            });
+        }
+    }
+    (!seen.is_empty()).then_some(swapped)
+}
+
+/// Whether LWJGL release `version` is `min` or newer; suffixes such as
+/// `-SNAPSHOT` are ignored
+fn lwjgl_at_least(version: &str, min: &str) -> bool {
+    let numbers = |version: &str| -> Vec<u32> {
+        version
+            .split(['.', '-', '+'])
+            .map_while(|part| part.parse().ok())
+            .collect()
+    };
+    numbers(version) >= numbers(min)
+}
+
+/// `java` if it can load natives built for `arch`, else an installed Java
+/// that can
+fn java_for_natives(java: String, arch: &str, profile: &Profile) -> Result<String> {
+    // A Java whose architecture can't be told is given the benefit of the doubt
+    let Some(java_arch) = validate_java_path(&java).arch else {
+        return Ok(java);
+    };
+    if java_arch == arch {
+        return Ok(java);
+    }
+    let hint = if arch == "x86_64" {
+        " (without ARM natives it runs under Rosetta)"
+    } else {
+        ""
+    };
+    if profile.runtime.java.is_some() {
+        bail!(
+            "{java} is an {java_arch} Java, but Minecraft {} needs an {arch} one on this machine{hint}",
+            profile.mc_version
+        );
+    }
+    let java = find_java_for_arch(&profile.mc_version, arch).with_context(|| {
+        format!(
+            "Minecraft {} needs an {arch} Java {} or newer on this machine{hint}; install one or set the profile's java path",
+            profile.mc_version,
+            get_required_java_version(&profile.mc_version)
+        )
+    })?;
+    eprintln!("Using {arch} Java {java} for Minecraft {}", profile.mc_version);
+    Ok(java)
 }
 
 fn build_args(
     version: &VersionJson,
     vars: &HashMap<String, String>,
+    ctx: &RuleContext,
 ) -> Result<(Vec<String>, Vec<String>)> {
     let mut jvm_args = Vec::new();
     let mut game_args = Vec::new();
 
     if let Some(arguments) = &version.arguments {
-        jvm_args.extend(collect_args(&arguments.jvm, vars));
-        game_args.extend(collect_args(&arguments.game, vars));
+        jvm_args.extend(collect_args(&arguments.jvm, vars, ctx));
+        game_args.extend(collect_args(&arguments.game, vars, ctx));
     } else if let Some(raw) = &version.minecraft_arguments {
         let parts = split(raw).context("failed to parse minecraftArguments")?;
         game_args.extend(parts.into_iter().map(|arg| substitute_vars(&arg, vars)));
+        // The applet-based clients look for their files here, as set by the
+        // old launcher
+        if let Some(game_dir) = vars.get("game_directory") {
+            jvm_args.push(format!("-Dminecraft.applet.TargetDirectory={game_dir}"));
+        }
     }
 
     Ok((jvm_args, game_args))
 }
 
-fn collect_args(list: &[Argument], vars: &HashMap<String, String>) -> Vec<String> {
-    let ctx = RuleContext::new();
+fn collect_args(list: &[Argument], vars: &HashMap<String, String>, ctx: &RuleContext) -> Vec<String> {
     let mut out = Vec::new();
     for arg in list {
         match arg {
             Argument::Simple(value) => out.push(substitute_vars(value, vars)),
             Argument::WithRules { rules, value } => {
-                if rules_allow(rules, &ctx) {
+                if rules_allow(rules, ctx) {
                     match value {
                         ArgValue::Single(value) => out.push(substitute_vars(value, vars)),
                         ArgValue::Multiple(values) => {
@@ -749,6 +2453,11 @@ fn build_var_map(
     vars.insert("assets_index_name".into(), asset_index.to_string());
     vars.insert("auth_uuid".into(), account.uuid.clone());
     vars.insert("auth_access_token".into(), account.access_token.clone());
+    // Pre-1.7 clients take the session as one argument
+    vars.insert(
+        "auth_session".into(),
+        format!("token:{}:{}", account.access_token, account.uuid),
+    );
     vars.insert("clientid".into(), account.uuid.clone());
     vars.insert("user_type".into(), "msa".to_string());
     vars.insert(
@@ -809,63 +2518,62 @@ fn strip_classpath_args(args: &mut Vec<String>) {
     }
 }
 
-fn resolve_java(override_java: Option<&str>, mc_version: &str) -> String {
+/// Java for a launch: the profile's own path, else a compatible managed
+/// runtime or installation, else Temurin downloaded into the managed
+/// runtimes if `auto_install_java` is on
+pub(crate) fn resolve_java(paths: &Paths, override_java: Option<&str>, mc_version: &str) -> Result<String> {
     // If user explicitly set a Java path, use it (they know what they're doing)
     if let Some(java) = override_java {
-        return java.to_string();
+        return Ok(java.to_string());
     }
 
-    let required_java = get_required_java_version(mc_version);
-
-    // Try to find a compatible Java installation
-    let installations = detect_installations();
-    for install in &installations {
-        if let Some(major) = install.major {
-            if is_java_compatible(major, mc_version) {
-                eprintln!(
-                    "Auto-selected Java {} ({}) for Minecraft {}",
-                    major,
-                    install.vendor.as_deref().unwrap_or("Unknown"),
-                    mc_version
-                );
-                return install.path.clone();
-            }
-        }
+    if let Some(java) = find_compatible_java(mc_version, &paths.java_runtimes) {
+        eprintln!("Auto-selected Java {java} for Minecraft {mc_version}");
+        return Ok(java);
     }
 
-    // Fall back to JAVA_HOME or system java, but warn if incompatible
-    let fallback = if let Ok(java_home) = std::env::var("JAVA_HOME") {
-        Path::new(&java_home).join("bin").join("java").to_string_lossy().to_string()
-    } else {
-        "java".to_string()
-    };
-
-    eprintln!(
-        "Warning: Could not find Java {} or newer for Minecraft {}. \
-         Using '{}' which may not be compatible.",
-        required_java, mc_version, fallback
-    );
+    let required_java = get_required_java_version(mc_version);
+    let auto_install = load_config(paths).is_ok_and(|config| config.auto_install_java);
+    if !auto_install {
+        bail!(
+            "no Java {required_java} or newer found for Minecraft {mc_version}; install one, set the profile's java path, \
+             or run `shard config set-auto-install-java on` to download it automatically"
+        );
+    }
 
-    fallback
+    eprintln!("Downloading Java {required_java} (Temurin) for Minecraft {mc_version}...");
+    let install_dir = paths.java_runtime_dir(&format!("temurin-{required_java}"));
+    let java = download_and_install_java(required_java, &install_dir, None)
+        .with_context(|| format!("failed to install Java {required_java}"))?;
+    Ok(java.to_string_lossy().into_owned())
 }
 
 fn download_text(url: &str) -> Result<String> {
     let client = Client::new();
-    let resp = client.get(url).send().context("failed to download")?;
-    let resp = resp.error_for_status().context("download failed")?;
-    let text = resp.text().context("failed to read response")?;
-    Ok(text)
+    // Retried as a whole so a connection dropped mid-body is retried too
+    with_mirror_fallback(url, |url| {
+        retry(|| {
+            let resp = client.get(url).send().context("failed to download")?;
+            let resp = resp.error_for_status().context("download failed")?;
+            let text = resp.text().context("failed to read response")?;
+            Ok(text)
+        })
+    })
 }
 
 fn download_json(url: &str) -> Result<Value> {
     let client = Client::new();
-    let resp = client.get(url).send().context("failed to download json")?;
-    let resp = resp.error_for_status().context("json download failed")?;
-    let json: Value = resp.json().context("failed to parse json")?;
-    Ok(json)
+    with_mirror_fallback(url, |url| {
+        retry(|| {
+            let resp = client.get(url).send().context("failed to download json")?;
+            let resp = resp.error_for_status().context("json download failed")?;
+            let json: Value = resp.json().context("failed to parse json")?;
+            Ok(json)
+        })
+    })
 }
 
-fn download_with_sha1(url: &str, path: &Path, expected_sha1: Option<&str>) -> Result<()> {
+pub(crate) fn download_with_sha1(url: &str, path: &Path, expected_sha1: Option<&str>) -> Result<()> {
     if path.exists() {
         if let Some(expected) = expected_sha1 {
             if let Ok(actual) = sha1_file(path)
@@ -877,37 +2585,11 @@ fn download_with_sha1(url: &str, path: &Path, expected_sha1: Option<&str>) -> Re
         }
     }
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .with_context(|| format!("failed to create dir: {}", parent.display()))?;
-    }
-
-    let tmp_path = path.with_extension("tmp");
     let client = Client::new();
-    let mut resp = client
-        .get(url)
-        .send()
-        .with_context(|| format!("failed to download: {url}"))?
-        .error_for_status()
-        .with_context(|| format!("download failed: {url}"))?;
-
-    let mut out = fs::File::create(&tmp_path)
-        .with_context(|| format!("failed to create file: {}", tmp_path.display()))?;
-    std::io::copy(&mut resp, &mut out).context("failed to write download")?;
-
-    if let Some(expected) = expected_sha1 {
-        let actual = sha1_file(&tmp_path)?;
-        if !actual.eq_ignore_ascii_case(expected) {
-            bail!("sha1 mismatch for {}", path.display());
-        }
-    }
-
-    fs::rename(&tmp_path, path)
-        .with_context(|| format!("failed to move file into place: {}", path.display()))?;
-    Ok(())
+    with_mirror_fallback(url, |url| download_resumable(&client, url, path, expected_sha1))
 }
 
-fn sha1_file(path: &Path) -> Result<String> {
+pub(crate) fn sha1_file(path: &Path) -> Result<String> {
     let mut file = fs::File::open(path)
         .with_context(|| format!("failed to open file for sha1: {}", path.display()))?;
     let mut hasher = Sha1::new();
@@ -923,6 +2605,47 @@ fn sha1_file(path: &Path) -> Result<String> {
     Ok(format!("{:x}", digest))
 }
 
+/// Marks a shared natives directory as fully extracted
+const NATIVES_COMPLETE: &str = ".shard-complete";
+
+/// Extract a version's native jars into its shared natives directory. Files
+/// go to a temporary sibling first, so a directory marked complete never
+/// holds a partial extraction, even when two launches race.
+fn populate_natives(dir: &Path, jars: &[(PathBuf, Option<&Extract>)]) -> Result<()> {
+    let mut tmp_name = dir.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".tmp-{}", std::process::id()));
+    let tmp = dir.with_file_name(tmp_name);
+    if tmp.exists() {
+        fs::remove_dir_all(&tmp)
+            .with_context(|| format!("failed to clear natives directory: {}", tmp.display()))?;
+    }
+    fs::create_dir_all(&tmp)
+        .write_context(&tmp, || format!("failed to create natives dir: {}", tmp.display()))?;
+    for (jar, extract) in jars {
+        extract_natives(jar, &tmp, *extract)?;
+    }
+    let marker = tmp.join(NATIVES_COMPLETE);
+    fs::write(&marker, "").write_context(&marker, || format!("failed to write {}", marker.display()))?;
+
+    if dir.join(NATIVES_COMPLETE).exists() {
+        // Another launch finished first
+        let _ = fs::remove_dir_all(&tmp);
+        return Ok(());
+    }
+    if dir.exists() {
+        fs::remove_dir_all(dir)
+            .with_context(|| format!("failed to clear natives directory: {}", dir.display()))?;
+    }
+    fs::rename(&tmp, dir).or_else(|err| {
+        let _ = fs::remove_dir_all(&tmp);
+        if dir.join(NATIVES_COMPLETE).exists() {
+            Ok(())
+        } else {
+            Err(err).with_context(|| format!("failed to move natives into {}", dir.display()))
+        }
+    })
+}
+
 fn extract_natives(path: &Path, dest: &Path, extract: Option<&Extract>) -> Result<()> {
     let file = fs::File::open(path)
         .with_context(|| format!("failed to open native jar: {}", path.display()))?;
@@ -967,8 +2690,9 @@ fn extract_natives(path: &Path, dest: &Path, extract: Option<&Extract>) -> Resul
             );
         }
         let mut out = fs::File::create(&out_path)
-            .with_context(|| format!("failed to create native file: {}", out_path.display()))?;
-        std::io::copy(&mut entry, &mut out).context("failed to extract native file")?;
+            .write_context(&out_path, || format!("failed to create native file: {}", out_path.display()))?;
+        std::io::copy(&mut entry, &mut out)
+            .write_context(&out_path, || "failed to extract native file")?;
     }
     Ok(())
 }
@@ -1001,14 +2725,14 @@ fn os_key() -> String {
 }
 
 fn arch_marker() -> &'static str {
-    if std::env::consts::ARCH.contains("64") {
+    if host_arch().contains("64") {
         "64"
     } else {
         "32"
     }
 }
 
-fn maven_path_from_name(name: &str) -> Option<String> {
+pub(crate) fn maven_path_from_name(name: &str) -> Option<String> {
     let parts: Vec<&str> = name.split(':').collect();
     if parts.len() < 3 {
         return None;
@@ -1053,14 +2777,29 @@ fn join_url(base: &str, path: &str) -> String {
 }
 
 #[derive(Clone, Deserialize)]
-struct VersionManifest {
-    versions: Vec<VersionEntry>,
+pub(crate) struct VersionManifest {
+    #[serde(default)]
+    pub(crate) latest: Option<LatestVersions>,
+    pub(crate) versions: Vec<VersionEntry>,
 }
 
 #[derive(Clone, Deserialize)]
-struct VersionEntry {
-    id: String,
+pub(crate) struct LatestVersions {
+    pub(crate) release: String,
+    pub(crate) snapshot: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct VersionEntry {
+    pub(crate) id: String,
     url: String,
+    #[serde(default)]
+    sha1: Option<String>,
+    /// `release`, `snapshot`, `old_beta` or `old_alpha`
+    #[serde(rename = "type", default)]
+    pub(crate) version_type: Option<String>,
+    #[serde(rename = "releaseTime", default)]
+    pub(crate) release_time: Option<String>,
 }
 
 #[derive(Clone, Deserialize)]
@@ -1155,7 +2894,7 @@ impl RuleContext {
     fn new() -> Self {
         let os_name = os_key();
         // Normalize architecture names for Minecraft manifest compatibility
-        let os_arch = match std::env::consts::ARCH {
+        let os_arch = match host_arch() {
             "aarch64" => "arm64".to_string(),
             arch => arch.to_string(),
         };
@@ -1176,13 +2915,14 @@ impl RuleContext {
 #[derive(Clone, Deserialize)]
 struct Downloads {
     client: Option<DownloadInfo>,
+    #[serde(default)]
+    server: Option<DownloadInfo>,
 }
 
 #[derive(Clone, Deserialize)]
 struct DownloadInfo {
     sha1: String,
     url: String,
-    #[allow(dead_code)]
     #[serde(default)]
     size: Option<u64>,
 }
@@ -1197,12 +2937,20 @@ struct AssetIndexEntry {
 #[derive(Clone, Deserialize)]
 struct AssetIndex {
     objects: HashMap<String, AssetObject>,
+    /// `legacy` index (1.6-1.7.2): assets are read by file name
+    #[serde(default, rename = "virtual")]
+    is_virtual: bool,
+    /// `pre-1.6` index: assets are read from `<game dir>/resources`
+    #[serde(default)]
+    map_to_resources: bool,
 }
 
 #[derive(Clone, Deserialize)]
 struct AssetObject {
     hash: String,
     #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
     url: Option<String>,
 }
 
@@ -1253,6 +3001,8 @@ struct LibraryArtifact {
     path: String,
     sha1: String,
     url: String,
+    #[serde(default)]
+    size: Option<u64>,
 }
 
 #[derive(Clone, Deserialize)]
@@ -1327,3 +3077,160 @@ fn merge_versions(mut parent: VersionJson, mut child: VersionJson) -> VersionJso
 
     child
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::{FakeCdn, TempRoot};
+    use crate::profile::{Runtime, create_profile};
+
+    #[test]
+    fn test_prepare_from_fake_cdn() {
+        let cdn = FakeCdn::start();
+        cdn.publish_version("1.21.4");
+        let root = TempRoot::new("prepare");
+        let paths = &root.paths;
+        let runtime = Runtime {
+            java: Some("/usr/bin/java".to_string()),
+            ..Runtime::default()
+        };
+        let profile = create_profile(paths, "fixture", "1.21.4", None, runtime).unwrap();
+
+        let estimate = estimate_prepare(paths, &profile).unwrap();
+        assert_eq!((estimate.client.files, estimate.libraries.files, estimate.assets.files), (1, 1, 1));
+        assert_eq!(estimate.bytes(), ("client jar of 1.21.4".len() + "fixture library".len() + "fixture sound".len()) as u64);
+        assert!(!cdn.requests().iter().any(|path| path.starts_with("maven/") || path.ends_with("client.jar")));
+
+        let plan = prepare(paths, &profile, &LaunchAccount::demo(), None).unwrap();
+        assert_eq!(plan.java_exec, "/usr/bin/java");
+        assert_eq!(plan.main_class, "net.minecraft.client.main.Main");
+        assert!(plan.classpath.contains("fixture-1.0.jar"));
+        assert!(plan.classpath.contains("1.21.4.jar"));
+        assert!(!plan.jvm_args.iter().any(|arg| arg == "-cp"));
+        assert!(plan.game_args.iter().any(|arg| arg == "--demo"));
+        assert!(cdn.requests().iter().any(|path| path.starts_with("assets/")));
+
+        // Everything is cached now; a second launch downloads nothing
+        let requests = cdn.requests().len();
+        prepare(paths, &profile, &LaunchAccount::demo(), None).unwrap();
+        assert_eq!(cdn.requests().len(), requests);
+        assert_eq!(estimate_prepare(paths, &profile).unwrap().files(), 0);
+    }
+
+    #[test]
+    fn test_concurrent_prepare() {
+        let cdn = FakeCdn::start();
+        cdn.publish_version("1.21.5");
+        let root = TempRoot::new("concurrent-prepare");
+        let paths = &root.paths;
+        let runtime = Runtime {
+            java: Some("/usr/bin/java".to_string()),
+            ..Runtime::default()
+        };
+        let first = create_profile(paths, "first", "1.21.5", None, runtime.clone()).unwrap();
+        let second = create_profile(paths, "second", "1.21.5", None, runtime).unwrap();
+
+        std::thread::scope(|scope| {
+            let launches = [&first, &second]
+                .map(|profile| scope.spawn(move || prepare(paths, profile, &LaunchAccount::demo(), None)));
+            for launch in launches {
+                launch.join().unwrap().unwrap();
+            }
+        });
+        // The launch that waited found everything downloaded
+        let client_requests = cdn.requests().iter().filter(|path| path.ends_with("client.jar")).count();
+        assert_eq!(client_requests, 1);
+        let jar = paths.minecraft_version_jar("1.21.5");
+        assert_eq!(fs::read(&jar).unwrap(), b"client jar of 1.21.5");
+        assert!(!jar.with_file_name("1.21.5.jar.lock").exists());
+    }
+
+    #[test]
+    fn test_concurrent_download() {
+        let cdn = FakeCdn::start();
+        let body = vec![7u8; 256 * 1024];
+        let sha1 = crate::fake_cdn::sha1_hex(&body);
+        let url = cdn.publish("files/shared.jar", body.clone());
+        let root = TempRoot::new("concurrent-download");
+        let dest = root.paths.minecraft_library_path("com/example/shared/1.0/shared-1.0.jar");
+
+        std::thread::scope(|scope| {
+            let downloads: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| download_with_sha1(&url, &dest, Some(&sha1))))
+                .collect();
+            for download in downloads {
+                download.join().unwrap().unwrap();
+            }
+        });
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        assert!(!dest.with_file_name("shared-1.0.jar.part").exists());
+        assert!(!dest.with_file_name("shared-1.0.jar.lock").exists());
+    }
+
+    #[test]
+    fn test_replace_access_token() {
+        let mut plan = LaunchPlan {
+            instance_dir: PathBuf::from("instance"),
+            wrapper: Vec::new(),
+            java_exec: "java".to_string(),
+            jvm_args: vec!["-Xmx2G".to_string()],
+            classpath: String::new(),
+            main_class: "net.minecraft.client.Minecraft".to_string(),
+            game_args: ["--session", "token:old-token:uuid", "--accessToken", "old-token"].map(String::from).to_vec(),
+            priority: None,
+            cpu_affinity: Vec::new(),
+            env: Vec::new(),
+        };
+        plan.replace_access_token("old-token", "new-token");
+        assert_eq!(plan.game_args, ["--session", "token:new-token:uuid", "--accessToken", "new-token"]);
+        assert_eq!(plan.jvm_args, ["-Xmx2G"]);
+
+        let json = serde_json::to_value(&plan).unwrap();
+        assert_eq!(json["instance_dir"], "instance");
+        assert_eq!(json["wrapper"], serde_json::json!([]));
+        assert_eq!(json["game_args"][3], "new-token");
+    }
+
+    #[test]
+    fn test_arm_lwjgl_libraries() {
+        // Shaped like 1.16.5: natives by classifier, plus a build only one
+        // platform gets
+        let libraries: Vec<Library> = serde_json::from_value(serde_json::json!([
+            { "name": "com.mojang:brigadier:1.0.17" },
+            { "name": "org.lwjgl:lwjgl:3.2.2", "natives": { "linux": "natives-linux", "osx": "natives-macos" } },
+            { "name": "org.lwjgl:lwjgl:3.2.1", "rules": [{ "action": "allow", "os": { "name": "nowhere" } }] },
+            { "name": "org.lwjgl:lwjgl-glfw:3.2.2" },
+            { "name": "org.lwjgl:lwjgl-glfw:3.2.2:natives-linux" },
+        ]))
+        .unwrap();
+        let swapped = arm_lwjgl_libraries(&libraries, "natives-linux-arm64").unwrap();
+        let names: Vec<&str> = swapped.iter().map(|library| library.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "com.mojang:brigadier:1.0.17",
+                "org.lwjgl:lwjgl:3.3.1",
+                "org.lwjgl:lwjgl:3.3.1:natives-linux-arm64",
+                "org.lwjgl:lwjgl-glfw:3.3.1",
+                "org.lwjgl:lwjgl-glfw:3.3.1:natives-linux-arm64",
+            ]
+        );
+        assert!(swapped[1..].iter().all(|library| library.natives.is_none() && library.url.as_deref() == Some(MAVEN_CENTRAL)));
+
+        // Newer releases only gain the natives; LWJGL 2 is left alone
+        let libraries: Vec<Library> = serde_json::from_value(serde_json::json!([
+            { "name": "org.lwjgl:lwjgl:3.3.3" },
+            { "name": "org.lwjgl:lwjgl:3.3.3:natives-linux" },
+        ]))
+        .unwrap();
+        let swapped = arm_lwjgl_libraries(&libraries, "natives-linux-arm64").unwrap();
+        assert_eq!(swapped[1].name, "org.lwjgl:lwjgl:3.3.3:natives-linux-arm64");
+        let legacy: Vec<Library> =
+            serde_json::from_value(serde_json::json!([{ "name": "org.lwjgl.lwjgl:lwjgl:2.9.4-nightly-20150209" }])).unwrap();
+        assert!(arm_lwjgl_libraries(&legacy, "natives-macos-arm64").is_none());
+
+        assert!(lwjgl_at_least("3.3.1", "3.3.1"));
+        assert!(lwjgl_at_least("3.3.2-SNAPSHOT", "3.3.1"));
+        assert!(!lwjgl_at_least("3.2.2", "3.3.1"));
+    }
+}
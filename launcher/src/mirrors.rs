@@ -0,0 +1,152 @@
+//! Download mirrors for Mojang resources
+//!
+//! piston-meta, libraries.minecraft.net and the asset CDN are slow to reach
+//! from some regions. BMCLAPI-style mirrors serve the same files under their
+//! own base URLs, so the configured [`DownloadMirrors`] rewrite official URLs
+//! and [`with_mirror_fallback`] tries the mirror first, then the official
+//! server. After a few mirror failures in a row the mirrors are skipped for
+//! the rest of the process, so a mirror that is down doesn't slow every file.
+
+use crate::disk::find_disk_error;
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Hosts covered by the manifest mirror: the version manifest, version
+/// JSONs, asset indexes and client jars
+const OFFICIAL_META: &[&str] = &[
+    "https://piston-meta.mojang.com/",
+    "https://piston-data.mojang.com/",
+    "https://launchermeta.mojang.com/",
+    "https://launcher.mojang.com/",
+];
+const OFFICIAL_LIBRARIES: &str = "https://libraries.minecraft.net/";
+const OFFICIAL_ASSETS: &str = "https://resources.download.minecraft.net/";
+
+const BMCLAPI: &str = "https://bmclapi2.bangbang93.com/";
+
+/// Mirror failures in a row after which mirrors are no longer tried
+const MAX_MIRROR_FAILURES: u32 = 3;
+
+/// Base URLs replacing Mojang's download servers; unset ones use the
+/// official server
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadMirrors {
+    /// Replaces piston-meta, piston-data and launchermeta
+    pub manifest: Option<String>,
+    /// Replaces libraries.minecraft.net
+    pub libraries: Option<String>,
+    /// Replaces resources.download.minecraft.net
+    pub assets: Option<String>,
+}
+
+impl DownloadMirrors {
+    /// Built-in mirror sets by name
+    pub fn preset(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "bmclapi" => Ok(Self {
+                manifest: Some(BMCLAPI.to_string()),
+                libraries: Some(format!("{BMCLAPI}maven/")),
+                assets: Some(format!("{BMCLAPI}assets/")),
+            }),
+            "off" | "official" | "none" => Ok(Self::default()),
+            other => bail!("unknown mirror preset: {other} (expected bmclapi or off)"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.manifest.is_none() && self.libraries.is_none() && self.assets.is_none()
+    }
+
+    /// `url` on its mirror, if one is configured for its server
+    pub fn rewrite(&self, url: &str) -> Option<String> {
+        let (rest, mirror) = if let Some(rest) = url.strip_prefix(OFFICIAL_LIBRARIES) {
+            (rest, self.libraries.as_deref()?)
+        } else if let Some(rest) = url.strip_prefix(OFFICIAL_ASSETS) {
+            (rest, self.assets.as_deref()?)
+        } else {
+            let rest = OFFICIAL_META.iter().find_map(|host| url.strip_prefix(host))?;
+            (rest, self.manifest.as_deref()?)
+        };
+        Some(format!("{}/{rest}", mirror.trim_end_matches('/')))
+    }
+}
+
+static MIRRORS: RwLock<Option<DownloadMirrors>> = RwLock::new(None);
+static MIRROR_FAILURES: AtomicU32 = AtomicU32::new(0);
+
+/// Replace the mirrors used by every download in this process
+pub fn set_download_mirrors(mirrors: DownloadMirrors) {
+    if let Ok(mut current) = MIRRORS.write() {
+        *current = Some(mirrors);
+    }
+    MIRROR_FAILURES.store(0, Ordering::Relaxed);
+}
+
+/// The mirrors in effect; none until [`set_download_mirrors`] is called
+pub fn download_mirrors() -> DownloadMirrors {
+    MIRRORS
+        .read()
+        .ok()
+        .and_then(|mirrors| mirrors.clone())
+        .unwrap_or_default()
+}
+
+/// Run `download` against the mirror of `url` if there is one, and against
+/// `url` itself if there isn't or the mirror fails
+pub fn with_mirror_fallback<T>(url: &str, mut download: impl FnMut(&str) -> Result<T>) -> Result<T> {
+    let mirrored = download_mirrors()
+        .rewrite(url)
+        .filter(|_| MIRROR_FAILURES.load(Ordering::Relaxed) < MAX_MIRROR_FAILURES);
+    if let Some(mirrored) = mirrored {
+        match download(&mirrored) {
+            Ok(value) => {
+                MIRROR_FAILURES.store(0, Ordering::Relaxed);
+                return Ok(value);
+            }
+            // The official server won't fix a full disk
+            Err(err) if find_disk_error(&err).is_some() => return Err(err),
+            Err(err) => {
+                if MIRROR_FAILURES.fetch_add(1, Ordering::Relaxed) + 1 == MAX_MIRROR_FAILURES {
+                    eprintln!("warning: download mirror keeps failing ({err:#}); using the official servers");
+                }
+            }
+        }
+    }
+    download(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite() {
+        let mirrors = DownloadMirrors::preset("bmclapi").unwrap();
+        assert_eq!(
+            mirrors.rewrite("https://piston-meta.mojang.com/mc/game/version_manifest_v2.json").as_deref(),
+            Some("https://bmclapi2.bangbang93.com/mc/game/version_manifest_v2.json")
+        );
+        assert_eq!(
+            mirrors.rewrite("https://libraries.minecraft.net/org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3.jar").as_deref(),
+            Some("https://bmclapi2.bangbang93.com/maven/org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3.jar")
+        );
+        assert_eq!(
+            mirrors.rewrite("https://resources.download.minecraft.net/ab/abcdef").as_deref(),
+            Some("https://bmclapi2.bangbang93.com/assets/ab/abcdef")
+        );
+        assert_eq!(mirrors.rewrite("https://maven.fabricmc.net/net/fabricmc/fabric-loader.jar"), None);
+
+        let libraries_only = DownloadMirrors {
+            libraries: Some("https://mirror.example/libs".to_string()),
+            ..DownloadMirrors::default()
+        };
+        assert_eq!(libraries_only.rewrite("https://piston-data.mojang.com/v1/objects/abc/client.jar"), None);
+        assert_eq!(
+            libraries_only.rewrite("https://libraries.minecraft.net/a/b.jar").as_deref(),
+            Some("https://mirror.example/libs/a/b.jar")
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Mod config files in a profile's instance `config/` directory
+//!
+//! Lists config files with their format, and reads/writes them safely: paths
+//! are confined to `config/`, JSON is validated before saving, writes go
+//! through a temp file, and the previous version is kept as a backup under
+//! `.shard-backups/config/` so an edit can be undone.
+
+use crate::paths::Paths;
+use crate::util::{normalize_path_separator, now_epoch_secs};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Backups kept per config file
+const MAX_BACKUPS: usize = 5;
+
+/// Largest config file that will be read for editing
+const MAX_CONFIG_SIZE: u64 = 4 * 1024 * 1024;
+
+impl Paths {
+    pub fn instance_config_dir(&self, profile_id: &str) -> PathBuf {
+        self.instance_dir(profile_id).join("config")
+    }
+
+    pub fn instance_config_backups(&self, profile_id: &str) -> PathBuf {
+        self.instance_dir(profile_id).join(".shard-backups").join("config")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Json5,
+    Properties,
+    Yaml,
+    Cfg,
+    Other,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Self {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        match ext.as_str() {
+            "toml" => Self::Toml,
+            "json" | "mcmeta" => Self::Json,
+            "json5" | "jsonc" => Self::Json5,
+            "properties" => Self::Properties,
+            "yml" | "yaml" => Self::Yaml,
+            "cfg" | "conf" | "ini" => Self::Cfg,
+            _ => Self::Other,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Toml => "toml",
+            Self::Json => "json",
+            Self::Json5 => "json5",
+            Self::Properties => "properties",
+            Self::Yaml => "yaml",
+            Self::Cfg => "cfg",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// A config file relative to the instance `config/` directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModConfigFile {
+    /// Path relative to `config/`, with forward slashes
+    pub path: String,
+    pub format: ConfigFormat,
+    pub size: u64,
+    pub modified: u64,
+}
+
+/// Resolve a relative config path, rejecting anything that escapes `config/`
+fn resolve_config_path(base: &Path, relative: &str) -> Result<PathBuf> {
+    let relative = normalize_path_separator(relative);
+    let rel_path = Path::new(&relative);
+    if relative.is_empty()
+        || rel_path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        bail!("invalid config path: {relative}");
+    }
+    Ok(base.join(rel_path))
+}
+
+fn collect_configs(base: &Path, dir: &Path, out: &mut Vec<ModConfigFile>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read dir: {}", dir.display()))? {
+        let entry = entry.context("failed to read dir entry")?;
+        let path = entry.path();
+        let file_type = entry.file_type().context("failed to read entry type")?;
+        if file_type.is_dir() {
+            collect_configs(base, &path, out)?;
+        } else if file_type.is_file() {
+            let metadata = entry.metadata().ok();
+            let relative = path
+                .strip_prefix(base)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+            out.push(ModConfigFile {
+                path: normalize_path_separator(&relative),
+                format: ConfigFormat::from_path(&path),
+                size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+                modified: metadata
+                    .and_then(|m| m.modified().ok())
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// List config files of a profile, sorted by path
+pub fn list_mod_configs(paths: &Paths, profile_id: &str) -> Result<Vec<ModConfigFile>> {
+    let base = paths.instance_config_dir(profile_id);
+    let mut files = Vec::new();
+    if base.exists() {
+        collect_configs(&base, &base, &mut files)?;
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Read a config file as text
+pub fn read_mod_config(paths: &Paths, profile_id: &str, relative: &str) -> Result<String> {
+    let path = resolve_config_path(&paths.instance_config_dir(profile_id), relative)?;
+    let size = fs::metadata(&path)
+        .with_context(|| format!("config file not found: {relative}"))?
+        .len();
+    if size > MAX_CONFIG_SIZE {
+        bail!("config file is too large to edit: {relative}");
+    }
+    fs::read_to_string(&path)
+        .with_context(|| format!("failed to read config (not UTF-8 text?): {}", path.display()))
+}
+
+/// Check content for the formats we can parse without extra dependencies
+pub fn validate_mod_config(format: ConfigFormat, content: &str) -> Result<()> {
+    if format == ConfigFormat::Json {
+        serde_json::from_str::<serde_json::Value>(content).context("invalid JSON")?;
+    }
+    Ok(())
+}
+
+fn backup_name(file_name: &str, stamp: u64) -> String {
+    format!("{file_name}.{stamp}.bak")
+}
+
+/// Backups of a config file, newest first
+fn list_backups(paths: &Paths, profile_id: &str, relative: &str) -> Result<Vec<PathBuf>> {
+    let backup_path = resolve_config_path(&paths.instance_config_backups(profile_id), relative)?;
+    let (Some(dir), Some(file_name)) = (
+        backup_path.parent(),
+        backup_path.file_name().and_then(|n| n.to_str()),
+    ) else {
+        return Ok(Vec::new());
+    };
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let prefix = format!("{file_name}.");
+    let mut backups: Vec<(u64, PathBuf)> = fs::read_dir(dir)
+        .with_context(|| format!("failed to read backups: {}", dir.display()))?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let stamp = name.strip_prefix(&prefix)?.strip_suffix(".bak")?.parse().ok()?;
+            Some((stamp, entry.path()))
+        })
+        .collect();
+    backups.sort_by_key(|(stamp, _)| std::cmp::Reverse(*stamp));
+    Ok(backups.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Write a config file, backing up the previous version. Returns the backup
+/// path if the file existed before.
+pub fn write_mod_config(
+    paths: &Paths,
+    profile_id: &str,
+    relative: &str,
+    content: &str,
+) -> Result<Option<PathBuf>> {
+    paths.ensure_writable()?;
+    let path = resolve_config_path(&paths.instance_config_dir(profile_id), relative)?;
+    validate_mod_config(ConfigFormat::from_path(&path), content)?;
+
+    let backup = if path.exists() {
+        let backup_target =
+            resolve_config_path(&paths.instance_config_backups(profile_id), relative)?;
+        let dir = backup_target.parent().context("invalid backup path")?;
+        fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create backup dir: {}", dir.display()))?;
+        let file_name = backup_target
+            .file_name()
+            .and_then(|n| n.to_str())
+            .context("invalid backup path")?;
+
+        // Keep backups unique even for several saves within one second
+        let mut stamp = now_epoch_secs();
+        let mut backup_path = dir.join(backup_name(file_name, stamp));
+        while backup_path.exists() {
+            stamp += 1;
+            backup_path = dir.join(backup_name(file_name, stamp));
+        }
+        fs::copy(&path, &backup_path)
+            .with_context(|| format!("failed to back up config: {}", path.display()))?;
+
+        for old in list_backups(paths, profile_id, relative)?.into_iter().skip(MAX_BACKUPS) {
+            let _ = fs::remove_file(old);
+        }
+        Some(backup_path)
+    } else {
+        None
+    };
+
+    let parent = path.parent().context("invalid config path")?;
+    fs::create_dir_all(parent)
+        .with_context(|| format!("failed to create config dir: {}", parent.display()))?;
+    // Append to the full name so foo.toml and foo.json don't share a temp file
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".shard-tmp");
+    let tmp = path.with_file_name(tmp_name);
+    fs::write(&tmp, content).with_context(|| format!("failed to write config: {}", tmp.display()))?;
+    fs::rename(&tmp, &path)
+        .with_context(|| format!("failed to replace config: {}", path.display()))?;
+
+    Ok(backup)
+}
+
+/// Restore the most recent backup of a config file
+pub fn restore_mod_config(paths: &Paths, profile_id: &str, relative: &str) -> Result<()> {
+    let path = resolve_config_path(&paths.instance_config_dir(profile_id), relative)?;
+    let latest = list_backups(paths, profile_id, relative)?
+        .into_iter()
+        .next()
+        .with_context(|| format!("no backup found for {relative}"))?;
+    fs::copy(&latest, &path)
+        .with_context(|| format!("failed to restore config: {}", path.display()))?;
+    fs::remove_file(&latest)
+        .with_context(|| format!("failed to remove backup: {}", latest.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_config_path_rejects_escapes() {
+        let base = Path::new("/instance/config");
+        assert!(resolve_config_path(base, "sodium-options.json").is_ok());
+        assert!(resolve_config_path(base, "jei/jei-client.toml").is_ok());
+        assert!(resolve_config_path(base, "../options.txt").is_err());
+        assert!(resolve_config_path(base, "/etc/passwd").is_err());
+        assert!(resolve_config_path(base, "").is_err());
+    }
+
+    #[test]
+    fn test_format_detection_and_validation() {
+        assert_eq!(ConfigFormat::from_path(Path::new("a/b.TOML")), ConfigFormat::Toml);
+        assert_eq!(ConfigFormat::from_path(Path::new("x.properties")), ConfigFormat::Properties);
+        assert!(validate_mod_config(ConfigFormat::Json, "{\"a\": 1}").is_ok());
+        assert!(validate_mod_config(ConfigFormat::Json, "{a: 1").is_err());
+        assert!(validate_mod_config(ConfigFormat::Toml, "not checked").is_ok());
+    }
+}
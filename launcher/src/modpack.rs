@@ -1,16 +1,33 @@
+use crate::blocklist::check_project;
+use crate::content_store::Platform;
+use crate::loader::LoaderKind;
 use crate::paths::Paths;
-use crate::profile::{ContentRef, Loader, Profile, Runtime, create_profile, load_profile, save_profile, upsert_mod, upsert_resourcepack, upsert_shaderpack};
-use crate::store::{ContentKind, store_content, store_from_url};
+use crate::instance::instance_file_name;
+use crate::jobs::Job;
+use crate::profile::{ContentRef, Loader, Profile, Runtime, create_profile, delete_profile, is_plain_folder_name, load_profile, load_profile_notes, save_profile, save_profile_notes, upsert_mod, upsert_resourcepack, upsert_shaderpack};
+use crate::shader_settings::list_shader_settings;
+use crate::store::{
+    ContentKind, configured_store_hash, content_store_path, hash_file_digests, store_content, store_from_url_with_sha1,
+};
+use crate::util::slugify;
 use anyhow::{Context, Result, bail};
-use serde::Deserialize;
-use sha1::{Sha1, Digest};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{Read, Write};
 use std::path::{Component, Path, PathBuf};
-use zip::ZipArchive;
-
-#[derive(Debug, Deserialize)]
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// Hosts the Modrinth format allows in `downloads`; other content is embedded
+const ALLOWED_DOWNLOAD_HOSTS: &[&str] = &[
+    "cdn.modrinth.com",
+    "github.com",
+    "raw.githubusercontent.com",
+    "gitlab.com",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 struct ModrinthIndex {
     #[serde(rename = "formatVersion")]
@@ -19,38 +36,65 @@ struct ModrinthIndex {
     #[serde(rename = "versionId")]
     version_id: String,
     name: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     summary: Option<String>,
     files: Vec<ModrinthFile>,
     dependencies: HashMap<String, String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct ModrinthFile {
     path: String,
     hashes: ModrinthHashes,
     downloads: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     env: Option<ModrinthEnv>,
     #[serde(rename = "fileSize")]
     file_size: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 struct ModrinthHashes {
     sha1: String,
     sha512: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 struct ModrinthEnv {
     client: Option<String>,
     server: Option<String>,
 }
 
-pub fn import_mrpack(paths: &Paths, pack_path: &Path, profile_id: Option<&str>) -> Result<Profile> {
+/// What importing a pack would create, without downloading or writing
+/// anything
+#[derive(Debug, Clone, Serialize)]
+pub struct MrpackImportPreview {
+    pub profile_id: String,
+    pub mc_version: String,
+    pub loader: Option<Loader>,
+    /// Files to download, with the size the pack lists for them
+    pub downloads: Vec<(String, Option<u64>)>,
+    /// Files extracted from the pack's overrides
+    pub overrides: Vec<String>,
+    pub notes: bool,
+}
+
+impl MrpackImportPreview {
+    /// Total of the listed download sizes; files without one count as zero
+    pub fn download_bytes(&self) -> u64 {
+        self.downloads.iter().filter_map(|(_, size)| *size).sum()
+    }
+}
+
+/// Open a pack and check its index; returns the archive, the index and the
+/// profile to import into
+fn open_mrpack(
+    paths: &Paths,
+    pack_path: &Path,
+    profile_id: Option<&str>,
+) -> Result<(ZipArchive<fs::File>, ModrinthIndex, String)> {
     let file = fs::File::open(pack_path)
         .with_context(|| format!("failed to open modpack: {}", pack_path.display()))?;
     let mut zip = ZipArchive::new(file).context("failed to read modpack zip")?;
@@ -58,20 +102,61 @@ pub fn import_mrpack(paths: &Paths, pack_path: &Path, profile_id: Option<&str>)
     let index = read_modrinth_index(&mut zip)?;
     validate_index(&index)?;
 
-    let (mc_version, loader) = resolve_dependencies(&index.dependencies)?;
-
     let profile_id = resolve_profile_id(paths, &index.name, profile_id)?;
     if paths.is_profile_present(&profile_id) {
         bail!("profile already exists: {}", profile_id);
     }
+    Ok((zip, index, profile_id))
+}
+
+pub fn preview_mrpack(paths: &Paths, pack_path: &Path, profile_id: Option<&str>) -> Result<MrpackImportPreview> {
+    let (mut zip, index, profile_id) = open_mrpack(paths, pack_path, profile_id)?;
+    let (mc_version, loader) = resolve_dependencies(&index.dependencies)?;
+    let downloads = index
+        .files
+        .iter()
+        .filter(|file| is_client_allowed(&file.env))
+        .map(|file| (file.path.clone(), file.file_size))
+        .collect();
+    let overrides = zip
+        .file_names()
+        .filter(|name| !name.ends_with('/'))
+        .filter_map(override_rel_path)
+        .map(str::to_string)
+        .collect();
+    let notes = read_pack_notes(&mut zip)?.is_some();
+    Ok(MrpackImportPreview {
+        profile_id,
+        mc_version,
+        loader,
+        downloads,
+        overrides,
+        notes,
+    })
+}
+
+pub fn import_mrpack(paths: &Paths, pack_path: &Path, profile_id: Option<&str>, job: &Job) -> Result<Profile> {
+    let (mut zip, index, profile_id) = open_mrpack(paths, pack_path, profile_id)?;
+    let (mc_version, loader) = resolve_dependencies(&index.dependencies)?;
 
     create_profile(paths, &profile_id, &mc_version, loader, Runtime::default())?;
 
     let overrides_dir = paths.profile_overrides(&profile_id);
     extract_overrides(&mut zip, &overrides_dir)?;
+    if let Some(notes) = read_pack_notes(&mut zip)? {
+        save_profile_notes(paths, &profile_id, &notes)?;
+    }
 
     let mut profile = load_profile(paths, &profile_id)?;
-    for file in &index.files {
+    let algorithm = configured_store_hash(paths);
+    let total = index.files.len() as u64;
+    for (done, file) in index.files.iter().enumerate() {
+        job.progress(done as u64, Some(total), Some(&file.path));
+        if let Err(err) = job.check_cancelled() {
+            // Don't leave a half-imported pack behind
+            let _ = delete_profile(paths, &profile_id);
+            return Err(err);
+        }
         if !is_client_allowed(&file.env) {
             continue;
         }
@@ -90,6 +175,7 @@ pub fn import_mrpack(paths: &Paths, pack_path: &Path, profile_id: Option<&str>)
                     &download_path,
                     Some(download_url.clone()),
                     file_name_override,
+                    algorithm,
                 )?;
                 let content_ref = ContentRef {
                     name: stored.name,
@@ -133,6 +219,199 @@ fn read_modrinth_index<R: Read + Seekable>(zip: &mut ZipArchive<R>) -> Result<Mo
     Ok(index)
 }
 
+/// Profile notes travel as a README at the pack root, outside `overrides/`
+const NOTES_ENTRY: &str = "README.md";
+
+fn read_pack_notes<R: Read + Seekable>(zip: &mut ZipArchive<R>) -> Result<Option<String>> {
+    let Ok(mut file) = zip.by_name(NOTES_ENTRY) else {
+        return Ok(None);
+    };
+    let mut notes = String::new();
+    file.read_to_string(&mut notes)
+        .context("failed to read modpack notes")?;
+    Ok(Some(notes))
+}
+
+/// What ended up in an exported pack
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MrpackExport {
+    /// Content referenced by download URL
+    pub linked: usize,
+    /// Content embedded as override files
+    pub embedded: usize,
+    /// Extra instance folders included as overrides
+    pub extra_folders: Vec<String>,
+    /// Whether the profile notes were included
+    pub notes: bool,
+    /// Shader settings files (`shaderpacks/*.txt`) included as overrides
+    #[serde(default)]
+    pub shader_settings: usize,
+}
+
+/// Export a profile as a Modrinth `.mrpack`.
+///
+/// Content with a permitted download URL is linked; everything else is
+/// embedded under `overrides/`. The profile's overrides, its shader settings
+/// and its configured extra instance folders (kubejs/, scripts/, ...) are
+/// included as overrides.
+pub fn export_mrpack(paths: &Paths, profile_id: &str, out_path: &Path) -> Result<MrpackExport> {
+    let profile = load_profile(paths, profile_id)?;
+    let file = fs::File::create(out_path)
+        .with_context(|| format!("failed to create modpack: {}", out_path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+    let mut written: HashSet<String> = HashSet::new();
+    let mut summary = MrpackExport::default();
+    let mut files = Vec::new();
+
+    let sections = [
+        (&profile.mods, ContentKind::Mod, "mods"),
+        (&profile.resourcepacks, ContentKind::ResourcePack, "resourcepacks"),
+        (&profile.shaderpacks, ContentKind::ShaderPack, "shaderpacks"),
+    ];
+    for (items, kind, dir) in sections {
+        for item in items.iter().filter(|item| item.enabled) {
+            let store_path = content_store_path(paths, kind, &item.hash);
+            if !store_path.exists() {
+                bail!("{} '{}' not found in store", kind.label(), item.name);
+            }
+            let rel = format!("{dir}/{}", instance_file_name(item, kind));
+            match item.source.as_deref().filter(|url| is_allowed_download(url)) {
+                Some(url) => {
+                    let digests = hash_file_digests(&store_path)?;
+                    files.push(ModrinthFile {
+                        path: rel,
+                        hashes: ModrinthHashes { sha1: digests.sha1, sha512: digests.sha512 },
+                        downloads: vec![url.to_string()],
+                        env: None,
+                        file_size: Some(fs::metadata(&store_path)?.len()),
+                    });
+                    summary.linked += 1;
+                }
+                None => {
+                    add_zip_file(&mut zip, options, &format!("overrides/{rel}"), &store_path)?;
+                    written.insert(rel);
+                    summary.embedded += 1;
+                }
+            }
+        }
+    }
+
+    let overrides_dir = paths.profile_overrides(profile_id);
+    add_zip_dir(&mut zip, options, &overrides_dir, "", &mut written)?;
+
+    let shaderpacks_dir = paths.instance_shaderpacks(profile_id);
+    for name in list_shader_settings(paths, profile_id)? {
+        let rel = format!("shaderpacks/{name}");
+        if written.insert(rel.clone()) {
+            add_zip_file(&mut zip, options, &format!("overrides/{rel}"), &shaderpacks_dir.join(&name))?;
+            summary.shader_settings += 1;
+        }
+    }
+
+    let instance_dir = paths.instance_dir(profile_id);
+    for folder in &profile.files.extra_folders {
+        let dir = instance_dir.join(folder);
+        if !is_plain_folder_name(folder) || !dir.is_dir() {
+            continue;
+        }
+        add_zip_dir(&mut zip, options, &dir, folder, &mut written)?;
+        summary.extra_folders.push(folder.clone());
+    }
+
+    if let Some(notes) = load_profile_notes(paths, profile_id)? {
+        zip.start_file(NOTES_ENTRY, options)
+            .context("failed to write modpack notes")?;
+        zip.write_all(notes.as_bytes())
+            .context("failed to write modpack notes")?;
+        summary.notes = true;
+    }
+
+    let index = ModrinthIndex {
+        format_version: 1,
+        game: "minecraft".to_string(),
+        version_id: "1.0.0".to_string(),
+        name: profile.id.clone(),
+        summary: None,
+        files,
+        dependencies: export_dependencies(&profile),
+    };
+    let data = serde_json::to_vec_pretty(&index).context("failed to serialize modrinth index")?;
+    zip.start_file("modrinth.index.json", options)
+        .context("failed to write modrinth index")?;
+    zip.write_all(&data).context("failed to write modrinth index")?;
+    zip.finish().context("failed to finish modpack zip")?;
+
+    Ok(summary)
+}
+
+fn is_allowed_download(url: &str) -> bool {
+    let Some(rest) = url.strip_prefix("https://") else {
+        return false;
+    };
+    let host = rest.split(['/', ':']).next().unwrap_or_default();
+    ALLOWED_DOWNLOAD_HOSTS.contains(&host)
+}
+
+fn export_dependencies(profile: &Profile) -> HashMap<String, String> {
+    let mut deps = HashMap::new();
+    deps.insert("minecraft".to_string(), profile.mc_version.clone());
+    if let Some(loader) = &profile.loader {
+        let key = match loader.loader_type.parse::<LoaderKind>() {
+            Ok(LoaderKind::Fabric) => "fabric-loader",
+            Ok(LoaderKind::Quilt) => "quilt-loader",
+            _ => loader.loader_type.as_str(),
+        };
+        deps.insert(key.to_string(), loader.version.clone());
+    }
+    deps
+}
+
+fn add_zip_file<W: Write + std::io::Seek>(
+    zip: &mut ZipWriter<W>,
+    options: SimpleFileOptions,
+    name: &str,
+    src: &Path,
+) -> Result<()> {
+    zip.start_file(name, options)
+        .with_context(|| format!("failed to add zip entry: {name}"))?;
+    let mut input = fs::File::open(src)
+        .with_context(|| format!("failed to open file: {}", src.display()))?;
+    std::io::copy(&mut input, zip)
+        .with_context(|| format!("failed to write zip entry: {name}"))?;
+    Ok(())
+}
+
+/// Add a directory under `overrides/<prefix>`, skipping paths already written
+fn add_zip_dir<W: Write + std::io::Seek>(
+    zip: &mut ZipWriter<W>,
+    options: SimpleFileOptions,
+    dir: &Path,
+    prefix: &str,
+    written: &mut HashSet<String>,
+) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("failed to read dir: {}", dir.display()))?
+        .flatten()
+        .collect();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let rel = if prefix.is_empty() { name } else { format!("{prefix}/{name}") };
+        let path = entry.path();
+        let file_type = entry.file_type().context("failed to read entry type")?;
+        if file_type.is_dir() {
+            add_zip_dir(zip, options, &path, &rel, written)?;
+        } else if file_type.is_file() && written.insert(rel.clone()) {
+            add_zip_file(zip, options, &format!("overrides/{rel}"), &path)?;
+        }
+    }
+    Ok(())
+}
+
 fn validate_index(index: &ModrinthIndex) -> Result<()> {
     if index.format_version != 1 {
         bail!("unsupported modpack format version: {}", index.format_version);
@@ -154,9 +433,9 @@ fn resolve_dependencies(deps: &HashMap<String, String>) -> Result<(String, Optio
     }
 
     let loader = if let Some(version) = deps.get("fabric-loader") {
-        Some(Loader { loader_type: "fabric".to_string(), version: version.clone() })
+        Some(Loader { loader_type: LoaderKind::Fabric.id().to_string(), version: version.clone() })
     } else if let Some(version) = deps.get("quilt-loader") {
-        Some(Loader { loader_type: "quilt".to_string(), version: version.clone() })
+        Some(Loader { loader_type: LoaderKind::Quilt.id().to_string(), version: version.clone() })
     } else {
         None
     };
@@ -184,22 +463,6 @@ fn resolve_profile_id(paths: &Paths, name: &str, requested: Option<&str>) -> Res
     Ok(candidate)
 }
 
-fn slugify(input: &str) -> String {
-    let mut out = String::new();
-    let mut last_dash = false;
-    for ch in input.chars() {
-        let lower = ch.to_ascii_lowercase();
-        if lower.is_ascii_alphanumeric() {
-            out.push(lower);
-            last_dash = false;
-        } else if !last_dash {
-            out.push('-');
-            last_dash = true;
-        }
-    }
-    out.trim_matches('-').to_string()
-}
-
 fn content_kind_for_path(path: &str) -> Option<ContentKind> {
     let normalized = path.replace('\\', "/");
     let normalized = normalized.trim_start_matches("./");
@@ -236,42 +499,47 @@ fn sanitize_rel_path(path: &str) -> Result<PathBuf> {
     Ok(out)
 }
 
+/// Project id of a Modrinth CDN url (`https://cdn.modrinth.com/data/<id>/versions/...`)
+fn modrinth_cdn_project(url: &str) -> Option<&str> {
+    let rest = url.strip_prefix("https://cdn.modrinth.com/data/")?;
+    let (project_id, rest) = rest.split_once('/')?;
+    (rest.starts_with("versions/") && !project_id.is_empty()).then_some(project_id)
+}
+
 fn download_with_hash(paths: &Paths, file: &ModrinthFile) -> Result<(PathBuf, String)> {
     if file.downloads.is_empty() {
         bail!("modpack file has no downloads: {}", file.path);
     }
 
+    // Refuse blocked projects before downloading; blocked hashes are caught
+    // when the file enters the store
+    for project_id in file.downloads.iter().filter_map(|url| modrinth_cdn_project(url)) {
+        check_project(paths, Platform::Modrinth, project_id, &file.path)?;
+    }
+
     let expected_sha1 = file.hashes.sha1.to_lowercase();
+    let mut last_err = None;
     for url in &file.downloads {
-        let (download_path, _file_name) = store_from_url(paths, url)?;
-        let actual = sha1_file(&download_path)?;
-        if actual == expected_sha1 {
-            if let Some(expected_size) = file.file_size {
-                let actual_size = fs::metadata(&download_path)?.len();
-                if actual_size != expected_size {
-                    bail!("file size mismatch for {}", file.path);
-                }
+        // Checked while downloading, so a wrong partial file is thrown away
+        // instead of resumed; another mirror may still have the right file
+        let download_path = match store_from_url_with_sha1(paths, url, Some(&expected_sha1)) {
+            Ok((download_path, _file_name)) => download_path,
+            Err(err) => {
+                last_err = Some(err);
+                continue;
+            }
+        };
+        if let Some(expected_size) = file.file_size {
+            let actual_size = fs::metadata(&download_path)?.len();
+            if actual_size != expected_size {
+                bail!("file size mismatch for {}", file.path);
             }
-            return Ok((download_path, url.clone()));
         }
+        return Ok((download_path, url.clone()));
     }
 
-    bail!("hash mismatch for {}", file.path)
-}
-
-fn sha1_file(path: &Path) -> Result<String> {
-    let mut file = fs::File::open(path)
-        .with_context(|| format!("failed to open file for hashing: {}", path.display()))?;
-    let mut hasher = Sha1::new();
-    let mut buf = [0u8; 64 * 1024];
-    loop {
-        let read = file.read(&mut buf).context("failed to read file")?;
-        if read == 0 {
-            break;
-        }
-        hasher.update(&buf[..read]);
-    }
-    Ok(hex::encode(hasher.finalize()))
+    let err = last_err.context("modpack file has no downloads")?;
+    Err(err.context(format!("failed to download {}", file.path)))
 }
 
 fn extract_overrides<R: Read + Seekable>(zip: &mut ZipArchive<R>, overrides_dir: &Path) -> Result<()> {
@@ -281,17 +549,9 @@ fn extract_overrides<R: Read + Seekable>(zip: &mut ZipArchive<R>, overrides_dir:
             continue;
         }
         let name = file.name().to_string();
-        let (prefix, rest) = if name.starts_with("overrides/") {
-            ("overrides/", &name["overrides/".len()..])
-        } else if name.starts_with("client-overrides/") {
-            ("client-overrides/", &name["client-overrides/".len()..])
-        } else {
+        let Some(rest) = override_rel_path(&name) else {
             continue;
         };
-        let _ = prefix; // prefix reserved for clarity
-        if rest.is_empty() {
-            continue;
-        }
         let rel = sanitize_rel_path(rest)?;
         let target = overrides_dir.join(rel);
         if let Some(parent) = target.parent() {
@@ -306,6 +566,13 @@ fn extract_overrides<R: Read + Seekable>(zip: &mut ZipArchive<R>, overrides_dir:
     Ok(())
 }
 
+/// Path inside the instance of an `overrides/` or `client-overrides/` entry
+fn override_rel_path(name: &str) -> Option<&str> {
+    name.strip_prefix("overrides/")
+        .or_else(|| name.strip_prefix("client-overrides/"))
+        .filter(|rest| !rest.is_empty())
+}
+
 fn write_override_file(overrides_dir: &Path, rel_path: &Path, src: &Path) -> Result<()> {
     let target = overrides_dir.join(rel_path);
     if let Some(parent) = target.parent() {
@@ -323,3 +590,74 @@ fn write_override_file(overrides_dir: &Path, rel_path: &Path, src: &Path) -> Res
 // Trait alias workaround to keep ZipArchive generic bounds tidy
 trait Seekable: std::io::Seek {}
 impl<T: std::io::Seek> Seekable for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_download_hosts() {
+        assert!(is_allowed_download("https://cdn.modrinth.com/data/AANobbMI/versions/x/sodium.jar"));
+        assert!(is_allowed_download("https://github.com/owner/repo/releases/download/v1/a.jar"));
+        assert!(!is_allowed_download("http://cdn.modrinth.com/data/a.jar"));
+        assert!(!is_allowed_download("https://edge.forgecdn.net/files/1/2/a.jar"));
+        assert!(!is_allowed_download("https://cdn.modrinth.com.evil.example/a.jar"));
+    }
+
+    #[test]
+    fn test_modrinth_cdn_project() {
+        assert_eq!(
+            modrinth_cdn_project("https://cdn.modrinth.com/data/AANobbMI/versions/x/sodium.jar"),
+            Some("AANobbMI")
+        );
+        assert_eq!(modrinth_cdn_project("https://cdn.modrinth.com/data/a.jar"), None);
+        assert_eq!(modrinth_cdn_project("https://github.com/owner/repo/releases/download/v1/a.jar"), None);
+    }
+
+    #[test]
+    fn test_import_mrpack_from_fake_cdn() {
+        use crate::fake_cdn::{FakeCdn, TempRoot, sha1_hex};
+        use crate::jobs::{JobKind, run_job};
+        use sha2::{Digest, Sha512};
+
+        let cdn = FakeCdn::start();
+        let root = TempRoot::new("mrpack-import");
+        let paths = &root.paths;
+        let jar = b"fixture mod".to_vec();
+        let url = cdn.publish("data/fixture/versions/1.0/fixture-mod.jar", jar.clone());
+        let index = serde_json::json!({
+            "formatVersion": 1,
+            "game": "minecraft",
+            "versionId": "1.0",
+            "name": "Fixture Pack",
+            "files": [{
+                "path": "mods/fixture-mod.jar",
+                "hashes": { "sha1": sha1_hex(&jar), "sha512": hex::encode(Sha512::digest(&jar)) },
+                "downloads": [url],
+                "fileSize": jar.len(),
+            }],
+            "dependencies": { "minecraft": "1.21.4" },
+        });
+        let pack = paths.root.join("fixture.mrpack");
+        let mut zip = ZipWriter::new(fs::File::create(&pack).unwrap());
+        zip.start_file("modrinth.index.json", SimpleFileOptions::default()).unwrap();
+        zip.write_all(index.to_string().as_bytes()).unwrap();
+        zip.start_file("overrides/options.txt", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"fov:90").unwrap();
+        zip.finish().unwrap();
+
+        let preview = preview_mrpack(paths, &pack, None).unwrap();
+        assert_eq!(preview.profile_id, "fixture-pack");
+        assert_eq!(preview.downloads, [("mods/fixture-mod.jar".to_string(), Some(jar.len() as u64))]);
+        assert_eq!(preview.overrides, ["options.txt"]);
+        assert!(!paths.is_profile_present("fixture-pack"));
+        assert!(cdn.requests().is_empty());
+
+        let profile = run_job(paths, JobKind::Import, "fixture", |job| import_mrpack(paths, &pack, None, job)).unwrap();
+        assert_eq!(profile.mc_version, "1.21.4");
+        assert_eq!(profile.mods.len(), 1);
+        assert_eq!(profile.mods[0].file_name.as_deref(), Some("fixture-mod.jar"));
+        assert!(paths.profile_overrides(&profile.id).join("options.txt").exists());
+        assert_eq!(cdn.requests(), ["data/fixture/versions/1.0/fixture-mod.jar"]);
+    }
+}
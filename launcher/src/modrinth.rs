@@ -1,7 +1,9 @@
+use crate::net::RetryExt;
 use anyhow::{Context, Result, bail};
 use reqwest::blocking::Client;
 use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 const API_BASE: &str = "https://api.modrinth.com/v2";
 const USER_AGENT_VALUE: &str = "shard-launcher/1.0 (https://github.com/th0rgal/shard)";
@@ -48,6 +50,18 @@ pub struct Project {
     pub game_versions: Vec<String>,
     pub updated: String,
     pub published: String,
+    #[serde(default)]
+    pub license: Option<License>,
+}
+
+/// SPDX-style license of a project
+#[derive(Debug, Clone, Deserialize)]
+pub struct License {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub url: Option<String>,
 }
 
 /// Version of a project
@@ -204,7 +218,7 @@ impl ModrinthClient {
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_with_retry()
             .context("failed to search Modrinth")?
             .error_for_status()
             .context("Modrinth search failed")?;
@@ -219,7 +233,7 @@ impl ModrinthClient {
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_with_retry()
             .context("failed to fetch project")?;
 
         if resp.status() == reqwest::StatusCode::NOT_FOUND {
@@ -239,7 +253,7 @@ impl ModrinthClient {
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_with_retry()
             .context("failed to fetch project versions")?
             .error_for_status()
             .context("Modrinth request failed")?;
@@ -272,7 +286,7 @@ impl ModrinthClient {
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_with_retry()
             .context("failed to fetch versions")?
             .error_for_status()
             .context("Modrinth request failed")?;
@@ -287,7 +301,7 @@ impl ModrinthClient {
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_with_retry()
             .context("failed to fetch version")?
             .error_for_status()
             .context("Modrinth request failed")?;
@@ -307,7 +321,7 @@ impl ModrinthClient {
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_with_retry()
             .context("failed to fetch versions")?
             .error_for_status()
             .context("Modrinth request failed")?;
@@ -315,6 +329,28 @@ impl ModrinthClient {
         resp.json().context("failed to parse versions")
     }
 
+    /// Look up the versions that published files with the given SHA-1
+    /// hashes. Hashes Modrinth doesn't know are missing from the result.
+    pub fn get_versions_by_sha1(&self, hashes: &[&str]) -> Result<HashMap<String, Version>> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let url = format!("{}/version_files", API_BASE);
+        let body = serde_json::json!({ "hashes": hashes, "algorithm": "sha1" });
+
+        let resp = self
+            .client
+            .post(&url)
+            .json(&body)
+            .send_with_retry()
+            .context("failed to look up version files")?
+            .error_for_status()
+            .context("Modrinth request failed")?;
+
+        resp.json().context("failed to parse version files")
+    }
+
     /// Get the latest compatible version for a project
     pub fn get_latest_version(
         &self,
@@ -351,7 +387,7 @@ impl ModrinthClient {
         let resp = self
             .client
             .get(&file.url)
-            .send()
+            .send_with_retry()
             .context("failed to download file")?
             .error_for_status()
             .context("download failed")?;
@@ -370,7 +406,7 @@ impl ModrinthClient {
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_with_retry()
             .context("failed to fetch categories")?
             .error_for_status()
             .context("Modrinth request failed")?;
@@ -385,7 +421,7 @@ impl ModrinthClient {
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_with_retry()
             .context("failed to fetch game versions")?
             .error_for_status()
             .context("Modrinth request failed")?;
@@ -400,7 +436,7 @@ impl ModrinthClient {
         let resp = self
             .client
             .get(&url)
-            .send()
+            .send_with_retry()
             .context("failed to fetch loaders")?
             .error_for_status()
             .context("Modrinth request failed")?;
@@ -0,0 +1,265 @@
+//! Retry with exponential backoff for network requests
+//!
+//! Manifest fetches, library downloads, content API calls and sign-in all go
+//! over the network, where a dropped connection or a 503 is usually gone a
+//! second later. Requests are sent through [`RetryExt::send_with_retry`], and
+//! streamed downloads are wrapped in [`retry`], so transient failures are
+//! retried per the process-wide [`RetryPolicy`] instead of failing the whole
+//! operation. Sign-in token requests use
+//! [`RetryExt::send_with_cautious_retry`], so a one-time code is never sent
+//! again after the server may already have redeemed it.
+
+use anyhow::Result;
+use reqwest::StatusCode;
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::RETRY_AFTER;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total tries, including the first one
+    pub attempts: u32,
+    /// Delay before the first retry; doubled for each one after
+    pub base_delay_ms: u64,
+    /// Upper bound for a single delay, including `Retry-After`
+    pub max_delay_ms: u64,
+    /// Fraction of each delay randomized in either direction (0.0 - 1.0)
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 4,
+            base_delay_ms: 500,
+            max_delay_ms: 8_000,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self {
+            attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before retry number `retry` (starting at 1), without jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64 << retry.saturating_sub(1).min(20);
+        Duration::from_millis(self.base_delay_ms.saturating_mul(factor).min(self.max_delay_ms))
+    }
+
+    /// Backoff before retry number `retry` with jitter applied
+    pub fn delay(&self, retry: u32) -> Duration {
+        let base = self.backoff(retry).as_secs_f64();
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        // Scale into [1 - jitter, 1 + jitter]
+        let scale = 1.0 + jitter * (2.0 * random_unit() - 1.0);
+        Duration::from_secs_f64(base * scale)
+    }
+
+    fn max_delay(&self) -> Duration {
+        Duration::from_millis(self.max_delay_ms)
+    }
+}
+
+/// Good enough randomness for spreading out retries
+fn random_unit() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    f64::from(nanos % 1_000_000) / 1_000_000.0
+}
+
+static POLICY: RwLock<Option<RetryPolicy>> = RwLock::new(None);
+
+/// Replace the policy used by every retrying request in this process
+pub fn set_retry_policy(policy: RetryPolicy) {
+    if let Ok(mut current) = POLICY.write() {
+        *current = Some(policy);
+    }
+}
+
+/// The policy in effect; the default until [`set_retry_policy`] is called
+pub fn retry_policy() -> RetryPolicy {
+    POLICY
+        .read()
+        .ok()
+        .and_then(|policy| *policy)
+        .unwrap_or_default()
+}
+
+/// Statuses worth asking again for: timeouts, rate limits and server errors
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn is_transient_reqwest(err: &reqwest::Error) -> bool {
+    err.is_connect() || err.is_timeout() || err.status().is_some_and(is_retryable_status)
+}
+
+fn is_transient_io(err: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+    matches!(
+        err.kind(),
+        ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::TimedOut
+            | ErrorKind::UnexpectedEof
+    )
+}
+
+/// Whether an error came from a flaky network rather than a bad request, a
+/// missing file or a full disk
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            is_transient_reqwest(err)
+        } else if let Some(err) = cause.downcast_ref::<std::io::Error>() {
+            // Body reads surface connection drops as io errors wrapping the
+            // reqwest error
+            err.get_ref()
+                .and_then(|inner| inner.downcast_ref::<reqwest::Error>())
+                .map_or_else(|| is_transient_io(err), is_transient_reqwest)
+        } else {
+            false
+        }
+    })
+}
+
+/// Run `op` until it succeeds, fails with a non-transient error, or the
+/// policy runs out of attempts
+pub fn retry<T>(mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let policy = retry_policy();
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < policy.attempts && is_transient(&err) => {
+                thread::sleep(policy.delay(attempt));
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+pub trait RetryExt {
+    /// Send the request, retrying connection failures and retryable statuses.
+    /// The final response is returned as-is, so callers still check
+    /// `error_for_status`.
+    fn send_with_retry(self) -> reqwest::Result<Response>;
+
+    /// Like [`send_with_retry`](Self::send_with_retry), for requests that must
+    /// not be repeated once the server may have acted on them, like redeeming
+    /// a one-time code: a request that timed out might have gone through, so
+    /// only connection failures and 429/5xx answers are retried.
+    fn send_with_cautious_retry(self) -> reqwest::Result<Response>;
+}
+
+impl RetryExt for RequestBuilder {
+    fn send_with_retry(self) -> reqwest::Result<Response> {
+        send_retrying(self, is_retryable_status, is_transient_reqwest)
+    }
+
+    fn send_with_cautious_retry(self) -> reqwest::Result<Response> {
+        send_retrying(self, is_cautious_retry_status, |err| {
+            err.is_connect() || err.status().is_some_and(is_cautious_retry_status)
+        })
+    }
+}
+
+/// Rate limits and server errors, but not 408: a timeout may come after the
+/// server handled the request
+fn is_cautious_retry_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn send_retrying(
+    mut request: RequestBuilder,
+    retry_status: fn(StatusCode) -> bool,
+    retry_error: fn(&reqwest::Error) -> bool,
+) -> reqwest::Result<Response> {
+    let policy = retry_policy();
+    let mut attempt = 1;
+    loop {
+        // Streaming bodies can't be replayed; those get a single try
+        let Some(next) = request.try_clone().filter(|_| attempt < policy.attempts) else {
+            return request.send();
+        };
+        let delay = match request.send() {
+            Ok(response) if retry_status(response.status()) => retry_after(&response)
+                .map(|wait| wait.min(policy.max_delay()))
+                .unwrap_or_else(|| policy.delay(attempt)),
+            Err(err) if retry_error(&err) => policy.delay(attempt),
+            result => return result,
+        };
+        thread::sleep(delay);
+        request = next;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            attempts: 10,
+            base_delay_ms: 250,
+            max_delay_ms: 1_500,
+            jitter: 0.5,
+        };
+        let delays: Vec<u64> = (1..=5).map(|n| policy.backoff(n).as_millis() as u64).collect();
+        assert_eq!(delays, vec![250, 500, 1_000, 1_500, 1_500]);
+
+        for retry in 1..=5 {
+            let base = policy.backoff(retry).as_secs_f64();
+            let delay = policy.delay(retry).as_secs_f64();
+            assert!(delay >= base * 0.5 - 1e-9 && delay <= base * 1.5 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_cautious_retry_skips_timeouts() {
+        assert!(is_retryable_status(StatusCode::REQUEST_TIMEOUT));
+        assert!(!is_cautious_retry_status(StatusCode::REQUEST_TIMEOUT));
+        assert!(is_cautious_retry_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_cautious_retry_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_cautious_retry_status(StatusCode::BAD_REQUEST));
+    }
+
+    #[test]
+    fn test_retry_stops_on_permanent_error() {
+        let mut calls = 0;
+        let result: Result<()> = retry(|| {
+            calls += 1;
+            anyhow::bail!("not found")
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
@@ -1,14 +1,33 @@
 use crate::accounts::{
-    Account, MinecraftTokens, MsaTokens, find_account_mut, load_accounts, save_accounts, upsert_account,
+    Account, AccountStatus, MinecraftTokens, MsaTokens, find_account_mut, load_accounts, save_accounts,
+    upsert_account,
+};
+use crate::auth::{
+    AuthError, BrowserAuth, DeviceCode, OAuthToken, SessionState, auth_failure_status, exchange_for_minecraft,
+    finish_browser_auth, poll_device_code, refresh_msa_token, validate_minecraft_session,
 };
-use crate::auth::{DeviceCode, exchange_for_minecraft, poll_device_code, refresh_msa_token};
 use crate::config::load_config;
-use crate::minecraft::LaunchAccount;
+use crate::library::{Library, LibraryItemInput};
+use crate::loader::LoaderKind;
+use crate::minecraft::{LaunchAccount, LaunchPlan};
 use crate::paths::Paths;
-use crate::profile::Loader;
-use crate::store::store_from_url;
-use anyhow::{Context, Result};
+use crate::profile::{
+    ContentRef, Loader, Profile, load_profile, remove_mod, remove_resourcepack, remove_shaderpack, save_profile,
+    upsert_mod, upsert_resourcepack, upsert_shaderpack,
+};
+use crate::store::{ContentKind, StoredContent, configured_store_hash, store_content, store_from_url};
+use crate::util::now_epoch_secs;
+use crate::version_tags::MC_TAG_PREFIX;
+use anyhow::{Context, Result, bail};
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long a browser sign-in may take before giving up
+const BROWSER_AUTH_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Minecraft tokens expiring sooner than this (in seconds) are renewed right
+/// before the game starts
+const LAUNCH_TOKEN_MARGIN: u64 = 15 * 60;
 
 pub fn parse_loader(value: &str) -> Result<Loader> {
     let mut parts = value.splitn(2, '@');
@@ -22,8 +41,9 @@ pub fn parse_loader(value: &str) -> Result<Loader> {
         .map(str::trim)
         .filter(|s| !s.is_empty())
         .context("loader version missing (expected type@version)")?;
+    let kind: LoaderKind = loader_type.parse()?;
     Ok(Loader {
-        loader_type: loader_type.to_string(),
+        loader_type: kind.id().to_string(),
         version: version.to_string(),
     })
 }
@@ -38,6 +58,127 @@ pub fn resolve_input(paths: &Paths, input: &str) -> Result<(PathBuf, Option<Stri
     }
 }
 
+/// Content put in a profile by [`add_content`]
+pub struct AddedContent {
+    /// The profile as saved
+    pub profile: Profile,
+    pub stored: StoredContent,
+    /// False when the profile already had this exact entry
+    pub changed: bool,
+}
+
+/// Store a file or url and add it to a profile's mods or packs
+pub fn add_content(
+    paths: &Paths,
+    profile_id: &str,
+    input: &str,
+    name: Option<String>,
+    version: Option<String>,
+    kind: ContentKind,
+) -> Result<AddedContent> {
+    let mut profile = load_profile(paths, profile_id)?;
+    let (path, source, file_name_hint) = resolve_input(paths, input)?;
+    let stored = store_content(paths, kind, &path, source, file_name_hint, configured_store_hash(paths))?;
+    let content_ref = ContentRef {
+        name: name.unwrap_or_else(|| stored.name.clone()),
+        hash: stored.hash.clone(),
+        version,
+        source: stored.source.clone(),
+        file_name: Some(stored.file_name.clone()),
+        platform: None, // Manual imports are local
+        project_id: None,
+        version_id: None,
+        enabled: true,
+        pinned: false,
+    };
+    let changed = upsert_content(&mut profile, content_ref.clone(), kind)?;
+    save_profile(paths, &profile)?;
+    if let Err(err) = record_in_library(paths, &profile, &content_ref, kind, Some(&stored)) {
+        eprintln!("warning: failed to add {} to the library: {err:#}", content_ref.name);
+    }
+    Ok(AddedContent {
+        profile,
+        stored,
+        changed,
+    })
+}
+
+/// Add a file downloaded from a content store to a profile and the library;
+/// false when the profile already had this exact entry
+pub fn add_store_content(paths: &Paths, profile: &mut Profile, content_ref: ContentRef, kind: ContentKind) -> Result<bool> {
+    let changed = upsert_content(profile, content_ref.clone(), kind)?;
+    save_profile(paths, profile)?;
+    if let Err(err) = record_in_library(paths, profile, &content_ref, kind, None) {
+        eprintln!("warning: failed to add {} to the library: {err:#}", content_ref.name);
+    }
+    Ok(changed)
+}
+
+fn upsert_content(profile: &mut Profile, content_ref: ContentRef, kind: ContentKind) -> Result<bool> {
+    Ok(match kind {
+        ContentKind::Mod => upsert_mod(profile, content_ref),
+        ContentKind::ResourcePack => upsert_resourcepack(profile, content_ref),
+        ContentKind::ShaderPack => upsert_shaderpack(profile, content_ref),
+        ContentKind::Skin => bail!("skins are not added to profiles"),
+    })
+}
+
+/// Keep content added to a profile in the library, tagged with the
+/// profile's Minecraft version and loader
+fn record_in_library(
+    paths: &Paths,
+    profile: &Profile,
+    content: &ContentRef,
+    kind: ContentKind,
+    stored: Option<&StoredContent>,
+) -> Result<()> {
+    let library = Library::from_paths(paths)?;
+    let platform = content.platform.clone().unwrap_or_else(|| {
+        let source = content.source.as_deref().unwrap_or_default();
+        if source.contains("modrinth.com") {
+            "modrinth".to_string()
+        } else if source.contains("curseforge.com") {
+            "curseforge".to_string()
+        } else {
+            "local".to_string()
+        }
+    });
+    let item = library.add_item(&LibraryItemInput {
+        hash: content.hash.clone(),
+        content_type: Some(kind.label().to_string()),
+        name: Some(content.name.clone()),
+        file_name: content.file_name.clone(),
+        source_url: content.source.clone(),
+        source_platform: Some(platform),
+        source_project_id: content.project_id.clone(),
+        source_version: content.version.clone(),
+        sha1: stored.map(|stored| stored.sha1.clone()),
+        sha512: stored.map(|stored| stored.sha512.clone()),
+        ..Default::default()
+    })?;
+    library.add_tag_to_item(item.id, &format!("{MC_TAG_PREFIX}{}", profile.mc_version))?;
+    if let Some(loader) = &profile.loader {
+        library.add_tag_to_item(item.id, &format!("loader:{}", loader.loader_type))?;
+    }
+    Ok(())
+}
+
+/// Remove a mod or pack from a profile by name or hash; false if it wasn't
+/// there
+pub fn remove_content(paths: &Paths, profile_id: &str, target: &str, kind: ContentKind) -> Result<bool> {
+    let mut profile = load_profile(paths, profile_id)?;
+    let changed = match kind {
+        ContentKind::Mod => remove_mod(&mut profile, target),
+        ContentKind::ResourcePack => remove_resourcepack(&mut profile, target),
+        ContentKind::ShaderPack => remove_shaderpack(&mut profile, target),
+        ContentKind::Skin => false,
+    };
+    if changed {
+        save_profile(paths, &profile)?;
+    }
+    Ok(changed)
+}
+
 pub fn expand_tilde(input: &str) -> Result<PathBuf> {
     if let Some(stripped) = input.strip_prefix("~/") {
         let home = dirs::home_dir().context("could not determine home directory")?;
@@ -54,6 +195,23 @@ pub fn finish_device_code_flow(
     device: &DeviceCode,
 ) -> Result<Account> {
     let token = poll_device_code(client_id, client_secret, device)?;
+    save_signed_in_account(paths, token)
+}
+
+/// Complete a sign-in started with [`crate::auth::start_browser_auth`] once
+/// the user is sent to its URL
+pub fn finish_browser_auth_flow(
+    paths: &Paths,
+    client_id: &str,
+    client_secret: Option<&str>,
+    auth: BrowserAuth,
+) -> Result<Account> {
+    let token = finish_browser_auth(auth, client_id, client_secret, BROWSER_AUTH_TIMEOUT)?;
+    save_signed_in_account(paths, token)
+}
+
+/// Trade a Microsoft token for Minecraft tokens and store the account
+fn save_signed_in_account(paths: &Paths, token: OAuthToken) -> Result<Account> {
     let minecraft_auth = exchange_for_minecraft(&token.access_token)?;
 
     let account = Account {
@@ -69,6 +227,7 @@ pub fn finish_device_code_flow(
             access_token: minecraft_auth.access_token,
             expires_at: minecraft_auth.expires_at,
         },
+        status: None,
     };
 
     let mut accounts = load_accounts(paths)?;
@@ -81,7 +240,48 @@ pub fn finish_device_code_flow(
     Ok(account)
 }
 
+/// Persist the actionable status carried by an auth failure on the account,
+/// so the UI can offer re-authentication. Returns the error unchanged.
+fn record_auth_failure(paths: &Paths, id: &str, err: anyhow::Error) -> anyhow::Error {
+    if let Some(status) = auth_failure_status(&err)
+        && let Ok(mut accounts) = load_accounts(paths)
+        && let Some(account) = find_account_mut(&mut accounts, id)
+    {
+        account.status = Some(status);
+        let _ = save_accounts(paths, &accounts);
+    }
+    err
+}
+
 pub fn resolve_launch_account(paths: &Paths, account_id: Option<String>) -> Result<LaunchAccount> {
+    let target = match account_id {
+        Some(id) => id,
+        None => load_accounts(paths)?
+            .active
+            .context("no account selected; use shard account add or shard account use")?,
+    };
+    refresh_launch_account(paths, &target, 0)
+}
+
+/// Renew the Minecraft token in a prepared plan if it expires within
+/// [`LAUNCH_TOKEN_MARGIN`]. The token is fetched before `prepare`, and a
+/// long one (a first Forge install) can outlast it, so this runs again
+/// right before the game is spawned.
+pub fn refresh_plan_account(paths: &Paths, plan: &mut LaunchPlan, account: &LaunchAccount) -> Result<()> {
+    let Some(expires_at) = account.expires_at else {
+        return Ok(());
+    };
+    if now_epoch_secs() + LAUNCH_TOKEN_MARGIN < expires_at {
+        return Ok(());
+    }
+    let fresh = refresh_launch_account(paths, &account.uuid, LAUNCH_TOKEN_MARGIN)?;
+    plan.replace_access_token(&account.access_token, &fresh.access_token);
+    Ok(())
+}
+
+/// Tokens of account `target`, renewing the Minecraft token if it expires
+/// within `margin` seconds (or within a minute) or was revoked
+fn refresh_launch_account(paths: &Paths, target: &str, margin: u64) -> Result<LaunchAccount> {
     let config = load_config(paths)?;
     let client_id = config.msa_client_id.context(
         "missing Microsoft client id; set SHARD_MS_CLIENT_ID or shard config set-client-id",
@@ -89,18 +289,16 @@ pub fn resolve_launch_account(paths: &Paths, account_id: Option<String>) -> Resu
     let client_secret = config.msa_client_secret.as_deref();
 
     let mut accounts = load_accounts(paths)?;
-    let target = account_id
-        .or_else(|| accounts.active.clone())
-        .context("no account selected; use shard account add or shard account use")?;
 
     // Refresh MSA token if expired, saving immediately to preserve the new refresh token
     // in case the subsequent Minecraft exchange fails
     {
-        let account = find_account_mut(&mut accounts, &target)
+        let account = find_account_mut(&mut accounts, target)
             .with_context(|| format!("account not found: {target}"))?;
         if account.msa.is_expired() {
             let refreshed =
-                refresh_msa_token(&client_id, client_secret, &account.msa.refresh_token)?;
+                refresh_msa_token(&client_id, client_secret, &account.msa.refresh_token)
+                    .map_err(|err| record_auth_failure(paths, target, err))?;
             account.msa = MsaTokens {
                 access_token: refreshed.access_token,
                 refresh_token: refreshed.refresh_token,
@@ -112,12 +310,19 @@ pub fn resolve_launch_account(paths: &Paths, account_id: Option<String>) -> Resu
 
     // Refresh Minecraft token if expired
     let (updated_account, old_uuid) = {
-        let account = find_account_mut(&mut accounts, &target)
+        let account = find_account_mut(&mut accounts, target)
             .with_context(|| format!("account not found: {target}"))?;
 
         let old_uuid = account.uuid.clone();
-        if account.minecraft.is_expired() {
-            let minecraft_auth = exchange_for_minecraft(&account.msa.access_token)?;
+        let username = account.username.clone();
+        let expiring = account.minecraft.is_expired() || account.minecraft.expires_within(margin);
+        // An unexpired token can still have been revoked; catch that here
+        // instead of letting the game boot and reject it
+        let rejected = !expiring && session_rejected(&account.minecraft.access_token, &username)?;
+        if expiring || rejected {
+            let minecraft_auth = exchange_for_minecraft(&account.msa.access_token)
+                .map_err(|err| if rejected { session_invalid(&username, err) } else { err })
+                .map_err(|err| record_auth_failure(paths, target, err))?;
             account.minecraft = MinecraftTokens {
                 access_token: minecraft_auth.access_token,
                 expires_at: minecraft_auth.expires_at,
@@ -126,6 +331,7 @@ pub fn resolve_launch_account(paths: &Paths, account_id: Option<String>) -> Resu
             account.xuid = minecraft_auth.xuid;
             account.uuid = minecraft_auth.uuid;
         }
+        account.status = None;
 
         (account.clone(), old_uuid)
     };
@@ -141,9 +347,40 @@ pub fn resolve_launch_account(paths: &Paths, account_id: Option<String>) -> Resu
         username: updated_account.username,
         access_token: updated_account.minecraft.access_token,
         xuid: updated_account.xuid,
+        demo: false,
+        expires_at: Some(updated_account.minecraft.expires_at),
     })
 }
 
+/// Whether Minecraft rejects a saved access token. An account without the
+/// game fails outright; an unreachable endpoint lets the launch go ahead.
+fn session_rejected(access_token: &str, username: &str) -> Result<bool> {
+    match validate_minecraft_session(access_token) {
+        SessionState::Valid => Ok(false),
+        SessionState::Invalid => Ok(true),
+        SessionState::NoProfile => bail!("{username} does not own Minecraft: Java Edition (launch with --demo to play the demo)"),
+        SessionState::Unknown => {
+            eprintln!("warning: could not validate the session for {username}; launching anyway");
+            Ok(false)
+        }
+    }
+}
+
+/// Error for a rejected session that couldn't be renewed. Keeps a more
+/// specific account status found while renewing.
+fn session_invalid(username: &str, err: anyhow::Error) -> anyhow::Error {
+    if auth_failure_status(&err).is_some() {
+        return err;
+    }
+    AuthError {
+        status: AccountStatus::SessionExpired,
+        message: format!(
+            "session invalid for {username}; re-adding the account is required (shard account reauth {username}): {err}"
+        ),
+    }
+    .into()
+}
+
 /// Ensures the account's tokens are fresh, refreshing if needed.
 /// Returns the updated account with fresh Minecraft access token.
 pub fn ensure_fresh_account(paths: &Paths, account_id: Option<String>) -> Result<Account> {
@@ -164,7 +401,8 @@ pub fn ensure_fresh_account(paths: &Paths, account_id: Option<String>) -> Result
             .with_context(|| format!("account not found: {target}"))?;
         if account.msa.is_expired() {
             let refreshed =
-                refresh_msa_token(&client_id, client_secret, &account.msa.refresh_token)?;
+                refresh_msa_token(&client_id, client_secret, &account.msa.refresh_token)
+                    .map_err(|err| record_auth_failure(paths, &target, err))?;
             account.msa = MsaTokens {
                 access_token: refreshed.access_token,
                 refresh_token: refreshed.refresh_token,
@@ -180,7 +418,8 @@ pub fn ensure_fresh_account(paths: &Paths, account_id: Option<String>) -> Result
             .with_context(|| format!("account not found: {target}"))?;
 
         if account.minecraft.is_expired() {
-            let minecraft_auth = exchange_for_minecraft(&account.msa.access_token)?;
+            let minecraft_auth = exchange_for_minecraft(&account.msa.access_token)
+                .map_err(|err| record_auth_failure(paths, &target, err))?;
             account.minecraft = MinecraftTokens {
                 access_token: minecraft_auth.access_token,
                 expires_at: minecraft_auth.expires_at,
@@ -189,6 +428,7 @@ pub fn ensure_fresh_account(paths: &Paths, account_id: Option<String>) -> Result
             account.xuid = minecraft_auth.xuid;
             account.uuid = minecraft_auth.uuid;
         }
+        account.status = None;
 
         account.clone()
     };
@@ -196,3 +436,37 @@ pub fn ensure_fresh_account(paths: &Paths, account_id: Option<String>) -> Result
     save_accounts(paths, &accounts)?;
     Ok(updated_account)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::TempRoot;
+    use crate::profile::{Runtime, create_profile};
+    use std::fs;
+
+    #[test]
+    fn test_add_and_remove_content() {
+        let root = TempRoot::new("ops-content");
+        let paths = &root.paths;
+        create_profile(paths, "modded", "1.21.1", None, Runtime::default()).unwrap();
+        let jar = paths.root.join("sodium.jar");
+        fs::write(&jar, b"not really a jar").unwrap();
+        let input = jar.to_string_lossy();
+
+        let added = add_content(paths, "modded", &input, None, None, ContentKind::Mod).unwrap();
+        assert!(added.changed);
+        assert_eq!(added.stored.file_name, "sodium.jar");
+        let again = add_content(paths, "modded", &input, None, None, ContentKind::Mod).unwrap();
+        assert!(!again.changed);
+        assert_eq!(load_profile(paths, "modded").unwrap().mods.len(), 1);
+
+        let item = Library::from_paths(paths).unwrap().get_item_by_hash(&added.stored.hash).unwrap().unwrap();
+        assert_eq!(item.source_platform.as_deref(), Some("local"));
+        assert_eq!(item.sha1.as_deref(), Some(added.stored.sha1.as_str()));
+        assert!(item.tags.iter().any(|tag| tag.name == "mc:1.21.1"));
+
+        assert!(remove_content(paths, "modded", &added.stored.hash, ContentKind::Mod).unwrap());
+        assert!(!remove_content(paths, "modded", &added.stored.hash, ContentKind::Mod).unwrap());
+        assert!(load_profile(paths, "modded").unwrap().mods.is_empty());
+    }
+}
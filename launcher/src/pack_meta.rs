@@ -0,0 +1,397 @@
+//! `pack.mcmeta` inspection for data packs and resource packs
+//!
+//! Minecraft skips a data pack whose `pack_format` doesn't match the game with
+//! nothing more than a log line, and never loads a data pack that ended up in
+//! the resourcepacks folder or a zip with an extra top-level folder. Reading the
+//! metadata when a pack is added shows the versions it targets and the
+//! namespaces it contains, so those mistakes surface before launching.
+
+use crate::disk::WriteContext;
+use crate::paths::Paths;
+use crate::profile::is_plain_folder_name;
+use crate::util::release_key;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const MCMETA: &str = "pack.mcmeta";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackKind {
+    Data,
+    Resource,
+}
+
+/// Data pack formats with the first and last release using them
+const DATA_PACK_FORMATS: &[(u32, &str, &str)] = &[
+    (4, "1.13", "1.14.4"),
+    (5, "1.15", "1.16.1"),
+    (6, "1.16.2", "1.16.5"),
+    (7, "1.17", "1.17.1"),
+    (8, "1.18", "1.18.1"),
+    (9, "1.18.2", "1.18.2"),
+    (10, "1.19", "1.19.3"),
+    (12, "1.19.4", "1.19.4"),
+    (15, "1.20", "1.20.1"),
+    (18, "1.20.2", "1.20.2"),
+    (26, "1.20.3", "1.20.4"),
+    (41, "1.20.5", "1.20.6"),
+    (48, "1.21", "1.21.1"),
+    (57, "1.21.2", "1.21.3"),
+    (61, "1.21.4", "1.21.4"),
+    (71, "1.21.5", "1.21.5"),
+    (80, "1.21.6", "1.21.6"),
+    (81, "1.21.7", "1.21.8"),
+];
+
+/// Resource pack formats with the first and last release using them
+const RESOURCE_PACK_FORMATS: &[(u32, &str, &str)] = &[
+    (1, "1.6.1", "1.8.9"),
+    (2, "1.9", "1.10.2"),
+    (3, "1.11", "1.12.2"),
+    (4, "1.13", "1.14.4"),
+    (5, "1.15", "1.16.1"),
+    (6, "1.16.2", "1.16.5"),
+    (7, "1.17", "1.17.1"),
+    (8, "1.18", "1.18.2"),
+    (9, "1.19", "1.19.2"),
+    (12, "1.19.3", "1.19.3"),
+    (13, "1.19.4", "1.19.4"),
+    (15, "1.20", "1.20.1"),
+    (18, "1.20.2", "1.20.2"),
+    (22, "1.20.3", "1.20.4"),
+    (32, "1.20.5", "1.20.6"),
+    (34, "1.21", "1.21.1"),
+    (42, "1.21.2", "1.21.3"),
+    (46, "1.21.4", "1.21.4"),
+    (55, "1.21.5", "1.21.5"),
+    (63, "1.21.6", "1.21.6"),
+    (64, "1.21.7", "1.21.8"),
+];
+
+fn format_table(kind: PackKind) -> &'static [(u32, &'static str, &'static str)] {
+    match kind {
+        PackKind::Data => DATA_PACK_FORMATS,
+        PackKind::Resource => RESOURCE_PACK_FORMATS,
+    }
+}
+
+/// Pack format a release expects; `None` for snapshots and unknown versions
+pub fn pack_format_for(kind: PackKind, mc_version: &str) -> Option<u32> {
+    let key = release_key(mc_version)?;
+    format_table(kind)
+        .iter()
+        .find(|(_, first, last)| {
+            release_key(first).is_some_and(|f| f <= key) && release_key(last).is_some_and(|l| key <= l)
+        })
+        .map(|(format, _, _)| *format)
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PackMeta {
+    pub description: String,
+    pub pack_format: u32,
+    /// Inclusive range from `supported_formats` or `min_format`/`max_format`
+    pub supported_formats: Option<(u32, u32)>,
+    /// Namespaces under `data/`
+    pub data_namespaces: Vec<String>,
+    /// Namespaces under `assets/`
+    pub asset_namespaces: Vec<String>,
+    /// Adds world generation (`worldgen/` or dimensions)
+    pub worldgen: bool,
+}
+
+impl PackMeta {
+    pub fn is_data_pack(&self) -> bool {
+        !self.data_namespaces.is_empty()
+    }
+
+    pub fn is_resource_pack(&self) -> bool {
+        !self.asset_namespaces.is_empty()
+    }
+
+    pub fn supports_format(&self, format: u32) -> bool {
+        match self.supported_formats {
+            Some((min, max)) => (min..=max).contains(&format),
+            None => self.pack_format == format,
+        }
+    }
+
+    /// Releases the declared formats cover, e.g. `1.20.3 - 1.21.1`
+    pub fn target_versions(&self, kind: PackKind) -> Option<String> {
+        let (min, max) = self.supported_formats.unwrap_or((self.pack_format, self.pack_format));
+        let table = format_table(kind);
+        let first = table.iter().find(|(format, _, _)| *format >= min && *format <= max)?;
+        let last = table.iter().rev().find(|(format, _, _)| *format >= min && *format <= max)?;
+        if first.1 == last.2 {
+            Some(first.1.to_string())
+        } else {
+            Some(format!("{} - {}", first.1, last.2))
+        }
+    }
+
+    /// Reasons the pack would be ignored or misbehave when used as `kind` on
+    /// `mc_version`
+    pub fn warnings(&self, kind: PackKind, mc_version: Option<&str>) -> Vec<String> {
+        let mut warnings = Vec::new();
+        match kind {
+            PackKind::Resource if self.is_data_pack() && !self.is_resource_pack() => {
+                warnings.push(
+                    "this is a data pack; Minecraft ignores it in resourcepacks, add it to a world's datapacks instead"
+                        .to_string(),
+                );
+                return warnings;
+            }
+            PackKind::Data if !self.is_data_pack() => warnings.push(
+                "no namespaces under data/; Minecraft will load nothing from this data pack".to_string(),
+            ),
+            _ => {}
+        }
+
+        if let Some(mc_version) = mc_version
+            && let Some(expected) = pack_format_for(kind, mc_version)
+            && !self.supports_format(expected)
+        {
+            let target = self
+                .target_versions(kind)
+                .map(|v| format!(" (Minecraft {v})"))
+                .unwrap_or_default();
+            warnings.push(format!(
+                "pack_format {}{target} does not match Minecraft {mc_version} (format {expected}); it may be skipped",
+                self.pack_format
+            ));
+        }
+
+        if kind == PackKind::Data && self.worldgen {
+            warnings.push("changes world generation; only newly generated chunks are affected".to_string());
+        }
+        warnings
+    }
+}
+
+/// Read the metadata and namespaces of a pack zip or folder
+pub fn read_pack_meta(path: &Path) -> Result<PackMeta> {
+    let (mcmeta, entries) = if path.is_dir() {
+        read_dir_pack(path)?
+    } else {
+        read_zip_pack(path)?
+    };
+    let mut meta = parse_mcmeta(&mcmeta)?;
+
+    let mut data = BTreeSet::new();
+    let mut assets = BTreeSet::new();
+    for entry in &entries {
+        let mut parts = entry.split('/');
+        let (Some(root), Some(namespace), Some(rest)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        if namespace.is_empty() {
+            continue;
+        }
+        match root {
+            "data" => {
+                data.insert(namespace.to_string());
+                if matches!(rest, "worldgen" | "dimension" | "dimension_type") {
+                    meta.worldgen = true;
+                }
+            }
+            "assets" => {
+                assets.insert(namespace.to_string());
+            }
+            _ => {}
+        }
+    }
+    meta.data_namespaces = data.into_iter().collect();
+    meta.asset_namespaces = assets.into_iter().collect();
+    Ok(meta)
+}
+
+fn read_zip_pack(path: &Path) -> Result<(String, Vec<String>)> {
+    let file = fs::File::open(path).with_context(|| format!("failed to open pack: {}", path.display()))?;
+    let mut zip = zip::ZipArchive::new(file).with_context(|| format!("not a zip file: {}", path.display()))?;
+    let entries: Vec<String> = zip.file_names().map(|name| name.replace('\\', "/")).collect();
+
+    let Ok(mut file) = zip.by_name(MCMETA) else {
+        if let Some(nested) = entries.iter().find(|name| name.ends_with("/pack.mcmeta")) {
+            bail!(
+                "{nested} is not at the root of the zip; Minecraft only finds packs with {MCMETA} at the top level"
+            );
+        }
+        bail!("{} has no {MCMETA}; it is not a data or resource pack", path.display());
+    };
+    let mut mcmeta = String::new();
+    file.read_to_string(&mut mcmeta)
+        .with_context(|| format!("failed to read {MCMETA}"))?;
+    Ok((mcmeta, entries))
+}
+
+fn read_dir_pack(path: &Path) -> Result<(String, Vec<String>)> {
+    let mcmeta = fs::read_to_string(path.join(MCMETA))
+        .with_context(|| format!("{} has no {MCMETA}", path.display()))?;
+    let mut entries = Vec::new();
+    for root in ["data", "assets"] {
+        let Ok(namespaces) = fs::read_dir(path.join(root)) else {
+            continue;
+        };
+        for namespace in namespaces.flatten() {
+            let Ok(children) = fs::read_dir(namespace.path()) else {
+                continue;
+            };
+            for child in children.flatten() {
+                entries.push(format!(
+                    "{root}/{}/{}",
+                    namespace.file_name().to_string_lossy(),
+                    child.file_name().to_string_lossy()
+                ));
+            }
+        }
+    }
+    Ok((mcmeta, entries))
+}
+
+fn parse_mcmeta(data: &str) -> Result<PackMeta> {
+    // Some packs ship a UTF-8 BOM, which serde_json rejects
+    let json: Value = serde_json::from_str(data.trim_start_matches('\u{feff}'))
+        .with_context(|| format!("failed to parse {MCMETA}"))?;
+    let pack = json.get("pack").with_context(|| format!("{MCMETA} has no \"pack\" section"))?;
+
+    let min_format = pack.get("min_format").and_then(format_major);
+    let max_format = pack.get("max_format").and_then(format_major);
+    let pack_format = pack
+        .get("pack_format")
+        .and_then(Value::as_u64)
+        .map(|f| f as u32)
+        .or(max_format)
+        .with_context(|| format!("{MCMETA} has no pack_format"))?;
+
+    let supported_formats = match (min_format, max_format) {
+        (Some(min), Some(max)) => Some((min, max)),
+        _ => pack.get("supported_formats").and_then(format_range),
+    };
+
+    Ok(PackMeta {
+        description: pack.get("description").map(text_component).unwrap_or_default(),
+        pack_format,
+        supported_formats,
+        ..Default::default()
+    })
+}
+
+/// Major version of a format written as `N` or `[N, minor]`
+fn format_major(value: &Value) -> Option<u32> {
+    match value {
+        Value::Array(parts) => parts.first()?.as_u64().map(|f| f as u32),
+        other => other.as_u64().map(|f| f as u32),
+    }
+}
+
+/// `supported_formats` as `N`, `[min, max]` or `{min_inclusive, max_inclusive}`
+fn format_range(value: &Value) -> Option<(u32, u32)> {
+    match value {
+        Value::Number(n) => n.as_u64().map(|f| (f as u32, f as u32)),
+        Value::Array(parts) => Some((format_major(parts.first()?)?, format_major(parts.get(1)?)?)),
+        Value::Object(map) => Some((
+            format_major(map.get("min_inclusive")?)?,
+            format_major(map.get("max_inclusive")?)?,
+        )),
+        _ => None,
+    }
+}
+
+/// Plain text of a chat component (string, object with `text`/`extra`, or array)
+fn text_component(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts.iter().map(text_component).collect(),
+        Value::Object(map) => {
+            let mut text = map.get("text").and_then(Value::as_str).unwrap_or_default().to_string();
+            if let Some(extra) = map.get("extra") {
+                text.push_str(&text_component(extra));
+            }
+            text
+        }
+        _ => String::new(),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackInspection {
+    pub kind: PackKind,
+    pub meta: PackMeta,
+    /// Releases the pack targets, when its format is known
+    pub target_versions: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Inspect a pack to be used as `kind`, checking it against `mc_version`
+pub fn inspect_pack(path: &Path, kind: PackKind, mc_version: Option<&str>) -> Result<PackInspection> {
+    let meta = read_pack_meta(path)?;
+    Ok(PackInspection {
+        kind,
+        target_versions: meta.target_versions(kind),
+        warnings: meta.warnings(kind, mc_version),
+        meta,
+    })
+}
+
+/// Folder of a world's data packs inside a profile's instance
+pub fn world_datapacks_dir(paths: &Paths, profile_id: &str, world: &str) -> Result<PathBuf> {
+    if !is_plain_folder_name(world) {
+        bail!("invalid world name: {world}");
+    }
+    let world_dir = paths.instance_dir(profile_id).join("saves").join(world);
+    if !world_dir.is_dir() {
+        bail!("world not found in profile {profile_id}: {world}");
+    }
+    Ok(world_dir.join("datapacks"))
+}
+
+/// Copy a data pack zip into a world, returning the installed path
+pub fn install_datapack(paths: &Paths, profile_id: &str, world: &str, source: &Path, file_name: &str) -> Result<PathBuf> {
+    paths.ensure_writable()?;
+    let dir = world_datapacks_dir(paths, profile_id, world)?;
+    fs::create_dir_all(&dir).write_context(&dir, || format!("failed to create dir: {}", dir.display()))?;
+    let dest = dir.join(file_name);
+    fs::copy(source, &dest).write_context(&dest, || format!("failed to copy data pack to {}", dest.display()))?;
+    Ok(dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_format_for_version() {
+        assert_eq!(pack_format_for(PackKind::Data, "1.20.1"), Some(15));
+        assert_eq!(pack_format_for(PackKind::Data, "1.21.3"), Some(57));
+        assert_eq!(pack_format_for(PackKind::Resource, "1.21.3"), Some(42));
+        assert_eq!(pack_format_for(PackKind::Data, "25w10a"), None);
+    }
+
+    #[test]
+    fn test_mcmeta_formats_and_warnings() {
+        let meta = parse_mcmeta(
+            r#"{"pack":{"pack_format":48,"supported_formats":{"min_inclusive":41,"max_inclusive":48},"description":[{"text":"Better "},"Caves"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(meta.description, "Better Caves");
+        assert_eq!(meta.supported_formats, Some((41, 48)));
+        assert_eq!(meta.target_versions(PackKind::Data).as_deref(), Some("1.20.5 - 1.21.1"));
+        assert!(meta.supports_format(41));
+
+        let meta = PackMeta {
+            data_namespaces: vec!["terralith".to_string()],
+            worldgen: true,
+            ..parse_mcmeta(r#"{"pack":{"pack_format":15,"description":"x"}}"#).unwrap()
+        };
+        assert_eq!(meta.warnings(PackKind::Data, Some("1.20.1")).len(), 1);
+        let warnings = meta.warnings(PackKind::Data, Some("1.21"));
+        assert!(warnings[0].contains("Minecraft 1.20 - 1.20.1"));
+        assert!(meta.warnings(PackKind::Resource, None)[0].contains("data pack"));
+    }
+}
@@ -1,9 +1,37 @@
-use anyhow::{Context, Result};
+use crate::disk::{DiskError, WriteContext};
+use crate::spaces::validate_space_name;
+use crate::util::copy_dir_all;
+use anyhow::{Context, Result, bail};
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that keeps everything under one directory
+pub const HOME_ENV: &str = "SHARD_HOME";
+
+/// Environment variable that overrides the active space
+pub const SPACE_ENV: &str = "SHARD_SPACE";
+
+/// Environment variable that opens the data dir read-only, e.g. to browse a
+/// data dir shared over the network without touching it
+pub const READ_ONLY_ENV: &str = "SHARD_READ_ONLY";
 
 #[derive(Debug, Clone)]
 pub struct Paths {
+    /// Shared data root (`SHARD_HOME` or `~/.shard`)
+    pub root: PathBuf,
+    /// Downloads and fetched metadata that can be thrown away at any time
+    pub cache_root: PathBuf,
+    /// Logs, jobs and other machine-local state worth keeping but not
+    /// backing up
+    pub state_root: PathBuf,
+    /// Launcher settings
+    pub config_root: PathBuf,
+    /// Active space; `None` uses the root directly
+    pub space: Option<String>,
+    /// Inspect mode: profiles, the store and the databases are only read,
+    /// and changes fail with an inspect [`DiskError`]
+    pub read_only: bool,
     pub store_mods: PathBuf,
     pub store_resourcepacks: PathBuf,
     pub store_shaderpacks: PathBuf,
@@ -25,27 +53,71 @@ pub struct Paths {
 }
 
 impl Paths {
+    /// `SHARD_HOME` keeps everything under one directory, as portable
+    /// installs and tests want. Otherwise data lives in `~/.shard` and
+    /// caches, state and config go to the platform's directories for them,
+    /// so cache cleaners leave user data alone and backups can skip caches.
     pub fn new() -> Result<Self> {
-        let mut base = if let Ok(value) = env::var("SHARD_HOME") {
-            PathBuf::from(value)
-        } else {
-            let home = dirs::home_dir().context("could not determine home directory")?;
-            home.join(".shard")
+        Self::with_data_dir(None)
+    }
+
+    /// Like [`Paths::new`], with `data_dir` (`--data-dir`) taking the place
+    /// of `SHARD_HOME` when given
+    pub fn with_data_dir(data_dir: Option<&Path>) -> Result<Self> {
+        let home = data_dir.map(Path::to_path_buf).or_else(|| env::var_os(HOME_ENV).map(PathBuf::from));
+        if let Some(mut base) = home {
+            if !base.is_absolute() {
+                let cwd = std::env::current_dir().context("failed to read current directory")?;
+                base = cwd.join(base);
+            }
+            return Ok(Self::at(base).with_read_only_setting());
+        }
+        let home = dirs::home_dir().context("could not determine home directory")?;
+        Ok(Self::with_roots(Roots::platform(home.join(".shard"))).with_read_only_setting())
+    }
+
+    /// Apply `SHARD_READ_ONLY`, else the config's `read_only` flag
+    fn with_read_only_setting(mut self) -> Self {
+        self.read_only = match env::var(READ_ONLY_ENV) {
+            Ok(value) => !matches!(value.trim().to_ascii_lowercase().as_str(), "" | "0" | "false" | "off"),
+            Err(_) => crate::config::load_config(&self).is_ok_and(|config| config.read_only),
         };
-        if !base.is_absolute() {
-            let cwd = std::env::current_dir().context("failed to read current directory")?;
-            base = cwd.join(base);
+        self
+    }
+
+    /// Fail with an inspect [`DiskError`] in inspect mode; called by every
+    /// API that changes the data dir before it touches anything
+    pub fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(DiskError::inspect(&self.root).into());
         }
+        Ok(())
+    }
+
+    /// Layout under an absolute data root, ignoring [`HOME_ENV`]
+    pub fn at(base: PathBuf) -> Self {
+        Self::with_roots(Roots::single(base))
+    }
+
+    fn with_roots(roots: Roots) -> Self {
+        let Roots { data: base, cache, state, config: config_dir } = roots;
+        let space = resolve_active_space(&base);
+        // Profiles, accounts and config are scoped to the active space;
+        // the content store, game files and caches stay shared.
+        let scoped = match &space {
+            Some(name) => base.join("spaces").join(name),
+            None => base.clone(),
+        };
 
         let store_mods = base.join("store").join("mods").join("sha256");
         let store_resourcepacks = base.join("store").join("resourcepacks").join("sha256");
         let store_shaderpacks = base.join("store").join("shaderpacks").join("sha256");
         let store_skins = base.join("store").join("skins").join("sha256");
-        let profiles = base.join("profiles");
-        let instances = base.join("instances");
-        let cache_downloads = base.join("caches").join("downloads");
-        let cache_manifests = base.join("caches").join("manifests");
-        let logs = base.join("logs");
+        let profiles = scoped.join("profiles");
+        let instances = scoped.join("instances");
+        let cache_downloads = cache.join("downloads");
+        let cache_manifests = cache.join("manifests");
+        let logs = state.join("logs");
 
         let minecraft_root = base.join("minecraft");
         let minecraft_versions = minecraft_root.join("versions");
@@ -53,13 +125,23 @@ impl Paths {
         let minecraft_assets_objects = minecraft_root.join("assets").join("objects");
         let minecraft_assets_indexes = minecraft_root.join("assets").join("indexes");
 
-        let accounts = base.join("accounts.json");
-        let config = base.join("config.json");
-        let library_db = base.join("library.db");
-        let profile_organization = base.join("profile-organization.json");
+        let accounts = scoped.join("accounts.json");
+        let config = match &space {
+            Some(name) => config_dir.join("spaces").join(name),
+            None => config_dir.clone(),
+        }
+        .join("config.json");
+        let library_db = scoped.join("library.db");
+        let profile_organization = scoped.join("profile-organization.json");
         let java_runtimes = base.join("java");
 
-        Ok(Self {
+        Self {
+            root: base,
+            cache_root: cache,
+            state_root: state,
+            config_root: config_dir,
+            space,
+            read_only: false,
             store_mods,
             store_resourcepacks,
             store_shaderpacks,
@@ -78,35 +160,45 @@ impl Paths {
             library_db,
             profile_organization,
             java_runtimes,
-        })
+        }
     }
 
     pub fn ensure(&self) -> Result<()> {
+        if self.read_only {
+            if !self.root.is_dir() {
+                bail!("data directory not found: {}", self.root.display());
+            }
+            return Ok(());
+        }
+        self.migrate_single_root_layout()?;
         std::fs::create_dir_all(&self.store_mods)
-            .context("failed to create store/mods directory")?;
+            .write_context(&self.store_mods, || "failed to create store/mods directory")?;
         std::fs::create_dir_all(&self.store_resourcepacks)
-            .context("failed to create store/resourcepacks directory")?;
+            .write_context(&self.store_resourcepacks, || "failed to create store/resourcepacks directory")?;
         std::fs::create_dir_all(&self.store_shaderpacks)
-            .context("failed to create store/shaderpacks directory")?;
+            .write_context(&self.store_shaderpacks, || "failed to create store/shaderpacks directory")?;
         std::fs::create_dir_all(&self.store_skins)
-            .context("failed to create store/skins directory")?;
-        std::fs::create_dir_all(&self.profiles).context("failed to create profiles directory")?;
-        std::fs::create_dir_all(&self.instances).context("failed to create instances directory")?;
+            .write_context(&self.store_skins, || "failed to create store/skins directory")?;
+        std::fs::create_dir_all(&self.profiles)
+            .write_context(&self.profiles, || "failed to create profiles directory")?;
+        std::fs::create_dir_all(&self.instances)
+            .write_context(&self.instances, || "failed to create instances directory")?;
         std::fs::create_dir_all(&self.cache_downloads)
-            .context("failed to create cache downloads directory")?;
+            .write_context(&self.cache_downloads, || "failed to create cache downloads directory")?;
         std::fs::create_dir_all(&self.cache_manifests)
-            .context("failed to create cache manifests directory")?;
-        std::fs::create_dir_all(&self.logs).context("failed to create logs directory")?;
+            .write_context(&self.cache_manifests, || "failed to create cache manifests directory")?;
+        std::fs::create_dir_all(&self.logs)
+            .write_context(&self.logs, || "failed to create logs directory")?;
         std::fs::create_dir_all(&self.minecraft_versions)
-            .context("failed to create minecraft versions directory")?;
+            .write_context(&self.minecraft_versions, || "failed to create minecraft versions directory")?;
         std::fs::create_dir_all(&self.minecraft_libraries)
-            .context("failed to create minecraft libraries directory")?;
+            .write_context(&self.minecraft_libraries, || "failed to create minecraft libraries directory")?;
         std::fs::create_dir_all(&self.minecraft_assets_objects)
-            .context("failed to create minecraft assets objects directory")?;
+            .write_context(&self.minecraft_assets_objects, || "failed to create minecraft assets objects directory")?;
         std::fs::create_dir_all(&self.minecraft_assets_indexes)
-            .context("failed to create minecraft assets indexes directory")?;
+            .write_context(&self.minecraft_assets_indexes, || "failed to create minecraft assets indexes directory")?;
         std::fs::create_dir_all(&self.java_runtimes)
-            .context("failed to create java runtimes directory")?;
+            .write_context(&self.java_runtimes, || "failed to create java runtimes directory")?;
         Ok(())
     }
 
@@ -122,24 +214,28 @@ impl Paths {
         self.profile_dir(id).join("overrides")
     }
 
+    pub fn profile_notes(&self, id: &str) -> PathBuf {
+        self.profile_dir(id).join("README.md")
+    }
+
     pub fn instance_dir(&self, id: &str) -> PathBuf {
         self.instances.join(id)
     }
 
     pub fn store_mod_path(&self, hash_hex: &str) -> PathBuf {
-        self.store_mods.join(hash_hex)
+        store_file_path(&self.store_mods, hash_hex)
     }
 
     pub fn store_resourcepack_path(&self, hash_hex: &str) -> PathBuf {
-        self.store_resourcepacks.join(hash_hex)
+        store_file_path(&self.store_resourcepacks, hash_hex)
     }
 
     pub fn store_shaderpack_path(&self, hash_hex: &str) -> PathBuf {
-        self.store_shaderpacks.join(hash_hex)
+        store_file_path(&self.store_shaderpacks, hash_hex)
     }
 
     pub fn store_skin_path(&self, hash_hex: &str) -> PathBuf {
-        self.store_skins.join(hash_hex)
+        store_file_path(&self.store_skins, hash_hex)
     }
 
     pub fn is_profile_present(&self, id: &str) -> bool {
@@ -158,10 +254,29 @@ impl Paths {
         self.minecraft_version_dir(id).join(format!("{id}.jar"))
     }
 
+    pub fn minecraft_server_jar(&self, id: &str) -> PathBuf {
+        self.minecraft_version_dir(id).join(format!("{id}-server.jar"))
+    }
+
     pub fn minecraft_library_path(&self, maven_path: &str) -> PathBuf {
         self.minecraft_libraries.join(maven_path)
     }
 
+    /// Natives extracted for a version on this OS and architecture, shared
+    /// by every instance of the version
+    pub fn minecraft_natives_dir(&self, version_id: &str) -> PathBuf {
+        self.minecraft_versions
+            .with_file_name("natives")
+            .join(format!("{version_id}-{}-{}", std::env::consts::OS, std::env::consts::ARCH))
+    }
+
+    /// Held while a version's files are downloaded and its loader installed
+    pub fn minecraft_prepare_lock(&self, mc_version: &str) -> PathBuf {
+        self.minecraft_versions
+            .with_file_name("locks")
+            .join(format!("{mc_version}.lock"))
+    }
+
     pub fn minecraft_asset_index(&self, id: &str) -> PathBuf {
         self.minecraft_assets_indexes.join(format!("{id}.json"))
     }
@@ -178,4 +293,200 @@ impl Paths {
     pub fn java_runtime_dir(&self, name: &str) -> PathBuf {
         self.java_runtimes.join(name)
     }
+
+    pub fn spaces_dir(&self) -> PathBuf {
+        self.root.join("spaces")
+    }
+
+    pub fn space_dir(&self, name: &str) -> PathBuf {
+        self.spaces_dir().join(name)
+    }
+
+    /// Where a space's config lives; the space dir itself unless config is
+    /// kept apart from data
+    pub fn space_config_dir(&self, name: &str) -> PathBuf {
+        self.config_root.join("spaces").join(name)
+    }
+
+    /// Move caches, state and config written by versions that kept
+    /// everything under the data root to their own directories. Anything
+    /// already at the new location is left alone.
+    fn migrate_single_root_layout(&self) -> Result<()> {
+        if self.state_root == self.root {
+            return Ok(());
+        }
+        let old = Self::at(self.root.clone());
+        let moves = [
+            (old.cache_downloads.clone(), self.cache_downloads.clone()),
+            (old.cache_manifests.clone(), self.cache_manifests.clone()),
+            (old.installer_logs_dir(), self.installer_logs_dir()),
+            (old.logs.clone(), self.logs.clone()),
+            (old.jobs_dir(), self.jobs_dir()),
+            (old.running_dir(), self.running_dir()),
+            (old.crash_stats_db(), self.crash_stats_db()),
+            (old.root.join("config.json"), self.config_root.join("config.json")),
+        ];
+        for (from, to) in moves {
+            move_legacy_entry(&from, &to)?;
+        }
+        if let Ok(entries) = fs::read_dir(old.spaces_dir()) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                move_legacy_entry(&entry.path().join("config.json"), &self.space_config_dir(&name).join("config.json"))?;
+            }
+        }
+        // Only removed once everything in it moved
+        let _ = fs::remove_dir(old.cache_root);
+        Ok(())
+    }
+
+    pub fn active_space_file(&self) -> PathBuf {
+        active_space_file(&self.root)
+    }
+}
+
+/// Top-level directories the layout is built from
+struct Roots {
+    data: PathBuf,
+    cache: PathBuf,
+    state: PathBuf,
+    config: PathBuf,
+}
+
+impl Roots {
+    /// Everything under `data`, as in the original layout
+    fn single(data: PathBuf) -> Self {
+        Self {
+            cache: data.join("caches"),
+            state: data.clone(),
+            config: data.clone(),
+            data,
+        }
+    }
+
+    /// The platform's cache, state and config directories, e.g.
+    /// `~/.cache/shard`, `~/.local/state/shard` and `~/.config/shard` on
+    /// Linux. Platforms without a state directory use the local data one.
+    fn platform(data: PathBuf) -> Self {
+        let shard = |dir: Option<PathBuf>| dir.map(|dir| dir.join("shard"));
+        let Some(cache) = shard(dirs::cache_dir()) else {
+            return Self::single(data);
+        };
+        let Some(state) = shard(dirs::state_dir().or_else(dirs::data_local_dir)) else {
+            return Self::single(data);
+        };
+        let Some(config) = shard(dirs::config_dir()) else {
+            return Self::single(data);
+        };
+        Self { data, cache, state, config }
+    }
+}
+
+/// Rename, or copy and delete when the target is on another filesystem
+fn move_legacy_entry(from: &Path, to: &Path) -> Result<()> {
+    if !from.exists() || to.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if from.is_dir() {
+        copy_dir_all(from, to)?;
+        fs::remove_dir_all(from).with_context(|| format!("failed to remove {}", from.display()))
+    } else {
+        fs::copy(from, to).write_context(to, || format!("failed to copy {} to {}", from.display(), to.display()))?;
+        fs::remove_file(from).with_context(|| format!("failed to remove {}", from.display()))
+    }
+}
+
+fn active_space_file(root: &Path) -> PathBuf {
+    root.join("active-space")
+}
+
+/// Active space from `SHARD_SPACE`, falling back to the `active-space` file.
+/// A name `create_space` wouldn't accept (such as `../x`, which would point
+/// outside `spaces/`) is ignored and the root used.
+fn resolve_active_space(root: &Path) -> Option<String> {
+    let name = match env::var(SPACE_ENV) {
+        Ok(value) => value,
+        Err(_) => std::fs::read_to_string(active_space_file(root)).ok()?,
+    };
+    let name = name.trim();
+    if name.is_empty() || name == "default" {
+        return None;
+    }
+    if let Err(err) = validate_space_name(name) {
+        eprintln!("warning: ignoring active space {name:?}: {err}");
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// Store file for a hash. Bare hex is SHA-256 and lives in `sha256_dir`;
+/// `blake3:<hex>` lives in the sibling `blake3` directory.
+fn store_file_path(sha256_dir: &Path, hash: &str) -> PathBuf {
+    match hash.strip_prefix("blake3:") {
+        Some(hex) => sha256_dir.with_file_name("blake3").join(hex),
+        None => sha256_dir.join(hash.strip_prefix("sha256:").unwrap_or(hash)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::TempDir;
+
+    #[test]
+    fn test_migrate_single_root_layout() {
+        let dir = TempDir::new("layout");
+        let data = dir.join("data");
+        let old = Paths::at(data.clone());
+        fs::create_dir_all(&old.cache_manifests).unwrap();
+        fs::write(old.cache_manifest("version_manifest_v2.json"), "{}").unwrap();
+        fs::create_dir_all(&old.logs).unwrap();
+        fs::write(old.logs.join("shard.log"), "log").unwrap();
+        fs::write(&old.config, "{}").unwrap();
+        fs::create_dir_all(old.space_dir("kids")).unwrap();
+        fs::write(old.space_dir("kids").join("config.json"), "{\"kids\":true}").unwrap();
+
+        let paths = Paths::with_roots(Roots {
+            data: data.clone(),
+            cache: dir.join("cache"),
+            state: dir.join("state"),
+            config: dir.join("config"),
+        });
+        paths.ensure().unwrap();
+        assert!(paths.cache_manifest("version_manifest_v2.json").exists());
+        assert_eq!(fs::read_to_string(paths.logs.join("shard.log")).unwrap(), "log");
+        assert_eq!(paths.config, dir.join("config").join("config.json"));
+        assert!(paths.config.exists());
+        assert_eq!(
+            fs::read_to_string(paths.space_config_dir("kids").join("config.json")).unwrap(),
+            "{\"kids\":true}"
+        );
+        assert!(!data.join("caches").exists());
+        assert!(!data.join("logs").exists());
+        assert!(!data.join("config.json").exists());
+    }
+
+    #[test]
+    fn test_with_data_dir() {
+        let dir = TempDir::new("data-dir");
+        let paths = Paths::with_data_dir(Some(&dir)).unwrap();
+        assert_eq!(paths.root, *dir);
+        assert_eq!(paths.config, dir.join("config.json"));
+        assert!(paths.cache_downloads.starts_with(&dir));
+    }
+
+    #[test]
+    fn test_active_space_stays_under_spaces() {
+        let dir = TempDir::new("space-name");
+        fs::write(active_space_file(&dir), "../../x\n").unwrap();
+        assert_eq!(resolve_active_space(&dir), None);
+        fs::write(active_space_file(&dir), "modded\n").unwrap();
+        assert_eq!(resolve_active_space(&dir).as_deref(), Some("modded"));
+    }
 }
@@ -0,0 +1,100 @@
+//! Per-profile playtime statistics
+//!
+//! Every finished game session is appended to `profiles/<id>/playtime.json`
+//! together with its resource usage summary.
+
+use crate::paths::Paths;
+use crate::resource_monitor::MetricsSummary;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Number of individual sessions kept; totals cover all sessions
+const MAX_SESSIONS: usize = 50;
+
+impl Paths {
+    pub fn profile_playtime(&self, id: &str) -> PathBuf {
+        self.profile_dir(id).join("playtime.json")
+    }
+}
+
+/// One finished game session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// Unix timestamp of the launch
+    pub started_at: u64,
+    pub duration_secs: u64,
+    #[serde(default)]
+    pub peak_rss_bytes: u64,
+    #[serde(default)]
+    pub peak_cpu_percent: f32,
+    #[serde(default)]
+    pub avg_cpu_percent: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+}
+
+impl SessionRecord {
+    pub fn new(started_at: u64, metrics: &MetricsSummary, exit_code: Option<i32>) -> Self {
+        Self {
+            started_at,
+            duration_secs: metrics.duration_secs,
+            peak_rss_bytes: metrics.peak_rss_bytes,
+            peak_cpu_percent: metrics.peak_cpu_percent,
+            avg_cpu_percent: metrics.avg_cpu_percent,
+            exit_code,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlaytimeStats {
+    #[serde(default)]
+    pub total_secs: u64,
+    #[serde(default)]
+    pub launches: u64,
+    #[serde(default)]
+    pub last_played: Option<u64>,
+    /// Highest resident memory seen in any session
+    #[serde(default)]
+    pub peak_rss_bytes: u64,
+    /// Most recent sessions, newest last
+    #[serde(default)]
+    pub sessions: Vec<SessionRecord>,
+}
+
+pub fn load_playtime(paths: &Paths, profile_id: &str) -> Result<PlaytimeStats> {
+    let path = paths.profile_playtime(profile_id);
+    if !path.exists() {
+        return Ok(PlaytimeStats::default());
+    }
+    let data = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read playtime stats: {}", path.display()))?;
+    serde_json::from_str(&data)
+        .with_context(|| format!("failed to parse playtime stats: {}", path.display()))
+}
+
+pub fn save_playtime(paths: &Paths, profile_id: &str, stats: &PlaytimeStats) -> Result<()> {
+    paths.ensure_writable()?;
+    let path = paths.profile_playtime(profile_id);
+    let data = serde_json::to_string_pretty(stats).context("failed to serialize playtime stats")?;
+    fs::write(&path, data)
+        .with_context(|| format!("failed to write playtime stats: {}", path.display()))
+}
+
+/// Append a finished session and update the totals
+pub fn record_session(paths: &Paths, profile_id: &str, session: SessionRecord) -> Result<PlaytimeStats> {
+    let mut stats = load_playtime(paths, profile_id)?;
+    stats.total_secs += session.duration_secs;
+    stats.launches += 1;
+    stats.last_played = Some(session.started_at);
+    stats.peak_rss_bytes = stats.peak_rss_bytes.max(session.peak_rss_bytes);
+    stats.sessions.push(session);
+    if stats.sessions.len() > MAX_SESSIONS {
+        let excess = stats.sessions.len() - MAX_SESSIONS;
+        stats.sessions.drain(..excess);
+    }
+    save_playtime(paths, profile_id, &stats)?;
+    Ok(stats)
+}
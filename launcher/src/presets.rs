@@ -0,0 +1,183 @@
+//! Named resource pack and shader pack presets per profile
+//!
+//! A preset ("performance", "cinematic") enables and disables groups of the
+//! profile's resource packs and shader packs, and can pick the shader pack
+//! the shader loader should use. Applying a preset saves the profile and
+//! writes the loader's shader config in the instance, so the next launch
+//! starts with it.
+
+use crate::disk::WriteContext;
+use crate::instance::instance_file_name;
+use crate::paths::Paths;
+use crate::profile::{ContentRef, Profile, ShaderLoader, load_profile, save_profile};
+use crate::store::ContentKind;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentPreset {
+    /// Resource/shader packs (name or hash) the preset enables
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enable: Vec<String>,
+    /// Resource/shader packs (name or hash) the preset disables
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disable: Vec<String>,
+    /// Shader pack made active (and enabled)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shaderpack: Option<String>,
+}
+
+/// Outcome of [`apply_preset`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetApplied {
+    pub profile: Profile,
+    /// Packs whose enabled flag changed
+    pub changed: Vec<String>,
+    /// Shader config written to select the shader pack
+    pub shader_config: Option<PathBuf>,
+    pub warnings: Vec<String>,
+}
+
+fn matches(item: &ContentRef, target: &str) -> bool {
+    item.name == target || item.hash == target || item.hash.strip_prefix("sha256:") == Some(target)
+}
+
+fn find_pack<'a>(profile: &'a mut Profile, target: &str) -> Option<&'a mut ContentRef> {
+    profile
+        .resourcepacks
+        .iter_mut()
+        .chain(profile.shaderpacks.iter_mut())
+        .find(|item| matches(item, target))
+}
+
+/// Check that every pack the preset names is in the profile
+fn validate_preset(profile: &Profile, preset: &ContentPreset) -> Result<()> {
+    let packs = || profile.resourcepacks.iter().chain(profile.shaderpacks.iter());
+    for target in preset.enable.iter().chain(preset.disable.iter()) {
+        if !packs().any(|item| matches(item, target)) {
+            bail!("resource pack or shader pack not found in profile {}: {target}", profile.id);
+        }
+    }
+    if let Some(target) = preset.enable.iter().find(|target| preset.disable.contains(target)) {
+        bail!("preset both enables and disables {target}");
+    }
+    if let Some(shader) = &preset.shaderpack
+        && !profile.shaderpacks.iter().any(|item| matches(item, shader))
+    {
+        bail!("shader pack not found in profile {}: {shader}", profile.id);
+    }
+    Ok(())
+}
+
+/// Create or replace a preset
+pub fn save_preset(paths: &Paths, profile_id: &str, name: &str, preset: ContentPreset) -> Result<Profile> {
+    paths.ensure_writable()?;
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("preset name cannot be empty");
+    }
+    let mut profile = load_profile(paths, profile_id)?;
+    validate_preset(&profile, &preset)?;
+    profile.presets.insert(name.to_string(), preset);
+    save_profile(paths, &profile)?;
+    Ok(profile)
+}
+
+pub fn delete_preset(paths: &Paths, profile_id: &str, name: &str) -> Result<Profile> {
+    paths.ensure_writable()?;
+    let mut profile = load_profile(paths, profile_id)?;
+    if profile.presets.remove(name).is_none() {
+        bail!("preset not found in profile {profile_id}: {name}");
+    }
+    save_profile(paths, &profile)?;
+    Ok(profile)
+}
+
+/// Toggle the preset's packs, select its shader pack and save the profile
+pub fn apply_preset(paths: &Paths, profile_id: &str, name: &str) -> Result<PresetApplied> {
+    let mut profile = load_profile(paths, profile_id)?;
+    let preset = profile
+        .presets
+        .get(name)
+        .cloned()
+        .with_context(|| format!("preset not found in profile {profile_id}: {name}"))?;
+    validate_preset(&profile, &preset)?;
+
+    let toggles = preset
+        .enable
+        .iter()
+        .chain(preset.shaderpack.iter())
+        .map(|target| (target, true))
+        .chain(preset.disable.iter().map(|target| (target, false)));
+    let mut changed = Vec::new();
+    for (target, enabled) in toggles {
+        if let Some(item) = find_pack(&mut profile, target)
+            && item.enabled != enabled
+        {
+            item.enabled = enabled;
+            changed.push(item.name.clone());
+        }
+    }
+    save_profile(paths, &profile)?;
+
+    let mut warnings = Vec::new();
+    let mut shader_config = None;
+    if let Some(target) = &preset.shaderpack {
+        let item = profile
+            .shaderpacks
+            .iter()
+            .find(|item| matches(item, target))
+            .context("shader pack disappeared from profile")?;
+        let file_name = instance_file_name(item, ContentKind::ShaderPack);
+        let instance_dir = paths.instance_dir(&profile.id);
+        match profile.primary_shader_loader() {
+            Some(ShaderLoader::Iris) => {
+                let path = instance_dir.join("config").join("iris.properties");
+                set_properties(&path, &[("shaderPack", &file_name), ("enableShaders", "true")])?;
+                shader_config = Some(path);
+            }
+            Some(ShaderLoader::Optifine) => {
+                let path = instance_dir.join("optionsshaders.txt");
+                set_properties(&path, &[("shaderPack", &file_name)])?;
+                shader_config = Some(path);
+            }
+            Some(ShaderLoader::Canvas) => {
+                warnings.push("Canvas does not load shader packs; the pack was only enabled".to_string());
+            }
+            None => {
+                warnings.push("no shader loader (Iris, OptiFine) in the profile; the pack was only enabled".to_string());
+            }
+        }
+    }
+
+    Ok(PresetApplied {
+        profile,
+        changed,
+        shader_config,
+        warnings,
+    })
+}
+
+/// Set `key=value` entries in a properties-style file, keeping other lines
+fn set_properties(path: &Path, entries: &[(&str, &str)]) -> Result<()> {
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let mut lines: Vec<String> = existing.lines().map(str::to_string).collect();
+    for (key, value) in entries {
+        let entry = format!("{key}={value}");
+        let found = lines
+            .iter_mut()
+            .find(|line| line.split_once('=').is_some_and(|(k, _)| k.trim() == *key));
+        match found {
+            Some(line) => *line = entry,
+            None => lines.push(entry),
+        }
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    let mut data = lines.join("\n");
+    data.push('\n');
+    fs::write(path, data).write_context(path, || format!("failed to write {}", path.display()))
+}
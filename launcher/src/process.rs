@@ -0,0 +1,156 @@
+//! Running game instances
+//!
+//! Every launched game is recorded under `running/` with its PID and start
+//! time, so any shard process (a second CLI call, the desktop app) can list
+//! and stop it. A record whose process is gone, or whose PID was reused by an
+//! unrelated process, is treated as stale and removed when listing.
+
+use crate::disk::WriteContext;
+use crate::paths::Paths;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System};
+
+/// How long a game gets to exit after a polite termination request
+pub const KILL_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Slack between the recorded start time and the one the OS reports
+const START_TIME_TOLERANCE_SECS: u64 = 5;
+
+impl Paths {
+    pub fn running_dir(&self) -> PathBuf {
+        self.state_root.join("running")
+    }
+
+    fn running_record(&self, profile_id: &str, pid: u32) -> PathBuf {
+        self.running_dir().join(format!("{profile_id}.{pid}.json"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunningInstance {
+    pub profile_id: String,
+    pub pid: u32,
+    /// Epoch seconds
+    pub started_at: u64,
+}
+
+/// Record a game process that was just started
+pub fn register_instance(paths: &Paths, profile_id: &str, pid: u32, started_at: u64) -> Result<RunningInstance> {
+    let instance = RunningInstance {
+        profile_id: profile_id.to_string(),
+        pid,
+        started_at,
+    };
+    let dir = paths.running_dir();
+    fs::create_dir_all(&dir).write_context(&dir, || format!("failed to create dir: {}", dir.display()))?;
+    let path = paths.running_record(profile_id, pid);
+    let data = serde_json::to_string_pretty(&instance).context("failed to serialize running instance")?;
+    fs::write(&path, data).write_context(&path, || format!("failed to write {}", path.display()))?;
+    Ok(instance)
+}
+
+/// Forget a game process once it exited
+pub fn unregister_instance(paths: &Paths, instance: &RunningInstance) {
+    let _ = fs::remove_file(paths.running_record(&instance.profile_id, instance.pid));
+}
+
+fn refresh_process(system: &mut System, pid: Pid) {
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, ProcessRefreshKind::nothing());
+}
+
+/// Whether the recorded process is still the game we started
+fn is_alive(system: &System, instance: &RunningInstance) -> bool {
+    system
+        .process(Pid::from_u32(instance.pid))
+        .is_some_and(|process| {
+            process.status() != ProcessStatus::Zombie
+                && process.start_time().abs_diff(instance.started_at) <= START_TIME_TOLERANCE_SECS
+        })
+}
+
+/// Running instances, oldest first. Stale records are removed.
+pub fn list_running(paths: &Paths) -> Result<Vec<RunningInstance>> {
+    let dir = paths.running_dir();
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut system = System::new();
+    let mut running = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let record = fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<RunningInstance>(&data).ok());
+        let Some(instance) = record else {
+            let _ = fs::remove_file(&path);
+            continue;
+        };
+        refresh_process(&mut system, Pid::from_u32(instance.pid));
+        if is_alive(&system, &instance) {
+            running.push(instance);
+        } else {
+            let _ = fs::remove_file(&path);
+        }
+    }
+    running.sort_by_key(|instance| (instance.started_at, instance.pid));
+    Ok(running)
+}
+
+/// Running instances of one profile
+pub fn running_for_profile(paths: &Paths, profile_id: &str) -> Result<Vec<RunningInstance>> {
+    Ok(list_running(paths)?
+        .into_iter()
+        .filter(|instance| instance.profile_id == profile_id)
+        .collect())
+}
+
+/// Stop every running instance of a profile: ask politely, then force after
+/// `grace`. Returns the instances that were stopped.
+pub fn kill_profile(paths: &Paths, profile_id: &str, grace: Duration) -> Result<Vec<RunningInstance>> {
+    let instances = running_for_profile(paths, profile_id)?;
+    if instances.is_empty() {
+        bail!("no running instance of profile {profile_id}");
+    }
+
+    let mut system = System::new();
+    for instance in &instances {
+        let pid = Pid::from_u32(instance.pid);
+        refresh_process(&mut system, pid);
+        if let Some(process) = system.process(pid) {
+            // Windows has no SIGTERM; kill_with returns None there
+            if process.kill_with(Signal::Term).is_none() {
+                process.kill();
+            }
+        }
+    }
+
+    let deadline = Instant::now() + grace;
+    for instance in &instances {
+        let pid = Pid::from_u32(instance.pid);
+        loop {
+            refresh_process(&mut system, pid);
+            if !is_alive(&system, instance) {
+                break;
+            }
+            if Instant::now() >= deadline {
+                if let Some(process) = system.process(pid)
+                    && !process.kill()
+                {
+                    bail!("failed to kill process {} of profile {profile_id}", instance.pid);
+                }
+                break;
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+        unregister_instance(paths, instance);
+    }
+    Ok(instances)
+}
@@ -1,14 +1,25 @@
+use crate::disk::WriteContext;
+use crate::lockfile::write_lockfile;
 use crate::paths::Paths;
-use crate::util::copy_dir_all;
+use crate::presets::ContentPreset;
+use crate::tunnel::TunnelConfig;
+use crate::util::clone_dir_all;
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
     pub id: String,
     #[serde(rename = "mcVersion")]
     pub mc_version: String,
+    /// Whether the profile is a game client or a dedicated server
+    #[serde(default, skip_serializing_if = "ProfileKind::is_client")]
+    pub kind: ProfileKind,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub loader: Option<Loader>,
     #[serde(default)]
@@ -21,6 +32,49 @@ pub struct Profile {
     pub runtime: Runtime,
     #[serde(default)]
     pub files: Files,
+    /// Tunnel started alongside launches to expose the server publicly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tunnel: Option<TunnelConfig>,
+    /// Named resource pack/shader pack presets
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub presets: BTreeMap<String, ContentPreset>,
+}
+
+/// What a profile runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileKind {
+    #[default]
+    Client,
+    /// A dedicated server, started with `shard server start`
+    Server,
+}
+
+impl ProfileKind {
+    pub const ALL: [ProfileKind; 2] = [ProfileKind::Client, ProfileKind::Server];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProfileKind::Client => "client",
+            ProfileKind::Server => "server",
+        }
+    }
+
+    pub fn is_client(&self) -> bool {
+        *self == ProfileKind::Client
+    }
+}
+
+impl FromStr for ProfileKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "client" => Ok(ProfileKind::Client),
+            "server" => Ok(ProfileKind::Server),
+            other => bail!("unknown profile kind: {other} (expected client or server)"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,6 +132,191 @@ pub struct Runtime {
     pub memory: Option<String>,
     #[serde(default)]
     pub args: Vec<String>,
+    /// Command the java invocation runs under, e.g. `gamemoderun` or
+    /// `mangohud`; the first entry is the binary, the rest its arguments
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wrapper: Vec<String>,
+    /// Curated GC flags added before `args`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jvm_preset: Option<JvmPreset>,
+    /// Scheduling priority of the game process; the OS default when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<ProcessPriority>,
+    /// CPU cores (0-based) the game may run on; empty for all of them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cpu_affinity: Vec<usize>,
+    /// Start fullscreen at a fixed resolution, set up for Gamescope and
+    /// Steam Input (Steam Deck game mode)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gamescope: Option<Gamescope>,
+}
+
+/// Curated JVM flag sets. Flags in `args` come after the preset's, so they
+/// win where both set the same option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JvmPreset {
+    /// Aikar's G1 tuning: short pauses with large heaps, suits modpacks
+    Aikar,
+    /// G1 with the flags the official launcher passes
+    G1gc,
+    /// Generational ZGC for the lowest pauses; needs Java 21
+    Zgc,
+}
+
+impl JvmPreset {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JvmPreset::Aikar => "aikar",
+            JvmPreset::G1gc => "g1gc",
+            JvmPreset::Zgc => "zgc",
+        }
+    }
+
+    pub fn jvm_flags(self) -> &'static [&'static str] {
+        match self {
+            JvmPreset::Aikar => &[
+                "-XX:+UseG1GC",
+                "-XX:+ParallelRefProcEnabled",
+                "-XX:MaxGCPauseMillis=200",
+                "-XX:+UnlockExperimentalVMOptions",
+                "-XX:+DisableExplicitGC",
+                "-XX:+AlwaysPreTouch",
+                "-XX:G1NewSizePercent=30",
+                "-XX:G1MaxNewSizePercent=40",
+                "-XX:G1HeapRegionSize=8M",
+                "-XX:G1ReservePercent=20",
+                "-XX:G1HeapWastePercent=5",
+                "-XX:G1MixedGCCountTarget=4",
+                "-XX:InitiatingHeapOccupancyPercent=15",
+                "-XX:G1MixedGCLiveThresholdPercent=90",
+                "-XX:G1RSetUpdatingPauseTimePercent=5",
+                "-XX:SurvivorRatio=32",
+                "-XX:+PerfDisableSharedMem",
+                "-XX:MaxTenuringThreshold=1",
+            ],
+            JvmPreset::G1gc => &[
+                "-XX:+UnlockExperimentalVMOptions",
+                "-XX:+UseG1GC",
+                "-XX:G1NewSizePercent=20",
+                "-XX:G1ReservePercent=20",
+                "-XX:MaxGCPauseMillis=50",
+                "-XX:G1HeapRegionSize=32M",
+            ],
+            JvmPreset::Zgc => &[
+                "-XX:+UseZGC",
+                "-XX:+ZGenerational",
+                "-XX:+AlwaysPreTouch",
+                "-XX:+DisableExplicitGC",
+            ],
+        }
+    }
+}
+
+impl FromStr for JvmPreset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "aikar" => Ok(JvmPreset::Aikar),
+            "g1gc" | "g1" => Ok(JvmPreset::G1gc),
+            "zgc" => Ok(JvmPreset::Zgc),
+            other => bail!("unknown JVM preset: {other} (expected aikar, g1gc or zgc)"),
+        }
+    }
+}
+
+/// Process priority, mapped to a nice level on Linux and macOS and a
+/// priority class on Windows. Above normal needs root (or `CAP_SYS_NICE`) on
+/// Linux and macOS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessPriority {
+    Low,
+    BelowNormal,
+    Normal,
+    AboveNormal,
+    High,
+}
+
+impl ProcessPriority {
+    pub const ALL: [ProcessPriority; 5] = [
+        ProcessPriority::Low,
+        ProcessPriority::BelowNormal,
+        ProcessPriority::Normal,
+        ProcessPriority::AboveNormal,
+        ProcessPriority::High,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProcessPriority::Low => "low",
+            ProcessPriority::BelowNormal => "below_normal",
+            ProcessPriority::Normal => "normal",
+            ProcessPriority::AboveNormal => "above_normal",
+            ProcessPriority::High => "high",
+        }
+    }
+
+    /// Unix nice level; lower runs first
+    pub fn nice(self) -> i32 {
+        match self {
+            ProcessPriority::Low => 10,
+            ProcessPriority::BelowNormal => 5,
+            ProcessPriority::Normal => 0,
+            ProcessPriority::AboveNormal => -5,
+            ProcessPriority::High => -10,
+        }
+    }
+}
+
+impl FromStr for ProcessPriority {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "low" | "idle" => Ok(ProcessPriority::Low),
+            "below_normal" => Ok(ProcessPriority::BelowNormal),
+            "normal" => Ok(ProcessPriority::Normal),
+            "above_normal" => Ok(ProcessPriority::AboveNormal),
+            "high" => Ok(ProcessPriority::High),
+            other => bail!("unknown priority: {other} (expected low, below-normal, normal, above-normal or high)"),
+        }
+    }
+}
+
+/// Resolution the game fills under Gamescope; written `1280x800`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Gamescope {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for Gamescope {
+    /// The Steam Deck's screen
+    fn default() -> Self {
+        Self { width: 1280, height: 800 }
+    }
+}
+
+impl fmt::Display for Gamescope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+impl FromStr for Gamescope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parsed = s.trim().to_ascii_lowercase().split_once('x').and_then(|(width, height)| {
+            Some(Self { width: width.trim().parse().ok()?, height: height.trim().parse().ok()? })
+        });
+        match parsed {
+            Some(size) if size.width > 0 && size.height > 0 => Ok(size),
+            _ => bail!("invalid resolution: {s} (expected WIDTHxHEIGHT, e.g. 1280x800)"),
+        }
+    }
 }
 
 
@@ -85,16 +324,27 @@ pub struct Runtime {
 pub struct Files {
     #[serde(default = "default_overrides")]
     pub config_overrides: String,
+    /// Instance folders carried along on export and clone (script/config packs)
+    #[serde(default = "default_extra_folders")]
+    pub extra_folders: Vec<String>,
 }
 
 fn default_overrides() -> String {
     "overrides".to_string()
 }
 
+fn default_extra_folders() -> Vec<String> {
+    ["kubejs", "scripts", "defaultconfigs"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
 impl Default for Files {
     fn default() -> Self {
         Self {
             config_overrides: default_overrides(),
+            extra_folders: default_extra_folders(),
         }
     }
 }
@@ -109,14 +359,15 @@ pub fn load_profile(paths: &Paths, id: &str) -> Result<Profile> {
 }
 
 pub fn save_profile(paths: &Paths, profile: &Profile) -> Result<()> {
+    paths.ensure_writable()?;
     let dir = paths.profile_dir(&profile.id);
     fs::create_dir_all(&dir)
-        .with_context(|| format!("failed to create profile directory: {}", dir.display()))?;
+        .write_context(&dir, || format!("failed to create profile directory: {}", dir.display()))?;
     let path = paths.profile_json(&profile.id);
     let data = serde_json::to_string_pretty(profile).context("failed to serialize profile")?;
     fs::write(&path, data)
-        .with_context(|| format!("failed to write profile file: {}", path.display()))?;
-    Ok(())
+        .write_context(&path, || format!("failed to write profile file: {}", path.display()))?;
+    write_lockfile(paths, profile)
 }
 
 pub fn list_profiles(paths: &Paths) -> Result<Vec<String>> {
@@ -148,23 +399,27 @@ pub fn create_profile(
     loader: Option<Loader>,
     runtime: Runtime,
 ) -> Result<Profile> {
+    paths.ensure_writable()?;
     if paths.is_profile_present(id) {
         bail!("profile already exists: {id}");
     }
     let profile = Profile {
         id: id.to_string(),
         mc_version: mc_version.to_string(),
+        kind: ProfileKind::Client,
         loader,
         mods: Vec::new(),
         resourcepacks: Vec::new(),
         shaderpacks: Vec::new(),
         runtime,
         files: Files::default(),
+        tunnel: None,
+        presets: BTreeMap::new(),
     };
     save_profile(paths, &profile)?;
 
     let overrides_dir = paths.profile_overrides(id);
-    fs::create_dir_all(&overrides_dir).with_context(|| {
+    fs::create_dir_all(&overrides_dir).write_context(&overrides_dir, || {
         format!(
             "failed to create overrides dir: {}",
             overrides_dir.display()
@@ -174,7 +429,31 @@ pub fn create_profile(
     Ok(profile)
 }
 
-pub fn clone_profile(paths: &Paths, src: &str, dst: &str) -> Result<Profile> {
+/// Instance entries not copied when cloning: content folders (and the list of
+/// files placed in them) are rebuilt from the store on launch, and history
+/// and logs belong to the source
+const CLONE_SKIP: &[&str] = &[
+    "mods",
+    "resourcepacks",
+    "shaderpacks",
+    ".shard-backups",
+    ".shard-managed.json",
+    "logs",
+    "crash-reports",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloneMode {
+    /// Manifest, overrides, notes and the instance (worlds, configs, ...)
+    #[default]
+    Full,
+    /// Manifest and notes only; the clone starts with a fresh instance
+    ManifestOnly,
+}
+
+pub fn clone_profile(paths: &Paths, src: &str, dst: &str, mode: CloneMode) -> Result<Profile> {
+    paths.ensure_writable()?;
     if paths.is_profile_present(dst) {
         bail!("profile already exists: {dst}");
     }
@@ -186,10 +465,10 @@ pub fn clone_profile(paths: &Paths, src: &str, dst: &str) -> Result<Profile> {
 
     let src_overrides = paths.profile_overrides(src);
     let dst_overrides = paths.profile_overrides(dst);
-    if src_overrides.exists() {
-        copy_dir_all(&src_overrides, &dst_overrides)?;
+    if mode == CloneMode::Full && src_overrides.exists() {
+        clone_dir_all(&src_overrides, &dst_overrides, &[])?;
     } else {
-        fs::create_dir_all(&dst_overrides).with_context(|| {
+        fs::create_dir_all(&dst_overrides).write_context(&dst_overrides, || {
             format!(
                 "failed to create overrides dir: {}",
                 dst_overrides.display()
@@ -197,22 +476,73 @@ pub fn clone_profile(paths: &Paths, src: &str, dst: &str) -> Result<Profile> {
         })?;
     }
 
+    let src_notes = paths.profile_notes(src);
+    if src_notes.exists() {
+        let dst_notes = paths.profile_notes(dst);
+        fs::copy(&src_notes, &dst_notes)
+            .write_context(&dst_notes, || format!("failed to copy profile notes: {}", src_notes.display()))?;
+    }
+
+    // Copy-on-write where supported, so large worlds clone almost instantly
+    if mode == CloneMode::Full {
+        clone_dir_all(&paths.instance_dir(src), &paths.instance_dir(dst), CLONE_SKIP)?;
+    }
+
     Ok(profile)
 }
 
+/// Markdown notes shipped with the profile, if any
+pub fn load_profile_notes(paths: &Paths, id: &str) -> Result<Option<String>> {
+    let path = paths.profile_notes(id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let notes = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read profile notes: {}", path.display()))?;
+    Ok(Some(notes))
+}
+
+/// Save profile notes; blank notes remove the file
+pub fn save_profile_notes(paths: &Paths, id: &str, notes: &str) -> Result<()> {
+    paths.ensure_writable()?;
+    if !paths.is_profile_present(id) {
+        bail!("profile not found: {id}");
+    }
+    let path = paths.profile_notes(id);
+    if notes.trim().is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)
+                .write_context(&path, || format!("failed to remove profile notes: {}", path.display()))?;
+        }
+        return Ok(());
+    }
+    fs::write(&path, notes)
+        .write_context(&path, || format!("failed to write profile notes: {}", path.display()))
+}
+
+/// Extra folder names must be a single plain path component
+pub fn is_plain_folder_name(name: &str) -> bool {
+    let mut components = std::path::Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    )
+}
+
 pub fn delete_profile(paths: &Paths, id: &str) -> Result<()> {
+    paths.ensure_writable()?;
     let profile_dir = paths.profiles.join(id);
     if !profile_dir.exists() {
         bail!("profile not found: {id}");
     }
 
     fs::remove_dir_all(&profile_dir)
-        .with_context(|| format!("failed to delete profile directory: {}", profile_dir.display()))?;
+        .write_context(&profile_dir, || format!("failed to delete profile directory: {}", profile_dir.display()))?;
 
     // Also remove the instance directory if it exists
     let instance_dir = paths.instances.join(id);
     if instance_dir.exists() {
-        fs::remove_dir_all(&instance_dir).with_context(|| {
+        fs::remove_dir_all(&instance_dir).write_context(&instance_dir, || {
             format!(
                 "failed to delete instance directory: {}",
                 instance_dir.display()
@@ -223,13 +553,32 @@ pub fn delete_profile(paths: &Paths, id: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn rename_profile(paths: &Paths, id: &str, new_id: &str) -> Result<Profile> {
+/// Directories deleted with a profile or moved when it is renamed: its
+/// manifest directory and, once it was launched, its instance
+pub fn profile_dirs(paths: &Paths, id: &str) -> Vec<PathBuf> {
+    [paths.profiles.join(id), paths.instances.join(id)]
+        .into_iter()
+        .filter(|dir| dir.exists())
+        .collect()
+}
+
+/// Whether `rename_profile` would go ahead, checked without changing anything
+pub fn check_rename(paths: &Paths, id: &str, new_id: &str) -> Result<()> {
     if id == new_id {
         bail!("new profile ID is the same as the current one");
     }
     if paths.is_profile_present(new_id) {
         bail!("profile already exists: {new_id}");
     }
+    if !paths.is_profile_present(id) {
+        bail!("profile not found: {id}");
+    }
+    Ok(())
+}
+
+pub fn rename_profile(paths: &Paths, id: &str, new_id: &str) -> Result<Profile> {
+    paths.ensure_writable()?;
+    check_rename(paths, id, new_id)?;
 
     // Load and update the profile
     let mut profile = load_profile(paths, id)
@@ -240,7 +589,7 @@ pub fn rename_profile(paths: &Paths, id: &str, new_id: &str) -> Result<Profile>
     let old_dir = paths.profiles.join(id);
     let new_dir = paths.profiles.join(new_id);
     fs::rename(&old_dir, &new_dir)
-        .with_context(|| format!("failed to rename profile directory: {} -> {}", old_dir.display(), new_dir.display()))?;
+        .write_context(&new_dir, || format!("failed to rename profile directory: {} -> {}", old_dir.display(), new_dir.display()))?;
 
     // Save the profile with the new ID
     save_profile(paths, &profile)?;
@@ -249,7 +598,7 @@ pub fn rename_profile(paths: &Paths, id: &str, new_id: &str) -> Result<Profile>
     let old_instance = paths.instances.join(id);
     let new_instance = paths.instances.join(new_id);
     if old_instance.exists() {
-        fs::rename(&old_instance, &new_instance).with_context(|| {
+        fs::rename(&old_instance, &new_instance).write_context(&new_instance, || {
             format!(
                 "failed to rename instance directory: {} -> {}",
                 old_instance.display(),
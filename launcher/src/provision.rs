@@ -0,0 +1,357 @@
+//! Declarative setup from a plan file
+//!
+//! A plan lists profiles with their Minecraft version, loader, runtime
+//! options and store content, plus the account to make active. Applying it
+//! creates what is missing and changes what differs, leaving everything else
+//! alone, so the same plan can be run on many machines (or again on one) to
+//! end up with the same setup. Plans are JSON or YAML, told apart by a
+//! `.yaml`/`.yml` extension or, failing that, by whether the file opens
+//! with `{`:
+//!
+//! ```json
+//! {
+//!   "profiles": [{
+//!     "id": "survival",
+//!     "mc_version": "1.21.1",
+//!     "loader": "fabric@0.16.9",
+//!     "runtime": { "memory": "4G", "jvm_preset": "aikar" },
+//!     "content": [{ "project": "sodium" }, { "project": "fabric-api" }]
+//!   }],
+//!   "active_account": "Steve"
+//! }
+//! ```
+//!
+//! ```yaml
+//! profiles:
+//!   - id: survival
+//!     mc_version: "1.21.1"
+//!     loader: fabric@0.16.9
+//!     content:
+//!       - project: sodium
+//! active_account: Steve
+//! ```
+
+use crate::accounts::{load_accounts, save_accounts, set_active};
+use crate::content_store::{ContentStore, ContentType, Platform, store_loader_filter};
+use crate::ops::parse_loader;
+use crate::paths::Paths;
+use crate::profile::{
+    ContentRef, Gamescope, JvmPreset, Loader, ProcessPriority, Profile, Runtime, create_profile, load_profile, save_profile, upsert_mod,
+    upsert_resourcepack, upsert_shaderpack,
+};
+use crate::scheduling::format_cpu_list;
+use crate::store::{HashAlgorithm, configured_store_hash};
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProvisionPlan {
+    #[serde(default)]
+    pub profiles: Vec<PlannedProfile>,
+    /// Username or uuid of a signed-in account to make active
+    #[serde(default)]
+    pub active_account: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlannedProfile {
+    pub id: String,
+    pub mc_version: String,
+    /// `type@version`, e.g. `fabric@0.16.9`
+    #[serde(default)]
+    pub loader: Option<String>,
+    #[serde(default)]
+    pub runtime: PlannedRuntime,
+    #[serde(default)]
+    pub content: Vec<PlannedContent>,
+}
+
+/// Runtime options to set; unset ones keep the profile's value
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PlannedRuntime {
+    #[serde(default)]
+    pub java: Option<String>,
+    #[serde(default)]
+    pub memory: Option<String>,
+    #[serde(default)]
+    pub args: Option<Vec<String>>,
+    #[serde(default)]
+    pub wrapper: Option<Vec<String>>,
+    #[serde(default)]
+    pub jvm_preset: Option<JvmPreset>,
+    #[serde(default)]
+    pub priority: Option<ProcessPriority>,
+    #[serde(default)]
+    pub cpu_affinity: Option<Vec<usize>>,
+    #[serde(default)]
+    pub gamescope: Option<Gamescope>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlannedContent {
+    /// Project slug or id
+    pub project: String,
+    #[serde(default = "default_platform")]
+    pub platform: Platform,
+    /// Defaults to the project's own type
+    #[serde(default)]
+    pub content_type: Option<ContentType>,
+    /// Version number or id; the newest compatible one when unset
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+fn default_platform() -> Platform {
+    Platform::Modrinth
+}
+
+pub fn load_plan(path: &Path) -> Result<ProvisionPlan> {
+    let data = fs::read_to_string(path).with_context(|| format!("failed to read plan: {}", path.display()))?;
+    let yaml = match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => true,
+        Some(ext) if ext.eq_ignore_ascii_case("json") => false,
+        _ => !data.trim_start().starts_with('{'),
+    };
+    parse_plan(&data, yaml).with_context(|| format!("failed to parse plan: {}", path.display()))
+}
+
+fn parse_plan(data: &str, yaml: bool) -> Result<ProvisionPlan> {
+    if yaml {
+        Ok(serde_yaml::from_str(data)?)
+    } else {
+        Ok(serde_json::from_str(data)?)
+    }
+}
+
+/// Bring profiles and accounts in line with `plan`. Returns a line per
+/// change, `+` for something added and `~` for something changed.
+pub fn apply_plan(paths: &Paths, store: &ContentStore, plan: &ProvisionPlan) -> Result<Vec<String>> {
+    paths.ensure_writable()?;
+    let algorithm = configured_store_hash(paths);
+    let mut changes = Vec::new();
+    for planned in &plan.profiles {
+        apply_profile(paths, store, algorithm, planned, &mut changes)
+            .with_context(|| format!("profile {}", planned.id))?;
+    }
+
+    if let Some(target) = &plan.active_account {
+        let mut accounts = load_accounts(paths)?;
+        let previous = accounts.active.clone();
+        if !set_active(&mut accounts, target) {
+            bail!("account not found: {target} (sign in with `shard account add` first)");
+        }
+        if accounts.active != previous {
+            save_accounts(paths, &accounts)?;
+            changes.push(format!("~ active account: {target}"));
+        }
+    }
+    Ok(changes)
+}
+
+fn apply_profile(
+    paths: &Paths,
+    store: &ContentStore,
+    algorithm: HashAlgorithm,
+    planned: &PlannedProfile,
+    changes: &mut Vec<String>,
+) -> Result<()> {
+    let id = &planned.id;
+    let loader = planned.loader.as_deref().map(parse_loader).transpose()?;
+    let mut profile = if paths.is_profile_present(id) {
+        let mut profile = load_profile(paths, id)?;
+        if profile.mc_version != planned.mc_version {
+            changes.push(format!("~ {id}: minecraft {} -> {}", profile.mc_version, planned.mc_version));
+            profile.mc_version = planned.mc_version.clone();
+        }
+        if let Some(loader) = loader
+            && !same_loader(profile.loader.as_ref(), &loader)
+        {
+            let from = describe_loader(profile.loader.as_ref());
+            changes.push(format!("~ {id}: loader {from} -> {}", describe_loader(Some(&loader))));
+            profile.loader = Some(loader);
+        }
+        profile
+    } else {
+        changes.push(format!("+ {id}: minecraft {}, {}", planned.mc_version, describe_loader(loader.as_ref())));
+        create_profile(paths, id, &planned.mc_version, loader, Runtime::default())?
+    };
+
+    apply_runtime(&mut profile.runtime, &planned.runtime, id, changes);
+    save_profile(paths, &profile)?;
+
+    for content in &planned.content {
+        install_content(paths, store, algorithm, &mut profile, content, changes)
+            .with_context(|| format!("failed to install {}", content.project))?;
+    }
+    Ok(())
+}
+
+fn apply_runtime(runtime: &mut Runtime, planned: &PlannedRuntime, id: &str, changes: &mut Vec<String>) {
+    if planned.java.is_some() && runtime.java != planned.java {
+        runtime.java = planned.java.clone();
+        changes.push(format!("~ {id}: java {}", planned.java.as_deref().unwrap_or_default()));
+    }
+    if planned.memory.is_some() && runtime.memory != planned.memory {
+        runtime.memory = planned.memory.clone();
+        changes.push(format!("~ {id}: memory {}", planned.memory.as_deref().unwrap_or_default()));
+    }
+    if let Some(args) = &planned.args
+        && &runtime.args != args
+    {
+        runtime.args = args.clone();
+        changes.push(format!("~ {id}: jvm args {}", args.join(" ")));
+    }
+    if let Some(wrapper) = &planned.wrapper
+        && &runtime.wrapper != wrapper
+    {
+        runtime.wrapper = wrapper.clone();
+        changes.push(format!("~ {id}: wrapper {}", wrapper.join(" ")));
+    }
+    if let Some(preset) = planned.jvm_preset
+        && runtime.jvm_preset != Some(preset)
+    {
+        runtime.jvm_preset = Some(preset);
+        changes.push(format!("~ {id}: jvm preset {}", preset.as_str()));
+    }
+    if let Some(priority) = planned.priority
+        && runtime.priority != Some(priority)
+    {
+        runtime.priority = Some(priority);
+        changes.push(format!("~ {id}: priority {}", priority.as_str()));
+    }
+    if let Some(cpus) = &planned.cpu_affinity
+        && &runtime.cpu_affinity != cpus
+    {
+        runtime.cpu_affinity = cpus.clone();
+        changes.push(format!("~ {id}: cpus {}", format_cpu_list(cpus)));
+    }
+    if let Some(size) = planned.gamescope
+        && runtime.gamescope != Some(size)
+    {
+        runtime.gamescope = Some(size);
+        changes.push(format!("~ {id}: gamescope {size}"));
+    }
+}
+
+/// Download and add one store project unless the profile already tracks it
+/// (at the planned version, if one is given)
+fn install_content(
+    paths: &Paths,
+    store: &ContentStore,
+    algorithm: HashAlgorithm,
+    profile: &mut Profile,
+    content: &PlannedContent,
+    changes: &mut Vec<String>,
+) -> Result<()> {
+    if tracked(profile, &content.project, content.version.as_deref()) {
+        return Ok(());
+    }
+    let item = store.get_project(content.platform, &content.project)?;
+    if tracked(profile, &item.id, content.version.as_deref()) {
+        return Ok(());
+    }
+    let ct = content.content_type.unwrap_or(item.content_type);
+    let version = match &content.version {
+        Some(wanted) => store
+            .get_versions(content.platform, &content.project, None, None)?
+            .into_iter()
+            .find(|v| &v.version == wanted || &v.id == wanted)
+            .with_context(|| format!("version not found: {wanted}"))?,
+        None => store.get_latest_version(
+            content.platform,
+            &content.project,
+            Some(&profile.mc_version),
+            store_loader_filter(profile, ct).as_deref(),
+        )?,
+    };
+
+    let mut content_ref = store.download_to_store(paths, &version, ct, algorithm)?;
+    content_ref.platform = Some(item.platform.to_string());
+    content_ref.project_id = Some(content.project.clone());
+    content_ref.version_id = Some(version.id.clone());
+    match ct {
+        ContentType::Mod | ContentType::ModPack => upsert_mod(profile, content_ref),
+        ContentType::ResourcePack => upsert_resourcepack(profile, content_ref),
+        ContentType::ShaderPack => upsert_shaderpack(profile, content_ref),
+    };
+    save_profile(paths, profile)?;
+    changes.push(format!("+ {}: {} {}", profile.id, item.name, version.version));
+    Ok(())
+}
+
+/// Whether the profile has content from `project`, at `version` when given
+fn tracked(profile: &Profile, project: &str, version: Option<&str>) -> bool {
+    let matches = |item: &&ContentRef| {
+        item.project_id.as_deref() == Some(project)
+            && version.is_none_or(|v| item.version.as_deref() == Some(v) || item.version_id.as_deref() == Some(v))
+    };
+    profile.mods.iter().chain(&profile.resourcepacks).chain(&profile.shaderpacks).any(|item| matches(&item))
+}
+
+fn same_loader(current: Option<&Loader>, wanted: &Loader) -> bool {
+    current.is_some_and(|l| l.loader_type == wanted.loader_type && l.version == wanted.version)
+}
+
+fn describe_loader(loader: Option<&Loader>) -> String {
+    match loader {
+        Some(loader) => format!("{}@{}", loader.loader_type, loader.version),
+        None => "vanilla".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::TempRoot;
+
+    #[test]
+    fn test_apply_plan_profiles() {
+        let root = TempRoot::new("provision");
+        let paths = &root.paths;
+        let store = ContentStore::modrinth_only();
+        let plan: ProvisionPlan = serde_json::from_str(
+            r#"{ "profiles": [{
+                "id": "survival",
+                "mc_version": "1.21.1",
+                "loader": "fabric@0.16.9",
+                "runtime": { "memory": "4G", "jvm_preset": "aikar" }
+            }] }"#,
+        )
+        .unwrap();
+
+        let changes = apply_plan(paths, &store, &plan).unwrap();
+        assert_eq!(changes.len(), 3, "{changes:?}");
+        let profile = load_profile(paths, "survival").unwrap();
+        assert_eq!(profile.loader.unwrap().loader_type, "fabric");
+        assert_eq!(profile.runtime.memory.as_deref(), Some("4G"));
+        assert_eq!(profile.runtime.jvm_preset, Some(JvmPreset::Aikar));
+
+        // Applying again changes nothing
+        assert!(apply_plan(paths, &store, &plan).unwrap().is_empty());
+
+        let mut newer = plan.clone();
+        newer.profiles[0].mc_version = "1.21.4".to_string();
+        assert_eq!(apply_plan(paths, &store, &newer).unwrap(), ["~ survival: minecraft 1.21.1 -> 1.21.4"]);
+    }
+
+    #[test]
+    fn test_load_plan_yaml() {
+        let root = TempRoot::new("provision-yaml");
+        let yaml = "profiles:\n  - id: survival\n    mc_version: \"1.21.1\"\n    runtime:\n      jvm_preset: aikar\n    content:\n      - project: sodium\nactive_account: Steve\n";
+        let json = r#"{ "profiles": [{ "id": "survival", "mc_version": "1.21.1" }] }"#;
+        for (name, data) in [("plan.yml", yaml), ("plan", yaml), ("plan.txt", json), ("plan.json", json)] {
+            let path = root.paths.root.join(name);
+            fs::write(&path, data).unwrap();
+            let plan = load_plan(&path).unwrap();
+            assert_eq!(plan.profiles[0].mc_version, "1.21.1", "{name}");
+        }
+
+        let plan = parse_plan(yaml, true).unwrap();
+        assert_eq!(plan.profiles[0].runtime.jvm_preset, Some(JvmPreset::Aikar));
+        assert_eq!(plan.profiles[0].content[0].project, "sodium");
+        assert_eq!(plan.active_account.as_deref(), Some("Steve"));
+        assert!(parse_plan(yaml, false).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! Built-in "essentials" setup recipes
+//!
+//! A recipe is a curated list of Modrinth projects rather than a frozen
+//! template: when it runs, the newest Minecraft release supported by every
+//! required project is picked and each project resolves to its current best
+//! version for that release.
+
+use crate::content_store::{ContentStore, ContentType, ContentVersion, Platform};
+use crate::paths::Paths;
+use crate::profile::{ContentRef, Loader, Runtime, create_profile, save_profile, upsert_mod, upsert_shaderpack};
+use crate::store::configured_store_hash;
+use crate::template::{VersionPin, select_pinned_version};
+use crate::util::release_key;
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RecipeEntry {
+    pub name: &'static str,
+    /// Modrinth project slug
+    pub project: &'static str,
+    /// Required entries decide the Minecraft version; optional ones are
+    /// skipped when unavailable
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Recipe {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub loader: &'static str,
+    pub memory: &'static str,
+    pub mods: &'static [RecipeEntry],
+    pub shaderpacks: &'static [RecipeEntry],
+}
+
+const fn entry(name: &'static str, project: &'static str, required: bool) -> RecipeEntry {
+    RecipeEntry { name, project, required }
+}
+
+pub const RECIPES: &[Recipe] = &[
+    Recipe {
+        id: "performance",
+        name: "Performance pack",
+        description: "Fabric with Sodium, Lithium, FerriteCore and other client optimizations.",
+        loader: "fabric",
+        memory: "4G",
+        mods: &[
+            entry("Fabric API", "fabric-api", true),
+            entry("Sodium", "sodium", true),
+            entry("Lithium", "lithium", true),
+            entry("FerriteCore", "ferrite-core", true),
+            entry("ImmediatelyFast", "immediatelyfast", false),
+            entry("Entity Culling", "entityculling", false),
+            entry("ModernFix", "modernfix", false),
+        ],
+        shaderpacks: &[],
+    },
+    Recipe {
+        id: "vanilla-plus",
+        name: "Vanilla+",
+        description: "Quality-of-life additions that keep the game feeling vanilla.",
+        loader: "fabric",
+        memory: "3G",
+        mods: &[
+            entry("Fabric API", "fabric-api", true),
+            entry("Sodium", "sodium", true),
+            entry("Mod Menu", "modmenu", true),
+            entry("AppleSkin", "appleskin", false),
+            entry("Mouse Tweaks", "mouse-tweaks", false),
+            entry("Jade", "jade", false),
+        ],
+        shaderpacks: &[],
+    },
+    Recipe {
+        id: "shaders",
+        name: "Shader starter",
+        description: "Sodium and Iris with Complementary Reimagined ready to enable.",
+        loader: "fabric",
+        memory: "4G",
+        mods: &[
+            entry("Fabric API", "fabric-api", true),
+            entry("Sodium", "sodium", true),
+            entry("Iris Shaders", "iris", true),
+        ],
+        shaderpacks: &[entry("Complementary Reimagined", "complementary-reimagined", false)],
+    },
+];
+
+pub fn find_recipe(id: &str) -> Result<&'static Recipe> {
+    RECIPES.iter().find(|r| r.id == id).with_context(|| {
+        let ids: Vec<&str> = RECIPES.iter().map(|r| r.id).collect();
+        format!("unknown recipe '{id}' (available: {})", ids.join(", "))
+    })
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QuickstartSummary {
+    pub profile_id: String,
+    pub mc_version: String,
+    pub installed: Vec<String>,
+    /// Optional entries with no version for the chosen Minecraft release
+    pub skipped: Vec<String>,
+    /// Entries that resolved but failed to download, with the error
+    pub failed: Vec<String>,
+}
+
+/// Newest release id present in every set
+pub fn newest_common_release(sets: &[HashSet<String>]) -> Option<String> {
+    let (first, rest) = sets.split_first()?;
+    first
+        .iter()
+        .filter(|id| rest.iter().all(|set| set.contains(*id)))
+        .filter_map(|id| Some((release_key(id)?, id)))
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, id)| id.clone())
+}
+
+struct Candidate {
+    entry: &'static RecipeEntry,
+    content_type: ContentType,
+    versions: Vec<ContentVersion>,
+}
+
+/// Create `profile_id` from a recipe. `mc_version` overrides the automatic
+/// choice of the newest release every required project supports.
+pub fn run_quickstart(
+    paths: &Paths,
+    store: &ContentStore,
+    recipe: &Recipe,
+    profile_id: &str,
+    mc_version: Option<&str>,
+) -> Result<QuickstartSummary> {
+    if paths.is_profile_present(profile_id) {
+        bail!("profile already exists: {profile_id}");
+    }
+
+    let mut summary = QuickstartSummary {
+        profile_id: profile_id.to_string(),
+        ..Default::default()
+    };
+    let entries = recipe
+        .mods
+        .iter()
+        .map(|e| (e, ContentType::Mod, recipe.loader))
+        .chain(recipe.shaderpacks.iter().map(|e| (e, ContentType::ShaderPack, "iris")));
+
+    let mut candidates = Vec::new();
+    for (entry, content_type, loader) in entries {
+        match store.get_versions(Platform::Modrinth, entry.project, None, Some(loader)) {
+            Ok(versions) => candidates.push(Candidate {
+                entry,
+                content_type,
+                versions,
+            }),
+            Err(err) if entry.required => {
+                return Err(err.context(format!("failed to look up {}", entry.name)));
+            }
+            Err(_) => summary.skipped.push(entry.name.to_string()),
+        }
+    }
+
+    let mc_version = match mc_version {
+        Some(version) => version.to_string(),
+        None => {
+            let sets: Vec<HashSet<String>> = candidates
+                .iter()
+                .filter(|c| c.entry.required)
+                .map(|c| c.versions.iter().flat_map(|v| v.game_versions.iter().cloned()).collect())
+                .collect();
+            newest_common_release(&sets)
+                .context("no Minecraft release is supported by all required projects")?
+        }
+    };
+
+    // Resolve everything before downloading so a missing required project
+    // leaves nothing behind
+    let mut resolved = Vec::new();
+    for candidate in candidates {
+        let compatible: Vec<ContentVersion> = candidate
+            .versions
+            .into_iter()
+            .filter(|v| v.game_versions.contains(&mc_version))
+            .collect();
+        match select_pinned_version(&compatible, &VersionPin::Latest) {
+            Some(version) => resolved.push((candidate.entry, candidate.content_type, version)),
+            None if candidate.entry.required => {
+                bail!("{} has no version for Minecraft {mc_version}", candidate.entry.name);
+            }
+            None => summary.skipped.push(candidate.entry.name.to_string()),
+        }
+    }
+
+    let algorithm = configured_store_hash(paths);
+    let mut refs: Vec<(ContentType, ContentRef)> = Vec::new();
+    for (entry, content_type, version) in resolved {
+        match store.download_to_store(paths, &version, content_type, algorithm) {
+            Ok(mut content_ref) => {
+                content_ref.platform = Some(Platform::Modrinth.to_string());
+                content_ref.project_id = Some(entry.project.to_string());
+                content_ref.version_id = Some(version.id.clone());
+                refs.push((content_type, content_ref));
+                summary.installed.push(entry.name.to_string());
+            }
+            Err(err) => summary.failed.push(format!("{}: {err}", entry.name)),
+        }
+    }
+
+    let loader = Loader {
+        loader_type: recipe.loader.to_string(),
+        version: "latest".to_string(),
+    };
+    let runtime = Runtime {
+        memory: Some(recipe.memory.to_string()),
+        ..Default::default()
+    };
+    let mut profile = create_profile(paths, profile_id, &mc_version, Some(loader), runtime)?;
+    for (content_type, content_ref) in refs {
+        match content_type {
+            ContentType::ShaderPack => upsert_shaderpack(&mut profile, content_ref),
+            _ => upsert_mod(&mut profile, content_ref),
+        };
+    }
+    save_profile(paths, &profile)?;
+
+    summary.mc_version = mc_version;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_newest_common_release() {
+        let sets = [
+            set(&["1.20.1", "1.21.4", "1.21.5", "25w10a"]),
+            set(&["1.20.1", "1.21.4", "25w10a", "1.21.10"]),
+            set(&["1.21.4", "1.20.1", "1.21.10"]),
+        ];
+        assert_eq!(newest_common_release(&sets).as_deref(), Some("1.21.4"));
+        assert_eq!(newest_common_release(&[set(&["1.9", "1.10"])]).as_deref(), Some("1.10"));
+        assert_eq!(newest_common_release(&[set(&["25w10a"])]), None);
+        assert_eq!(newest_common_release(&[]), None);
+    }
+
+    #[test]
+    fn test_recipes_are_well_formed() {
+        for recipe in RECIPES {
+            assert!(find_recipe(recipe.id).is_ok());
+            assert!(recipe.mods.iter().any(|e| e.required), "{} needs a required mod", recipe.id);
+        }
+        assert!(find_recipe("nope").is_err());
+    }
+}
@@ -0,0 +1,164 @@
+//! Per-instance resource sampling while the game runs
+//!
+//! A background thread samples the game process' CPU and resident memory at a
+//! fixed interval, hands each sample to a callback (used for live metrics in
+//! the UI) and accumulates a summary that is returned when monitoring stops.
+
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Default interval between samples
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// One sample of a running instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceMetrics {
+    pub pid: u32,
+    /// CPU usage in percent of one core (may exceed 100 on multi-core systems)
+    pub cpu_percent: f32,
+    /// Resident set size in bytes
+    pub rss_bytes: u64,
+    /// Seconds since monitoring started
+    pub elapsed_secs: u64,
+}
+
+/// Aggregated metrics for a finished session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricsSummary {
+    pub duration_secs: u64,
+    pub peak_rss_bytes: u64,
+    pub peak_cpu_percent: f32,
+    pub avg_cpu_percent: f32,
+    pub samples: u64,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    peak_rss_bytes: u64,
+    peak_cpu_percent: f32,
+    cpu_total: f64,
+    samples: u64,
+}
+
+impl Accumulator {
+    fn add(&mut self, metrics: &InstanceMetrics) {
+        self.peak_rss_bytes = self.peak_rss_bytes.max(metrics.rss_bytes);
+        self.peak_cpu_percent = self.peak_cpu_percent.max(metrics.cpu_percent);
+        self.cpu_total += metrics.cpu_percent as f64;
+        self.samples += 1;
+    }
+
+    fn finish(self, duration: Duration) -> MetricsSummary {
+        MetricsSummary {
+            duration_secs: duration.as_secs(),
+            peak_rss_bytes: self.peak_rss_bytes,
+            peak_cpu_percent: self.peak_cpu_percent,
+            avg_cpu_percent: if self.samples == 0 {
+                0.0
+            } else {
+                (self.cpu_total / self.samples as f64) as f32
+            },
+            samples: self.samples,
+        }
+    }
+}
+
+/// Handle to a running monitor thread
+pub struct ResourceMonitor {
+    stop_tx: Sender<()>,
+    thread: Option<JoinHandle<Accumulator>>,
+    started: Instant,
+}
+
+impl ResourceMonitor {
+    /// Start sampling `pid` every `interval`, calling `on_sample` for each sample
+    pub fn start<F>(pid: u32, interval: Duration, mut on_sample: F) -> Self
+    where
+        F: FnMut(&InstanceMetrics) + Send + 'static,
+    {
+        let (stop_tx, stop_rx) = mpsc::channel();
+        let started = Instant::now();
+
+        let thread = thread::spawn(move || {
+            let pid_handle = Pid::from_u32(pid);
+            let refresh = ProcessRefreshKind::nothing().with_cpu().with_memory();
+            let mut system = System::new();
+            let mut acc = Accumulator::default();
+
+            // CPU usage is computed between two refreshes; prime the first one
+            system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid_handle]), true, refresh);
+
+            // Runs until stopped or the stop handle is dropped
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                system.refresh_processes_specifics(
+                    ProcessesToUpdate::Some(&[pid_handle]),
+                    true,
+                    refresh,
+                );
+                let Some(process) = system.process(pid_handle) else {
+                    break;
+                };
+                let metrics = InstanceMetrics {
+                    pid,
+                    cpu_percent: process.cpu_usage(),
+                    rss_bytes: process.memory(),
+                    elapsed_secs: started.elapsed().as_secs(),
+                };
+                acc.add(&metrics);
+                on_sample(&metrics);
+            }
+
+            acc
+        });
+
+        Self {
+            stop_tx,
+            thread: Some(thread),
+            started,
+        }
+    }
+
+    /// Stop sampling and return the session summary
+    pub fn stop(mut self) -> MetricsSummary {
+        let duration = self.started.elapsed();
+        let _ = self.stop_tx.send(());
+        let acc = self
+            .thread
+            .take()
+            .and_then(|thread| thread.join().ok())
+            .unwrap_or_default();
+        acc.finish(duration)
+    }
+}
+
+impl Drop for ResourceMonitor {
+    fn drop(&mut self) {
+        let _ = self.stop_tx.send(());
+    }
+}
+
+/// Format a byte count as MiB for display
+pub fn format_mib(bytes: u64) -> String {
+    format!("{:.0} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor_samples_current_process() {
+        let monitor = ResourceMonitor::start(
+            std::process::id(),
+            Duration::from_millis(50),
+            |_| {},
+        );
+        thread::sleep(Duration::from_millis(300));
+        let summary = monitor.stop();
+        assert!(summary.samples > 0);
+        assert!(summary.peak_rss_bytes > 0);
+    }
+}
@@ -0,0 +1,239 @@
+//! Priority and CPU affinity of the game process
+//!
+//! Both are set up on the [`Command`] before the game starts, so the
+//! wrapper, the JVM and every thread it creates inherit them: nice levels
+//! and affinity masks on Linux are per thread, and setting them on a running
+//! JVM would miss the threads it already started. Windows takes the priority
+//! class as a creation flag; its affinity is process-wide and is set once
+//! the process runs. macOS has no affinity control, so it is skipped there.
+//!
+//! None of this blocks a launch. What didn't take is reported by
+//! [`check_scheduling`] as warnings.
+
+use crate::profile::ProcessPriority;
+use anyhow::{Context, Result, bail};
+use std::process::Command;
+use std::thread;
+
+/// Cores this machine has, or 1 if unknown
+fn cpu_count() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Parse a core list like `0-3,6`
+pub fn parse_cpu_list(value: &str) -> Result<Vec<usize>> {
+    let mut cpus = Vec::new();
+    for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let parse = |s: &str| s.trim().parse::<usize>().with_context(|| format!("invalid CPU number: {s}"));
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    bail!("invalid CPU range: {part}");
+                }
+                cpus.extend(start..=end);
+            }
+            None => cpus.push(parse(part)?),
+        }
+    }
+    cpus.sort_unstable();
+    cpus.dedup();
+    Ok(cpus)
+}
+
+/// Format a core list the way [`parse_cpu_list`] reads it
+pub fn format_cpu_list(cpus: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &cpu in cpus {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == cpu => *end = cpu,
+            _ => ranges.push((cpu, cpu)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(start, end)| if start == end { start.to_string() } else { format!("{start}-{end}") })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Cores of `cpus` this machine has
+fn usable_cpus(cpus: &[usize]) -> Vec<usize> {
+    let count = cpu_count();
+    cpus.iter().copied().filter(|&cpu| cpu < count).collect()
+}
+
+/// Make `command` start with `priority` and on `cpus` (all when empty)
+pub fn apply_scheduling(command: &mut Command, priority: Option<ProcessPriority>, cpus: &[usize]) {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    {
+        use std::os::unix::process::CommandExt;
+
+        #[cfg(target_os = "linux")]
+        let mask = {
+            let usable = usable_cpus(cpus);
+            (!usable.is_empty()).then(|| {
+                // SAFETY: cpu_set_t is plain data and CPU_SET stays in bounds
+                // for indices below CPU_SETSIZE
+                let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+                for cpu in usable.into_iter().filter(|&cpu| cpu < libc::CPU_SETSIZE as usize) {
+                    unsafe { libc::CPU_SET(cpu, &mut set) };
+                }
+                set
+            })
+        };
+        #[cfg(not(target_os = "linux"))]
+        let _ = cpus;
+
+        // SAFETY: the hook only makes async-signal-safe system calls, and
+        // ignores their failures so the game starts regardless
+        unsafe {
+            command.pre_exec(move || {
+                if let Some(priority) = priority {
+                    libc::setpriority(libc::PRIO_PROCESS, 0, priority.nice());
+                }
+                #[cfg(target_os = "linux")]
+                if let Some(set) = &mask {
+                    libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), set);
+                }
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        let _ = cpus;
+        if let Some(priority) = priority {
+            command.creation_flags(priority_class(priority));
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    let _ = (command, priority, cpus);
+}
+
+/// Windows priority class flag for `CreateProcess`
+#[cfg(windows)]
+fn priority_class(priority: ProcessPriority) -> u32 {
+    match priority {
+        ProcessPriority::Low => 0x0000_0040,
+        ProcessPriority::BelowNormal => 0x0000_4000,
+        ProcessPriority::Normal => 0x0000_0020,
+        ProcessPriority::AboveNormal => 0x0000_8000,
+        ProcessPriority::High => 0x0000_0080,
+    }
+}
+
+/// Finish what can only be set on the running process and return a warning
+/// for each setting that didn't take
+pub fn check_scheduling(pid: u32, priority: Option<ProcessPriority>, cpus: &[usize]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if !cpus.is_empty() {
+        let usable = usable_cpus(cpus);
+        if usable.len() < cpus.len() {
+            warnings.push(format!(
+                "this machine has {} cores; ignoring cores {} of the affinity",
+                cpu_count(),
+                format_cpu_list(&cpus.iter().copied().filter(|cpu| !usable.contains(cpu)).collect::<Vec<_>>())
+            ));
+        }
+        if let Err(err) = finish_affinity(pid, &usable) {
+            warnings.push(format!("failed to set CPU affinity: {err:#}"));
+        }
+    }
+    if let Some(priority) = priority
+        && let Err(err) = check_priority(pid, priority)
+    {
+        warnings.push(format!("failed to set priority {}: {err:#}", priority.as_str()));
+    }
+    warnings
+}
+
+#[cfg(target_os = "linux")]
+fn finish_affinity(_pid: u32, _cpus: &[usize]) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(windows)]
+fn finish_affinity(pid: u32, cpus: &[usize]) -> Result<()> {
+    let mask: u64 = cpus.iter().filter(|&&cpu| cpu < 64).fold(0, |mask, cpu| mask | (1 << cpu));
+    if mask == 0 {
+        return Ok(());
+    }
+    let script = format!("(Get-Process -Id {pid}).ProcessorAffinity = {mask}");
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .context("failed to run powershell")?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn finish_affinity(_pid: u32, _cpus: &[usize]) -> Result<()> {
+    bail!("not supported on this platform")
+}
+
+/// The nice level is set in the child where failures can't be reported, so
+/// read it back
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn check_priority(pid: u32, priority: ProcessPriority) -> Result<()> {
+    // SAFETY: getpriority only reads scheduling state; -1 is a valid result,
+    // so errno tells failures apart
+    let nice = unsafe {
+        *errno_location() = 0;
+        libc::getpriority(libc::PRIO_PROCESS, pid as libc::id_t)
+    };
+    if nice == -1 && unsafe { *errno_location() } != 0 {
+        // Already gone; nothing left to check
+        return Ok(());
+    }
+    if nice != priority.nice() {
+        if priority.nice() < 0 {
+            bail!("raising priority needs root or CAP_SYS_NICE");
+        }
+        bail!("the game runs at nice level {nice}");
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+unsafe fn errno_location() -> *mut libc::c_int {
+    unsafe { libc::__errno_location() }
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn errno_location() -> *mut libc::c_int {
+    unsafe { libc::__error() }
+}
+
+#[cfg(windows)]
+fn check_priority(_pid: u32, _priority: ProcessPriority) -> Result<()> {
+    // The priority class is a creation flag; spawning would have failed
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn check_priority(_pid: u32, _priority: ProcessPriority) -> Result<()> {
+    bail!("not supported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3, 6,2").unwrap(), [0, 1, 2, 3, 6]);
+        assert_eq!(parse_cpu_list("").unwrap(), Vec::<usize>::new());
+        assert!(parse_cpu_list("3-1").is_err());
+        assert!(parse_cpu_list("a").is_err());
+        assert_eq!(format_cpu_list(&[0, 1, 2, 3, 6, 8, 9]), "0-3,6,8-9");
+        assert_eq!("below-normal".parse::<ProcessPriority>().unwrap(), ProcessPriority::BelowNormal);
+    }
+}
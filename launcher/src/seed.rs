@@ -0,0 +1,415 @@
+//! LAN seeding of shared game files between machines
+//!
+//! `shard seed --listen` serves the asset, library and content-store
+//! directories over plain HTTP on the local network; `shard seed --from
+//! <host>` pulls whatever the local machine is missing. The peer is not
+//! trusted for hashes: content-addressed files (asset objects, store entries)
+//! are checked against the hash in their file name, and libraries and asset
+//! indexes against the SHA-1 an installed version JSON lists for them; files
+//! no local version lists are not pulled. Every downloaded file is verified
+//! before it is moved into place.
+
+use crate::minecraft::{listed_file_sha1s, sha1_file};
+use crate::paths::Paths;
+use crate::store::hash_file;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+/// Default port for `seed --listen`
+pub const DEFAULT_SEED_PORT: u16 = 25590;
+
+/// A served file, addressed by root and relative path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeedEntry {
+    pub root: String,
+    /// Path relative to the root, with forward slashes
+    pub path: String,
+    pub size: u64,
+    /// `sha1:<hex>` or `sha256:<hex>`
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeedManifest {
+    pub entries: Vec<SeedEntry>,
+}
+
+impl SeedManifest {
+    pub fn total_size(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeedPullSummary {
+    pub fetched: usize,
+    pub skipped: usize,
+    /// Libraries and indexes no installed version JSON lists a SHA-1 for
+    pub unlisted: usize,
+    pub bytes: u64,
+}
+
+/// How file hashes are known for a root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Addressing {
+    /// File name is the SHA-1 (asset objects)
+    Sha1Name,
+    /// File name is the SHA-256 (content store)
+    Sha256Name,
+    /// Hash is computed when serving and checked against the version JSONs
+    /// when pulling
+    Listed,
+}
+
+/// Directories shared by seeding
+fn seed_roots(paths: &Paths) -> Vec<(&'static str, PathBuf, Addressing)> {
+    vec![
+        ("assets/objects", paths.minecraft_assets_objects.clone(), Addressing::Sha1Name),
+        ("assets/indexes", paths.minecraft_assets_indexes.clone(), Addressing::Listed),
+        ("libraries", paths.minecraft_libraries.clone(), Addressing::Listed),
+        ("store/mods", paths.store_mods.clone(), Addressing::Sha256Name),
+        ("store/resourcepacks", paths.store_resourcepacks.clone(), Addressing::Sha256Name),
+        ("store/shaderpacks", paths.store_shaderpacks.clone(), Addressing::Sha256Name),
+        ("store/skins", paths.store_skins.clone(), Addressing::Sha256Name),
+    ]
+}
+
+fn is_hex_of_len(name: &str, len: usize) -> bool {
+    name.len() == len && name.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Hash given by a content-addressed file name
+fn name_hash(path: &Path, addressing: Addressing) -> Option<String> {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    match addressing {
+        Addressing::Sha1Name if is_hex_of_len(name, 40) => Some(format!("sha1:{name}")),
+        Addressing::Sha256Name if is_hex_of_len(name, 64) => Some(format!("sha256:{name}")),
+        _ => None,
+    }
+}
+
+fn entry_hash(path: &Path, addressing: Addressing) -> Result<Option<String>> {
+    Ok(match addressing {
+        Addressing::Listed => Some(format!("sha1:{}", sha1_file(path)?)),
+        // Stray files in content-addressed dirs are not shared
+        Addressing::Sha1Name | Addressing::Sha256Name => name_hash(path, addressing),
+    })
+}
+
+/// SHA-1s the local version JSONs list, by seed root
+struct ListedHashes {
+    libraries: HashMap<String, String>,
+    indexes: HashMap<String, String>,
+}
+
+impl ListedHashes {
+    fn load(paths: &Paths) -> Result<Self> {
+        let (libraries, indexes) = listed_file_sha1s(paths)?;
+        Ok(Self { libraries, indexes })
+    }
+
+    /// Hash a pulled file must have, independent of what the peer claims.
+    /// None when nothing local vouches for the file.
+    fn expected_hash(&self, entry: &SeedEntry, target: &Path, addressing: Addressing) -> Option<String> {
+        let listed = match entry.root.as_str() {
+            "libraries" => &self.libraries,
+            "assets/indexes" => &self.indexes,
+            _ => return name_hash(target, addressing),
+        };
+        listed.get(&entry.path).map(|sha1| format!("sha1:{}", sha1.to_ascii_lowercase()))
+    }
+}
+
+fn collect_entries(
+    root: &str,
+    base: &Path,
+    dir: &Path,
+    addressing: Addressing,
+    out: &mut Vec<SeedEntry>,
+) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read dir: {}", dir.display()))? {
+        let entry = entry.context("failed to read dir entry")?;
+        let path = entry.path();
+        let file_type = entry.file_type().context("failed to read entry type")?;
+        if file_type.is_dir() {
+            collect_entries(root, base, &path, addressing, out)?;
+        } else if file_type.is_file() {
+            let Some(hash) = entry_hash(&path, addressing)? else {
+                continue;
+            };
+            let relative = path.strip_prefix(base).unwrap_or(&path).to_string_lossy();
+            out.push(SeedEntry {
+                root: root.to_string(),
+                path: relative.replace('\\', "/"),
+                size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                hash,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// List every shareable file. Libraries and indexes are hashed, so this can
+/// take a moment on large installs.
+pub fn build_seed_manifest(paths: &Paths) -> Result<SeedManifest> {
+    let mut entries = Vec::new();
+    for (root, dir, addressing) in seed_roots(paths) {
+        if dir.exists() {
+            collect_entries(root, &dir, &dir, addressing, &mut entries)?;
+        }
+    }
+    Ok(SeedManifest { entries })
+}
+
+/// Resolve a root/path pair to a local file, rejecting traversal
+fn local_path(paths: &Paths, root: &str, relative: &str) -> Result<(PathBuf, Addressing)> {
+    let (_, base, addressing) = seed_roots(paths)
+        .into_iter()
+        .find(|(name, _, _)| *name == root)
+        .with_context(|| format!("unknown seed root: {root}"))?;
+    let rel_path = Path::new(relative);
+    if relative.is_empty() || rel_path.components().any(|c| !matches!(c, Component::Normal(_))) {
+        bail!("invalid seed path: {relative}");
+    }
+    Ok((base.join(rel_path), addressing))
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)
+}
+
+fn handle_connection(mut stream: TcpStream, manifest_json: &[u8], files: &HashMap<String, PathBuf>) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone().context("failed to clone stream")?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).context("failed to read request")?;
+    // Drain headers; requests carry no body
+    let mut header = String::new();
+    while reader.read_line(&mut header).context("failed to read headers")? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (Some("GET"), Some(target)) = (parts.next(), parts.next()) else {
+        write_response(&mut stream, "405 Method Not Allowed", "text/plain", b"method not allowed")?;
+        return Ok(());
+    };
+
+    if target == "/manifest" {
+        write_response(&mut stream, "200 OK", "application/json", manifest_json)?;
+        return Ok(());
+    }
+
+    // Only files listed in the manifest are ever served
+    let key = target
+        .strip_prefix("/file/")
+        .map(|k| urlencoding::decode(k).map(|k| k.into_owned()).unwrap_or_default());
+    let Some(path) = key.as_ref().and_then(|k| files.get(k)) else {
+        write_response(&mut stream, "404 Not Found", "text/plain", b"not found")?;
+        return Ok(());
+    };
+
+    let mut file = fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {len}\r\nConnection: close\r\n\r\n"
+    )?;
+    std::io::copy(&mut file, &mut stream).context("failed to send file")?;
+    Ok(())
+}
+
+/// Serve the local seed manifest and files until the process is stopped
+pub fn serve_seed(paths: &Paths, port: u16, on_ready: impl FnOnce(&SeedManifest)) -> Result<()> {
+    let manifest = build_seed_manifest(paths)?;
+    let mut files = HashMap::new();
+    for entry in &manifest.entries {
+        let (path, _) = local_path(paths, &entry.root, &entry.path)?;
+        files.insert(format!("{}/{}", entry.root, entry.path), path);
+    }
+    let manifest_json = Arc::new(serde_json::to_vec(&manifest).context("failed to serialize seed manifest")?);
+    let files = Arc::new(files);
+
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .with_context(|| format!("failed to listen on port {port}"))?;
+    on_ready(&manifest);
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let manifest_json = Arc::clone(&manifest_json);
+        let files = Arc::clone(&files);
+        thread::spawn(move || {
+            if let Err(err) = handle_connection(stream, &manifest_json, &files) {
+                eprintln!("seed: {err:#}");
+            }
+        });
+    }
+    Ok(())
+}
+
+fn seed_base_url(host: &str) -> String {
+    let host = host.trim_end_matches('/');
+    let host = host.strip_prefix("http://").unwrap_or(host);
+    if host.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok()) {
+        format!("http://{host}")
+    } else {
+        format!("http://{host}:{DEFAULT_SEED_PORT}")
+    }
+}
+
+fn local_matches(path: &Path, expected: &str, addressing: Addressing) -> Result<bool> {
+    if !path.is_file() {
+        return Ok(false);
+    }
+    if addressing != Addressing::Listed {
+        // Content-addressed: the name already is the hash
+        return Ok(true);
+    }
+    verify_hash(path, expected)
+}
+
+fn verify_hash(path: &Path, expected: &str) -> Result<bool> {
+    let actual = match expected.split_once(':') {
+        Some(("sha1", _)) => format!("sha1:{}", sha1_file(path)?),
+        Some(("sha256", _)) => format!("sha256:{}", hash_file(path)?),
+        _ => bail!("unsupported hash: {expected}"),
+    };
+    Ok(actual.eq_ignore_ascii_case(expected))
+}
+
+/// Pull missing shared files from another machine running `seed --listen`.
+/// `on_progress` receives (processed, total, entry) for each manifest entry.
+pub fn pull_seed(
+    paths: &Paths,
+    host: &str,
+    mut on_progress: impl FnMut(usize, usize, &SeedEntry),
+) -> Result<SeedPullSummary> {
+    let base = seed_base_url(host);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(None)
+        .build()
+        .context("failed to create http client")?;
+    let manifest: SeedManifest = client
+        .get(format!("{base}/manifest"))
+        .send()
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("failed to reach seed at {base}"))?
+        .json()
+        .context("failed to parse seed manifest")?;
+
+    let listed = ListedHashes::load(paths)?;
+    let mut summary = SeedPullSummary::default();
+    let total = manifest.entries.len();
+    for (idx, entry) in manifest.entries.iter().enumerate() {
+        on_progress(idx + 1, total, entry);
+        let (target, addressing) = local_path(paths, &entry.root, &entry.path)?;
+        let Some(expected) = listed.expected_hash(entry, &target, addressing) else {
+            if addressing == Addressing::Listed {
+                summary.unlisted += 1;
+                continue;
+            }
+            bail!("seed entry is not content-addressed: {}/{}", entry.root, entry.path);
+        };
+        if addressing != Addressing::Listed && !expected.eq_ignore_ascii_case(&entry.hash) {
+            bail!(
+                "seed entry {}/{} claims {} but its name says {expected}",
+                entry.root,
+                entry.path,
+                entry.hash
+            );
+        }
+        if local_matches(&target, &expected, addressing)? {
+            summary.skipped += 1;
+            continue;
+        }
+
+        let parent = target.parent().context("invalid seed path")?;
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create dir: {}", parent.display()))?;
+        let partial = target.with_extension("seed-part");
+        let url = format!(
+            "{base}/file/{}",
+            urlencoding::encode(&format!("{}/{}", entry.root, entry.path))
+        );
+        let mut response = client
+            .get(&url)
+            .send()
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("failed to fetch {}/{}", entry.root, entry.path))?;
+        let mut out = fs::File::create(&partial)
+            .with_context(|| format!("failed to create {}", partial.display()))?;
+        let bytes = std::io::copy(&mut response, &mut out)
+            .with_context(|| format!("failed to download {}/{}", entry.root, entry.path))?;
+        drop(out);
+
+        if !verify_hash(&partial, &expected)? {
+            let _ = fs::remove_file(&partial);
+            bail!("hash mismatch for {}/{}", entry.root, entry.path);
+        }
+        fs::rename(&partial, &target)
+            .with_context(|| format!("failed to move {} into place", target.display()))?;
+        summary.fetched += 1;
+        summary.bytes += bytes;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_base_url() {
+        assert_eq!(seed_base_url("192.168.1.20"), "http://192.168.1.20:25590");
+        assert_eq!(seed_base_url("desk.local:4000"), "http://desk.local:4000");
+        assert_eq!(seed_base_url("http://desk.local:4000/"), "http://desk.local:4000");
+    }
+
+    #[test]
+    fn test_content_addressed_names() {
+        let sha1 = "a".repeat(40);
+        let hash = entry_hash(Path::new(&format!("objects/aa/{sha1}")), Addressing::Sha1Name).unwrap();
+        assert_eq!(hash, Some(format!("sha1:{sha1}")));
+        let stray = entry_hash(Path::new("store/mods/README"), Addressing::Sha256Name).unwrap();
+        assert_eq!(stray, None);
+    }
+
+    #[test]
+    fn test_expected_hash_ignores_peer_claims() {
+        let listed = ListedHashes {
+            libraries: HashMap::from([("org/lib/1.0/lib-1.0.jar".to_string(), "ABC123".to_string())]),
+            indexes: HashMap::new(),
+        };
+        let entry = |root: &str, path: &str| SeedEntry {
+            root: root.to_string(),
+            path: path.to_string(),
+            size: 0,
+            hash: format!("sha1:{}", "f".repeat(40)),
+        };
+
+        let sha1 = "a".repeat(40);
+        let object = entry("assets/objects", &format!("aa/{sha1}"));
+        let expected = listed.expected_hash(&object, Path::new(&object.path), Addressing::Sha1Name);
+        assert_eq!(expected, Some(format!("sha1:{sha1}")));
+
+        let library = entry("libraries", "org/lib/1.0/lib-1.0.jar");
+        let expected = listed.expected_hash(&library, Path::new(&library.path), Addressing::Listed);
+        assert_eq!(expected.as_deref(), Some("sha1:abc123"));
+
+        let unlisted = entry("libraries", "org/other/1.0/other-1.0.jar");
+        assert_eq!(listed.expected_hash(&unlisted, Path::new(&unlisted.path), Addressing::Listed), None);
+    }
+}
@@ -0,0 +1,437 @@
+//! Dedicated servers
+//!
+//! A profile of kind [`ProfileKind::Server`] runs a dedicated server in its
+//! instance directory instead of the game: the vanilla server jar, Fabric or
+//! Quilt loaded from their server libraries on top of it, or a Forge or
+//! NeoForge server set up there by the loader's installer. Mods are linked
+//! in the same way as for a client, and `shard server config` edits the
+//! server's own `server.properties`. Mojang's EULA has to be accepted once
+//! per server.
+//!
+//! A server started in the foreground keeps its console on the terminal. A
+//! detached one is reached over RCON, which `shard server console` and
+//! `shard server stop` use when it is enabled in `server.properties`.
+
+use crate::disk::WriteContext;
+use crate::installer::{INSTALLER_TIMEOUT, InstallTarget, run_installer};
+use crate::instance::materialize_instance;
+use crate::loader::LoaderKind;
+use crate::minecraft::{
+    download_loader_installer, ensure_server_jar, ensure_server_loader, forge_version_id,
+    resolve_java, resolve_neoforge_latest_version,
+};
+use crate::paths::Paths;
+use crate::process::{
+    RunningInstance, kill_profile, register_instance, running_for_profile, unregister_instance,
+};
+use crate::profile::{Profile, ProfileKind};
+use crate::server_properties::load_server_properties;
+use crate::tunnel::start_profile_tunnel;
+use crate::util::now_epoch_secs;
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Where Mojang publishes the EULA servers have to accept
+pub const EULA_URL: &str = "https://aka.ms/MinecraftEULA";
+
+/// How long a stopping server gets to save its worlds before it is killed
+pub const SERVER_STOP_GRACE: Duration = Duration::from_secs(60);
+
+/// Loader build the instance's installer-made server files belong to
+const INSTALLED_MARKER: &str = ".shard-server";
+
+/// RCON packet types
+const RCON_LOGIN: i32 = 3;
+const RCON_COMMAND: i32 = 2;
+
+/// Largest RCON packet a server sends
+const RCON_MAX_PACKET: usize = 4096 + 10;
+
+impl Paths {
+    pub fn instance_eula(&self, profile_id: &str) -> PathBuf {
+        self.instance_dir(profile_id).join("eula.txt")
+    }
+}
+
+/// A server ready to start
+#[derive(Debug, Clone)]
+pub struct ServerPlan {
+    pub java_exec: String,
+    /// Everything after `java`, ending with `nogui`
+    pub args: Vec<String>,
+    pub instance_dir: PathBuf,
+    pub wrapper: Vec<String>,
+}
+
+impl ServerPlan {
+    /// The java invocation, run through the wrapper if the profile has one
+    pub fn command(&self) -> Command {
+        let mut command = match self.wrapper.split_first() {
+            Some((wrapper, wrapper_args)) => {
+                let mut command = Command::new(wrapper);
+                command.args(wrapper_args).arg(&self.java_exec);
+                command
+            }
+            None => Command::new(&self.java_exec),
+        };
+        command.args(&self.args).current_dir(&self.instance_dir);
+        command
+    }
+}
+
+/// Whether the server's `eula.txt` says the EULA was accepted
+pub fn eula_accepted(paths: &Paths, profile_id: &str) -> bool {
+    fs::read_to_string(paths.instance_eula(profile_id))
+        .is_ok_and(|text| text.lines().any(|line| line.trim().eq_ignore_ascii_case("eula=true")))
+}
+
+/// Record that the user accepted the EULA, as the server expects
+pub fn accept_eula(paths: &Paths, profile_id: &str) -> Result<()> {
+    let path = paths.instance_eula(profile_id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    let text = format!("# Accepted with shard; see {EULA_URL}\neula=true\n");
+    fs::write(&path, text).write_context(&path, || format!("failed to write {}", path.display()))
+}
+
+/// Link the server's mods, download or install the server and build the
+/// java invocation
+pub fn prepare_server(paths: &Paths, profile: &Profile) -> Result<ServerPlan> {
+    if profile.kind != ProfileKind::Server {
+        bail!("{} is not a server profile; create one with `shard profile create --server`", profile.id);
+    }
+    if !eula_accepted(paths, &profile.id) {
+        bail!("the Minecraft EULA ({EULA_URL}) has to be accepted first; start again with --accept-eula");
+    }
+    let instance_dir = materialize_instance(paths, profile)?;
+    let java = resolve_java(paths, profile.runtime.java.as_deref(), &profile.mc_version)?;
+
+    let mut args = Vec::new();
+    if let Some(memory) = &profile.runtime.memory {
+        args.push(format!("-Xmx{memory}"));
+    }
+    if let Some(preset) = profile.runtime.jvm_preset {
+        args.extend(preset.jvm_flags().iter().map(|flag| flag.to_string()));
+    }
+    args.extend(profile.runtime.args.iter().cloned());
+
+    let kind = match &profile.loader {
+        Some(loader) => Some(loader.loader_type.parse::<LoaderKind>()?),
+        None => None,
+    };
+    match (kind, &profile.loader) {
+        (Some(kind @ (LoaderKind::Fabric | LoaderKind::Quilt)), Some(loader)) => {
+            let server_jar = ensure_server_jar(paths, &profile.mc_version)?;
+            let server = ensure_server_loader(paths, kind, &profile.mc_version, &loader.version)?;
+            let sep = if cfg!(windows) { ";" } else { ":" };
+            let classpath = server
+                .classpath
+                .iter()
+                .map(|path| path.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(sep);
+            args.push(format!("-D{}={}", server.game_jar_property, server_jar.display()));
+            args.extend(["-cp".to_string(), classpath, server.main_class]);
+        }
+        (Some(kind @ (LoaderKind::Forge | LoaderKind::NeoForge)), Some(loader)) => {
+            let version = match kind {
+                LoaderKind::Forge => forge_version_id(&profile.mc_version, &loader.version)?,
+                _ if loader.version.eq_ignore_ascii_case("latest") => {
+                    resolve_neoforge_latest_version(&profile.mc_version)?
+                }
+                _ => loader.version.clone(),
+            };
+            install_loader_server(paths, kind, &version, &java, &instance_dir)?;
+            args.extend(installed_server_args(&instance_dir, kind, &version)?);
+        }
+        (Some(kind), _) => bail!("{} can't run a dedicated server", kind.name()),
+        (None, _) => {
+            let server_jar = ensure_server_jar(paths, &profile.mc_version)?;
+            args.extend(["-jar".to_string(), server_jar.to_string_lossy().into_owned()]);
+        }
+    }
+    args.push("nogui".to_string());
+
+    Ok(ServerPlan {
+        java_exec: java,
+        args,
+        instance_dir,
+        wrapper: profile.runtime.wrapper.clone(),
+    })
+}
+
+/// Run the loader's installer into the instance unless it already holds
+/// this build's server
+fn install_loader_server(paths: &Paths, kind: LoaderKind, version: &str, java: &str, instance_dir: &Path) -> Result<()> {
+    let marker = instance_dir.join(INSTALLED_MARKER);
+    let build = format!("{}-{version}", kind.id());
+    if fs::read_to_string(&marker).is_ok_and(|installed| installed.trim() == build) {
+        return Ok(());
+    }
+    let installer = download_loader_installer(paths, kind, version)?;
+    eprintln!("installing {} {version} server...", kind.name());
+    run_installer(
+        java,
+        &installer,
+        InstallTarget::Server(instance_dir),
+        instance_dir,
+        &paths.installer_log(&installer),
+        INSTALLER_TIMEOUT,
+        &mut |milestone| eprintln!("{}...", milestone.label().to_lowercase()),
+    )?;
+    fs::write(&marker, &build).write_context(&marker, || format!("failed to write {}", marker.display()))
+}
+
+/// Java arguments that start an installed Forge or NeoForge server: the
+/// argument file installers since 1.17 write, else the jar older ones leave
+fn installed_server_args(instance_dir: &Path, kind: LoaderKind, version: &str) -> Result<Vec<String>> {
+    let group = match kind {
+        LoaderKind::NeoForge => "libraries/net/neoforged/neoforge",
+        _ => "libraries/net/minecraftforge/forge",
+    };
+    let args_file = Path::new(group)
+        .join(version)
+        .join(if cfg!(windows) { "win_args.txt" } else { "unix_args.txt" });
+    if instance_dir.join(&args_file).exists() {
+        return Ok(vec![format!("@{}", args_file.display())]);
+    }
+    let jar = fs::read_dir(instance_dir)
+        .with_context(|| format!("failed to read {}", instance_dir.display()))?
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .find(|name| name.starts_with(kind.id()) && name.ends_with(".jar") && !name.contains("installer"))
+        .with_context(|| format!("the {} installer left no server to start", kind.name()))?;
+    Ok(vec!["-jar".to_string(), jar])
+}
+
+/// Run the server with its console on this terminal until it stops; the
+/// profile's tunnel runs alongside
+pub fn start_server(paths: &Paths, profile: &Profile) -> Result<()> {
+    let plan = prepare_server(paths, profile)?;
+    // Tunnel lives as long as this function; dropping it stops the process
+    let mut tunnel = start_profile_tunnel(paths, profile)?;
+    if let Some(handle) = tunnel.as_mut() {
+        match handle.wait_for_address(Duration::from_secs(15)) {
+            Some(address) => eprintln!("{} tunnel: {address}", handle.provider().as_str()),
+            None => eprintln!("{} tunnel started (public address not reported yet)", handle.provider().as_str()),
+        }
+    }
+
+    let started_at = now_epoch_secs();
+    let mut child = plan
+        .command()
+        .spawn()
+        .with_context(|| format!("failed to start {}", plan.java_exec))?;
+    // Best effort; only needed for `shard ps` and `shard server stop`
+    let instance = register_instance(paths, &profile.id, child.id(), started_at).ok();
+    let status = child.wait().context("failed to wait for the server");
+    if let Some(instance) = &instance {
+        unregister_instance(paths, instance);
+    }
+    let status = status?;
+    if !status.success() {
+        bail!("server exited with status {status}; see {}", plan.instance_dir.join("logs").display());
+    }
+    Ok(())
+}
+
+/// Start the server in the background. Its output only goes to its logs;
+/// the console is reachable over RCON.
+pub fn start_server_detached(paths: &Paths, profile: &Profile) -> Result<RunningInstance> {
+    let plan = prepare_server(paths, profile)?;
+    if profile.tunnel.is_some() {
+        eprintln!("warning: the tunnel is not started for detached servers");
+    }
+    let started_at = now_epoch_secs();
+    let child = plan
+        .command()
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to start {}", plan.java_exec))?;
+    register_instance(paths, &profile.id, child.id(), started_at)
+}
+
+/// Stop a running server: with the `stop` command over RCON when it is
+/// enabled, so the worlds are saved, else by asking the process to exit.
+/// Returns the instances that were stopped.
+pub fn stop_server(paths: &Paths, profile_id: &str) -> Result<Vec<RunningInstance>> {
+    let instances = running_for_profile(paths, profile_id)?;
+    if instances.is_empty() {
+        bail!("server {profile_id} is not running");
+    }
+    if let Ok(mut console) = connect_console(paths, profile_id)
+        && console.command("stop").is_ok()
+    {
+        let deadline = Instant::now() + SERVER_STOP_GRACE;
+        while Instant::now() < deadline {
+            if running_for_profile(paths, profile_id)?.is_empty() {
+                return Ok(instances);
+            }
+            thread::sleep(Duration::from_millis(250));
+        }
+    }
+    kill_profile(paths, profile_id, SERVER_STOP_GRACE)
+}
+
+/// Open the server's RCON console, as configured in its `server.properties`
+pub fn connect_console(paths: &Paths, profile_id: &str) -> Result<Rcon> {
+    let properties = load_server_properties(paths, profile_id)?;
+    let password = properties.get("rcon.password").unwrap_or_default();
+    if properties.get("enable-rcon") != Some("true") || password.is_empty() {
+        bail!(
+            "RCON is off for {profile_id}; turn it on with `shard server config set {profile_id} enable-rcon true` \
+             and `shard server config set {profile_id} rcon.password <password>`, then restart the server"
+        );
+    }
+    let port = properties
+        .get("rcon.port")
+        .and_then(|port| port.parse::<u16>().ok())
+        .unwrap_or(25575);
+    Rcon::connect(port, password)
+}
+
+/// A logged-in RCON connection to a local server
+pub struct Rcon {
+    stream: TcpStream,
+    next_id: i32,
+}
+
+impl Rcon {
+    pub fn connect(port: u16, password: &str) -> Result<Rcon> {
+        let stream = TcpStream::connect(("127.0.0.1", port))
+            .with_context(|| format!("failed to connect to RCON on port {port}; is the server running?"))?;
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .context("failed to configure RCON connection")?;
+        let mut rcon = Rcon { stream, next_id: 1 };
+        let id = rcon.send(RCON_LOGIN, password)?;
+        let (reply_id, _) = read_packet(&mut rcon.stream)?;
+        // The server answers a wrong password with id -1
+        if reply_id != id {
+            bail!("RCON login failed; check rcon.password");
+        }
+        Ok(rcon)
+    }
+
+    /// Run a console command and return what the server answered
+    pub fn command(&mut self, command: &str) -> Result<String> {
+        let id = self.send(RCON_COMMAND, command)?;
+        loop {
+            let (reply_id, body) = read_packet(&mut self.stream)?;
+            if reply_id == id {
+                return Ok(body);
+            }
+        }
+    }
+
+    fn send(&mut self, kind: i32, body: &str) -> Result<i32> {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_packet(&mut self.stream, id, kind, body)?;
+        Ok(id)
+    }
+}
+
+fn write_packet(out: &mut impl Write, id: i32, kind: i32, body: &str) -> Result<()> {
+    let mut packet = Vec::with_capacity(body.len() + 14);
+    packet.extend_from_slice(&(body.len() as i32 + 10).to_le_bytes());
+    packet.extend_from_slice(&id.to_le_bytes());
+    packet.extend_from_slice(&kind.to_le_bytes());
+    packet.extend_from_slice(body.as_bytes());
+    packet.extend_from_slice(&[0, 0]);
+    out.write_all(&packet).context("failed to send RCON packet")
+}
+
+/// Id and body of the next packet
+fn read_packet(input: &mut impl Read) -> Result<(i32, String)> {
+    let mut len = [0u8; 4];
+    input.read_exact(&mut len).context("RCON connection closed")?;
+    let len = i32::from_le_bytes(len);
+    if !(10..=RCON_MAX_PACKET as i32).contains(&len) {
+        bail!("invalid RCON packet length {len}");
+    }
+    let mut packet = vec![0u8; len as usize];
+    input.read_exact(&mut packet).context("RCON connection closed")?;
+    let id = i32::from_le_bytes([packet[0], packet[1], packet[2], packet[3]]);
+    let body = String::from_utf8_lossy(&packet[8..packet.len() - 2]).into_owned();
+    Ok((id, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_cdn::{FakeCdn, TempDir, TempRoot};
+    use crate::profile::{Runtime, create_profile};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_rcon() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let (id, password) = read_packet(&mut stream).unwrap();
+            let reply_id = if password == "hunter2" { id } else { -1 };
+            write_packet(&mut stream, reply_id, RCON_COMMAND, "").unwrap();
+            let (id, command) = read_packet(&mut stream).unwrap();
+            write_packet(&mut stream, id, 0, &format!("ran {command}")).unwrap();
+        });
+
+        let mut rcon = Rcon::connect(port, "hunter2").unwrap();
+        assert_eq!(rcon.command("list").unwrap(), "ran list");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_prepare_vanilla_server() {
+        let cdn = FakeCdn::start();
+        cdn.publish_version("1.21.4");
+        let root = TempRoot::new("server");
+        let paths = &root.paths;
+        let runtime = Runtime {
+            java: Some("/usr/bin/java".to_string()),
+            memory: Some("2G".to_string()),
+            ..Runtime::default()
+        };
+        let mut profile = create_profile(paths, "smp", "1.21.4", None, runtime).unwrap();
+        assert!(prepare_server(paths, &profile).is_err());
+        profile.kind = ProfileKind::Server;
+        assert!(prepare_server(paths, &profile).unwrap_err().to_string().contains("EULA"));
+
+        accept_eula(paths, &profile.id).unwrap();
+        assert!(eula_accepted(paths, &profile.id));
+        let plan = prepare_server(paths, &profile).unwrap();
+        let jar = paths.minecraft_server_jar("1.21.4");
+        assert_eq!(fs::read(&jar).unwrap(), b"server jar of 1.21.4");
+        assert_eq!(plan.args, ["-Xmx2G", "-jar", &jar.to_string_lossy(), "nogui"]);
+        assert_eq!(plan.instance_dir, paths.instance_dir("smp"));
+    }
+
+    #[test]
+    fn test_installed_server_args() {
+        let dir = TempDir::new("server-args");
+        fs::write(dir.join("forge-1.12.2-14.23.5.2859-installer.jar"), "").unwrap();
+        fs::write(dir.join("forge-1.12.2-14.23.5.2859.jar"), "").unwrap();
+        assert_eq!(
+            installed_server_args(&dir, LoaderKind::Forge, "1.12.2-14.23.5.2859").unwrap(),
+            ["-jar", "forge-1.12.2-14.23.5.2859.jar"]
+        );
+
+        let args_dir = dir.join("libraries/net/neoforged/neoforge/21.1.77");
+        fs::create_dir_all(&args_dir).unwrap();
+        let args_file = if cfg!(windows) { "win_args.txt" } else { "unix_args.txt" };
+        fs::write(args_dir.join(args_file), "").unwrap();
+        let args = installed_server_args(&dir, LoaderKind::NeoForge, "21.1.77").unwrap();
+        assert_eq!(args.len(), 1);
+        assert!(args[0].starts_with('@') && args[0].ends_with(args_file));
+    }
+}
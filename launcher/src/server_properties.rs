@@ -0,0 +1,456 @@
+//! Typed editor for `server.properties`
+//!
+//! Known keys are validated against their expected type, while unknown keys,
+//! comments and line order are preserved so mods and hand edits survive a save.
+
+use crate::paths::Paths;
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// Value type of a known server property
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PropertyKind {
+    Bool,
+    Int { min: i64, max: i64 },
+    String,
+    Enum { values: &'static [&'static str] },
+}
+
+/// Specification of a known server property
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PropertySpec {
+    pub key: &'static str,
+    pub kind: PropertyKind,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+const DIFFICULTIES: &[&str] = &["peaceful", "easy", "normal", "hard"];
+const GAMEMODES: &[&str] = &["survival", "creative", "adventure", "spectator"];
+
+/// Known vanilla server properties.
+/// Listed in the order they are shown in the settings form.
+pub const KNOWN_PROPERTIES: &[PropertySpec] = &[
+    PropertySpec { key: "motd", kind: PropertyKind::String, default: "A Minecraft Server", description: "Message shown in the server list" },
+    PropertySpec { key: "server-port", kind: PropertyKind::Int { min: 1, max: 65535 }, default: "25565", description: "TCP port the server listens on" },
+    PropertySpec { key: "server-ip", kind: PropertyKind::String, default: "", description: "Address to bind to (empty for all interfaces)" },
+    PropertySpec { key: "max-players", kind: PropertyKind::Int { min: 0, max: 2147483647 }, default: "20", description: "Maximum number of connected players" },
+    PropertySpec { key: "online-mode", kind: PropertyKind::Bool, default: "true", description: "Verify players against Minecraft account servers" },
+    PropertySpec { key: "white-list", kind: PropertyKind::Bool, default: "false", description: "Only allow players on the whitelist" },
+    PropertySpec { key: "enforce-whitelist", kind: PropertyKind::Bool, default: "false", description: "Kick players not on the whitelist when it is reloaded" },
+    PropertySpec { key: "pvp", kind: PropertyKind::Bool, default: "true", description: "Allow players to damage each other" },
+    PropertySpec { key: "difficulty", kind: PropertyKind::Enum { values: DIFFICULTIES }, default: "easy", description: "World difficulty" },
+    PropertySpec { key: "gamemode", kind: PropertyKind::Enum { values: GAMEMODES }, default: "survival", description: "Default game mode for new players" },
+    PropertySpec { key: "force-gamemode", kind: PropertyKind::Bool, default: "false", description: "Reset players to the default game mode on join" },
+    PropertySpec { key: "hardcore", kind: PropertyKind::Bool, default: "false", description: "Players are set to spectator on death" },
+    PropertySpec { key: "level-name", kind: PropertyKind::String, default: "world", description: "World folder name" },
+    PropertySpec { key: "level-seed", kind: PropertyKind::String, default: "", description: "Seed used when generating a new world" },
+    PropertySpec { key: "level-type", kind: PropertyKind::String, default: "minecraft:normal", description: "World preset used when generating a new world" },
+    PropertySpec { key: "generate-structures", kind: PropertyKind::Bool, default: "true", description: "Generate villages, temples and other structures" },
+    PropertySpec { key: "allow-nether", kind: PropertyKind::Bool, default: "true", description: "Allow players to travel to the Nether" },
+    PropertySpec { key: "allow-flight", kind: PropertyKind::Bool, default: "false", description: "Do not kick players that appear to be flying" },
+    PropertySpec { key: "spawn-monsters", kind: PropertyKind::Bool, default: "true", description: "Spawn hostile mobs" },
+    PropertySpec { key: "spawn-protection", kind: PropertyKind::Int { min: 0, max: 2147483647 }, default: "16", description: "Radius around spawn that only operators can modify" },
+    PropertySpec { key: "view-distance", kind: PropertyKind::Int { min: 3, max: 32 }, default: "10", description: "Chunk radius sent to clients" },
+    PropertySpec { key: "simulation-distance", kind: PropertyKind::Int { min: 3, max: 32 }, default: "10", description: "Chunk radius that is ticked around players" },
+    PropertySpec { key: "max-world-size", kind: PropertyKind::Int { min: 1, max: 29999984 }, default: "29999984", description: "Maximum world border radius" },
+    PropertySpec { key: "player-idle-timeout", kind: PropertyKind::Int { min: 0, max: 2147483647 }, default: "0", description: "Minutes before idle players are kicked (0 to disable)" },
+    PropertySpec { key: "op-permission-level", kind: PropertyKind::Int { min: 0, max: 4 }, default: "4", description: "Permission level granted by /op" },
+    PropertySpec { key: "enable-command-block", kind: PropertyKind::Bool, default: "false", description: "Allow command blocks to run" },
+    PropertySpec { key: "enable-rcon", kind: PropertyKind::Bool, default: "false", description: "Enable remote console access" },
+    PropertySpec { key: "rcon.port", kind: PropertyKind::Int { min: 1, max: 65535 }, default: "25575", description: "Remote console port" },
+    PropertySpec { key: "rcon.password", kind: PropertyKind::String, default: "", description: "Remote console password" },
+    PropertySpec { key: "enable-query", kind: PropertyKind::Bool, default: "false", description: "Enable the GameSpy4 query protocol" },
+    PropertySpec { key: "query.port", kind: PropertyKind::Int { min: 1, max: 65535 }, default: "25565", description: "Query protocol port" },
+    PropertySpec { key: "resource-pack", kind: PropertyKind::String, default: "", description: "URL of a resource pack offered to clients" },
+    PropertySpec { key: "resource-pack-sha1", kind: PropertyKind::String, default: "", description: "SHA-1 of the offered resource pack" },
+    PropertySpec { key: "require-resource-pack", kind: PropertyKind::Bool, default: "false", description: "Disconnect clients that decline the resource pack" },
+];
+
+/// Look up the specification of a known property
+pub fn property_spec(key: &str) -> Option<&'static PropertySpec> {
+    KNOWN_PROPERTIES.iter().find(|spec| spec.key == key)
+}
+
+/// Validate and normalize a value for a known property.
+/// Unknown keys are accepted as-is.
+pub fn validate_property(key: &str, value: &str) -> Result<String> {
+    let Some(spec) = property_spec(key) else {
+        return Ok(value.to_string());
+    };
+
+    match spec.kind {
+        PropertyKind::Bool => match value.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok("true".to_string()),
+            "false" | "no" | "off" | "0" => Ok("false".to_string()),
+            _ => bail!("{key} expects true or false, got: {value}"),
+        },
+        PropertyKind::Int { min, max } => {
+            let parsed: i64 = value
+                .trim()
+                .parse()
+                .with_context(|| format!("{key} expects an integer, got: {value}"))?;
+            if parsed < min || parsed > max {
+                bail!("{key} must be between {min} and {max}, got: {parsed}");
+            }
+            Ok(parsed.to_string())
+        }
+        PropertyKind::Enum { values } => {
+            let lower = value.trim().to_lowercase();
+            if values.contains(&lower.as_str()) {
+                Ok(lower)
+            } else {
+                bail!("{key} must be one of: {}, got: {value}", values.join(", "))
+            }
+        }
+        PropertyKind::String => Ok(value.to_string()),
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Line {
+    /// Comment or blank line, kept verbatim
+    Other(String),
+    /// Key/value entry; `raw` is kept so untouched entries round-trip unchanged
+    Entry {
+        key: String,
+        value: String,
+        raw: Option<String>,
+    },
+}
+
+/// Parsed `server.properties` file
+#[derive(Debug, Clone, Default)]
+pub struct ServerProperties {
+    lines: Vec<Line>,
+}
+
+impl ServerProperties {
+    /// Parse the contents of a `server.properties` file
+    pub fn parse(input: &str) -> Self {
+        let mut lines = Vec::new();
+        let mut pending_raw = String::new();
+        let mut pending = String::new();
+
+        for line in input.lines() {
+            // Java properties allow continuation lines ending in an odd number of backslashes
+            let trailing = line.chars().rev().take_while(|c| *c == '\\').count();
+            let continued = !pending_raw.is_empty();
+            let logical = if continued { line.trim_start() } else { line };
+            pending_raw.push_str(line);
+            if trailing % 2 == 1 {
+                pending.push_str(&logical[..logical.len() - 1]);
+                pending_raw.push('\n');
+                continue;
+            }
+            pending.push_str(logical);
+            let raw = std::mem::take(&mut pending_raw);
+            let full = std::mem::take(&mut pending);
+
+            let trimmed = full.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('!') {
+                lines.push(Line::Other(raw));
+                continue;
+            }
+
+            let (key, value) = split_entry(trimmed);
+            lines.push(Line::Entry {
+                key: unescape(&key),
+                value: unescape(&value),
+                raw: Some(raw),
+            });
+        }
+
+        if !pending_raw.is_empty() {
+            lines.push(Line::Other(pending_raw.trim_end_matches('\n').to_string()));
+        }
+
+        Self { lines }
+    }
+
+    /// Get the value of a key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().rev().find_map(|line| match line {
+            Line::Entry { key: k, value, .. } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Set a key after validating it against the known property types
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let key = key.trim();
+        if key.is_empty() {
+            bail!("property key cannot be empty");
+        }
+        let value = validate_property(key, value)?;
+        self.set_raw(key, value);
+        Ok(())
+    }
+
+    fn set_raw(&mut self, key: &str, new_value: String) {
+        for line in self.lines.iter_mut().rev() {
+            if let Line::Entry { key: k, value, raw } = line
+                && k == key
+            {
+                if *value != new_value {
+                    *value = new_value;
+                    *raw = None;
+                }
+                return;
+            }
+        }
+        self.lines.push(Line::Entry {
+            key: key.to_string(),
+            value: new_value,
+            raw: None,
+        });
+    }
+
+    /// Remove a key, returning whether it was present
+    pub fn remove(&mut self, key: &str) -> bool {
+        let before = self.lines.len();
+        self.lines
+            .retain(|line| !matches!(line, Line::Entry { key: k, .. } if k == key));
+        before != self.lines.len()
+    }
+
+    /// All entries in file order
+    pub fn entries(&self) -> Vec<(String, String)> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                Line::Entry { key, value, .. } => Some((key.clone(), value.clone())),
+                Line::Other(_) => None,
+            })
+            .collect()
+    }
+
+    /// Serialize back to `server.properties` format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for line in &self.lines {
+            match line {
+                Line::Other(text) => out.push_str(text),
+                Line::Entry { raw: Some(raw), .. } => out.push_str(raw),
+                Line::Entry { key, value, raw: None } => {
+                    out.push_str(&escape(key, true));
+                    out.push('=');
+                    out.push_str(&escape(value, false));
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Build the typed view used by the settings form
+    pub fn view(&self) -> ServerPropertiesView {
+        let fields = KNOWN_PROPERTIES
+            .iter()
+            .map(|spec| ServerPropertyField {
+                key: spec.key.to_string(),
+                kind: spec.kind,
+                value: self.get(spec.key).map(str::to_string),
+                default: spec.default.to_string(),
+                description: spec.description.to_string(),
+            })
+            .collect();
+        let extras = self
+            .entries()
+            .into_iter()
+            .filter(|(key, _)| property_spec(key).is_none())
+            .collect();
+        ServerPropertiesView { fields, extras }
+    }
+}
+
+/// A known property as shown in the settings form
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerPropertyField {
+    pub key: String,
+    pub kind: PropertyKind,
+    /// Current value, if set in the file
+    pub value: Option<String>,
+    pub default: String,
+    pub description: String,
+}
+
+/// Typed view over a `server.properties` file
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerPropertiesView {
+    /// Known properties with their current values
+    pub fields: Vec<ServerPropertyField>,
+    /// Unknown properties, preserved as-is
+    pub extras: Vec<(String, String)>,
+}
+
+impl Paths {
+    /// Get the server.properties path for a profile instance
+    pub fn instance_server_properties(&self, profile_id: &str) -> PathBuf {
+        self.instance_dir(profile_id).join("server.properties")
+    }
+}
+
+/// Load `server.properties` for a profile (empty if the file does not exist yet)
+pub fn load_server_properties(paths: &Paths, profile_id: &str) -> Result<ServerProperties> {
+    let path = paths.instance_server_properties(profile_id);
+    if !path.exists() {
+        return Ok(ServerProperties::default());
+    }
+    let data = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read server properties: {}", path.display()))?;
+    Ok(ServerProperties::parse(&data))
+}
+
+/// Save `server.properties` for a profile
+pub fn save_server_properties(
+    paths: &Paths,
+    profile_id: &str,
+    properties: &ServerProperties,
+) -> Result<()> {
+    paths.ensure_writable()?;
+    let path = paths.instance_server_properties(profile_id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create instance dir: {}", parent.display()))?;
+    }
+    fs::write(&path, properties.render())
+        .with_context(|| format!("failed to write server properties: {}", path.display()))?;
+    Ok(())
+}
+
+/// Set a single property for a profile and save the file
+pub fn set_server_property(
+    paths: &Paths,
+    profile_id: &str,
+    key: &str,
+    value: &str,
+) -> Result<ServerProperties> {
+    if !paths.is_profile_present(profile_id) {
+        bail!("profile not found: {profile_id}");
+    }
+    let mut properties = load_server_properties(paths, profile_id)?;
+    properties.set(key, value)?;
+    save_server_properties(paths, profile_id, &properties)?;
+    Ok(properties)
+}
+
+fn split_entry(line: &str) -> (String, String) {
+    let mut key = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                key.push(ch);
+                if let Some(next) = chars.next() {
+                    key.push(next);
+                }
+            }
+            '=' | ':' => break,
+            c if c.is_whitespace() => {
+                // Whitespace separator, optionally followed by '=' or ':'
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+                if matches!(chars.peek(), Some('=') | Some(':')) {
+                    chars.next();
+                }
+                break;
+            }
+            c => key.push(c),
+        }
+    }
+    let value: String = chars.collect();
+    (key, value.trim_start().to_string())
+}
+
+fn unescape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('f') => out.push('\u{0C}'),
+            Some('u') => {
+                let code: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                    Some(c) => out.push(c),
+                    None => {
+                        out.push_str("\\u");
+                        out.push_str(&code);
+                    }
+                }
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+fn escape(input: &str, is_key: bool) -> String {
+    let mut out = String::with_capacity(input.len());
+    for (idx, ch) in input.chars().enumerate() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\u{0C}' => out.push_str("\\f"),
+            '=' | ':' | '#' | '!' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            ' ' if is_key || idx == 0 => out.push_str("\\ "),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_preserves_unknown_and_comments() {
+        let input = "#Minecraft server properties\n#Mon Jan 01 00:00:00 UTC 2024\nmotd=A Minecraft Server\ncustom-mod-key=hello\nserver-port=25565\n";
+        let props = ServerProperties::parse(input);
+        assert_eq!(props.render(), input);
+        assert_eq!(props.get("custom-mod-key"), Some("hello"));
+    }
+
+    #[test]
+    fn test_set_validates_known_keys() {
+        let mut props = ServerProperties::parse("difficulty=easy\n");
+        assert!(props.set("server-port", "70000").is_err());
+        assert!(props.set("online-mode", "maybe").is_err());
+        assert!(props.set("difficulty", "legendary").is_err());
+        props.set("difficulty", "HARD").unwrap();
+        props.set("pvp", "off").unwrap();
+        props.set("anything-else", "free form").unwrap();
+        assert_eq!(props.get("difficulty"), Some("hard"));
+        assert_eq!(props.get("pvp"), Some("false"));
+        assert_eq!(props.get("anything-else"), Some("free form"));
+    }
+
+    #[test]
+    fn test_escaping() {
+        let props = ServerProperties::parse("level-type=minecraft\\:normal\nmotd=\\u00A7aGreen\n");
+        assert_eq!(props.get("level-type"), Some("minecraft:normal"));
+        assert_eq!(props.get("motd"), Some("\u{00A7}aGreen"));
+
+        let mut props = ServerProperties::default();
+        props.set("level-type", "minecraft:flat").unwrap();
+        assert_eq!(props.render(), "level-type=minecraft\\:flat\n");
+    }
+}
@@ -0,0 +1,231 @@
+//! Abnormal game exits
+//!
+//! When the game exits with a failure status, [`detect_crash`] looks for what
+//! it left behind during the session: the newest crash report, else a JVM
+//! fatal error log, else the fatal and error lines at the end of
+//! `latest.log`. The few lines that explain the crash go into the error the
+//! CLI prints and the `launch-status` event the desktop app shows, so nobody
+//! has to dig through `crash-reports/` to find out what happened.
+
+use crate::logs::{LogLevel, list_crash_reports, parse_log_line};
+use crate::paths::Paths;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::time::UNIX_EPOCH;
+
+/// Most lines kept from a crash report or log
+const MAX_LINES: usize = 8;
+
+/// Stack frames kept after a crash report's exception
+const REPORT_FRAMES: usize = 3;
+
+/// A game session that ended with a failure status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCrash {
+    /// Exit status as the platform prints it
+    pub status: String,
+    /// Crash report or JVM error log written during the session
+    pub report: Option<PathBuf>,
+    /// Whether `report` is a JVM fatal error log
+    pub jvm_crash: bool,
+    /// What the report says happened, or the last fatal and error lines of
+    /// the game log
+    pub lines: Vec<String>,
+}
+
+impl SessionCrash {
+    /// One line naming the exit status and where the details are
+    pub fn summary(&self) -> String {
+        match &self.report {
+            Some(report) if self.jvm_crash => format!(
+                "minecraft exited with status {}; the JVM crashed, see {}",
+                self.status,
+                report.display()
+            ),
+            Some(report) => format!(
+                "minecraft exited with status {}; crash report: {}",
+                self.status,
+                report.display()
+            ),
+            None => format!("minecraft exited with status {}", self.status),
+        }
+    }
+}
+
+impl fmt::Display for SessionCrash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary())?;
+        for line in &self.lines {
+            write!(f, "\n  {line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What the game left behind if it exited with a failure status; `since` is
+/// when the session started, in unix seconds
+pub fn detect_crash(paths: &Paths, profile_id: &str, since: u64, status: ExitStatus) -> Option<SessionCrash> {
+    if status.success() {
+        return None;
+    }
+    let mut crash = SessionCrash {
+        status: status.to_string(),
+        report: None,
+        jvm_crash: false,
+        lines: Vec::new(),
+    };
+
+    let recent: Vec<_> = list_crash_reports(paths, profile_id)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|file| file.modified >= since)
+        .collect();
+    // Minecraft's own report explains more than the JVM's when there are both
+    let report = recent.iter().find(|file| !file.jvm_crash).or_else(|| recent.first());
+    if let Some(report) = report {
+        if let Ok(text) = fs::read_to_string(&report.path) {
+            crash.lines = if report.jvm_crash {
+                jvm_error_excerpt(&text)
+            } else {
+                crash_report_excerpt(&text)
+            };
+        }
+        crash.report = Some(report.path.clone());
+        crash.jvm_crash = report.jvm_crash;
+    }
+
+    if crash.lines.is_empty() {
+        let log = paths.instance_latest_log(profile_id);
+        let written_this_session = fs::metadata(&log)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .is_some_and(|modified| modified.as_secs() >= since);
+        if written_this_session && let Ok(text) = fs::read_to_string(&log) {
+            crash.lines = log_excerpt(&text);
+        }
+    }
+    Some(crash)
+}
+
+/// Description, exception, top frames and suspected mods of a Minecraft
+/// crash report
+fn crash_report_excerpt(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let mut excerpt = Vec::new();
+    if let Some(description) = lines.iter().position(|line| line.starts_with("Description:")) {
+        excerpt.push(lines[description].to_string());
+        let mut rest = lines[description + 1..].iter().skip_while(|line| line.is_empty());
+        if let Some(exception) = rest.next() {
+            excerpt.push(exception.to_string());
+            excerpt.extend(
+                rest.take_while(|line| line.starts_with("at "))
+                    .take(REPORT_FRAMES)
+                    .map(|line| line.to_string()),
+            );
+        }
+    }
+    if let Some(suspects) = lines
+        .iter()
+        .find(|line| line.starts_with("Suspected Mod:") || line.starts_with("Suspected Mods:"))
+    {
+        excerpt.push(suspects.to_string());
+    }
+    excerpt.truncate(MAX_LINES);
+    excerpt
+}
+
+/// The header of a JVM fatal error log: the signal and the problematic frame
+fn jvm_error_excerpt(text: &str) -> Vec<String> {
+    const NOISE: [&str; 6] = ["JRE version", "Java VM", "Core dump", "If you would like", "http", "The crash happened"];
+    text.lines()
+        .take_while(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim())
+        .filter(|line| !line.is_empty() && !NOISE.iter().any(|noise| line.starts_with(noise)))
+        .take(MAX_LINES)
+        .map(str::to_string)
+        .collect()
+}
+
+/// The last fatal and error lines of a game log
+fn log_excerpt(text: &str) -> Vec<String> {
+    let errors: Vec<String> = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_log_line(line, i as u64 + 1))
+        .filter(|entry| matches!(entry.level, LogLevel::Error | LogLevel::Fatal))
+        .map(|entry| entry.message)
+        .collect();
+    errors[errors.len().saturating_sub(MAX_LINES)..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_report_excerpt() {
+        let report = "---- Minecraft Crash Report ----\n\
+            // Don't be sad, have a hug! <3\n\
+            \n\
+            Time: 2026-10-18 12:00:00\n\
+            Description: Rendering overlay\n\
+            \n\
+            java.lang.NullPointerException: Cannot invoke \"Object.hashCode()\"\n\
+            \tat com.example.mod.Overlay.render(Overlay.java:42)\n\
+            \tat net.minecraft.client.gui.Gui.render(Gui.java:100)\n\
+            \tat net.minecraft.client.renderer.GameRenderer.render(GameRenderer.java:900)\n\
+            \tat net.minecraft.client.Minecraft.runTick(Minecraft.java:1200)\n\
+            \n\
+            \tSuspected Mods: Example Mod (examplemod)\n";
+        assert_eq!(
+            crash_report_excerpt(report),
+            [
+                "Description: Rendering overlay",
+                "java.lang.NullPointerException: Cannot invoke \"Object.hashCode()\"",
+                "at com.example.mod.Overlay.render(Overlay.java:42)",
+                "at net.minecraft.client.gui.Gui.render(Gui.java:100)",
+                "at net.minecraft.client.renderer.GameRenderer.render(GameRenderer.java:900)",
+                "Suspected Mods: Example Mod (examplemod)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_jvm_error_excerpt() {
+        let log = "#\n\
+            # A fatal error has been detected by the Java Runtime Environment:\n\
+            #\n\
+            #  SIGSEGV (0xb) at pc=0x00007f1c, pid=4242, tid=4243\n\
+            #\n\
+            # JRE version: OpenJDK Runtime Environment (21.0.4+7)\n\
+            # Problematic frame:\n\
+            # C  [libGL.so.1+0x1234]\n\
+            #\n\
+            # Core dump will be written.\n\
+            #\n\
+            \n\
+            ---------------  S U M M A R Y ------------\n";
+        assert_eq!(
+            jvm_error_excerpt(log),
+            [
+                "A fatal error has been detected by the Java Runtime Environment:",
+                "SIGSEGV (0xb) at pc=0x00007f1c, pid=4242, tid=4243",
+                "Problematic frame:",
+                "C  [libGL.so.1+0x1234]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_log_excerpt() {
+        let log = "[12:00:00] [main/INFO]: Loading Minecraft\n\
+            [12:00:01] [Render thread/ERROR]: Failed to load shader\n\
+            [12:00:02] [Render thread/WARN]: Missing texture\n\
+            [12:00:03] [Render thread/FATAL]: Unreported exception thrown!\n";
+        assert_eq!(log_excerpt(log), ["Failed to load shader", "Unreported exception thrown!"]);
+    }
+}
@@ -0,0 +1,77 @@
+//! Per-shaderpack settings files
+//!
+//! Iris and OptiFine save the options of each shader pack next to it, as
+//! `shaderpacks/<pack file>.txt`. The packs themselves are relinked from the
+//! store on every launch, but these files are the user's tuning: they are
+//! kept across launches, exported with the profile and can be copied to
+//! other profiles.
+
+use crate::disk::WriteContext;
+use crate::instance::instance_file_name;
+use crate::paths::Paths;
+use crate::profile::load_profile;
+use crate::store::ContentKind;
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::PathBuf;
+
+impl Paths {
+    pub fn instance_shaderpacks(&self, profile_id: &str) -> PathBuf {
+        self.instance_dir(profile_id).join("shaderpacks")
+    }
+}
+
+/// Whether a file in `shaderpacks/` holds shader settings rather than a pack
+pub fn is_shader_settings_file(file_name: &str) -> bool {
+    file_name.to_ascii_lowercase().ends_with(".txt")
+}
+
+/// Settings files of a profile's instance, by file name
+pub fn list_shader_settings(paths: &Paths, profile_id: &str) -> Result<Vec<String>> {
+    let dir = paths.instance_shaderpacks(profile_id);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut files: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| is_shader_settings_file(name))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Copy shader settings from one profile's instance to another's, replacing
+/// the target's files. `pack` limits the copy to one shader pack of the
+/// source profile (name, hash or file name). Returns the files copied.
+pub fn copy_shader_settings(paths: &Paths, from: &str, to: &str, pack: Option<&str>) -> Result<Vec<String>> {
+    let source = load_profile(paths, from)?;
+    load_profile(paths, to)?;
+
+    let mut files = list_shader_settings(paths, from)?;
+    if let Some(pack) = pack {
+        let file_name = source
+            .shaderpacks
+            .iter()
+            .find(|item| item.name == pack || item.hash == pack)
+            .map(|item| instance_file_name(item, ContentKind::ShaderPack))
+            .unwrap_or_else(|| pack.to_string());
+        let settings = format!("{file_name}.txt");
+        files.retain(|name| *name == settings);
+        if files.is_empty() {
+            bail!("no shader settings for {pack} in profile {from}");
+        }
+    }
+
+    let src_dir = paths.instance_shaderpacks(from);
+    let dst_dir = paths.instance_shaderpacks(to);
+    fs::create_dir_all(&dst_dir).write_context(&dst_dir, || format!("failed to create dir: {}", dst_dir.display()))?;
+    for name in &files {
+        let src = src_dir.join(name);
+        let dst = dst_dir.join(name);
+        let data = fs::read(&src).with_context(|| format!("failed to read {}", src.display()))?;
+        fs::write(&dst, data).write_context(&dst, || format!("failed to write {}", dst.display()))?;
+    }
+    Ok(files)
+}
@@ -1,3 +1,4 @@
+use crate::net::RetryExt;
 use anyhow::{Context, Result, bail};
 use reqwest::blocking::{Client, Response};
 use serde::{Deserialize, Serialize};
@@ -84,7 +85,7 @@ pub fn get_profile(access_token: &str) -> Result<MinecraftProfile> {
     let resp = client
         .get(MC_PROFILE_URL)
         .bearer_auth(access_token)
-        .send()
+        .send_with_retry()
         .context("failed to fetch Minecraft profile")?
         .error_for_status()
         .context("Minecraft profile request failed")?;
@@ -123,7 +124,7 @@ pub fn upload_skin(access_token: &str, skin_path: &Path, variant: SkinVariant) -
         .post(MC_SKINS_URL)
         .bearer_auth(access_token)
         .multipart(form)
-        .send()
+        .send_with_retry()
         .context("failed to upload skin")?;
 
     check_response(resp, "skin upload")
@@ -150,7 +151,7 @@ pub fn set_skin_url(access_token: &str, url: &str, variant: SkinVariant) -> Resu
         .post(MC_SKINS_URL)
         .bearer_auth(access_token)
         .json(&body)
-        .send()
+        .send_with_retry()
         .context("failed to set skin from URL")?;
 
     check_response(resp, "set skin from URL")
@@ -164,7 +165,7 @@ pub fn reset_skin(access_token: &str) -> Result<()> {
     let resp = client
         .delete(&url)
         .bearer_auth(access_token)
-        .send()
+        .send_with_retry()
         .context("failed to reset skin")?;
 
     check_response(resp, "skin reset")
@@ -185,7 +186,7 @@ pub fn set_cape(access_token: &str, cape_id: &str) -> Result<()> {
         .put(MC_CAPES_ACTIVE_URL)
         .bearer_auth(access_token)
         .json(&body)
-        .send()
+        .send_with_retry()
         .context("failed to set cape")?;
 
     check_response(resp, "set cape")
@@ -198,7 +199,7 @@ pub fn hide_cape(access_token: &str) -> Result<()> {
     let resp = client
         .delete(MC_CAPES_ACTIVE_URL)
         .bearer_auth(access_token)
-        .send()
+        .send_with_retry()
         .context("failed to hide cape")?;
 
     check_response(resp, "hide cape")
@@ -277,7 +278,7 @@ pub fn download_and_cache_skin(url: &str, store_path: &Path) -> Result<PathBuf>
 
     let resp = client
         .get(&url)
-        .send()
+        .send_with_retry()
         .with_context(|| format!("failed to fetch skin from {}", url))?;
 
     if !resp.status().is_success() {
@@ -322,7 +323,7 @@ pub fn download_and_cache_cape(url: &str, store_path: &Path) -> Result<Option<Pa
 
     let resp = client
         .get(&url)
-        .send()
+        .send_with_retry()
         .with_context(|| format!("failed to fetch cape from {}", url))?;
 
     // Cape might not exist (404 is common)
@@ -0,0 +1,57 @@
+//! Snapshot tracking profile
+//!
+//! Players who test every snapshot can designate one profile in the config.
+//! [`sync_snapshot_profile`] checks Mojang's manifest for a newer snapshot and
+//! creates that profile, or moves it to the new version, when one appears.
+
+use crate::config::load_config;
+use crate::minecraft::resolve_latest_snapshot;
+use crate::paths::Paths;
+use crate::profile::{Runtime, create_profile, load_profile, save_profile};
+use anyhow::Result;
+use serde::Serialize;
+use std::time::Duration;
+
+/// How often background watchers should call [`sync_snapshot_profile`]
+pub const SNAPSHOT_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum age of the cached version manifest when checking for snapshots
+const MANIFEST_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotUpdate {
+    pub profile_id: String,
+    /// Version the profile was on; `None` when it was just created
+    pub previous: Option<String>,
+    pub version: String,
+}
+
+/// Move the configured snapshot profile to the latest snapshot. Returns `None`
+/// when tracking is off or the profile is already current.
+pub fn sync_snapshot_profile(paths: &Paths) -> Result<Option<SnapshotUpdate>> {
+    let Some(profile_id) = load_config(paths)?.snapshot_profile else {
+        return Ok(None);
+    };
+    let version = resolve_latest_snapshot(paths, MANIFEST_MAX_AGE)?;
+
+    if !paths.is_profile_present(&profile_id) {
+        create_profile(paths, &profile_id, &version, None, Runtime::default())?;
+        return Ok(Some(SnapshotUpdate {
+            profile_id,
+            previous: None,
+            version,
+        }));
+    }
+
+    let mut profile = load_profile(paths, &profile_id)?;
+    if profile.mc_version == version {
+        return Ok(None);
+    }
+    let previous = std::mem::replace(&mut profile.mc_version, version.clone());
+    save_profile(paths, &profile)?;
+    Ok(Some(SnapshotUpdate {
+        profile_id,
+        previous: Some(previous),
+        version,
+    }))
+}
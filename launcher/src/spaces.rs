@@ -0,0 +1,169 @@
+//! Named launcher spaces
+//!
+//! A space scopes profiles, instances, accounts, config and the library under
+//! `spaces/<name>/` so several people can share one data directory. The content
+//! store, game files, caches and Java runtimes remain shared between spaces.
+
+use crate::paths::Paths;
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::fs;
+
+/// Name of the implicit space that lives directly in the data root
+pub const DEFAULT_SPACE: &str = "default";
+
+/// Summary of a space for listings
+#[derive(Debug, Clone, Serialize)]
+pub struct SpaceInfo {
+    pub name: String,
+    pub active: bool,
+    pub profile_count: usize,
+}
+
+/// Validate a space name (lowercase letters, digits, `-` and `_`)
+pub fn validate_space_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > 32 {
+        bail!("space name must be 1-32 characters");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        bail!("space name may only contain lowercase letters, digits, '-' and '_'");
+    }
+    Ok(())
+}
+
+/// Name of the active space
+pub fn active_space(paths: &Paths) -> &str {
+    paths.space.as_deref().unwrap_or(DEFAULT_SPACE)
+}
+
+fn count_profiles(profiles_dir: &std::path::Path) -> usize {
+    fs::read_dir(profiles_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().join("profile.json").exists())
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// List all spaces, including the default one
+pub fn list_spaces(paths: &Paths) -> Result<Vec<SpaceInfo>> {
+    let active = active_space(paths);
+    let mut spaces = vec![SpaceInfo {
+        name: DEFAULT_SPACE.to_string(),
+        active: active == DEFAULT_SPACE,
+        profile_count: count_profiles(&paths.root.join("profiles")),
+    }];
+
+    let spaces_dir = paths.spaces_dir();
+    if spaces_dir.exists() {
+        let mut names: Vec<String> = fs::read_dir(&spaces_dir)
+            .with_context(|| format!("failed to read spaces dir: {}", spaces_dir.display()))?
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+            .collect();
+        names.sort();
+        for name in names {
+            spaces.push(SpaceInfo {
+                active: active == name,
+                profile_count: count_profiles(&paths.space_dir(&name).join("profiles")),
+                name,
+            });
+        }
+    }
+
+    Ok(spaces)
+}
+
+/// Create a new, empty space
+pub fn create_space(paths: &Paths, name: &str) -> Result<()> {
+    paths.ensure_writable()?;
+    validate_space_name(name)?;
+    if name == DEFAULT_SPACE {
+        bail!("the default space always exists");
+    }
+    let dir = paths.space_dir(name);
+    if dir.exists() {
+        bail!("space already exists: {name}");
+    }
+    fs::create_dir_all(dir.join("profiles"))
+        .with_context(|| format!("failed to create space dir: {}", dir.display()))?;
+    Ok(())
+}
+
+/// Switch the active space (persisted in the data root)
+pub fn set_active_space(paths: &Paths, name: &str) -> Result<()> {
+    paths.ensure_writable()?;
+    let file = paths.active_space_file();
+    if name == DEFAULT_SPACE {
+        if file.exists() {
+            fs::remove_file(&file)
+                .with_context(|| format!("failed to clear active space: {}", file.display()))?;
+        }
+        return Ok(());
+    }
+    validate_space_name(name)?;
+    if !paths.space_dir(name).exists() {
+        bail!("space not found: {name}");
+    }
+    fs::write(&file, name)
+        .with_context(|| format!("failed to write active space: {}", file.display()))?;
+    Ok(())
+}
+
+/// Delete a space and everything scoped to it
+pub fn delete_space(paths: &Paths, name: &str) -> Result<()> {
+    paths.ensure_writable()?;
+    if name == DEFAULT_SPACE {
+        bail!("the default space cannot be deleted");
+    }
+    validate_space_name(name)?;
+    if active_space(paths) == name {
+        bail!("cannot delete the active space; switch to another space first");
+    }
+    let dir = paths.space_dir(name);
+    if !dir.exists() {
+        bail!("space not found: {name}");
+    }
+    fs::remove_dir_all(&dir)
+        .with_context(|| format!("failed to delete space: {}", dir.display()))?;
+    let _ = fs::remove_dir_all(paths.space_config_dir(name));
+
+    // The selection file may still point at the deleted space (e.g. when
+    // SHARD_SPACE overrode it for this process)
+    let file = paths.active_space_file();
+    if fs::read_to_string(&file).is_ok_and(|active| active.trim() == name) {
+        let _ = fs::remove_file(&file);
+    }
+    Ok(())
+}
+
+/// Warn when the data directory belongs to a different OS user than the one
+/// running the launcher. Spaces only separate data by convention; real
+/// isolation needs one data directory per OS account.
+#[cfg(unix)]
+pub fn data_dir_owner_warning(paths: &Paths) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let home = dirs::home_dir()?;
+    let home_uid = fs::metadata(&home).ok()?.uid();
+    let root_uid = fs::metadata(&paths.root).ok()?.uid();
+    if home_uid != root_uid {
+        Some(format!(
+            "data directory {} is owned by another OS user (uid {root_uid}); spaces do not isolate data between OS users",
+            paths.root.display()
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+pub fn data_dir_owner_warning(_paths: &Paths) -> Option<String> {
+    None
+}
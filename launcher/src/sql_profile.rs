@@ -0,0 +1,75 @@
+//! Timing of SQLite statements
+//!
+//! With `SHARD_SQL_PROFILE` set, every statement the library, asset and crash
+//! databases run is logged to stderr with how long it took. A number logs
+//! only statements that took at least that many milliseconds, e.g.
+//! `SHARD_SQL_PROFILE=20 shard library list --tag perf`; `on` logs them all.
+//! `shard library doctor` explains why a slow filter is slow.
+
+use rusqlite::Connection;
+use std::env;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Environment variable enabling statement timing
+pub const SQL_PROFILE_ENV: &str = "SHARD_SQL_PROFILE";
+
+/// Longest statement text logged
+const MAX_LOGGED_SQL: usize = 240;
+
+static THRESHOLD: OnceLock<Option<Duration>> = OnceLock::new();
+
+fn threshold() -> Option<Duration> {
+    *THRESHOLD.get_or_init(|| parse_threshold(env::var(SQL_PROFILE_ENV).ok().as_deref()))
+}
+
+/// Least duration worth logging, if profiling is on
+fn parse_threshold(value: Option<&str>) -> Option<Duration> {
+    let value = value?.trim().to_ascii_lowercase();
+    match value.as_str() {
+        "" | "off" | "false" => None,
+        "on" | "true" | "all" => Some(Duration::ZERO),
+        ms => ms.parse::<u64>().ok().map(Duration::from_millis),
+    }
+}
+
+/// Log the statements `conn` runs when profiling is on
+pub(crate) fn profile_connection(conn: &mut Connection) {
+    if threshold().is_some() {
+        conn.profile(Some(log_statement));
+    }
+}
+
+fn log_statement(sql: &str, duration: Duration) {
+    if threshold().is_some_and(|min| duration >= min) {
+        eprintln!("sql {:>9.2}ms  {}", duration.as_secs_f64() * 1000.0, compact_sql(sql));
+    }
+}
+
+/// A statement on one line, cut short if long
+fn compact_sql(sql: &str) -> String {
+    let mut line = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((cut, _)) = line.char_indices().nth(MAX_LOGGED_SQL) {
+        line.truncate(cut);
+        line.push_str("...");
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_threshold() {
+        assert_eq!(parse_threshold(None), None);
+        assert_eq!(parse_threshold(Some("off")), None);
+        assert_eq!(parse_threshold(Some("on")), Some(Duration::ZERO));
+        assert_eq!(parse_threshold(Some(" 25 ")), Some(Duration::from_millis(25)));
+        assert_eq!(parse_threshold(Some("fast")), None);
+        assert_eq!(
+            compact_sql("SELECT id\n            FROM library_items\n  WHERE hash = ?1"),
+            "SELECT id FROM library_items WHERE hash = ?1"
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Non-Steam game shortcuts for profiles
+//!
+//! Adding a profile to Steam as a non-Steam game is how it shows up in
+//! Steam Deck game mode. `shard profile steam-shortcut` writes a `.desktop`
+//! entry, which Steam's "Add a Non-Steam Game" dialog lists on Linux, and
+//! can add the shortcut to Steam's `shortcuts.vdf` directly. Either runs
+//! `shard launch <id> --gamescope`.
+//!
+//! `shortcuts.vdf` is Valve's binary KeyValues format: a type byte, a
+//! NUL-terminated key and the value per field, with `0x08` closing a map.
+//! Steam keeps the file in memory and writes it back on exit, so it has to
+//! be closed while a shortcut is added.
+
+use crate::disk::WriteContext;
+use anyhow::{Context, Result, bail};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const VDF_MAP: u8 = 0x00;
+const VDF_STRING: u8 = 0x01;
+const VDF_INT: u8 = 0x02;
+const VDF_FLOAT: u8 = 0x03;
+const VDF_UINT64: u8 = 0x07;
+const VDF_END: u8 = 0x08;
+
+#[derive(Debug, Clone, PartialEq)]
+enum VdfValue {
+    Map(Vec<(String, VdfValue)>),
+    String(String),
+    Int(u32),
+    /// Floats and 64-bit integers, kept as read
+    Raw(u8, Vec<u8>),
+}
+
+/// A Steam shortcut that launches a profile
+#[derive(Debug, Clone)]
+pub struct SteamShortcut {
+    pub name: String,
+    pub exe: PathBuf,
+    pub start_dir: PathBuf,
+    /// Arguments after `exe`
+    pub args: Vec<String>,
+}
+
+impl SteamShortcut {
+    /// Launch `profile_id` in Gamescope mode through this shard binary;
+    /// `data_dir` is passed along when the launcher doesn't use the default
+    pub fn for_profile(profile_id: &str, data_dir: Option<&Path>) -> Result<Self> {
+        let exe = env::current_exe().context("failed to locate the shard binary")?;
+        let start_dir = exe.parent().map(Path::to_path_buf).unwrap_or_default();
+        let mut args = Vec::new();
+        if let Some(dir) = data_dir {
+            args.extend(["--data-dir".to_string(), dir.to_string_lossy().to_string()]);
+        }
+        args.extend(["launch", profile_id, "--gamescope"].map(String::from));
+        Ok(Self {
+            name: format!("Minecraft ({profile_id})"),
+            exe,
+            start_dir,
+            args,
+        })
+    }
+
+    /// Freedesktop entry that runs the shortcut
+    pub fn desktop_entry(&self) -> String {
+        let exec: Vec<String> = std::iter::once(self.exe.to_string_lossy().to_string())
+            .chain(self.args.iter().cloned())
+            .map(|arg| desktop_exec_quote(&arg))
+            .collect();
+        format!(
+            "[Desktop Entry]\nType=Application\nName={}\nComment=Launch with Shard\nExec={}\nPath={}\nTerminal=false\nCategories=Game;\n",
+            self.name,
+            exec.join(" "),
+            self.start_dir.display()
+        )
+    }
+
+    /// Steam derives non-Steam app ids from the quoted exe and the name
+    fn app_id(&self) -> u32 {
+        crc32(format!("{}{}", quoted(&self.exe), self.name).as_bytes()) | 0x8000_0000
+    }
+
+    fn vdf_entry(&self) -> Vec<(String, VdfValue)> {
+        let string = |value: &str| VdfValue::String(value.to_string());
+        [
+            ("appid", VdfValue::Int(self.app_id())),
+            ("AppName", string(&self.name)),
+            ("Exe", string(&quoted(&self.exe))),
+            ("StartDir", string(&quoted(&self.start_dir))),
+            ("icon", string("")),
+            ("ShortcutPath", string("")),
+            ("LaunchOptions", string(&shell_words::join(&self.args))),
+            ("IsHidden", VdfValue::Int(0)),
+            ("AllowDesktopConfig", VdfValue::Int(1)),
+            ("AllowOverlay", VdfValue::Int(1)),
+            ("OpenVR", VdfValue::Int(0)),
+            ("Devkit", VdfValue::Int(0)),
+            ("DevkitGameID", string("")),
+            ("DevkitOverrideAppID", VdfValue::Int(0)),
+            ("LastPlayTime", VdfValue::Int(0)),
+            ("FlatpakAppID", string("")),
+            ("tags", VdfValue::Map(Vec::new())),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+    }
+}
+
+fn quoted(path: &Path) -> String {
+    format!("\"{}\"", path.display())
+}
+
+/// Quote an `Exec=` argument: reserved characters need double quotes, in
+/// which `"`, `` ` ``, `$` and `\` are escaped, and the value's own string
+/// escaping doubles every backslash once more
+fn desktop_exec_quote(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    let reserved = [
+        ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(', ')', '`',
+    ];
+    if !arg.is_empty() && !arg.contains(reserved) {
+        return arg;
+    }
+    let mut out = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out.replace('\\', "\\\\")
+}
+
+/// Where a profile's `.desktop` entry goes: the user's applications
+/// folder, which Steam and desktop menus read
+pub fn desktop_entry_path(profile_id: &str) -> Result<PathBuf> {
+    let data = dirs::data_dir().context("could not determine the user data directory")?;
+    Ok(data.join("applications").join(format!("shard-{profile_id}.desktop")))
+}
+
+pub fn write_desktop_entry(shortcut: &SteamShortcut, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create {}", parent.display()))?;
+    }
+    fs::write(path, shortcut.desktop_entry()).write_context(path, || format!("failed to write {}", path.display()))
+}
+
+fn steam_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        if cfg!(target_os = "macos") {
+            roots.push(home.join("Library/Application Support/Steam"));
+        } else {
+            roots.push(home.join(".steam/steam"));
+            roots.push(home.join(".local/share/Steam"));
+            roots.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+        }
+    }
+    if cfg!(windows)
+        && let Some(dir) = env::var_os("ProgramFiles(x86)")
+    {
+        roots.push(PathBuf::from(dir).join("Steam"));
+    }
+    roots
+}
+
+/// `shortcuts.vdf` of every Steam account signed in on this machine,
+/// whether or not it exists yet
+pub fn steam_shortcut_files() -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    for root in steam_roots() {
+        let Ok(entries) = fs::read_dir(root.join("userdata")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            let is_account = entry.file_name().to_str().is_some_and(|name| name != "0" && name.bytes().all(|b| b.is_ascii_digit()));
+            // ~/.steam/steam usually links to one of the other roots
+            let key = fs::canonicalize(&dir).unwrap_or_else(|_| dir.clone());
+            if is_account && dir.join("config").is_dir() && seen.insert(key) {
+                files.push(dir.join("config").join("shortcuts.vdf"));
+            }
+        }
+    }
+    files
+}
+
+/// Add `shortcut` to a `shortcuts.vdf`, or update the entry of the same
+/// name. Returns whether it was added.
+pub fn add_to_shortcuts_vdf(path: &Path, shortcut: &SteamShortcut) -> Result<bool> {
+    let mut root = if path.exists() {
+        let data = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        parse_vdf(&data).with_context(|| format!("failed to parse {}", path.display()))?
+    } else {
+        Vec::new()
+    };
+    let added = upsert_shortcut(&mut root, shortcut)?;
+    if path.exists() {
+        let backup = path.with_extension("vdf.bak");
+        fs::copy(path, &backup).write_context(&backup, || format!("failed to back up {}", path.display()))?;
+    }
+    fs::write(path, write_vdf(&root)).write_context(path, || format!("failed to write {}", path.display()))?;
+    Ok(added)
+}
+
+fn upsert_shortcut(root: &mut Vec<(String, VdfValue)>, shortcut: &SteamShortcut) -> Result<bool> {
+    if !root.iter().any(|(key, _)| key.eq_ignore_ascii_case("shortcuts")) {
+        root.push(("shortcuts".to_string(), VdfValue::Map(Vec::new())));
+    }
+    let Some((_, VdfValue::Map(shortcuts))) = root.iter_mut().find(|(key, _)| key.eq_ignore_ascii_case("shortcuts"))
+    else {
+        bail!("shortcuts is not a list");
+    };
+
+    let existing = shortcuts.iter_mut().find_map(|(_, entry)| match entry {
+        VdfValue::Map(fields) if field(fields, "AppName") == Some(&VdfValue::String(shortcut.name.clone())) => Some(fields),
+        _ => None,
+    });
+    match existing {
+        Some(fields) => {
+            // Keep the app id and play time Steam already tracks
+            for (key, value) in shortcut.vdf_entry() {
+                if matches!(key.as_str(), "Exe" | "StartDir" | "LaunchOptions") {
+                    set_field(fields, &key, value);
+                }
+            }
+            Ok(false)
+        }
+        None => {
+            // Entries are keyed by their position
+            let index = shortcuts.len().to_string();
+            shortcuts.push((index, VdfValue::Map(shortcut.vdf_entry())));
+            Ok(true)
+        }
+    }
+}
+
+/// Steam has written keys in more than one casing over the years
+fn field<'a>(fields: &'a [(String, VdfValue)], key: &str) -> Option<&'a VdfValue> {
+    fields.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, value)| value)
+}
+
+fn set_field(fields: &mut Vec<(String, VdfValue)>, key: &str, value: VdfValue) {
+    match fields.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(key)) {
+        Some((_, existing)) => *existing = value,
+        None => fields.push((key.to_string(), value)),
+    }
+}
+
+fn parse_vdf(data: &[u8]) -> Result<Vec<(String, VdfValue)>> {
+    let mut pos = 0;
+    let root = parse_map(data, &mut pos)?;
+    if pos != data.len() {
+        bail!("unexpected data after the end");
+    }
+    Ok(root)
+}
+
+fn parse_map(data: &[u8], pos: &mut usize) -> Result<Vec<(String, VdfValue)>> {
+    let mut fields = Vec::new();
+    loop {
+        let kind = *data.get(*pos).context("file ends early")?;
+        *pos += 1;
+        if kind == VDF_END {
+            return Ok(fields);
+        }
+        let key = read_string(data, pos)?;
+        let value = match kind {
+            VDF_MAP => VdfValue::Map(parse_map(data, pos)?),
+            VDF_STRING => VdfValue::String(read_string(data, pos)?),
+            VDF_INT => VdfValue::Int(u32::from_le_bytes(read_bytes(data, pos, 4)?.try_into()?)),
+            VDF_FLOAT => VdfValue::Raw(kind, read_bytes(data, pos, 4)?.to_vec()),
+            VDF_UINT64 => VdfValue::Raw(kind, read_bytes(data, pos, 8)?.to_vec()),
+            other => bail!("unknown field type {other:#04x} at {key}"),
+        };
+        fields.push((key, value));
+    }
+}
+
+fn read_string(data: &[u8], pos: &mut usize) -> Result<String> {
+    let len = data[*pos..].iter().position(|&b| b == 0).context("unterminated string")?;
+    let value = String::from_utf8_lossy(&data[*pos..*pos + len]).to_string();
+    *pos += len + 1;
+    Ok(value)
+}
+
+fn read_bytes<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8]> {
+    let bytes = data.get(*pos..*pos + len).context("file ends early")?;
+    *pos += len;
+    Ok(bytes)
+}
+
+fn write_vdf(root: &[(String, VdfValue)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_map(&mut out, root);
+    out
+}
+
+fn write_map(out: &mut Vec<u8>, fields: &[(String, VdfValue)]) {
+    for (key, value) in fields {
+        let kind = match value {
+            VdfValue::Map(_) => VDF_MAP,
+            VdfValue::String(_) => VDF_STRING,
+            VdfValue::Int(_) => VDF_INT,
+            VdfValue::Raw(kind, _) => *kind,
+        };
+        out.push(kind);
+        out.extend_from_slice(key.as_bytes());
+        out.push(0);
+        match value {
+            VdfValue::Map(fields) => write_map(out, fields),
+            VdfValue::String(value) => {
+                out.extend_from_slice(value.as_bytes());
+                out.push(0);
+            }
+            VdfValue::Int(value) => out.extend_from_slice(&value.to_le_bytes()),
+            VdfValue::Raw(_, bytes) => out.extend_from_slice(bytes),
+        }
+    }
+    out.push(VDF_END);
+}
+
+/// CRC-32 (IEEE), as Steam uses for shortcut ids
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortcut(profile_id: &str) -> SteamShortcut {
+        SteamShortcut {
+            name: format!("Minecraft ({profile_id})"),
+            exe: PathBuf::from("/home/deck/bin/shard"),
+            start_dir: PathBuf::from("/home/deck/bin"),
+            args: ["launch", profile_id, "--gamescope"].map(String::from).to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_shortcuts_vdf() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let mut root = Vec::new();
+        assert!(upsert_shortcut(&mut root, &shortcut("survival")).unwrap());
+        assert!(upsert_shortcut(&mut root, &shortcut("creative")).unwrap());
+        // Same name again updates the entry in place
+        let mut moved = shortcut("survival");
+        moved.exe = PathBuf::from("/opt/shard/shard");
+        assert!(!upsert_shortcut(&mut root, &moved).unwrap());
+
+        let data = write_vdf(&root);
+        assert!(data.starts_with(b"\x00shortcuts\x00\x000\x00\x02appid\x00"));
+        assert!(data.ends_with(b"\x08\x08\x08\x08"));
+        let parsed = parse_vdf(&data).unwrap();
+        assert_eq!(parsed, root);
+
+        let VdfValue::Map(shortcuts) = &parsed[0].1 else { panic!("shortcuts is not a map") };
+        assert_eq!(shortcuts.len(), 2);
+        let VdfValue::Map(first) = &shortcuts[0].1 else { panic!("entry is not a map") };
+        assert_eq!(field(first, "exe"), Some(&VdfValue::String("\"/opt/shard/shard\"".to_string())));
+        assert_eq!(field(first, "LaunchOptions"), Some(&VdfValue::String("launch survival --gamescope".to_string())));
+        assert_eq!(field(first, "appid"), Some(&VdfValue::Int(shortcut("survival").app_id())));
+    }
+
+    #[test]
+    fn test_desktop_entry() {
+        let mut entry = shortcut("my pack");
+        entry.args.insert(0, "--data-dir".to_string());
+        entry.args.insert(1, "/mnt/sd card/shard".to_string());
+        assert!(entry.desktop_entry().contains(
+            "\nExec=/home/deck/bin/shard --data-dir \"/mnt/sd card/shard\" launch \"my pack\" --gamescope\n"
+        ));
+        assert_eq!(desktop_exec_quote("100%"), "100%%");
+        assert_eq!(desktop_exec_quote("a$b"), "\"a\\\\$b\"");
+    }
+}
@@ -1,11 +1,20 @@
+use crate::blocklist::check_file_hashes;
+use crate::config::load_config;
+use crate::disk::WriteContext;
+use crate::download::download_resumable;
 use crate::paths::Paths;
+use crate::throttle::{Cancelled, Throttle};
 use crate::util::sanitize_filename;
 use anyhow::{Context, Result, bail};
 use reqwest::Url;
-use sha2::{Digest, Sha256};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
 use std::fs;
-use std::io::{Read, Write};
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy)]
 pub enum ContentKind {
@@ -18,6 +27,9 @@ pub enum ContentKind {
 #[derive(Debug, Clone)]
 pub struct StoredContent {
     pub hash: String,
+    /// Secondary digests used by platform hash lookups (e.g. Modrinth)
+    pub sha1: String,
+    pub sha512: String,
     pub name: String,
     pub file_name: String,
     pub source: Option<String>,
@@ -34,43 +46,197 @@ impl ContentKind {
     }
 }
 
-pub fn hash_file(path: &Path) -> Result<String> {
+/// Hash addressing content in the store. SHA-256 is the default; BLAKE3 is
+/// much faster on CPUs without SHA extensions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    /// Algorithm of a recorded hash; unprefixed hashes predate other
+    /// algorithms and are SHA-256
+    pub fn of(hash: &str) -> Self {
+        if hash.starts_with("blake3:") {
+            HashAlgorithm::Blake3
+        } else {
+            HashAlgorithm::Sha256
+        }
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sha256" => Ok(HashAlgorithm::Sha256),
+            "blake3" => Ok(HashAlgorithm::Blake3),
+            other => bail!("unknown hash algorithm: {other} (expected sha256 or blake3)"),
+        }
+    }
+}
+
+enum ContentHasher {
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl ContentHasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => ContentHasher::Sha256(Sha256::new()),
+            HashAlgorithm::Blake3 => ContentHasher::Blake3(Box::default()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            ContentHasher::Sha256(hasher) => hasher.update(data),
+            ContentHasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            ContentHasher::Sha256(hasher) => hex::encode(hasher.finalize()),
+            ContentHasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+/// Feed a file to `on_chunk` in 64 KiB reads
+fn read_chunks(path: &Path, mut on_chunk: impl FnMut(&[u8])) -> Result<()> {
     let mut file = fs::File::open(path)
         .with_context(|| format!("failed to open file for hashing: {}", path.display()))?;
-    let mut hasher = Sha256::new();
     let mut buf = [0u8; 1024 * 64];
     loop {
         let read = file
             .read(&mut buf)
             .context("failed to read file for hashing")?;
         if read == 0 {
-            break;
+            return Ok(());
         }
-        hasher.update(&buf[..read]);
+        on_chunk(&buf[..read]);
     }
-    let digest = hasher.finalize();
-    Ok(hex::encode(digest))
 }
 
+pub fn hash_file(path: &Path) -> Result<String> {
+    hash_file_with(path, HashAlgorithm::Sha256)
+}
+
+/// Hex digest of a file with the given algorithm
+pub fn hash_file_with(path: &Path, algorithm: HashAlgorithm) -> Result<String> {
+    let mut hasher = ContentHasher::new(algorithm);
+    read_chunks(path, |chunk| hasher.update(chunk))?;
+    Ok(hasher.finalize_hex())
+}
+
+/// SHA-256 plus the secondary digests platforms expect for hash lookups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDigests {
+    pub sha256: String,
+    pub sha1: String,
+    pub sha512: String,
+}
+
+/// Compute all digests in a single pass over the file
+pub fn hash_file_digests(path: &Path) -> Result<FileDigests> {
+    let mut sha256 = Sha256::new();
+    let mut sha1 = Sha1::new();
+    let mut sha512 = Sha512::new();
+    read_chunks(path, |chunk| {
+        sha256.update(chunk);
+        sha1.update(chunk);
+        sha512.update(chunk);
+    })?;
+    Ok(FileDigests {
+        sha256: hex::encode(sha256.finalize()),
+        sha1: hex::encode(sha1.finalize()),
+        sha512: hex::encode(sha512.finalize()),
+    })
+}
+
+/// Store key of a recorded hash. SHA-256 files were addressed by bare hex
+/// before other algorithms existed, so only that prefix is dropped;
+/// `blake3:<hex>` keeps its prefix.
 pub fn normalize_hash(input: &str) -> &str {
     input.strip_prefix("sha256:").unwrap_or(input)
 }
 
+/// Hash in the `<algorithm>:<hex>` form recorded in profiles
+pub fn prefixed_hash(hash: &str) -> String {
+    if hash.contains(':') {
+        hash.to_string()
+    } else {
+        format!("sha256:{hash}")
+    }
+}
+
+/// Directories holding one kind of content, one per hash algorithm
+pub fn store_dirs(sha256_dir: &Path) -> [(HashAlgorithm, PathBuf); 2] {
+    [
+        (HashAlgorithm::Sha256, sha256_dir.to_path_buf()),
+        (HashAlgorithm::Blake3, sha256_dir.with_file_name("blake3")),
+    ]
+}
+
+/// Hash algorithm the config picks for new store entries; load it once per
+/// batch rather than once per file
+pub fn configured_store_hash(paths: &Paths) -> HashAlgorithm {
+    load_config(paths).map(|config| config.store_hash).unwrap_or_default()
+}
+
+/// Store a file named by its `algorithm` hash
 pub fn store_content(
     paths: &Paths,
     kind: ContentKind,
     input_path: &Path,
     source: Option<String>,
     file_name_override: Option<String>,
+    algorithm: HashAlgorithm,
 ) -> Result<StoredContent> {
+    paths.ensure_writable()?;
     if !input_path.exists() {
         bail!("file not found: {}", input_path.display());
     }
 
-    let hash_hex = hash_file(input_path)?;
-    let store_path = content_store_path(paths, kind, &hash_hex);
+    let mut hasher = ContentHasher::new(algorithm);
+    let mut sha1 = Sha1::new();
+    let mut sha512 = Sha512::new();
+    read_chunks(input_path, |chunk| {
+        hasher.update(chunk);
+        sha1.update(chunk);
+        sha512.update(chunk);
+    })?;
+    let hash_hex = hasher.finalize_hex();
+    let hash = format!("{}:{hash_hex}", algorithm.as_str());
+    let sha1 = hex::encode(sha1.finalize());
+    let sha512 = hex::encode(sha512.finalize());
+    let subject = input_path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    check_file_hashes(
+        paths,
+        &[hash.clone(), format!("sha1:{sha1}"), format!("sha512:{sha512}")],
+        &subject,
+    )?;
+    let store_path = content_store_path(paths, kind, &hash);
+    if let Some(parent) = store_path.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
     if !store_path.exists() {
-        fs::copy(input_path, &store_path).with_context(|| {
+        fs::copy(input_path, &store_path).write_context(&store_path, || {
             format!(
                 "failed to copy {} to store {}",
                 input_path.display(),
@@ -94,7 +260,9 @@ pub fn store_content(
         .unwrap_or_else(|| format!("{}-{}", kind.label(), &hash_hex[..8]));
 
     Ok(StoredContent {
-        hash: format!("sha256:{hash_hex}"),
+        hash,
+        sha1,
+        sha512,
         name,
         file_name,
         source,
@@ -102,6 +270,15 @@ pub fn store_content(
 }
 
 pub fn store_from_url(paths: &Paths, url: &str) -> Result<(PathBuf, String)> {
+    store_from_url_with_sha1(paths, url, None)
+}
+
+/// Download `url` into the download cache. The cache file is named after the
+/// URL, so a download interrupted by a crash or restart is resumed the next
+/// time the same URL is fetched; `expected_sha1` is checked before the file
+/// is moved into place.
+pub fn store_from_url_with_sha1(paths: &Paths, url: &str, expected_sha1: Option<&str>) -> Result<(PathBuf, String)> {
+    paths.ensure_writable()?;
     let parsed = Url::parse(url).context("invalid url")?;
     let file_name = parsed
         .path_segments()
@@ -110,24 +287,12 @@ pub fn store_from_url(paths: &Paths, url: &str) -> Result<(PathBuf, String)> {
         .unwrap_or("download.zip");
 
     let file_name = sanitize_filename(file_name);
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .context("time went backwards")?
-        .as_secs();
+    let url_hash = hex::encode(Sha1::digest(url.as_bytes()));
     let download_path = paths
         .cache_downloads
-        .join(format!("{}-{}", timestamp, file_name));
-
-    let mut response = reqwest::blocking::get(parsed)?.error_for_status()?;
-    let mut out = fs::File::create(&download_path).with_context(|| {
-        format!(
-            "failed to create download file: {}",
-            download_path.display()
-        )
-    })?;
-    std::io::copy(&mut response, &mut out).context("failed to write download file")?;
-    out.flush().context("failed to flush download file")?;
+        .join(format!("{}-{}", &url_hash[..12], file_name));
 
+    download_resumable(&Client::new(), url, &download_path, expected_sha1)?;
     Ok((download_path, file_name))
 }
 
@@ -140,3 +305,167 @@ pub fn content_store_path(paths: &Paths, kind: ContentKind, hash: &str) -> PathB
         ContentKind::Skin => paths.store_skin_path(hash_hex),
     }
 }
+
+/// A store file whose content no longer matches its hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorruptContent {
+    pub kind: String,
+    pub hash: String,
+    pub actual: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoreVerifyReport {
+    pub checked: usize,
+    pub bytes: u64,
+    pub corrupt: Vec<CorruptContent>,
+    /// Stopped early; the counts cover only what was checked
+    pub cancelled: bool,
+}
+
+/// Hash a file while reporting reads to the throttle
+fn hash_file_throttled(
+    path: &Path,
+    algorithm: HashAlgorithm,
+    throttle: &mut Throttle,
+) -> Result<std::result::Result<String, Cancelled>> {
+    let mut file = fs::File::open(path)
+        .with_context(|| format!("failed to open file for hashing: {}", path.display()))?;
+    let mut hasher = ContentHasher::new(algorithm);
+    let mut buf = [0u8; 1024 * 64];
+    loop {
+        let read = file
+            .read(&mut buf)
+            .context("failed to read file for hashing")?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+        if let Err(cancelled) = throttle.consume(read as u64) {
+            return Ok(Err(cancelled));
+        }
+    }
+    Ok(Ok(hasher.finalize_hex()))
+}
+
+/// Re-hash every file in the content store and report the ones that no longer
+/// match their name. Reads are paced by `throttle`, and a cancelled throttle
+/// returns the partial report.
+pub fn verify_store(
+    paths: &Paths,
+    throttle: &mut Throttle,
+    mut on_progress: impl FnMut(&StoreVerifyReport),
+) -> Result<StoreVerifyReport> {
+    let mut report = StoreVerifyReport::default();
+    let dirs = [
+        (ContentKind::Mod, &paths.store_mods),
+        (ContentKind::ResourcePack, &paths.store_resourcepacks),
+        (ContentKind::ShaderPack, &paths.store_shaderpacks),
+        (ContentKind::Skin, &paths.store_skins),
+    ]
+    .into_iter()
+    .flat_map(|(kind, dir)| store_dirs(dir).map(|(algorithm, dir)| (kind, algorithm, dir)));
+    for (kind, algorithm, dir) in dirs {
+        if !dir.exists() {
+            continue;
+        }
+        let mut entries: Vec<PathBuf> = fs::read_dir(&dir)
+            .with_context(|| format!("failed to read store dir: {}", dir.display()))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect();
+        entries.sort();
+
+        for path in entries {
+            // Skip partial downloads and anything else not named by its hash
+            let Some(hash) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .filter(|n| n.len() == 64 && n.bytes().all(|b| b.is_ascii_hexdigit()))
+                .map(str::to_ascii_lowercase)
+            else {
+                continue;
+            };
+            let actual = match hash_file_throttled(&path, algorithm, throttle)? {
+                Ok(actual) => actual,
+                Err(Cancelled) => {
+                    report.cancelled = true;
+                    return Ok(report);
+                }
+            };
+            report.checked += 1;
+            report.bytes += fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            if actual != hash {
+                let (hash, actual) = match algorithm {
+                    HashAlgorithm::Sha256 => (hash, actual),
+                    HashAlgorithm::Blake3 => (format!("blake3:{hash}"), format!("blake3:{actual}")),
+                };
+                report.corrupt.push(CorruptContent {
+                    kind: kind.label().to_string(),
+                    hash,
+                    actual,
+                    path,
+                });
+            }
+            on_progress(&report);
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_prefixes() {
+        assert_eq!(HashAlgorithm::of("abc"), HashAlgorithm::Sha256);
+        assert_eq!(HashAlgorithm::of("sha256:abc"), HashAlgorithm::Sha256);
+        assert_eq!(HashAlgorithm::of("blake3:abc"), HashAlgorithm::Blake3);
+        assert_eq!(normalize_hash("sha256:abc"), "abc");
+        assert_eq!(normalize_hash("blake3:abc"), "blake3:abc");
+        assert_eq!(prefixed_hash("abc"), "sha256:abc");
+        assert_eq!(prefixed_hash("blake3:abc"), "blake3:abc");
+    }
+
+    #[test]
+    fn test_blake3_content_hash() {
+        let mut hasher = ContentHasher::new(HashAlgorithm::Blake3);
+        hasher.update(b"");
+        assert_eq!(
+            hasher.finalize_hex(),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+
+    #[test]
+    fn test_store_from_fake_cdn() {
+        use crate::fake_cdn::{FakeCdn, TempRoot, sha1_hex};
+
+        let cdn = FakeCdn::start();
+        let root = TempRoot::new("store-install");
+        let paths = &root.paths;
+        let jar = b"fixture shader".to_vec();
+        let url = cdn.publish("data/fixture/versions/2.0/fixture-shader.zip", jar.clone());
+
+        let (download, file_name) = store_from_url_with_sha1(paths, &url, Some(&sha1_hex(&jar))).unwrap();
+        assert_eq!(file_name, "fixture-shader.zip");
+        let stored = store_content(
+            paths,
+            ContentKind::ShaderPack,
+            &download,
+            Some(url.clone()),
+            None,
+            HashAlgorithm::Sha256,
+        )
+        .unwrap();
+        assert_eq!(fs::read(content_store_path(paths, ContentKind::ShaderPack, &stored.hash)).unwrap(), jar);
+
+        let wrong = store_from_url_with_sha1(paths, &cdn.url("data/other.zip"), None);
+        assert!(wrong.is_err(), "missing files fail instead of storing an error page");
+        let mismatch = store_from_url_with_sha1(paths, &url, Some(&sha1_hex(b"something else")));
+        assert!(mismatch.is_err());
+    }
+}
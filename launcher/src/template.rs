@@ -1,5 +1,7 @@
+use crate::content_store::{ContentStore, ContentVersion, Platform};
 use crate::paths::Paths;
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -51,7 +53,8 @@ pub struct TemplateContent {
     pub name: String,
     /// Source type and identifier
     pub source: ContentSource,
-    /// Optional specific version (defaults to latest compatible)
+    /// Optional version pin: an exact version id/number, or a range such as
+    /// `>=0.5, <0.6` or `0.5.*` (defaults to latest compatible)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
     /// Whether this content is required or optional
@@ -101,7 +104,7 @@ pub struct TemplateRuntime {
 impl Paths {
     /// Get the templates directory path
     pub fn templates_dir(&self) -> PathBuf {
-        self.profiles.parent().unwrap().join("templates")
+        self.root.join("templates")
     }
 
     /// Get path to a specific template
@@ -127,6 +130,7 @@ pub fn load_template(paths: &Paths, id: &str) -> Result<Template> {
 
 /// Save a template
 pub fn save_template(paths: &Paths, template: &Template) -> Result<()> {
+    paths.ensure_writable()?;
     let dir = paths.templates_dir();
     fs::create_dir_all(&dir)
         .with_context(|| format!("failed to create templates directory: {}", dir.display()))?;
@@ -160,6 +164,7 @@ pub fn list_templates(paths: &Paths) -> Result<Vec<String>> {
 
 /// Delete a template by ID
 pub fn delete_template(paths: &Paths, id: &str) -> Result<bool> {
+    paths.ensure_writable()?;
     let path = paths.template_json(id);
     if path.exists() {
         fs::remove_file(&path)
@@ -170,6 +175,127 @@ pub fn delete_template(paths: &Paths, id: &str) -> Result<bool> {
     }
 }
 
+/// How a template content entry chooses its version
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionPin {
+    Latest,
+    /// Platform version id or exact version number
+    Exact(String),
+    Range(VersionReq),
+}
+
+impl VersionPin {
+    pub fn parse(spec: Option<&str>) -> Result<Self> {
+        let spec = spec.map(str::trim).unwrap_or_default();
+        if spec.is_empty() || spec.eq_ignore_ascii_case("latest") {
+            return Ok(Self::Latest);
+        }
+        let is_range = spec.contains(',')
+            || spec.contains('*')
+            || spec.starts_with(['>', '<', '=', '^', '~']);
+        if is_range {
+            let req = VersionReq::parse(spec)
+                .with_context(|| format!("invalid version range: {spec}"))?;
+            return Ok(Self::Range(req));
+        }
+        Ok(Self::Exact(spec.to_string()))
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        !matches!(self, Self::Latest)
+    }
+}
+
+impl TemplateContent {
+    pub fn version_pin(&self) -> Result<VersionPin> {
+        VersionPin::parse(self.version.as_deref())
+    }
+}
+
+impl Template {
+    /// Check every content pin parses before anything is downloaded
+    pub fn validate_pins(&self) -> Result<()> {
+        for content in self.mods.iter().chain(&self.resourcepacks).chain(&self.shaderpacks) {
+            content
+                .version_pin()
+                .with_context(|| format!("invalid version pin for {}", content.name))?;
+        }
+        Ok(())
+    }
+}
+
+/// Best-effort semver for mod version numbers such as `0.5.3+mc1.20.1` or
+/// `mc1.20.1-0.5.3`. Segments naming a supported game version are skipped.
+pub fn lenient_version(number: &str, game_versions: &[String]) -> Option<Version> {
+    number
+        .split(['-', '+'])
+        .map(|seg| seg.trim_start_matches(['v', 'V']))
+        .filter(|seg| !game_versions.iter().any(|g| g == seg))
+        .find_map(|seg| {
+            let parts: Vec<&str> = seg.split('.').collect();
+            if parts.len() > 3 || parts.iter().any(|p| p.is_empty() || !p.bytes().all(|b| b.is_ascii_digit())) {
+                return None;
+            }
+            let mut nums = parts.iter().map(|p| p.parse::<u64>().ok());
+            Some(Version::new(
+                nums.next().flatten()?,
+                nums.next().flatten().unwrap_or(0),
+                nums.next().flatten().unwrap_or(0),
+            ))
+        })
+}
+
+/// Pick the version matching a pin from candidates ordered newest first
+pub fn select_pinned_version(candidates: &[ContentVersion], pin: &VersionPin) -> Option<ContentVersion> {
+    match pin {
+        VersionPin::Latest => candidates
+            .iter()
+            .find(|v| v.release_type == "release")
+            .or_else(|| candidates.first())
+            .cloned(),
+        VersionPin::Exact(spec) => candidates
+            .iter()
+            .find(|v| v.id == *spec || v.version == *spec)
+            .cloned(),
+        VersionPin::Range(req) => candidates
+            .iter()
+            .filter_map(|v| {
+                let parsed = lenient_version(&v.version, &v.game_versions)?;
+                req.matches(&parsed).then_some((parsed, v))
+            })
+            // max_by_key keeps the last maximum; iterate oldest first so ties go to the newest upload
+            .rev()
+            .max_by_key(|(parsed, _)| parsed.clone())
+            .map(|(_, v)| v.clone()),
+    }
+}
+
+/// Resolve the version a template content entry installs, honouring its pin.
+///
+/// Exact pins are looked up across all versions of the project so they win
+/// over the template's game version/loader; ranges only consider compatible
+/// versions.
+pub fn resolve_template_version(
+    store: &ContentStore,
+    project: &str,
+    pin: &VersionPin,
+    game_version: Option<&str>,
+    loader: Option<&str>,
+) -> Result<ContentVersion> {
+    let candidates = match pin {
+        VersionPin::Exact(_) => store.get_versions(Platform::Modrinth, project, None, None)?,
+        _ => store.get_versions(Platform::Modrinth, project, game_version, loader)?,
+    };
+    match select_pinned_version(&candidates, pin) {
+        Some(version) => Ok(version),
+        None => match pin {
+            VersionPin::Latest => bail!("no compatible versions found"),
+            VersionPin::Exact(spec) => bail!("pinned version {spec} not found"),
+            VersionPin::Range(req) => bail!("no compatible version matches {req}"),
+        },
+    }
+}
+
 /// Create a built-in vanilla template
 pub fn create_vanilla_template() -> Template {
     Template {
@@ -272,3 +398,58 @@ pub fn init_builtin_templates(paths: &Paths) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: &str, number: &str, release_type: &str) -> ContentVersion {
+        ContentVersion {
+            id: id.to_string(),
+            project_id: "sodium".to_string(),
+            name: number.to_string(),
+            version: number.to_string(),
+            download_url: String::new(),
+            filename: format!("{id}.jar"),
+            size: 0,
+            sha256: None,
+            sha1: None,
+            platform: Platform::Modrinth,
+            game_versions: vec!["1.20.1".to_string()],
+            loaders: vec!["fabric".to_string()],
+            release_type: release_type.to_string(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_lenient_version_skips_game_version() {
+        let games = vec!["1.20.1".to_string()];
+        assert_eq!(lenient_version("mc1.20.1-0.5.3", &games), Some(Version::new(0, 5, 3)));
+        assert_eq!(lenient_version("1.20.1-0.5.3", &games), Some(Version::new(0, 5, 3)));
+        assert_eq!(lenient_version("0.5+mc1.20.1", &games), Some(Version::new(0, 5, 0)));
+        assert_eq!(lenient_version("v2.1.0", &[]), Some(Version::new(2, 1, 0)));
+        assert_eq!(lenient_version("beta", &[]), None);
+    }
+
+    #[test]
+    fn test_select_pinned_version() {
+        // Newest first, as returned by the platform
+        let candidates = vec![
+            version("c", "0.6.0", "beta"),
+            version("b", "0.5.4", "release"),
+            version("a", "0.5.1", "release"),
+        ];
+        let pick = |spec: &str| {
+            let pin = VersionPin::parse(Some(spec)).unwrap();
+            select_pinned_version(&candidates, &pin).map(|v| v.id)
+        };
+        assert_eq!(pick("latest").as_deref(), Some("b"));
+        assert_eq!(pick("a").as_deref(), Some("a"));
+        assert_eq!(pick("0.5.1").as_deref(), Some("a"));
+        assert_eq!(pick(">=0.5, <0.6").as_deref(), Some("b"));
+        assert_eq!(pick("0.6.*").as_deref(), Some("c"));
+        assert_eq!(pick("0.4.0"), None);
+        assert!(VersionPin::parse(Some(">=banana")).is_err());
+    }
+}
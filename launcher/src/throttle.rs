@@ -0,0 +1,123 @@
+//! Pacing and cancellation for long-running background work
+//!
+//! Integrity checks read every file in the store. Run at full speed they
+//! saturate the disk and make the desktop app or a running game stutter, so
+//! they report their I/O to a [`Throttle`], which sleeps to stay under a byte
+//! rate and stops early once its [`CancelToken`] is cancelled.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Read rate used by low-priority mode
+pub const LOW_PRIORITY_BYTES_PER_SEC: u64 = 16 * 1024 * 1024;
+
+/// Shared flag to stop background work from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Returned by [`Throttle::consume`] once the work was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("operation cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+#[derive(Debug)]
+pub struct Throttle {
+    bytes_per_sec: Option<u64>,
+    cancel: CancelToken,
+    started: Instant,
+    consumed: u64,
+}
+
+impl Throttle {
+    /// Run at full speed, only checking for cancellation
+    pub fn unlimited(cancel: CancelToken) -> Self {
+        Self::new(None, cancel)
+    }
+
+    /// Yield to foreground work by capping reads at
+    /// [`LOW_PRIORITY_BYTES_PER_SEC`]
+    pub fn low_priority(cancel: CancelToken) -> Self {
+        Self::new(Some(LOW_PRIORITY_BYTES_PER_SEC), cancel)
+    }
+
+    pub fn new(bytes_per_sec: Option<u64>, cancel: CancelToken) -> Self {
+        Self {
+            bytes_per_sec: bytes_per_sec.filter(|rate| *rate > 0),
+            cancel,
+            started: Instant::now(),
+            consumed: 0,
+        }
+    }
+
+    pub fn is_low_priority(&self) -> bool {
+        self.bytes_per_sec.is_some()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Record `bytes` of I/O, sleeping as needed to stay under the rate
+    pub fn consume(&mut self, bytes: u64) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            return Err(Cancelled);
+        }
+        self.consumed += bytes;
+        let Some(rate) = self.bytes_per_sec else {
+            return Ok(());
+        };
+        let due = Duration::from_secs_f64(self.consumed as f64 / rate as f64);
+        let elapsed = self.started.elapsed();
+        if due > elapsed {
+            thread::sleep(due - elapsed);
+        } else {
+            thread::yield_now();
+        }
+        if self.is_cancelled() {
+            return Err(Cancelled);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_paces_and_cancels() {
+        let cancel = CancelToken::new();
+        let mut throttle = Throttle::new(Some(1024 * 1024), cancel.clone());
+        let started = Instant::now();
+        throttle.consume(100 * 1024).unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(90));
+
+        cancel.cancel();
+        assert_eq!(throttle.consume(1), Err(Cancelled));
+        assert!(Throttle::unlimited(cancel).consume(1).is_err());
+    }
+}
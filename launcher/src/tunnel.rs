@@ -0,0 +1,260 @@
+//! Tunnel helper integration (playit.gg, ngrok, or any custom binary)
+//!
+//! A tunnel is started alongside a profile launch, its output is scanned for the
+//! public address, and the process is stopped when the game/server exits.
+
+use crate::paths::Paths;
+use crate::profile::Profile;
+use crate::server_properties::load_server_properties;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Known tunnel providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TunnelProvider {
+    Ngrok,
+    Playit,
+    Custom,
+}
+
+impl TunnelProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TunnelProvider::Ngrok => "ngrok",
+            TunnelProvider::Playit => "playit",
+            TunnelProvider::Custom => "custom",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "ngrok" => Some(TunnelProvider::Ngrok),
+            "playit" | "playit.gg" | "playit-gg" => Some(TunnelProvider::Playit),
+            "custom" => Some(TunnelProvider::Custom),
+            _ => None,
+        }
+    }
+
+    /// Default binary name looked up on PATH
+    fn default_binary(&self) -> Option<&'static str> {
+        match self {
+            TunnelProvider::Ngrok => Some("ngrok"),
+            TunnelProvider::Playit => Some("playit"),
+            TunnelProvider::Custom => None,
+        }
+    }
+
+    /// Default arguments; `{port}` is replaced with the local port
+    fn default_args(&self) -> Vec<String> {
+        match self {
+            TunnelProvider::Ngrok => vec![
+                "tcp".to_string(),
+                "{port}".to_string(),
+                "--log".to_string(),
+                "stdout".to_string(),
+                "--log-format".to_string(),
+                "logfmt".to_string(),
+            ],
+            TunnelProvider::Playit | TunnelProvider::Custom => Vec::new(),
+        }
+    }
+}
+
+/// Per-profile tunnel configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TunnelConfig {
+    pub provider: TunnelProvider,
+    /// Path to the tunnel binary (defaults to the provider's binary on PATH)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary: Option<String>,
+    /// Arguments passed to the binary (defaults to the provider's arguments)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Local port to expose (defaults to the server port)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+}
+
+/// A running tunnel process
+pub struct TunnelHandle {
+    provider: TunnelProvider,
+    child: Child,
+    address: Arc<Mutex<Option<String>>>,
+}
+
+impl TunnelHandle {
+    pub fn provider(&self) -> TunnelProvider {
+        self.provider
+    }
+
+    /// Public address if the tunnel has reported one yet
+    pub fn public_address(&self) -> Option<String> {
+        self.address.lock().ok().and_then(|a| a.clone())
+    }
+
+    /// Block until the tunnel reports a public address or the timeout elapses
+    pub fn wait_for_address(&mut self, timeout: Duration) -> Option<String> {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if let Some(address) = self.public_address() {
+                return Some(address);
+            }
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return None;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        self.public_address()
+    }
+
+    /// Whether the tunnel process is still running
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Stop the tunnel process
+    pub fn stop(&mut self) {
+        if self.is_running() {
+            let _ = self.child.kill();
+        }
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for TunnelHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Start a tunnel for the given local port
+pub fn start_tunnel(config: &TunnelConfig, local_port: u16) -> Result<TunnelHandle> {
+    let binary = config
+        .binary
+        .as_deref()
+        .or(config.provider.default_binary())
+        .context("custom tunnel requires a binary path")?;
+    let port = config.port.unwrap_or(local_port);
+    let args = if config.args.is_empty() {
+        config.provider.default_args()
+    } else {
+        config.args.clone()
+    };
+    let args: Vec<String> = args
+        .iter()
+        .map(|arg| arg.replace("{port}", &port.to_string()))
+        .collect();
+
+    let mut child = Command::new(binary)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to start tunnel: {binary}"))?;
+
+    let address = Arc::new(Mutex::new(None));
+    if let Some(stdout) = child.stdout.take() {
+        spawn_output_reader(stdout, Arc::clone(&address));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        spawn_output_reader(stderr, Arc::clone(&address));
+    }
+
+    // Catch binaries that exit immediately (bad args, missing auth token)
+    thread::sleep(Duration::from_millis(200));
+    if let Ok(Some(status)) = child.try_wait() {
+        bail!("tunnel exited immediately with status {status}");
+    }
+
+    Ok(TunnelHandle {
+        provider: config.provider,
+        child,
+        address,
+    })
+}
+
+/// Port used when server.properties does not set one
+pub const DEFAULT_SERVER_PORT: u16 = 25565;
+
+/// Start the profile's configured tunnel, if any.
+/// The local port defaults to `server-port` from the instance's server.properties.
+pub fn start_profile_tunnel(paths: &Paths, profile: &Profile) -> Result<Option<TunnelHandle>> {
+    let Some(config) = profile.tunnel.as_ref() else {
+        return Ok(None);
+    };
+    let local_port = load_server_properties(paths, &profile.id)?
+        .get("server-port")
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_SERVER_PORT);
+    start_tunnel(config, local_port).map(Some)
+}
+
+fn spawn_output_reader<R: Read + Send + 'static>(reader: R, address: Arc<Mutex<Option<String>>>) {
+    thread::spawn(move || {
+        let reader = BufReader::new(reader);
+        for line in reader.lines().map_while(std::result::Result::ok) {
+            if let Some(found) = extract_public_address(&line)
+                && let Ok(mut slot) = address.lock()
+                && slot.is_none()
+            {
+                *slot = Some(found);
+            }
+        }
+    });
+}
+
+/// Extract a public address from a line of tunnel output
+pub fn extract_public_address(line: &str) -> Option<String> {
+    // ngrok logfmt: `... msg="started tunnel" ... url=tcp://0.tcp.eu.ngrok.io:12345`
+    if let Some(idx) = line.find("url=tcp://") {
+        let rest = &line[idx + "url=tcp://".len()..];
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        return Some(rest[..end].trim_matches('"').to_string());
+    }
+
+    // playit.gg and generic tools: first token that looks like a public host
+    for token in line.split(|c: char| c.is_whitespace() || c == '"' || c == '\'') {
+        let token = token
+            .trim_start_matches("tcp://")
+            .trim_end_matches([',', '.', ';']);
+        if token.ends_with(".joinmc.link")
+            || token.contains(".ply.gg")
+            || token.contains(".playit.gg:")
+            || token.contains(".tcp.ngrok.io:")
+        {
+            return Some(token.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_ngrok_address() {
+        let line = r#"t=2024-01-01T00:00:00+0000 lvl=info msg="started tunnel" obj=tunnels name=command_line addr=//localhost:25565 url=tcp://0.tcp.eu.ngrok.io:14321"#;
+        assert_eq!(
+            extract_public_address(line).as_deref(),
+            Some("0.tcp.eu.ngrok.io:14321")
+        );
+    }
+
+    #[test]
+    fn test_extract_playit_address() {
+        let line = "tunnel running: example-host.joinmc.link => 127.0.0.1:25565";
+        assert_eq!(
+            extract_public_address(line).as_deref(),
+            Some("example-host.joinmc.link")
+        );
+        assert_eq!(extract_public_address("starting agent..."), None);
+    }
+}
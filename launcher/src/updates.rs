@@ -5,9 +5,16 @@
 //! - Calculating storage usage statistics
 //! - Deduplication savings tracking
 
-use crate::content_store::{ContentStore, ContentType, Platform};
+use crate::asset_db::asset_usage;
+use crate::content_store::{ContentStore, ContentType, ContentVersion, Platform};
+use crate::instance::sync_instance_content;
+use crate::instance_folders::{FolderUsage, aux_folder_usage};
+use crate::library::{Library, LibraryContentType, LibraryItemInput};
 use crate::paths::Paths;
 use crate::profile::{ContentRef, Profile, load_profile, save_profile, list_profiles};
+use crate::store::{ContentKind, HashAlgorithm, content_store_path, prefixed_hash, store_dirs};
+use crate::version_tags::HASH_BATCH;
+use crate::watchlist::{WatchedRelease, check_watched_releases};
 use anyhow::{Result, Context};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -30,6 +37,13 @@ pub struct StorageStats {
     pub minecraft_bytes: u64,
     /// Storage used by the library database
     pub database_bytes: u64,
+    /// Storage used by large instance folders (replays, schematics) that were
+    /// not relocated to another disk
+    #[serde(default)]
+    pub instance_folders_bytes: u64,
+    /// Per-profile sizes of large instance folders
+    #[serde(default)]
+    pub instance_folders: Vec<FolderUsage>,
     /// Number of unique content items
     pub unique_items: u32,
     /// Number of profile references to content
@@ -62,6 +76,9 @@ pub struct ContentUpdate {
 pub struct UpdateCheckResult {
     /// Content items with available updates
     pub updates: Vec<ContentUpdate>,
+    /// New releases of watched projects (see [`crate::watchlist`])
+    #[serde(default)]
+    pub watched: Vec<WatchedRelease>,
     /// Number of items checked
     pub checked: u32,
     /// Number of items that couldn't be checked (manual imports, etc.)
@@ -75,14 +92,22 @@ pub fn get_storage_stats(paths: &Paths) -> Result<StorageStats> {
     let mut stats = StorageStats::default();
 
     // Calculate store sizes
-    stats.mods_bytes = dir_size(&paths.store_mods)?;
-    stats.resourcepacks_bytes = dir_size(&paths.store_resourcepacks)?;
-    stats.shaderpacks_bytes = dir_size(&paths.store_shaderpacks)?;
-    stats.skins_bytes = dir_size(&paths.store_skins)?;
+    stats.mods_bytes = store_size(&paths.store_mods)?;
+    stats.resourcepacks_bytes = store_size(&paths.store_resourcepacks)?;
+    stats.shaderpacks_bytes = store_size(&paths.store_shaderpacks)?;
+    stats.skins_bytes = store_size(&paths.store_skins)?;
 
-    // Calculate Minecraft data size
+    // Calculate Minecraft data size; asset objects come from their database
+    // rather than walking thousands of files
     let minecraft_root = paths.minecraft_versions.parent().unwrap_or(&paths.minecraft_versions);
-    stats.minecraft_bytes = dir_size(minecraft_root)?;
+    let assets_root = paths.minecraft_assets_objects.parent().unwrap_or(&paths.minecraft_assets_objects);
+    for entry in fs::read_dir(minecraft_root).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path != assets_root {
+            stats.minecraft_bytes += if path.is_dir() { dir_size(&path)? } else { entry.metadata()?.len() };
+        }
+    }
+    stats.minecraft_bytes += dir_size(&paths.minecraft_assets_indexes)? + asset_usage(paths)?.bytes;
 
     // Database size
     if paths.library_db.exists() {
@@ -91,13 +116,22 @@ pub fn get_storage_stats(paths: &Paths) -> Result<StorageStats> {
             .unwrap_or(0);
     }
 
+    stats.instance_folders = aux_folder_usage(paths)?;
+    stats.instance_folders_bytes = stats
+        .instance_folders
+        .iter()
+        .filter(|usage| usage.relocated_to.is_none())
+        .map(|usage| usage.bytes)
+        .sum();
+
     // Total bytes
     stats.total_bytes = stats.mods_bytes
         + stats.resourcepacks_bytes
         + stats.shaderpacks_bytes
         + stats.skins_bytes
         + stats.minecraft_bytes
-        + stats.database_bytes;
+        + stats.database_bytes
+        + stats.instance_folders_bytes;
 
     // Count unique items and references
     let mut unique_hashes: HashSet<String> = HashSet::new();
@@ -110,11 +144,15 @@ pub fn get_storage_stats(paths: &Paths) -> Result<StorageStats> {
         (&paths.store_shaderpacks, "shaderpack"),
         (&paths.store_skins, "skin"),
     ] {
-        if store_path.exists() {
-            if let Ok(entries) = fs::read_dir(store_path) {
+        for (algorithm, dir) in store_dirs(store_path) {
+            if let Ok(entries) = fs::read_dir(&dir) {
                 for entry in entries.flatten() {
                     if entry.path().is_file() {
-                        let hash = entry.file_name().to_string_lossy().to_string();
+                        let name = entry.file_name().to_string_lossy().to_string();
+                        let hash = match algorithm {
+                            HashAlgorithm::Sha256 => name,
+                            HashAlgorithm::Blake3 => format!("blake3:{name}"),
+                        };
                         unique_hashes.insert(hash);
                     }
                 }
@@ -160,10 +198,12 @@ pub fn get_storage_stats(paths: &Paths) -> Result<StorageStats> {
     Ok(stats)
 }
 
-/// Check for updates for all content in all profiles
+/// Check for updates for all content in all profiles and for new releases
+/// of watched projects
 pub fn check_all_updates(paths: &Paths, curseforge_api_key: Option<&str>) -> Result<UpdateCheckResult> {
     let mut result = UpdateCheckResult::default();
     let store = ContentStore::new(curseforge_api_key);
+    let library = Library::from_paths(paths).ok();
 
     let profile_ids = list_profiles(paths)?;
 
@@ -176,12 +216,15 @@ pub fn check_all_updates(paths: &Paths, curseforge_api_key: Option<&str>) -> Res
             }
         };
 
+        let identified = identify_profile_content(library.as_ref(), &store, &profile, &mut result);
+
         // Check mods
         check_content_updates(
             &store,
             &profile,
             &profile.mods,
             "mod",
+            &identified,
             &mut result,
         );
 
@@ -191,6 +234,7 @@ pub fn check_all_updates(paths: &Paths, curseforge_api_key: Option<&str>) -> Res
             &profile,
             &profile.resourcepacks,
             "resourcepack",
+            &identified,
             &mut result,
         );
 
@@ -200,10 +244,20 @@ pub fn check_all_updates(paths: &Paths, curseforge_api_key: Option<&str>) -> Res
             &profile,
             &profile.shaderpacks,
             "shaderpack",
+            &identified,
             &mut result,
         );
     }
 
+    // Watched projects don't need to be installed anywhere
+    match check_watched_releases(paths, &store) {
+        Ok((releases, errors)) => {
+            result.watched = releases;
+            result.errors.extend(errors);
+        }
+        Err(e) => result.errors.push(format!("Failed to load watch list: {}", e)),
+    }
+
     Ok(result)
 }
 
@@ -217,9 +271,11 @@ pub fn check_profile_updates(
     let store = ContentStore::new(curseforge_api_key);
 
     let profile = load_profile(paths, profile_id)?;
+    let library = Library::from_paths(paths).ok();
+    let identified = identify_profile_content(library.as_ref(), &store, &profile, &mut result);
 
     // Check mods
-    check_content_updates(&store, &profile, &profile.mods, "mod", &mut result);
+    check_content_updates(&store, &profile, &profile.mods, "mod", &identified, &mut result);
 
     // Check resourcepacks
     check_content_updates(
@@ -227,6 +283,7 @@ pub fn check_profile_updates(
         &profile,
         &profile.resourcepacks,
         "resourcepack",
+        &identified,
         &mut result,
     );
 
@@ -236,17 +293,42 @@ pub fn check_profile_updates(
         &profile,
         &profile.shaderpacks,
         "shaderpack",
+        &identified,
         &mut result,
     );
 
     Ok(result)
 }
 
+/// [`identify_by_digest`] over all of a profile's content. Failures are
+/// reported and leave the content unidentified.
+fn identify_profile_content(
+    library: Option<&Library>,
+    store: &ContentStore,
+    profile: &Profile,
+    result: &mut UpdateCheckResult,
+) -> HashMap<String, (String, String)> {
+    let Some(library) = library else {
+        return HashMap::new();
+    };
+    let contents: Vec<&ContentRef> = profile
+        .mods
+        .iter()
+        .chain(&profile.resourcepacks)
+        .chain(&profile.shaderpacks)
+        .collect();
+    identify_by_digest(library, store, &contents).unwrap_or_else(|e| {
+        result.errors.push(format!("Failed to identify content of {}: {}", profile.id, e));
+        HashMap::new()
+    })
+}
+
 fn check_content_updates(
     store: &ContentStore,
     profile: &Profile,
     content_list: &[ContentRef],
     content_type: &str,
+    identified: &HashMap<String, (String, String)>,
     result: &mut UpdateCheckResult,
 ) {
     let loader = profile.loader.as_ref().map(|l| l.loader_type.as_str());
@@ -258,14 +340,18 @@ fn check_content_updates(
             continue;
         }
 
-        // Can only check updates if we have platform info
-        let (platform, project_id) = match (&content.platform, &content.project_id) {
-            (Some(p), Some(id)) => (p.as_str(), id.as_str()),
-            _ => {
-                // No platform info - manual import
-                result.skipped += 1;
-                continue;
-            }
+        // Can only check updates if we have platform info, or the file is
+        // known to Modrinth by digest
+        let (platform, project_id, current_version_id) = match (&content.platform, &content.project_id) {
+            (Some(p), Some(id)) => (p.as_str(), id.as_str(), content.version_id.as_deref().unwrap_or("")),
+            _ => match identified.get(&content.hash) {
+                Some((project_id, version_id)) => ("modrinth", project_id.as_str(), version_id.as_str()),
+                None => {
+                    // Unknown manual import
+                    result.skipped += 1;
+                    continue;
+                }
+            },
         };
 
         // Parse platform
@@ -299,7 +385,6 @@ fn check_content_updates(
         };
 
         // Compare versions
-        let current_version_id = content.version_id.as_deref().unwrap_or("");
         if latest.id != current_version_id {
             // There's an update available
             result.updates.push(ContentUpdate {
@@ -315,7 +400,37 @@ fn check_content_updates(
     }
 }
 
-/// Apply a specific update to a profile
+/// A content update resolved against the store, before anything is
+/// downloaded or saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePlan {
+    pub profile_id: String,
+    pub content_type: String,
+    /// The profile's reference as it is now
+    pub current: ContentRef,
+    /// Platform the update comes from; manual imports identified by
+    /// digest get "modrinth"
+    pub platform: String,
+    pub project_id: String,
+    /// The store version that would replace the current file
+    pub version: ContentVersion,
+}
+
+/// Resolve an update without applying it, for previews and dry runs
+pub fn plan_update(
+    paths: &Paths,
+    profile_id: &str,
+    content_name: &str,
+    content_type: &str,
+    new_version_id: &str,
+    curseforge_api_key: Option<&str>,
+) -> Result<UpdatePlan> {
+    let store = ContentStore::new(curseforge_api_key);
+    let profile = load_profile(paths, profile_id)?;
+    resolve_update(paths, &store, &profile, content_name, content_type, new_version_id)
+}
+
+/// Apply a specific update to a profile, storing the new file under `algorithm`
 pub fn apply_update(
     paths: &Paths,
     profile_id: &str,
@@ -323,65 +438,211 @@ pub fn apply_update(
     content_type: &str,
     new_version_id: &str,
     curseforge_api_key: Option<&str>,
+    algorithm: HashAlgorithm,
 ) -> Result<Profile> {
     let store = ContentStore::new(curseforge_api_key);
     let mut profile = load_profile(paths, profile_id)?;
+    let plan = resolve_update(paths, &store, &profile, content_name, content_type, new_version_id)?;
 
-    // Find the content to update
+    // Download and store the new version
+    let ct = match content_type {
+        "mod" => ContentType::Mod,
+        "resourcepack" => ContentType::ResourcePack,
+        "shaderpack" => ContentType::ShaderPack,
+        _ => ContentType::Mod,
+    };
+    let new_ref = store.download_to_store(paths, &plan.version, ct, algorithm)?;
+
+    // Update the content reference
     let content_list = match content_type {
-        "mod" => &mut profile.mods,
         "resourcepack" => &mut profile.resourcepacks,
         "shaderpack" => &mut profile.shaderpacks,
-        _ => return Err(anyhow::anyhow!("invalid content type: {}", content_type)),
+        _ => &mut profile.mods,
     };
+    let content = content_list
+        .iter_mut()
+        .find(|c| c.name == content_name)
+        .ok_or_else(|| anyhow::anyhow!("content not found: {}", content_name))?;
+    content.hash = new_ref.hash;
+    content.version = new_ref.version;
+    content.version_id = Some(plan.version.id.clone());
+    content.file_name = new_ref.file_name;
+    content.source = new_ref.source;
+    content.platform = Some(plan.platform.clone());
+    content.project_id = Some(plan.project_id.clone());
+    let updated = content.clone();
 
+    save_profile(paths, &profile)?;
+
+    // Provenance is informational; a library failure must not fail the update
+    let _ = record_update_provenance(paths, &plan.current, &updated, content_type);
+
+    Ok(profile)
+}
+
+/// Put back the version an update replaced, as linked in the library. The
+/// earlier file must still be in the store. The content is pinned so the
+/// next update run doesn't undo the rollback.
+pub fn rollback_update(paths: &Paths, profile_id: &str, content_name: &str, content_type: &str) -> Result<Profile> {
+    let kind = match content_type {
+        "mod" => ContentKind::Mod,
+        "resourcepack" => ContentKind::ResourcePack,
+        "shaderpack" => ContentKind::ShaderPack,
+        _ => return Err(anyhow::anyhow!("invalid content type: {}", content_type)),
+    };
+    let mut profile = load_profile(paths, profile_id)?;
+    let content_list = match kind {
+        ContentKind::ResourcePack => &mut profile.resourcepacks,
+        ContentKind::ShaderPack => &mut profile.shaderpacks,
+        _ => &mut profile.mods,
+    };
     let content = content_list
         .iter_mut()
         .find(|c| c.name == content_name)
         .ok_or_else(|| anyhow::anyhow!("content not found: {}", content_name))?;
 
-    // Get platform info
-    let platform = content
-        .platform
-        .as_ref()
-        .ok_or_else(|| anyhow::anyhow!("content has no platform info"))?;
-    let project_id = content
-        .project_id
-        .as_ref()
-        .ok_or_else(|| anyhow::anyhow!("content has no project ID"))?;
-
-    let platform = match platform.to_lowercase().as_str() {
+    let library = Library::from_paths(paths)?;
+    let previous = match library.get_item_by_hash(&content.hash)?.and_then(|item| item.replaces) {
+        Some(id) => library.get_item(id)?,
+        None => None,
+    }
+    .ok_or_else(|| anyhow::anyhow!("no earlier version of {} is recorded", content_name))?;
+    if !content_store_path(paths, kind, &previous.hash).exists() {
+        return Err(anyhow::anyhow!(
+            "{} {} is no longer in the store",
+            previous.name,
+            previous.source_version.as_deref().unwrap_or("(unknown version)")
+        ));
+    }
+
+    content.hash = prefixed_hash(&previous.hash);
+    content.version = previous.source_version;
+    content.version_id = None;
+    content.file_name = previous.file_name;
+    content.source = previous.source_url;
+    content.pinned = true;
+    save_profile(paths, &profile)?;
+    Ok(profile)
+}
+
+/// Find the content and the store version that would replace it
+fn resolve_update(
+    paths: &Paths,
+    store: &ContentStore,
+    profile: &Profile,
+    content_name: &str,
+    content_type: &str,
+    new_version_id: &str,
+) -> Result<UpdatePlan> {
+    let content_list = match content_type {
+        "mod" => &profile.mods,
+        "resourcepack" => &profile.resourcepacks,
+        "shaderpack" => &profile.shaderpacks,
+        _ => return Err(anyhow::anyhow!("invalid content type: {}", content_type)),
+    };
+    let content = content_list
+        .iter()
+        .find(|c| c.name == content_name)
+        .ok_or_else(|| anyhow::anyhow!("content not found: {}", content_name))?;
+
+    // Get platform info, identifying manual imports by digest
+    let (platform_name, project_id) = match (&content.platform, &content.project_id) {
+        (Some(platform), Some(project_id)) => (platform.clone(), project_id.clone()),
+        _ => {
+            let library = Library::from_paths(paths)?;
+            let (project_id, _) = identify_by_digest(&library, store, &[content])?
+                .remove(&content.hash)
+                .ok_or_else(|| anyhow::anyhow!("content has no platform info"))?;
+            ("modrinth".to_string(), project_id)
+        }
+    };
+
+    let platform = match platform_name.to_lowercase().as_str() {
         "modrinth" => Platform::Modrinth,
         "curseforge" => Platform::CurseForge,
-        _ => return Err(anyhow::anyhow!("unsupported platform: {}", platform)),
+        _ => return Err(anyhow::anyhow!("unsupported platform: {}", platform_name)),
     };
 
     // Get all versions and find the requested one
-    let versions = store.get_versions(platform, project_id, None, None)?;
+    let versions = store.get_versions(platform, &project_id, None, None)?;
     let version = versions
         .into_iter()
         .find(|v| v.id == new_version_id)
         .ok_or_else(|| anyhow::anyhow!("version not found: {}", new_version_id))?;
 
-    // Download and store the new version
-    let ct = match content_type {
-        "mod" => ContentType::Mod,
-        "resourcepack" => ContentType::ResourcePack,
-        "shaderpack" => ContentType::ShaderPack,
-        _ => ContentType::Mod,
-    };
+    Ok(UpdatePlan {
+        profile_id: profile.id.clone(),
+        content_type: content_type.to_string(),
+        current: content.clone(),
+        platform: platform_name,
+        project_id,
+        version,
+    })
+}
 
-    let new_ref = store.download_to_store(paths, &version, ct)?;
+/// Modrinth project and version ids of content without platform info,
+/// found by the SHA-1 digests the library recorded for its files (filled in
+/// by `library sync`). Keyed by content hash.
+fn identify_by_digest(
+    library: &Library,
+    store: &ContentStore,
+    contents: &[&ContentRef],
+) -> Result<HashMap<String, (String, String)>> {
+    let mut by_sha1: HashMap<String, &str> = HashMap::new();
+    for content in contents {
+        if content.platform.is_some() && content.project_id.is_some() {
+            continue;
+        }
+        if let Some(sha1) = library.get_item_by_hash(&content.hash)?.and_then(|item| item.sha1) {
+            by_sha1.insert(sha1, &content.hash);
+        }
+    }
 
-    // Update the content reference
-    content.hash = new_ref.hash;
-    content.version = new_ref.version;
-    content.version_id = Some(new_version_id.to_string());
-    content.file_name = new_ref.file_name;
-    content.source = new_ref.source;
+    let hashes: Vec<&str> = by_sha1.keys().map(String::as_str).collect();
+    let mut identified = HashMap::new();
+    for batch in hashes.chunks(HASH_BATCH) {
+        for (sha1, ids) in store.modrinth_ids_by_sha1(batch)? {
+            if let Some(hash) = by_sha1.get(&sha1) {
+                identified.insert(hash.to_string(), ids);
+            }
+        }
+    }
+    Ok(identified)
+}
 
-    save_profile(paths, &profile)?;
-    Ok(profile)
+/// Link the updated library item to the one it replaced
+fn record_update_provenance(
+    paths: &Paths,
+    previous: &ContentRef,
+    updated: &ContentRef,
+    content_type: &str,
+) -> Result<()> {
+    if previous.hash == updated.hash {
+        return Ok(());
+    }
+    let library = Library::from_paths(paths)?;
+    let content_type = LibraryContentType::from_str(content_type).unwrap_or(LibraryContentType::Mod);
+
+    let old_item = match library.get_item_by_hash(&previous.hash)? {
+        Some(item) => item,
+        None => library.add_item(&library_input(previous, content_type))?,
+    };
+    let new_item = library.add_item(&library_input(updated, content_type))?;
+    library.record_replacement(old_item.id, new_item.id)
+}
+
+fn library_input(content: &ContentRef, content_type: LibraryContentType) -> LibraryItemInput {
+    LibraryItemInput {
+        hash: content.hash.clone(),
+        content_type: Some(content_type.as_str().to_string()),
+        name: Some(content.name.clone()),
+        file_name: content.file_name.clone(),
+        source_url: content.source.clone(),
+        source_platform: content.platform.clone(),
+        source_project_id: content.project_id.clone(),
+        source_version: content.version.clone(),
+        ..Default::default()
+    }
 }
 
 /// Set pinned state for a content item
@@ -435,6 +696,10 @@ pub fn set_content_enabled(
 
     content.enabled = enabled;
     save_profile(paths, &profile)?;
+    // Update an existing instance right away instead of on the next launch
+    if paths.instance_dir(profile_id).exists() {
+        sync_instance_content(paths, &profile)?;
+    }
     Ok(profile)
 }
 
@@ -443,6 +708,11 @@ fn normalize_hash(hash: &str) -> String {
     hash.strip_prefix("sha256:").unwrap_or(hash).to_string()
 }
 
+/// Size of a content kind across its per-algorithm store directories
+fn store_size(sha256_dir: &std::path::Path) -> Result<u64> {
+    store_dirs(sha256_dir).iter().map(|(_, dir)| dir_size(dir)).sum()
+}
+
 /// Calculate the total size of a directory recursively
 fn dir_size(path: &std::path::Path) -> Result<u64> {
     if !path.exists() {
@@ -1,5 +1,11 @@
+use crate::disk::WriteContext;
+use aes_gcm::aead::OsRng;
+use aes_gcm::aead::rand_core::RngCore;
 use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 
 pub fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
@@ -48,6 +54,96 @@ pub fn copy_dir_merge(src: &Path, dst: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Recursively copy `src` into `dst`, cloning files copy-on-write where the
+/// filesystem supports it. Top-level entries named in `skip` and symlinks are
+/// left out.
+/// Total size of the files under `path`; unreadable entries count as empty
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+pub fn clone_dir_all(src: &Path, dst: &Path, skip: &[&str]) -> Result<()> {
+    if !src.exists() {
+        return Ok(());
+    }
+    fs::create_dir_all(dst).write_context(dst, || format!("failed to create dir: {}", dst.display()))?;
+    for entry in
+        fs::read_dir(src).with_context(|| format!("failed to read dir: {}", src.display()))?
+    {
+        let entry = entry.context("failed to read dir entry")?;
+        if skip.iter().any(|name| entry.file_name() == **name) {
+            continue;
+        }
+        let file_type = entry.file_type().context("failed to read entry type")?;
+        let from = entry.path();
+        let to = dst.join(entry.file_name());
+        if file_type.is_dir() {
+            clone_dir_all(&from, &to, &[])?;
+        } else if file_type.is_file() {
+            reflink_or_copy(&from, &to).write_context(&to, || {
+                format!("failed to copy {} to {}", from.display(), to.display())
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy a file as a copy-on-write clone on filesystems that support it
+/// (btrfs, XFS, APFS), falling back to a regular copy
+pub fn reflink_or_copy(src: &Path, dst: &Path) -> io::Result<()> {
+    if reflink(src, dst).is_ok() {
+        return Ok(());
+    }
+    fs::copy(src, dst).map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let source = fs::File::open(src)?;
+    let target = fs::OpenOptions::new().write(true).create_new(true).open(dst)?;
+    // SAFETY: both descriptors are open for the duration of the call
+    let result = unsafe { libc::ioctl(target.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) };
+    if result == 0 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    drop(target);
+    let _ = fs::remove_file(dst);
+    Err(err)
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let src = CString::new(src.as_os_str().as_bytes())?;
+    let dst = CString::new(dst.as_os_str().as_bytes())?;
+    // SAFETY: both paths are valid NUL-terminated strings
+    if unsafe { libc::clonefile(src.as_ptr(), dst.as_ptr(), 0) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_src: &Path, _dst: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
 pub fn sanitize_filename(name: &str) -> String {
     let mut out = String::with_capacity(name.len());
     for ch in name.chars() {
@@ -64,6 +160,22 @@ pub fn sanitize_filename(name: &str) -> String {
     }
 }
 
+pub fn slugify(input: &str) -> String {
+    let mut out = String::new();
+    let mut last_dash = false;
+    for ch in input.chars() {
+        let lower = ch.to_ascii_lowercase();
+        if lower.is_ascii_alphanumeric() {
+            out.push(lower);
+            last_dash = false;
+        } else if !last_dash {
+            out.push('-');
+            last_dash = true;
+        }
+    }
+    out.trim_matches('-').to_string()
+}
+
 pub fn unique_path(base_dir: &Path, file_name: &str) -> PathBuf {
     let mut candidate = base_dir.join(file_name);
     if !candidate.exists() {
@@ -98,3 +210,16 @@ pub fn now_epoch_secs() -> u64 {
         .unwrap_or_default()
         .as_secs()
 }
+
+/// 32 random bytes from the OS, URL-safe base64 without padding
+pub fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Numeric key for release ids such as `1.21.4`; snapshots and pre-releases
+/// have none
+pub fn release_key(id: &str) -> Option<Vec<u32>> {
+    id.split('.').map(|part| part.parse().ok()).collect()
+}
@@ -0,0 +1,463 @@
+//! Import from the official Minecraft launcher
+//!
+//! Reads `launcher_profiles.json` and `launcher_accounts.json` from a `.minecraft`
+//! directory to pre-fill profiles. Account tokens are never imported (the official
+//! launcher encrypts them); known accounts are reported so the user can re-authenticate.
+
+use crate::accounts::load_accounts;
+use crate::minecraft::resolve_latest_version;
+use crate::paths::Paths;
+use crate::profile::{Loader, Runtime, create_profile};
+use crate::util::slugify;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Files copied from a vanilla profile's game directory into the profile overrides
+const COPIED_GAME_FILES: &[&str] = &["options.txt", "servers.dat"];
+
+#[derive(Debug, Deserialize)]
+struct LauncherProfilesFile {
+    #[serde(default)]
+    profiles: HashMap<String, LauncherProfileEntry>,
+    /// Pre-2021 launchers stored accounts here
+    #[serde(default, rename = "authenticationDatabase")]
+    authentication_database: HashMap<String, LegacyAuthEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LauncherProfileEntry {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default, rename = "type")]
+    profile_type: Option<String>,
+    #[serde(default, rename = "lastVersionId")]
+    last_version_id: Option<String>,
+    #[serde(default, rename = "gameDir")]
+    game_dir: Option<String>,
+    #[serde(default, rename = "javaDir")]
+    java_dir: Option<String>,
+    #[serde(default, rename = "javaArgs")]
+    java_args: Option<String>,
+    #[serde(default, rename = "lastUsed")]
+    last_used: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyAuthEntry {
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    profiles: HashMap<String, LegacyAuthProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyAuthProfile {
+    #[serde(default, rename = "displayName")]
+    display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LauncherAccountsFile {
+    #[serde(default)]
+    accounts: HashMap<String, LauncherAccountEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LauncherAccountEntry {
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default, rename = "minecraftProfile")]
+    minecraft_profile: Option<LauncherMinecraftProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LauncherMinecraftProfile {
+    id: String,
+    name: String,
+}
+
+/// A profile found in the official launcher
+#[derive(Debug, Clone, Serialize)]
+pub struct VanillaProfile {
+    pub name: String,
+    /// Version id as stored by the launcher (may include a loader, e.g. `fabric-loader-0.15.7-1.20.4`)
+    pub version_id: Option<String>,
+    /// `latest-release`, `latest-snapshot` or `custom`
+    pub profile_type: Option<String>,
+    pub game_dir: Option<PathBuf>,
+    pub java: Option<String>,
+    pub java_args: Option<String>,
+    /// ISO 8601 timestamp of the last launch
+    pub last_used: Option<String>,
+}
+
+/// An account known to the official launcher
+#[derive(Debug, Clone, Serialize)]
+pub struct VanillaAccount {
+    pub username: String,
+    pub uuid: Option<String>,
+    /// Whether a Shard account with this UUID/username already exists
+    pub already_added: bool,
+}
+
+/// Outcome for one imported profile
+#[derive(Debug, Clone, Serialize)]
+pub struct VanillaProfileImport {
+    pub name: String,
+    pub profile_id: Option<String>,
+    pub skipped: Option<String>,
+}
+
+/// Result of importing from the official launcher
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VanillaImportResult {
+    pub profiles: Vec<VanillaProfileImport>,
+    /// Accounts that need to be signed in again via `account add`
+    pub accounts: Vec<VanillaAccount>,
+}
+
+/// Default `.minecraft` location for the current OS
+pub fn default_minecraft_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        dirs::config_dir().map(|dir| dir.join(".minecraft"))
+    } else if cfg!(target_os = "macos") {
+        dirs::data_dir().map(|dir| dir.join("minecraft"))
+    } else {
+        dirs::home_dir().map(|dir| dir.join(".minecraft"))
+    }
+}
+
+fn read_profiles_file(minecraft_dir: &Path) -> Result<Option<LauncherProfilesFile>> {
+    let path = minecraft_dir.join("launcher_profiles.json");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read launcher profiles: {}", path.display()))?;
+    let file = serde_json::from_str(&data)
+        .with_context(|| format!("failed to parse launcher profiles: {}", path.display()))?;
+    Ok(Some(file))
+}
+
+/// List profiles from `launcher_profiles.json`
+pub fn read_vanilla_profiles(minecraft_dir: &Path) -> Result<Vec<VanillaProfile>> {
+    let Some(file) = read_profiles_file(minecraft_dir)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut profiles: Vec<VanillaProfile> = file
+        .profiles
+        .into_iter()
+        .map(|(key, entry)| {
+            let name = entry
+                .name
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| match entry.profile_type.as_deref() {
+                    Some("latest-release") => "Latest release".to_string(),
+                    Some("latest-snapshot") => "Latest snapshot".to_string(),
+                    _ => key.clone(),
+                });
+            VanillaProfile {
+                name,
+                version_id: entry.last_version_id,
+                profile_type: entry.profile_type,
+                game_dir: entry.game_dir.map(PathBuf::from),
+                java: entry.java_dir,
+                java_args: entry.java_args,
+                last_used: entry.last_used,
+            }
+        })
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+/// List accounts from `launcher_accounts.json` (or the legacy auth database)
+pub fn read_vanilla_accounts(paths: &Paths, minecraft_dir: &Path) -> Result<Vec<VanillaAccount>> {
+    let mut found: Vec<(String, Option<String>)> = Vec::new();
+
+    let accounts_path = minecraft_dir.join("launcher_accounts.json");
+    if accounts_path.exists() {
+        let data = fs::read_to_string(&accounts_path).with_context(|| {
+            format!("failed to read launcher accounts: {}", accounts_path.display())
+        })?;
+        let file: LauncherAccountsFile = serde_json::from_str(&data).with_context(|| {
+            format!("failed to parse launcher accounts: {}", accounts_path.display())
+        })?;
+        for entry in file.accounts.into_values() {
+            match entry.minecraft_profile {
+                Some(profile) => found.push((profile.name, Some(profile.id))),
+                None => {
+                    if let Some(username) = entry.username {
+                        found.push((username, None));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(file) = read_profiles_file(minecraft_dir)? {
+        for entry in file.authentication_database.into_values() {
+            if entry.profiles.is_empty() {
+                if let Some(username) = entry.username {
+                    found.push((username, None));
+                }
+                continue;
+            }
+            for (uuid, profile) in entry.profiles {
+                let name = profile
+                    .display_name
+                    .or_else(|| entry.username.clone())
+                    .unwrap_or_else(|| uuid.clone());
+                found.push((name, Some(uuid)));
+            }
+        }
+    }
+
+    let existing = load_accounts(paths)?;
+    let mut accounts: Vec<VanillaAccount> = Vec::new();
+    for (username, uuid) in found {
+        let uuid = uuid.map(|id| id.replace('-', "").to_lowercase());
+        if accounts
+            .iter()
+            .any(|a| a.username.eq_ignore_ascii_case(&username) || (a.uuid.is_some() && a.uuid == uuid))
+        {
+            continue;
+        }
+        let already_added = existing.accounts.iter().any(|account| {
+            account.username.eq_ignore_ascii_case(&username)
+                || uuid
+                    .as_deref()
+                    .is_some_and(|id| account.uuid.replace('-', "").eq_ignore_ascii_case(id))
+        });
+        accounts.push(VanillaAccount {
+            username,
+            uuid,
+            already_added,
+        });
+    }
+    Ok(accounts)
+}
+
+/// Split a launcher version id into the Minecraft version and mod loader
+pub fn parse_vanilla_version_id(version_id: &str) -> (String, Option<Loader>) {
+    let loader = |loader_type: &str, version: &str| {
+        Some(Loader {
+            loader_type: loader_type.to_string(),
+            version: version.to_string(),
+        })
+    };
+
+    // fabric-loader-<loader>-<mc>, quilt-loader-<loader>-<mc>
+    for (prefix, loader_type) in [("fabric-loader-", "fabric"), ("quilt-loader-", "quilt")] {
+        if let Some(rest) = version_id.strip_prefix(prefix)
+            && let Some((loader_version, mc_version)) = rest.split_once('-')
+        {
+            return (mc_version.to_string(), loader(loader_type, loader_version));
+        }
+    }
+
+    // neoforge-<version>; 20.4.x -> 1.20.4, 21.0.x -> 1.21
+    if let Some(version) = version_id.strip_prefix("neoforge-") {
+        let mut parts = version.split('.');
+        if let (Some(major), Some(minor)) = (parts.next(), parts.next()) {
+            let mc_version = if minor == "0" {
+                format!("1.{major}")
+            } else {
+                format!("1.{major}.{minor}")
+            };
+            return (mc_version, loader("neoforge", version));
+        }
+    }
+
+    // <mc>-forge-<version>, and the older <mc>-Forge<version>-<mc>
+    let lower = version_id.to_lowercase();
+    if let Some(idx) = lower.find("-forge") {
+        let mc_version = &version_id[..idx];
+        let rest = version_id[idx + "-forge".len()..].trim_start_matches('-');
+        let forge_version = rest
+            .strip_suffix(&format!("-{mc_version}"))
+            .unwrap_or(rest);
+        if !forge_version.is_empty() {
+            return (mc_version.to_string(), loader("forge", forge_version));
+        }
+    }
+
+    (version_id.to_string(), None)
+}
+
+/// Split launcher JVM arguments into a memory setting and remaining args
+fn split_java_args(java_args: &str) -> (Option<String>, Vec<String>) {
+    let args = shell_words::split(java_args)
+        .unwrap_or_else(|_| java_args.split_whitespace().map(str::to_string).collect());
+    let mut memory = None;
+    let mut rest = Vec::new();
+    for arg in args {
+        match arg.strip_prefix("-Xmx") {
+            Some(value) => memory = Some(value.to_string()),
+            None => rest.push(arg),
+        }
+    }
+    (memory, rest)
+}
+
+/// Create Shard profiles for every profile in the official launcher and report
+/// accounts that need re-authentication. Existing profiles are left untouched.
+pub fn import_vanilla_launcher(paths: &Paths, minecraft_dir: &Path) -> Result<VanillaImportResult> {
+    if !minecraft_dir.exists() {
+        bail!("minecraft directory not found: {}", minecraft_dir.display());
+    }
+
+    let mut result = VanillaImportResult::default();
+    for vanilla in read_vanilla_profiles(minecraft_dir)? {
+        result.profiles.push(import_profile(paths, minecraft_dir, &vanilla));
+    }
+    result.accounts = read_vanilla_accounts(paths, minecraft_dir)?;
+    Ok(result)
+}
+
+fn import_profile(paths: &Paths, minecraft_dir: &Path, vanilla: &VanillaProfile) -> VanillaProfileImport {
+    let skipped = |reason: String| VanillaProfileImport {
+        name: vanilla.name.clone(),
+        profile_id: None,
+        skipped: Some(reason),
+    };
+
+    let profile_id = slugify(&vanilla.name);
+    if profile_id.is_empty() {
+        return skipped("name has no usable characters".to_string());
+    }
+    if paths.is_profile_present(&profile_id) {
+        return skipped(format!("profile already exists: {profile_id}"));
+    }
+
+    // The launcher's built-in profiles store the alias as the version too
+    let version_id = match (vanilla.profile_type.as_deref(), vanilla.version_id.as_deref()) {
+        (Some("latest-snapshot"), _) | (_, Some("latest-snapshot")) => resolve_latest_version(paths, true),
+        (Some("latest-release"), _) | (_, Some("latest-release")) => resolve_latest_version(paths, false),
+        (_, Some(id)) if !id.is_empty() => Ok(id.to_string()),
+        _ => resolve_latest_version(paths, false),
+    };
+    let version_id = match version_id {
+        Ok(id) => id,
+        Err(err) => return skipped(format!("failed to resolve version: {err}")),
+    };
+    let (mc_version, loader) = parse_vanilla_version_id(&version_id);
+
+    let (memory, args) = vanilla
+        .java_args
+        .as_deref()
+        .map(split_java_args)
+        .unwrap_or_default();
+    let runtime = Runtime {
+        java: vanilla.java.clone(),
+        memory,
+        args,
+        wrapper: Vec::new(),
+        jvm_preset: None,
+        priority: None,
+        cpu_affinity: Vec::new(),
+        gamescope: None,
+    };
+
+    if let Err(err) = create_profile(paths, &profile_id, &mc_version, loader, runtime) {
+        return skipped(err.to_string());
+    }
+
+    // Carry over game settings and the server list
+    let game_dir = vanilla
+        .game_dir
+        .clone()
+        .unwrap_or_else(|| minecraft_dir.to_path_buf());
+    let overrides = paths.profile_overrides(&profile_id);
+    for file in COPIED_GAME_FILES {
+        let src = game_dir.join(file);
+        if src.is_file() {
+            let _ = fs::copy(&src, overrides.join(file));
+        }
+    }
+
+    VanillaProfileImport {
+        name: vanilla.name.clone(),
+        profile_id: Some(profile_id),
+        skipped: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vanilla_version_id() {
+        let (mc, loader) = parse_vanilla_version_id("1.20.4");
+        assert_eq!(mc, "1.20.4");
+        assert!(loader.is_none());
+
+        let (mc, loader) = parse_vanilla_version_id("fabric-loader-0.15.7-1.20.4");
+        let loader = loader.unwrap();
+        assert_eq!(mc, "1.20.4");
+        assert_eq!((loader.loader_type.as_str(), loader.version.as_str()), ("fabric", "0.15.7"));
+
+        let (mc, loader) = parse_vanilla_version_id("1.20.1-forge-47.2.0");
+        let loader = loader.unwrap();
+        assert_eq!(mc, "1.20.1");
+        assert_eq!((loader.loader_type.as_str(), loader.version.as_str()), ("forge", "47.2.0"));
+
+        let (mc, loader) = parse_vanilla_version_id("1.7.10-Forge10.13.4.1614-1.7.10");
+        assert_eq!(mc, "1.7.10");
+        assert_eq!(loader.unwrap().version, "10.13.4.1614");
+
+        let (mc, loader) = parse_vanilla_version_id("neoforge-21.0.167");
+        assert_eq!(mc, "1.21");
+        assert_eq!(loader.unwrap().loader_type, "neoforge");
+    }
+
+    #[test]
+    fn test_import_default_launcher_profile() {
+        use crate::fake_cdn::{FakeCdn, TempRoot};
+        use crate::profile::load_profile;
+
+        let cdn = FakeCdn::start();
+        cdn.publish_version("1.21.4");
+        let root = TempRoot::new("vanilla-import");
+        let paths = &root.paths;
+        let minecraft_dir = paths.root.join("dot-minecraft");
+        fs::create_dir_all(&minecraft_dir).unwrap();
+        // As written by a fresh install of the official launcher
+        fs::write(
+            minecraft_dir.join("launcher_profiles.json"),
+            r#"{
+                "profiles": {
+                    "7c4f1a3e9b2d": {
+                        "created": "1970-01-01T00:00:00.000Z",
+                        "icon": "Grass",
+                        "lastUsed": "1970-01-01T00:00:00.000Z",
+                        "lastVersionId": "latest-release",
+                        "name": "",
+                        "type": "latest-release"
+                    }
+                },
+                "settings": { "enableSnapshots": false },
+                "version": 3
+            }"#,
+        )
+        .unwrap();
+
+        let result = import_vanilla_launcher(paths, &minecraft_dir).unwrap();
+        let id = result.profiles[0].profile_id.clone().expect("profile was imported");
+        let profile = load_profile(paths, &id).unwrap();
+        assert_eq!(profile.mc_version, "1.21.4");
+        assert!(profile.loader.is_none());
+    }
+
+    #[test]
+    fn test_split_java_args() {
+        let (memory, args) = split_java_args("-Xmx4G -XX:+UseG1GC -Xms1G");
+        assert_eq!(memory.as_deref(), Some("4G"));
+        assert_eq!(args, vec!["-XX:+UseG1GC", "-Xms1G"]);
+    }
+}
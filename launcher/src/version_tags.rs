@@ -0,0 +1,157 @@
+//! Library tags for supported Minecraft versions
+//!
+//! Items are tagged `mc:1.20.x`, `mc:1.21.x`, ... from what the content
+//! stores publish, rather than from whichever profile happened to import
+//! them. Files known to Modrinth are looked up by SHA-1, so the tags describe
+//! that exact file; other items with a project id fall back to the versions
+//! the project supports as a whole. Only `mc:` tags are touched.
+
+use crate::content_store::{ContentStore, Platform};
+use crate::disk::WriteContext;
+use crate::library::{Library, LibraryFilter, LibraryItem};
+use crate::paths::Paths;
+use crate::util::now_epoch_secs;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const MC_TAG_PREFIX: &str = "mc:";
+
+/// How often the desktop app refreshes the tags in the background
+pub const AUTO_TAG_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Hashes sent per Modrinth lookup
+pub(crate) const HASH_BATCH: usize = 200;
+
+impl Paths {
+    fn auto_tag_stamp(&self) -> PathBuf {
+        self.cache_manifests.join("mc-tags.json")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AutoTagResult {
+    /// Items whose `mc:` tags changed
+    pub tagged: usize,
+    /// Items whose tags were already up to date
+    pub unchanged: usize,
+    /// Items no store knows about
+    pub unknown: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AutoTagStamp {
+    checked_at: u64,
+}
+
+/// Family tag for a release version: `1.20.4` and `1.20` give `mc:1.20.x`.
+/// Snapshots, pre-releases and non-version labels (`Forge`, `Client`) give
+/// `None`.
+pub fn mc_version_tag(game_version: &str) -> Option<String> {
+    let parts: Vec<&str> = game_version.split('.').collect();
+    if !(2..=3).contains(&parts.len())
+        || parts.iter().any(|part| part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+    Some(format!("{MC_TAG_PREFIX}{}.{}.x", parts[0], parts[1]))
+}
+
+/// Sorted, deduplicated family tags for a list of game versions
+pub fn mc_version_tags(game_versions: &[String]) -> Vec<String> {
+    game_versions
+        .iter()
+        .filter_map(|version| mc_version_tag(version))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+fn store_platform(item: &LibraryItem) -> Option<Platform> {
+    match item.source_platform.as_deref()? {
+        "modrinth" => Some(Platform::Modrinth),
+        "curseforge" => Some(Platform::CurseForge),
+        _ => None,
+    }
+}
+
+/// Tag every library item with the Minecraft versions its store data
+/// supports
+pub fn auto_tag_library(paths: &Paths, library: &Library, store: &ContentStore) -> Result<AutoTagResult> {
+    let mut result = AutoTagResult::default();
+    let items = library.list_items(&LibraryFilter::default())?;
+
+    let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+    let hashes: Vec<&str> = items.iter().filter_map(|item| item.sha1.as_deref()).collect();
+    for batch in hashes.chunks(HASH_BATCH) {
+        match store.game_versions_by_sha1(batch) {
+            Ok(found) => by_hash.extend(found),
+            Err(e) => result.errors.push(format!("Modrinth hash lookup failed: {e}")),
+        }
+    }
+
+    let mut by_project: HashMap<(Platform, String), Option<Vec<String>>> = HashMap::new();
+    for item in &items {
+        let mut game_versions = item.sha1.as_ref().and_then(|sha1| by_hash.get(sha1)).cloned();
+        if game_versions.is_none()
+            && let (Some(platform), Some(project_id)) = (store_platform(item), item.source_project_id.as_ref())
+            && (platform == Platform::Modrinth || store.has_curseforge())
+        {
+            game_versions = by_project
+                .entry((platform, project_id.clone()))
+                .or_insert_with(|| match store.get_project(platform, project_id) {
+                    Ok(project) => Some(project.game_versions),
+                    Err(e) => {
+                        result.errors.push(format!("{}: {e}", item.name));
+                        None
+                    }
+                })
+                .clone();
+        }
+
+        let Some(game_versions) = game_versions else {
+            result.unknown += 1;
+            continue;
+        };
+        if library.set_prefixed_tags(item.id, MC_TAG_PREFIX, &mc_version_tags(&game_versions))? {
+            result.tagged += 1;
+        } else {
+            result.unchanged += 1;
+        }
+    }
+
+    let stamp = paths.auto_tag_stamp();
+    let data = serde_json::to_string(&AutoTagStamp {
+        checked_at: now_epoch_secs(),
+    })
+    .context("failed to serialize auto-tag stamp")?;
+    fs::write(&stamp, data).write_context(&stamp, || format!("failed to write {}", stamp.display()))?;
+    Ok(result)
+}
+
+/// Whether [`AUTO_TAG_INTERVAL`] passed since the last [`auto_tag_library`]
+pub fn auto_tag_due(paths: &Paths) -> bool {
+    let checked_at = fs::read_to_string(paths.auto_tag_stamp())
+        .ok()
+        .and_then(|data| serde_json::from_str::<AutoTagStamp>(&data).ok())
+        .map_or(0, |stamp| stamp.checked_at);
+    now_epoch_secs().saturating_sub(checked_at) >= AUTO_TAG_INTERVAL.as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mc_version_tags() {
+        let versions: Vec<String> = ["1.21.1", "1.21", "1.20.4", "24w14a", "1.21-pre1", "Forge", "Client", "1.20.1"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        assert_eq!(mc_version_tags(&versions), vec!["mc:1.20.x", "mc:1.21.x"]);
+    }
+}
@@ -0,0 +1,175 @@
+//! Minecraft versions by release channel
+//!
+//! Mojang's manifest only types versions as `release`, `snapshot`,
+//! `old_beta` and `old_alpha`; April Fools releases are listed as plain
+//! snapshots. [`list_versions`] classifies every version into a
+//! [`VersionChannel`] and filters by channel, reading the manifest from the
+//! shared cache so the CLI, the desktop app and launches agree on it.
+
+use crate::minecraft::load_version_manifest_within;
+use crate::paths::Paths;
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How old the cached manifest may be before listing refetches it
+const MANIFEST_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// April Fools versions that follow the regular snapshot naming
+const APRIL_FOOLS_IDS: &[&str] = &["15w14a", "1.RV-Pre1", "3D Shareware v1.34"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionChannel {
+    Release,
+    /// Snapshots, pre-releases and release candidates
+    Snapshot,
+    Beta,
+    Alpha,
+    AprilFools,
+}
+
+impl VersionChannel {
+    pub const ALL: [VersionChannel; 5] = [
+        VersionChannel::Release,
+        VersionChannel::Snapshot,
+        VersionChannel::Beta,
+        VersionChannel::Alpha,
+        VersionChannel::AprilFools,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VersionChannel::Release => "release",
+            VersionChannel::Snapshot => "snapshot",
+            VersionChannel::Beta => "beta",
+            VersionChannel::Alpha => "alpha",
+            VersionChannel::AprilFools => "april_fools",
+        }
+    }
+}
+
+impl FromStr for VersionChannel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "release" | "releases" => Ok(VersionChannel::Release),
+            "snapshot" | "snapshots" => Ok(VersionChannel::Snapshot),
+            "beta" | "old_beta" => Ok(VersionChannel::Beta),
+            "alpha" | "old_alpha" => Ok(VersionChannel::Alpha),
+            "april_fools" => Ok(VersionChannel::AprilFools),
+            other => bail!("unknown version channel: {other} (expected release, snapshot, beta, alpha or april-fools)"),
+        }
+    }
+}
+
+/// A version from Mojang's manifest with its channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameVersion {
+    pub id: String,
+    /// Type as listed in the manifest
+    #[serde(rename = "type")]
+    pub version_type: String,
+    #[serde(rename = "releaseTime")]
+    pub release_time: Option<String>,
+    pub channel: VersionChannel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionList {
+    /// Newest first
+    pub versions: Vec<GameVersion>,
+    pub latest_release: Option<String>,
+    pub latest_snapshot: Option<String>,
+}
+
+/// `NNwNNx`, the regular weekly snapshot id
+fn is_weekly_snapshot_id(id: &str) -> bool {
+    let bytes = id.as_bytes();
+    bytes.len() == 6
+        && bytes[..2].iter().all(u8::is_ascii_digit)
+        && bytes[2] == b'w'
+        && bytes[3..5].iter().all(u8::is_ascii_digit)
+        && bytes[5].is_ascii_lowercase()
+}
+
+/// Channel of a manifest entry. April Fools snapshots come out around April
+/// 1st with ids that don't follow the snapshot or pre-release naming.
+pub fn classify_version(id: &str, version_type: &str, release_time: Option<&str>) -> VersionChannel {
+    match version_type {
+        "release" => VersionChannel::Release,
+        "old_beta" => VersionChannel::Beta,
+        "old_alpha" => VersionChannel::Alpha,
+        _ => {
+            let around_april_first = release_time
+                .and_then(|time| time.get(5..10))
+                .is_some_and(|date| matches!(date, "03-31" | "04-01" | "04-02"));
+            let regular = is_weekly_snapshot_id(id)
+                || id.contains("-pre")
+                || id.contains("-rc")
+                || id.contains(" Pre-Release ");
+            if APRIL_FOOLS_IDS.contains(&id) || (around_april_first && !regular) {
+                VersionChannel::AprilFools
+            } else {
+                VersionChannel::Snapshot
+            }
+        }
+    }
+}
+
+/// Every version in the manifest, or only those in `channels` when it isn't
+/// empty. Falls back to a stale cached manifest when Mojang can't be reached.
+pub fn list_versions(paths: &Paths, channels: &[VersionChannel]) -> Result<VersionList> {
+    let manifest = match load_version_manifest_within(paths, MANIFEST_MAX_AGE.as_secs()) {
+        Ok(manifest) => manifest,
+        Err(err) => load_version_manifest_within(paths, u64::MAX).map_err(|_| err)?,
+    };
+    let versions = manifest
+        .versions
+        .into_iter()
+        .map(|entry| {
+            let version_type = entry.version_type.unwrap_or_else(|| "release".to_string());
+            GameVersion {
+                channel: classify_version(&entry.id, &version_type, entry.release_time.as_deref()),
+                id: entry.id,
+                version_type,
+                release_time: entry.release_time,
+            }
+        })
+        .filter(|version| channels.is_empty() || channels.contains(&version.channel))
+        .collect();
+    Ok(VersionList {
+        versions,
+        latest_release: manifest.latest.as_ref().map(|latest| latest.release.clone()),
+        latest_snapshot: manifest.latest.map(|latest| latest.snapshot),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_version() {
+        let classify = |id, ty, time| classify_version(id, ty, Some(time));
+        assert_eq!(classify("1.21.4", "release", "2024-12-03T10:12:57+00:00"), VersionChannel::Release);
+        assert_eq!(classify("24w14a", "snapshot", "2024-04-03T12:00:00+00:00"), VersionChannel::Snapshot);
+        assert_eq!(classify("1.20.5-pre1", "snapshot", "2024-04-01T12:00:00+00:00"), VersionChannel::Snapshot);
+        assert_eq!(classify("24w14potato", "snapshot", "2024-04-01T12:00:00+00:00"), VersionChannel::AprilFools);
+        assert_eq!(classify("23w13a_or_b", "snapshot", "2023-04-01T12:00:00+00:00"), VersionChannel::AprilFools);
+        assert_eq!(classify("15w14a", "snapshot", "2015-04-01T09:00:00+00:00"), VersionChannel::AprilFools);
+        assert_eq!(classify("b1.7.3", "old_beta", "2011-07-07T22:00:00+00:00"), VersionChannel::Beta);
+        assert_eq!(classify("a1.2.6", "old_alpha", "2010-12-02T22:00:00+00:00"), VersionChannel::Alpha);
+    }
+
+    #[test]
+    fn test_parse_version_channel() {
+        for channel in VersionChannel::ALL {
+            assert_eq!(channel.as_str().parse::<VersionChannel>().unwrap(), channel);
+        }
+        assert_eq!("april-fools".parse::<VersionChannel>().unwrap(), VersionChannel::AprilFools);
+        assert!("nightly".parse::<VersionChannel>().is_err());
+    }
+}
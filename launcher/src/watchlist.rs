@@ -0,0 +1,159 @@
+//! Projects watched for new releases
+//!
+//! A watch list entry follows a store project that doesn't have to be
+//! installed in any profile. Update checks report a release once its version
+//! differs from the last one the user has seen, so a favorite mod's new
+//! version shows up alongside regular content updates until it is dismissed.
+
+use crate::content_store::{ContentStore, ContentType, Platform};
+use crate::disk::WriteContext;
+use crate::paths::Paths;
+use crate::util::now_epoch_secs;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+impl Paths {
+    fn watchlist(&self) -> PathBuf {
+        self.config.with_file_name("watchlist.json")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchedProject {
+    pub platform: Platform,
+    pub project_id: String,
+    pub name: String,
+    pub content_type: ContentType,
+    /// Only releases for this Minecraft version count
+    #[serde(default)]
+    pub mc_version: Option<String>,
+    /// Only releases for this loader count
+    #[serde(default)]
+    pub loader: Option<String>,
+    /// Version id of the newest release the user has seen
+    #[serde(default)]
+    pub seen_version_id: Option<String>,
+    #[serde(default)]
+    pub seen_version: Option<String>,
+    pub added_at: u64,
+}
+
+/// A watched project with a release the user hasn't seen yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchedRelease {
+    pub project: WatchedProject,
+    pub latest_version: String,
+    pub latest_version_id: String,
+}
+
+pub fn load_watchlist(paths: &Paths) -> Result<Vec<WatchedProject>> {
+    let path = paths.watchlist();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&data).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn save_watchlist(paths: &Paths, watched: &[WatchedProject]) -> Result<()> {
+    let path = paths.watchlist();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).write_context(parent, || format!("failed to create dir: {}", parent.display()))?;
+    }
+    let data = serde_json::to_string_pretty(watched).context("failed to serialize watch list")?;
+    fs::write(&path, data).write_context(&path, || format!("failed to write {}", path.display()))
+}
+
+/// Start watching a project. Its current latest release counts as seen, so
+/// only releases published from now on are reported.
+pub fn watch_project(
+    paths: &Paths,
+    store: &ContentStore,
+    platform: Platform,
+    project: &str,
+    mc_version: Option<&str>,
+    loader: Option<&str>,
+) -> Result<WatchedProject> {
+    let item = store.get_project(platform, project)?;
+    let mut watched = load_watchlist(paths)?;
+    if watched.iter().any(|w| w.platform == platform && w.project_id == item.id) {
+        bail!("already watching {} ({})", item.name, item.id);
+    }
+
+    // A project with no matching release yet is still worth watching
+    let latest = store.find_latest_version(platform, &item.id, mc_version, loader)?;
+    let entry = WatchedProject {
+        platform,
+        project_id: item.id,
+        name: item.name,
+        content_type: item.content_type,
+        mc_version: mc_version.map(str::to_string),
+        loader: loader.map(str::to_string),
+        seen_version_id: latest.as_ref().map(|v| v.id.clone()),
+        seen_version: latest.map(|v| v.version),
+        added_at: now_epoch_secs(),
+    };
+    watched.push(entry.clone());
+    save_watchlist(paths, &watched)?;
+    Ok(entry)
+}
+
+fn find_watched(watched: &[WatchedProject], platform: Platform, project: &str) -> Option<usize> {
+    watched.iter().position(|w| {
+        w.platform == platform && (w.project_id == project || w.name.eq_ignore_ascii_case(project))
+    })
+}
+
+/// Stop watching a project, by id or name
+pub fn unwatch_project(paths: &Paths, platform: Platform, project: &str) -> Result<WatchedProject> {
+    let mut watched = load_watchlist(paths)?;
+    let Some(index) = find_watched(&watched, platform, project) else {
+        bail!("not watching {project} on {platform}");
+    };
+    let removed = watched.remove(index);
+    save_watchlist(paths, &watched)?;
+    Ok(removed)
+}
+
+/// Mark a release as seen so it is no longer reported
+pub fn mark_release_seen(paths: &Paths, platform: Platform, project: &str, version_id: &str, version: &str) -> Result<()> {
+    let mut watched = load_watchlist(paths)?;
+    let Some(index) = find_watched(&watched, platform, project) else {
+        bail!("not watching {project} on {platform}");
+    };
+    watched[index].seen_version_id = Some(version_id.to_string());
+    watched[index].seen_version = Some(version.to_string());
+    save_watchlist(paths, &watched)
+}
+
+/// Unseen releases of watched projects. Projects that couldn't be checked
+/// are reported in the returned errors.
+pub fn check_watched_releases(paths: &Paths, store: &ContentStore) -> Result<(Vec<WatchedRelease>, Vec<String>)> {
+    let mut releases = Vec::new();
+    let mut errors = Vec::new();
+    for project in load_watchlist(paths)? {
+        let latest = match store.find_latest_version(
+            project.platform,
+            &project.project_id,
+            project.mc_version.as_deref(),
+            project.loader.as_deref(),
+        ) {
+            Ok(Some(v)) => v,
+            Ok(None) => continue,
+            Err(e) => {
+                errors.push(format!("Failed to check watched {} ({}): {}", project.name, project.project_id, e));
+                continue;
+            }
+        };
+        if project.seen_version_id.as_deref() != Some(latest.id.as_str()) {
+            releases.push(WatchedRelease {
+                project,
+                latest_version: latest.version,
+                latest_version_id: latest.id,
+            });
+        }
+    }
+    Ok((releases, errors))
+}